@@ -0,0 +1,395 @@
+use crate::control_flow_graph::{CFGEdgeKind, CFGNode, ControlFlowGraph};
+use crate::def_use::InstructionLocation;
+use crate::weak_topological_order::{WTOComponent, WeakTopologicalOrder};
+use llvm_ir::instruction::Phi;
+use llvm_ir::{BasicBlock, Function, Instruction, Name, Operand, Terminator};
+use std::collections::HashMap;
+
+/// An abstract domain: a lattice of abstract states, each of which
+/// over-approximates the possible concrete states of a function at some
+/// point.
+///
+/// The bottom element (no possible states, i.e., the point can't be reached)
+/// is represented by the absence of a state, so it needn't be part of the
+/// domain.
+pub trait AbstractDomain: Clone + PartialEq {
+    /// Get the least upper bound of the two states: a state which includes
+    /// every concrete state either includes
+    fn join(&self, other: &Self) -> Self;
+
+    /// Widen this state, the previous state at a widening point, by `newer`:
+    /// get an upper bound of both, such that any sequence of repeated
+    /// widenings eventually stops changing.
+    ///
+    /// The default implementation is [`join()`](#tymethod.join), which is
+    /// only suitable for domains of finite height.
+    fn widen(&self, newer: &Self) -> Self {
+        self.join(newer)
+    }
+
+    /// Narrow this state, the state at a widening point after widening, by
+    /// `newer`, a state recomputed from it: get a state which includes
+    /// `newer`, to recover some of the precision lost by widening.
+    ///
+    /// The default implementation is just `newer`, which is sound because
+    /// [`AbstractInterpreter`](struct.AbstractInterpreter.html) makes a
+    /// bounded number of narrowing passes.
+    fn narrow(&self, newer: &Self) -> Self {
+        let _ = self;
+        newer.clone()
+    }
+}
+
+/// The abstract semantics of the instructions of a function, over some
+/// [`AbstractDomain`](trait.AbstractDomain.html): how each instruction, and
+/// each edge of the CFG, transforms the abstract state.
+///
+/// To compute a fixpoint of the semantics over a function, use
+/// [`AbstractInterpreter`](struct.AbstractInterpreter.html) or
+/// [`FunctionAnalysis::abstract_interpretation()`](struct.FunctionAnalysis.html#method.abstract_interpretation).
+/// [`IntervalSemantics`](struct.IntervalSemantics.html) is the reference
+/// implementation.
+pub trait AbstractSemantics<'m> {
+    /// The abstract domain
+    type State: AbstractDomain;
+
+    /// Get the state on entry to the function
+    fn entry_state(&self) -> Self::State;
+
+    /// Update `state` with the effect of the given instruction, which isn't a
+    /// `phi` (see [`phi()`](#tymethod.phi))
+    fn instruction(&self, state: &mut Self::State, inst: &'m Instruction);
+
+    /// Update `state` with the effect of the given terminator before it
+    /// transfers control, e.g., defining the result of an `invoke`.
+    ///
+    /// The default implementation does nothing.
+    fn terminator(&self, state: &mut Self::State, term: &'m Terminator) {
+        let _ = (state, term);
+    }
+
+    /// Get the state along a CFG edge which is the outcome `kind` of the
+    /// terminator `term`, given the state just after the terminator, or
+    /// `None` if the edge can't be taken in that state. This is the place to
+    /// refine the state with a branch condition.
+    ///
+    /// The default implementation returns the state unchanged.
+    fn edge(
+        &self,
+        state: &Self::State,
+        term: &'m Terminator,
+        kind: CFGEdgeKind<'m>,
+    ) -> Option<Self::State> {
+        let _ = (term, kind);
+        Some(state.clone())
+    }
+
+    /// Update `state` with the assignment of `incoming` to the result of the
+    /// given `phi`, along an edge into the `phi`'s block. The `phi`s of a block
+    /// are assigned together, so `edge` is the state along the edge before any
+    /// of them.
+    fn phi(&self, edge: &Self::State, state: &mut Self::State, phi: &'m Phi, incoming: &'m Operand);
+}
+
+/// A fixpoint engine for [`AbstractSemantics`](trait.AbstractSemantics.html).
+///
+/// Blocks are visited in a
+/// [`WeakTopologicalOrder`](struct.WeakTopologicalOrder.html), stabilizing
+/// each component before moving on (Bourdoncle's recursive strategy). Each
+/// head's state is joined with its previous state for the first few visits,
+/// then widened, so that iteration terminates; then a few passes over the
+/// whole function narrow the states at the heads, to recover some of the
+/// precision lost by widening.
+///
+/// Use [`FunctionAnalysis::abstract_interpretation()`](struct.FunctionAnalysis.html#method.abstract_interpretation)
+/// to run an interpreter with the default settings.
+#[derive(Clone, Debug)]
+pub struct AbstractInterpreter {
+    /// Number of visits to a head before its state is widened
+    widening_delay: usize,
+    /// Number of narrowing passes
+    narrowing_passes: usize,
+}
+
+impl Default for AbstractInterpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AbstractInterpreter {
+    /// Get an interpreter with the default settings: widening after 2 visits
+    /// to a head, and 2 narrowing passes
+    pub fn new() -> Self {
+        Self {
+            widening_delay: 2,
+            narrowing_passes: 2,
+        }
+    }
+
+    /// Join the state at each head with its previous state for the first
+    /// `visits` visits to the head, and only widen it after that
+    pub fn with_widening_delay(mut self, visits: usize) -> Self {
+        self.widening_delay = visits;
+        self
+    }
+
+    /// Make the given number of narrowing passes after widening
+    pub fn with_narrowing_passes(mut self, passes: usize) -> Self {
+        self.narrowing_passes = passes;
+        self
+    }
+
+    /// Compute a fixpoint of the given semantics over the given function,
+    /// whose CFG and weak topological ordering are given
+    pub fn run<'m, S: AbstractSemantics<'m>>(
+        &self,
+        function: &'m Function,
+        cfg: &ControlFlowGraph<'m>,
+        wto: &WeakTopologicalOrder<'m>,
+        semantics: S,
+    ) -> AbstractInterpretation<'m, S> {
+        let mut solver = Solver {
+            function,
+            cfg,
+            semantics: &semantics,
+            widening_delay: self.widening_delay,
+            block_entry: HashMap::new(),
+            block_exit: HashMap::new(),
+            visits: HashMap::new(),
+        };
+        solver.stabilize(wto.components());
+        for _ in 0..self.narrowing_passes {
+            solver.narrow(wto.components());
+        }
+        let block_entry = solver.block_entry;
+        AbstractInterpretation {
+            function,
+            semantics,
+            block_entry,
+        }
+    }
+}
+
+/// The result of running an [`AbstractInterpreter`](struct.AbstractInterpreter.html):
+/// an abstract state at each point of a function.
+pub struct AbstractInterpretation<'m, S: AbstractSemantics<'m>> {
+    /// The function
+    function: &'m Function,
+    /// The semantics
+    semantics: S,
+    /// State at the entry of each block which may execute, after its `phi`s
+    block_entry: HashMap<&'m Name, S::State>,
+}
+
+impl<'m, S: AbstractSemantics<'m>> AbstractInterpretation<'m, S> {
+    /// Get the semantics which were interpreted
+    pub fn semantics(&self) -> &S {
+        &self.semantics
+    }
+
+    /// Get the state at the entry of the given block, after its `phi`s have
+    /// been assigned, or `None` if the block can't execute.
+    pub fn state_at_block_entry(&self, block: &Name) -> Option<&S::State> {
+        self.block_entry.get(block)
+    }
+
+    /// Get the state just before the instruction (or terminator) at the given
+    /// location executes, or `None` if it can't execute. Since the block's
+    /// `phi`s execute together on entry to the block, for the index of any
+    /// `phi` this is the state after all of them.
+    ///
+    /// Panics if the location isn't in this function.
+    pub fn state_at(&self, location: InstructionLocation) -> Option<S::State> {
+        let bb = self.block(location.block);
+        if location.index > bb.instrs.len() {
+            panic!(
+                "state_at(): index {} out of range for block {} in function {}",
+                location.index, location.block, self.function.name
+            );
+        }
+        let mut state = self.block_entry.get(location.block)?.clone();
+        for inst in &bb.instrs[..location.index] {
+            if !matches!(inst, Instruction::Phi(_)) {
+                self.semantics.instruction(&mut state, inst);
+            }
+        }
+        Some(state)
+    }
+
+    /// Can the block with the given `Name` execute, as far as the
+    /// interpretation knows?
+    pub fn is_block_reachable(&self, block: &Name) -> bool {
+        self.block_entry.contains_key(block)
+    }
+
+    /// Get the semantics, and the state at the entry of each block which may
+    /// execute
+    pub(crate) fn into_parts(self) -> (S, HashMap<&'m Name, S::State>) {
+        (self.semantics, self.block_entry)
+    }
+
+    fn block(&self, block: &Name) -> &'m BasicBlock {
+        self.function.get_bb_by_name(block).unwrap_or_else(|| {
+            panic!(
+                "Failed to find block {} in function {}",
+                block, self.function.name
+            )
+        })
+    }
+}
+
+/// State of an `AbstractInterpreter` run
+struct Solver<'a, 'm, S: AbstractSemantics<'m>> {
+    function: &'m Function,
+    cfg: &'a ControlFlowGraph<'m>,
+    semantics: &'a S,
+    widening_delay: usize,
+    /// State at the entry of each block which may execute, after its `phi`s
+    block_entry: HashMap<&'m Name, S::State>,
+    /// State at the exit of each block which may execute, after its terminator
+    /// but before any edge
+    block_exit: HashMap<&'m Name, S::State>,
+    /// Number of times each head has been visited
+    visits: HashMap<&'m Name, usize>,
+}
+
+impl<'a, 'm, S: AbstractSemantics<'m>> Solver<'a, 'm, S> {
+    /// Visit the given components in order, iterating over each component
+    /// until its head is stable
+    fn stabilize(&mut self, components: &[WTOComponent<'m>]) {
+        for component in components {
+            match component {
+                WTOComponent::Vertex(block) => {
+                    let entry = self.incoming(block);
+                    self.execute(block, entry);
+                }
+                WTOComponent::Component { head, components } => {
+                    self.stabilize_component(head, components)
+                }
+            }
+        }
+    }
+
+    /// Iterate over the component with the given head until the head is
+    /// stable
+    fn stabilize_component(&mut self, head: &'m Name, components: &[WTOComponent<'m>]) {
+        // the component is visited at least once, since the states flowing
+        // into it may have changed
+        for iteration in 0.. {
+            let visits = self.visits.get(head).copied().unwrap_or(0);
+            let entry = match (self.block_entry.get(head), self.incoming(head)) {
+                (Some(old), Some(incoming)) if visits >= self.widening_delay => {
+                    Some(old.widen(&incoming))
+                }
+                (Some(old), Some(incoming)) => Some(old.join(&incoming)),
+                (_, incoming) => incoming,
+            };
+            if iteration > 0 && entry.as_ref() == self.block_entry.get(head) {
+                break;
+            }
+            let reachable = entry.is_some();
+            self.execute(head, entry);
+            self.visits.insert(head, visits + 1);
+            self.stabilize(components);
+            if !reachable {
+                break;
+            }
+        }
+    }
+
+    /// Visit the given components in order, narrowing the state at each head
+    fn narrow(&mut self, components: &[WTOComponent<'m>]) {
+        for component in components {
+            match component {
+                WTOComponent::Vertex(block) => {
+                    let entry = self.incoming(block);
+                    self.execute(block, entry);
+                }
+                WTOComponent::Component { head, components } => {
+                    let entry = match (self.block_entry.get(head), self.incoming(head)) {
+                        (Some(old), Some(incoming)) => Some(old.narrow(&incoming)),
+                        (_, incoming) => incoming,
+                    };
+                    self.execute(head, entry);
+                    self.narrow(components);
+                }
+            }
+        }
+    }
+
+    /// Get the join of the states along the edges into the given block (and,
+    /// for the entry block, the state on entry to the function), or `None` if
+    /// none of them may be taken
+    fn incoming(&self, block: &'m Name) -> Option<S::State> {
+        let mut entry = if block == self.cfg.entry() {
+            Some(self.semantics.entry_state())
+        } else {
+            None
+        };
+        let bb = self.block(block);
+        for pred in self.cfg.preds(block) {
+            let exit = match self.block_exit.get(pred) {
+                Some(exit) => exit,
+                None => continue,
+            };
+            let term = &self.block(pred).term;
+            for &kind in self.cfg.edge_kinds(pred, CFGNode::Block(block)) {
+                let edge = match self.semantics.edge(exit, term, kind) {
+                    Some(edge) => edge,
+                    None => continue,
+                };
+                let mut state = edge.clone();
+                for inst in &bb.instrs {
+                    let phi = match inst {
+                        Instruction::Phi(phi) => phi,
+                        _ => break,
+                    };
+                    if let Some((incoming, _)) = phi
+                        .incoming_values
+                        .iter()
+                        .find(|(_, incoming)| incoming == pred)
+                    {
+                        self.semantics.phi(&edge, &mut state, phi, incoming);
+                    }
+                }
+                entry = Some(match entry {
+                    Some(entry) => entry.join(&state),
+                    None => state,
+                });
+            }
+        }
+        entry
+    }
+
+    /// Record the given state at the entry of the given block, and compute
+    /// the state at its exit; or record that it can't execute
+    fn execute(&mut self, block: &'m Name, entry: Option<S::State>) {
+        let mut state = match entry {
+            Some(entry) => entry,
+            None => {
+                self.block_entry.remove(block);
+                self.block_exit.remove(block);
+                return;
+            }
+        };
+        self.block_entry.insert(block, state.clone());
+        let bb = self.block(block);
+        for inst in &bb.instrs {
+            if !matches!(inst, Instruction::Phi(_)) {
+                self.semantics.instruction(&mut state, inst);
+            }
+        }
+        self.semantics.terminator(&mut state, &bb.term);
+        self.block_exit.insert(block, state);
+    }
+
+    fn block(&self, block: &Name) -> &'m BasicBlock {
+        self.function.get_bb_by_name(block).unwrap_or_else(|| {
+            panic!(
+                "Failed to find block {} in function {}",
+                block, self.function.name
+            )
+        })
+    }
+}
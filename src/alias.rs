@@ -0,0 +1,338 @@
+use llvm_ir::types::{FPType, Type};
+use llvm_ir::{Constant, Function, Instruction, Module, Name, Operand, TypeRef};
+use std::collections::{HashMap, HashSet};
+
+/// An alias analysis answers whether two pointers within a function may refer
+/// to overlapping memory.
+///
+/// Alias analyses are used by the
+/// [`DataDependenceGraph`](struct.DataDependenceGraph.html) to decide which
+/// memory accesses may depend on each other; see
+/// [`FunctionAnalysis::data_dependence_graph_with_alias_analysis()`](struct.FunctionAnalysis.html#method.data_dependence_graph_with_alias_analysis).
+pub trait AliasAnalysis<'m> {
+    /// May the two pointers (both operands within the function being
+    /// analyzed) refer to overlapping memory? Returning `true` is always safe;
+    /// `false` should be returned only if they definitely don't.
+    fn may_alias(&self, ptr1: &'m Operand, ptr2: &'m Operand) -> bool;
+}
+
+/// The default alias analysis: two pointers are known not to alias only when
+/// they are based on different `alloca`s or globals
+pub(crate) struct UnderlyingObjectAliasAnalysis<'m> {
+    /// Map from each instruction result to the instruction producing it
+    defs: HashMap<&'m Name, &'m Instruction>,
+    /// The `Module` containing the function, if known (see
+    /// `underlying_object()`)
+    module: Option<&'m Module>,
+}
+
+impl<'m> UnderlyingObjectAliasAnalysis<'m> {
+    pub(crate) fn new(function: &'m Function, module: Option<&'m Module>) -> Self {
+        Self {
+            module,
+            defs: function
+                .basic_blocks
+                .iter()
+                .flat_map(|bb| bb.instrs.iter())
+                .filter_map(|inst| inst.try_get_result().map(|dest| (dest, inst)))
+                .collect(),
+        }
+    }
+}
+
+impl<'m> AliasAnalysis<'m> for UnderlyingObjectAliasAnalysis<'m> {
+    fn may_alias(&self, ptr1: &'m Operand, ptr2: &'m Operand) -> bool {
+        match (
+            underlying_object(ptr1, &self.defs, self.module),
+            underlying_object(ptr2, &self.defs, self.module),
+        ) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        }
+    }
+}
+
+/// A type-based alias analysis for a particular function, in the spirit of
+/// C's strict aliasing rules: two pointers may alias only if the types they
+/// point to are compatible, or one may be a reinterpretation of the other.
+///
+/// Two types are compatible if they are the same type, either of them is `i8`
+/// (C's `char`, which may alias anything), both are pointer types, or one is an
+/// aggregate containing a type compatible with the other. Named struct types
+/// are treated as compatible with every type, since their definitions live in
+/// the `Module`.
+///
+/// Memory of one type may also be accessed as another type through a bitcast
+/// pointer, e.g. an `i64` load through a bitcast `{ i32, i32 }*`. So two
+/// types which aren't compatible may still alias if they have the same size,
+/// or if one is a scalar with the same size as some scalar within the other
+/// (e.g., a `float` and the `i32` in a `{ i32, i64 }`). Types whose sizes
+/// depend on the target's data layout, such as pointers or structs which may
+/// contain padding, may alias any type. Accesses of part of a larger scalar
+/// (e.g., an `i32` access of half of an `i64`) are assumed not to happen.
+///
+/// Pointers based on the same `alloca` or global may alias whatever their
+/// types (e.g., pointers to different members of a union), and pointers based
+/// on different `alloca`s or globals never alias; the types are only
+/// considered otherwise. A `GlobalAlias` is the same global as its aliasee.
+/// For a [`FunctionAnalysis`](struct.FunctionAnalysis.html) created without
+/// its `Module`, which globals are aliases isn't known, so pointers based on
+/// globals are treated like any other pointers.
+///
+/// This relies on typed pointers to know what each pointer points to. With
+/// opaque pointers (LLVM 15 and later), this analysis gives the same answers
+/// as the default one, which only considers `alloca`s and globals.
+///
+/// LLVM itself uses `!tbaa` metadata emitted by the frontend, which `llvm-ir`
+/// doesn't expose; this analysis assumes that the code obeys strict aliasing,
+/// which isn't true of all code (e.g., C compiled with
+/// `-fno-strict-aliasing`).
+///
+/// To construct a `TypeBasedAliasAnalysis`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct TypeBasedAliasAnalysis<'m> {
+    objects: UnderlyingObjectAliasAnalysis<'m>,
+}
+
+impl<'m> TypeBasedAliasAnalysis<'m> {
+    pub(crate) fn new(function: &'m Function, module: Option<&'m Module>) -> Self {
+        Self {
+            objects: UnderlyingObjectAliasAnalysis::new(function, module),
+        }
+    }
+}
+
+/// Get the type the given pointer points to, if it's known
+#[cfg(feature = "llvm-14-or-lower")]
+fn pointee_type(pointer: &Operand) -> Option<TypeRef> {
+    match pointer {
+        Operand::LocalOperand { ty, .. } => match ty.as_ref() {
+            Type::PointerType { pointee_type, .. } => Some(pointee_type.clone()),
+            _ => None,
+        },
+        Operand::ConstantOperand(constant) => match constant.as_ref() {
+            Constant::GlobalReference { ty, .. } => Some(ty.clone()),
+            _ => None,
+        },
+        Operand::MetadataOperand => None,
+    }
+}
+
+/// Get the type the given pointer points to, if it's known. With opaque
+/// pointers, it's only known for pointers directly to an `alloca` or global,
+/// and those are already handled by their underlying objects.
+#[cfg(feature = "llvm-15-or-greater")]
+fn pointee_type(_pointer: &Operand) -> Option<TypeRef> {
+    None
+}
+
+impl<'m> AliasAnalysis<'m> for TypeBasedAliasAnalysis<'m> {
+    fn may_alias(&self, ptr1: &'m Operand, ptr2: &'m Operand) -> bool {
+        if let (Some(a), Some(b)) = (
+            underlying_object(ptr1, &self.objects.defs, self.objects.module),
+            underlying_object(ptr2, &self.objects.defs, self.objects.module),
+        ) {
+            return a == b;
+        }
+        match (pointee_type(ptr1), pointee_type(ptr2)) {
+            (Some(a), Some(b)) => types_compatible(&a, &b) || may_reinterpret(&a, &b),
+            _ => true,
+        }
+    }
+}
+
+/// May memory of type `a` overlap memory of type `b`, under strict aliasing?
+fn types_compatible(a: &Type, b: &Type) -> bool {
+    let is_char = |ty: &Type| matches!(ty, Type::IntegerType { bits: 8 });
+    let is_pointer = |ty: &Type| matches!(ty, Type::PointerType { .. });
+    a == b
+        || is_char(a)
+        || is_char(b)
+        || (is_pointer(a) && is_pointer(b))
+        || contains_compatible(a, b)
+        || contains_compatible(b, a)
+}
+
+/// Is `outer` an aggregate with a member (transitively) compatible with `inner`?
+fn contains_compatible(outer: &Type, inner: &Type) -> bool {
+    match outer {
+        Type::ArrayType { element_type, .. } | Type::VectorType { element_type, .. } => {
+            types_compatible(element_type, inner)
+        }
+        Type::StructType { element_types, .. } => element_types
+            .iter()
+            .any(|element| types_compatible(element, inner)),
+        Type::NamedStructType { .. } => true,
+        _ => false,
+    }
+}
+
+/// May memory of type `a` be accessed as type `b`, or vice versa, through a
+/// bitcast pointer? This is the case if both have the same size, or one is a
+/// scalar with the same size as a scalar within the other. Returns `true` if
+/// the size of either type isn't known.
+fn may_reinterpret(a: &Type, b: &Type) -> bool {
+    match (size_in_bytes(a), size_in_bytes(b)) {
+        (Some(a_size), Some(b_size)) => {
+            a_size == b_size || reinterprets_scalar(a, b) || reinterprets_scalar(b, a)
+        }
+        _ => true,
+    }
+}
+
+/// Is `scalar` an integer or floating-point type with the same size as some
+/// scalar within `outer`?
+fn reinterprets_scalar(scalar: &Type, outer: &Type) -> bool {
+    if !matches!(scalar, Type::IntegerType { .. } | Type::FPType(_)) {
+        return false;
+    }
+    match outer {
+        Type::ArrayType { element_type, .. } | Type::VectorType { element_type, .. } => {
+            reinterprets_scalar(scalar, element_type)
+        }
+        Type::StructType { element_types, .. } => element_types
+            .iter()
+            .any(|element| reinterprets_scalar(scalar, element)),
+        _ => size_in_bytes(scalar) == size_in_bytes(outer),
+    }
+}
+
+/// Get the size of the given type in bytes, if it doesn't depend on the
+/// target's data layout. That is, the type contains no pointers, named
+/// structs, or padding (which we only rule out for packed structs, and for
+/// structs whose elements all have the same power-of-two size).
+fn size_in_bytes(ty: &Type) -> Option<u64> {
+    match ty {
+        Type::IntegerType { bits } => {
+            let size = u64::from(*bits).div_ceil(8);
+            Some(size).filter(|size| size.is_power_of_two())
+        }
+        Type::FPType(fpt) => match fpt {
+            FPType::Half => Some(2),
+            FPType::Single => Some(4),
+            FPType::Double => Some(8),
+            FPType::FP128 => Some(16),
+            _ => None,
+        },
+        Type::VectorType {
+            element_type,
+            num_elements,
+            ..
+        } => {
+            if is_scalable_vector(ty) {
+                return None;
+            }
+            let size = size_in_bytes(element_type)? * *num_elements as u64;
+            Some(size).filter(|size| size.is_power_of_two())
+        }
+        Type::ArrayType {
+            element_type,
+            num_elements,
+        } => Some(size_in_bytes(element_type)? * *num_elements as u64),
+        Type::StructType {
+            element_types,
+            is_packed,
+        } => {
+            let sizes = element_types
+                .iter()
+                .map(|element| size_in_bytes(element))
+                .collect::<Option<Vec<u64>>>()?;
+            let unpadded = *is_packed
+                || sizes
+                    .iter()
+                    .all(|&size| size == sizes[0] && size.is_power_of_two());
+            if unpadded {
+                Some(sizes.iter().sum())
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Is the given type a scalable vector, whose size isn't known statically?
+#[cfg(feature = "llvm-11-or-greater")]
+fn is_scalable_vector(ty: &Type) -> bool {
+    matches!(ty, Type::VectorType { scalable: true, .. })
+}
+
+/// Is the given type a scalable vector, whose size isn't known statically?
+#[cfg(feature = "llvm-10-or-lower")]
+fn is_scalable_vector(_ty: &Type) -> bool {
+    false
+}
+
+/// An object whose memory is known to be disjoint from that of other objects
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum MemoryObject<'m> {
+    /// The `alloca` with this result
+    Alloca(&'m Name),
+    /// The global with this name
+    Global(&'m Name),
+}
+
+/// Get the `alloca` or global which the given pointer is based on, looking
+/// through `getelementptr`s and casts, or `None` if it isn't known.
+///
+/// A pointer to a `GlobalAlias` is based on the global it aliases, which is
+/// found in the given `Module`. If the `Module` isn't given, a pointer to any
+/// global may be a pointer to an alias, so isn't known to be based on
+/// anything.
+pub(crate) fn underlying_object<'m>(
+    mut pointer: &'m Operand,
+    defs: &HashMap<&'m Name, &'m Instruction>,
+    module: Option<&'m Module>,
+) -> Option<MemoryObject<'m>> {
+    // in unreachable blocks, a `getelementptr` or cast may be based on itself
+    let mut visited: HashSet<&'m Name> = HashSet::new();
+    loop {
+        pointer = match pointer {
+            Operand::LocalOperand { name, .. } => {
+                if !visited.insert(name) {
+                    return None;
+                }
+                match defs.get(name)? {
+                    Instruction::Alloca(_) => return Some(MemoryObject::Alloca(name)),
+                    Instruction::GetElementPtr(gep) => &gep.address,
+                    Instruction::BitCast(bitcast) => &bitcast.operand,
+                    Instruction::AddrSpaceCast(cast) => &cast.operand,
+                    _ => return None,
+                }
+            }
+            Operand::ConstantOperand(constant) => return underlying_global(constant, module),
+            Operand::MetadataOperand => return None,
+        };
+    }
+}
+
+/// Get the global which the given constant pointer is based on (see
+/// `underlying_object()`)
+fn underlying_global<'m>(
+    mut constant: &'m Constant,
+    module: Option<&'m Module>,
+) -> Option<MemoryObject<'m>> {
+    let module = module?;
+    // bound the number of aliases followed, in case of (invalid) cyclic aliases
+    let mut aliases_left = module.global_aliases.len();
+    loop {
+        constant = match constant {
+            Constant::GlobalReference { name, .. } => {
+                let alias = module.global_aliases.iter().find(|a| &a.name == name);
+                match alias {
+                    None => return Some(MemoryObject::Global(name)),
+                    Some(_) if aliases_left == 0 => return None,
+                    Some(alias) => {
+                        aliases_left -= 1;
+                        alias.aliasee.as_ref()
+                    }
+                }
+            }
+            Constant::GetElementPtr(gep) => gep.address.as_ref(),
+            Constant::BitCast(bitcast) => bitcast.operand.as_ref(),
+            Constant::AddrSpaceCast(cast) => cast.operand.as_ref(),
+            _ => return None,
+        };
+    }
+}
@@ -0,0 +1,283 @@
+use crate::control_flow_graph::ControlFlowGraph;
+use crate::dominator_tree::DominatorTree;
+use either::Either;
+use llvm_ir::{Constant, Function, Instruction, Name, Operand, Terminator};
+use std::collections::{HashMap, HashSet};
+
+/// A table of approximate costs used to compute [`BlockCosts`](struct.BlockCosts.html).
+///
+/// Costs are unitless; the default table roughly approximates cycle counts on
+/// a typical out-of-order CPU, but any consistent scale (e.g., code size) can
+/// be used instead.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CostModel {
+    /// Cost of each opcode, keyed by its LLVM mnemonic (e.g., `"add"`, `"load"`,
+    /// or `"br"`). Opcodes not in this table cost `default_cost`.
+    pub opcode_costs: HashMap<String, u64>,
+    /// Cost of any opcode not in `opcode_costs`
+    pub default_cost: u64,
+    /// Additional cost of each `call` or `invoke` of something other than an
+    /// LLVM intrinsic, on top of the cost of the `call` or `invoke` opcode
+    /// itself
+    pub call_penalty: u64,
+}
+
+impl Default for CostModel {
+    fn default() -> Self {
+        let opcode_costs = [
+            ("mul", 3),
+            ("udiv", 20),
+            ("sdiv", 20),
+            ("urem", 20),
+            ("srem", 20),
+            ("fadd", 3),
+            ("fsub", 3),
+            ("fmul", 3),
+            ("fdiv", 20),
+            ("frem", 20),
+            ("load", 4),
+            ("store", 4),
+            ("fence", 10),
+            ("cmpxchg", 20),
+            ("atomicrmw", 20),
+            ("bitcast", 0),
+            ("phi", 0),
+            ("unreachable", 0),
+        ]
+        .iter()
+        .map(|&(opcode, cost)| (opcode.to_owned(), cost))
+        .collect();
+        Self {
+            opcode_costs,
+            default_cost: 1,
+            call_penalty: 10,
+        }
+    }
+}
+
+impl CostModel {
+    /// Get the cost of the given opcode (given by its LLVM mnemonic, e.g.,
+    /// `"add"`)
+    pub fn opcode_cost(&self, opcode: &str) -> u64 {
+        self.opcode_costs
+            .get(opcode)
+            .copied()
+            .unwrap_or(self.default_cost)
+    }
+
+    /// Set the cost of the given opcode (given by its LLVM mnemonic, e.g.,
+    /// `"add"`), returning the modified `CostModel`
+    pub fn with_opcode_cost(mut self, opcode: impl Into<String>, cost: u64) -> Self {
+        self.opcode_costs.insert(opcode.into(), cost);
+        self
+    }
+
+    fn instruction_cost(&self, inst: &Instruction) -> u64 {
+        let cost = self.opcode_cost(instruction_opcode(inst));
+        match inst {
+            Instruction::Call(call) => cost + self.penalty_for_callee(&call.function),
+            _ => cost,
+        }
+    }
+
+    fn terminator_cost(&self, term: &Terminator) -> u64 {
+        let cost = self.opcode_cost(terminator_opcode(term));
+        match term {
+            Terminator::Invoke(invoke) => cost + self.penalty_for_callee(&invoke.function),
+            _ => cost,
+        }
+    }
+
+    fn penalty_for_callee<T>(&self, callee: &Either<T, Operand>) -> u64 {
+        match callee {
+            Either::Right(Operand::ConstantOperand(cref)) => match cref.as_ref() {
+                Constant::GlobalReference {
+                    name: Name::Name(name),
+                    ..
+                } if name.starts_with("llvm.") => 0,
+                _ => self.call_penalty,
+            },
+            Either::Right(_) => self.call_penalty,
+            Either::Left(_) => 0, // inline assembly
+        }
+    }
+}
+
+/// Static cost estimates for each basic block in a particular function, with
+/// per-function and per-loop rollups.
+///
+/// Costs are computed according to a [`CostModel`](struct.CostModel.html): a
+/// block's cost is the sum of the costs of its instructions and terminator.
+/// These are estimates of the cost of executing each block once; combine them
+/// with block frequencies (e.g., from profiling) to get a static profile.
+///
+/// To construct a `BlockCosts` with the default `CostModel`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct BlockCosts<'m> {
+    /// Name of the function being analyzed
+    function: &'m str,
+    /// Cost of each block, in the order the blocks appear in the function
+    block_costs: Vec<(&'m Name, u64)>,
+    /// Map from loop header to the blocks in that loop
+    loops: HashMap<&'m Name, HashSet<&'m Name>>,
+}
+
+impl<'m> BlockCosts<'m> {
+    pub(crate) fn new(
+        function: &'m Function,
+        cfg: &ControlFlowGraph<'m>,
+        domtree: &DominatorTree<'m>,
+        model: &CostModel,
+    ) -> Self {
+        let block_costs = function
+            .basic_blocks
+            .iter()
+            .map(|bb| {
+                let cost = bb
+                    .instrs
+                    .iter()
+                    .map(|inst| model.instruction_cost(inst))
+                    .sum::<u64>()
+                    + model.terminator_cost(&bb.term);
+                (&bb.name, cost)
+            })
+            .collect();
+        Self {
+            function: &function.name,
+            block_costs,
+            loops: cfg.natural_loops(domtree),
+        }
+    }
+
+    /// Iterate over the blocks in the function along with their costs, in the
+    /// order the blocks appear in the function.
+    pub fn blocks<'s>(&'s self) -> impl Iterator<Item = (&'m Name, u64)> + 's {
+        self.block_costs.iter().copied()
+    }
+
+    /// Get the cost of the block with the given `Name`.
+    ///
+    /// Panics if the given block is not found in the function.
+    pub fn block_cost(&self, block: &Name) -> u64 {
+        self.block_costs
+            .iter()
+            .find(|(name, _)| *name == block)
+            .map(|&(_, cost)| cost)
+            .unwrap_or_else(|| {
+                panic!(
+                    "block_cost(): block {:?} not found in function {:?}",
+                    block, self.function
+                )
+            })
+    }
+
+    /// Get the total cost of all the blocks in the function, i.e., the cost of
+    /// executing every block once.
+    pub fn function_cost(&self) -> u64 {
+        self.block_costs.iter().map(|&(_, cost)| cost).sum()
+    }
+
+    /// Get the total cost of one iteration of the loop with the given header,
+    /// i.e., the sum of the costs of the blocks in that loop (including any
+    /// nested loops). Loops are the natural loops of the CFG's back edges, and
+    /// loops sharing a header are considered a single loop.
+    ///
+    /// Returns `None` if the given block is not a loop header.
+    pub fn loop_cost(&self, header: &Name) -> Option<u64> {
+        self.loops.get(header).map(|body| {
+            self.block_costs
+                .iter()
+                .filter(|(name, _)| body.contains(name))
+                .map(|&(_, cost)| cost)
+                .sum()
+        })
+    }
+
+    /// Iterate over the headers of the loops in the function along with the
+    /// cost of one iteration of each loop (see [`loop_cost()`](#method.loop_cost)),
+    /// in the order the headers appear in the function.
+    pub fn loop_costs<'s>(&'s self) -> impl Iterator<Item = (&'m Name, u64)> + 's {
+        self.block_costs
+            .iter()
+            .filter_map(move |&(name, _)| self.loop_cost(name).map(|cost| (name, cost)))
+    }
+}
+
+/// Get the LLVM mnemonic of the given instruction's opcode
+fn instruction_opcode(inst: &Instruction) -> &'static str {
+    match inst {
+        Instruction::Add(_) => "add",
+        Instruction::Sub(_) => "sub",
+        Instruction::Mul(_) => "mul",
+        Instruction::UDiv(_) => "udiv",
+        Instruction::SDiv(_) => "sdiv",
+        Instruction::URem(_) => "urem",
+        Instruction::SRem(_) => "srem",
+        Instruction::And(_) => "and",
+        Instruction::Or(_) => "or",
+        Instruction::Xor(_) => "xor",
+        Instruction::Shl(_) => "shl",
+        Instruction::LShr(_) => "lshr",
+        Instruction::AShr(_) => "ashr",
+        Instruction::FAdd(_) => "fadd",
+        Instruction::FSub(_) => "fsub",
+        Instruction::FMul(_) => "fmul",
+        Instruction::FDiv(_) => "fdiv",
+        Instruction::FRem(_) => "frem",
+        Instruction::FNeg(_) => "fneg",
+        Instruction::ExtractElement(_) => "extractelement",
+        Instruction::InsertElement(_) => "insertelement",
+        Instruction::ShuffleVector(_) => "shufflevector",
+        Instruction::ExtractValue(_) => "extractvalue",
+        Instruction::InsertValue(_) => "insertvalue",
+        Instruction::Alloca(_) => "alloca",
+        Instruction::Load(_) => "load",
+        Instruction::Store(_) => "store",
+        Instruction::Fence(_) => "fence",
+        Instruction::CmpXchg(_) => "cmpxchg",
+        Instruction::AtomicRMW(_) => "atomicrmw",
+        Instruction::GetElementPtr(_) => "getelementptr",
+        Instruction::Trunc(_) => "trunc",
+        Instruction::ZExt(_) => "zext",
+        Instruction::SExt(_) => "sext",
+        Instruction::FPTrunc(_) => "fptrunc",
+        Instruction::FPExt(_) => "fpext",
+        Instruction::FPToUI(_) => "fptoui",
+        Instruction::FPToSI(_) => "fptosi",
+        Instruction::UIToFP(_) => "uitofp",
+        Instruction::SIToFP(_) => "sitofp",
+        Instruction::PtrToInt(_) => "ptrtoint",
+        Instruction::IntToPtr(_) => "inttoptr",
+        Instruction::BitCast(_) => "bitcast",
+        Instruction::AddrSpaceCast(_) => "addrspacecast",
+        Instruction::ICmp(_) => "icmp",
+        Instruction::FCmp(_) => "fcmp",
+        Instruction::Phi(_) => "phi",
+        Instruction::Select(_) => "select",
+        #[cfg(feature = "llvm-10-or-greater")]
+        Instruction::Freeze(_) => "freeze",
+        Instruction::Call(_) => "call",
+        Instruction::VAArg(_) => "va_arg",
+        Instruction::LandingPad(_) => "landingpad",
+        Instruction::CatchPad(_) => "catchpad",
+        Instruction::CleanupPad(_) => "cleanuppad",
+    }
+}
+
+/// Get the LLVM mnemonic of the given terminator's opcode
+fn terminator_opcode(term: &Terminator) -> &'static str {
+    match term {
+        Terminator::Ret(_) => "ret",
+        Terminator::Br(_) | Terminator::CondBr(_) => "br",
+        Terminator::Switch(_) => "switch",
+        Terminator::IndirectBr(_) => "indirectbr",
+        Terminator::Invoke(_) => "invoke",
+        Terminator::Resume(_) => "resume",
+        Terminator::Unreachable(_) => "unreachable",
+        Terminator::CleanupRet(_) => "cleanupret",
+        Terminator::CatchRet(_) => "catchret",
+        Terminator::CatchSwitch(_) => "catchswitch",
+        Terminator::CallBr(_) => "callbr",
+    }
+}
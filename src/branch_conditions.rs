@@ -0,0 +1,270 @@
+use crate::control_dep_graph::ControlDependenceGraph;
+use crate::control_flow_graph::{callbr_indirect_dests, CFGNode};
+use crate::dominator_tree::PostDominatorTree;
+use llvm_ir::{
+    BasicBlock, ConstantRef, Function, Instruction, IntPredicate, Name, Operand, Terminator,
+};
+use std::collections::HashMap;
+
+/// The branch conditions which control each basic block in a particular
+/// function: for each block which a given block is (immediately) control
+/// dependent on, the condition that block branches on, and which outcome(s) of
+/// the branch lead to the given block.
+///
+/// This ties the [`ControlDependenceGraph`](struct.ControlDependenceGraph.html)
+/// back to the IR, e.g. for constructing path conditions.
+///
+/// To construct a `BranchConditions`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct BranchConditions<'m> {
+    /// The function being analyzed
+    function: &'m Function,
+    /// Map from each `icmp` result in the function to the `icmp` producing it
+    icmps: HashMap<&'m Name, ICmpCondition<'m>>,
+    /// Map from block name to the conditions controlling that block
+    controlling: HashMap<&'m Name, Vec<ControlCondition<'m>>>,
+}
+
+/// A condition controlling whether a block executes: the block `block` branches
+/// on `condition`, and the block in question executes if the branch has any of
+/// the outcomes in `outcomes`
+#[derive(Clone, PartialEq, Debug, Hash)]
+pub struct ControlCondition<'m> {
+    /// Name of the controlling block, i.e., the block whose terminator is the
+    /// branch
+    pub block: &'m Name,
+    /// The condition the controlling block branches on
+    pub condition: BranchCondition<'m>,
+    /// The outcome(s) of the branch which lead to the dependent block
+    pub outcomes: Vec<BranchOutcome<'m>>,
+}
+
+/// The condition a block's terminator branches on
+#[derive(Clone, PartialEq, Debug, Hash)]
+pub enum BranchCondition<'m> {
+    /// A conditional `br` on a boolean value
+    Bool {
+        /// The boolean value being branched on
+        value: &'m Operand,
+        /// If the value is the result of an `icmp` in the same function, the
+        /// details of that comparison
+        icmp: Option<ICmpCondition<'m>>,
+    },
+    /// A `switch` on an integer value
+    Switch {
+        /// The integer value being switched on
+        value: &'m Operand,
+    },
+    /// An `indirectbr` on an address
+    IndirectBr {
+        /// The address being branched to
+        address: &'m Operand,
+    },
+    /// Whether an `invoke` or `callbr` returns normally
+    Call,
+    /// The terminator does not branch on a value (e.g., an unconditional `br`,
+    /// or a `catchswitch`)
+    None,
+}
+
+/// An integer comparison (`icmp`) used as a branch condition
+#[derive(Clone, PartialEq, Debug, Hash)]
+pub struct ICmpCondition<'m> {
+    /// The comparison predicate
+    pub predicate: IntPredicate,
+    /// The first (left-hand) operand of the comparison
+    pub operand0: &'m Operand,
+    /// The second (right-hand) operand of the comparison
+    pub operand1: &'m Operand,
+}
+
+/// One possible outcome of a branch
+#[derive(Clone, PartialEq, Debug, Hash)]
+pub enum BranchOutcome<'m> {
+    /// The condition of a conditional `br` was true
+    True,
+    /// The condition of a conditional `br` was false
+    False,
+    /// The value of a `switch` matched the given case
+    Case(&'m ConstantRef),
+    /// The value of a `switch` matched none of the cases
+    Default,
+    /// An `invoke` or `callbr` returned normally
+    Normal,
+    /// An `invoke` (or other EH terminator) unwound
+    Unwind,
+    /// Control transferred to the given block (for terminators such as
+    /// `indirectbr` or `catchswitch`, whose outcomes are identified only by
+    /// their destinations, or a `callbr` jumping to an indirect destination)
+    Dest(&'m Name),
+}
+
+impl<'m> BranchConditions<'m> {
+    pub(crate) fn new(
+        function: &'m Function,
+        postdomtree: &PostDominatorTree<'m>,
+        cdg: &ControlDependenceGraph<'m>,
+    ) -> Self {
+        let icmps = function
+            .basic_blocks
+            .iter()
+            .flat_map(|bb| bb.instrs.iter())
+            .filter_map(|inst| match inst {
+                Instruction::ICmp(icmp) => Some((
+                    &icmp.dest,
+                    ICmpCondition {
+                        predicate: icmp.predicate,
+                        operand0: &icmp.operand0,
+                        operand1: &icmp.operand1,
+                    },
+                )),
+                _ => None,
+            })
+            .collect();
+        let mut branch_conditions = Self {
+            function,
+            icmps,
+            controlling: HashMap::new(),
+        };
+        for bb in &function.basic_blocks {
+            let conditions = cdg
+                .get_imm_control_dependencies(&bb.name)
+                .map(|controller| {
+                    let controller_bb = branch_conditions.get_bb(controller);
+                    ControlCondition {
+                        block: controller,
+                        condition: branch_conditions.condition_of_term(&controller_bb.term),
+                        outcomes: outcomes(function, controller_bb)
+                            .into_iter()
+                            .filter(|(_, dest)| {
+                                postdomtree
+                                    .postdominates(CFGNode::Block(&bb.name), CFGNode::Block(dest))
+                            })
+                            .map(|(outcome, _)| outcome)
+                            .collect(),
+                    }
+                })
+                .collect();
+            branch_conditions.controlling.insert(&bb.name, conditions);
+        }
+        branch_conditions
+    }
+
+    /// Get the conditions which immediately control the block with the given
+    /// `Name`: one for each block which `block` has an immediate control
+    /// dependency on (see
+    /// [`ControlDependenceGraph::get_imm_control_dependencies()`](struct.ControlDependenceGraph.html#method.get_imm_control_dependencies)).
+    pub fn controlling_conditions<'s>(
+        &'s self,
+        block: &'m Name,
+    ) -> impl Iterator<Item = &'s ControlCondition<'m>> + 's {
+        self.controlling
+            .get(block)
+            .unwrap_or_else(|| panic!("controlling_conditions: block {:?} not found", block))
+            .iter()
+    }
+
+    /// Get the condition which the terminator of the block with the given
+    /// `Name` branches on.
+    pub fn branch_condition(&self, block: &'m Name) -> BranchCondition<'m> {
+        self.condition_of_term(&self.get_bb(block).term)
+    }
+
+    /// Get the possible outcomes of the terminator of the block with the given
+    /// `Name`, along with the block each outcome leads to.
+    pub fn outcomes(&self, block: &'m Name) -> Vec<(BranchOutcome<'m>, &'m Name)> {
+        outcomes(self.function, self.get_bb(block))
+    }
+
+    fn get_bb(&self, block: &Name) -> &'m llvm_ir::BasicBlock {
+        self.function.get_bb_by_name(block).unwrap_or_else(|| {
+            panic!(
+                "Block {:?} not found in function {:?}",
+                block, self.function.name
+            )
+        })
+    }
+
+    fn condition_of_term(&self, term: &'m Terminator) -> BranchCondition<'m> {
+        match term {
+            Terminator::CondBr(condbr) => BranchCondition::Bool {
+                value: &condbr.condition,
+                icmp: match &condbr.condition {
+                    Operand::LocalOperand { name, .. } => self.icmps.get(name).cloned(),
+                    _ => None,
+                },
+            },
+            Terminator::Switch(switch) => BranchCondition::Switch {
+                value: &switch.operand,
+            },
+            Terminator::IndirectBr(ibr) => BranchCondition::IndirectBr {
+                address: &ibr.operand,
+            },
+            Terminator::Invoke(_) | Terminator::CallBr(_) => BranchCondition::Call,
+            _ => BranchCondition::None,
+        }
+    }
+}
+
+/// Get the possible outcomes of the terminator of the given block, along with
+/// the block each outcome leads to
+fn outcomes<'m>(function: &'m Function, bb: &'m BasicBlock) -> Vec<(BranchOutcome<'m>, &'m Name)> {
+    match &bb.term {
+        Terminator::Br(br) => vec![(BranchOutcome::Dest(&br.dest), &br.dest)],
+        Terminator::CondBr(condbr) => vec![
+            (BranchOutcome::True, &condbr.true_dest),
+            (BranchOutcome::False, &condbr.false_dest),
+        ],
+        Terminator::Switch(switch) => switch
+            .dests
+            .iter()
+            .map(|(value, dest)| (BranchOutcome::Case(value), dest))
+            .chain(std::iter::once((
+                BranchOutcome::Default,
+                &switch.default_dest,
+            )))
+            .collect(),
+        Terminator::IndirectBr(ibr) => ibr
+            .possible_dests
+            .iter()
+            .map(|dest| (BranchOutcome::Dest(dest), dest))
+            .collect(),
+        Terminator::Invoke(invoke) => vec![
+            (BranchOutcome::Normal, &invoke.return_label),
+            (BranchOutcome::Unwind, &invoke.exception_label),
+        ],
+        Terminator::CallBr(callbr) => {
+            std::iter::once((BranchOutcome::Normal, &callbr.return_label))
+                .chain(
+                    callbr_indirect_dests(function, &bb.name, callbr, false)
+                        .into_iter()
+                        .map(|dest| (BranchOutcome::Dest(dest), dest)),
+                )
+                .collect()
+        }
+        Terminator::CleanupRet(cleanupret) => cleanupret
+            .unwind_dest
+            .iter()
+            .map(|dest| (BranchOutcome::Unwind, dest))
+            .collect(),
+        Terminator::CatchRet(catchret) => {
+            vec![(
+                BranchOutcome::Dest(&catchret.successor),
+                &catchret.successor,
+            )]
+        }
+        Terminator::CatchSwitch(catchswitch) => catchswitch
+            .catch_handlers
+            .iter()
+            .map(|dest| (BranchOutcome::Dest(dest), dest))
+            .chain(
+                catchswitch
+                    .default_unwind_dest
+                    .iter()
+                    .map(|dest| (BranchOutcome::Unwind, dest)),
+            )
+            .collect(),
+        Terminator::Ret(_) | Terminator::Resume(_) | Terminator::Unreachable(_) => vec![],
+    }
+}
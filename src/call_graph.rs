@@ -1,75 +1,444 @@
+use crate::function_ptr_tables::FunctionPtrTables;
 use crate::functions_by_type::FunctionsByType;
+use crate::reachability::ReachabilityIndex;
 use either::Either;
 use llvm_ir::{
+    function::{FunctionAttribute, ParameterAttribute},
     instruction::{Call, InlineAssembly},
-    terminator::Invoke,
-    Constant, Instruction, Module, Name, Operand, Terminator, TypeRef,
+    terminator::{CallBr, Invoke, Ret},
+    Constant, Function, Instruction, Module, Name, Operand, Terminator, TypeRef,
 };
 use petgraph::prelude::*;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
 
 /// The call graph for the analyzed `Module`(s): which functions may call which
-/// other functions.
+/// other functions, and from which call sites.
+///
+/// Nodes are [`FunctionName`](enum.FunctionName.html)s, so that functions
+/// with numbered names (e.g., as produced by `opt -strip`) are distinguished
+/// from each other, and from functions with the same number in other
+/// `Module`s.
+///
+/// Calls through a `GlobalAlias` are treated as direct calls of the aliasee.
+/// Calls through a `GlobalIFunc` are treated as indirect calls which may call
+/// any function the IFunc's resolver may return (or, if those can't be
+/// determined, any function of the appropriate type). Calls through function
+/// pointers loaded from constant tables (see
+/// [`FunctionPtrTables`](struct.FunctionPtrTables.html)) may only call the
+/// functions in the corresponding part of the table.
+///
+/// Calls of `llvm.experimental.gc.statepoint` (as used by garbage-collected
+/// languages) are treated as calls of the function they wrap, and calls of
+/// `llvm.experimental.gc.relocate` and `llvm.experimental.gc.result` are
+/// ignored, as they don't call anything. `llvm-ir` doesn't expose operand
+/// bundles (such as `"deopt"` or `"gc-live"` bundles), so they are ignored;
+/// this doesn't affect the callee of a call carrying them.
 ///
 /// To construct a `CallGraph`, use [`ModuleAnalysis`](struct.ModuleAnalysis.html)
 /// or [`CrossModuleAnalysis`](struct.CrossModuleAnalysis.html).
 pub struct CallGraph<'m> {
     /// the call graph itself. Nodes are function names, and an edge from F to G
-    /// indicates F may call G
-    graph: DiGraphMap<&'m str, ()>,
+    /// indicates F may call G. Each edge's weight is the list of call sites in
+    /// F which may call G
+    pub(crate) graph: DiGraphMap<FunctionName<'m>, Vec<CallSite<'m>>>,
+    /// Map from the name of each function which is defined (not just declared)
+    /// in the analyzed `Module`(s) to the name of the `Module` defining it
+    defined: HashMap<FunctionName<'m>, &'m str>,
+    /// Call sites which pass the address of a function as an argument
+    callbacks: Vec<CallbackRegistration<'m>>,
+}
+
+/// A `Call`, `Invoke`, or `CallBr` instruction
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct CallSite<'m> {
+    /// Name of the function containing the call
+    pub caller: FunctionName<'m>,
+    /// Name of the basic block containing the call
+    pub block: &'m Name,
+    /// Index of the call within the basic block. If the call is the block's
+    /// terminator (i.e., an `Invoke` or `CallBr`), this is equal to the number
+    /// of (non-terminator) instructions in the block.
+    pub index: usize,
+    /// Whether the call is a `Call`, an `Invoke`, or a `CallBr`
+    pub kind: CallSiteKind,
+    /// Whether the call is a direct call of the callee, or a call through a
+    /// function pointer which may point to the callee
+    pub edge_kind: CallEdgeKind,
+    /// Whether the call is a tail call, i.e., a `Call` marked `tail` or
+    /// `musttail`. (`llvm-ir` does not distinguish these two markers.) This is
+    /// always `false` for `Invoke`s and `CallBr`s.
+    pub is_tail_call: bool,
+}
+
+/// The kind of instruction at a [`CallSite`](struct.CallSite.html)
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum CallSiteKind {
+    /// A `Call` instruction
+    Call,
+    /// An `Invoke` terminator
+    Invoke,
+    /// A `CallBr` terminator
+    CallBr,
+}
+
+/// How a call site calls a particular callee
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub enum CallEdgeKind {
+    /// The call site calls the callee directly, by name
+    Direct,
+    /// The call site calls through a function pointer, which may (or may not)
+    /// point to the callee. Such edges are conservative guesses: this analysis
+    /// assumes that function pointers may point to any function in the
+    /// analyzed `Module`(s) that has the appropriate type.
+    Indirect,
+    /// The call site spawns a thread whose entry function is the callee. Such
+    /// edges only appear in call graphs returned by
+    /// [`CallGraph::with_thread_spawn_edges()`](struct.CallGraph.html#method.with_thread_spawn_edges).
+    Spawn,
+    /// The call site passes the callee's address as an argument to some other
+    /// function, which may call it. Such edges only appear in call graphs
+    /// returned by [`CallGraph::with_callback_edges()`](struct.CallGraph.html#method.with_callback_edges).
+    Callback,
+}
+
+/// A call site which passes the address of a function as an argument, e.g.,
+/// the comparator passed to `qsort`
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct CallbackRegistration<'m> {
+    /// The call site passing the function's address. Its `edge_kind` is
+    /// `CallEdgeKind::Callback`.
+    pub site: CallSite<'m>,
+    /// Name of the function called at `site` (e.g., `qsort`), or `None` if
+    /// `site` calls through a function pointer
+    pub callee: Option<FunctionName<'m>>,
+    /// Name of the function whose address is passed
+    pub callback: FunctionName<'m>,
+    /// Index of the argument which is the function's address
+    pub arg_index: usize,
+}
+
+/// The name of a function, as used throughout this crate (e.g., for the nodes
+/// of the [`CallGraph`](struct.CallGraph.html), or in
+/// [`ModuleAnalysis::fn_analysis()`](struct.ModuleAnalysis.html#method.fn_analysis)).
+///
+/// Functions with numbered names (e.g., as produced by `opt -strip`) have the
+/// empty string as their name in `llvm-ir`, so they are instead identified by
+/// their number and the `Module` containing them: `@0` in one `Module` is a
+/// different function than `@0` in another.
+///
+/// A `FunctionName` can be created from a `&str`, giving a `Named` function;
+/// so methods taking a `FunctionName` can also be passed a plain name, e.g.
+/// `call_graph.callers("foo")`. It can also be compared with a `&str`, and is
+/// displayed as the function's name, or for a numbered function as `@`
+/// followed by its number, e.g. `@0`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FunctionName<'m> {
+    /// A function with a name, e.g. `main`
+    Named(&'m str),
+    /// A function with a numbered name
+    Numbered {
+        /// Name of the `Module` containing the function
+        module: &'m str,
+        /// The number of the function's `Name` in `llvm-ir`. `llvm-ir` numbers
+        /// defined functions before declared ones, so this may differ from the
+        /// function's number in LLVM assembly.
+        n: usize,
+    },
+}
+
+impl<'m> FunctionName<'m> {
+    /// Get the function's name, or `None` for a function with a numbered name
+    pub fn as_named(self) -> Option<&'m str> {
+        match self {
+            Self::Named(name) => Some(name),
+            Self::Numbered { .. } => None,
+        }
+    }
+
+    /// Get the name of the `Module` containing the function, for a function
+    /// with a numbered name; or `None` for a function with a name, which may be
+    /// declared or defined in several `Module`s
+    pub fn module(self) -> Option<&'m str> {
+        match self {
+            Self::Named(_) => None,
+            Self::Numbered { module, .. } => Some(module),
+        }
+    }
+}
+
+impl<'m> From<&'m str> for FunctionName<'m> {
+    fn from(name: &'m str) -> Self {
+        Self::Named(name)
+    }
+}
+
+impl<'m, 'a> From<&'a &'m str> for FunctionName<'m> {
+    fn from(name: &'a &'m str) -> Self {
+        Self::Named(name)
+    }
+}
+
+impl<'m> From<&'m String> for FunctionName<'m> {
+    fn from(name: &'m String) -> Self {
+        Self::Named(name)
+    }
+}
+
+impl<'m> fmt::Display for FunctionName<'m> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Named(name) => write!(f, "{}", name),
+            Self::Numbered { n, .. } => write!(f, "@{}", n),
+        }
+    }
+}
+
+/// Debug-formatted like a string (e.g., `"main"`), or for a numbered function,
+/// like `@0 (in "module.bc")`
+impl<'m> fmt::Debug for FunctionName<'m> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Named(name) => write!(f, "{:?}", name),
+            Self::Numbered { module, n } => write!(f, "@{} (in {:?})", n, module),
+        }
+    }
+}
+
+impl<'m> PartialEq<str> for FunctionName<'m> {
+    fn eq(&self, other: &str) -> bool {
+        match self {
+            Self::Named(name) => *name == other,
+            Self::Numbered { n, .. } => other.strip_prefix('@') == Some(n.to_string().as_str()),
+        }
+    }
+}
+
+impl<'m, 'a> PartialEq<&'a str> for FunctionName<'m> {
+    fn eq(&self, other: &&'a str) -> bool {
+        self == *other
+    }
+}
+
+impl<'m> PartialEq<FunctionName<'m>> for str {
+    fn eq(&self, other: &FunctionName<'m>) -> bool {
+        other == self
+    }
+}
+
+impl<'m, 'a> PartialEq<FunctionName<'m>> for &'a str {
+    fn eq(&self, other: &FunctionName<'m>) -> bool {
+        other == *self
+    }
+}
+
+/// Functions are ordered by their names as displayed, so that e.g. sorting a
+/// list of functions sorts them by name. Numbered functions with the same
+/// number are then ordered by the name of their `Module`.
+impl<'m> Ord for FunctionName<'m> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Named(a), Self::Named(b)) => a.cmp(b),
+            _ => (self.to_string(), self.module()).cmp(&(other.to_string(), other.module())),
+        }
+    }
+}
+
+impl<'m> PartialOrd for FunctionName<'m> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 impl<'m> CallGraph<'m> {
+    /// Well-known functions which spawn a thread, each with the index of its
+    /// argument which is the new thread's entry function, for use with
+    /// [`thread_spawns()`](#method.thread_spawns) and
+    /// [`with_thread_spawn_edges()`](#method.with_thread_spawn_edges).
+    ///
+    /// Rust's `std::thread::spawn` takes a closure rather than a function
+    /// pointer, so it is not included here.
+    pub const THREAD_SPAWN_APIS: &'static [(&'static str, usize)] = &[
+        ("pthread_create", 2),
+        ("thrd_create", 1),
+        ("CreateThread", 2),
+        ("_beginthread", 0),
+        ("_beginthreadex", 2),
+        ("g_thread_new", 1),
+        ("g_thread_try_new", 1),
+    ];
+
     pub(crate) fn new(
         modules: impl IntoIterator<Item = &'m Module>,
         functions_by_type: &FunctionsByType<'m>,
+        function_ptr_tables: &FunctionPtrTables<'m>,
     ) -> Self {
-        let mut graph: DiGraphMap<&'m str, ()> = DiGraphMap::new();
+        let modules: Vec<&'m Module> = modules.into_iter().collect();
+        let mut graph: DiGraphMap<FunctionName<'m>, Vec<CallSite<'m>>> = DiGraphMap::new();
+        let mut defined = HashMap::new();
+        let aliases = alias_targets(&modules);
+        let ifuncs = ifunc_targets(&modules, &aliases);
+
+        let add_site =
+            |graph: &mut DiGraphMap<_, Vec<_>>, callee: FunctionName<'m>, site: CallSite<'m>| {
+                match graph.edge_weight_mut(site.caller, callee) {
+                    Some(sites) => sites.push(site),
+                    None => {
+                        graph.add_edge(site.caller, callee, vec![site]);
+                    }
+                }
+            };
+
+        let functions: HashSet<FunctionName<'m>> = modules
+            .iter()
+            .flat_map(|module| function_names(module))
+            .collect();
+        // counted once per module, rather than for each call site
+        let num_numbered: HashMap<&'m str, usize> = modules
+            .iter()
+            .map(|module| (module.name.as_str(), num_numbered_functions(module)))
+            .collect();
+        let function_name = |module: &'m Module, name: &'m Name| {
+            global_name(module, num_numbered[module.name.as_str()], name)
+        };
+        let mut callbacks = vec![];
+
+        let add_callbacks = |callbacks: &mut Vec<CallbackRegistration<'m>>,
+                             site: CallSite<'m>,
+                             call: &CallOrInvoke<'m>| {
+            // for a statepoint, the wrapped function is the callee, and
+            // argument indices are relative to the wrapped call
+            let (callee, arguments) = match call.statepoint_call() {
+                Some((target, arguments)) => (operand_global(target), arguments),
+                None => (
+                    call.callee().as_ref().right().and_then(operand_global),
+                    call.arguments(),
+                ),
+            };
+            let callee = callee
+                .and_then(|name| function_name(call.module(), name))
+                .map(|name| resolve_alias(&aliases, name));
+            for (arg_index, (arg, _)) in arguments.iter().enumerate() {
+                if let Operand::ConstantOperand(cref) = arg {
+                    let name =
+                        callee_global(cref).and_then(|name| function_name(call.module(), name));
+                    if let Some(name) = name {
+                        let callback = resolve_alias(&aliases, name);
+                        if functions.contains(&callback) {
+                            callbacks.push(CallbackRegistration {
+                                site: CallSite {
+                                    edge_kind: CallEdgeKind::Callback,
+                                    ..site
+                                },
+                                callee,
+                                callback,
+                                arg_index,
+                            });
+                        }
+                    }
+                }
+            }
+        };
 
         let add_edge_for_call = |graph: &mut DiGraphMap<_, _>,
-                                 caller: &'m str,
+                                 callbacks: &mut Vec<_>,
+                                 caller: &'m Function,
+                                 site: CallSite<'m>,
                                  call: CallOrInvoke<'m>| {
-            match call.callee() {
-                Either::Right(Operand::ConstantOperand(cref)) => {
-                    match cref.as_ref() {
-                        Constant::GlobalReference { name: Name::Name(name), .. } => {
-                            graph.add_edge(caller, name, ());
+            add_callbacks(callbacks, site, &call);
+            let callee = match call.callee() {
+                Either::Right(op) => op,
+                Either::Left(_) => return, // ignore calls to inline assembly
+            };
+            if call.is_statepoint_projection() {
+                return; // these only extract values from a statepoint's token
+            }
+            // A call of `llvm.experimental.gc.statepoint` is really a call of
+            // the function it wraps. With opaque pointers, we don't know the
+            // type of a wrapped function pointer, so if that's what it wraps,
+            // we can't find the functions it may point to by their type.
+            let statepoint_target = call.statepoint_call().map(|(target, _)| target);
+            let callee = statepoint_target.unwrap_or(callee);
+            let callee_ty = || match statepoint_target {
+                Some(target) => call.statepoint_target_ty(target),
+                None => call.callee_ty(),
+            };
+            // `None` for a function pointer other than a constant global
+            let callee_name =
+                operand_global(callee).and_then(|name| function_name(call.module(), name));
+            let indirect_site = CallSite {
+                edge_kind: CallEdgeKind::Indirect,
+                ..site
+            };
+            // Calls through an alias are direct calls of the aliasee. Calls
+            // through an IFunc may call any function its resolver may return,
+            // and are treated like calls through a function pointer.
+            let callee_name = callee_name.map(|name| resolve_alias(&aliases, name));
+            match callee_name.map(|name| (name, name.as_named().and_then(|name| ifuncs.get(name))))
+            {
+                Some((_, Some(Some(targets)))) => {
+                    for &target in targets {
+                        add_site(graph, target, indirect_site);
+                    }
+                }
+                Some((name, None)) if functions.contains(&name) => add_site(graph, name, site),
+                Some((_, Some(None))) => {
+                    if let Some(ty) = callee_ty() {
+                        for target in functions_by_type.functions_with_type(&ty) {
+                            add_site(graph, target, indirect_site);
                         }
-                        Constant::GlobalReference { name, .. } => {
-                            unimplemented!("Call of a function with a numbered name: {name:?}")
+                    }
+                }
+                _ => {
+                    // This is a call through a function pointer, or of a
+                    // global which isn't a function (e.g., a global variable,
+                    // or an alias we couldn't resolve).
+                    // If the function pointer was loaded from a constant table,
+                    // it can only point to the functions in that part of the
+                    // table. Otherwise, assume that it could point to any
+                    // function in the current module that has the appropriate
+                    // type
+                    match function_ptr_tables.possible_targets(call.module(), caller, callee) {
+                        Some(targets) => {
+                            for target in targets {
+                                add_site(graph, target, indirect_site);
+                            }
                         }
-                        _ => {
-                            // a constant function pointer.
-                            // Assume that this function pointer could point
-                            // to any function in the current module that has
-                            // the appropriate type
-                            for target in functions_by_type.functions_with_type(&call.callee_ty()) {
-                                graph.add_edge(caller, target, ());
+                        None => {
+                            if let Some(ty) = callee_ty() {
+                                for target in functions_by_type.functions_with_type(&ty) {
+                                    add_site(graph, target, indirect_site);
+                                }
                             }
                         }
                     }
                 }
-                Either::Right(_) => {
-                    // Assume that this function pointer could point to any
-                    // function in the current module that has the
-                    // appropriate type
-                    for target in functions_by_type.functions_with_type(&call.callee_ty()) {
-                        graph.add_edge(caller, target, ());
-                    }
-                }
-                Either::Left(_) => {} // ignore calls to inline assembly
             }
         };
 
-        // Find all call (and Invoke) instructions and add the appropriate edges
-        for module in modules {
-            for f in &module.functions {
-                graph.add_node(&f.name); // just to ensure all functions end up getting nodes in the graph by the end
+        // Find all call (and Invoke and CallBr) instructions and add the appropriate edges.
+        // Sites are initially marked `Direct`; `add_edge_for_call` marks them
+        // `Indirect` as appropriate
+        for &module in &modules {
+            for (f, name) in functions_with_names(module) {
+                graph.add_node(name); // just to ensure all functions end up getting nodes in the graph by the end
+                defined.insert(name, module.name.as_str());
                 for bb in &f.basic_blocks {
-                    for inst in &bb.instrs {
+                    for (index, inst) in bb.instrs.iter().enumerate() {
                         if let Instruction::Call(call) = inst {
                             add_edge_for_call(
                                 &mut graph,
-                                &f.name,
+                                &mut callbacks,
+                                f,
+                                CallSite {
+                                    caller: name,
+                                    block: &bb.name,
+                                    index,
+                                    kind: CallSiteKind::Call,
+                                    edge_kind: CallEdgeKind::Direct,
+                                    is_tail_call: call.is_tail_call,
+                                },
                                 CallOrInvoke::Call { call, module },
                             );
                         }
@@ -77,15 +446,227 @@ impl<'m> CallGraph<'m> {
                     if let Terminator::Invoke(invoke) = &bb.term {
                         add_edge_for_call(
                             &mut graph,
-                            &f.name,
+                            &mut callbacks,
+                            f,
+                            CallSite {
+                                caller: name,
+                                block: &bb.name,
+                                index: bb.instrs.len(),
+                                kind: CallSiteKind::Invoke,
+                                edge_kind: CallEdgeKind::Direct,
+                                is_tail_call: false,
+                            },
                             CallOrInvoke::Invoke { invoke, module },
                         );
                     }
+                    if let Terminator::CallBr(callbr) = &bb.term {
+                        add_edge_for_call(
+                            &mut graph,
+                            &mut callbacks,
+                            f,
+                            CallSite {
+                                caller: name,
+                                block: &bb.name,
+                                index: bb.instrs.len(),
+                                kind: CallSiteKind::CallBr,
+                                edge_kind: CallEdgeKind::Direct,
+                                is_tail_call: false,
+                            },
+                            CallOrInvoke::CallBr { callbr, module },
+                        );
+                    }
                 }
             }
         }
 
-        Self { graph }
+        Self {
+            graph,
+            defined,
+            callbacks,
+        }
+    }
+
+    /// Get the underlying `petgraph` graph, e.g., to run `petgraph` algorithms
+    /// on it. Nodes are function names, and an edge from F to G indicates F may
+    /// call G; each edge's weight is the list of call sites in F which may
+    /// call G (see [`call_sites()`](#method.call_sites)).
+    pub fn inner(&self) -> &DiGraphMap<FunctionName<'m>, Vec<CallSite<'m>>> {
+        &self.graph
+    }
+
+    /// Convert the call graph to a `petgraph` `Graph` (e.g., for algorithms
+    /// which require a `Graph` rather than a `GraphMap`), along with a map from
+    /// each function name to its `NodeIndex` in the `Graph`. Node and edge
+    /// weights are as in [`inner()`](#method.inner).
+    pub fn to_graph(
+        &self,
+    ) -> (
+        DiGraph<FunctionName<'m>, Vec<CallSite<'m>>>,
+        HashMap<FunctionName<'m>, NodeIndex>,
+    ) {
+        let graph = self.graph.clone().into_graph::<u32>();
+        let indices = graph.node_indices().map(|idx| (graph[idx], idx)).collect();
+        (graph, indices)
+    }
+
+    /// Get a copy of this `CallGraph` containing only the functions for which
+    /// `keep` returns `true`. Edges to or from the other functions are dropped,
+    /// so e.g. `callees()` on the resulting `CallGraph` will never return a
+    /// function for which `keep` returned `false`.
+    pub fn filtered(&self, keep: impl Fn(FunctionName<'m>) -> bool) -> Self {
+        let mut graph = DiGraphMap::new();
+        for func in self.graph.nodes().filter(|&func| keep(func)) {
+            graph.add_node(func);
+        }
+        for (caller, callee, sites) in self.graph.all_edges() {
+            if graph.contains_node(caller) && graph.contains_node(callee) {
+                graph.add_edge(caller, callee, sites.clone());
+            }
+        }
+        let defined = self
+            .defined
+            .iter()
+            .filter(|(&func, _)| keep(func))
+            .map(|(&func, &module)| (func, module))
+            .collect();
+        let callbacks = self
+            .callbacks
+            .iter()
+            .filter(|reg| keep(reg.site.caller) && keep(reg.callback))
+            .cloned()
+            .collect();
+        Self {
+            graph,
+            defined,
+            callbacks,
+        }
+    }
+
+    /// Get a copy of this `CallGraph` with additional edges for callbacks: for
+    /// each of the [`callback_registrations()`](#method.callback_registrations),
+    /// an edge from the function containing the registration to the callback,
+    /// whose call site is the registration's call site (with
+    /// `CallEdgeKind::Callback`). This models the callee of that call site
+    /// (e.g., `qsort`) as possibly calling the callback on the caller's behalf.
+    ///
+    /// Without these edges, functions which are only ever called as callbacks
+    /// (e.g., event handlers) appear to be uncalled.
+    pub fn with_callback_edges(&self) -> Self {
+        let mut graph = self.graph.clone();
+        for reg in &self.callbacks {
+            match graph.edge_weight_mut(reg.site.caller, reg.callback) {
+                Some(sites) => sites.push(reg.site),
+                None => {
+                    graph.add_edge(reg.site.caller, reg.callback, vec![reg.site]);
+                }
+            }
+        }
+        Self {
+            graph,
+            defined: self.defined.clone(),
+            callbacks: self.callbacks.clone(),
+        }
+    }
+
+    /// Iterate over the call sites in the analyzed `Module`(s) which spawn a
+    /// thread, i.e., the [`callback_registrations()`](#method.callback_registrations)
+    /// which pass a thread entry function to one of the given thread-spawning
+    /// functions. Each of `apis` is the name of a thread-spawning function and
+    /// the index of its argument which is the entry function; for the usual
+    /// APIs, use [`THREAD_SPAWN_APIS`](#associatedconstant.THREAD_SPAWN_APIS).
+    pub fn thread_spawns<'s>(
+        &'s self,
+        apis: &'s [(&'s str, usize)],
+    ) -> impl Iterator<Item = &'s CallbackRegistration<'m>> + 's {
+        self.callbacks.iter().filter(move |reg| {
+            reg.callee.is_some_and(|callee| {
+                apis.iter()
+                    .any(|&(api, arg_index)| callee == api && arg_index == reg.arg_index)
+            })
+        })
+    }
+
+    /// Get a copy of this `CallGraph` with additional edges for spawned
+    /// threads: for each of the [`thread_spawns()`](#method.thread_spawns)
+    /// using the given `apis`, an edge from the function spawning the thread to
+    /// the thread's entry function, whose call site is the call of the
+    /// thread-spawning function (with `CallEdgeKind::Spawn`).
+    ///
+    /// Without these edges, thread entry functions appear to be uncalled.
+    pub fn with_thread_spawn_edges(&self, apis: &[(&str, usize)]) -> Self {
+        let mut graph = self.graph.clone();
+        for reg in self.thread_spawns(apis) {
+            let site = CallSite {
+                edge_kind: CallEdgeKind::Spawn,
+                ..reg.site
+            };
+            match graph.edge_weight_mut(site.caller, reg.callback) {
+                Some(sites) => sites.push(site),
+                None => {
+                    graph.add_edge(site.caller, reg.callback, vec![site]);
+                }
+            }
+        }
+        Self {
+            graph,
+            defined: self.defined.clone(),
+            callbacks: self.callbacks.clone(),
+        }
+    }
+
+    /// Iterate over every call site in the analyzed `Module`(s) which passes
+    /// the address of a function as an argument (e.g., the comparator passed
+    /// to `qsort`, or a handler passed to a registration function), in the
+    /// order the call sites appear.
+    ///
+    /// The call graph itself does not include edges for these callbacks; see
+    /// [`with_callback_edges()`](#method.with_callback_edges).
+    pub fn callback_registrations<'s>(
+        &'s self,
+    ) -> impl Iterator<Item = &'s CallbackRegistration<'m>> + 's {
+        self.callbacks.iter()
+    }
+
+    /// Iterate over the call sites which pass the address of the function with
+    /// the given name as an argument (see
+    /// [`callback_registrations()`](#method.callback_registrations)).
+    pub fn callback_registrations_of<'s>(
+        &'s self,
+        func_name: impl Into<FunctionName<'s>>,
+    ) -> impl Iterator<Item = &'s CallbackRegistration<'m>> + 's {
+        let func_name = func_name.into();
+        let regs: Vec<_> = self
+            .callbacks
+            .iter()
+            .filter(|reg| reg.callback == func_name)
+            .collect();
+        regs.into_iter()
+    }
+
+    /// Get a copy of this `CallGraph` containing only the functions defined in
+    /// the `Module`s with the given names (e.g., to exclude vendored
+    /// dependencies from a whole-program call graph), plus the functions which
+    /// are only declared (not defined) in the analyzed `Module`(s), such as
+    /// library functions. This is equivalent to calling
+    /// [`filtered()`](#method.filtered) with an appropriate predicate.
+    pub fn restricted_to_modules(&self, mod_names: &[&str]) -> Self {
+        self.filtered(|func| match self.defined.get(&func) {
+            Some(module) => mod_names.contains(module),
+            None => true,
+        })
+    }
+
+    /// Get a copy of this `CallGraph` without any LLVM intrinsics (functions
+    /// whose names begin with `llvm.`, such as `llvm.lifetime.start` or
+    /// `llvm.memcpy`). This is equivalent to calling
+    /// [`filtered()`](#method.filtered) with a predicate which rejects
+    /// intrinsics.
+    pub fn without_intrinsics(&self) -> Self {
+        self.filtered(|func| {
+            !func
+                .as_named()
+                .is_some_and(|name| name.starts_with("llvm."))
+        })
     }
 
     /// Get the names of functions in the analyzed `Module`(s) which may call the
@@ -95,7 +676,11 @@ impl<'m> CallGraph<'m> {
     /// any function in the analyzed `Module`(s) that has the appropriate type.
     ///
     /// Panics if the given function is not found in the analyzed `Module`(s).
-    pub fn callers<'s>(&'s self, func_name: &'m str) -> impl Iterator<Item = &'m str> + 's {
+    pub fn callers<'s>(
+        &'s self,
+        func_name: impl Into<FunctionName<'m>>,
+    ) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        let func_name = func_name.into();
         if !self.graph.contains_node(func_name) {
             panic!(
                 "callers(): function named {:?} not found in the Module(s)",
@@ -113,7 +698,11 @@ impl<'m> CallGraph<'m> {
     /// any function in the analyzed `Module`(s) that has the appropriate type.
     ///
     /// Panics if the given function is not found in the analyzed `Module`(s).
-    pub fn callees<'s>(&'s self, func_name: &'m str) -> impl Iterator<Item = &'m str> + 's {
+    pub fn callees<'s>(
+        &'s self,
+        func_name: impl Into<FunctionName<'m>>,
+    ) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        let func_name = func_name.into();
         if !self.graph.contains_node(func_name) {
             panic!(
                 "callees(): function named {:?} not found in the Module(s)",
@@ -123,50 +712,1171 @@ impl<'m> CallGraph<'m> {
         self.graph
             .neighbors_directed(func_name, Direction::Outgoing)
     }
+
+    /// Get the names of functions in the analyzed `Module`(s) which call the
+    /// given function directly, by name. Unlike [`callers()`](#method.callers),
+    /// this excludes functions which may call the given function only through
+    /// a function pointer.
+    ///
+    /// Panics if the given function is not found in the analyzed `Module`(s).
+    pub fn direct_callers<'s>(
+        &'s self,
+        func_name: impl Into<FunctionName<'m>>,
+    ) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        let func_name = func_name.into();
+        let callers: Vec<_> = self
+            .callers(func_name)
+            .filter(|&caller| self.edge_kind(caller, func_name) == Some(CallEdgeKind::Direct))
+            .collect();
+        callers.into_iter()
+    }
+
+    /// Get the names of functions in the analyzed `Module`(s) which are called
+    /// directly, by name, by the given function. Unlike
+    /// [`callees()`](#method.callees), this excludes functions which may be
+    /// called only through a function pointer.
+    ///
+    /// Panics if the given function is not found in the analyzed `Module`(s).
+    pub fn direct_callees<'s>(
+        &'s self,
+        func_name: impl Into<FunctionName<'m>>,
+    ) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        let func_name = func_name.into();
+        let callees: Vec<_> = self
+            .callees(func_name)
+            .filter(|&callee| self.edge_kind(func_name, callee) == Some(CallEdgeKind::Direct))
+            .collect();
+        callees.into_iter()
+    }
+
+    /// Get the (mangled) names of the functions in the call graph whose
+    /// demangled name is `demangled`, in sorted order.
+    ///
+    /// For Rust functions, `demangled` may include or omit the trailing hash
+    /// (e.g., `std::panicking::begin_panic`). For C++ functions, it may
+    /// include or omit the parameter types (e.g., `foo(int)` or just `foo`), so
+    /// an overloaded name may match several functions. Names which aren't
+    /// mangled (e.g., C functions) match themselves.
+    ///
+    /// This method is only available with the `demangle` feature.
+    #[cfg(feature = "demangle")]
+    pub fn resolve_demangled(&self, demangled: &str) -> Vec<FunctionName<'m>> {
+        let mut funcs: Vec<FunctionName<'m>> = self
+            .graph
+            .nodes()
+            .filter(|func| {
+                *func == demangled
+                    || func.as_named().is_some_and(|name| {
+                        crate::demangle::demangled_forms(name)
+                            .iter()
+                            .any(|form| form == demangled)
+                    })
+            })
+            .collect();
+        funcs.sort_unstable();
+        funcs
+    }
+
+    /// Get the (mangled) names of the functions which may call any function
+    /// with the given demangled name (see
+    /// [`resolve_demangled()`](#method.resolve_demangled)), in sorted order.
+    ///
+    /// This method is only available with the `demangle` feature.
+    ///
+    /// Panics if no function with the given demangled name is found in the
+    /// analyzed `Module`(s).
+    #[cfg(feature = "demangle")]
+    pub fn callers_demangled(&self, demangled: &str) -> Vec<FunctionName<'m>> {
+        let funcs = self.resolve_demangled(demangled);
+        if funcs.is_empty() {
+            panic!(
+                "callers_demangled(): function with demangled name {:?} not found in the Module(s)",
+                demangled
+            )
+        }
+        let mut callers: Vec<FunctionName<'m>> = funcs
+            .into_iter()
+            .flat_map(|func| self.callers(func))
+            .collect();
+        callers.sort_unstable();
+        callers.dedup();
+        callers
+    }
+
+    /// Get the (mangled) names of the functions which may be called by any
+    /// function with the given demangled name (see
+    /// [`resolve_demangled()`](#method.resolve_demangled)), in sorted order.
+    ///
+    /// This method is only available with the `demangle` feature.
+    ///
+    /// Panics if no function with the given demangled name is found in the
+    /// analyzed `Module`(s).
+    #[cfg(feature = "demangle")]
+    pub fn callees_demangled(&self, demangled: &str) -> Vec<FunctionName<'m>> {
+        let funcs = self.resolve_demangled(demangled);
+        if funcs.is_empty() {
+            panic!(
+                "callees_demangled(): function with demangled name {:?} not found in the Module(s)",
+                demangled
+            )
+        }
+        let mut callees: Vec<FunctionName<'m>> = funcs
+            .into_iter()
+            .flat_map(|func| self.callees(func))
+            .collect();
+        callees.sort_unstable();
+        callees.dedup();
+        callees
+    }
+
+    /// Get the names of functions in the analyzed `Module`(s) which may be
+    /// called by the given function from a tail call site (see
+    /// [`CallSite::is_tail_call`](struct.CallSite.html#structfield.is_tail_call)).
+    /// A callee is included if any call site which may call it is a tail call.
+    ///
+    /// Panics if the given function is not found in the analyzed `Module`(s).
+    pub fn tail_callees<'s>(
+        &'s self,
+        func_name: impl Into<FunctionName<'m>>,
+    ) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        let func_name = func_name.into();
+        let callees: Vec<_> = self
+            .callees(func_name)
+            .filter(|&callee| self.has_tail_call(func_name, callee))
+            .collect();
+        callees.into_iter()
+    }
+
+    /// Get the names of functions in the analyzed `Module`(s) which may call
+    /// the given function from a tail call site (see
+    /// [`tail_callees()`](#method.tail_callees)).
+    ///
+    /// Panics if the given function is not found in the analyzed `Module`(s).
+    pub fn tail_callers<'s>(
+        &'s self,
+        func_name: impl Into<FunctionName<'m>>,
+    ) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        let func_name = func_name.into();
+        let callers: Vec<_> = self
+            .callers(func_name)
+            .filter(|&caller| self.has_tail_call(caller, func_name))
+            .collect();
+        callers.into_iter()
+    }
+
+    fn has_tail_call(&self, caller: FunctionName<'m>, callee: FunctionName<'m>) -> bool {
+        self.graph
+            .edge_weight(caller, callee)
+            .is_some_and(|sites| sites.iter().any(|site| site.is_tail_call))
+    }
+
+    /// Get the kind of the call-graph edge from `caller` to `callee`: `Direct`
+    /// if any call site in `caller` calls `callee` directly, `Indirect` if
+    /// `caller` may call `callee` only through function pointers, `Spawn` if
+    /// `caller` only spawns threads running `callee` (see
+    /// [`with_thread_spawn_edges()`](#method.with_thread_spawn_edges)),
+    /// `Callback` if `caller` only passes `callee` as a callback (see
+    /// [`with_callback_edges()`](#method.with_callback_edges)), or `None` if
+    /// `caller` may not call `callee` at all.
+    pub fn edge_kind<'n>(
+        &self,
+        caller: impl Into<FunctionName<'n>>,
+        callee: impl Into<FunctionName<'n>>,
+    ) -> Option<CallEdgeKind> {
+        self.graph
+            .edge_weight(caller.into(), callee.into())
+            .and_then(|sites| sites.iter().map(|site| site.edge_kind).min())
+    }
+
+    /// Get the number of call sites in the function `caller` which may call the
+    /// function `callee` (see [`call_sites()`](#method.call_sites)). This is 0
+    /// if `caller` may not call `callee`.
+    ///
+    /// Panics if `caller` is not found in the analyzed `Module`(s).
+    pub fn call_count(
+        &self,
+        caller: impl Into<FunctionName<'m>>,
+        callee: impl Into<FunctionName<'m>>,
+    ) -> usize {
+        self.call_sites(caller, callee).count()
+    }
+
+    /// Get the names of functions in the analyzed `Module`(s) which may
+    /// (directly or transitively) call the given function, in breadth-first
+    /// order. The given function itself is included only if it is (directly or
+    /// mutually) recursive.
+    ///
+    /// This analysis conservatively assumes that function pointers may point to
+    /// any function in the analyzed `Module`(s) that has the appropriate type.
+    ///
+    /// Panics if the given function is not found in the analyzed `Module`(s).
+    pub fn transitive_callers<'s>(
+        &'s self,
+        func_name: impl Into<FunctionName<'m>>,
+    ) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        let func_name = func_name.into();
+        if !self.graph.contains_node(func_name) {
+            panic!(
+                "transitive_callers(): function named {:?} not found in the Module(s)",
+                func_name
+            )
+        }
+        self.transitive_neighbors(func_name, Direction::Incoming)
+    }
+
+    /// Get the names of functions in the analyzed `Module`(s) which may be
+    /// (directly or transitively) called by the given function, in
+    /// breadth-first order. The given function itself is included only if it
+    /// is (directly or mutually) recursive.
+    ///
+    /// This analysis conservatively assumes that function pointers may point to
+    /// any function in the analyzed `Module`(s) that has the appropriate type.
+    ///
+    /// Panics if the given function is not found in the analyzed `Module`(s).
+    pub fn transitive_callees<'s>(
+        &'s self,
+        func_name: impl Into<FunctionName<'m>>,
+    ) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        let func_name = func_name.into();
+        if !self.graph.contains_node(func_name) {
+            panic!(
+                "transitive_callees(): function named {:?} not found in the Module(s)",
+                func_name
+            )
+        }
+        self.transitive_neighbors(func_name, Direction::Outgoing)
+    }
+
+    /// Breadth-first traversal of the neighbors of `func_name` in the given
+    /// direction, not including `func_name` itself unless it is reachable from
+    /// itself
+    fn transitive_neighbors<'s>(
+        &'s self,
+        func_name: FunctionName<'m>,
+        dir: Direction,
+    ) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        let mut seen: HashSet<FunctionName<'m>> = HashSet::new();
+        let mut queue: VecDeque<FunctionName<'m>> =
+            self.graph.neighbors_directed(func_name, dir).collect();
+        std::iter::from_fn(move || {
+            while let Some(func) = queue.pop_front() {
+                if seen.insert(func) {
+                    queue.extend(self.graph.neighbors_directed(func, dir));
+                    return Some(func);
+                }
+            }
+            None
+        })
+    }
+
+    /// Is the given function only declared (not defined) in the analyzed
+    /// `Module`(s)? Calls of such functions are calls into code which is not
+    /// available for analysis, e.g., library functions or LLVM intrinsics.
+    ///
+    /// Panics if the given function is not found in the call graph.
+    pub fn is_declaration(&self, func_name: impl Into<FunctionName<'m>>) -> bool {
+        let func_name = func_name.into();
+        if !self.graph.contains_node(func_name) {
+            panic!(
+                "is_declaration(): function named {:?} not found in the Module(s)",
+                func_name
+            )
+        }
+        !self.defined.contains_key(&func_name)
+    }
+
+    /// Get the names of the functions in the call graph which are only
+    /// declared (not defined) in the analyzed `Module`(s). See
+    /// [`is_declaration()`](#method.is_declaration).
+    pub fn declarations<'s>(&'s self) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        self.graph
+            .nodes()
+            .filter(move |func| !self.defined.contains_key(func))
+    }
+
+    /// Get the name of the `Module` which defines the given function, or
+    /// `None` if the function is only declared (not defined) in the analyzed
+    /// `Module`(s).
+    ///
+    /// Panics if the given function is not found in the call graph.
+    pub fn defining_module(&self, func_name: impl Into<FunctionName<'m>>) -> Option<&'m str> {
+        let func_name = func_name.into();
+        if !self.graph.contains_node(func_name) {
+            panic!(
+                "defining_module(): function named {:?} not found in the Module(s)",
+                func_name
+            )
+        }
+        self.defined.get(&func_name).copied()
+    }
+
+    /// Get the names of the functions defined in the `Module` with the given
+    /// name.
+    pub fn functions_in_module<'s>(
+        &'s self,
+        mod_name: &'s str,
+    ) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        self.defined
+            .iter()
+            .filter(move |(_, &module)| module == mod_name)
+            .map(|(&func, _)| func)
+    }
+
+    /// Get the names of the functions in the call graph which have no callers
+    /// (other than, possibly, themselves). These are the natural starting
+    /// points for top-down traversals of the call graph.
+    ///
+    /// This analysis conservatively assumes that function pointers may point to
+    /// any function in the analyzed `Module`(s) that has the appropriate type.
+    pub fn roots<'s>(&'s self) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        self.graph.nodes().filter(move |&func| {
+            self.graph
+                .neighbors_directed(func, Direction::Incoming)
+                .all(|caller| caller == func)
+        })
+    }
+
+    /// Get the names of the functions in the call graph which call no functions
+    /// (other than, possibly, themselves). These are the natural starting
+    /// points for bottom-up traversals of the call graph.
+    ///
+    /// This includes functions which are only declared (not defined) in the
+    /// analyzed `Module`(s).
+    pub fn leaves<'s>(&'s self) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        self.graph.nodes().filter(move |&func| {
+            self.graph
+                .neighbors_directed(func, Direction::Outgoing)
+                .all(|callee| callee == func)
+        })
+    }
+
+    /// Get the call sites in the function `caller` which may call the function
+    /// `callee`, in the order they appear in `caller`. If `caller` may not call
+    /// `callee`, the iterator will be empty.
+    ///
+    /// This analysis conservatively assumes that function pointers may point to
+    /// any function in the analyzed `Module`(s) that has the appropriate type,
+    /// so a call through a function pointer is a call site for each such
+    /// function.
+    ///
+    /// Panics if `caller` is not found in the analyzed `Module`(s).
+    pub fn call_sites<'s>(
+        &'s self,
+        caller: impl Into<FunctionName<'m>>,
+        callee: impl Into<FunctionName<'m>>,
+    ) -> impl Iterator<Item = &'s CallSite<'m>> + 's {
+        let (caller, callee) = (caller.into(), callee.into());
+        if !self.graph.contains_node(caller) {
+            panic!(
+                "call_sites(): function named {:?} not found in the Module(s)",
+                caller
+            )
+        }
+        self.graph.edge_weight(caller, callee).into_iter().flatten()
+    }
+
+    /// Get the functions which may be called at each call site, by the call
+    /// site's caller, block, and index. Only direct and indirect calls are
+    /// included (not thread spawns or callbacks); call sites which may not
+    /// call any function in the analyzed `Module`(s), such as calls to inline
+    /// assembly, are absent.
+    pub(crate) fn callees_by_site(
+        &self,
+    ) -> HashMap<(FunctionName<'m>, &'m Name, usize), Vec<FunctionName<'m>>> {
+        let mut callees: HashMap<_, Vec<FunctionName<'m>>> = HashMap::new();
+        for (_, callee, sites) in self.graph.all_edges() {
+            for site in sites {
+                if matches!(
+                    site.edge_kind,
+                    CallEdgeKind::Direct | CallEdgeKind::Indirect
+                ) {
+                    callees
+                        .entry((site.caller, site.block, site.index))
+                        .or_default()
+                        .push(callee);
+                }
+            }
+        }
+        callees
+    }
+
+    /// Get a shortest call path from the function `from` to the function `to`,
+    /// i.e., a shortest sequence of functions, starting with `from` and ending
+    /// with `to`, in which each function may call the next. Returns `None` if
+    /// `from` cannot (directly or transitively) call `to`. If `from` and `to`
+    /// are the same function, the path is just that function.
+    ///
+    /// This analysis conservatively assumes that function pointers may point to
+    /// any function in the analyzed `Module`(s) that has the appropriate type.
+    ///
+    /// Panics if `from` or `to` is not found in the analyzed `Module`(s).
+    pub fn shortest_path(
+        &self,
+        from: impl Into<FunctionName<'m>>,
+        to: impl Into<FunctionName<'m>>,
+    ) -> Option<Vec<FunctionName<'m>>> {
+        let (from, to) = (from.into(), to.into());
+        for &func in &[from, to] {
+            if !self.graph.contains_node(func) {
+                panic!(
+                    "shortest_path(): function named {:?} not found in the Module(s)",
+                    func
+                )
+            }
+        }
+        // map from each function visited to the function we reached it from
+        let mut preds: HashMap<FunctionName<'m>, FunctionName<'m>> = HashMap::new();
+        let mut queue = VecDeque::from(vec![from]);
+        while let Some(func) = queue.pop_front() {
+            if func == to {
+                let mut path = vec![to];
+                let mut cur = to;
+                while cur != from {
+                    cur = preds[&cur];
+                    path.push(cur);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for callee in self.graph.neighbors_directed(func, Direction::Outgoing) {
+                if callee != from && !preds.contains_key(&callee) {
+                    preds.insert(callee, func);
+                    queue.push_back(callee);
+                }
+            }
+        }
+        None
+    }
+
+    /// Enumerate the simple call paths from the function `from` to the function
+    /// `to`: sequences of distinct functions, starting with `from` and ending
+    /// with `to`, in which each function may call the next. If `from` and `to`
+    /// are the same function, the only path is just that function.
+    ///
+    /// Only paths with at most `max_calls` calls (i.e., at most `max_calls + 1`
+    /// functions) are returned, and at most `max_paths` paths are returned in
+    /// total; `None` means no limit. Since the number of paths may be
+    /// exponential in the size of the call graph, setting at least one of
+    /// these limits is recommended for large `Module`s.
+    ///
+    /// This analysis conservatively assumes that function pointers may point to
+    /// any function in the analyzed `Module`(s) that has the appropriate type.
+    ///
+    /// Panics if `from` or `to` is not found in the analyzed `Module`(s).
+    pub fn call_paths(
+        &self,
+        from: impl Into<FunctionName<'m>>,
+        to: impl Into<FunctionName<'m>>,
+        max_calls: Option<usize>,
+        max_paths: Option<usize>,
+    ) -> Vec<Vec<FunctionName<'m>>> {
+        let (from, to) = (from.into(), to.into());
+        for &func in &[from, to] {
+            if !self.graph.contains_node(func) {
+                panic!(
+                    "call_paths(): function named {:?} not found in the Module(s)",
+                    func
+                )
+            }
+        }
+        let max_paths = max_paths.unwrap_or(usize::MAX);
+        let mut paths = vec![];
+        if max_paths == 0 {
+            return paths;
+        }
+        if from == to {
+            paths.push(vec![from]);
+            return paths;
+        }
+        // depth-first search. `stack` holds the callees of each function on
+        // the current path which remain to be explored
+        let mut path = vec![from];
+        let mut stack = vec![self.graph.neighbors_directed(from, Direction::Outgoing)];
+        while let Some(callees) = stack.last_mut() {
+            match callees.next() {
+                Some(callee) if callee == to => {
+                    if max_calls.is_some_and(|max| path.len() > max) {
+                        continue;
+                    }
+                    let mut found = path.clone();
+                    found.push(to);
+                    paths.push(found);
+                    if paths.len() >= max_paths {
+                        break;
+                    }
+                }
+                Some(callee) => {
+                    let within_limit = match max_calls {
+                        Some(max) => path.len() < max,
+                        None => true,
+                    };
+                    if !path.contains(&callee) && within_limit {
+                        path.push(callee);
+                        stack.push(self.graph.neighbors_directed(callee, Direction::Outgoing));
+                    }
+                }
+                None => {
+                    stack.pop();
+                    path.pop();
+                }
+            }
+        }
+        paths
+    }
+
+    /// Can the function `from` (transitively) call the function `to`, without
+    /// going through any of the functions in `excluded`? That is, is there a
+    /// path in the call graph from `from` to `to` which doesn't pass through
+    /// any of the `excluded` functions?
+    ///
+    /// Every function is considered to reach itself. If `from` or `to` is in
+    /// `excluded`, this returns `false`.
+    ///
+    /// This analysis conservatively assumes that function pointers may point to
+    /// any function in the analyzed `Module`(s) that has the appropriate type.
+    ///
+    /// Panics if `from` or `to` is not found in the analyzed `Module`(s).
+    pub fn is_reachable_excluding(
+        &self,
+        from: impl Into<FunctionName<'m>>,
+        to: impl Into<FunctionName<'m>>,
+        excluded: &[FunctionName],
+    ) -> bool {
+        let to = to.into();
+        if !self.graph.contains_node(to) {
+            panic!(
+                "is_reachable_excluding(): function named {:?} not found in the Module(s)",
+                to
+            )
+        }
+        self.reachable_excluding(from, excluded).contains(&to)
+    }
+
+    /// Get the names of all functions which the function `from` can
+    /// (transitively) call without going through any of the functions in
+    /// `excluded`.
+    ///
+    /// The result includes `from` itself, unless `from` is in `excluded`, in
+    /// which case the result is empty.
+    ///
+    /// This analysis conservatively assumes that function pointers may point to
+    /// any function in the analyzed `Module`(s) that has the appropriate type.
+    ///
+    /// Panics if `from` is not found in the analyzed `Module`(s).
+    pub fn reachable_excluding(
+        &self,
+        from: impl Into<FunctionName<'m>>,
+        excluded: &[FunctionName],
+    ) -> HashSet<FunctionName<'m>> {
+        let from = from.into();
+        if !self.graph.contains_node(from) {
+            panic!(
+                "reachable_excluding(): function named {:?} not found in the Module(s)",
+                from
+            )
+        }
+        let mut reachable = HashSet::new();
+        if excluded.contains(&from) {
+            return reachable;
+        }
+        let mut worklist = vec![from];
+        while let Some(func) = worklist.pop() {
+            if reachable.insert(func) {
+                worklist.extend(
+                    self.graph
+                        .neighbors_directed(func, Direction::Outgoing)
+                        .filter(|callee| !excluded.contains(callee)),
+                );
+            }
+        }
+        reachable
+    }
+
+    /// Get the strongly connected components (SCCs) of the call graph, i.e.,
+    /// the groups of mutually recursive functions. Each function is in exactly
+    /// one SCC; functions which are not (mutually) recursive are in an SCC by
+    /// themselves.
+    ///
+    /// The SCCs are returned in reverse topological order, i.e., the SCC
+    /// containing a callee appears before the SCCs containing its callers
+    /// (unless they are in the same SCC). This is the order needed for
+    /// bottom-up interprocedural analyses. Functions within each SCC are
+    /// sorted by name.
+    pub fn sccs(&self) -> Vec<Vec<FunctionName<'m>>> {
+        petgraph::algo::tarjan_scc(&self.graph)
+            .into_iter()
+            .map(|mut scc| {
+                scc.sort_unstable();
+                scc
+            })
+            .collect()
+    }
+
+    /// Is the given function directly recursive, i.e., may it call itself?
+    ///
+    /// Panics if the given function is not found in the analyzed `Module`(s).
+    pub fn is_directly_recursive(&self, func_name: impl Into<FunctionName<'m>>) -> bool {
+        let func_name = func_name.into();
+        if !self.graph.contains_node(func_name) {
+            panic!(
+                "is_directly_recursive(): function named {:?} not found in the Module(s)",
+                func_name
+            )
+        }
+        self.graph.contains_edge(func_name, func_name)
+    }
+
+    /// Is the given function (directly or mutually) recursive, i.e., may it
+    /// (directly or transitively) call itself?
+    ///
+    /// Panics if the given function is not found in the analyzed `Module`(s).
+    pub fn is_recursive(&self, func_name: impl Into<FunctionName<'m>>) -> bool {
+        let func_name = func_name.into();
+        self.is_directly_recursive(func_name)
+            || self
+                .transitive_callees(func_name)
+                .any(|callee| callee == func_name)
+    }
+
+    /// Get the recursion cycles in the call graph: the SCCs (see
+    /// [`sccs()`](#method.sccs)) which contain either more than one function
+    /// (mutual recursion) or a single directly recursive function.
+    ///
+    /// Like `sccs()`, these are returned in reverse topological order, and the
+    /// functions within each are sorted by name. Each recursion cycle may
+    /// contain several overlapping elementary cycles; these are not enumerated
+    /// separately.
+    pub fn recursion_cycles(&self) -> Vec<Vec<FunctionName<'m>>> {
+        self.sccs()
+            .into_iter()
+            .filter(|scc| scc.len() > 1 || self.graph.contains_edge(scc[0], scc[0]))
+            .collect()
+    }
+
+    /// Iterate over the SCCs of the call graph (see [`sccs()`](#method.sccs))
+    /// bottom-up: each SCC is yielded only after all of the SCCs containing
+    /// functions it may call (other than itself). Each group of mutually
+    /// recursive functions is yielded as a single unit.
+    ///
+    /// This is the canonical order for computing function summaries: when an
+    /// SCC is visited, summaries for all of its callees outside the SCC are
+    /// already available, and only the functions within the SCC need to be
+    /// iterated to a fixpoint.
+    pub fn sccs_bottom_up(&self) -> impl Iterator<Item = Vec<FunctionName<'m>>> {
+        self.sccs().into_iter()
+    }
+
+    /// Iterate over the SCCs of the call graph (see [`sccs()`](#method.sccs))
+    /// top-down: the reverse of [`sccs_bottom_up()`](#method.sccs_bottom_up),
+    /// so each SCC is yielded before any SCC containing functions it may call.
+    pub fn sccs_top_down(&self) -> impl Iterator<Item = Vec<FunctionName<'m>>> {
+        self.sccs().into_iter().rev()
+    }
+
+    /// Get the condensation of the call graph: the DAG whose nodes are the
+    /// call graph's SCCs (see [`sccs()`](#method.sccs)), with an edge from one
+    /// SCC to another if any function in the first may call any function in
+    /// the second.
+    pub fn condensation(&self) -> CallGraphCondensation<'m> {
+        let sccs = self.sccs();
+        let scc_indices: HashMap<FunctionName<'m>, usize> = sccs
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, scc)| scc.iter().map(move |&func| (func, idx)))
+            .collect();
+        let mut graph = DiGraphMap::with_capacity(sccs.len(), self.graph.edge_count());
+        for idx in 0..sccs.len() {
+            graph.add_node(idx);
+        }
+        for (caller, callee, _) in self.graph.all_edges() {
+            let (from, to) = (scc_indices[&caller], scc_indices[&callee]);
+            if from != to {
+                graph.add_edge(from, to, ());
+            }
+        }
+        CallGraphCondensation {
+            sccs,
+            scc_indices,
+            graph,
+        }
+    }
+
+    /// Build a [`ReachabilityIndex`](struct.ReachabilityIndex.html) for the
+    /// call graph, which answers "may this function (transitively) call that
+    /// function?" in constant time. Building the index is expensive, so this
+    /// is only worthwhile when making many such queries.
+    pub fn reachability_index(&self) -> ReachabilityIndex<'m> {
+        ReachabilityIndex::new(self)
+    }
+}
+
+/// The condensation of a [`CallGraph`](struct.CallGraph.html): a DAG whose
+/// nodes are the call graph's strongly connected components (SCCs), i.e., its
+/// groups of mutually recursive functions.
+///
+/// SCCs are identified by their index in [`sccs()`](#method.sccs), which is in
+/// reverse topological order (callees before callers).
+///
+/// To construct a `CallGraphCondensation`, use
+/// [`CallGraph::condensation()`](struct.CallGraph.html#method.condensation).
+pub struct CallGraphCondensation<'m> {
+    /// The SCCs, in reverse topological order
+    sccs: Vec<Vec<FunctionName<'m>>>,
+    /// Map from function name to the index of its SCC
+    scc_indices: HashMap<FunctionName<'m>, usize>,
+    /// The condensed graph. Nodes are SCC indices, and an edge from A to B
+    /// indicates some function in A may call some function in B
+    graph: DiGraphMap<usize, ()>,
+}
+
+impl<'m> CallGraphCondensation<'m> {
+    /// Get all of the SCCs, in reverse topological order (callees before
+    /// callers). Functions within each SCC are sorted by name.
+    pub fn sccs(&self) -> &[Vec<FunctionName<'m>>] {
+        &self.sccs
+    }
+
+    /// Get the number of SCCs
+    pub fn num_sccs(&self) -> usize {
+        self.sccs.len()
+    }
+
+    /// Get the functions in the SCC with the given index
+    ///
+    /// Panics if the index is out of range.
+    pub fn scc(&self, idx: usize) -> &[FunctionName<'m>] {
+        &self.sccs[idx]
+    }
+
+    /// Get the index of the SCC containing the given function.
+    ///
+    /// Panics if the given function is not found in the analyzed `Module`(s).
+    pub fn scc_index<'n>(&self, func_name: impl Into<FunctionName<'n>>) -> usize {
+        let func_name = func_name.into();
+        *self.scc_indices.get(&func_name).unwrap_or_else(|| {
+            panic!(
+                "scc_index(): function named {:?} not found in the Module(s)",
+                func_name
+            )
+        })
+    }
+
+    /// Get the indices of the SCCs containing functions which may be called by
+    /// functions in the SCC with the given index (not including that SCC
+    /// itself).
+    pub fn succs<'s>(&'s self, idx: usize) -> impl Iterator<Item = usize> + 's {
+        self.graph.neighbors_directed(idx, Direction::Outgoing)
+    }
+
+    /// Get the indices of the SCCs containing functions which may call
+    /// functions in the SCC with the given index (not including that SCC
+    /// itself).
+    pub fn preds<'s>(&'s self, idx: usize) -> impl Iterator<Item = usize> + 's {
+        self.graph.neighbors_directed(idx, Direction::Incoming)
+    }
+}
+
+/// Get the name of the function called, if `callee` (the function operand of a
+/// `Call` or `Invoke`) is a direct reference to a named function, possibly
+/// through casts (see `callee_global()`)
+pub(crate) fn direct_callee(callee: &Operand) -> Option<&str> {
+    match callee {
+        Operand::ConstantOperand(cref) => match callee_global(cref)? {
+            Name::Name(name) => Some(name),
+            Name::Number(_) => None,
+        },
+        _ => None,
+    }
+}
+
+/// Get the `Name` of the global referenced by `callee` (a constant function
+/// operand of a `Call` or `Invoke`), looking through any bitcasts, address
+/// space casts, or all-zero `GetElementPtr`s. These appear, e.g., in calls of
+/// functions declared without prototypes in C:
+/// `call i32 (i32, ...) bitcast (i32 (...)* @f to i32 (i32, ...)*)(i32 1)`
+pub(crate) fn callee_global(callee: &Constant) -> Option<&Name> {
+    match callee {
+        Constant::GlobalReference { name, .. } => Some(name),
+        Constant::BitCast(bitcast) => callee_global(&bitcast.operand),
+        Constant::AddrSpaceCast(cast) => callee_global(&cast.operand),
+        Constant::GetElementPtr(gep)
+            if gep
+                .indices
+                .iter()
+                .all(|idx| matches!(idx.as_ref(), Constant::Int { value: 0, .. })) =>
+        {
+            callee_global(&gep.address)
+        }
+        _ => None,
+    }
+}
+
+/// Get the global which the given operand refers to, if it is a (possibly
+/// cast) reference to a global (see `callee_global()`)
+fn operand_global(op: &Operand) -> Option<&Name> {
+    match op {
+        Operand::ConstantOperand(cref) => callee_global(cref),
+        _ => None,
+    }
 }
 
-enum CallOrInvoke<'a> {
+/// Get a map from the name of each (named) `GlobalAlias` in the given
+/// `Module`s to the name of the global it directly aliases. Numbered aliasees
+/// are resolved in the `Module` containing the alias (see `global_name()`).
+pub(crate) fn alias_targets<'m>(modules: &[&'m Module]) -> HashMap<&'m str, FunctionName<'m>> {
+    let mut aliases = HashMap::new();
+    for module in modules {
+        if module.global_aliases.is_empty() {
+            continue;
+        }
+        let num_numbered = num_numbered_functions(module);
+        for alias in &module.global_aliases {
+            let name = match &alias.name {
+                Name::Name(name) => name.as_str(),
+                Name::Number(_) => continue,
+            };
+            let aliasee = callee_global(&alias.aliasee)
+                .and_then(|aliasee| global_name(module, num_numbered, aliasee));
+            if let Some(aliasee) = aliasee {
+                aliases.insert(name, aliasee);
+            }
+        }
+    }
+    aliases
+}
+
+/// Follow the given chain of aliases (see `alias_targets()`) from `name` to
+/// the global it ultimately refers to. If `name` is not an alias, it is
+/// returned unchanged.
+pub(crate) fn resolve_alias<'m>(
+    aliases: &HashMap<&'m str, FunctionName<'m>>,
+    mut name: FunctionName<'m>,
+) -> FunctionName<'m> {
+    // bound the number of steps, in case of (invalid) cyclic aliases
+    for _ in 0..aliases.len() {
+        match name.as_named().and_then(|alias| aliases.get(alias)) {
+            Some(&aliasee) => name = aliasee,
+            None => break,
+        }
+    }
+    name
+}
+
+/// Get a map from the name of each (named) `GlobalIFunc` in the given
+/// `Module`s to the names of the functions its resolver may return, or `None`
+/// if those can't be determined (e.g., because the resolver isn't defined in
+/// the given `Module`s, or returns a function pointer loaded from memory)
+fn ifunc_targets<'m>(
+    modules: &[&'m Module],
+    aliases: &HashMap<&'m str, FunctionName<'m>>,
+) -> HashMap<&'m str, Option<Vec<FunctionName<'m>>>> {
+    let mut ifuncs = HashMap::new();
+    for module in modules {
+        if module.global_ifuncs.is_empty() {
+            continue;
+        }
+        let num_numbered = num_numbered_functions(module);
+        let function_name = |name: &'m Name| {
+            global_name(module, num_numbered, name).map(|name| resolve_alias(aliases, name))
+        };
+        for ifunc in &module.global_ifuncs {
+            let name = match &ifunc.name {
+                Name::Name(name) => name.as_str(),
+                Name::Number(_) => continue,
+            };
+            let resolver = match callee_global(&ifunc.resolver_fn).and_then(function_name) {
+                Some(resolver) => resolver,
+                None => {
+                    ifuncs.insert(name, None);
+                    continue;
+                }
+            };
+            let resolver = modules
+                .iter()
+                .find_map(|module| function_by_name(module, resolver));
+            let targets = resolver.and_then(|resolver| {
+                let mut targets = vec![];
+                let mut visited = HashSet::new();
+                for bb in &resolver.basic_blocks {
+                    if let Terminator::Ret(Ret {
+                        return_operand: Some(op),
+                        ..
+                    }) = &bb.term
+                    {
+                        if !returned_functions(
+                            resolver,
+                            op,
+                            &function_name,
+                            &mut visited,
+                            &mut targets,
+                        ) {
+                            return None;
+                        }
+                    }
+                }
+                targets.sort_unstable();
+                targets.dedup();
+                Some(targets)
+            });
+            ifuncs.insert(name, targets);
+        }
+    }
+    ifuncs
+}
+
+/// Add to `targets` the names of the functions which the given operand of an
+/// IFunc resolver may evaluate to, looking through `phi`s and `select`s.
+/// `function_name` gives the (alias-resolved) name of the function referenced
+/// by a global's `Name`. Returns `false` if the operand may evaluate to
+/// something else.
+fn returned_functions<'m>(
+    resolver: &'m Function,
+    op: &'m Operand,
+    function_name: &dyn Fn(&'m Name) -> Option<FunctionName<'m>>,
+    visited: &mut HashSet<&'m Name>,
+    targets: &mut Vec<FunctionName<'m>>,
+) -> bool {
+    match op {
+        Operand::ConstantOperand(cref) => match callee_global(cref).and_then(function_name) {
+            Some(name) => {
+                targets.push(name);
+                true
+            }
+            None => false,
+        },
+        Operand::LocalOperand { name, .. } => {
+            if !visited.insert(name) {
+                return true; // already handled (e.g., a cycle of `phi`s)
+            }
+            let inst = resolver
+                .basic_blocks
+                .iter()
+                .flat_map(|bb| bb.instrs.iter())
+                .find(|inst| inst.try_get_result() == Some(name));
+            match inst {
+                Some(Instruction::Phi(phi)) => phi.incoming_values.iter().all(|(value, _)| {
+                    returned_functions(resolver, value, function_name, visited, targets)
+                }),
+                Some(Instruction::Select(select)) => {
+                    returned_functions(
+                        resolver,
+                        &select.true_value,
+                        function_name,
+                        visited,
+                        targets,
+                    ) && returned_functions(
+                        resolver,
+                        &select.false_value,
+                        function_name,
+                        visited,
+                        targets,
+                    )
+                }
+                _ => false,
+            }
+        }
+        Operand::MetadataOperand => false,
+    }
+}
+
+/// Get the number of functions in the given `Module` with numbered names.
+///
+/// `llvm-ir` numbers unnamed globals in order, starting with the defined
+/// functions and then the declared functions, and gives unnamed functions the
+/// empty string as their name. So the global with the numbered `Name` `N` is a
+/// function if and only if `N` is less than this number.
+pub(crate) fn num_numbered_functions(module: &Module) -> usize {
+    module
+        .functions
+        .iter()
+        .map(|f| f.name.as_str())
+        .chain(module.func_declarations.iter().map(|f| f.name.as_str()))
+        .filter(|name| name.is_empty())
+        .count()
+}
+
+/// Get the names of the functions defined in the given `Module`, in order,
+/// followed by the names of the functions it declares.
+pub(crate) fn function_names(module: &Module) -> impl Iterator<Item = FunctionName<'_>> {
+    let mut n = 0;
+    module
+        .functions
+        .iter()
+        .map(|f| f.name.as_str())
+        .chain(module.func_declarations.iter().map(|f| f.name.as_str()))
+        .map(move |name| {
+            if name.is_empty() {
+                n += 1;
+                FunctionName::Numbered {
+                    module: &module.name,
+                    n: n - 1,
+                }
+            } else {
+                FunctionName::Named(name)
+            }
+        })
+}
+
+/// Iterate over the functions defined in the given `Module`, along with their
+/// names (see `function_names()`)
+pub(crate) fn functions_with_names(
+    module: &Module,
+) -> impl Iterator<Item = (&Function, FunctionName<'_>)> {
+    module.functions.iter().zip(function_names(module))
+}
+
+/// Get the function defined in the given `Module` with the given name
+pub(crate) fn function_by_name<'m>(module: &'m Module, name: FunctionName) -> Option<&'m Function> {
+    functions_with_names(module)
+        .find(|&(_, f_name)| f_name == name)
+        .map(|(f, _)| f)
+}
+
+/// Is there a function declared (but not defined) in the given `Module` with
+/// the given name?
+pub(crate) fn is_declared(module: &Module, name: FunctionName) -> bool {
+    function_names(module)
+        .skip(module.functions.len())
+        .any(|decl_name| decl_name == name)
+}
+
+/// Get the name of the function referenced by the given `Name` in the given
+/// `Module`, which has `num_numbered` functions with numbered names (see
+/// `num_numbered_functions()`). Returns `None` for a numbered global which is
+/// not a function.
+pub(crate) fn global_name<'m>(
+    module: &'m Module,
+    num_numbered: usize,
+    name: &'m Name,
+) -> Option<FunctionName<'m>> {
+    match name {
+        Name::Name(name) => Some(FunctionName::Named(name.as_str())),
+        Name::Number(n) if *n < num_numbered => Some(FunctionName::Numbered {
+            module: &module.name,
+            n: *n,
+        }),
+        Name::Number(_) => None,
+    }
+}
+
+/// An argument of a call, along with its attributes
+type Argument = (Operand, Vec<ParameterAttribute>);
+
+pub(crate) enum CallOrInvoke<'a> {
     Call {
-        #[cfg_attr(feature = "llvm-15-or-greater", allow(dead_code))]
         module: &'a Module,
         call: &'a Call,
     },
     Invoke {
-        #[cfg_attr(feature = "llvm-15-or-greater", allow(dead_code))]
         module: &'a Module,
         invoke: &'a Invoke,
     },
+    CallBr {
+        module: &'a Module,
+        callbr: &'a CallBr,
+    },
 }
 
 impl<'a> CallOrInvoke<'a> {
-    #[cfg(feature = "llvm-14-or-lower")]
     fn module(&self) -> &'a Module {
         match self {
             Self::Call { module, .. } => module,
             Self::Invoke { module, .. } => module,
+            Self::CallBr { module, .. } => module,
         }
     }
 
-    fn callee(&self) -> &'a Either<InlineAssembly, Operand> {
+    pub(crate) fn callee(&self) -> &'a Either<InlineAssembly, Operand> {
         match self {
             Self::Call { call, .. } => &call.function,
             Self::Invoke { invoke, .. } => &invoke.function,
+            Self::CallBr { callbr, .. } => &callbr.function,
+        }
+    }
+
+    pub(crate) fn function_attributes(&self) -> &'a [FunctionAttribute] {
+        match self {
+            Self::Call { call, .. } => &call.function_attributes,
+            Self::Invoke { invoke, .. } => &invoke.function_attributes,
+            Self::CallBr { callbr, .. } => &callbr.function_attributes,
+        }
+    }
+
+    pub(crate) fn arguments(&self) -> &'a [Argument] {
+        match self {
+            Self::Call { call, .. } => &call.arguments,
+            Self::Invoke { invoke, .. } => &invoke.arguments,
+            Self::CallBr { callbr, .. } => &callbr.arguments,
+        }
+    }
+
+    /// If this is a call of `llvm.experimental.gc.statepoint`, get the
+    /// function pointer it wraps and the arguments it passes to that function
+    fn statepoint_call(&self) -> Option<(&'a Operand, &'a [Argument])> {
+        match self.callee().as_ref().right().and_then(operand_global) {
+            Some(Name::Name(name)) if name.starts_with("llvm.experimental.gc.statepoint.") => {}
+            _ => return None,
+        }
+        // the arguments are the ID, the number of patch bytes, the wrapped
+        // function, the number of arguments to it, the flags, and then the
+        // arguments to the wrapped function
+        let arguments = self.arguments();
+        let num_call_args = match &arguments.get(3)?.0 {
+            Operand::ConstantOperand(cref) => match cref.as_ref() {
+                Constant::Int { value, .. } => *value as usize,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        // an argument count too large to index with isn't a well-formed
+        // statepoint
+        let end = 5usize.checked_add(num_call_args)?;
+        Some((&arguments.get(2)?.0, arguments.get(5..end)?))
+    }
+
+    /// Is this a call of `llvm.experimental.gc.relocate` or
+    /// `llvm.experimental.gc.result`, which get values from the token
+    /// returned by a statepoint
+    fn is_statepoint_projection(&self) -> bool {
+        match self.callee().as_ref().right().and_then(operand_global) {
+            Some(Name::Name(name)) => {
+                name.starts_with("llvm.experimental.gc.relocate.")
+                    || name.starts_with("llvm.experimental.gc.result.")
+            }
+            _ => false,
+        }
+    }
+
+    /// Get the type of the function pointed to by `target`, the function
+    /// pointer wrapped by a statepoint (see `statepoint_call()`). Returns
+    /// `None` with opaque pointers, which don't record this.
+    fn statepoint_target_ty(&self, target: &Operand) -> Option<TypeRef> {
+        #[cfg(feature = "llvm-14-or-lower")]
+        match self.module().type_of(target).as_ref() {
+            llvm_ir::Type::PointerType { pointee_type, .. } => Some(pointee_type.clone()),
+            _ => None,
+        }
+        #[cfg(feature = "llvm-15-or-greater")]
+        {
+            let _ = target;
+            None
         }
     }
 
-    fn callee_ty(&self) -> TypeRef {
+    /// Get the type of the function called. Returns `None` if it isn't
+    /// known, e.g., for a `CallBr` with opaque pointers, which doesn't record
+    /// its function type.
+    pub(crate) fn callee_ty(&self) -> Option<TypeRef> {
         #[cfg(feature = "llvm-14-or-lower")]
         match self.module().type_of(self.callee()).as_ref() {
-            llvm_ir::Type::PointerType { pointee_type, .. } => pointee_type.clone(),
-            ty => panic!(
-                "Expected function pointer to have pointer type, but got {:?}",
-                ty
-            ),
+            llvm_ir::Type::PointerType { pointee_type, .. } => Some(pointee_type.clone()),
+            _ => None,
         }
         #[cfg(feature = "llvm-15-or-greater")]
         match self {
-            Self::Call { call, .. } => call.function_ty.clone(),
-            Self::Invoke { invoke, .. } => invoke.function_ty.clone(),
+            Self::Call { call, .. } => Some(call.function_ty.clone()),
+            Self::Invoke { invoke, .. } => Some(invoke.function_ty.clone()),
+            Self::CallBr { .. } => None,
         }
     }
 }
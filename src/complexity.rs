@@ -0,0 +1,261 @@
+use crate::call_graph::{CallGraph, FunctionName};
+use crate::control_flow_graph::ControlFlowGraph;
+use crate::loops::LoopAnalysis;
+use crate::FunctionAnalysis;
+use llvm_ir::Function;
+
+/// A per-function complexity metric which can be used to rank functions in a
+/// [`ComplexityReport`](struct.ComplexityReport.html)
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub enum ComplexityMetric {
+    /// Number of basic blocks
+    Blocks,
+    /// Number of instructions, including terminators
+    Instructions,
+    /// McCabe cyclomatic complexity: the number of decision points in the CFG,
+    /// plus one
+    CyclomaticComplexity,
+    /// Maximum loop nesting depth (0 for a function with no loops)
+    LoopDepth,
+    /// Number of distinct functions which may be called (according to the
+    /// [`CallGraph`](struct.CallGraph.html))
+    Callees,
+}
+
+impl ComplexityMetric {
+    /// All of the available metrics
+    pub const ALL: [ComplexityMetric; 5] = [
+        ComplexityMetric::Blocks,
+        ComplexityMetric::Instructions,
+        ComplexityMetric::CyclomaticComplexity,
+        ComplexityMetric::LoopDepth,
+        ComplexityMetric::Callees,
+    ];
+}
+
+/// The complexity metrics for a single function
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct FunctionComplexity<'m> {
+    /// Name of the function
+    pub function: FunctionName<'m>,
+    /// Number of basic blocks
+    pub blocks: usize,
+    /// Number of instructions, including terminators
+    pub instructions: usize,
+    /// McCabe cyclomatic complexity
+    pub cyclomatic_complexity: usize,
+    /// Maximum loop nesting depth
+    pub loop_depth: usize,
+    /// Number of distinct functions which may be called
+    pub callees: usize,
+}
+
+impl<'m> FunctionComplexity<'m> {
+    /// Get the value of the given metric for this function
+    pub fn metric(&self, metric: ComplexityMetric) -> usize {
+        match metric {
+            ComplexityMetric::Blocks => self.blocks,
+            ComplexityMetric::Instructions => self.instructions,
+            ComplexityMetric::CyclomaticComplexity => self.cyclomatic_complexity,
+            ComplexityMetric::LoopDepth => self.loop_depth,
+            ComplexityMetric::Callees => self.callees,
+        }
+    }
+}
+
+/// Basic size and shape metrics of a single function's
+/// [`ControlFlowGraph`](struct.ControlFlowGraph.html).
+///
+/// To get the `CFGMetrics` for a function, use
+/// [`FunctionAnalysis::cfg_metrics()`](struct.FunctionAnalysis.html#method.cfg_metrics).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct CFGMetrics {
+    /// Number of basic blocks
+    pub blocks: usize,
+    /// Number of CFG edges, including edges to `CFGNode::Return`. Several
+    /// outcomes of a terminator with the same destination (e.g., `switch`
+    /// cases) count as a single edge.
+    pub edges: usize,
+    /// McCabe cyclomatic complexity: the number of decision points in the CFG,
+    /// plus one
+    pub cyclomatic_complexity: usize,
+    /// Number of blocks which may directly exit the function, i.e., which have
+    /// an edge to `CFGNode::Return`
+    pub exits: usize,
+    /// Maximum number of successors of any block (counting
+    /// `CFGNode::Return`)
+    pub max_out_degree: usize,
+    /// Maximum loop nesting depth, or 0 for a function with no loops (see
+    /// [`LoopAnalysis::max_loop_depth()`](struct.LoopAnalysis.html#method.max_loop_depth))
+    pub max_loop_depth: usize,
+}
+
+impl CFGMetrics {
+    pub(crate) fn new<'m>(
+        function: &'m Function,
+        cfg: &ControlFlowGraph<'m>,
+        loops: &LoopAnalysis<'m>,
+    ) -> Self {
+        let out_degrees: Vec<usize> = function
+            .basic_blocks
+            .iter()
+            .map(|bb| cfg.succs(&bb.name).count())
+            .collect();
+        Self {
+            blocks: function.basic_blocks.len(),
+            edges: cfg.num_edges(),
+            cyclomatic_complexity: out_degrees
+                .iter()
+                .map(|degree| degree.saturating_sub(1))
+                .sum::<usize>()
+                + 1,
+            exits: cfg.preds_of_return().count(),
+            max_out_degree: out_degrees.iter().copied().max().unwrap_or(0),
+            max_loop_depth: loops.max_loop_depth(),
+        }
+    }
+}
+
+/// Maximum allowed values for each [`ComplexityMetric`](enum.ComplexityMetric.html),
+/// for use with [`ComplexityReport::violations()`](struct.ComplexityReport.html#method.violations).
+/// A threshold of `None` means that metric is not checked.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Hash)]
+pub struct ComplexityThresholds {
+    /// Maximum number of basic blocks
+    pub blocks: Option<usize>,
+    /// Maximum number of instructions
+    pub instructions: Option<usize>,
+    /// Maximum cyclomatic complexity
+    pub cyclomatic_complexity: Option<usize>,
+    /// Maximum loop nesting depth
+    pub loop_depth: Option<usize>,
+    /// Maximum number of distinct callees
+    pub callees: Option<usize>,
+}
+
+impl ComplexityThresholds {
+    /// Get the threshold for the given metric
+    pub fn threshold(&self, metric: ComplexityMetric) -> Option<usize> {
+        match metric {
+            ComplexityMetric::Blocks => self.blocks,
+            ComplexityMetric::Instructions => self.instructions,
+            ComplexityMetric::CyclomaticComplexity => self.cyclomatic_complexity,
+            ComplexityMetric::LoopDepth => self.loop_depth,
+            ComplexityMetric::Callees => self.callees,
+        }
+    }
+}
+
+/// A function which exceeds one of the [`ComplexityThresholds`](struct.ComplexityThresholds.html)
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct ThresholdViolation<'m> {
+    /// Name of the function
+    pub function: FunctionName<'m>,
+    /// The metric which exceeds its threshold
+    pub metric: ComplexityMetric,
+    /// The value of the metric for this function
+    pub value: usize,
+    /// The threshold which was exceeded
+    pub threshold: usize,
+}
+
+/// Complexity metrics for every function in the analyzed `Module`(s), with
+/// support for ranking functions by any metric and for checking the metrics
+/// against thresholds (e.g., as a CI gate).
+///
+/// To construct a `ComplexityReport`, use [`ModuleAnalysis`](struct.ModuleAnalysis.html)
+/// or [`CrossModuleAnalysis`](struct.CrossModuleAnalysis.html).
+pub struct ComplexityReport<'m> {
+    /// Metrics for each function, sorted by function name
+    functions: Vec<FunctionComplexity<'m>>,
+}
+
+impl<'m> ComplexityReport<'m> {
+    pub(crate) fn new<'a>(
+        fn_analyses: impl IntoIterator<Item = (FunctionName<'m>, &'a FunctionAnalysis<'m>)>,
+        call_graph: &CallGraph<'m>,
+    ) -> Self
+    where
+        'm: 'a,
+    {
+        let mut functions: Vec<FunctionComplexity<'m>> = fn_analyses
+            .into_iter()
+            .map(|(func_name, fn_analysis)| {
+                let function = fn_analysis.function;
+                let metrics = fn_analysis.cfg_metrics();
+                FunctionComplexity {
+                    function: func_name,
+                    blocks: metrics.blocks,
+                    instructions: function
+                        .basic_blocks
+                        .iter()
+                        .map(|bb| bb.instrs.len() + 1)
+                        .sum(),
+                    cyclomatic_complexity: metrics.cyclomatic_complexity,
+                    loop_depth: metrics.max_loop_depth,
+                    callees: call_graph.callees(func_name).count(),
+                }
+            })
+            .collect();
+        functions.sort_unstable_by_key(|fc| fc.function);
+        Self { functions }
+    }
+
+    /// Iterate over the metrics for every function in the analyzed
+    /// `Module`(s), sorted by function name.
+    pub fn functions<'s>(&'s self) -> impl Iterator<Item = &'s FunctionComplexity<'m>> + 's {
+        self.functions.iter()
+    }
+
+    /// Get the metrics for the function with the given name.
+    ///
+    /// Panics if the given function is not found in the analyzed `Module`(s).
+    pub fn function<'n>(&self, func_name: impl Into<FunctionName<'n>>) -> &FunctionComplexity<'m> {
+        let func_name = func_name.into();
+        self.functions
+            .binary_search_by_key(&func_name, |fc| fc.function)
+            .map(|idx| &self.functions[idx])
+            .unwrap_or_else(|_| {
+                panic!(
+                    "function(): function named {:?} not found in the Module(s)",
+                    func_name
+                )
+            })
+    }
+
+    /// Get the (up to) `n` functions with the highest values of the given
+    /// metric, in descending order of that metric. Ties are broken by function
+    /// name.
+    pub fn top_n(&self, metric: ComplexityMetric, n: usize) -> Vec<&FunctionComplexity<'m>> {
+        let mut ranked: Vec<&FunctionComplexity<'m>> = self.functions.iter().collect();
+        // stable sort, so ties remain sorted by function name
+        ranked.sort_by_key(|fc| std::cmp::Reverse(fc.metric(metric)));
+        ranked.truncate(n);
+        ranked
+    }
+
+    /// Get every case where a function's metric exceeds the corresponding
+    /// threshold, sorted by function name and then by metric. An empty result
+    /// means all functions are within the thresholds.
+    pub fn violations(&self, thresholds: &ComplexityThresholds) -> Vec<ThresholdViolation<'m>> {
+        self.functions
+            .iter()
+            .flat_map(|fc| {
+                ComplexityMetric::ALL.iter().filter_map(move |&metric| {
+                    let threshold = thresholds.threshold(metric)?;
+                    let value = fc.metric(metric);
+                    if value > threshold {
+                        Some(ThresholdViolation {
+                            function: fc.function,
+                            metric,
+                            value,
+                            threshold,
+                        })
+                    } else {
+                        None
+                    }
+                })
+            })
+            .collect()
+    }
+}
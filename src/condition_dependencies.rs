@@ -0,0 +1,175 @@
+use crate::alias::{underlying_object, MemoryObject};
+use crate::def_use::{DefUseChains, Definition};
+use crate::operands::{instruction_operands, terminator_operands};
+use either::Either;
+use llvm_ir::{Function, Instruction, Module, Name, Operand, Terminator};
+use std::collections::{HashMap, HashSet};
+
+/// For each conditional branch and `switch` in a particular function, the
+/// values its condition depends on: the local SSA values it's computed from,
+/// transitively, and so which of the function's parameters, and which globals
+/// loaded from, may affect which way it goes.
+///
+/// Dependencies are followed through the operands of the instructions
+/// defining values, including `phi`s, and the addresses and arguments of
+/// `load`s and `call`s; but not through memory. So a condition computed from
+/// a `load` depends on the address loaded from, and if the address is based
+/// on a global, the global is recorded as loaded; but not on the values which
+/// may have been stored there. See
+/// [`DataDependenceGraph`](struct.DataDependenceGraph.html) for dependences
+/// through memory.
+///
+/// To construct a `ConditionDependencies`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct ConditionDependencies<'m> {
+    /// The dependencies of each conditional branch or `switch`, in the order
+    /// of their blocks
+    branches: Vec<BranchDependencies<'m>>,
+    /// Index of the dependencies of each block's terminator in `branches`
+    indices: HashMap<&'m Name, usize>,
+}
+
+/// The values which the condition of a particular conditional branch or
+/// `switch` depends on; see
+/// [`ConditionDependencies`](struct.ConditionDependencies.html)
+#[derive(Clone, PartialEq, Debug)]
+pub struct BranchDependencies<'m> {
+    /// Name of the block whose terminator is the branch
+    pub block: &'m Name,
+    /// The condition of the branch (or the value the `switch` is on)
+    pub condition: &'m Operand,
+    /// The local SSA values (parameters and instruction results) the
+    /// condition depends on, including the condition itself if it's local,
+    /// in the order they're defined in the function (parameters first)
+    pub values: Vec<&'m Name>,
+    /// The indices of the function parameters the condition depends on, in
+    /// order
+    pub parameters: Vec<usize>,
+    /// The globals loaded from by `load`s the condition depends on, sorted
+    pub loaded_globals: Vec<&'m Name>,
+}
+
+impl<'m> ConditionDependencies<'m> {
+    pub(crate) fn new(
+        function: &'m Function,
+        module: Option<&'m Module>,
+        def_use: &DefUseChains<'m>,
+    ) -> Self {
+        // position of each local value in the function, to sort by
+        let positions: HashMap<&'m Name, usize> = function
+            .parameters
+            .iter()
+            .map(|param| &param.name)
+            .chain(function.basic_blocks.iter().flat_map(|bb| {
+                bb.instrs
+                    .iter()
+                    .filter_map(|inst| inst.try_get_result())
+                    .chain(bb.term.try_get_result())
+            }))
+            .enumerate()
+            .map(|(i, value)| (value, i))
+            .collect();
+        let defs: HashMap<&'m Name, &'m Instruction> = function
+            .basic_blocks
+            .iter()
+            .flat_map(|bb| &bb.instrs)
+            .filter_map(|inst| inst.try_get_result().map(|dest| (dest, inst)))
+            .collect();
+
+        let mut branches = Vec::new();
+        for bb in &function.basic_blocks {
+            let condition = match &bb.term {
+                Terminator::CondBr(condbr) => &condbr.condition,
+                Terminator::Switch(switch) => &switch.operand,
+                _ => continue,
+            };
+            let mut values: HashSet<&'m Name> = HashSet::new();
+            let mut parameters = Vec::new();
+            let mut loaded_globals = Vec::new();
+            let mut worklist = vec![condition];
+            while let Some(operand) = worklist.pop() {
+                let value = match operand {
+                    Operand::LocalOperand { name, .. } => name,
+                    _ => continue,
+                };
+                if !values.insert(value) {
+                    continue;
+                }
+                match def_use.definition(value) {
+                    Some(Definition::Parameter(index)) => parameters.push(index),
+                    Some(Definition::Instruction(location)) => {
+                        match def_use.instruction(location) {
+                            Either::Left(inst) => {
+                                if let Instruction::Load(load) = inst {
+                                    if let Some(MemoryObject::Global(global)) =
+                                        underlying_object(&load.address, &defs, module)
+                                    {
+                                        loaded_globals.push(global);
+                                    }
+                                }
+                                worklist.extend(instruction_operands(inst));
+                            }
+                            Either::Right(term) => worklist.extend(terminator_operands(term)),
+                        }
+                    }
+                    None => {}
+                }
+            }
+            let mut values: Vec<&'m Name> = values.into_iter().collect();
+            values.sort_unstable_by_key(|value| positions.get(value));
+            parameters.sort_unstable();
+            loaded_globals.sort_unstable();
+            loaded_globals.dedup();
+            branches.push(BranchDependencies {
+                block: &bb.name,
+                condition,
+                values,
+                parameters,
+                loaded_globals,
+            });
+        }
+        let indices = branches
+            .iter()
+            .enumerate()
+            .map(|(i, branch)| (branch.block, i))
+            .collect();
+        Self { branches, indices }
+    }
+
+    /// Iterate over the dependencies of every conditional branch and `switch`
+    /// in the function, in the order of their blocks
+    pub fn branches(&self) -> impl Iterator<Item = &BranchDependencies<'m>> {
+        self.branches.iter()
+    }
+
+    /// Get the dependencies of the terminator of the block with the given
+    /// `Name`, or `None` if it isn't a conditional branch or `switch`
+    pub fn branch(&self, block: &Name) -> Option<&BranchDependencies<'m>> {
+        self.indices.get(block).map(|&i| &self.branches[i])
+    }
+
+    /// Iterate over the conditional branches and `switch`es whose conditions
+    /// depend on the function parameter with the given index, in the order of
+    /// their blocks
+    pub fn branches_depending_on_parameter(
+        &self,
+        index: usize,
+    ) -> impl Iterator<Item = &BranchDependencies<'m>> {
+        self.branches
+            .iter()
+            .filter(move |branch| branch.parameters.contains(&index))
+    }
+
+    /// Iterate over the conditional branches and `switch`es whose conditions
+    /// depend on loads from the global with the given `Name`, in the order of
+    /// their blocks
+    pub fn branches_depending_on_global<'s>(
+        &'s self,
+        global: &'s Name,
+    ) -> impl Iterator<Item = &'s BranchDependencies<'m>> {
+        self.branches
+            .iter()
+            .filter(move |branch| branch.loaded_globals.contains(&global))
+    }
+}
@@ -0,0 +1,468 @@
+use crate::control_flow_graph::{CFGEdgeKind, CFGNode, ControlFlowGraph};
+use crate::def_use::{DefUseChains, InstructionLocation};
+use crate::loops::{negate, signed};
+use either::Either;
+use llvm_ir::{
+    Constant, ConstantRef, Function, Instruction, IntPredicate, Name, Operand, Terminator,
+};
+use std::collections::{HashMap, HashSet};
+
+/// What is known about a value during propagation
+#[derive(Clone, PartialEq, Debug)]
+enum Lattice {
+    /// The value hasn't been found to be defined yet; it may never be
+    Undefined,
+    /// The value is always this constant
+    Constant(ConstantRef),
+    /// The value may not be constant
+    Overdefined,
+}
+
+impl Lattice {
+    fn meet(&self, other: &Self) -> Self {
+        match (self, other) {
+            (Lattice::Undefined, x) | (x, Lattice::Undefined) => x.clone(),
+            (Lattice::Constant(a), Lattice::Constant(b)) if a == b => self.clone(),
+            _ => Lattice::Overdefined,
+        }
+    }
+
+    /// Get the value of an integer constant of at most 64 bits, as `(bits,
+    /// value)`
+    fn as_int(&self) -> Option<(u32, u64)> {
+        match self {
+            Lattice::Constant(constant) => match constant.as_ref() {
+                Constant::Int { bits, value } if *bits <= 64 => Some((*bits, *value)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// The results of sparse conditional constant propagation (SCCP) on a
+/// particular function: which SSA values are provably constant, and which CFG
+/// edges (and blocks) provably never execute.
+///
+/// Propagation is optimistic, as in Wegman and Zadeck's algorithm: values
+/// are assumed constant, and edges unexecuted, until shown otherwise. So, for
+/// instance, a value which stays the same around a loop is found to be
+/// constant, and a branch on a constant condition executes only one of its
+/// successors, whose values then don't affect any `phi`s after the branch.
+///
+/// Integer arithmetic, bitwise operations, shifts, comparisons, casts between
+/// integer types, `select`s, and `phi`s are folded, for integers of at most 64
+/// bits. Other constants (e.g., addresses of globals) propagate through `phi`s
+/// and `select`s, but aren't folded. Parameters and the results of all other
+/// instructions (e.g., `load`s and `call`s) may not be constant; and so may
+/// `undef`s, which aren't exploited.
+///
+/// The edges which never execute can be removed from the CFG with
+/// [`ControlFlowGraph::without_edges()`](struct.ControlFlowGraph.html#method.without_edges),
+/// for more precise dominators, postdominators, and control dependencies; see
+/// [`dead_edges()`](#method.dead_edges). The results are cached by
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), e.g.
+/// [`pruned_control_flow_graph()`](struct.FunctionAnalysis.html#method.pruned_control_flow_graph).
+///
+/// To construct a `ConstantPropagation`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct ConstantPropagation<'m> {
+    /// The function
+    function: &'m Function,
+    /// The local values which are constant
+    constants: HashMap<&'m Name, ConstantRef>,
+    /// The blocks which may execute
+    executable_blocks: HashSet<&'m Name>,
+    /// The CFG edges which may execute
+    executable_edges: HashSet<(&'m Name, CFGNode<'m>)>,
+    /// The CFG edges which never execute, sorted
+    dead_edges: Vec<(&'m Name, CFGNode<'m>)>,
+}
+
+/// State of the propagation
+struct Propagator<'a, 'm> {
+    function: &'m Function,
+    cfg: &'a ControlFlowGraph<'m>,
+    def_use: &'a DefUseChains<'m>,
+    values: HashMap<&'m Name, Lattice>,
+    executable_blocks: HashSet<&'m Name>,
+    executable_edges: HashSet<(&'m Name, CFGNode<'m>)>,
+    /// Blocks newly reached by an edge
+    block_worklist: Vec<&'m Name>,
+    /// Values whose lattice values have changed
+    value_worklist: Vec<&'m Name>,
+}
+
+impl<'m> ConstantPropagation<'m> {
+    pub(crate) fn new(
+        function: &'m Function,
+        cfg: &ControlFlowGraph<'m>,
+        def_use: &DefUseChains<'m>,
+    ) -> Self {
+        let mut propagator = Propagator {
+            function,
+            cfg,
+            def_use,
+            values: function
+                .parameters
+                .iter()
+                .map(|param| (&param.name, Lattice::Overdefined))
+                .collect(),
+            executable_blocks: HashSet::new(),
+            executable_edges: HashSet::new(),
+            block_worklist: vec![cfg.entry()],
+            value_worklist: Vec::new(),
+        };
+        propagator.run();
+
+        let constants = propagator
+            .values
+            .into_iter()
+            .filter_map(|(value, lattice)| match lattice {
+                Lattice::Constant(constant) => Some((value, constant)),
+                _ => None,
+            })
+            .collect();
+        let mut dead_edges: Vec<(&'m Name, CFGNode<'m>)> = function
+            .basic_blocks
+            .iter()
+            .flat_map(|bb| cfg.succs(&bb.name).map(move |succ| (&bb.name, succ)))
+            .filter(|edge| !propagator.executable_edges.contains(edge))
+            .collect();
+        dead_edges.sort_unstable();
+        Self {
+            function,
+            constants,
+            executable_blocks: propagator.executable_blocks,
+            executable_edges: propagator.executable_edges,
+            dead_edges,
+        }
+    }
+
+    /// Get the constant value of the local value with the given `Name`, if it
+    /// is provably constant. Values which can never be computed (because the
+    /// instruction defining them never executes) aren't considered constant.
+    pub fn constant(&self, value: &Name) -> Option<&ConstantRef> {
+        self.constants.get(value)
+    }
+
+    /// Is the local value with the given `Name` provably constant?
+    pub fn is_constant(&self, value: &Name) -> bool {
+        self.constants.contains_key(value)
+    }
+
+    /// Iterate over the local values which are provably constant, along with
+    /// their values, in no particular order
+    pub fn constants<'s>(&'s self) -> impl Iterator<Item = (&'m Name, &'s ConstantRef)> + 's {
+        self.constants
+            .iter()
+            .map(|(&value, constant)| (value, constant))
+    }
+
+    /// May the block with the given `Name` execute?
+    pub fn is_block_executable(&self, block: &Name) -> bool {
+        self.executable_blocks.contains(block)
+    }
+
+    /// Iterate over the blocks which provably never execute, in the order they
+    /// appear in the function
+    pub fn dead_blocks<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
+        self.function
+            .basic_blocks
+            .iter()
+            .map(|bb| &bb.name)
+            .filter(move |block| !self.executable_blocks.contains(block))
+    }
+
+    /// May the CFG edge from the block `from` to `to` execute? This is `false`
+    /// for edges which aren't in the CFG.
+    pub fn is_edge_executable(&self, from: &'m Name, to: CFGNode<'m>) -> bool {
+        self.executable_edges.contains(&(from, to))
+    }
+
+    /// Get the CFG edges which provably never execute, sorted, as pairs `(from,
+    /// to)`. This includes all edges out of blocks which never execute.
+    ///
+    /// To get the CFG without these edges, pass them to
+    /// [`ControlFlowGraph::without_edges()`](struct.ControlFlowGraph.html#method.without_edges).
+    pub fn dead_edges(&self) -> &[(&'m Name, CFGNode<'m>)] {
+        &self.dead_edges
+    }
+
+    /// Iterate over the infeasible CFG edges, sorted, as pairs `(from, to)`:
+    /// the edges out of blocks which may execute, but which provably never
+    /// execute themselves, because the block branches on a condition which
+    /// is constant. These are the untaken outcomes of constant branches; the
+    /// rest of the [`dead_edges()`](#method.dead_edges) are just out of blocks
+    /// which never execute.
+    pub fn infeasible_edges(&self) -> impl Iterator<Item = (&'m Name, CFGNode<'m>)> + '_ {
+        self.dead_edges
+            .iter()
+            .copied()
+            .filter(move |(from, _)| self.executable_blocks.contains(from))
+    }
+}
+
+impl<'a, 'm> Propagator<'a, 'm> {
+    fn run(&mut self) {
+        loop {
+            if let Some(block) = self.block_worklist.pop() {
+                let bb = self.function.get_bb_by_name(block).unwrap_or_else(|| {
+                    panic!(
+                        "Failed to find block {} in function {}",
+                        block, self.function.name
+                    )
+                });
+                let first_visit = self.executable_blocks.insert(block);
+                for (index, inst) in bb.instrs.iter().enumerate() {
+                    let location = InstructionLocation { block, index };
+                    match inst {
+                        // a new edge into the block affects only its `phi`s
+                        Instruction::Phi(_) => self.visit(location),
+                        _ if first_visit => self.visit(location),
+                        _ => break,
+                    }
+                }
+                if first_visit {
+                    self.visit(InstructionLocation {
+                        block,
+                        index: bb.instrs.len(),
+                    });
+                }
+            } else if let Some(value) = self.value_worklist.pop() {
+                for &location in self.def_use.uses(value) {
+                    if self.executable_blocks.contains(location.block) {
+                        self.visit(location);
+                    }
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Evaluate the instruction (or terminator) at the given location, which
+    /// is in an executable block
+    fn visit(&mut self, location: InstructionLocation<'m>) {
+        match self.def_use.instruction(location) {
+            Either::Left(inst) => {
+                let result = match inst.try_get_result() {
+                    Some(result) => result,
+                    None => return,
+                };
+                let lattice = match inst {
+                    Instruction::Phi(phi) => phi
+                        .incoming_values
+                        .iter()
+                        .filter(|(_, pred)| {
+                            self.executable_edges
+                                .contains(&(pred, CFGNode::Block(location.block)))
+                        })
+                        .fold(Lattice::Undefined, |lattice, (value, _)| {
+                            lattice.meet(&self.operand(value))
+                        }),
+                    _ => self.evaluate(inst),
+                };
+                self.update(result, lattice);
+            }
+            Either::Right(term) => {
+                if let Some(result) = term.try_get_result() {
+                    self.update(result, Lattice::Overdefined);
+                }
+                let block = location.block;
+                let succs: Vec<(CFGNode<'m>, CFGEdgeKind<'m>)> =
+                    self.cfg.labeled_succs(block).collect();
+                for (succ, kind) in succs {
+                    if self.executable_edges.contains(&(block, succ)) || !self.may_take(term, kind)
+                    {
+                        continue;
+                    }
+                    self.executable_edges.insert((block, succ));
+                    if let CFGNode::Block(succ) = succ {
+                        self.block_worklist.push(succ);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Lower the lattice value of the given value to `lattice`, if that's a
+    /// change
+    fn update(&mut self, value: &'m Name, lattice: Lattice) {
+        let old = self.values.entry(value).or_insert(Lattice::Undefined);
+        // values only move down the lattice, so an `Overdefined` value stays
+        // that way
+        if *old != lattice && *old != Lattice::Overdefined {
+            *old = lattice;
+            self.value_worklist.push(value);
+        }
+    }
+
+    /// Get the lattice value of the given operand
+    fn operand(&self, operand: &Operand) -> Lattice {
+        match operand {
+            Operand::LocalOperand { name, .. } => {
+                self.values.get(name).cloned().unwrap_or(Lattice::Undefined)
+            }
+            Operand::ConstantOperand(constant) => match constant.as_ref() {
+                Constant::Undef(_) => Lattice::Overdefined,
+                #[cfg(feature = "llvm-12-or-greater")]
+                Constant::Poison(_) => Lattice::Overdefined,
+                _ => Lattice::Constant(constant.clone()),
+            },
+            Operand::MetadataOperand => Lattice::Overdefined,
+        }
+    }
+
+    /// May the given edge out of the given terminator execute, given what's
+    /// known so far?
+    fn may_take(&self, term: &'m Terminator, kind: CFGEdgeKind<'m>) -> bool {
+        match (term, kind) {
+            (Terminator::CondBr(condbr), CFGEdgeKind::CondBr(outcome)) => {
+                let condition = self.operand(&condbr.condition);
+                match condition.as_int() {
+                    Some((_, value)) => (value == 1) == outcome,
+                    None => condition != Lattice::Undefined,
+                }
+            }
+            (Terminator::Switch(switch), CFGEdgeKind::SwitchCase(case)) => {
+                match self.operand(&switch.operand) {
+                    Lattice::Undefined => false,
+                    Lattice::Constant(constant) => constant == *case,
+                    Lattice::Overdefined => true,
+                }
+            }
+            (Terminator::Switch(switch), CFGEdgeKind::SwitchDefault) => {
+                match self.operand(&switch.operand) {
+                    Lattice::Undefined => false,
+                    Lattice::Constant(constant) => {
+                        !switch.dests.iter().any(|(case, _)| *case == constant)
+                    }
+                    Lattice::Overdefined => true,
+                }
+            }
+            _ => true,
+        }
+    }
+
+    /// Evaluate the given instruction, which isn't a `phi`
+    fn evaluate(&self, inst: &'m Instruction) -> Lattice {
+        let operands: Vec<Lattice> = match inst {
+            Instruction::Select(select) => {
+                let condition = self.operand(&select.condition);
+                return match condition.as_int() {
+                    Some((_, 1)) => self.operand(&select.true_value),
+                    Some((_, 0)) => self.operand(&select.false_value),
+                    _ if condition == Lattice::Undefined => Lattice::Undefined,
+                    // a vector or non-integer condition
+                    _ => self
+                        .operand(&select.true_value)
+                        .meet(&self.operand(&select.false_value)),
+                };
+            }
+            Instruction::Add(i) => vec![self.operand(&i.operand0), self.operand(&i.operand1)],
+            Instruction::Sub(i) => vec![self.operand(&i.operand0), self.operand(&i.operand1)],
+            Instruction::Mul(i) => vec![self.operand(&i.operand0), self.operand(&i.operand1)],
+            Instruction::UDiv(i) => vec![self.operand(&i.operand0), self.operand(&i.operand1)],
+            Instruction::SDiv(i) => vec![self.operand(&i.operand0), self.operand(&i.operand1)],
+            Instruction::URem(i) => vec![self.operand(&i.operand0), self.operand(&i.operand1)],
+            Instruction::SRem(i) => vec![self.operand(&i.operand0), self.operand(&i.operand1)],
+            Instruction::And(i) => vec![self.operand(&i.operand0), self.operand(&i.operand1)],
+            Instruction::Or(i) => vec![self.operand(&i.operand0), self.operand(&i.operand1)],
+            Instruction::Xor(i) => vec![self.operand(&i.operand0), self.operand(&i.operand1)],
+            Instruction::Shl(i) => vec![self.operand(&i.operand0), self.operand(&i.operand1)],
+            Instruction::LShr(i) => vec![self.operand(&i.operand0), self.operand(&i.operand1)],
+            Instruction::AShr(i) => vec![self.operand(&i.operand0), self.operand(&i.operand1)],
+            Instruction::ICmp(i) => vec![self.operand(&i.operand0), self.operand(&i.operand1)],
+            Instruction::Trunc(i) => vec![self.operand(&i.operand)],
+            Instruction::ZExt(i) => vec![self.operand(&i.operand)],
+            Instruction::SExt(i) => vec![self.operand(&i.operand)],
+            #[cfg(feature = "llvm-10-or-greater")]
+            Instruction::Freeze(i) => return self.operand(&i.operand),
+            _ => return Lattice::Overdefined,
+        };
+        if operands.contains(&Lattice::Overdefined) {
+            return Lattice::Overdefined;
+        }
+        if operands.contains(&Lattice::Undefined) {
+            return Lattice::Undefined;
+        }
+        let ints: Option<Vec<(u32, u64)>> = operands.iter().map(Lattice::as_int).collect();
+        match ints.and_then(|ints| fold(inst, &ints)) {
+            Some((bits, value)) => Lattice::Constant(ConstantRef::new(Constant::Int {
+                bits,
+                value: value & mask(bits),
+            })),
+            None => Lattice::Overdefined,
+        }
+    }
+}
+
+/// Fold the given instruction, whose operands are the given integer constants,
+/// to an integer constant, as `(bits, value)`
+fn fold(inst: &Instruction, operands: &[(u32, u64)]) -> Option<(u32, u64)> {
+    let (bits, a) = operands[0];
+    let b = operands.get(1).map(|&(_, b)| b).unwrap_or(0);
+    let (sa, sb) = (signed(bits, a), signed(bits, b));
+    let shift = |b: u64| {
+        if b < u64::from(bits) {
+            Some(b as u32)
+        } else {
+            None
+        }
+    };
+    let value = match inst {
+        Instruction::Add(_) => a.wrapping_add(b),
+        Instruction::Sub(_) => a.wrapping_sub(b),
+        Instruction::Mul(_) => a.wrapping_mul(b),
+        Instruction::UDiv(_) => a.checked_div(b)?,
+        Instruction::URem(_) => a.checked_rem(b)?,
+        // `i128`s can't overflow here, and the result is truncated
+        Instruction::SDiv(_) if sb != 0 => (sa / sb) as u64,
+        Instruction::SRem(_) if sb != 0 => (sa % sb) as u64,
+        Instruction::And(_) => a & b,
+        Instruction::Or(_) => a | b,
+        Instruction::Xor(_) => a ^ b,
+        Instruction::Shl(_) => a << shift(b)?,
+        Instruction::LShr(_) => a >> shift(b)?,
+        Instruction::AShr(_) => (sa >> shift(b)?) as u64,
+        Instruction::ICmp(icmp) => return Some((1, compare(icmp.predicate, bits, a, b).into())),
+        Instruction::Trunc(trunc) => return Some((int_bits(&trunc.to_type)?, a)),
+        Instruction::ZExt(zext) => return Some((int_bits(&zext.to_type)?, a)),
+        Instruction::SExt(sext) => return Some((int_bits(&sext.to_type)?, sa as u64)),
+        _ => return None,
+    };
+    Some((bits, value))
+}
+
+/// Evaluate `a predicate b` for `bits`-wide integers `a` and `b`
+fn compare(predicate: IntPredicate, bits: u32, a: u64, b: u64) -> bool {
+    let (sa, sb) = (signed(bits, a), signed(bits, b));
+    match predicate {
+        IntPredicate::EQ => a == b,
+        IntPredicate::UGT => a > b,
+        IntPredicate::UGE => a >= b,
+        IntPredicate::ULT => a < b,
+        IntPredicate::ULE => a <= b,
+        IntPredicate::SGT => sa > sb,
+        IntPredicate::SGE => sa >= sb,
+        IntPredicate::SLT => sa < sb,
+        IntPredicate::SLE => sa <= sb,
+        IntPredicate::NE => !compare(negate(predicate), bits, a, b),
+    }
+}
+
+/// Get the mask of the low `bits` bits
+fn mask(bits: u32) -> u64 {
+    u64::MAX >> (64 - bits)
+}
+
+/// Get the number of bits of the given type, if it's an integer type of at
+/// most 64 bits
+fn int_bits(ty: &llvm_ir::Type) -> Option<u32> {
+    match ty {
+        llvm_ir::Type::IntegerType { bits } if *bits <= 64 => Some(*bits),
+        _ => None,
+    }
+}
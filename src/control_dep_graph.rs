@@ -1,9 +1,11 @@
-use crate::control_flow_graph::{CFGNode, ControlFlowGraph};
+use crate::control_flow_graph::{CFGEdgeKind, CFGNode, ControlFlowGraph};
 use crate::dominator_tree::PostDominatorTree;
 use llvm_ir::Name;
 use petgraph::prelude::{DfsPostOrder, DiGraphMap, Direction};
 use petgraph::visit::Walker;
-use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 
 /// The control dependence graph for a particular function.
 /// https://en.wikipedia.org/wiki/Data_dependency#Control_Dependency
@@ -15,14 +17,43 @@ pub struct ControlDependenceGraph<'m> {
     /// The graph itself. An edge from bbX to bbY indicates that bbX has an
     /// immediate control dependence on bbY. A path from bbX to bbY indicates
     /// that bbX has a control dependence on bbY.
-    graph: DiGraphMap<CFGNode<'m>, ()>,
+    ///
+    /// Each edge's weight is the list of outcomes of bbY's terminator which
+    /// lead to bbX, i.e., the CFG edges from bbY which induce the dependence
+    pub(crate) graph: DiGraphMap<CFGNode<'m>, Vec<CFGEdgeKind<'m>>>,
 
     /// Entry node for the function
     pub(crate) entry_node: CFGNode<'m>,
 }
 
+/// A set of blocks with identical immediate control dependences, including
+/// which outcome of each controlling branch leads to them; see
+/// [`ControlDependenceGraph::regions()`](struct.ControlDependenceGraph.html#method.regions).
+/// These are the region nodes of Ferrante, Ottenstein, and Warren's program
+/// dependence graph: the blocks in a region execute under exactly the same
+/// conditions.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ControlDependenceRegion<'m> {
+    /// The blocks in the region, in the order they appear in the function
+    pub blocks: Vec<&'m Name>,
+    /// The immediate control dependences shared by the blocks in the region:
+    /// each controlling block, with an outcome of its terminator which leads
+    /// to the region. These are sorted by the controlling block's `Name`. This
+    /// is empty for the region of blocks which execute whenever the function
+    /// does, such as the entry block.
+    pub dependences: Vec<(&'m Name, CFGEdgeKind<'m>)>,
+}
+
 impl<'m> ControlDependenceGraph<'m> {
-    pub(crate) fn new(cfg: &ControlFlowGraph<'m>, postdomtree: &PostDominatorTree<'m>) -> Self {
+    /// Compute the control dependence graph of the given CFG, whose
+    /// postdominator tree is `postdomtree`.
+    ///
+    /// For a function's own CFG, use
+    /// [`FunctionAnalysis::control_dependence_graph()`](struct.FunctionAnalysis.html#method.control_dependence_graph)
+    /// instead, which caches the result. This is for CFGs derived from it,
+    /// such as those given by
+    /// [`ControlFlowGraph::without_edges()`](struct.ControlFlowGraph.html#method.without_edges).
+    pub fn new(cfg: &ControlFlowGraph<'m>, postdomtree: &PostDominatorTree<'m>) -> Self {
         // algorithm thanks to Cytron, Ferrante, Rosen, et al. "Efficiently Computing Static Single Assignment Form and the Control Dependence Graph"
         // https://www.cs.utexas.edu/~pingali/CS380C/2010/papers/ssaCytron.pdf (Figure 10)
 
@@ -50,6 +81,27 @@ impl<'m> ControlDependenceGraph<'m> {
             }
         }
 
+        // label each dependence of bbX on bbY with the outcomes of bbY's
+        // terminator which lead to bbX: those whose destination is
+        // postdominated by bbX. Every node of the CFG is included, so that
+        // `regions()` sees blocks without any control dependencies too
+        let mut labeled = DiGraphMap::with_capacity(cfg.graph.node_count(), graph.edge_count());
+        for node in cfg.graph.nodes() {
+            labeled.add_node(node);
+        }
+        for (dependent, controller, ()) in graph.all_edges() {
+            let kinds = match controller {
+                CFGNode::Block(controller) => cfg
+                    .labeled_succs(controller)
+                    .filter(|&(dest, _)| postdomtree.postdominates(dependent, dest))
+                    .map(|(_, kind)| kind)
+                    .collect(),
+                CFGNode::Return => vec![],
+            };
+            labeled.add_edge(dependent, controller, kinds);
+        }
+        let graph = labeled;
+
         Self {
             graph,
             entry_node: cfg.entry_node,
@@ -76,6 +128,34 @@ impl<'m> ControlDependenceGraph<'m> {
             })
     }
 
+    /// Get the blocks that `block` has an immediate control dependency on, along
+    /// with the outcome of each one's terminator which leads to `block`. A
+    /// block appears once for each such outcome (see
+    /// [`edge_kinds()`](#method.edge_kinds)).
+    pub fn labeled_imm_control_dependencies<'s>(
+        &'s self,
+        block: &'m Name,
+    ) -> impl Iterator<Item = (&'m Name, CFGEdgeKind<'m>)> + 's {
+        self.graph
+            .edges_directed(CFGNode::Block(block), Direction::Outgoing)
+            .flat_map(|(_, controller, kinds)| match controller {
+                CFGNode::Block(controller) => kinds.iter().map(move |&kind| (controller, kind)),
+                CFGNode::Return => panic!("Nothing should be control-dependent on Return"),
+            })
+    }
+
+    /// Get the outcomes of the terminator of block `controller` which lead to
+    /// `block`, i.e., which way `controller` must branch for `block` to
+    /// execute. For instance, this is `[CFGEdgeKind::CondBr(true)]` if `block`
+    /// executes only when `controller`'s condition is true. If `block` doesn't
+    /// have an immediate control dependency on `controller`, this is empty.
+    pub fn edge_kinds(&self, block: &'m Name, controller: &'m Name) -> &[CFGEdgeKind<'m>] {
+        self.graph
+            .edge_weight(CFGNode::Block(block), CFGNode::Block(controller))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
     /// Get the blocks that `block` has a control dependency on (including
     /// transitively).
     ///
@@ -88,7 +168,10 @@ impl<'m> ControlDependenceGraph<'m> {
         ControlDependenciesIterator::new(self, block)
     }
 
-    /// Get the blocks that have an immediate control dependency on `block`.
+    /// Get the blocks that have an immediate control dependency on `block`,
+    /// i.e., the blocks whose execution is decided directly by `block`'s
+    /// terminator. This is the inverse of
+    /// [`get_imm_control_dependencies()`](#method.get_imm_control_dependencies).
     pub fn get_imm_control_dependents<'s>(
         &'s self,
         block: &'m Name,
@@ -145,6 +228,47 @@ impl<'m> ControlDependenceGraph<'m> {
         }
     }
 
+    /// Partition the blocks of the function into regions of blocks with
+    /// identical immediate control dependences (see
+    /// [`labeled_imm_control_dependencies()`](#method.labeled_imm_control_dependencies)).
+    /// Regions are in the order of their first block in the function.
+    ///
+    /// Unlike the CDG itself, the result of this method is not cached.
+    pub fn regions(&self) -> Vec<ControlDependenceRegion<'m>> {
+        let mut regions: Vec<ControlDependenceRegion<'m>> = Vec::new();
+        // map from the hash of a region's dependences to the indices of the
+        // regions with that hash (`CFGEdgeKind` isn't `Eq`, so we can't key on
+        // the dependences themselves)
+        let mut by_hash: HashMap<u64, Vec<usize>> = HashMap::new();
+        for node in self.graph.nodes() {
+            let block = match node {
+                CFGNode::Block(block) => block,
+                CFGNode::Return => continue,
+            };
+            let mut dependences: Vec<(&'m Name, CFGEdgeKind<'m>)> =
+                self.labeled_imm_control_dependencies(block).collect();
+            // stable sort, so each controller's outcomes stay in terminator order
+            dependences.sort_by_key(|&(controller, _)| controller);
+            let mut hasher = DefaultHasher::new();
+            dependences.hash(&mut hasher);
+            let candidates = by_hash.entry(hasher.finish()).or_default();
+            match candidates
+                .iter()
+                .find(|&&idx| regions[idx].dependences == dependences)
+            {
+                Some(&idx) => regions[idx].blocks.push(block),
+                None => {
+                    candidates.push(regions.len());
+                    regions.push(ControlDependenceRegion {
+                        blocks: vec![block],
+                        dependences,
+                    });
+                }
+            }
+        }
+        regions
+    }
+
     /// Get the `Name` of the entry block for the function
     pub fn entry(&self) -> &'m Name {
         match self.entry_node {
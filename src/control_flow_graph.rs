@@ -1,5 +1,9 @@
-use llvm_ir::{Function, Name, Terminator};
-use petgraph::prelude::{DiGraphMap, Direction};
+use crate::dominator_tree::DominatorTree;
+use llvm_ir::terminator::CallBr;
+use llvm_ir::{Constant, ConstantRef, Function, Instruction, Name, Operand, Terminator};
+use petgraph::prelude::{DfsPostOrder, DiGraphMap, Direction};
+use petgraph::visit::{Dfs, Reversed, Walker};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt;
 
 /// The control flow graph for a particular function.
@@ -7,16 +11,98 @@ use std::fmt;
 /// To construct a `ControlFlowGraph`, use
 /// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
 /// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+///
+/// <a name="callbr"></a>
+/// `llvm-ir` doesn't record the indirect destinations of a `callbr` (i.e.,
+/// `asm goto`), so by default the CFG only has edges from a `callbr` to the
+/// indirect destinations it's known to have: blocks other than its normal
+/// destination with a `phi` that has an incoming value from the `callbr`'s
+/// block. Other indirect destinations (those without `phi`s) are missing, and
+/// may appear unreachable. For a conservative CFG, use
+/// [`FunctionAnalysis::control_flow_graph_with_all_callbr_edges()`](struct.FunctionAnalysis.html#method.control_flow_graph_with_all_callbr_edges),
+/// which assumes the inline assembly may jump to any block other than the
+/// entry block, unless a `phi` in that block rules it out, or (through LLVM 14)
+/// the `callbr` has no `blockaddress` arguments. (Through LLVM 14, the indirect
+/// destinations are exactly the blocks named in the `callbr`'s `blockaddress`
+/// arguments, but `llvm-ir` doesn't record which block a `blockaddress`
+/// refers to either.)
+///
+/// CFG edges are labeled with the terminator outcomes they represent (see
+/// [`CFGEdgeKind`](enum.CFGEdgeKind.html)). They may also have weights, e.g.
+/// branch weights from profiling, but these must be supplied by the caller
+/// (see [`with_edge_weights()`](#method.with_edge_weights)): `llvm-ir` doesn't
+/// expose instruction metadata, so `!prof` branch weights in the bitcode
+/// aren't available to this crate.
 pub struct ControlFlowGraph<'m> {
     /// The graph itself. Nodes are basic block names, and an edge from bbX to
     /// bbY indicates that control may (immediately) flow from bbX to bbY
     ///
-    /// Or, an edge from bbX to `Return` indicates that the function may return
-    /// from bbX
-    pub(crate) graph: DiGraphMap<CFGNode<'m>, ()>,
+    /// Or, an edge from bbX to `Return` indicates that the function may exit
+    /// from bbX, in one of the ways selected by `exits`
+    ///
+    /// Each edge's weight is the list of outcomes of bbX's terminator which
+    /// transfer control along that edge
+    pub(crate) graph: DiGraphMap<CFGNode<'m>, Vec<CFGEdgeKind<'m>>>,
 
     /// Entry node for the function
     pub(crate) entry_node: CFGNode<'m>,
+
+    /// Which kinds of function exit have an edge to `Return`
+    pub(crate) exits: Vec<ExitKind>,
+
+    /// Weights of edges, as supplied to `with_edge_weights()`. Edges without
+    /// a weight are absent
+    weights: HashMap<(CFGNode<'m>, CFGNode<'m>), u64>,
+}
+
+/// Get the indirect destinations of the given `callbr`, which terminates the
+/// block named `block` in `function`.
+///
+/// `llvm-ir` doesn't expose a `callbr`'s indirect destinations, so if
+/// `all_possible` is false, this is only the destinations which are known: the
+/// blocks other than the `callbr`'s normal destination with a `phi` which has
+/// an incoming value from `block`.
+///
+/// If `all_possible` is true, it's a conservative approximation instead: any
+/// block other than the entry block and the `callbr`'s normal destination,
+/// except that
+///   - blocks with a `phi` which has no incoming value from `block` can't be
+///     destinations of `block`'s terminator;
+///   - through LLVM 14, each indirect destination must be passed to the
+///     `callbr` as a `blockaddress` argument, so if there are no such
+///     arguments, there are no indirect destinations.
+pub(crate) fn callbr_indirect_dests<'m>(
+    function: &'m Function,
+    block: &Name,
+    callbr: &CallBr,
+    all_possible: bool,
+) -> Vec<&'m Name> {
+    if cfg!(feature = "llvm-14-or-lower")
+        && !callbr.arguments.iter().any(|(arg, _)| {
+            matches!(arg, Operand::ConstantOperand(c) if matches!(c.as_ref(), Constant::BlockAddress))
+        })
+    {
+        return vec![];
+    }
+    function
+        .basic_blocks
+        .iter()
+        .skip(1)
+        .filter(|bb| bb.name != callbr.return_label)
+        .filter(|bb| {
+            let mut phis = bb
+                .instrs
+                .iter()
+                .filter_map(|inst| match inst {
+                    Instruction::Phi(phi) => Some(phi),
+                    _ => None,
+                })
+                .peekable();
+            (all_possible || phis.peek().is_some())
+                && phis.all(|phi| phi.incoming_values.iter().any(|(_, pred)| pred == block))
+        })
+        .map(|bb| &bb.name)
+        .collect()
 }
 
 /// A CFGNode represents a basic block, or the special node `Return`
@@ -24,8 +110,156 @@ pub struct ControlFlowGraph<'m> {
 pub enum CFGNode<'m> {
     /// The block with the given `Name`
     Block(&'m Name),
-    /// The special `Return` node indicating function return
+    /// The special `Return` node indicating function exit. By default, this
+    /// is the single sink for both returning normally and unwinding to the
+    /// caller; see [`ExitKind`](enum.ExitKind.html).
+    Return,
+}
+
+/// A way in which control may leave a function.
+///
+/// A `ControlFlowGraph` has edges to `CFGNode::Return` only for the kinds of
+/// exit it was constructed with. The `ControlFlowGraph` given by
+/// [`FunctionAnalysis::control_flow_graph()`](struct.FunctionAnalysis.html#method.control_flow_graph)
+/// merges [`ExitKind::DEFAULT`](#associatedconstant.DEFAULT) into its `Return`
+/// node, and blocks ending in `unreachable` simply have no successors. Use
+/// [`FunctionAnalysis::control_flow_graph_with_exits()`](struct.FunctionAnalysis.html#method.control_flow_graph_with_exits)
+/// to choose a different set of exits, e.g., to compute postdominance with
+/// respect to normal returns only.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum ExitKind {
+    /// A `ret`
+    Return,
+    /// An `unreachable`
+    Unreachable,
+    /// A `resume`, or a `cleanupret` or `catchswitch` which unwinds to the
+    /// caller
+    Unwind,
+    /// A virtual exit from each infinite loop, i.e., each loop (or set of
+    /// nested loops) which control can never leave, not even by going to
+    /// another exit. The edge to `CFGNode::Return` is from the loop's last
+    /// block in the order the blocks appear in the function.
+    ///
+    /// This is the standard way to make postdominance and control dependence
+    /// meaningful for blocks which can't reach any other exit: without it,
+    /// such blocks don't appear in the `PostDominatorTree` at all, and have no
+    /// control dependencies. Blocks ending in `unreachable` (and not in a
+    /// loop) aren't affected; for those, use `ExitKind::Unreachable`.
+    InfiniteLoop,
+}
+
+impl ExitKind {
+    /// The exits which are merged into `CFGNode::Return` by default: `Return`
+    /// and `Unwind`
+    pub const DEFAULT: &'static [ExitKind] = &[ExitKind::Return, ExitKind::Unwind];
+}
+
+/// Which outcome of a block's terminator a CFG edge represents
+#[derive(Clone, Copy, PartialEq, Debug, Hash)]
+pub enum CFGEdgeKind<'m> {
+    /// An unconditional `br`, or a `catchret`
+    Unconditional,
+    /// A conditional `br` whose condition had the given value
+    CondBr(bool),
+    /// A `switch` whose value matched the given case
+    SwitchCase(&'m ConstantRef),
+    /// A `switch` whose value matched none of the cases
+    SwitchDefault,
+    /// An `indirectbr` to this destination
+    IndirectBr,
+    /// An `invoke` whose callee returned normally
+    InvokeNormal,
+    /// An `invoke` whose callee unwound
+    InvokeException,
+    /// A `catchswitch` transferring control to one of its handlers
+    CatchHandler,
+    /// A `cleanupret` or `catchswitch` unwinding, either to a block in this
+    /// function, or (for an edge to `CFGNode::Return`) to the caller
+    Unwind,
+    /// A `ret` (always an edge to `CFGNode::Return`)
     Return,
+    /// A `resume` (always an edge to `CFGNode::Return`)
+    Resume,
+    /// An `unreachable` (always an edge to `CFGNode::Return`), or in CFGs from
+    /// [`NoReturnAnalysis`](struct.NoReturnAnalysis.html), a call which never
+    /// returns. These edges are only present if `ExitKind::Unreachable` was
+    /// requested.
+    Unreachable,
+    /// A `callbr` whose inline assembly fell through to its normal
+    /// destination
+    CallBrFallthrough,
+    /// A `callbr` whose inline assembly jumped to one of its indirect
+    /// destinations (see
+    /// [`ControlFlowGraph`](struct.ControlFlowGraph.html#callbr) for how these
+    /// are determined)
+    CallBrIndirect,
+    /// An abnormal edge from a block containing a call which may `longjmp`, to
+    /// a block containing a `setjmp` which the `longjmp` may return to (see
+    /// [`SetjmpAnalysis::cfg_with_abnormal_edges()`](struct.SetjmpAnalysis.html#method.cfg_with_abnormal_edges))
+    Longjmp,
+    /// A virtual edge to `CFGNode::Return` from a block in an infinite loop.
+    /// These edges are only present if `ExitKind::InfiniteLoop` was requested.
+    InfiniteLoop,
+}
+
+impl<'m> CFGEdgeKind<'m> {
+    /// Is this edge only taken while an exception is propagating? This is true
+    /// for `InvokeException`, `CatchHandler`, `Unwind`, and `Resume` edges.
+    pub fn is_exceptional(&self) -> bool {
+        matches!(
+            self,
+            CFGEdgeKind::InvokeException
+                | CFGEdgeKind::CatchHandler
+                | CFGEdgeKind::Unwind
+                | CFGEdgeKind::Resume
+        )
+    }
+
+    /// A number identifying the kind of edge, for
+    /// [`ControlFlowGraph::structural_hash()`](struct.ControlFlowGraph.html#method.structural_hash).
+    /// These must not change, so that the hash is stable.
+    fn structural_tag(&self) -> u64 {
+        match self {
+            CFGEdgeKind::Unconditional => 0,
+            CFGEdgeKind::CondBr(false) => 1,
+            CFGEdgeKind::CondBr(true) => 2,
+            CFGEdgeKind::SwitchCase(_) => 3,
+            CFGEdgeKind::SwitchDefault => 4,
+            CFGEdgeKind::IndirectBr => 5,
+            CFGEdgeKind::InvokeNormal => 6,
+            CFGEdgeKind::InvokeException => 7,
+            CFGEdgeKind::CatchHandler => 8,
+            CFGEdgeKind::Unwind => 9,
+            CFGEdgeKind::Return => 10,
+            CFGEdgeKind::Resume => 11,
+            CFGEdgeKind::Unreachable => 12,
+            CFGEdgeKind::CallBrFallthrough => 13,
+            CFGEdgeKind::CallBrIndirect => 14,
+            CFGEdgeKind::Longjmp => 15,
+            CFGEdgeKind::InfiniteLoop => 16,
+        }
+    }
+}
+
+/// 64-bit FNV-1a hasher, which (unlike `std`'s `DefaultHasher`) is guaranteed
+/// to give the same results everywhere
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        for byte in value.to_le_bytes() {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
 }
 
 impl<'m> fmt::Display for CFGNode<'m> {
@@ -39,96 +273,206 @@ impl<'m> fmt::Display for CFGNode<'m> {
 
 impl<'m> ControlFlowGraph<'m> {
     pub(crate) fn new(function: &'m Function) -> Self {
-        let mut graph: DiGraphMap<CFGNode<'m>, ()> = DiGraphMap::with_capacity(
-            function.basic_blocks.len() + 1,
-            2 * function.basic_blocks.len(), // arbitrary guess
-        );
+        Self::with_exits(function, ExitKind::DEFAULT)
+    }
+
+    /// Construct the CFG with edges to `CFGNode::Return` for only the given
+    /// kinds of exit
+    pub(crate) fn with_exits(function: &'m Function, exits: &[ExitKind]) -> Self {
+        Self::with_options(function, exits, false)
+    }
+
+    /// Construct the CFG with edges to `CFGNode::Return` for only the given
+    /// kinds of exit, and if `all_callbr_edges` is true, with an edge from each
+    /// `callbr` to every block it may possibly jump to (see
+    /// [`ControlFlowGraph`](struct.ControlFlowGraph.html#callbr))
+    pub(crate) fn with_options(
+        function: &'m Function,
+        exits: &[ExitKind],
+        all_callbr_edges: bool,
+    ) -> Self {
+        let mut cfg = Self {
+            graph: DiGraphMap::with_capacity(
+                function.basic_blocks.len() + 1,
+                2 * function.basic_blocks.len(), // arbitrary guess
+            ),
+            entry_node: CFGNode::Block(&function.basic_blocks[0].name),
+            exits: exits.to_vec(),
+            weights: HashMap::new(),
+        };
+        let returns = exits.contains(&ExitKind::Return);
+        let unwinds = exits.contains(&ExitKind::Unwind);
+        let unreachables = exits.contains(&ExitKind::Unreachable);
 
         for bb in &function.basic_blocks {
+            // every block is a node, even if it has no predecessors or
+            // successors
+            cfg.graph.add_node(CFGNode::Block(&bb.name));
+        }
+        for bb in &function.basic_blocks {
+            let from = CFGNode::Block(&bb.name);
             match &bb.term {
                 Terminator::Br(br) => {
-                    graph.add_edge(CFGNode::Block(&bb.name), CFGNode::Block(&br.dest), ());
+                    cfg.add_edge(from, CFGNode::Block(&br.dest), CFGEdgeKind::Unconditional);
                 }
                 Terminator::CondBr(condbr) => {
-                    graph.add_edge(
-                        CFGNode::Block(&bb.name),
+                    cfg.add_edge(
+                        from,
                         CFGNode::Block(&condbr.true_dest),
-                        (),
+                        CFGEdgeKind::CondBr(true),
                     );
-                    graph.add_edge(
-                        CFGNode::Block(&bb.name),
+                    cfg.add_edge(
+                        from,
                         CFGNode::Block(&condbr.false_dest),
-                        (),
+                        CFGEdgeKind::CondBr(false),
                     );
                 }
                 Terminator::IndirectBr(ibr) => {
                     for dest in &ibr.possible_dests {
-                        graph.add_edge(CFGNode::Block(&bb.name), CFGNode::Block(dest), ());
+                        cfg.add_edge(from, CFGNode::Block(dest), CFGEdgeKind::IndirectBr);
                     }
                 }
                 Terminator::Switch(switch) => {
-                    graph.add_edge(
-                        CFGNode::Block(&bb.name),
+                    cfg.add_edge(
+                        from,
                         CFGNode::Block(&switch.default_dest),
-                        (),
+                        CFGEdgeKind::SwitchDefault,
                     );
-                    for (_, dest) in &switch.dests {
-                        graph.add_edge(CFGNode::Block(&bb.name), CFGNode::Block(dest), ());
+                    for (value, dest) in &switch.dests {
+                        cfg.add_edge(from, CFGNode::Block(dest), CFGEdgeKind::SwitchCase(value));
                     }
                 }
-                Terminator::Ret(_) | Terminator::Resume(_) => {
-                    graph.add_edge(CFGNode::Block(&bb.name), CFGNode::Return, ());
+                Terminator::Ret(_) => {
+                    if returns {
+                        cfg.add_edge(from, CFGNode::Return, CFGEdgeKind::Return);
+                    }
+                }
+                Terminator::Resume(_) => {
+                    if unwinds {
+                        cfg.add_edge(from, CFGNode::Return, CFGEdgeKind::Resume);
+                    }
                 }
                 Terminator::Invoke(invoke) => {
-                    graph.add_edge(
-                        CFGNode::Block(&bb.name),
+                    cfg.add_edge(
+                        from,
                         CFGNode::Block(&invoke.return_label),
-                        (),
+                        CFGEdgeKind::InvokeNormal,
                     );
-                    graph.add_edge(
-                        CFGNode::Block(&bb.name),
+                    cfg.add_edge(
+                        from,
                         CFGNode::Block(&invoke.exception_label),
-                        (),
+                        CFGEdgeKind::InvokeException,
                     );
                 }
                 Terminator::CleanupRet(cleanupret) => {
                     if let Some(dest) = &cleanupret.unwind_dest {
-                        graph.add_edge(CFGNode::Block(&bb.name), CFGNode::Block(dest), ());
-                    } else {
-                        graph.add_edge(CFGNode::Block(&bb.name), CFGNode::Return, ());
+                        cfg.add_edge(from, CFGNode::Block(dest), CFGEdgeKind::Unwind);
+                    } else if unwinds {
+                        cfg.add_edge(from, CFGNode::Return, CFGEdgeKind::Unwind);
                     }
                 }
                 Terminator::CatchRet(catchret) => {
                     // Despite its name, my reading of the LLVM 10 LangRef indicates that CatchRet cannot directly return from the function
-                    graph.add_edge(
-                        CFGNode::Block(&bb.name),
+                    cfg.add_edge(
+                        from,
                         CFGNode::Block(&catchret.successor),
-                        (),
+                        CFGEdgeKind::Unconditional,
                     );
                 }
                 Terminator::CatchSwitch(catchswitch) => {
                     if let Some(dest) = &catchswitch.default_unwind_dest {
-                        graph.add_edge(CFGNode::Block(&bb.name), CFGNode::Block(dest), ());
-                    } else {
-                        graph.add_edge(CFGNode::Block(&bb.name), CFGNode::Return, ());
+                        cfg.add_edge(from, CFGNode::Block(dest), CFGEdgeKind::Unwind);
+                    } else if unwinds {
+                        cfg.add_edge(from, CFGNode::Return, CFGEdgeKind::Unwind);
                     }
                     for handler in &catchswitch.catch_handlers {
-                        graph.add_edge(CFGNode::Block(&bb.name), CFGNode::Block(handler), ());
+                        cfg.add_edge(from, CFGNode::Block(handler), CFGEdgeKind::CatchHandler);
+                    }
+                }
+                Terminator::CallBr(callbr) => {
+                    cfg.add_edge(
+                        from,
+                        CFGNode::Block(&callbr.return_label),
+                        CFGEdgeKind::CallBrFallthrough,
+                    );
+                    for dest in callbr_indirect_dests(function, &bb.name, callbr, all_callbr_edges)
+                    {
+                        cfg.add_edge(from, CFGNode::Block(dest), CFGEdgeKind::CallBrIndirect);
                     }
                 }
-                Terminator::CallBr(_) => unimplemented!("CallBr instruction"),
                 Terminator::Unreachable(_) => {
-                    // no successors
+                    if unreachables {
+                        cfg.add_edge(from, CFGNode::Return, CFGEdgeKind::Unreachable);
+                    }
                 }
             }
         }
+        if exits.contains(&ExitKind::InfiniteLoop) {
+            cfg.add_infinite_loop_exits();
+        }
 
-        Self {
-            graph,
-            entry_node: CFGNode::Block(&function.basic_blocks[0].name),
+        cfg
+    }
+
+    /// Add an edge to `Return` from each infinite loop (see
+    /// `ExitKind::InfiniteLoop`): each SCC which contains a cycle, but has no
+    /// edges to other SCCs or to `Return`
+    fn add_infinite_loop_exits(&mut self) {
+        let condensation = self.condensation();
+        for (idx, scc) in condensation.sccs().iter().enumerate() {
+            let infinite = condensation.is_cyclic(idx)
+                && condensation.succs(idx).next().is_none()
+                && !scc.iter().any(|&block| {
+                    self.graph
+                        .contains_edge(CFGNode::Block(block), CFGNode::Return)
+                });
+            if infinite {
+                let last = scc[scc.len() - 1];
+                self.add_edge(
+                    CFGNode::Block(last),
+                    CFGNode::Return,
+                    CFGEdgeKind::InfiniteLoop,
+                );
+            }
+        }
+    }
+
+    /// Add an edge from `from` to `to`, or if there already is one, add `kind`
+    /// to its kinds
+    pub(crate) fn add_edge(&mut self, from: CFGNode<'m>, to: CFGNode<'m>, kind: CFGEdgeKind<'m>) {
+        match self.graph.edge_weight_mut(from, to) {
+            Some(kinds) => kinds.push(kind),
+            None => {
+                self.graph.add_edge(from, to, vec![kind]);
+            }
         }
     }
 
+    /// Iterate over all of the basic blocks in the function (i.e., all nodes
+    /// of the CFG other than `CFGNode::Return`), in the order they appear in
+    /// the function. This includes blocks which are unreachable from the entry
+    /// block.
+    pub fn blocks<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
+        self.graph.nodes().filter_map(|node| match node {
+            CFGNode::Block(block) => Some(block),
+            CFGNode::Return => None,
+        })
+    }
+
+    /// Get the number of basic blocks in the function, i.e., the number of
+    /// nodes in the CFG other than `CFGNode::Return`
+    pub fn num_blocks(&self) -> usize {
+        self.graph.node_count() - usize::from(self.graph.contains_node(CFGNode::Return))
+    }
+
+    /// Get the number of edges in the CFG, including edges to
+    /// `CFGNode::Return`. Several outcomes of a terminator with the same
+    /// destination (see [`edge_kinds()`](#method.edge_kinds)) count as a single
+    /// edge.
+    pub fn num_edges(&self) -> usize {
+        self.graph.edge_count()
+    }
+
     /// Get the predecessors of the basic block with the given `Name`
     pub fn preds<'s>(&'s self, block: &'m Name) -> impl Iterator<Item = &'m Name> + 's {
         self.preds_of_cfgnode(CFGNode::Block(block))
@@ -140,6 +484,47 @@ impl<'m> ControlFlowGraph<'m> {
         self.preds_of_cfgnode(CFGNode::Return)
     }
 
+    /// Get the blocks which may directly exit the function in the given way.
+    /// This is empty if this CFG doesn't include that kind of exit (see
+    /// [`exits()`](#method.exits)).
+    pub fn preds_of_exit<'s>(&'s self, kind: ExitKind) -> impl Iterator<Item = &'m Name> + 's {
+        self.preds_of_return().filter(move |&block| {
+            self.edge_kinds(block, CFGNode::Return)
+                .iter()
+                .any(|edge_kind| match edge_kind {
+                    CFGEdgeKind::Return => kind == ExitKind::Return,
+                    CFGEdgeKind::Unreachable => kind == ExitKind::Unreachable,
+                    CFGEdgeKind::InfiniteLoop => kind == ExitKind::InfiniteLoop,
+                    _ => kind == ExitKind::Unwind,
+                })
+        })
+    }
+
+    /// Get the blocks whose terminators may leave the function (e.g., `ret`,
+    /// `resume`, or `unreachable`), in the order they appear in the function.
+    /// These are the blocks which have an edge to `CFGNode::Return` (see
+    /// [`preds_of_return()`](#method.preds_of_return)), plus any blocks with
+    /// no successors at all, e.g., blocks ending in `unreachable` in a CFG
+    /// which doesn't treat `unreachable` as an exit (see
+    /// [`exits()`](#method.exits)).
+    pub fn exit_blocks<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
+        self.graph.nodes().filter_map(move |node| match node {
+            CFGNode::Block(block)
+                if self.succs(block).next().is_none()
+                    || self.graph.contains_edge(node, CFGNode::Return) =>
+            {
+                Some(block)
+            }
+            _ => None,
+        })
+    }
+
+    /// Get the kinds of function exit which this CFG connects to
+    /// `CFGNode::Return`
+    pub fn exits(&self) -> &[ExitKind] {
+        &self.exits
+    }
+
     pub(crate) fn preds_of_cfgnode<'s>(
         &'s self,
         node: CFGNode<'m>,
@@ -165,6 +550,184 @@ impl<'m> ControlFlowGraph<'m> {
             .neighbors_directed(CFGNode::Block(block), Direction::Outgoing)
     }
 
+    /// Get the outcomes of the terminator of block `from` which transfer
+    /// control to `to`. Usually there is just one, but, e.g., several cases of
+    /// a `switch` may have the same destination. If `to` is not a successor of
+    /// `from`, this is empty.
+    pub fn edge_kinds(&self, from: &'m Name, to: CFGNode<'m>) -> &[CFGEdgeKind<'m>] {
+        self.graph
+            .edge_weight(CFGNode::Block(from), to)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Get the weight of the edge from block `from` to `to`, as given to
+    /// [`with_edge_weights()`](#method.with_edge_weights), or `None` if the
+    /// edge has no weight (or isn't in the CFG).
+    pub fn edge_weight(&self, from: &'m Name, to: CFGNode<'m>) -> Option<u64> {
+        self.weights.get(&(CFGNode::Block(from), to)).copied()
+    }
+
+    /// Get a copy of this CFG with the given weights on its edges, each
+    /// specified as a triple `(from, to, weight)`; e.g., branch weights from a
+    /// profile, or from the `!prof` metadata of the function's terminators.
+    /// These replace any weights the edges already have, and are returned by
+    /// [`edge_weight()`](#method.edge_weight). Weights of edges which aren't in
+    /// the CFG are ignored.
+    ///
+    /// Weights are kept by methods which return a copy of the CFG with some
+    /// edges removed, such as [`without_edges()`](#method.without_edges).
+    pub fn with_edge_weights(
+        &self,
+        weights: impl IntoIterator<Item = (&'m Name, CFGNode<'m>, u64)>,
+    ) -> Self {
+        let mut cfg = self.filter(|_| true, |_, _, _| true);
+        for (from, to, weight) in weights {
+            let from = CFGNode::Block(from);
+            if cfg.graph.contains_edge(from, to) {
+                cfg.weights.insert((from, to), weight);
+            }
+        }
+        cfg
+    }
+
+    /// Get the successors of the basic block with the given `Name`, along
+    /// with the outcome of the block's terminator which leads to each. A
+    /// successor appears once for each such outcome (see
+    /// [`edge_kinds()`](#method.edge_kinds)).
+    pub fn labeled_succs<'s>(
+        &'s self,
+        block: &'m Name,
+    ) -> impl Iterator<Item = (CFGNode<'m>, CFGEdgeKind<'m>)> + 's {
+        self.graph
+            .edges_directed(CFGNode::Block(block), Direction::Outgoing)
+            .flat_map(|(_, succ, kinds)| kinds.iter().map(move |&kind| (succ, kind)))
+    }
+
+    /// Get the exceptional edges of the CFG (see
+    /// [`CFGEdgeKind::is_exceptional()`](enum.CFGEdgeKind.html#method.is_exceptional)),
+    /// i.e., the edges along which exceptions propagate, as triples
+    /// `(from, to, kind)`
+    pub fn exceptional_edges<'s>(
+        &'s self,
+    ) -> impl Iterator<Item = (&'m Name, CFGNode<'m>, CFGEdgeKind<'m>)> + 's {
+        self.graph.all_edges().flat_map(|(from, to, kinds)| {
+            let from = match from {
+                CFGNode::Block(block) => block,
+                CFGNode::Return => panic!("Shouldn't have an edge from CFGNode::Return"),
+            };
+            kinds
+                .iter()
+                .filter(|kind| kind.is_exceptional())
+                .map(move |&kind| (from, to, kind))
+        })
+    }
+
+    /// Get the CFG of the function as it would be if nothing ever threw: a
+    /// copy of this CFG without any exceptional edges (see
+    /// [`CFGEdgeKind::is_exceptional()`](enum.CFGEdgeKind.html#method.is_exceptional)).
+    /// Landing pads and other exception handlers are unreachable in the
+    /// result, and it has no `ExitKind::Unwind` exits.
+    pub fn without_exceptional_edges(&self) -> Self {
+        let mut cfg = self.filter_edges(|kind| !kind.is_exceptional());
+        cfg.exits.retain(|&exit| exit != ExitKind::Unwind);
+        cfg
+    }
+
+    /// Get a copy of this CFG without the given edges, each specified as a
+    /// pair `(from, to)`; e.g., the CFG as it would be if those edges were
+    /// known never to be taken. Edges which aren't in the CFG are ignored.
+    ///
+    /// This CFG is unchanged. To compute dominators, postdominators, or
+    /// control dependencies of the result, pass it to
+    /// [`DominatorTree::new()`](struct.DominatorTree.html#method.new),
+    /// [`PostDominatorTree::new()`](struct.PostDominatorTree.html#method.new),
+    /// or
+    /// [`ControlDependenceGraph::new()`](struct.ControlDependenceGraph.html#method.new).
+    pub fn without_edges(&self, edges: impl IntoIterator<Item = (&'m Name, CFGNode<'m>)>) -> Self {
+        let edges: HashSet<(CFGNode<'m>, CFGNode<'m>)> = edges
+            .into_iter()
+            .map(|(from, to)| (CFGNode::Block(from), to))
+            .collect();
+        self.filter(|_| true, |from, to, _| !edges.contains(&(from, to)))
+    }
+
+    /// Get a copy of this CFG without the given blocks, or any edges to or
+    /// from them; e.g., the CFG as it would be if those blocks were known
+    /// never to execute. Blocks which aren't in the CFG are ignored.
+    ///
+    /// As with [`without_edges()`](#method.without_edges), this CFG is
+    /// unchanged.
+    ///
+    /// Panics if the given blocks include the entry block.
+    pub fn without_blocks(&self, blocks: impl IntoIterator<Item = &'m Name>) -> Self {
+        let blocks: HashSet<CFGNode<'m>> = blocks.into_iter().map(CFGNode::Block).collect();
+        if blocks.contains(&self.entry_node) {
+            panic!(
+                "without_blocks(): can't remove the entry block {:?}",
+                self.entry()
+            );
+        }
+        self.filter(
+            |node| !blocks.contains(&node),
+            |from, to, _| !blocks.contains(&from) && !blocks.contains(&to),
+        )
+    }
+
+    /// Get a copy of this CFG with only the edge kinds for which `keep`
+    /// returns `true`. Edges left without any kinds are removed.
+    fn filter_edges(&self, keep: impl Fn(&CFGEdgeKind<'m>) -> bool) -> Self {
+        self.filter(|_| true, |_, _, kind| keep(kind))
+    }
+
+    /// Get a copy of this CFG with only the nodes for which `keep_node` returns
+    /// `true`, and only the edge kinds for which `keep_edge` (given the edge's
+    /// endpoints and kind) returns `true`. Edges left without any kinds are
+    /// removed. `keep_edge` must not keep edges to or from removed nodes.
+    ///
+    /// If this CFG has `ExitKind::InfiniteLoop` exits, they're recomputed for
+    /// the result, since removing edges may create new infinite loops.
+    ///
+    /// (Rather than removing nodes and edges from a copy of `self.graph`, this
+    /// builds a new graph, since removal doesn't preserve the order of nodes
+    /// and edges.)
+    pub(crate) fn filter(
+        &self,
+        keep_node: impl Fn(CFGNode<'m>) -> bool,
+        keep_edge: impl Fn(CFGNode<'m>, CFGNode<'m>, &CFGEdgeKind<'m>) -> bool,
+    ) -> Self {
+        let mut graph = DiGraphMap::with_capacity(self.graph.node_count(), self.graph.edge_count());
+        for node in self.graph.nodes().filter(|&node| keep_node(node)) {
+            graph.add_node(node);
+        }
+        for (from, to, kinds) in self.graph.all_edges() {
+            let kinds: Vec<CFGEdgeKind<'m>> = kinds
+                .iter()
+                .copied()
+                .filter(|kind| *kind != CFGEdgeKind::InfiniteLoop && keep_edge(from, to, kind))
+                .collect();
+            if !kinds.is_empty() {
+                graph.add_edge(from, to, kinds);
+            }
+        }
+        let weights = self
+            .weights
+            .iter()
+            .filter(|((from, to), _)| graph.contains_edge(*from, *to))
+            .map(|(&edge, &weight)| (edge, weight))
+            .collect();
+        let mut cfg = Self {
+            graph,
+            entry_node: self.entry_node,
+            exits: self.exits.clone(),
+            weights,
+        };
+        if cfg.exits.contains(&ExitKind::InfiniteLoop) {
+            cfg.add_infinite_loop_exits();
+        }
+        cfg
+    }
+
     /// Get the `Name` of the entry block for the function
     pub fn entry(&self) -> &'m Name {
         match self.entry_node {
@@ -173,11 +736,494 @@ impl<'m> ControlFlowGraph<'m> {
         }
     }
 
+    /// Iterate over the blocks reachable from the entry block in post-order:
+    /// each block appears after all of its successors, except for successors
+    /// along back edges. Blocks unreachable from the entry block are not
+    /// included.
+    pub fn post_order<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
+        DfsPostOrder::new(&self.graph, self.entry_node)
+            .iter(&self.graph)
+            .filter_map(|node| match node {
+                CFGNode::Block(block) => Some(block),
+                CFGNode::Return => None,
+            })
+    }
+
+    /// Iterate over the blocks reachable from the entry block in reverse
+    /// post-order: the entry block comes first, and each block appears before
+    /// all of its successors, except for successors along back edges. This is
+    /// the usual iteration order for forward dataflow analyses. Blocks
+    /// unreachable from the entry block are not included.
+    pub fn reverse_post_order(&self) -> impl Iterator<Item = &'m Name> {
+        let mut blocks: Vec<&'m Name> = self.post_order().collect();
+        blocks.reverse();
+        blocks.into_iter()
+    }
+
+    /// Get the minimum number of CFG edges on any path from the entry block to
+    /// the given block: 0 for the entry block itself, 1 for its successors,
+    /// etc.
+    ///
+    /// Returns `None` if the block is unreachable from the entry block.
+    ///
+    /// To get the distances for many blocks, it's more efficient to use
+    /// [`distances_from_entry()`](#method.distances_from_entry).
+    pub fn distance_from_entry(&self, block: &'m Name) -> Option<usize> {
+        self.distances_from_entry().get(block).copied()
+    }
+
+    /// Get a map from each block reachable from the entry block to its
+    /// distance from the entry block (see
+    /// [`distance_from_entry()`](#method.distance_from_entry)). Blocks
+    /// unreachable from the entry block are not included.
+    pub fn distances_from_entry(&self) -> HashMap<&'m Name, usize> {
+        let mut distances: HashMap<&'m Name, usize> = HashMap::new();
+        distances.insert(self.entry(), 0);
+        let mut queue: VecDeque<&'m Name> = VecDeque::from([self.entry()]);
+        while let Some(block) = queue.pop_front() {
+            let distance = distances[block] + 1;
+            for succ in self.succs(block) {
+                if let CFGNode::Block(succ) = succ {
+                    if !distances.contains_key(succ) {
+                        distances.insert(succ, distance);
+                        queue.push_back(succ);
+                    }
+                }
+            }
+        }
+        distances
+    }
+
+    /// Get the blocks which lie on some path from block `a` to block `b`,
+    /// including `a` and `b` themselves. This is empty if `b` is not reachable
+    /// from `a`.
+    ///
+    /// Paths may go around cycles, so if `a == b`, the result includes every
+    /// block on a cycle through `a`, as well as `a` itself.
+    pub fn blocks_between(&self, a: &'m Name, b: &'m Name) -> HashSet<&'m Name> {
+        let from_a: HashSet<CFGNode<'m>> = Dfs::new(&self.graph, CFGNode::Block(a))
+            .iter(&self.graph)
+            .collect();
+        if !from_a.contains(&CFGNode::Block(b)) {
+            return HashSet::new();
+        }
+        let reversed = Reversed(&self.graph);
+        Dfs::new(reversed, CFGNode::Block(b))
+            .iter(reversed)
+            .filter_map(|node| match node {
+                CFGNode::Block(block) if from_a.contains(&node) => Some(block),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Enumerate simple paths (paths which don't visit any block more than
+    /// once) from block `from` to block `to`, as sequences of blocks including
+    /// `from` and `to` themselves. If `from == to`, the only such path is the
+    /// single block itself.
+    ///
+    /// At most `max_paths` paths are returned, and only paths of at most
+    /// `max_len` blocks are considered. Paths are found by depth-first search,
+    /// so if there are more than `max_paths` paths, which ones are returned is
+    /// deterministic but otherwise unspecified. The result is empty if `to` is
+    /// not reachable from `from` within these bounds.
+    pub fn paths(
+        &self,
+        from: &'m Name,
+        to: &'m Name,
+        max_paths: usize,
+        max_len: usize,
+    ) -> Vec<Vec<&'m Name>> {
+        let mut paths = Vec::new();
+        if max_paths > 0 && max_len > 0 {
+            let mut path = vec![from];
+            self.extend_paths(&mut path, to, max_paths, max_len, &mut paths);
+        }
+        paths
+    }
+
+    /// Add to `paths` the simple paths to `to` which begin with `path`, until
+    /// there are `max_paths` of them
+    fn extend_paths(
+        &self,
+        path: &mut Vec<&'m Name>,
+        to: &'m Name,
+        max_paths: usize,
+        max_len: usize,
+        paths: &mut Vec<Vec<&'m Name>>,
+    ) {
+        let block = path[path.len() - 1];
+        if block == to {
+            paths.push(path.clone());
+            return;
+        }
+        if path.len() == max_len {
+            return;
+        }
+        for succ in self.succs(block) {
+            if let CFGNode::Block(succ) = succ {
+                if !path.contains(&succ) {
+                    path.push(succ);
+                    self.extend_paths(path, to, max_paths, max_len, paths);
+                    path.pop();
+                    if paths.len() == max_paths {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Get the back edges of the CFG, as pairs `(latch, header)`: edges from a
+    /// block reachable from the entry block to a block which dominates it.
+    /// Each back edge closes a loop whose header is the edge's destination.
+    /// Back edges are returned in the order of their latches' appearance in
+    /// the function.
+    ///
+    /// If the CFG is irreducible, some of its cycles have no back edge and are
+    /// not found by this or the other loop methods. Neither are cycles of
+    /// blocks unreachable from the entry block.
+    pub fn back_edges(&self, domtree: &DominatorTree<'m>) -> Vec<(&'m Name, &'m Name)> {
+        self.graph
+            .all_edges()
+            .filter_map(|(from, to, _)| match (from, to) {
+                (CFGNode::Block(latch), CFGNode::Block(header))
+                    if domtree.is_reachable(from) && domtree.dominates(to, from) =>
+                {
+                    Some((latch, header))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get the natural loop with the given header: the blocks reachable from
+    /// the entry block which can reach one of the header's back edges (see
+    /// [`back_edges()`](#method.back_edges)) without going through the header,
+    /// plus the header itself. All of the natural loops of back edges to the
+    /// same header are merged into a single loop.
+    ///
+    /// Returns `None` if the given block is not the destination of any back
+    /// edge.
+    pub fn natural_loop(
+        &self,
+        header: &'m Name,
+        domtree: &DominatorTree<'m>,
+    ) -> Option<HashSet<&'m Name>> {
+        let latches: Vec<&'m Name> = self
+            .preds(header)
+            .filter(|&pred| {
+                domtree.is_reachable(CFGNode::Block(pred))
+                    && domtree.dominates(CFGNode::Block(header), CFGNode::Block(pred))
+            })
+            .collect();
+        if latches.is_empty() {
+            return None;
+        }
+        let mut body: HashSet<&'m Name> = std::iter::once(header).collect();
+        self.add_to_loop(&mut body, latches, domtree);
+        Some(body)
+    }
+
+    /// Get all of the natural loops of the CFG (see
+    /// [`natural_loop()`](#method.natural_loop)), as a map from loop header to
+    /// the blocks in that loop (including the header).
+    pub fn natural_loops(
+        &self,
+        domtree: &DominatorTree<'m>,
+    ) -> HashMap<&'m Name, HashSet<&'m Name>> {
+        let mut loops: HashMap<&'m Name, HashSet<&'m Name>> = HashMap::new();
+        for (latch, header) in self.back_edges(domtree) {
+            let body = loops
+                .entry(header)
+                .or_insert_with(|| std::iter::once(header).collect());
+            self.add_to_loop(body, vec![latch], domtree);
+        }
+        loops
+    }
+
+    /// Add the given blocks to the loop `body`, along with all of their
+    /// transitive predecessors which are reachable from the entry block and
+    /// not already in `body`. `body` must already contain the loop header,
+    /// which stops the search.
+    fn add_to_loop(
+        &self,
+        body: &mut HashSet<&'m Name>,
+        mut worklist: Vec<&'m Name>,
+        domtree: &DominatorTree<'m>,
+    ) {
+        while let Some(block) = worklist.pop() {
+            if body.insert(block) {
+                worklist.extend(
+                    self.preds(block)
+                        .filter(|&pred| domtree.is_reachable(CFGNode::Block(pred))),
+                );
+            }
+        }
+    }
+
+    /// Get the condensation of the CFG: the DAG obtained by collapsing each of
+    /// its strongly connected components (e.g., each loop, including any loops
+    /// nested within it) into a single node. Edges to `CFGNode::Return` are
+    /// ignored.
+    pub fn condensation(&self) -> CFGCondensation<'m> {
+        let positions: HashMap<&'m Name, usize> = self
+            .blocks()
+            .enumerate()
+            .map(|(pos, block)| (block, pos))
+            .collect();
+        let mut blocks_only: DiGraphMap<&'m Name, ()> = DiGraphMap::new();
+        for block in self.blocks() {
+            blocks_only.add_node(block);
+        }
+        for (from, to, _) in self.graph.all_edges() {
+            if let (CFGNode::Block(from), CFGNode::Block(to)) = (from, to) {
+                blocks_only.add_edge(from, to, ());
+            }
+        }
+        // `tarjan_scc()` returns the SCCs in reverse topological order
+        let mut sccs = petgraph::algo::tarjan_scc(&blocks_only);
+        sccs.reverse();
+        for scc in &mut sccs {
+            scc.sort_unstable_by_key(|block| positions[block]);
+        }
+        let scc_indices: HashMap<&'m Name, usize> = sccs
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, scc)| scc.iter().map(move |&block| (block, idx)))
+            .collect();
+        let mut graph = DiGraphMap::with_capacity(sccs.len(), blocks_only.edge_count());
+        let mut cyclic = vec![false; sccs.len()];
+        for idx in 0..sccs.len() {
+            graph.add_node(idx);
+        }
+        for (from, to, _) in blocks_only.all_edges() {
+            let (from, to) = (scc_indices[from], scc_indices[to]);
+            if from == to {
+                cyclic[from] = true;
+            } else {
+                graph.add_edge(from, to, ());
+            }
+        }
+        CFGCondensation {
+            entry: scc_indices[self.entry()],
+            sccs,
+            scc_indices,
+            cyclic,
+            graph,
+        }
+    }
+
+    /// Get a fingerprint of the shape of the CFG: a hash of its nodes and
+    /// edges (including edge kinds, but not `switch` case values) which doesn't
+    /// depend on the names of the blocks. Functions whose CFGs have the same
+    /// shape, e.g., the same function in two different builds, have the same
+    /// `structural_hash()`, although (as with any hash) functions with
+    /// different shapes may also collide.
+    ///
+    /// Blocks are identified by the order in which a depth-first search from
+    /// the entry block visits them, taking successors in the order of the
+    /// terminator's operands, so the hash also doesn't depend on the order
+    /// of the blocks in the function, except for blocks unreachable from the
+    /// entry block. The hash is computed with a fixed algorithm (64-bit
+    /// FNV-1a), so it's stable across runs, platforms, and Rust versions.
+    pub fn structural_hash(&self) -> u64 {
+        // number the blocks in DFS preorder, then any unreachable blocks in
+        // function order
+        let mut numbers: HashMap<&'m Name, u64> = HashMap::new();
+        let mut order: Vec<&'m Name> = Vec::new();
+        let mut stack = vec![self.entry()];
+        while let Some(block) = stack.pop() {
+            if !numbers.contains_key(block) {
+                numbers.insert(block, order.len() as u64);
+                order.push(block);
+                // push in reverse so that the first successor is visited first
+                let succs: Vec<CFGNode<'m>> = self.succs(block).collect();
+                for succ in succs.into_iter().rev() {
+                    if let CFGNode::Block(succ) = succ {
+                        stack.push(succ);
+                    }
+                }
+            }
+        }
+        for block in self.blocks() {
+            if !numbers.contains_key(block) {
+                numbers.insert(block, order.len() as u64);
+                order.push(block);
+            }
+        }
+
+        let mut hasher = Fnv1a::new();
+        hasher.write_u64(order.len() as u64);
+        for &block in &order {
+            hasher.write_u64(self.succs(block).count() as u64);
+            for succ in self.succs(block) {
+                hasher.write_u64(match succ {
+                    CFGNode::Block(succ) => numbers[succ],
+                    CFGNode::Return => u64::MAX,
+                });
+                let kinds = self.edge_kinds(block, succ);
+                hasher.write_u64(kinds.len() as u64);
+                for kind in kinds {
+                    hasher.write_u64(kind.structural_tag());
+                }
+            }
+        }
+        hasher.finish()
+    }
+
     /// Get the reversed CFG; i.e., the CFG where all edges have been reversed
     pub(crate) fn reversed(&self) -> Self {
         Self {
-            graph: DiGraphMap::from_edges(self.graph.all_edges().map(|(a, b, _)| (b, a, ()))),
+            graph: DiGraphMap::from_edges(
+                self.graph
+                    .all_edges()
+                    .map(|(a, b, kinds)| (b, a, kinds.clone())),
+            ),
             entry_node: CFGNode::Return,
+            exits: self.exits.clone(),
+            weights: self
+                .weights
+                .iter()
+                .map(|(&(a, b), &weight)| ((b, a), weight))
+                .collect(),
+        }
+    }
+}
+
+/// The condensation of a [`ControlFlowGraph`](struct.ControlFlowGraph.html): a
+/// DAG whose nodes are the CFG's strongly connected components (SCCs). Each
+/// loop in the CFG (including any loops nested within it) is part of a single
+/// SCC, and each block which isn't part of any loop is an SCC by itself.
+///
+/// SCCs are identified by their index in [`sccs()`](#method.sccs), which is in
+/// topological order (predecessors before successors), so the SCC containing
+/// the entry block comes before every other SCC reachable from it.
+///
+/// To construct a `CFGCondensation`, use
+/// [`ControlFlowGraph::condensation()`](struct.ControlFlowGraph.html#method.condensation).
+pub struct CFGCondensation<'m> {
+    /// The SCCs, in topological order
+    sccs: Vec<Vec<&'m Name>>,
+    /// Map from block name to the index of its SCC
+    scc_indices: HashMap<&'m Name, usize>,
+    /// For each SCC, whether it contains a cycle (i.e., more than one block,
+    /// or a block which may branch to itself)
+    cyclic: Vec<bool>,
+    /// The condensed graph. Nodes are SCC indices, and an edge from A to B
+    /// indicates some block in A may branch to some block in B
+    graph: DiGraphMap<usize, ()>,
+    /// Index of the SCC containing the entry block
+    entry: usize,
+}
+
+impl<'m> CFGCondensation<'m> {
+    /// Get all of the SCCs, in topological order (predecessors before
+    /// successors). Blocks within each SCC are in the order they appear in the
+    /// function.
+    pub fn sccs(&self) -> &[Vec<&'m Name>] {
+        &self.sccs
+    }
+
+    /// Get the number of SCCs
+    pub fn num_sccs(&self) -> usize {
+        self.sccs.len()
+    }
+
+    /// Get the blocks in the SCC with the given index
+    ///
+    /// Panics if the index is out of range.
+    pub fn scc(&self, idx: usize) -> &[&'m Name] {
+        &self.sccs[idx]
+    }
+
+    /// Get the index of the SCC containing the block with the given `Name`.
+    ///
+    /// Panics if the block is not found in the CFG.
+    pub fn scc_index(&self, block: &Name) -> usize {
+        *self
+            .scc_indices
+            .get(block)
+            .unwrap_or_else(|| panic!("scc_index(): block {:?} not found in the CFG", block))
+    }
+
+    /// Get the index of the SCC containing the entry block
+    pub fn entry_scc(&self) -> usize {
+        self.entry
+    }
+
+    /// Does the SCC with the given index contain a cycle, i.e., is it (part
+    /// of) a loop? This is `false` only for SCCs consisting of a single block
+    /// which can't branch to itself.
+    ///
+    /// Panics if the index is out of range.
+    pub fn is_cyclic(&self, idx: usize) -> bool {
+        self.cyclic[idx]
+    }
+
+    /// Get the indices of the SCCs containing blocks which may be branched to
+    /// from blocks in the SCC with the given index (not including that SCC
+    /// itself).
+    pub fn succs<'s>(&'s self, idx: usize) -> impl Iterator<Item = usize> + 's {
+        self.graph.neighbors_directed(idx, Direction::Outgoing)
+    }
+
+    /// Get the indices of the SCCs containing blocks which may branch to
+    /// blocks in the SCC with the given index (not including that SCC itself).
+    pub fn preds<'s>(&'s self, idx: usize) -> impl Iterator<Item = usize> + 's {
+        self.graph.neighbors_directed(idx, Direction::Incoming)
+    }
+
+    /// Get a longest path through the condensation, starting from the SCC
+    /// containing the entry block, as a list of SCC indices. The length of a
+    /// path is the number of SCCs on it, so this is the longest acyclic path
+    /// through the CFG if every loop is counted as a single step.
+    ///
+    /// If there are several longest paths, this returns the one that ends
+    /// earliest in the topological order.
+    pub fn longest_path(&self) -> Vec<usize> {
+        self.longest_weighted_path(|_| 1).1
+    }
+
+    /// Like [`longest_path()`](#method.longest_path), but the length of a path
+    /// is the sum of the weights of the SCCs on it, as given by the `weight`
+    /// closure (which is passed the blocks in each SCC). Returns the length
+    /// of the path, along with the path itself.
+    ///
+    /// For instance, weighting each SCC by the total number of instructions
+    /// (including terminators) in its blocks gives a first-order bound on the
+    /// number of instructions executed in one call to the function, if each
+    /// loop ran for only one iteration; or use
+    /// [`BlockCosts::block_cost()`](struct.BlockCosts.html#method.block_cost)
+    /// to weight blocks by their estimated cost.
+    pub fn longest_weighted_path(
+        &self,
+        mut weight: impl FnMut(&[&'m Name]) -> u64,
+    ) -> (u64, Vec<usize>) {
+        // SCCs are in topological order, so each SCC's longest incoming path
+        // is final by the time we get to it
+        let mut incoming: Vec<Option<u64>> = vec![None; self.sccs.len()];
+        let mut best_pred: Vec<Option<usize>> = vec![None; self.sccs.len()];
+        incoming[self.entry] = Some(0);
+        let mut longest = (0, self.entry);
+        for idx in self.entry..self.sccs.len() {
+            if let Some(len) = incoming[idx] {
+                let len = len + weight(&self.sccs[idx]);
+                if len > longest.0 || idx == self.entry {
+                    longest = (len, idx);
+                }
+                for succ in self.succs(idx) {
+                    if incoming[succ] < Some(len) {
+                        incoming[succ] = Some(len);
+                        best_pred[succ] = Some(idx);
+                    }
+                }
+            }
         }
+        let (len, last) = longest;
+        let mut path: Vec<usize> =
+            std::iter::successors(Some(last), |&idx| best_pred[idx]).collect();
+        path.reverse();
+        (len, path)
     }
 }
@@ -0,0 +1,331 @@
+use crate::alias::{underlying_object, AliasAnalysis, MemoryObject};
+use crate::call_graph::direct_callee;
+use crate::control_flow_graph::{CFGNode, ControlFlowGraph};
+use crate::def_use::{DefUseChains, InstructionLocation};
+use either::Either;
+use llvm_ir::function::FunctionAttribute;
+use llvm_ir::instruction::InlineAssembly;
+use llvm_ir::{Function, Instruction, Name, Operand, Terminator};
+use petgraph::prelude::{DiGraphMap, Direction};
+use std::collections::{HashMap, HashSet};
+
+/// The data dependence graph for a particular function: which instructions
+/// (and terminators) each instruction depends on for its operands, either
+/// directly through an SSA value, or through memory.
+///
+/// Memory dependences are conservative. An instruction which may read memory
+/// depends on each instruction which may write memory that may alias it, and
+/// which may execute before it. By default, two pointers are known not to
+/// alias only when they are based on different `alloca`s or globals; see
+/// [`FunctionAnalysis::data_dependence_graph_with_alias_analysis()`](struct.FunctionAnalysis.html#method.data_dependence_graph_with_alias_analysis)
+/// to use a different [`AliasAnalysis`](trait.AliasAnalysis.html). Calls are
+/// assumed to read and write any memory, unless the call is marked `readnone`,
+/// `readonly`, or `writeonly`; calls to the `llvm.dbg.*` and `llvm.lifetime.*`
+/// intrinsics are ignored.
+///
+/// Together with the [`ControlDependenceGraph`](struct.ControlDependenceGraph.html),
+/// this makes up the program dependence graph.
+///
+/// To construct a `DataDependenceGraph`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct DataDependenceGraph<'m> {
+    /// The graph itself. An edge from X to Y indicates that instruction X
+    /// depends on instruction Y.
+    ///
+    /// Each edge's weight is the list of ways X depends on Y: through the SSA
+    /// value defined by Y, through memory, or both
+    graph: DiGraphMap<InstructionLocation<'m>, Vec<DataDependenceKind<'m>>>,
+}
+
+/// The way an instruction depends on another; see
+/// [`DataDependenceGraph`](struct.DataDependenceGraph.html)
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum DataDependenceKind<'m> {
+    /// The instruction uses the SSA value with this `Name`, which is the
+    /// result of the other instruction
+    Value(&'m Name),
+    /// The instruction may read memory which the other instruction may have
+    /// written
+    Memory,
+}
+
+impl<'m> DataDependenceGraph<'m> {
+    pub(crate) fn new(
+        function: &'m Function,
+        cfg: &ControlFlowGraph<'m>,
+        def_use: &DefUseChains<'m>,
+        alias_analysis: &dyn AliasAnalysis<'m>,
+    ) -> Self {
+        let mut graph: DiGraphMap<InstructionLocation<'m>, Vec<DataDependenceKind<'m>>> =
+            DiGraphMap::new();
+        let defs: HashMap<&'m Name, &'m Instruction> = function
+            .basic_blocks
+            .iter()
+            .flat_map(|bb| bb.instrs.iter())
+            .filter_map(|inst| inst.try_get_result().map(|dest| (dest, inst)))
+            .collect();
+        // memory accesses, as (location, access)
+        let mut accesses: Vec<(InstructionLocation<'m>, MemoryAccess<'m>)> = Vec::new();
+        for bb in &function.basic_blocks {
+            let insts = bb
+                .instrs
+                .iter()
+                .map(Either::Left)
+                .chain(std::iter::once(Either::Right(&bb.term)));
+            for (index, inst) in insts.enumerate() {
+                let location = InstructionLocation {
+                    block: &bb.name,
+                    index,
+                };
+                graph.add_node(location);
+                let result = match inst {
+                    Either::Left(inst) => inst.try_get_result(),
+                    Either::Right(term) => term.try_get_result(),
+                };
+                if let Some(result) = result {
+                    for &user in def_use.uses(result) {
+                        add_dependence(
+                            &mut graph,
+                            user,
+                            location,
+                            DataDependenceKind::Value(result),
+                        );
+                    }
+                }
+                if let Some(access) = MemoryAccess::of(inst, &defs) {
+                    accesses.push((location, access));
+                }
+            }
+        }
+
+        // blocks reachable from each block by a nonempty path, computed on
+        // demand
+        let mut reachable: HashMap<&'m Name, HashSet<&'m Name>> = HashMap::new();
+        for (write_loc, write) in accesses.iter().filter(|(_, access)| access.writes) {
+            let reachable = reachable
+                .entry(write_loc.block)
+                .or_insert_with(|| reachable_blocks(cfg, write_loc.block));
+            for (read_loc, read) in accesses.iter().filter(|(_, access)| access.reads) {
+                let after = reachable.contains(read_loc.block)
+                    || (read_loc.block == write_loc.block && read_loc.index > write_loc.index);
+                if after && read.may_alias(write, alias_analysis) {
+                    add_dependence(
+                        &mut graph,
+                        *read_loc,
+                        *write_loc,
+                        DataDependenceKind::Memory,
+                    );
+                }
+            }
+        }
+
+        Self { graph }
+    }
+
+    /// Get the instructions (and terminators) which the instruction at the
+    /// given location immediately depends on.
+    ///
+    /// Panics if the location isn't in this function.
+    pub fn dependencies<'s>(
+        &'s self,
+        inst: InstructionLocation<'m>,
+    ) -> impl Iterator<Item = InstructionLocation<'m>> + 's {
+        self.check_node(inst);
+        self.graph.neighbors_directed(inst, Direction::Outgoing)
+    }
+
+    /// Get the instructions (and terminators) which the instruction at the
+    /// given location immediately depends on, along with the way it depends
+    /// on each one. An instruction appears once for each way (see
+    /// [`edge_kinds()`](#method.edge_kinds)).
+    ///
+    /// Panics if the location isn't in this function.
+    pub fn labeled_dependencies<'s>(
+        &'s self,
+        inst: InstructionLocation<'m>,
+    ) -> impl Iterator<Item = (InstructionLocation<'m>, DataDependenceKind<'m>)> + 's {
+        self.check_node(inst);
+        self.graph
+            .edges_directed(inst, Direction::Outgoing)
+            .flat_map(|(_, dependency, kinds)| kinds.iter().map(move |&kind| (dependency, kind)))
+    }
+
+    /// Get the instructions (and terminators) which immediately depend on the
+    /// instruction at the given location. This is the inverse of
+    /// [`dependencies()`](#method.dependencies).
+    ///
+    /// Panics if the location isn't in this function.
+    pub fn dependents<'s>(
+        &'s self,
+        inst: InstructionLocation<'m>,
+    ) -> impl Iterator<Item = InstructionLocation<'m>> + 's {
+        self.check_node(inst);
+        self.graph.neighbors_directed(inst, Direction::Incoming)
+    }
+
+    /// Get the ways the instruction at `inst` immediately depends on the
+    /// instruction at `dependency`. If it doesn't, this is empty.
+    pub fn edge_kinds(
+        &self,
+        inst: InstructionLocation<'m>,
+        dependency: InstructionLocation<'m>,
+    ) -> &[DataDependenceKind<'m>] {
+        self.graph
+            .edge_weight(inst, dependency)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Does the instruction at `inst` immediately depend on the instruction at
+    /// `dependency`?
+    pub fn is_data_dependent(
+        &self,
+        inst: InstructionLocation<'m>,
+        dependency: InstructionLocation<'m>,
+    ) -> bool {
+        self.graph.contains_edge(inst, dependency)
+    }
+
+    fn check_node(&self, inst: InstructionLocation<'m>) {
+        if !self.graph.contains_node(inst) {
+            panic!(
+                "instruction {} at index {} not found in the data dependence graph",
+                inst.block, inst.index
+            );
+        }
+    }
+}
+
+/// Add a dependence of the given kind from `from` to `to`
+fn add_dependence<'m>(
+    graph: &mut DiGraphMap<InstructionLocation<'m>, Vec<DataDependenceKind<'m>>>,
+    from: InstructionLocation<'m>,
+    to: InstructionLocation<'m>,
+    kind: DataDependenceKind<'m>,
+) {
+    if let Some(kinds) = graph.edge_weight_mut(from, to) {
+        kinds.push(kind);
+    } else {
+        graph.add_edge(from, to, vec![kind]);
+    }
+}
+
+/// The blocks reachable from `block` by a nonempty path
+fn reachable_blocks<'m>(cfg: &ControlFlowGraph<'m>, block: &'m Name) -> HashSet<&'m Name> {
+    let mut reachable = HashSet::new();
+    let mut worklist = vec![block];
+    while let Some(block) = worklist.pop() {
+        for succ in cfg.succs(block) {
+            if let CFGNode::Block(succ) = succ {
+                if reachable.insert(succ) {
+                    worklist.push(succ);
+                }
+            }
+        }
+    }
+    reachable
+}
+
+/// The memory accessed by an instruction
+pub(crate) struct MemoryAccess<'m> {
+    pub(crate) reads: bool,
+    pub(crate) writes: bool,
+    /// The accessed address, or `None` if it may be any memory
+    pub(crate) address: Option<&'m Operand>,
+    /// The object the accessed address is based on, or `None` if it may be
+    /// any memory
+    pub(crate) object: Option<MemoryObject<'m>>,
+}
+
+impl<'m> MemoryAccess<'m> {
+    /// Get the memory access performed by the given instruction or terminator,
+    /// if any
+    pub(crate) fn of(
+        inst: Either<&'m Instruction, &'m Terminator>,
+        defs: &HashMap<&'m Name, &'m Instruction>,
+    ) -> Option<Self> {
+        let at = |reads, writes, address| {
+            Some(Self {
+                reads,
+                writes,
+                address: Some(address),
+                // only `alloca`s are needed here, and those don't depend on the
+                // `Module`
+                object: underlying_object(address, defs, None),
+            })
+        };
+        let (callee, attributes) = match inst {
+            Either::Left(Instruction::Load(load)) => return at(true, false, &load.address),
+            Either::Left(Instruction::Store(store)) => return at(false, true, &store.address),
+            Either::Left(Instruction::CmpXchg(cmpxchg)) => return at(true, true, &cmpxchg.address),
+            Either::Left(Instruction::AtomicRMW(rmw)) => return at(true, true, &rmw.address),
+            Either::Left(Instruction::VAArg(vaarg)) => return at(true, true, &vaarg.arg_list),
+            Either::Left(Instruction::Call(call)) => (&call.function, &call.function_attributes),
+            Either::Right(Terminator::Invoke(invoke)) => {
+                (&invoke.function, &invoke.function_attributes)
+            }
+            Either::Right(Terminator::CallBr(callbr)) => {
+                (&callbr.function, &callbr.function_attributes)
+            }
+            _ => return None,
+        };
+        let (reads, writes) = call_effects(callee, attributes);
+        if reads || writes {
+            Some(Self {
+                reads,
+                writes,
+                address: None,
+                object: None,
+            })
+        } else {
+            None
+        }
+    }
+
+    fn may_alias(&self, other: &Self, alias_analysis: &dyn AliasAnalysis<'m>) -> bool {
+        match (self.address, other.address) {
+            (Some(a), Some(b)) => alias_analysis.may_alias(a, b),
+            _ => true,
+        }
+    }
+}
+
+/// Whether a call may read and write memory, respectively
+fn call_effects(
+    callee: &Either<InlineAssembly, Operand>,
+    attributes: &[FunctionAttribute],
+) -> (bool, bool) {
+    if let Either::Right(callee) = callee {
+        if direct_callee(callee)
+            .is_some_and(|name| name.starts_with("llvm.dbg.") || name.starts_with("llvm.lifetime."))
+        {
+            return (false, false);
+        }
+    }
+    let (mut reads, mut writes) = (true, true);
+    for attribute in attributes {
+        match attribute {
+            FunctionAttribute::ReadNone => return (false, false),
+            FunctionAttribute::ReadOnly => writes = false,
+            FunctionAttribute::WriteOnly => reads = false,
+            #[cfg(feature = "llvm-16-or-greater")]
+            FunctionAttribute::Memory {
+                default,
+                argmem,
+                inaccessible_mem,
+            } => {
+                use llvm_ir::function::MemoryEffect;
+                let effects = [default, argmem, inaccessible_mem];
+                reads = effects
+                    .iter()
+                    .any(|e| matches!(e, MemoryEffect::Read | MemoryEffect::ReadWrite));
+                writes = effects
+                    .iter()
+                    .any(|e| matches!(e, MemoryEffect::Write | MemoryEffect::ReadWrite));
+            }
+            _ => {}
+        }
+    }
+    (reads, writes)
+}
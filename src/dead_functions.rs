@@ -0,0 +1,197 @@
+use crate::call_graph::{functions_with_names, CallGraph, FunctionName};
+use crate::xref::{XRef, XRefIndex};
+use llvm_ir::module::Linkage;
+use llvm_ir::{Module, Name};
+use std::collections::{HashMap, HashSet};
+
+/// Finds the functions which are dead, i.e., which can't be reached from any
+/// of a set of entry points.
+///
+/// A function is live if it is an entry point, or if it may be called by a
+/// live function (according to the [`CallGraph`](struct.CallGraph.html)), or
+/// if its address is taken anywhere which is live: in a live function, or in
+/// the initializer of a live global variable. Global variables, aliases, and
+/// IFuncs are live if they are referenced by a live function or global. The
+/// special globals `llvm.global_ctors`, `llvm.global_dtors`, `llvm.used`, and
+/// `llvm.compiler.used` are always live, as are globals with numbered names.
+///
+/// By default, the entry points are `main` and all externally visible
+/// functions (those without `private` or `internal` linkage), and all
+/// externally visible global variables, aliases, and IFuncs are also live.
+/// Use `dead_function_analysis_with_entry_points()` on
+/// [`ModuleAnalysis`](struct.ModuleAnalysis.html#method.dead_function_analysis_with_entry_points)
+/// or [`CrossModuleAnalysis`](struct.CrossModuleAnalysis.html#method.dead_function_analysis_with_entry_points)
+/// to choose the entry points yourself, e.g., when analyzing a whole program.
+///
+/// Only functions defined in the analyzed `Module`(s) are reported; functions
+/// which are only declared have no code which could be removed.
+///
+/// To construct a `DeadFunctionAnalysis` with the default entry points, use
+/// [`ModuleAnalysis`](struct.ModuleAnalysis.html) or
+/// [`CrossModuleAnalysis`](struct.CrossModuleAnalysis.html).
+pub struct DeadFunctionAnalysis<'m> {
+    /// The entry points, sorted by name
+    entry_points: Vec<FunctionName<'m>>,
+    /// The functions defined in the analyzed `Module`(s), sorted by name
+    defined: Vec<FunctionName<'m>>,
+    /// All of the live symbols (functions, global variables, aliases, and
+    /// IFuncs). Symbols other than functions are `Named` by their names.
+    live: HashSet<FunctionName<'m>>,
+}
+
+/// Globals which are live no matter what the entry points are
+const ALWAYS_LIVE_GLOBALS: [&str; 4] = [
+    "llvm.global_ctors",
+    "llvm.global_dtors",
+    "llvm.used",
+    "llvm.compiler.used",
+];
+
+impl<'m> DeadFunctionAnalysis<'m> {
+    /// If `entry_points` is `None`, use the default entry points
+    pub(crate) fn new(
+        modules: impl IntoIterator<Item = &'m Module>,
+        call_graph: &CallGraph<'m>,
+        xref_index: &XRefIndex<'m>,
+        entry_points: Option<&[FunctionName]>,
+    ) -> Self {
+        let modules: Vec<&'m Module> = modules.into_iter().collect();
+        let mut defined: Vec<FunctionName<'m>> = modules
+            .iter()
+            .flat_map(|module| functions_with_names(module).map(|(_, name)| name))
+            .collect();
+        defined.sort_unstable();
+        defined.dedup();
+
+        let mut roots: Vec<FunctionName<'m>> = vec![];
+        let mut entry_points: Vec<FunctionName<'m>> = match entry_points {
+            Some(entry_points) => entry_points
+                .iter()
+                .map(|&entry| match defined.binary_search(&entry) {
+                    Ok(idx) => defined[idx],
+                    Err(_) => panic!(
+                        "dead_function_analysis_with_entry_points(): function named {:?} not found in the Module(s)",
+                        entry
+                    ),
+                })
+                .collect(),
+            None => {
+                roots.extend(modules.iter().flat_map(|module| {
+                    let globals = module
+                        .global_vars
+                        .iter()
+                        .map(|g| (&g.name, &g.linkage))
+                        .chain(module.global_aliases.iter().map(|a| (&a.name, &a.linkage)))
+                        .chain(module.global_ifuncs.iter().map(|i| (&i.name, &i.linkage)));
+                    globals.filter_map(|(name, linkage)| match name {
+                        Name::Name(name) if is_externally_visible(linkage) => {
+                            Some(FunctionName::Named(name.as_str()))
+                        }
+                        _ => None,
+                    })
+                }));
+                modules
+                    .iter()
+                    .flat_map(|module| functions_with_names(module))
+                    .filter(|(f, name)| *name == "main" || is_externally_visible(&f.linkage))
+                    .map(|(_, name)| name)
+                    .collect()
+            }
+        };
+        entry_points.sort_unstable();
+        entry_points.dedup();
+        roots.extend(&entry_points);
+        roots.extend(modules.iter().flat_map(|module| {
+            module.global_vars.iter().filter_map(|g| match &g.name {
+                Name::Name(name) if ALWAYS_LIVE_GLOBALS.contains(&name.as_str()) => {
+                    Some(FunctionName::Named(name.as_str()))
+                }
+                _ => None,
+            })
+        }));
+
+        // Map from each symbol to the (named) symbols it references. Symbols
+        // referenced from places with numbered names are roots.
+        let mut references: HashMap<FunctionName<'m>, Vec<FunctionName<'m>>> = HashMap::new();
+        for symbol in xref_index.referenced_symbols() {
+            let symbol = match symbol {
+                Name::Name(name) => name.as_str(),
+                Name::Number(_) => continue,
+            };
+            for xref in xref_index.references_to(symbol) {
+                let referrer = match *xref {
+                    XRef::Instruction { function, .. } => Some(function),
+                    XRef::GlobalInitializer { global: name, .. }
+                    | XRef::Alias { alias: name, .. }
+                    | XRef::IFunc { ifunc: name, .. } => match name {
+                        Name::Name(name) => Some(FunctionName::Named(name.as_str())),
+                        Name::Number(_) => None,
+                    },
+                };
+                let symbol = FunctionName::Named(symbol);
+                match referrer {
+                    Some(referrer) => references.entry(referrer).or_default().push(symbol),
+                    None => roots.push(symbol),
+                }
+            }
+        }
+
+        let mut live = HashSet::new();
+        while let Some(symbol) = roots.pop() {
+            if live.insert(symbol) {
+                roots.extend(references.get(&symbol).into_iter().flatten());
+                if call_graph.inner().contains_node(symbol) {
+                    roots.extend(call_graph.inner().neighbors(symbol));
+                }
+            }
+        }
+
+        Self {
+            entry_points,
+            defined,
+            live,
+        }
+    }
+
+    /// Iterate over the entry points, sorted by name.
+    pub fn entry_points<'s>(&'s self) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        self.entry_points.iter().copied()
+    }
+
+    /// Iterate over the dead functions defined in the analyzed `Module`(s),
+    /// sorted by name.
+    pub fn dead_functions<'s>(&'s self) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        self.defined
+            .iter()
+            .copied()
+            .filter(move |func| !self.live.contains(func))
+    }
+
+    /// Iterate over the live functions defined in the analyzed `Module`(s),
+    /// sorted by name.
+    pub fn live_functions<'s>(&'s self) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        self.defined
+            .iter()
+            .copied()
+            .filter(move |func| self.live.contains(func))
+    }
+
+    /// Is the given function dead?
+    ///
+    /// Panics if the given function is not defined in the analyzed `Module`(s).
+    pub fn is_dead<'n>(&self, func_name: impl Into<FunctionName<'n>>) -> bool {
+        let func_name = func_name.into();
+        if self.defined.binary_search(&func_name).is_err() {
+            panic!(
+                "is_dead(): function named {:?} not found in the Module(s)",
+                func_name
+            )
+        }
+        !self.live.contains(&func_name)
+    }
+}
+
+/// Is a symbol with the given linkage visible outside of its `Module`?
+fn is_externally_visible(linkage: &Linkage) -> bool {
+    !matches!(linkage, Linkage::Private | Linkage::Internal)
+}
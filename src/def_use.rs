@@ -0,0 +1,198 @@
+use crate::operands::{instruction_operands, terminator_operands};
+use either::Either;
+use llvm_ir::{Function, Instruction, Name, Operand, Terminator};
+use std::collections::HashMap;
+
+/// An instruction or terminator in a particular function, identified by its
+/// basic block and its index within that block. For the block's terminator,
+/// the index is equal to the number of (non-terminator) instructions in the
+/// block.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub struct InstructionLocation<'m> {
+    /// Name of the basic block containing the instruction
+    pub block: &'m Name,
+    /// Index of the instruction within the basic block
+    pub index: usize,
+}
+
+/// Where a local value is defined; see
+/// [`DefUseChains::definition()`](struct.DefUseChains.html#method.definition)
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Definition<'m> {
+    /// The value is the function's parameter with the given index
+    Parameter(usize),
+    /// The value is the result of the instruction (or terminator, e.g., an
+    /// `invoke`) at the given location
+    Instruction(InstructionLocation<'m>),
+}
+
+/// The def-use chains of a particular function: for each local value (each
+/// parameter, and the result of each instruction or terminator), the
+/// instructions and terminators which use it as an operand; and conversely,
+/// for each local value, where it is defined.
+///
+/// To construct a `DefUseChains`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct DefUseChains<'m> {
+    /// The function
+    function: &'m Function,
+    /// Map from each local value to its uses, in the order they appear in the
+    /// function. Values without uses have no entry.
+    uses: HashMap<&'m Name, Vec<InstructionLocation<'m>>>,
+    /// Map from each local value to its definition
+    defs: HashMap<&'m Name, Definition<'m>>,
+    /// Index of each block in the function
+    block_indices: HashMap<&'m Name, usize>,
+}
+
+impl<'m> DefUseChains<'m> {
+    pub(crate) fn new(function: &'m Function) -> Self {
+        let mut uses: HashMap<&'m Name, Vec<InstructionLocation<'m>>> = HashMap::new();
+        let mut add_uses = |operands: Vec<&'m Operand>, location: InstructionLocation<'m>| {
+            for operand in operands {
+                if let Operand::LocalOperand { name, .. } = operand {
+                    let locations = uses.entry(name).or_default();
+                    // an instruction which uses a value several times is
+                    // reported only once
+                    if locations.last() != Some(&location) {
+                        locations.push(location);
+                    }
+                }
+            }
+        };
+        for bb in &function.basic_blocks {
+            for (index, inst) in bb.instrs.iter().enumerate() {
+                add_uses(
+                    instruction_operands(inst),
+                    InstructionLocation {
+                        block: &bb.name,
+                        index,
+                    },
+                );
+            }
+            add_uses(
+                terminator_operands(&bb.term),
+                InstructionLocation {
+                    block: &bb.name,
+                    index: bb.instrs.len(),
+                },
+            );
+        }
+        let mut defs: HashMap<&'m Name, Definition<'m>> = function
+            .parameters
+            .iter()
+            .enumerate()
+            .map(|(i, param)| (&param.name, Definition::Parameter(i)))
+            .collect();
+        for bb in &function.basic_blocks {
+            let results = bb
+                .instrs
+                .iter()
+                .map(|inst| inst.try_get_result())
+                .chain(std::iter::once(bb.term.try_get_result()));
+            for (index, result) in results.enumerate() {
+                if let Some(result) = result {
+                    defs.insert(
+                        result,
+                        Definition::Instruction(InstructionLocation {
+                            block: &bb.name,
+                            index,
+                        }),
+                    );
+                }
+            }
+        }
+        let block_indices = function
+            .basic_blocks
+            .iter()
+            .enumerate()
+            .map(|(i, bb)| (&bb.name, i))
+            .collect();
+        Self {
+            function,
+            uses,
+            defs,
+            block_indices,
+        }
+    }
+
+    /// Get the instructions and terminators which use the local value with
+    /// the given `Name`, in the order they appear in the function. An
+    /// instruction which uses the value several times (e.g., `add %x, %x`)
+    /// appears only once.
+    pub fn uses(&self, value: &Name) -> &[InstructionLocation<'m>] {
+        self.uses.get(value).map_or(&[], |uses| uses.as_slice())
+    }
+
+    /// Get the number of instructions and terminators which use the local
+    /// value with the given `Name`
+    pub fn num_uses(&self, value: &Name) -> usize {
+        self.uses(value).len()
+    }
+
+    /// Is the local value with the given `Name` used by any instruction or
+    /// terminator?
+    pub fn is_used(&self, value: &Name) -> bool {
+        !self.uses(value).is_empty()
+    }
+
+    /// Iterate over the instructions and terminators which use the local value
+    /// with the given `Name`, in the order they appear in the function
+    pub fn users<'s>(
+        &'s self,
+        value: &Name,
+    ) -> impl Iterator<Item = Either<&'m Instruction, &'m Terminator>> + 's {
+        self.uses(value)
+            .iter()
+            .map(move |&location| self.instruction(location))
+    }
+
+    /// Get the definition of the local value with the given `Name`: either the
+    /// function parameter it refers to, or the location of the instruction
+    /// whose result it is. Returns `None` if there is no such value in the
+    /// function (e.g., if the `Name` is a basic block's).
+    pub fn definition(&self, value: &Name) -> Option<Definition<'m>> {
+        self.defs.get(value).copied()
+    }
+
+    /// Get the instruction (or terminator) whose result is the local value
+    /// with the given `Name`, or `None` if the value is a parameter or isn't
+    /// in the function
+    pub fn defining_instruction(
+        &self,
+        value: &Name,
+    ) -> Option<Either<&'m Instruction, &'m Terminator>> {
+        match self.definition(value)? {
+            Definition::Parameter(_) => None,
+            Definition::Instruction(location) => Some(self.instruction(location)),
+        }
+    }
+
+    /// Get the instruction (or terminator) at the given location.
+    ///
+    /// Panics if the location isn't in this function.
+    pub fn instruction(
+        &self,
+        location: InstructionLocation<'m>,
+    ) -> Either<&'m Instruction, &'m Terminator> {
+        let bb = self
+            .block_indices
+            .get(location.block)
+            .map(|&i| &self.function.basic_blocks[i])
+            .unwrap_or_else(|| {
+                panic!(
+                    "instruction(): block {} not found in function {}",
+                    location.block, self.function.name
+                )
+            });
+        match bb.instrs.get(location.index) {
+            Some(inst) => Either::Left(inst),
+            None if location.index == bb.instrs.len() => Either::Right(&bb.term),
+            None => panic!(
+                "instruction(): index {} out of range for block {} in function {}",
+                location.index, location.block, self.function.name
+            ),
+        }
+    }
+}
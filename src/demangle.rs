@@ -0,0 +1,37 @@
+use cpp_demangle::{DemangleOptions, Symbol};
+
+/// Demangle the given Rust or C++ symbol name (e.g., as found in
+/// `Function.name`), returning `None` if it is not a mangled Rust or C++ name.
+///
+/// For Rust symbols, the result does not include the trailing hash (e.g.,
+/// `std::panicking::begin_panic` rather than
+/// `std::panicking::begin_panic::h5ae0871c3ba84f98`). For C++ symbols, the
+/// result includes the parameter types (e.g., `foo(int)`).
+///
+/// This function is only available with the `demangle` feature.
+pub fn demangle(symbol: &str) -> Option<String> {
+    if let Ok(demangled) = rustc_demangle::try_demangle(symbol) {
+        return Some(format!("{:#}", demangled));
+    }
+    Symbol::new(symbol)
+        .ok()?
+        .demangle(&DemangleOptions::new())
+        .ok()
+}
+
+/// Get the forms of the demangled name of the given symbol which a demangled
+/// name given by the user should match: for Rust symbols, with and without the
+/// hash; for C++ symbols, with and without the parameter types. Empty if the
+/// symbol is not a mangled Rust or C++ name.
+pub(crate) fn demangled_forms(symbol: &str) -> Vec<String> {
+    if let Ok(demangled) = rustc_demangle::try_demangle(symbol) {
+        return vec![format!("{}", demangled), format!("{:#}", demangled)];
+    }
+    match Symbol::new(symbol) {
+        Ok(sym) => [DemangleOptions::new(), DemangleOptions::new().no_params()]
+            .iter()
+            .filter_map(|options| sym.demangle(options).ok())
+            .collect(),
+        Err(_) => vec![],
+    }
+}
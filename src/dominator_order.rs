@@ -0,0 +1,68 @@
+use crate::control_dep_graph::ControlDependenceGraph;
+use crate::control_flow_graph::{CFGNode, ControlFlowGraph};
+use crate::dominator_tree::DominatorTree;
+use llvm_ir::{BasicBlock, Function, Name};
+use std::collections::HashMap;
+
+/// A basic block, together with the CFG and control-dependence information
+/// most often needed when visiting it. See
+/// [`FunctionAnalysis::blocks_in_dominator_order()`](struct.FunctionAnalysis.html#method.blocks_in_dominator_order).
+#[derive(Clone, PartialEq, Debug)]
+pub struct DominatorOrderBlock<'m> {
+    /// The block itself
+    pub block: &'m BasicBlock,
+    /// The block's immediate dominator, or `None` for the entry block
+    pub idom: Option<&'m Name>,
+    /// Depth of the block in the dominator tree: 0 for the entry block, 1 for
+    /// the blocks it immediately dominates, and so on. When visiting blocks in
+    /// dominator order, state scoped to the subtrees of blocks with depth
+    /// greater than or equal to this block's depth can be discarded before
+    /// visiting this block.
+    pub depth: usize,
+    /// The block's predecessors in the CFG
+    pub preds: Vec<&'m Name>,
+    /// The block's successors in the CFG, including `CFGNode::Return` if the
+    /// block may exit the function
+    pub succs: Vec<CFGNode<'m>>,
+    /// The blocks this block has an immediate control dependency on
+    pub control_dependencies: Vec<&'m Name>,
+}
+
+/// Get the reachable blocks of the function in dominator-tree preorder, with
+/// the children of each block in the order they appear in the function
+pub(crate) fn blocks_in_dominator_order<'m>(
+    function: &'m Function,
+    cfg: &ControlFlowGraph<'m>,
+    domtree: &DominatorTree<'m>,
+    cdg: &ControlDependenceGraph<'m>,
+) -> Vec<DominatorOrderBlock<'m>> {
+    let positions: HashMap<&'m Name, usize> = function
+        .basic_blocks
+        .iter()
+        .enumerate()
+        .map(|(i, bb)| (&bb.name, i))
+        .collect();
+    let mut blocks = Vec::new();
+    let mut stack = vec![(&function.basic_blocks[0], 0)];
+    while let Some((block, depth)) = stack.pop() {
+        let mut children: Vec<&'m BasicBlock> = domtree
+            .children(&block.name)
+            .filter_map(|child| match child {
+                CFGNode::Block(child) => Some(&function.basic_blocks[positions[child]]),
+                CFGNode::Return => None,
+            })
+            .collect();
+        // pushed in reverse, so that they're popped in function order
+        children.sort_by_key(|child| std::cmp::Reverse(positions[&child.name]));
+        stack.extend(children.into_iter().map(|child| (child, depth + 1)));
+        blocks.push(DominatorOrderBlock {
+            block,
+            idom: domtree.idom(&block.name),
+            depth,
+            preds: cfg.preds(&block.name).collect(),
+            succs: cfg.succs(&block.name).collect(),
+            control_dependencies: cdg.get_imm_control_dependencies(&block.name).collect(),
+        });
+    }
+    blocks
+}
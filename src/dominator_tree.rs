@@ -1,9 +1,9 @@
 use crate::control_flow_graph::{CFGNode, ControlFlowGraph};
 use llvm_ir::Name;
-use petgraph::prelude::{Dfs, DiGraphMap, Direction};
+use petgraph::prelude::{Dfs, DfsPostOrder, DiGraphMap, Direction};
 use petgraph::visit::Walker;
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// The dominator tree for a particular function.
 ///
@@ -23,10 +23,18 @@ pub struct DominatorTree<'m> {
 
     /// Entry node for the function
     pub(crate) entry_node: CFGNode<'m>,
+
+    /// Map from each node in the tree to its discovery and finishing times in
+    /// a DFS of the tree; see `tree_intervals()`
+    intervals: HashMap<CFGNode<'m>, (usize, usize)>,
 }
 
 /// The postdominator tree for a particular function.
 ///
+/// Only blocks which can reach the function exit appear in the tree, so if the
+/// function never returns (e.g., it ends in an infinite loop), the tree is
+/// empty. See [`ipostdom()`](#method.ipostdom).
+///
 /// To construct a `PostDominatorTree`, use
 /// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
 /// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
@@ -40,9 +48,152 @@ pub struct PostDominatorTree<'m> {
     ///   - Of the blocks that strictly postdominate bbY, bbX is the closest to bbY
     ///     (farthest from exit) along paths from bbY to the function exit
     pub(crate) graph: DiGraphMap<CFGNode<'m>, ()>,
+
+    /// Map from each node in the tree to its discovery and finishing times in
+    /// a DFS of the tree; see `tree_intervals()`
+    intervals: HashMap<CFGNode<'m>, (usize, usize)>,
+}
+
+/// An algorithm for constructing a `DominatorTree` or `PostDominatorTree`.
+/// All of the algorithms give the same tree; they differ only in performance.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum DominatorAlgorithm {
+    /// Choose an algorithm based on the size of the CFG: `SemiNCA` for CFGs
+    /// with more than 1000 nodes, and `Iterative` otherwise
+    Auto,
+    /// The iterative algorithm of Cooper, Harvey, and Kennedy ("A Simple,
+    /// Fast Dominance Algorithm"), which is fast for typical CFGs, but may
+    /// need many passes over very large ones
+    Iterative,
+    /// The Semi-NCA algorithm (Georgiadis, "Linear-Time Algorithms for
+    /// Dominators and Related Problems"), as used by LLVM, which takes nearly
+    /// linear time regardless of the shape of the CFG
+    SemiNCA,
+}
+
+/// Number of CFG nodes above which `DominatorAlgorithm::Auto` chooses
+/// `DominatorAlgorithm::SemiNCA`
+const SEMI_NCA_THRESHOLD: usize = 1000;
+
+/// Build the dominator tree of the given CFG (as a graph with an edge from each
+/// node's immediate dominator to the node) using the given algorithm
+fn build_tree<'m>(
+    cfg: &ControlFlowGraph<'m>,
+    algorithm: DominatorAlgorithm,
+) -> DiGraphMap<CFGNode<'m>, ()> {
+    let semi_nca = match algorithm {
+        DominatorAlgorithm::Auto => cfg.graph.node_count() > SEMI_NCA_THRESHOLD,
+        DominatorAlgorithm::Iterative => false,
+        DominatorAlgorithm::SemiNCA => true,
+    };
+    if semi_nca {
+        build_tree_semi_nca(cfg)
+    } else {
+        DomTreeBuilder::new(cfg).build()
+    }
+}
+
+/// Build the dominator tree of the given CFG using the Semi-NCA algorithm.
+///
+/// Algorithm from Loukas Georgiadis, "Linear-Time Algorithms for Dominators
+/// and Related Problems" (PhD thesis, 2005), following LLVM's implementation
+/// in `GenericDomTreeConstruction.h`. Internally, nodes are identified by
+/// their DFS preorder numbers, with the entry node numbered 0.
+fn build_tree_semi_nca<'m>(cfg: &ControlFlowGraph<'m>) -> DiGraphMap<CFGNode<'m>, ()> {
+    // number the reachable nodes in DFS preorder, recording each node's parent
+    // in the DFS tree
+    let mut nodes: Vec<CFGNode<'m>> = Vec::new();
+    let mut numbers: HashMap<CFGNode<'m>, usize> = HashMap::new();
+    let mut parents: Vec<usize> = Vec::new();
+    let mut stack = vec![(cfg.entry_node, 0)];
+    while let Some((node, parent)) = stack.pop() {
+        if numbers.contains_key(&node) {
+            continue;
+        }
+        let number = nodes.len();
+        numbers.insert(node, number);
+        nodes.push(node);
+        parents.push(parent);
+        for succ in cfg.graph.neighbors_directed(node, Direction::Outgoing) {
+            if !numbers.contains_key(&succ) {
+                stack.push((succ, number));
+            }
+        }
+    }
+
+    // compute semidominators, in reverse preorder. `ancestors` and `labels`
+    // are the path-compressed forest of nodes processed so far, where each
+    // node's label is the node with the minimum semidominator on its
+    // (compressed) path
+    let num_nodes = nodes.len();
+    let mut semis: Vec<usize> = (0..num_nodes).collect();
+    let mut labels: Vec<usize> = (0..num_nodes).collect();
+    let mut ancestors: Vec<usize> = parents.clone();
+    for w in (1..num_nodes).rev() {
+        semis[w] = parents[w];
+        for pred in cfg.preds_as_nodes(nodes[w]) {
+            if let Some(&v) = numbers.get(&pred) {
+                let u = eval(v, w + 1, &mut ancestors, &mut labels, &semis);
+                semis[w] = semis[w].min(semis[u]);
+            }
+        }
+    }
+
+    // each node's immediate dominator is the nearest common ancestor, in the
+    // dominator tree, of its DFS parent and its semidominator. Computing them
+    // in preorder ensures that each node's ancestors are already final.
+    let mut idoms: Vec<usize> = parents;
+    for w in 1..num_nodes {
+        let mut idom = idoms[w];
+        while idom > semis[w] {
+            idom = idoms[idom];
+        }
+        idoms[w] = idom;
+    }
+
+    DiGraphMap::from_edges((1..num_nodes).map(|w| (nodes[idoms[w]], nodes[w])))
+}
+
+/// For Semi-NCA: get the node with the minimum semidominator on the path from
+/// `v` up to (but not including) its nearest ancestor which hasn't been linked
+/// into the forest yet, compressing the path along the way. Nodes numbered
+/// `last_linked` or higher have been linked.
+fn eval(
+    v: usize,
+    last_linked: usize,
+    ancestors: &mut [usize],
+    labels: &mut [usize],
+    semis: &[usize],
+) -> usize {
+    if ancestors[v] < last_linked {
+        return labels[v];
+    }
+    let mut path = Vec::new();
+    let mut node = v;
+    loop {
+        path.push(node);
+        node = ancestors[node];
+        if ancestors[node] < last_linked {
+            break;
+        }
+    }
+    // now compress the path, from the top down
+    let mut prev = node;
+    let mut prev_label = labels[prev];
+    while let Some(node) = path.pop() {
+        ancestors[node] = ancestors[prev];
+        if semis[prev_label] < semis[labels[node]] {
+            labels[node] = prev_label;
+        } else {
+            prev_label = labels[node];
+        }
+        prev = node;
+    }
+    labels[v]
 }
 
 /// Contains state used when constructing the `DominatorTree` or `PostDominatorTree`
+/// with `DominatorAlgorithm::Iterative`
 struct DomTreeBuilder<'m, 'a> {
     /// The `ControlFlowGraph` we're working from
     cfg: &'a ControlFlowGraph<'m>,
@@ -159,9 +310,23 @@ impl<'m, 'a> DomTreeBuilder<'m, 'a> {
 }
 
 impl<'m> DominatorTree<'m> {
-    pub(crate) fn new(cfg: &ControlFlowGraph<'m>) -> Self {
+    /// Compute the dominator tree of the given CFG.
+    ///
+    /// For a function's own CFG, use
+    /// [`FunctionAnalysis::dominator_tree()`](struct.FunctionAnalysis.html#method.dominator_tree)
+    /// instead, which caches the result. This is for CFGs derived from it,
+    /// such as those given by
+    /// [`ControlFlowGraph::without_edges()`](struct.ControlFlowGraph.html#method.without_edges).
+    pub fn new(cfg: &ControlFlowGraph<'m>) -> Self {
+        Self::with_algorithm(cfg, DominatorAlgorithm::Auto)
+    }
+
+    /// Compute the dominator tree of the given CFG using the given algorithm.
+    pub fn with_algorithm(cfg: &ControlFlowGraph<'m>, algorithm: DominatorAlgorithm) -> Self {
+        let graph = build_tree(cfg, algorithm);
         Self {
-            graph: DomTreeBuilder::new(cfg).build(),
+            intervals: tree_intervals(&graph, cfg.entry_node),
+            graph,
             entry_node: cfg.entry_node,
         }
     }
@@ -181,7 +346,7 @@ impl<'m> DominatorTree<'m> {
             .graph
             .neighbors_directed(CFGNode::Block(block), Direction::Incoming);
         let idom = parents.next()?;
-        if let Some(_) = parents.next() {
+        if parents.next().is_some() {
             panic!("Block {:?} should have only one immediate dominator", block);
         }
         match idom {
@@ -202,14 +367,15 @@ impl<'m> DominatorTree<'m> {
     ///     the function
     ///
     /// If the return node is unreachable (e.g., due to an infinite loop in the
-    /// function), then the return node has no immediate dominator, and `None` will
-    /// be returned.
+    /// function, or because every path through the function ends in
+    /// `unreachable`), then the return node has no immediate dominator, and
+    /// `None` will be returned.
     pub fn idom_of_return(&self) -> Option<&'m Name> {
         let mut parents = self
             .graph
             .neighbors_directed(CFGNode::Return, Direction::Incoming);
         let idom = parents.next()?;
-        if let Some(_) = parents.next() {
+        if parents.next().is_some() {
             panic!("Return node should have only one immediate dominator");
         }
         match idom {
@@ -227,13 +393,98 @@ impl<'m> DominatorTree<'m> {
             .neighbors_directed(CFGNode::Block(block), Direction::Outgoing)
     }
 
+    /// Get the siblings of the given basic block in the dominator tree, i.e.,
+    /// get all the other nodes which have the same immediate dominator as
+    /// `block`.
+    ///
+    /// This is empty for the entry block and for any unreachable blocks. See
+    /// notes on `idom()`.
+    pub fn siblings<'s>(&'s self, block: &'m Name) -> impl Iterator<Item = CFGNode<'m>> + 's {
+        self.idom(block)
+            .into_iter()
+            .flat_map(move |idom| self.children(idom))
+            .filter(move |&sibling| sibling != CFGNode::Block(block))
+    }
+
+    /// Iterate over the strict dominators of the given basic block, from its
+    /// immediate dominator up to the entry block; i.e., iterate over the path
+    /// from `block` to the root of the dominator tree, excluding `block`
+    /// itself.
+    ///
+    /// This is empty for the entry block and for any unreachable blocks. See
+    /// notes on `idom()`.
+    pub fn idom_chain<'s>(&'s self, block: &'m Name) -> impl Iterator<Item = &'m Name> + 's {
+        std::iter::successors(self.idom(block), move |&idom| self.idom(idom))
+    }
+
+    /// Iterate over the nodes of the dominator tree in preorder, starting
+    /// from the entry block: each node appears before all of the nodes it
+    /// strictly dominates. Unreachable blocks are not included, and the order
+    /// of siblings (nodes with the same immediate dominator) is unspecified.
+    pub fn preorder<'s>(&'s self) -> impl Iterator<Item = CFGNode<'m>> + 's {
+        self.preorder_from(self.entry_node)
+    }
+
+    /// Like [`preorder()`](#method.preorder), but iterate over only the
+    /// subtree rooted at `root`, i.e., `root` and the nodes it dominates. This
+    /// is empty if `root` is unreachable.
+    pub fn preorder_from<'s>(
+        &'s self,
+        root: CFGNode<'m>,
+    ) -> impl Iterator<Item = CFGNode<'m>> + 's {
+        let reachable = self.is_reachable(root);
+        Dfs::new(&self.graph, root)
+            .iter(&self.graph)
+            .filter(move |_| reachable)
+    }
+
+    /// Iterate over the nodes of the dominator tree in postorder, ending with
+    /// the entry block: each node appears after all of the nodes it strictly
+    /// dominates. Unreachable blocks are not included, and the order of
+    /// siblings (nodes with the same immediate dominator) is unspecified.
+    pub fn postorder<'s>(&'s self) -> impl Iterator<Item = CFGNode<'m>> + 's {
+        self.postorder_from(self.entry_node)
+    }
+
+    /// Like [`postorder()`](#method.postorder), but iterate over only the
+    /// subtree rooted at `root`, i.e., `root` and the nodes it dominates. This
+    /// is empty if `root` is unreachable.
+    pub fn postorder_from<'s>(
+        &'s self,
+        root: CFGNode<'m>,
+    ) -> impl Iterator<Item = CFGNode<'m>> + 's {
+        let reachable = self.is_reachable(root);
+        DfsPostOrder::new(&self.graph, root)
+            .iter(&self.graph)
+            .filter(move |_| reachable)
+    }
+
+    /// Get all the nodes dominated by the given node, including the node itself
+    /// (see `dominates()`). This is empty if `node` is unreachable.
+    pub fn dominated_by(&self, node: CFGNode<'m>) -> HashSet<CFGNode<'m>> {
+        self.preorder_from(node).collect()
+    }
+
+    /// Is the given node reachable from the entry block, i.e., is it in the
+    /// dominator tree at all?
+    pub(crate) fn is_reachable(&self, node: CFGNode<'m>) -> bool {
+        node == self.entry_node
+            || self
+                .graph
+                .neighbors_directed(node, Direction::Incoming)
+                .next()
+                .is_some()
+    }
+
     /// Does `node_a` dominate `node_b`?
     ///
     /// Note that every node dominates itself by definition, so if
     /// `node_a == node_b`, this returns `true`.
     /// See also `strictly_dominates()`
+    ///
+    /// This takes constant time, so it's suitable for issuing many queries.
     pub fn dominates(&self, node_a: CFGNode<'m>, node_b: CFGNode<'m>) -> bool {
-        petgraph::algo::has_path_connecting(&self.graph, node_a, node_b, None)
+        node_a == node_b || interval_contains(&self.intervals, node_a, node_b)
     }
 
     /// Does `node_a` strictly dominate `node_b`?
@@ -244,6 +495,32 @@ impl<'m> DominatorTree<'m> {
         node_a != node_b && self.dominates(node_a, node_b)
     }
 
+    /// Does the CFG edge from `from` to `to` dominate the block `block`, i.e.,
+    /// does every path from the entry block to `block` go through that edge?
+    ///
+    /// `cfg` must be the CFG this tree was computed from. If `from` has more
+    /// than one edge to `to` (e.g., both outcomes of a conditional branch go
+    /// to `to`), none of them dominates anything.
+    pub fn edge_dominates(
+        &self,
+        cfg: &ControlFlowGraph<'m>,
+        from: &'m Name,
+        to: &'m Name,
+        block: &'m Name,
+    ) -> bool {
+        // the edge dominates `block` if `to` does, and every other way into
+        // `to` comes from a block `to` dominates (i.e., around a loop), or
+        // from a block which can't be reached from the entry block
+        cfg.edge_kinds(from, CFGNode::Block(to)).len() == 1
+            && to != self.entry()
+            && self.dominates(CFGNode::Block(to), CFGNode::Block(block))
+            && cfg.preds(to).all(|pred| {
+                pred == from
+                    || !self.is_reachable(CFGNode::Block(pred))
+                    || self.dominates(CFGNode::Block(to), CFGNode::Block(pred))
+            })
+    }
+
     /// Get the `Name` of the entry block for the function
     pub fn entry(&self) -> &'m Name {
         match self.entry_node {
@@ -251,23 +528,51 @@ impl<'m> DominatorTree<'m> {
             CFGNode::Return => panic!("Return node should not be entry"),
         }
     }
+
+    /// Render the dominator tree in Graphviz DOT format, with an edge from
+    /// each node to each of the nodes it immediately dominates. Unreachable
+    /// blocks are not included.
+    pub fn to_dot(&self) -> String {
+        tree_to_dot("DominatorTree", self.entry_node, &self.graph)
+    }
 }
 
 impl<'m> PostDominatorTree<'m> {
-    pub(crate) fn new(cfg: &ControlFlowGraph<'m>) -> Self {
+    /// Compute the postdominator tree of the given CFG.
+    ///
+    /// For a function's own CFG, use
+    /// [`FunctionAnalysis::postdominator_tree()`](struct.FunctionAnalysis.html#method.postdominator_tree)
+    /// instead, which caches the result. This is for CFGs derived from it,
+    /// such as those given by
+    /// [`ControlFlowGraph::without_edges()`](struct.ControlFlowGraph.html#method.without_edges).
+    pub fn new(cfg: &ControlFlowGraph<'m>) -> Self {
+        Self::with_algorithm(cfg, DominatorAlgorithm::Auto)
+    }
+
+    /// Compute the postdominator tree of the given CFG using the given
+    /// algorithm.
+    pub fn with_algorithm(cfg: &ControlFlowGraph<'m>, algorithm: DominatorAlgorithm) -> Self {
         // The postdominator relation for `cfg` is the dominator relation on
         // the reversed `cfg` (Cytron et al, p. 477)
 
+        let graph = build_tree(&cfg.reversed(), algorithm);
         Self {
-            graph: DomTreeBuilder::new(&cfg.reversed()).build(),
+            intervals: tree_intervals(&graph, CFGNode::Return),
+            graph,
         }
     }
 
     /// Get the immediate postdominator of the basic block with the given `Name`.
     ///
-    /// This will be `None` for unreachable blocks (or, in some cases, when the
-    /// function return is unreachable, e.g. due to an infinite loop), and `Some`
-    /// for all other blocks.
+    /// This will be `None` for blocks which can't reach the function exit
+    /// (e.g., blocks in an infinite loop, or leading only to `unreachable`),
+    /// and `Some` for all other blocks. In particular, if the function never
+    /// returns, it's `None` for every block. Paths which never reach the
+    /// function exit are ignored, so a block may be postdominated by a block
+    /// which some paths from it never reach. See also
+    /// [`reaches_exit()`](#method.reaches_exit); to give blocks in infinite
+    /// loops an immediate postdominator as well, build the tree with
+    /// [`ExitKind::InfiniteLoop`](enum.ExitKind.html#variant.InfiniteLoop).
     ///
     /// A block bbX is the immediate postdominator of bbY if and only if:
     ///   - bbX strictly postdominates bbY, i.e., bbX appears on every control-flow
@@ -281,12 +586,23 @@ impl<'m> PostDominatorTree<'m> {
         self.ipostdom_of_cfgnode(CFGNode::Block(block))
     }
 
+    /// Can the basic block with the given `Name` reach the function exit, i.e.,
+    /// does it appear in the postdominator tree at all?
+    ///
+    /// This is `false` for blocks in divergent regions of the function, e.g.,
+    /// blocks in an infinite loop, or leading only to `unreachable`. Those
+    /// blocks have no immediate postdominator or children, and neither
+    /// postdominate nor are postdominated by any other node.
+    pub fn reaches_exit(&self, block: &'m Name) -> bool {
+        self.intervals.contains_key(&CFGNode::Block(block))
+    }
+
     /// See notes on `ipostdom()`, but in addition, this will be `None` for
     /// `CFGNode::Return`
     pub(crate) fn ipostdom_of_cfgnode(&self, node: CFGNode<'m>) -> Option<CFGNode<'m>> {
         let mut parents = self.graph.neighbors_directed(node, Direction::Incoming);
         let ipostdom = parents.next()?;
-        if let Some(_) = parents.next() {
+        if parents.next().is_some() {
             panic!(
                 "Block {:?} should have only one immediate postdominator",
                 node
@@ -313,7 +629,7 @@ impl<'m> PostDominatorTree<'m> {
     /// Get the children of `CFGNode::Return` in the postdominator tree, i.e.,
     /// get all the blocks which are immediately postdominated by `CFGNode::Return`.
     ///
-    /// See notes on `ipostdom()`.
+    /// This is empty if the function never returns. See notes on `ipostdom()`.
     pub fn children_of_return<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
         self.graph
             .neighbors_directed(CFGNode::Return, Direction::Outgoing)
@@ -325,13 +641,92 @@ impl<'m> PostDominatorTree<'m> {
             })
     }
 
+    /// Get the siblings of the given basic block in the postdominator tree,
+    /// i.e., get all the other blocks which have the same immediate
+    /// postdominator as `block`.
+    ///
+    /// This is empty for blocks which can't reach the function exit. See notes
+    /// on `ipostdom()`.
+    pub fn siblings<'s>(&'s self, block: &'m Name) -> impl Iterator<Item = CFGNode<'m>> + 's {
+        self.ipostdom(block)
+            .into_iter()
+            .flat_map(move |ipostdom| self.children_of_cfgnode(ipostdom))
+            .filter(move |&sibling| sibling != CFGNode::Block(block))
+    }
+
+    /// Iterate over the strict postdominators of the given basic block, from
+    /// its immediate postdominator up to `CFGNode::Return`; i.e., iterate over
+    /// the path from `block` to the root of the postdominator tree, excluding
+    /// `block` itself.
+    ///
+    /// This is empty for blocks which can't reach the function exit. See notes
+    /// on `ipostdom()`.
+    pub fn ipostdom_chain<'s>(&'s self, block: &'m Name) -> impl Iterator<Item = CFGNode<'m>> + 's {
+        std::iter::successors(self.ipostdom(block), move |&ipostdom| {
+            self.ipostdom_of_cfgnode(ipostdom)
+        })
+    }
+
+    /// Iterate over the nodes of the postdominator tree in preorder, starting
+    /// from `CFGNode::Return`: each node appears before all of the nodes it
+    /// strictly postdominates. Blocks which can't reach the function exit are
+    /// not included, and the order of siblings (nodes with the same immediate
+    /// postdominator) is unspecified.
+    pub fn preorder<'s>(&'s self) -> impl Iterator<Item = CFGNode<'m>> + 's {
+        self.preorder_from(CFGNode::Return)
+    }
+
+    /// Like [`preorder()`](#method.preorder), but iterate over only the
+    /// subtree rooted at `root`, i.e., `root` and the nodes it postdominates.
+    /// This is empty if `root` can't reach the function exit.
+    pub fn preorder_from<'s>(
+        &'s self,
+        root: CFGNode<'m>,
+    ) -> impl Iterator<Item = CFGNode<'m>> + 's {
+        let in_tree = self.intervals.contains_key(&root);
+        Dfs::new(&self.graph, root)
+            .iter(&self.graph)
+            .filter(move |_| in_tree)
+    }
+
+    /// Iterate over the nodes of the postdominator tree in postorder, ending
+    /// with `CFGNode::Return`: each node appears after all of the nodes it
+    /// strictly postdominates. Blocks which can't reach the function exit are
+    /// not included, and the order of siblings (nodes with the same immediate
+    /// postdominator) is unspecified.
+    pub fn postorder<'s>(&'s self) -> impl Iterator<Item = CFGNode<'m>> + 's {
+        self.postorder_from(CFGNode::Return)
+    }
+
+    /// Like [`postorder()`](#method.postorder), but iterate over only the
+    /// subtree rooted at `root`, i.e., `root` and the nodes it postdominates.
+    /// This is empty if `root` can't reach the function exit.
+    pub fn postorder_from<'s>(
+        &'s self,
+        root: CFGNode<'m>,
+    ) -> impl Iterator<Item = CFGNode<'m>> + 's {
+        let in_tree = self.intervals.contains_key(&root);
+        DfsPostOrder::new(&self.graph, root)
+            .iter(&self.graph)
+            .filter(move |_| in_tree)
+    }
+
+    /// Get all the nodes postdominated by the given node, including the node
+    /// itself (see `postdominates()`). This is empty if `node` can't reach
+    /// the function exit.
+    pub fn postdominated_by(&self, node: CFGNode<'m>) -> HashSet<CFGNode<'m>> {
+        self.preorder_from(node).collect()
+    }
+
     /// Does `node_a` postdominate `node_b`?
     ///
     /// Note that every node postdominates itself by definition, so if
     /// `node_a == node_b`, this returns `true`.
     /// See also `strictly_postdominates()`
+    ///
+    /// This takes constant time, so it's suitable for issuing many queries.
     pub fn postdominates(&self, node_a: CFGNode<'m>, node_b: CFGNode<'m>) -> bool {
-        petgraph::algo::has_path_connecting(&self.graph, node_a, node_b, None)
+        node_a == node_b || interval_contains(&self.intervals, node_a, node_b)
     }
 
     /// Does `node_a` strictly postdominate `node_b`?
@@ -341,4 +736,92 @@ impl<'m> PostDominatorTree<'m> {
     pub fn strictly_postdominates(&self, node_a: CFGNode<'m>, node_b: CFGNode<'m>) -> bool {
         node_a != node_b && self.postdominates(node_a, node_b)
     }
+
+    /// Render the postdominator tree in Graphviz DOT format, with an edge from
+    /// each node to each of the nodes it immediately postdominates. Blocks
+    /// which can't reach the function exit are not included.
+    pub fn to_dot(&self) -> String {
+        tree_to_dot("PostDominatorTree", CFGNode::Return, &self.graph)
+    }
+}
+
+/// Number the nodes of the given tree with their discovery and finishing times
+/// in a DFS from `root`. Then a node is an ancestor of another (or the same
+/// node) if and only if its interval contains the other's.
+///
+/// The DFS uses an explicit stack rather than recursion, so that deep trees
+/// (e.g., of functions with long chains of blocks) can't overflow the stack.
+fn tree_intervals<'m>(
+    tree: &DiGraphMap<CFGNode<'m>, ()>,
+    root: CFGNode<'m>,
+) -> HashMap<CFGNode<'m>, (usize, usize)> {
+    let mut intervals = HashMap::new();
+    let mut time = 0;
+    intervals.insert(root, (time, 0));
+    time += 1;
+    let mut stack = vec![(root, tree.neighbors_directed(root, Direction::Outgoing))];
+    while let Some((node, children)) = stack.last_mut() {
+        match children.next() {
+            Some(child) => {
+                // in a tree, each node is discovered exactly once
+                if !intervals.contains_key(&child) {
+                    intervals.insert(child, (time, 0));
+                    time += 1;
+                    stack.push((child, tree.neighbors_directed(child, Direction::Outgoing)));
+                }
+            }
+            None => {
+                if let Some(interval) = intervals.get_mut(node) {
+                    interval.1 = time;
+                }
+                time += 1;
+                stack.pop();
+            }
+        }
+    }
+    intervals
+}
+
+/// Is `node_a` an ancestor of `node_b` (or the same node) in the tree with the
+/// given `tree_intervals()`?
+fn interval_contains<'m>(
+    intervals: &HashMap<CFGNode<'m>, (usize, usize)>,
+    node_a: CFGNode<'m>,
+    node_b: CFGNode<'m>,
+) -> bool {
+    match (intervals.get(&node_a), intervals.get(&node_b)) {
+        (Some(&(start_a, end_a)), Some(&(start_b, end_b))) => start_a <= start_b && end_b <= end_a,
+        _ => false,
+    }
+}
+
+/// Render the given tree in Graphviz DOT format, as a graph with the given
+/// name. Nodes and edges are sorted, so the output is deterministic.
+fn tree_to_dot<'m>(name: &str, root: CFGNode<'m>, tree: &DiGraphMap<CFGNode<'m>, ()>) -> String {
+    // quote a node's name, escaping as necessary
+    fn quote(node: CFGNode) -> String {
+        format!(
+            "\"{}\"",
+            node.to_string().replace('\\', "\\\\").replace('"', "\\\"")
+        )
+    }
+
+    let mut nodes: Vec<CFGNode<'m>> = std::iter::once(root).chain(tree.nodes()).collect();
+    nodes.sort();
+    nodes.dedup();
+    let mut edges: Vec<(CFGNode<'m>, CFGNode<'m>)> = tree
+        .all_edges()
+        .map(|(parent, child, _)| (parent, child))
+        .collect();
+    edges.sort();
+
+    let mut dot = format!("digraph {} {{\n", name);
+    for node in nodes {
+        dot.push_str(&format!("    {};\n", quote(node)));
+    }
+    for (parent, child) in edges {
+        dot.push_str(&format!("    {} -> {};\n", quote(parent), quote(child)));
+    }
+    dot.push_str("}\n");
+    dot
 }
@@ -0,0 +1,39 @@
+use std::fmt;
+
+/// An error returned by the `try_` methods of [`ModuleAnalysis`](struct.ModuleAnalysis.html),
+/// [`CrossModuleAnalysis`](struct.CrossModuleAnalysis.html), and
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), for clients which
+/// would rather handle these cases than panic.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum AnalysisError {
+    /// No function with the given name exists in the analyzed `Module`(s)
+    FunctionNotFound(String),
+    /// The function with the given name is only declared, not defined, in the
+    /// analyzed `Module`(s), or has no basic blocks, so it has no body to
+    /// analyze
+    FunctionHasNoBody(String),
+    /// No module with the given name exists in the `CrossModuleAnalysis`
+    ModuleNotFound(String),
+}
+
+impl fmt::Display for AnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AnalysisError::FunctionNotFound(name) => {
+                write!(f, "Function named {:?} not found in the Module(s)", name)
+            }
+            AnalysisError::FunctionHasNoBody(name) => {
+                write!(f, "Function named {:?} has no body to analyze", name)
+            }
+            AnalysisError::ModuleNotFound(name) => {
+                write!(
+                    f,
+                    "Module named {:?} not found in the CrossModuleAnalysis",
+                    name
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for AnalysisError {}
@@ -0,0 +1,429 @@
+use crate::call_graph::{
+    direct_callee, functions_with_names, CallGraph, CallOrInvoke, FunctionName,
+};
+use crate::control_flow_graph::{CFGEdgeKind, CFGNode, ControlFlowGraph};
+use either::Either;
+use llvm_ir::{
+    function::FunctionAttribute, BasicBlock, Function, Instruction, Module, Name, Terminator,
+};
+use petgraph::prelude::*;
+use petgraph::visit::{Bfs, Dfs};
+use std::collections::{HashMap, HashSet};
+
+/// Describes how exceptions may propagate through the analyzed `Module`(s):
+/// which functions may throw (unwind), and for each function, which exception
+/// handlers (landing pads, or other EH pads) its exceptions may reach, and
+/// whether they may escape the program entirely.
+///
+/// A function is considered to possibly throw if it contains a `resume` (or
+/// other terminator which unwinds to its caller), or an ordinary call (not an
+/// `invoke`) to a function which may throw, in a block which may be reached.
+/// Blocks reached only by exceptions from `invoke`s which may not throw (such
+/// as the landing pads of `invoke`s of `nounwind` functions) aren't
+/// considered reachable. Functions marked `nounwind`, calls marked
+/// `nounwind`, calls to functions marked `nounwind`, and calls to LLVM
+/// intrinsics are assumed not to throw. Calls to functions which are only
+/// declared in the analyzed `Module`(s) are conservatively assumed to throw
+/// unless marked `nounwind`. Calls through function pointers may throw if any
+/// function the [`CallGraph`](struct.CallGraph.html) says they may call may
+/// throw, or if they may not call any function in the analyzed `Module`(s).
+///
+/// An exception reaching a handler may continue to propagate, if the handler
+/// may `resume` (e.g., because it is a cleanup, or doesn't match the
+/// exception's type). Exceptions propagating out of a function with no callers
+/// in the analyzed `Module`(s) (e.g., `main`) are considered to escape.
+///
+/// To construct an `ExceptionPropagationGraph`, use [`ModuleAnalysis`](struct.ModuleAnalysis.html)
+/// or [`CrossModuleAnalysis`](struct.CrossModuleAnalysis.html).
+pub struct ExceptionPropagationGraph<'m> {
+    /// The graph itself. An edge from A to B indicates that exceptions may
+    /// propagate directly from A to B
+    graph: DiGraphMap<ExceptionNode<'m>, ()>,
+    /// Map from the name of each function defined in the analyzed `Module`(s)
+    /// to whether it may throw
+    may_throw: HashMap<FunctionName<'m>, bool>,
+    /// All call sites at which an exception may be thrown
+    sites: Vec<UnwindSite<'m>>,
+}
+
+/// A node in the [`ExceptionPropagationGraph`](struct.ExceptionPropagationGraph.html)
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub enum ExceptionNode<'m> {
+    /// Exceptions propagating out of the function with the given name
+    Function(FunctionName<'m>),
+    /// The exception handler beginning at the given block (the unwind
+    /// destination of an `invoke`)
+    Handler {
+        /// Name of the function containing the handler
+        function: FunctionName<'m>,
+        /// Name of the handler block
+        block: &'m Name,
+    },
+    /// Exceptions escaping the program
+    Escape,
+}
+
+/// A call site at which an exception may be thrown
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct UnwindSite<'m> {
+    /// Name of the function containing the call
+    pub caller: FunctionName<'m>,
+    /// Name of the basic block containing the call
+    pub block: &'m Name,
+    /// Index of the call within the basic block. If the call is the block's
+    /// terminator (i.e., an `Invoke`), this is equal to the number of
+    /// (non-terminator) instructions in the block.
+    pub index: usize,
+    /// Names of the functions which may be called here and which may throw.
+    /// For calls through function pointers, this includes any function the
+    /// [`CallGraph`](struct.CallGraph.html) says may be called here which may
+    /// throw, and may be empty if there are none.
+    pub callees: Vec<FunctionName<'m>>,
+    /// For an `invoke`, the block where exceptions thrown by the call are
+    /// handled. For an ordinary call, `None`, indicating that exceptions
+    /// propagate out of the caller.
+    pub handler: Option<&'m Name>,
+}
+
+impl<'m> ExceptionPropagationGraph<'m> {
+    pub(crate) fn new(
+        modules: impl IntoIterator<Item = &'m Module>,
+        call_graph: &CallGraph<'m>,
+    ) -> Self {
+        let functions: Vec<(&'m Module, &'m Function, FunctionName<'m>)> = modules
+            .into_iter()
+            .flat_map(|m| functions_with_names(m).map(move |(f, name)| (m, f, name)))
+            .collect();
+
+        let callees = call_graph.callees_by_site();
+        let cfgs: Vec<ControlFlowGraph<'m>> = functions
+            .iter()
+            .map(|(_, f, _)| ControlFlowGraph::new(f))
+            .collect();
+
+        // compute which functions may throw, iterating to a fixpoint
+        let mut may_throw: HashMap<FunctionName<'m>, bool> = functions
+            .iter()
+            .map(|&(_, _, name)| (name, false))
+            .collect();
+        loop {
+            let mut changed = false;
+            for (&(module, f, name), cfg) in functions.iter().zip(&cfgs) {
+                if may_throw[&name] || is_nounwind(&f.function_attributes) {
+                    continue;
+                }
+                if function_may_throw(module, f, name, cfg, &callees, &may_throw) {
+                    may_throw.insert(name, true);
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        let mut graph: DiGraphMap<ExceptionNode<'m>, ()> = DiGraphMap::new();
+        let mut sites = vec![];
+        for (&(module, f, name), cfg) in functions.iter().zip(&cfgs) {
+            graph.add_node(ExceptionNode::Function(name));
+            for bb in &f.basic_blocks {
+                let calls = bb
+                    .instrs
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, inst)| match inst {
+                        Instruction::Call(call) => {
+                            Some((index, CallOrInvoke::Call { call, module }, None))
+                        }
+                        _ => None,
+                    })
+                    .chain(match &bb.term {
+                        Terminator::Invoke(invoke) => Some((
+                            bb.instrs.len(),
+                            CallOrInvoke::Invoke { invoke, module },
+                            Some(&invoke.exception_label),
+                        )),
+                        _ => None,
+                    });
+                for (index, call, handler) in calls {
+                    let site_callees = callees.get(&(name, &bb.name, index));
+                    if !call_may_throw(&call, site_callees, &may_throw) {
+                        continue;
+                    }
+                    let callees: Vec<FunctionName<'m>> = match (call.callee(), site_callees) {
+                        (Either::Right(_), Some(site_callees)) => site_callees
+                            .iter()
+                            .copied()
+                            .filter(|&name| callee_may_throw(name, &may_throw))
+                            .collect(),
+                        (Either::Right(callee), None) => direct_callee(callee)
+                            .map(FunctionName::Named)
+                            .into_iter()
+                            .collect(),
+                        (Either::Left(_), _) => vec![],
+                    };
+                    let dest = match handler {
+                        Some(block) => {
+                            if handler_may_resume(f, cfg, block) {
+                                graph.add_edge(
+                                    ExceptionNode::Handler {
+                                        function: name,
+                                        block,
+                                    },
+                                    ExceptionNode::Function(name),
+                                    (),
+                                );
+                            }
+                            ExceptionNode::Handler {
+                                function: name,
+                                block,
+                            }
+                        }
+                        None => ExceptionNode::Function(name),
+                    };
+                    for &callee in &callees {
+                        graph.add_edge(ExceptionNode::Function(callee), dest, ());
+                    }
+                    sites.push(UnwindSite {
+                        caller: name,
+                        block: &bb.name,
+                        index,
+                        callees,
+                        handler,
+                    });
+                }
+            }
+            if may_throw[&name] && call_graph.callers(name).next().is_none() {
+                graph.add_edge(ExceptionNode::Function(name), ExceptionNode::Escape, ());
+            }
+        }
+
+        Self {
+            graph,
+            may_throw,
+            sites,
+        }
+    }
+
+    /// Might the function with the given name throw (unwind)?
+    ///
+    /// Functions which are only declared in the analyzed `Module`(s) are
+    /// assumed to possibly throw, unless they are LLVM intrinsics.
+    pub fn may_throw<'n>(&self, func_name: impl Into<FunctionName<'n>>) -> bool {
+        callee_may_throw(func_name.into(), &self.may_throw)
+    }
+
+    /// Iterate over all of the call sites in the analyzed `Module`(s) at which
+    /// an exception may be thrown.
+    pub fn sites<'s>(&'s self) -> impl Iterator<Item = &'s UnwindSite<'m>> + 's {
+        self.sites.iter()
+    }
+
+    /// Iterate over the call sites in the function with the given name at which
+    /// an exception may be thrown.
+    pub fn sites_in<'s>(
+        &'s self,
+        func_name: impl Into<FunctionName<'s>>,
+    ) -> impl Iterator<Item = &'s UnwindSite<'m>> + 's {
+        let func_name = func_name.into();
+        let sites: Vec<_> = self
+            .sites
+            .iter()
+            .filter(|site| site.caller == func_name)
+            .collect();
+        sites.into_iter()
+    }
+
+    /// Get the handlers which exceptions thrown by the function with the given
+    /// name may (directly or transitively) reach, as pairs of (function name,
+    /// handler block name), sorted.
+    ///
+    /// This includes handlers reached after other handlers `resume`.
+    pub fn handlers<'s>(
+        &'s self,
+        func_name: impl Into<FunctionName<'m>>,
+    ) -> impl Iterator<Item = (FunctionName<'m>, &'m Name)> + 's {
+        let mut handlers: Vec<(FunctionName<'m>, &'m Name)> = self
+            .reachable_from(func_name.into())
+            .into_iter()
+            .filter_map(|node| match node {
+                ExceptionNode::Handler { function, block } => Some((function, block)),
+                _ => None,
+            })
+            .collect();
+        handlers.sort_unstable();
+        handlers.into_iter()
+    }
+
+    /// Might exceptions thrown by the function with the given name escape the
+    /// program, i.e., propagate out of a function with no callers?
+    pub fn escapes(&self, func_name: impl Into<FunctionName<'m>>) -> bool {
+        self.reachable_from(func_name.into())
+            .contains(&ExceptionNode::Escape)
+    }
+
+    /// Get the nodes which exceptions may propagate directly to from the given
+    /// node.
+    pub fn succs<'s>(
+        &'s self,
+        node: ExceptionNode<'m>,
+    ) -> impl Iterator<Item = ExceptionNode<'m>> + 's {
+        self.graph
+            .contains_node(node)
+            .then(|| self.graph.neighbors_directed(node, Direction::Outgoing))
+            .into_iter()
+            .flatten()
+    }
+
+    /// Get the nodes which exceptions may propagate directly from to reach the
+    /// given node.
+    pub fn preds<'s>(
+        &'s self,
+        node: ExceptionNode<'m>,
+    ) -> impl Iterator<Item = ExceptionNode<'m>> + 's {
+        self.graph
+            .contains_node(node)
+            .then(|| self.graph.neighbors_directed(node, Direction::Incoming))
+            .into_iter()
+            .flatten()
+    }
+
+    /// Get all the nodes reachable from the given function's node (not
+    /// including that node itself, unless it is part of a cycle)
+    fn reachable_from(&self, func_name: FunctionName<'m>) -> Vec<ExceptionNode<'m>> {
+        let start = ExceptionNode::Function(func_name);
+        if !self.graph.contains_node(start) {
+            return vec![];
+        }
+        let mut reachable = vec![];
+        let mut bfs = Bfs::new(&self.graph, start);
+        while let Some(node) = bfs.next(&self.graph) {
+            if node != start {
+                reachable.push(node);
+            }
+        }
+        reachable
+    }
+}
+
+fn is_nounwind(attrs: &[FunctionAttribute]) -> bool {
+    attrs.contains(&FunctionAttribute::NoUnwind)
+}
+
+fn is_intrinsic(func_name: FunctionName) -> bool {
+    func_name
+        .as_named()
+        .is_some_and(|name| name.starts_with("llvm."))
+}
+
+/// Is the given terminator one which unwinds to the function's caller?
+fn unwinds_to_caller(term: &Terminator) -> bool {
+    match term {
+        Terminator::Resume(_) => true,
+        Terminator::CleanupRet(cleanupret) => cleanupret.unwind_dest.is_none(),
+        Terminator::CatchSwitch(catchswitch) => catchswitch.default_unwind_dest.is_none(),
+        _ => false,
+    }
+}
+
+/// Might the given function throw, given the current knowledge of which
+/// defined functions may throw? Only the blocks reachable from its entry
+/// without exceptions, or by exceptions which may be thrown, are considered.
+fn function_may_throw<'m>(
+    module: &'m Module,
+    f: &'m Function,
+    name: FunctionName<'m>,
+    cfg: &ControlFlowGraph<'m>,
+    callees: &HashMap<(FunctionName<'m>, &'m Name, usize), Vec<FunctionName<'m>>>,
+    may_throw: &HashMap<FunctionName<'m>, bool>,
+) -> bool {
+    let site_may_throw = |bb: &'m BasicBlock, index: usize, call: &CallOrInvoke| {
+        let site_callees = callees.get(&(name, &bb.name, index));
+        call_may_throw(call, site_callees, may_throw)
+    };
+    let mut reachable: HashSet<&'m Name> = HashSet::new();
+    let mut worklist = vec![cfg.entry()];
+    while let Some(block) = worklist.pop() {
+        if !reachable.insert(block) {
+            continue;
+        }
+        let bb = f
+            .get_bb_by_name(block)
+            .expect("CFG blocks should be in the function");
+        if unwinds_to_caller(&bb.term) {
+            return true;
+        }
+        for (index, inst) in bb.instrs.iter().enumerate() {
+            if let Instruction::Call(call) = inst {
+                if site_may_throw(bb, index, &CallOrInvoke::Call { call, module }) {
+                    return true;
+                }
+            }
+        }
+        let invoke_may_throw = match &bb.term {
+            Terminator::Invoke(invoke) => {
+                let call = CallOrInvoke::Invoke { invoke, module };
+                site_may_throw(bb, bb.instrs.len(), &call)
+            }
+            _ => false,
+        };
+        for (succ, kind) in cfg.labeled_succs(block) {
+            if let CFGNode::Block(succ) = succ {
+                if kind != CFGEdgeKind::InvokeException || invoke_may_throw {
+                    worklist.push(succ);
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Might the given call throw, given the functions which the call graph says
+/// it may call (if any), and the current knowledge of which defined functions
+/// may throw?
+fn call_may_throw(
+    call: &CallOrInvoke,
+    callees: Option<&Vec<FunctionName>>,
+    may_throw: &HashMap<FunctionName, bool>,
+) -> bool {
+    if is_nounwind(call.function_attributes()) {
+        return false;
+    }
+    match (call.callee(), callees) {
+        (Either::Right(_), Some(callees)) => callees
+            .iter()
+            .any(|&name| callee_may_throw(name, may_throw)),
+        (Either::Right(callee), None) => match direct_callee(callee) {
+            Some(name) => callee_may_throw(FunctionName::Named(name), may_throw),
+            // a function pointer which may not point to any function in the
+            // analyzed `Module`(s)
+            None => true,
+        },
+        (Either::Left(_), _) => false, // inline assembly
+    }
+}
+
+/// Might the function with the given name throw, given the current knowledge
+/// of which defined functions may throw?
+fn callee_may_throw(name: FunctionName, may_throw: &HashMap<FunctionName, bool>) -> bool {
+    match may_throw.get(&name) {
+        Some(may_throw) => *may_throw,
+        None => !is_intrinsic(name),
+    }
+}
+
+/// Might the handler beginning at `handler` reach a terminator which unwinds
+/// to the function's caller?
+fn handler_may_resume(f: &Function, cfg: &ControlFlowGraph, handler: &Name) -> bool {
+    let mut dfs = Dfs::new(&cfg.graph, CFGNode::Block(handler));
+    while let Some(node) = dfs.next(&cfg.graph) {
+        if let CFGNode::Block(block) = node {
+            if f.get_bb_by_name(block)
+                .is_some_and(|bb| unwinds_to_caller(&bb.term))
+            {
+                return true;
+            }
+        }
+    }
+    false
+}
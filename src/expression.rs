@@ -0,0 +1,226 @@
+use crate::operands::instruction_operands;
+use llvm_ir::instruction::*;
+use llvm_ir::{Instruction, IntPredicate, Name, Operand};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// An expression computed by a pure instruction: one which has no side
+/// effects, doesn't access memory, and whose result depends only on its
+/// operands. Arithmetic, bitwise, comparison, cast, vector and aggregate
+/// instructions, `getelementptr`s and `select`s compute expressions; `load`s,
+/// `call`s, `phi`s, `alloca`s and `freeze`s don't.
+///
+/// Two instructions compute the same `Expression` if they have the same
+/// opcode, operands, and other attributes (e.g., comparison predicate or
+/// destination type), regardless of the names of their results. Operands are
+/// compared syntactically, so `add %a, %b` and `add %b, %a` are different
+/// expressions.
+#[derive(Clone, Debug)]
+pub struct Expression {
+    /// The instruction computing the expression, with its result name and
+    /// debug location replaced by fixed placeholders
+    instruction: Instruction,
+}
+
+impl Expression {
+    /// Get the expression computed by the given instruction, or `None` if the
+    /// instruction isn't pure (see [`Expression`](struct.Expression.html))
+    pub fn new(inst: &Instruction) -> Option<Self> {
+        macro_rules! normalized {
+            ($($variant:ident),* $(,)?) => {
+                match inst {
+                    $(Instruction::$variant(i) => Instruction::$variant($variant {
+                        dest: Name::Number(0),
+                        debugloc: None,
+                        ..i.clone()
+                    }),)*
+                    _ => return None,
+                }
+            };
+        }
+        let instruction = normalized!(
+            Add,
+            Sub,
+            Mul,
+            UDiv,
+            SDiv,
+            URem,
+            SRem,
+            And,
+            Or,
+            Xor,
+            Shl,
+            LShr,
+            AShr,
+            FAdd,
+            FSub,
+            FMul,
+            FDiv,
+            FRem,
+            FNeg,
+            ExtractElement,
+            InsertElement,
+            ShuffleVector,
+            ExtractValue,
+            InsertValue,
+            GetElementPtr,
+            Trunc,
+            ZExt,
+            SExt,
+            FPTrunc,
+            FPExt,
+            FPToUI,
+            FPToSI,
+            UIToFP,
+            SIToFP,
+            PtrToInt,
+            IntToPtr,
+            BitCast,
+            AddrSpaceCast,
+            ICmp,
+            FCmp,
+            Select,
+        );
+        Some(Self { instruction })
+    }
+
+    /// Iterate over the local values (parameters and instruction results)
+    /// which the expression uses as operands
+    pub fn local_operands(&self) -> impl Iterator<Item = &Name> {
+        instruction_operands(&self.instruction)
+            .into_iter()
+            .filter_map(|operand| match operand {
+                Operand::LocalOperand { name, .. } => Some(name),
+                _ => None,
+            })
+    }
+
+    /// Get the expression with each local operand replaced by its number
+    /// according to `number` (leaving operands it gives `None` for as they
+    /// are), and the operands of commutative instructions ordered by number.
+    /// So two expressions which compute the same value, given that values
+    /// with the same number are the same, have the same numbered expression.
+    pub(crate) fn numbered(&self, number: impl Fn(&Name) -> Option<usize>) -> Self {
+        let mut instruction = self.instruction.clone();
+        for operand in operands_mut(&mut instruction) {
+            if let Operand::LocalOperand { name, .. } = operand {
+                if let Some(number) = number(name) {
+                    *name = Name::Number(number);
+                }
+            }
+        }
+        // numbered operands first, in order; then the others, as they are
+        let key = |operand: &Operand| match operand {
+            Operand::LocalOperand {
+                name: Name::Number(number),
+                ..
+            } => (0, *number),
+            _ => (1, 0),
+        };
+        let commutative = match &mut instruction {
+            Instruction::Add(i) => Some((&mut i.operand0, &mut i.operand1)),
+            Instruction::Mul(i) => Some((&mut i.operand0, &mut i.operand1)),
+            Instruction::And(i) => Some((&mut i.operand0, &mut i.operand1)),
+            Instruction::Or(i) => Some((&mut i.operand0, &mut i.operand1)),
+            Instruction::Xor(i) => Some((&mut i.operand0, &mut i.operand1)),
+            Instruction::FAdd(i) => Some((&mut i.operand0, &mut i.operand1)),
+            Instruction::FMul(i) => Some((&mut i.operand0, &mut i.operand1)),
+            Instruction::ICmp(i) if matches!(i.predicate, IntPredicate::EQ | IntPredicate::NE) => {
+                Some((&mut i.operand0, &mut i.operand1))
+            }
+            _ => None,
+        };
+        if let Some((operand0, operand1)) = commutative {
+            if key(operand1) < key(operand0) {
+                std::mem::swap(operand0, operand1);
+            }
+        }
+        Self { instruction }
+    }
+}
+
+/// Get mutable references to the `Operand`s used by the given instruction,
+/// which computes an expression
+fn operands_mut(inst: &mut Instruction) -> Vec<&mut Operand> {
+    macro_rules! operands {
+        ($($variant:ident => [$($field:ident),*]),* $(,)?) => {
+            match inst {
+                $(Instruction::$variant(i) => vec![$(&mut i.$field),*],)*
+                Instruction::GetElementPtr(i) => std::iter::once(&mut i.address)
+                    .chain(i.indices.iter_mut())
+                    .collect(),
+                _ => vec![],
+            }
+        };
+    }
+    operands!(
+        Add => [operand0, operand1],
+        Sub => [operand0, operand1],
+        Mul => [operand0, operand1],
+        UDiv => [operand0, operand1],
+        SDiv => [operand0, operand1],
+        URem => [operand0, operand1],
+        SRem => [operand0, operand1],
+        And => [operand0, operand1],
+        Or => [operand0, operand1],
+        Xor => [operand0, operand1],
+        Shl => [operand0, operand1],
+        LShr => [operand0, operand1],
+        AShr => [operand0, operand1],
+        FAdd => [operand0, operand1],
+        FSub => [operand0, operand1],
+        FMul => [operand0, operand1],
+        FDiv => [operand0, operand1],
+        FRem => [operand0, operand1],
+        FNeg => [operand],
+        ExtractElement => [vector, index],
+        InsertElement => [vector, element, index],
+        ShuffleVector => [operand0, operand1],
+        ExtractValue => [aggregate],
+        InsertValue => [aggregate, element],
+        Trunc => [operand],
+        ZExt => [operand],
+        SExt => [operand],
+        FPTrunc => [operand],
+        FPExt => [operand],
+        FPToUI => [operand],
+        FPToSI => [operand],
+        UIToFP => [operand],
+        SIToFP => [operand],
+        PtrToInt => [operand],
+        IntToPtr => [operand],
+        BitCast => [operand],
+        AddrSpaceCast => [operand],
+        ICmp => [operand0, operand1],
+        FCmp => [operand0, operand1],
+        Select => [condition, true_value, false_value],
+    )
+}
+
+impl PartialEq for Expression {
+    fn eq(&self, other: &Self) -> bool {
+        self.instruction == other.instruction
+    }
+}
+
+// `Instruction` isn't `Eq` only because floating-point constants aren't; an
+// expression with a NaN operand compares unequal even to itself, which at
+// worst makes an analysis miss that it's computed twice
+impl Eq for Expression {}
+
+impl Hash for Expression {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.instruction.hash(state)
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // display the instruction without its placeholder result name
+        let instruction = self.instruction.to_string();
+        match instruction.split_once(" = ") {
+            Some((_, expression)) => write!(f, "{}", expression),
+            None => write!(f, "{}", instruction),
+        }
+    }
+}
@@ -0,0 +1,390 @@
+use crate::call_graph::{
+    alias_targets, callee_global, function_names, global_name, num_numbered_functions,
+    resolve_alias, FunctionName,
+};
+use llvm_ir::module::Linkage;
+use llvm_ir::{Constant, ConstantRef, Function, Instruction, Module, Name, Operand};
+use std::collections::{HashMap, HashSet};
+
+/// Finds function pointers stored in the initializers of global variables,
+/// such as tables of operations (`struct file_operations` and friends),
+/// vtable-like structs, or dispatch tables.
+///
+/// The [`CallGraph`](struct.CallGraph.html) uses this to resolve indirect
+/// calls through function pointers loaded from constant tables: such a call
+/// may only call the functions stored in the corresponding slot(s) of the
+/// table, rather than any function of the appropriate type.
+///
+/// To construct a `FunctionPtrTables`, use [`ModuleAnalysis`](struct.ModuleAnalysis.html)
+/// or [`CrossModuleAnalysis`](struct.CrossModuleAnalysis.html).
+pub struct FunctionPtrTables<'m> {
+    /// Map from the name of a `Module` and the name of a global variable
+    /// defined in it to information about that global, for each global whose
+    /// initializer contains at least one function pointer. Internal globals of
+    /// different `Module`s may share a name, so the `Module` is part of the key.
+    tables: HashMap<(&'m str, &'m str), Table<'m>>,
+}
+
+/// A function pointer stored in a global variable's initializer
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct FunctionPtrTableEntry<'m> {
+    /// Name of the global variable
+    pub global: &'m str,
+    /// Position of the function pointer within the global's initializer, as a
+    /// list of indices into nested structs, arrays, and vectors (as in an
+    /// `extractvalue`). For a global which is just a single function pointer,
+    /// this is empty.
+    pub path: Vec<u64>,
+    /// Name of the function pointed to
+    pub function: FunctionName<'m>,
+}
+
+struct Table<'m> {
+    /// Is the global marked `constant`
+    is_constant: bool,
+    /// Does the global have private or internal linkage, i.e., is it only
+    /// visible within its own `Module`
+    is_local: bool,
+    /// The function pointers in the global's initializer, in order
+    entries: Vec<FunctionPtrTableEntry<'m>>,
+    /// Positions within the initializer holding pointers we couldn't resolve to
+    /// a function or rule out (e.g., `inttoptr` constant expressions)
+    unknown: Vec<Vec<u64>>,
+}
+
+impl<'m> FunctionPtrTables<'m> {
+    pub(crate) fn new(modules: impl IntoIterator<Item = &'m Module>) -> Self {
+        let modules: Vec<&'m Module> = modules.into_iter().collect();
+        let globals = Globals {
+            functions: modules
+                .iter()
+                .flat_map(|module| function_names(module))
+                .collect(),
+            aliases: alias_targets(&modules),
+            num_numbered: modules
+                .iter()
+                .map(|module| (module.name.as_str(), num_numbered_functions(module)))
+                .collect(),
+            indirect: modules
+                .iter()
+                .flat_map(|module| {
+                    let aliases = module.global_aliases.iter().map(|alias| &alias.name);
+                    let ifuncs = module.global_ifuncs.iter().map(|ifunc| &ifunc.name);
+                    aliases.chain(ifuncs)
+                })
+                .filter_map(|name| match name {
+                    Name::Name(name) => Some(name.as_str()),
+                    Name::Number(_) => None,
+                })
+                .collect(),
+        };
+        let mut tables = HashMap::new();
+        for (module, global) in modules
+            .iter()
+            .flat_map(|&module| module.global_vars.iter().map(move |g| (module, g)))
+        {
+            let name = match &global.name {
+                Name::Name(name) => name.as_str(),
+                Name::Number(_) => continue,
+            };
+            if let Some(initializer) = &global.initializer {
+                let mut table = Table {
+                    is_constant: global.is_constant,
+                    is_local: matches!(global.linkage, Linkage::Private | Linkage::Internal),
+                    entries: vec![],
+                    unknown: vec![],
+                };
+                scan_initializer(module, name, initializer, &globals, &mut vec![], &mut table);
+                if !table.entries.is_empty() {
+                    tables.insert((module.name.as_str(), name), table);
+                }
+            }
+        }
+        Self { tables }
+    }
+
+    /// Iterate over the names of the global variables whose initializers
+    /// contain at least one function pointer.
+    pub fn tables<'s>(&'s self) -> impl Iterator<Item = &'m str> + 's {
+        let mut globals: Vec<&'m str> = self.tables.keys().map(|&(_, global)| global).collect();
+        globals.sort_unstable();
+        globals.dedup();
+        globals.into_iter()
+    }
+
+    /// Iterate over the function pointers in the initializer of the global
+    /// variable with the given name, in the order they appear in the
+    /// initializer. If the global doesn't exist or contains no function
+    /// pointers, this will be empty. If several of the analyzed `Module`s
+    /// define (internal) globals with the given name, this includes the
+    /// function pointers of each of them.
+    pub fn entries<'s>(
+        &'s self,
+        global: &str,
+    ) -> impl Iterator<Item = &'s FunctionPtrTableEntry<'m>> + 's {
+        let mut tables: Vec<(&'m str, &'s Table<'m>)> = self
+            .tables
+            .iter()
+            .filter(|(&(_, name), _)| name == global)
+            .map(|(&(module, _), table)| (module, table))
+            .collect();
+        tables.sort_unstable_by_key(|&(module, _)| module);
+        tables
+            .into_iter()
+            .flat_map(|(_, table)| table.entries.iter())
+    }
+
+    /// Iterate over the distinct functions whose addresses are stored in the
+    /// initializer of the global variable with the given name.
+    pub fn functions_in<'s>(&'s self, global: &str) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        let mut funcs: Vec<FunctionName<'m>> =
+            self.entries(global).map(|entry| entry.function).collect();
+        funcs.sort_unstable();
+        funcs.dedup();
+        funcs.into_iter()
+    }
+
+    /// Iterate over the names of the global variables whose initializers
+    /// contain the address of the function with the given name.
+    pub fn tables_containing<'s>(
+        &'s self,
+        func_name: impl Into<FunctionName<'s>>,
+    ) -> impl Iterator<Item = &'m str> + 's {
+        let func_name = func_name.into();
+        let mut globals: Vec<&'m str> = self
+            .tables
+            .iter()
+            .filter(|(_, table)| table.entries.iter().any(|e| e.function == func_name))
+            .map(|(&(_, global), _)| global)
+            .collect();
+        globals.sort_unstable();
+        globals.dedup();
+        globals.into_iter()
+    }
+
+    /// If `op` (an operand in `function`, which is defined in `module`) is a
+    /// function pointer loaded from a known slot (or slots) of a constant
+    /// table, get the functions it may point to. Returns `None` if this can't
+    /// be determined.
+    pub(crate) fn possible_targets(
+        &self,
+        module: &'m Module,
+        function: &'m Function,
+        op: &Operand,
+    ) -> Option<Vec<FunctionName<'m>>> {
+        let name = match op {
+            Operand::LocalOperand { name, .. } => name,
+            _ => return None,
+        };
+        let load = function
+            .basic_blocks
+            .iter()
+            .flat_map(|bb| bb.instrs.iter())
+            .find_map(|inst| match inst {
+                Instruction::Load(load) if &load.dest == name => Some(load),
+                _ => None,
+            })?;
+        let (global, pattern) = slot_of_address(function, &load.address)?;
+        let table = self.table(module, global)?;
+        if !table.is_constant || table.unknown.iter().any(|path| matches(path, &pattern)) {
+            return None;
+        }
+        let mut targets: Vec<FunctionName<'m>> = table
+            .entries
+            .iter()
+            .filter(|entry| matches(&entry.path, &pattern))
+            .map(|entry| entry.function)
+            .collect();
+        targets.sort_unstable();
+        targets.dedup();
+        Some(targets)
+    }
+
+    /// Get the table which the global with the given name refers to in the
+    /// given `Module`: the one the `Module` defines, if any, and otherwise the
+    /// one (non-internal) global with that name defined in another `Module`.
+    fn table(&self, module: &'m Module, global: &str) -> Option<&Table<'m>> {
+        if let Some(table) = self.tables.get(&(module.name.as_str(), global)) {
+            return Some(table);
+        }
+        let defined_here = module.global_vars.iter().any(|g| {
+            g.initializer.is_some()
+                && matches!(&g.name, Name::Name(name) if name.as_str() == global)
+        });
+        if defined_here {
+            return None; // defined here, but not a table of function pointers
+        }
+        let mut external = self
+            .tables
+            .iter()
+            .filter(|(&(_, name), table)| name == global && !table.is_local)
+            .map(|(_, table)| table);
+        match (external.next(), external.next()) {
+            (Some(table), None) => Some(table),
+            _ => None,
+        }
+    }
+}
+
+/// The globals of the analyzed `Module`(s) which a pointer in an initializer
+/// may point to
+struct Globals<'m> {
+    /// Names of all functions
+    functions: HashSet<FunctionName<'m>>,
+    /// Map from the name of each `GlobalAlias` to the global it directly
+    /// aliases (see `alias_targets()`)
+    aliases: HashMap<&'m str, FunctionName<'m>>,
+    /// Map from the name of each `Module` to its number of functions with
+    /// numbered names (see `num_numbered_functions()`)
+    num_numbered: HashMap<&'m str, usize>,
+    /// Names of all `GlobalAlias`es and `GlobalIFunc`s. A pointer to one of
+    /// these which isn't resolved to a function (e.g., the target of an alias
+    /// we can't determine, or any IFunc) may point to some unknown function.
+    indirect: HashSet<&'m str>,
+}
+
+/// Record the function pointers (and unknown pointers) in the given constant,
+/// which is found at position `path` in the initializer of `global` (in
+/// `module`)
+fn scan_initializer<'m>(
+    module: &'m Module,
+    global: &'m str,
+    constant: &'m ConstantRef,
+    globals: &Globals<'m>,
+    path: &mut Vec<u64>,
+    table: &mut Table<'m>,
+) {
+    let elements = match constant.as_ref() {
+        Constant::Struct { values, .. } => values,
+        Constant::Array { elements, .. } => elements,
+        Constant::Vector(elements) => elements,
+        Constant::GlobalReference { .. }
+        | Constant::BitCast(_)
+        | Constant::AddrSpaceCast(_)
+        | Constant::GetElementPtr(_) => {
+            // a pointer to an alias is a pointer to its aliasee
+            let num_numbered = globals.num_numbered[module.name.as_str()];
+            let name = callee_global(constant).map(|name| {
+                global_name(module, num_numbered, name)
+                    .map(|name| resolve_alias(&globals.aliases, name))
+            });
+            match name {
+                Some(Some(name)) if globals.functions.contains(&name) => {
+                    table.entries.push(FunctionPtrTableEntry {
+                        global,
+                        path: path.clone(),
+                        function: name,
+                    })
+                }
+                Some(Some(FunctionName::Named(name))) if globals.indirect.contains(name) => {
+                    table.unknown.push(path.clone())
+                }
+                Some(_) => {} // a pointer to some global which isn't a function
+                None => table.unknown.push(path.clone()),
+            }
+            return;
+        }
+        Constant::IntToPtr(_) => {
+            table.unknown.push(path.clone());
+            return;
+        }
+        #[cfg(feature = "llvm-16-or-lower")]
+        Constant::Select(_) => {
+            table.unknown.push(path.clone());
+            return;
+        }
+        _ => return, // e.g., integers, null pointers, or `zeroinitializer`
+    };
+    for (idx, element) in elements.iter().enumerate() {
+        path.push(idx as u64);
+        scan_initializer(module, global, element, globals, path, table);
+        path.pop();
+    }
+}
+
+/// If the given address (an operand in `function`) points to a slot within a
+/// global variable, get the name of that global and the position of the slot
+/// within it. In the position, `None` means any index.
+///
+/// This relies on typed pointers to relate `getelementptr` indices to
+/// positions in the global's initializer, so with opaque pointers, it always
+/// returns `None`.
+#[cfg(feature = "llvm-14-or-lower")]
+fn slot_of_address<'m>(
+    function: &'m Function,
+    address: &'m Operand,
+) -> Option<(&'m str, Vec<Option<u64>>)> {
+    let (global, indices): (&Constant, Vec<Option<u64>>) = match address {
+        Operand::ConstantOperand(cref) => match cref.as_ref() {
+            Constant::GetElementPtr(gep) => (
+                &gep.address,
+                gep.indices.iter().map(|idx| constant_index(idx)).collect(),
+            ),
+            global => (global, vec![Some(0)]),
+        },
+        Operand::LocalOperand { name, .. } => {
+            let gep = function
+                .basic_blocks
+                .iter()
+                .flat_map(|bb| bb.instrs.iter())
+                .find_map(|inst| match inst {
+                    Instruction::GetElementPtr(gep) if &gep.dest == name => Some(gep),
+                    _ => None,
+                })?;
+            let global = match &gep.address {
+                Operand::ConstantOperand(cref) => cref.as_ref(),
+                _ => return None,
+            };
+            let indices = gep
+                .indices
+                .iter()
+                .map(|idx| match idx {
+                    Operand::ConstantOperand(cref) => constant_index(cref),
+                    _ => None,
+                })
+                .collect();
+            (global, indices)
+        }
+        Operand::MetadataOperand => return None,
+    };
+    let global = match global {
+        Constant::GlobalReference {
+            name: Name::Name(name),
+            ..
+        } => name.as_str(),
+        _ => return None,
+    };
+    // the first index steps over the global itself, so must be 0 to stay
+    // within the global
+    match indices.split_first() {
+        Some((Some(0), rest)) => Some((global, rest.to_vec())),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "llvm-15-or-greater")]
+fn slot_of_address<'m>(
+    _function: &'m Function,
+    _address: &'m Operand,
+) -> Option<(&'m str, Vec<Option<u64>>)> {
+    None
+}
+
+#[cfg(feature = "llvm-14-or-lower")]
+fn constant_index(idx: &Constant) -> Option<u64> {
+    match idx {
+        Constant::Int { value, .. } => Some(*value),
+        _ => None,
+    }
+}
+
+/// Does the given position within a global's initializer match the given
+/// pattern (as returned by `slot_of_address()`)? The position matches if it
+/// is within the part of the initializer the pattern refers to.
+fn matches(path: &[u64], pattern: &[Option<u64>]) -> bool {
+    path.len() >= pattern.len()
+        && path
+            .iter()
+            .zip(pattern)
+            .all(|(idx, pat)| pat.is_none() || *pat == Some(*idx))
+}
@@ -1,3 +1,4 @@
+use crate::call_graph::{functions_with_names, FunctionName};
 use llvm_ir::{Module, TypeRef};
 use std::collections::{HashMap, HashSet};
 
@@ -7,17 +8,15 @@ use std::collections::{HashMap, HashSet};
 /// To construct a `FunctionsByType`, use [`ModuleAnalysis`](struct.ModuleAnalysis.html)
 /// or [`CrossModuleAnalysis`](struct.CrossModuleAnalysis.html).
 pub struct FunctionsByType<'m> {
-    map: HashMap<TypeRef, HashSet<&'m str>>,
+    map: HashMap<TypeRef, HashSet<FunctionName<'m>>>,
 }
 
 impl<'m> FunctionsByType<'m> {
     pub(crate) fn new(modules: impl IntoIterator<Item = &'m Module>) -> Self {
-        let mut map: HashMap<TypeRef, HashSet<&'m str>> = HashMap::new();
+        let mut map: HashMap<TypeRef, HashSet<FunctionName<'m>>> = HashMap::new();
         for module in modules {
-            for func in &module.functions {
-                map.entry(module.type_of(func))
-                    .or_default()
-                    .insert(&func.name);
+            for (func, name) in functions_with_names(module) {
+                map.entry(module.type_of(func)).or_default().insert(name);
             }
         }
         Self { map }
@@ -25,11 +24,13 @@ impl<'m> FunctionsByType<'m> {
 
     /// Iterate over all of the functions in the analyzed `Module`(s) that have
     /// the specified type
-    pub fn functions_with_type<'s>(&'s self, ty: &TypeRef) -> impl Iterator<Item = &'m str> + 's {
+    pub fn functions_with_type<'s>(
+        &'s self,
+        ty: &TypeRef,
+    ) -> impl Iterator<Item = FunctionName<'m>> + 's {
         self.map
             .get(ty)
             .into_iter()
-            .map(|hs| hs.iter().copied())
-            .flatten()
+            .flat_map(|hs| hs.iter().copied())
     }
 }
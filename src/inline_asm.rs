@@ -0,0 +1,289 @@
+use crate::call_graph::{functions_with_names, FunctionName};
+use llvm_ir::{Instruction, Module, Name, Operand, Terminator, TypeRef};
+
+/// An inventory of all the inline assembly in the analyzed `Module`(s): both
+/// calls to inline assembly within functions, and module-level assembly.
+///
+/// Note that `llvm-ir` does not currently expose the template string or the
+/// constraint string of inline assembly called from within a function, only
+/// its type. Constraint strings obtained by other means can be parsed with
+/// [`parse_asm_constraints()`](fn.parse_asm_constraints.html).
+///
+/// To construct an `InlineAsmInventory`, use [`ModuleAnalysis`](struct.ModuleAnalysis.html)
+/// or [`CrossModuleAnalysis`](struct.CrossModuleAnalysis.html).
+pub struct InlineAsmInventory<'m> {
+    /// All calls to inline assembly
+    call_sites: Vec<InlineAsmCallSite<'m>>,
+    /// Module-level assembly, for each module which has any
+    module_asm: Vec<ModuleAsm<'m>>,
+}
+
+/// A call to inline assembly
+#[derive(Clone, PartialEq, Debug)]
+pub struct InlineAsmCallSite<'m> {
+    /// Name of the function containing the call
+    pub caller: FunctionName<'m>,
+    /// Name of the basic block containing the call
+    pub block: &'m Name,
+    /// Index of the call within the basic block. If the call is the block's
+    /// terminator (i.e., a `CallBr` or `Invoke`), this is equal to the number
+    /// of (non-terminator) instructions in the block.
+    pub index: usize,
+    /// The type of the inline assembly (a function type)
+    pub ty: TypeRef,
+    /// The operands passed to the inline assembly
+    pub arguments: Vec<&'m Operand>,
+    /// The result of the call, if any
+    pub dest: Option<&'m Name>,
+    /// Is this a `CallBr` (i.e., `asm goto`)?
+    pub is_callbr: bool,
+}
+
+/// The module-level assembly for a single `Module`
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ModuleAsm<'m> {
+    /// Name of the `Module`
+    pub module: &'m str,
+    /// The full module-level assembly
+    pub assembly: &'m str,
+}
+
+impl<'m> ModuleAsm<'m> {
+    /// Iterate over the nonempty lines of the module-level assembly, with
+    /// surrounding whitespace removed
+    pub fn lines(&self) -> impl Iterator<Item = &'m str> {
+        self.assembly
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+    }
+}
+
+impl<'m> InlineAsmInventory<'m> {
+    pub(crate) fn new(modules: impl IntoIterator<Item = &'m Module>) -> Self {
+        let mut call_sites = vec![];
+        let mut module_asm = vec![];
+        for module in modules {
+            if !module.inline_assembly.trim().is_empty() {
+                module_asm.push(ModuleAsm {
+                    module: &module.name,
+                    assembly: &module.inline_assembly,
+                });
+            }
+            for (f, name) in functions_with_names(module) {
+                for bb in &f.basic_blocks {
+                    for (index, inst) in bb.instrs.iter().enumerate() {
+                        if let Instruction::Call(call) = inst {
+                            if let Some(asm) = call.function.as_ref().left() {
+                                call_sites.push(InlineAsmCallSite {
+                                    caller: name,
+                                    block: &bb.name,
+                                    index,
+                                    ty: asm.ty.clone(),
+                                    arguments: call.arguments.iter().map(|(op, _)| op).collect(),
+                                    dest: call.dest.as_ref(),
+                                    is_callbr: false,
+                                });
+                            }
+                        }
+                    }
+                    let term_asm = match &bb.term {
+                        Terminator::Invoke(invoke) => invoke
+                            .function
+                            .as_ref()
+                            .left()
+                            .map(|asm| (asm, &invoke.arguments, &invoke.result, false)),
+                        Terminator::CallBr(callbr) => callbr
+                            .function
+                            .as_ref()
+                            .left()
+                            .map(|asm| (asm, &callbr.arguments, &callbr.result, true)),
+                        _ => None,
+                    };
+                    if let Some((asm, arguments, result, is_callbr)) = term_asm {
+                        call_sites.push(InlineAsmCallSite {
+                            caller: name,
+                            block: &bb.name,
+                            index: bb.instrs.len(),
+                            ty: asm.ty.clone(),
+                            arguments: arguments.iter().map(|(op, _)| op).collect(),
+                            dest: Some(result),
+                            is_callbr,
+                        });
+                    }
+                }
+            }
+        }
+        Self {
+            call_sites,
+            module_asm,
+        }
+    }
+
+    /// Iterate over all of the calls to inline assembly in the analyzed
+    /// `Module`(s).
+    pub fn call_sites<'s>(&'s self) -> impl Iterator<Item = &'s InlineAsmCallSite<'m>> + 's {
+        self.call_sites.iter()
+    }
+
+    /// Iterate over the calls to inline assembly in the function with the
+    /// given name.
+    pub fn call_sites_in<'s>(
+        &'s self,
+        func_name: impl Into<FunctionName<'s>>,
+    ) -> impl Iterator<Item = &'s InlineAsmCallSite<'m>> + 's {
+        let func_name = func_name.into();
+        let sites: Vec<&'s InlineAsmCallSite<'m>> = self
+            .call_sites
+            .iter()
+            .filter(|site| site.caller == func_name)
+            .collect();
+        sites.into_iter()
+    }
+
+    /// Iterate over the names of functions which contain calls to inline
+    /// assembly. Each function is only reported once.
+    pub fn functions_with_asm<'s>(&'s self) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        let mut funcs: Vec<FunctionName<'m>> =
+            self.call_sites.iter().map(|site| site.caller).collect();
+        funcs.sort_unstable();
+        funcs.dedup();
+        funcs.into_iter()
+    }
+
+    /// Iterate over the module-level assembly in the analyzed `Module`(s).
+    /// Modules with no module-level assembly are not included.
+    pub fn module_asm<'s>(&'s self) -> impl Iterator<Item = &'s ModuleAsm<'m>> + 's {
+        self.module_asm.iter()
+    }
+}
+
+/// A single constraint from an inline assembly constraint string, as described
+/// in the [LLVM LangRef](https://llvm.org/docs/LangRef.html#inline-asm-constraint-string)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AsmConstraint {
+    /// Whether this is an output, input, or clobber constraint
+    pub kind: AsmConstraintKind,
+    /// For outputs, whether the output is early-clobber (`&`)
+    pub early_clobber: bool,
+    /// Whether the operand is indirect (`*`), i.e., passed via pointer
+    pub indirect: bool,
+    /// The constraint codes (e.g., `"r"`, `"m"`, `"{eax}"`, or `"0"` for a
+    /// tied operand). Multiple codes indicate that any of them may be used.
+    /// For clobbers, this is the clobbered register or `"memory"`, with the
+    /// braces removed.
+    pub codes: Vec<String>,
+}
+
+/// The kind of an [`AsmConstraint`](struct.AsmConstraint.html)
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum AsmConstraintKind {
+    /// An output (`=`)
+    Output,
+    /// An input
+    Input,
+    /// A clobber (`~`)
+    Clobber,
+}
+
+/// Parse an LLVM inline assembly constraint string, such as
+/// `"=r,0,r,~{cc},~{memory}"`, into its individual constraints.
+pub fn parse_asm_constraints(constraints: &str) -> Vec<AsmConstraint> {
+    split_constraints(constraints)
+        .into_iter()
+        .filter(|c| !c.is_empty())
+        .map(parse_asm_constraint)
+        .collect()
+}
+
+/// Split a constraint string on the commas which are not inside braces
+fn split_constraints(constraints: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in constraints.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(constraints[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(constraints[start..].trim());
+    parts
+}
+
+fn parse_asm_constraint(constraint: &str) -> AsmConstraint {
+    if let Some(clobber) = constraint.strip_prefix('~') {
+        return AsmConstraint {
+            kind: AsmConstraintKind::Clobber,
+            early_clobber: false,
+            indirect: false,
+            codes: vec![clobber.trim_matches(|c| c == '{' || c == '}').to_owned()],
+        };
+    }
+    let mut rest = constraint;
+    let kind = match rest.strip_prefix('=') {
+        Some(r) => {
+            rest = r;
+            AsmConstraintKind::Output
+        }
+        None => AsmConstraintKind::Input,
+    };
+    let early_clobber = match rest.strip_prefix('&') {
+        Some(r) => {
+            rest = r;
+            true
+        }
+        None => false,
+    };
+    let indirect = match rest.strip_prefix('*') {
+        Some(r) => {
+            rest = r;
+            true
+        }
+        None => false,
+    };
+    AsmConstraint {
+        kind,
+        early_clobber,
+        indirect,
+        codes: split_codes(rest),
+    }
+}
+
+/// Split the codes of a single constraint, e.g. `"{eax}r"` into `["{eax}", "r"]`
+/// or `"imr"` into `["i", "m", "r"]`. Alternatives separated by `|` are also
+/// split.
+fn split_codes(codes: &str) -> Vec<String> {
+    let mut result = vec![];
+    let mut chars = codes.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '|' => {}
+            '{' => {
+                let end = codes[i..].find('}').map_or(codes.len(), |j| i + j + 1);
+                result.push(codes[i..end].to_owned());
+                while chars.peek().is_some_and(|&(j, _)| j < end) {
+                    chars.next();
+                }
+            }
+            '0'..='9' => {
+                let mut end = i + 1;
+                while let Some(&(j, d)) = chars.peek() {
+                    if !d.is_ascii_digit() {
+                        break;
+                    }
+                    end = j + 1;
+                    chars.next();
+                }
+                result.push(codes[i..end].to_owned());
+            }
+            _ => result.push(c.to_string()),
+        }
+    }
+    result
+}
@@ -4,17 +4,118 @@
 //! For a more thorough introduction to the crate and how to get started,
 //! see the [crate's README](https://github.com/cdisselkoen/llvm-ir-analysis/blob/main/README.md).
 
+mod abstract_interpretation;
+mod alias;
+mod block_costs;
+mod branch_conditions;
 mod call_graph;
+mod complexity;
+mod condition_dependencies;
+mod constant_propagation;
 mod control_dep_graph;
 mod control_flow_graph;
+mod data_dep_graph;
+mod dead_functions;
+mod def_use;
+#[cfg(feature = "demangle")]
+mod demangle;
+mod dominator_order;
 mod dominator_tree;
+mod error;
+mod exception_propagation;
+mod expression;
+mod function_ptr_tables;
 mod functions_by_type;
+mod inline_asm;
+mod liveness;
+mod loops;
+mod memory_ssa;
+mod mod_ref;
+mod noreturn;
+mod null_checks;
+mod operands;
+mod personality;
+mod phi_webs;
+mod purity;
+mod reachability;
+mod reaching_defs;
+mod regions;
+#[cfg(feature = "serde")]
+mod serialize;
+mod setjmp;
+mod slicing;
+mod taint;
+mod value_numbering;
+mod value_ranges;
+mod varargs;
+mod very_busy_expressions;
+mod weak_topological_order;
+mod xref;
 
-pub use crate::call_graph::CallGraph;
-pub use crate::control_dep_graph::ControlDependenceGraph;
-pub use crate::control_flow_graph::{CFGNode, ControlFlowGraph};
-pub use crate::dominator_tree::{DominatorTree, PostDominatorTree};
+pub use crate::abstract_interpretation::{
+    AbstractDomain, AbstractInterpretation, AbstractInterpreter, AbstractSemantics,
+};
+use crate::alias::UnderlyingObjectAliasAnalysis;
+pub use crate::alias::{AliasAnalysis, TypeBasedAliasAnalysis};
+pub use crate::block_costs::{BlockCosts, CostModel};
+pub use crate::branch_conditions::{
+    BranchCondition, BranchConditions, BranchOutcome, ControlCondition, ICmpCondition,
+};
+use crate::call_graph::{function_by_name, functions_with_names, is_declared};
+pub use crate::call_graph::{
+    CallEdgeKind, CallGraph, CallGraphCondensation, CallSite, CallSiteKind, CallbackRegistration,
+    FunctionName,
+};
+pub use crate::complexity::{
+    CFGMetrics, ComplexityMetric, ComplexityReport, ComplexityThresholds, FunctionComplexity,
+    ThresholdViolation,
+};
+pub use crate::condition_dependencies::{BranchDependencies, ConditionDependencies};
+pub use crate::constant_propagation::ConstantPropagation;
+pub use crate::control_dep_graph::{ControlDependenceGraph, ControlDependenceRegion};
+pub use crate::control_flow_graph::{
+    CFGCondensation, CFGEdgeKind, CFGNode, ControlFlowGraph, ExitKind,
+};
+pub use crate::data_dep_graph::{DataDependenceGraph, DataDependenceKind};
+pub use crate::dead_functions::DeadFunctionAnalysis;
+pub use crate::def_use::{DefUseChains, Definition, InstructionLocation};
+#[cfg(feature = "demangle")]
+pub use crate::demangle::demangle;
+pub use crate::dominator_order::DominatorOrderBlock;
+pub use crate::dominator_tree::{DominatorAlgorithm, DominatorTree, PostDominatorTree};
+pub use crate::error::AnalysisError;
+pub use crate::exception_propagation::{ExceptionNode, ExceptionPropagationGraph, UnwindSite};
+pub use crate::expression::Expression;
+pub use crate::function_ptr_tables::{FunctionPtrTableEntry, FunctionPtrTables};
 pub use crate::functions_by_type::FunctionsByType;
+pub use crate::inline_asm::{
+    parse_asm_constraints, AsmConstraint, AsmConstraintKind, InlineAsmCallSite, InlineAsmInventory,
+    ModuleAsm,
+};
+pub use crate::liveness::Liveness;
+pub use crate::loops::{Loop, LoopAnalysis, LoopEffects, TripCount};
+pub use crate::memory_ssa::{MemoryDefinition, MemorySSA};
+pub use crate::mod_ref::{ModRefAnalysis, ModRefSet};
+pub use crate::noreturn::NoReturnAnalysis;
+pub use crate::null_checks::{Dereference, DereferenceKind, NullCheck, NullChecks};
+pub use crate::personality::{EHScheme, FunctionPersonality, PersonalityAnalysis};
+pub use crate::phi_webs::PhiWebs;
+pub use crate::purity::{Purity, PurityAnalysis};
+pub use crate::reachability::ReachabilityIndex;
+pub use crate::reaching_defs::{ReachingDefinition, ReachingDefinitions, Variable};
+pub use crate::regions::{Region, RegionInfo};
+pub use crate::setjmp::{JmpCallSite, SetjmpAnalysis};
+pub use crate::slicing::{Slice, SliceCriterion};
+use crate::slicing::{SliceDirection, Slicer};
+pub use crate::taint::{FunctionMatcher, TaintAnalysis, TaintConfig, TaintFlow, TaintStep};
+pub use crate::value_numbering::ValueNumbering;
+pub use crate::value_ranges::{IntervalSemantics, IntervalState, ValueRange, ValueRanges};
+pub use crate::varargs::{
+    VaArgSite, VaCopy, VaListOp, VarargsAnalysis, VariadicCallSite, VariadicFunctionInfo,
+};
+pub use crate::very_busy_expressions::VeryBusyExpressions;
+pub use crate::weak_topological_order::{WTOComponent, WeakTopologicalOrder};
+pub use crate::xref::{XRef, XRefIndex};
 use llvm_ir::{Function, Module};
 use log::debug;
 use std::cell::{Ref, RefCell};
@@ -24,6 +125,10 @@ use std::collections::HashMap;
 // have to worry about matching versions.
 pub use llvm_ir;
 
+// Likewise re-export petgraph, whose types appear in our API (e.g.,
+// `CallGraph::inner()`).
+pub use petgraph;
+
 /// Computes (and caches the results of) various analyses on a given `Module`
 pub struct ModuleAnalysis<'m> {
     /// Reference to the `llvm-ir` `Module`
@@ -33,8 +138,35 @@ pub struct ModuleAnalysis<'m> {
     /// `FunctionsByType`, which allows you to iterate over the module's
     /// functions by type
     functions_by_type: SimpleCache<FunctionsByType<'m>>,
-    /// Map from function name to the `FunctionAnalysis` for that function
-    fn_analyses: HashMap<&'m str, FunctionAnalysis<'m>>,
+    /// Function pointers stored in the module's global variables
+    function_ptr_tables: SimpleCache<FunctionPtrTables<'m>>,
+    /// `SetjmpAnalysis` for the module
+    setjmp_analysis: SimpleCache<SetjmpAnalysis<'m>>,
+    /// `VarargsAnalysis` for the module
+    varargs_analysis: SimpleCache<VarargsAnalysis<'m>>,
+    /// `InlineAsmInventory` for the module
+    inline_asm_inventory: SimpleCache<InlineAsmInventory<'m>>,
+    /// Cross-reference index for the module
+    xref_index: SimpleCache<XRefIndex<'m>>,
+    /// `ExceptionPropagationGraph` for the module
+    exception_propagation: SimpleCache<ExceptionPropagationGraph<'m>>,
+    /// `PersonalityAnalysis` for the module
+    personality_analysis: SimpleCache<PersonalityAnalysis<'m>>,
+    /// `ComplexityReport` for the module
+    complexity_report: SimpleCache<ComplexityReport<'m>>,
+    /// `DeadFunctionAnalysis` for the module, with the default entry points
+    dead_function_analysis: SimpleCache<DeadFunctionAnalysis<'m>>,
+    /// `ModRefAnalysis` for the module
+    mod_ref_analysis: SimpleCache<ModRefAnalysis<'m>>,
+    /// `PurityAnalysis` for the module
+    purity_analysis: SimpleCache<PurityAnalysis<'m>>,
+    /// `NoReturnAnalysis` for the module
+    noreturn_analysis: SimpleCache<NoReturnAnalysis<'m>>,
+    /// `FunctionAnalysis` for each function defined in the module, in order
+    fn_analyses: Vec<FunctionAnalysis<'m>>,
+    /// Map from function name to the index of the `FunctionAnalysis` for that
+    /// function in `fn_analyses`
+    fn_indices: HashMap<FunctionName<'m>, usize>,
 }
 
 impl<'m> ModuleAnalysis<'m> {
@@ -47,10 +179,26 @@ impl<'m> ModuleAnalysis<'m> {
             module,
             call_graph: SimpleCache::new(),
             functions_by_type: SimpleCache::new(),
+            function_ptr_tables: SimpleCache::new(),
+            setjmp_analysis: SimpleCache::new(),
+            varargs_analysis: SimpleCache::new(),
+            inline_asm_inventory: SimpleCache::new(),
+            xref_index: SimpleCache::new(),
+            exception_propagation: SimpleCache::new(),
+            personality_analysis: SimpleCache::new(),
+            complexity_report: SimpleCache::new(),
+            dead_function_analysis: SimpleCache::new(),
+            mod_ref_analysis: SimpleCache::new(),
+            purity_analysis: SimpleCache::new(),
+            noreturn_analysis: SimpleCache::new(),
             fn_analyses: module
                 .functions
                 .iter()
-                .map(|f| (f.name.as_str(), FunctionAnalysis::new(f)))
+                .map(|f| FunctionAnalysis::in_module(f, module))
+                .collect(),
+            fn_indices: functions_with_names(module)
+                .enumerate()
+                .map(|(idx, (_, name))| (name, idx))
                 .collect(),
         }
     }
@@ -62,30 +210,198 @@ impl<'m> ModuleAnalysis<'m> {
     }
 
     /// Get the `CallGraph` for the `Module`.
-    pub fn call_graph(&self) -> Ref<CallGraph<'m>> {
+    pub fn call_graph(&self) -> Ref<'_, CallGraph<'m>> {
         self.call_graph.get_or_insert_with(|| {
             let functions_by_type = self.functions_by_type();
+            let function_ptr_tables = self.function_ptr_tables();
             debug!("computing single-module call graph");
-            CallGraph::new(std::iter::once(self.module), &functions_by_type)
+            CallGraph::new(
+                std::iter::once(self.module),
+                &functions_by_type,
+                &function_ptr_tables,
+            )
         })
     }
 
     /// Get the `FunctionsByType` for the `Module`.
-    pub fn functions_by_type(&self) -> Ref<FunctionsByType<'m>> {
+    pub fn functions_by_type(&self) -> Ref<'_, FunctionsByType<'m>> {
         self.functions_by_type.get_or_insert_with(|| {
             debug!("computing single-module functions-by-type");
             FunctionsByType::new(std::iter::once(self.module))
         })
     }
 
+    /// Get the `FunctionPtrTables` for the `Module`.
+    pub fn function_ptr_tables(&self) -> Ref<'_, FunctionPtrTables<'m>> {
+        self.function_ptr_tables.get_or_insert_with(|| {
+            debug!("computing single-module function pointer tables");
+            FunctionPtrTables::new(std::iter::once(self.module))
+        })
+    }
+
+    /// Get the `SetjmpAnalysis` for the `Module`.
+    pub fn setjmp_analysis(&self) -> Ref<'_, SetjmpAnalysis<'m>> {
+        self.setjmp_analysis.get_or_insert_with(|| {
+            let call_graph = self.call_graph();
+            debug!("computing single-module setjmp analysis");
+            SetjmpAnalysis::new(std::iter::once(self.module), &call_graph)
+        })
+    }
+
+    /// Get the `VarargsAnalysis` for the `Module`.
+    pub fn varargs_analysis(&self) -> Ref<'_, VarargsAnalysis<'m>> {
+        self.varargs_analysis.get_or_insert_with(|| {
+            debug!("computing single-module varargs analysis");
+            VarargsAnalysis::new(std::iter::once(self.module))
+        })
+    }
+
+    /// Get the `InlineAsmInventory` for the `Module`.
+    pub fn inline_asm_inventory(&self) -> Ref<'_, InlineAsmInventory<'m>> {
+        self.inline_asm_inventory.get_or_insert_with(|| {
+            debug!("computing single-module inline asm inventory");
+            InlineAsmInventory::new(std::iter::once(self.module))
+        })
+    }
+
+    /// Get the `XRefIndex` for the `Module`.
+    pub fn xref_index(&self) -> Ref<'_, XRefIndex<'m>> {
+        self.xref_index.get_or_insert_with(|| {
+            debug!("computing single-module xref index");
+            XRefIndex::new(std::iter::once(self.module))
+        })
+    }
+
+    /// Get the `ExceptionPropagationGraph` for the `Module`.
+    pub fn exception_propagation(&self) -> Ref<'_, ExceptionPropagationGraph<'m>> {
+        self.exception_propagation.get_or_insert_with(|| {
+            let call_graph = self.call_graph();
+            debug!("computing single-module exception propagation graph");
+            ExceptionPropagationGraph::new(std::iter::once(self.module), &call_graph)
+        })
+    }
+
+    /// Get the `PersonalityAnalysis` for the `Module`.
+    pub fn personality_analysis(&self) -> Ref<'_, PersonalityAnalysis<'m>> {
+        self.personality_analysis.get_or_insert_with(|| {
+            debug!("computing single-module personality analysis");
+            PersonalityAnalysis::new(std::iter::once(self.module))
+        })
+    }
+
+    /// Get the `ComplexityReport` for the `Module`.
+    pub fn complexity_report(&self) -> Ref<'_, ComplexityReport<'m>> {
+        self.complexity_report.get_or_insert_with(|| {
+            let call_graph = self.call_graph();
+            debug!("computing single-module complexity report");
+            ComplexityReport::new(
+                self.fn_indices
+                    .iter()
+                    .map(|(&name, &idx)| (name, &self.fn_analyses[idx])),
+                &call_graph,
+            )
+        })
+    }
+
+    /// Get the `DeadFunctionAnalysis` for the `Module`, with the default entry
+    /// points (see [`DeadFunctionAnalysis`](struct.DeadFunctionAnalysis.html)).
+    pub fn dead_function_analysis(&self) -> Ref<'_, DeadFunctionAnalysis<'m>> {
+        self.dead_function_analysis.get_or_insert_with(|| {
+            let call_graph = self.call_graph();
+            let xref_index = self.xref_index();
+            debug!("computing single-module dead function analysis");
+            DeadFunctionAnalysis::new(std::iter::once(self.module), &call_graph, &xref_index, None)
+        })
+    }
+
+    /// Compute a `DeadFunctionAnalysis` for the `Module` using the given entry
+    /// points, rather than the default ones.
+    ///
+    /// Unlike [`dead_function_analysis()`](#method.dead_function_analysis),
+    /// the result of this method is not cached.
+    ///
+    /// Panics if any of the entry points is not defined in the `Module`.
+    pub fn dead_function_analysis_with_entry_points(
+        &self,
+        entry_points: &[FunctionName],
+    ) -> DeadFunctionAnalysis<'m> {
+        let call_graph = self.call_graph();
+        let xref_index = self.xref_index();
+        DeadFunctionAnalysis::new(
+            std::iter::once(self.module),
+            &call_graph,
+            &xref_index,
+            Some(entry_points),
+        )
+    }
+
+    /// Get the `ModRefAnalysis` for the `Module`.
+    pub fn mod_ref_analysis(&self) -> Ref<'_, ModRefAnalysis<'m>> {
+        self.mod_ref_analysis.get_or_insert_with(|| {
+            let call_graph = self.call_graph();
+            debug!("computing single-module mod/ref analysis");
+            ModRefAnalysis::new(std::iter::once(self.module), &call_graph)
+        })
+    }
+
+    /// Get the `PurityAnalysis` for the `Module`.
+    pub fn purity_analysis(&self) -> Ref<'_, PurityAnalysis<'m>> {
+        self.purity_analysis.get_or_insert_with(|| {
+            let call_graph = self.call_graph();
+            debug!("computing single-module purity analysis");
+            PurityAnalysis::new(std::iter::once(self.module), &call_graph)
+        })
+    }
+
+    /// Get the `NoReturnAnalysis` for the `Module`.
+    pub fn noreturn_analysis(&self) -> Ref<'_, NoReturnAnalysis<'m>> {
+        self.noreturn_analysis.get_or_insert_with(|| {
+            let call_graph = self.call_graph();
+            debug!("computing single-module noreturn analysis");
+            NoReturnAnalysis::new(std::iter::once(self.module), &call_graph)
+        })
+    }
+
+    /// Compute a `TaintAnalysis` for the `Module`, finding the flows of data
+    /// from the sources to the sinks of the given `TaintConfig`.
+    ///
+    /// The result of this method is not cached, but the per-function analyses
+    /// it uses are.
+    pub fn taint_analysis(&self, config: &TaintConfig) -> TaintAnalysis<'m> {
+        debug!("computing single-module taint analysis");
+        TaintAnalysis::new(self, config)
+    }
+
     /// Get the `FunctionAnalysis` for the function with the given name.
     ///
-    /// Panics if no function of that name exists in the `Module` which the
-    /// `ModuleAnalysis` was created with.
-    pub fn fn_analysis<'s>(&'s self, func_name: &str) -> &'s FunctionAnalysis<'m> {
-        self.fn_analyses
-            .get(func_name)
-            .unwrap_or_else(|| panic!("Function named {:?} not found in the Module", func_name))
+    /// Panics if no function of that name is defined in the `Module` which the
+    /// `ModuleAnalysis` was created with. See
+    /// [`try_fn_analysis()`](#method.try_fn_analysis) for a non-panicking
+    /// version.
+    pub fn fn_analysis<'s, 'n>(
+        &'s self,
+        func_name: impl Into<FunctionName<'n>>,
+    ) -> &'s FunctionAnalysis<'m> {
+        self.try_fn_analysis(func_name)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get the `FunctionAnalysis` for the function with the given name, or an
+    /// error if no function of that name is defined in the `Module` which the
+    /// `ModuleAnalysis` was created with: `FunctionHasNoBody` if the function
+    /// is only declared, or `FunctionNotFound` if it doesn't exist at all.
+    pub fn try_fn_analysis<'s, 'n>(
+        &'s self,
+        func_name: impl Into<FunctionName<'n>>,
+    ) -> Result<&'s FunctionAnalysis<'m>, AnalysisError> {
+        let func_name = func_name.into();
+        match self.fn_indices.get(&func_name) {
+            Some(&idx) => Ok(&self.fn_analyses[idx]),
+            None if is_declared(self.module, func_name) => {
+                Err(AnalysisError::FunctionHasNoBody(func_name.to_string()))
+            }
+            None => Err(AnalysisError::FunctionNotFound(func_name.to_string())),
+        }
     }
 }
 
@@ -99,6 +415,30 @@ pub struct CrossModuleAnalysis<'m> {
     call_graph: SimpleCache<CallGraph<'m>>,
     /// `FunctionsByType`, which allows you to iterate over functions by type
     functions_by_type: SimpleCache<FunctionsByType<'m>>,
+    /// Function pointers stored in global variables in all the modules
+    function_ptr_tables: SimpleCache<FunctionPtrTables<'m>>,
+    /// `SetjmpAnalysis` for the `Module`(s)
+    setjmp_analysis: SimpleCache<SetjmpAnalysis<'m>>,
+    /// `VarargsAnalysis` for the `Module`(s)
+    varargs_analysis: SimpleCache<VarargsAnalysis<'m>>,
+    /// `InlineAsmInventory` for the `Module`(s)
+    inline_asm_inventory: SimpleCache<InlineAsmInventory<'m>>,
+    /// Cross-reference index for all the modules
+    xref_index: SimpleCache<XRefIndex<'m>>,
+    /// `ExceptionPropagationGraph` for all the modules
+    exception_propagation: SimpleCache<ExceptionPropagationGraph<'m>>,
+    /// `PersonalityAnalysis` for all the modules
+    personality_analysis: SimpleCache<PersonalityAnalysis<'m>>,
+    /// `ComplexityReport` for all the modules
+    complexity_report: SimpleCache<ComplexityReport<'m>>,
+    /// `DeadFunctionAnalysis` for all the modules, with the default entry points
+    dead_function_analysis: SimpleCache<DeadFunctionAnalysis<'m>>,
+    /// `ModRefAnalysis` for all the modules
+    mod_ref_analysis: SimpleCache<ModRefAnalysis<'m>>,
+    /// `PurityAnalysis` for all the modules
+    purity_analysis: SimpleCache<PurityAnalysis<'m>>,
+    /// `NoReturnAnalysis` for all the modules
+    noreturn_analysis: SimpleCache<NoReturnAnalysis<'m>>,
     /// Map from module name to the `ModuleAnalysis` for that module
     module_analyses: HashMap<&'m str, ModuleAnalysis<'m>>,
 }
@@ -119,6 +459,18 @@ impl<'m> CrossModuleAnalysis<'m> {
             modules,
             call_graph: SimpleCache::new(),
             functions_by_type: SimpleCache::new(),
+            function_ptr_tables: SimpleCache::new(),
+            setjmp_analysis: SimpleCache::new(),
+            varargs_analysis: SimpleCache::new(),
+            inline_asm_inventory: SimpleCache::new(),
+            xref_index: SimpleCache::new(),
+            exception_propagation: SimpleCache::new(),
+            personality_analysis: SimpleCache::new(),
+            complexity_report: SimpleCache::new(),
+            dead_function_analysis: SimpleCache::new(),
+            mod_ref_analysis: SimpleCache::new(),
+            purity_analysis: SimpleCache::new(),
+            noreturn_analysis: SimpleCache::new(),
             module_analyses,
         }
     }
@@ -130,49 +482,235 @@ impl<'m> CrossModuleAnalysis<'m> {
 
     /// Iterate over all the `Function`s in the analyzed `Module`(s).
     pub fn functions<'s>(&'s self) -> impl Iterator<Item = &'m Function> + 's {
-        self.modules().map(|m| m.functions.iter()).flatten()
+        self.modules().flat_map(|m| m.functions.iter())
     }
 
     /// Get the full `CallGraph` for the `Module`(s).
     ///
     /// This will include both cross-module and within-module calls.
-    pub fn call_graph(&self) -> Ref<CallGraph<'m>> {
+    pub fn call_graph(&self) -> Ref<'_, CallGraph<'m>> {
         self.call_graph.get_or_insert_with(|| {
             let functions_by_type = self.functions_by_type();
+            let function_ptr_tables = self.function_ptr_tables();
             debug!("computing multi-module call graph");
-            CallGraph::new(self.modules(), &functions_by_type)
+            CallGraph::new(self.modules(), &functions_by_type, &function_ptr_tables)
         })
     }
 
+    /// Get the `CallGraph` for the `Module`(s), restricted to the functions
+    /// defined in the `Module`s with the given names (plus any functions which
+    /// are only declared, not defined, in the analyzed `Module`(s)). See
+    /// [`CallGraph::restricted_to_modules()`](struct.CallGraph.html#method.restricted_to_modules).
+    ///
+    /// Unlike [`call_graph()`](#method.call_graph), the result of this method
+    /// is not cached.
+    ///
+    /// Panics if any of the given names is not the name of one of the
+    /// `Module`(s) which the `CrossModuleAnalysis` was created with.
+    pub fn call_graph_for_modules(&self, mod_names: &[&str]) -> CallGraph<'m> {
+        for mod_name in mod_names {
+            if !self.module_analyses.contains_key(mod_name) {
+                panic!(
+                    "Module named {:?} not found in the CrossModuleAnalysis",
+                    mod_name
+                )
+            }
+        }
+        self.call_graph().restricted_to_modules(mod_names)
+    }
+
     /// Get the `FunctionsByType` for the `Module`(s).
-    pub fn functions_by_type(&self) -> Ref<FunctionsByType<'m>> {
+    pub fn functions_by_type(&self) -> Ref<'_, FunctionsByType<'m>> {
         self.functions_by_type.get_or_insert_with(|| {
             debug!("computing multi-module functions-by-type");
             FunctionsByType::new(self.modules())
         })
     }
 
+    /// Get the `FunctionPtrTables` for the `Module`(s).
+    pub fn function_ptr_tables(&self) -> Ref<'_, FunctionPtrTables<'m>> {
+        self.function_ptr_tables.get_or_insert_with(|| {
+            debug!("computing multi-module function pointer tables");
+            FunctionPtrTables::new(self.modules())
+        })
+    }
+
+    /// Get the `SetjmpAnalysis` for the `Module`(s).
+    pub fn setjmp_analysis(&self) -> Ref<'_, SetjmpAnalysis<'m>> {
+        self.setjmp_analysis.get_or_insert_with(|| {
+            let call_graph = self.call_graph();
+            debug!("computing multi-module setjmp analysis");
+            SetjmpAnalysis::new(self.modules(), &call_graph)
+        })
+    }
+
+    /// Get the `VarargsAnalysis` for the `Module`(s).
+    pub fn varargs_analysis(&self) -> Ref<'_, VarargsAnalysis<'m>> {
+        self.varargs_analysis.get_or_insert_with(|| {
+            debug!("computing multi-module varargs analysis");
+            VarargsAnalysis::new(self.modules())
+        })
+    }
+
+    /// Get the `InlineAsmInventory` for the `Module`(s).
+    pub fn inline_asm_inventory(&self) -> Ref<'_, InlineAsmInventory<'m>> {
+        self.inline_asm_inventory.get_or_insert_with(|| {
+            debug!("computing multi-module inline asm inventory");
+            InlineAsmInventory::new(self.modules())
+        })
+    }
+
+    /// Get the `XRefIndex` for the `Module`(s).
+    pub fn xref_index(&self) -> Ref<'_, XRefIndex<'m>> {
+        self.xref_index.get_or_insert_with(|| {
+            debug!("computing multi-module xref index");
+            XRefIndex::new(self.modules())
+        })
+    }
+
+    /// Get the `ExceptionPropagationGraph` for the `Module`(s).
+    pub fn exception_propagation(&self) -> Ref<'_, ExceptionPropagationGraph<'m>> {
+        self.exception_propagation.get_or_insert_with(|| {
+            let call_graph = self.call_graph();
+            debug!("computing multi-module exception propagation graph");
+            ExceptionPropagationGraph::new(self.modules(), &call_graph)
+        })
+    }
+
+    /// Get the `PersonalityAnalysis` for the `Module`(s).
+    pub fn personality_analysis(&self) -> Ref<'_, PersonalityAnalysis<'m>> {
+        self.personality_analysis.get_or_insert_with(|| {
+            debug!("computing multi-module personality analysis");
+            PersonalityAnalysis::new(self.modules())
+        })
+    }
+
+    /// Get the `ComplexityReport` for the `Module`(s).
+    pub fn complexity_report(&self) -> Ref<'_, ComplexityReport<'m>> {
+        self.complexity_report.get_or_insert_with(|| {
+            let call_graph = self.call_graph();
+            debug!("computing multi-module complexity report");
+            ComplexityReport::new(
+                self.module_analyses.values().flat_map(|analysis| {
+                    analysis
+                        .fn_indices
+                        .iter()
+                        .map(move |(&name, &idx)| (name, &analysis.fn_analyses[idx]))
+                }),
+                &call_graph,
+            )
+        })
+    }
+
+    /// Get the `DeadFunctionAnalysis` for the `Module`(s), with the default entry
+    /// points (see [`DeadFunctionAnalysis`](struct.DeadFunctionAnalysis.html)).
+    pub fn dead_function_analysis(&self) -> Ref<'_, DeadFunctionAnalysis<'m>> {
+        self.dead_function_analysis.get_or_insert_with(|| {
+            let call_graph = self.call_graph();
+            let xref_index = self.xref_index();
+            debug!("computing multi-module dead function analysis");
+            DeadFunctionAnalysis::new(self.modules(), &call_graph, &xref_index, None)
+        })
+    }
+
+    /// Compute a `DeadFunctionAnalysis` for the `Module`(s) using the given entry
+    /// points, rather than the default ones.
+    ///
+    /// Unlike [`dead_function_analysis()`](#method.dead_function_analysis),
+    /// the result of this method is not cached.
+    ///
+    /// Panics if any of the entry points is not defined in the `Module`(s).
+    pub fn dead_function_analysis_with_entry_points(
+        &self,
+        entry_points: &[FunctionName],
+    ) -> DeadFunctionAnalysis<'m> {
+        let call_graph = self.call_graph();
+        let xref_index = self.xref_index();
+        DeadFunctionAnalysis::new(self.modules(), &call_graph, &xref_index, Some(entry_points))
+    }
+
+    /// Get the `ModRefAnalysis` for the `Module`(s).
+    pub fn mod_ref_analysis(&self) -> Ref<'_, ModRefAnalysis<'m>> {
+        self.mod_ref_analysis.get_or_insert_with(|| {
+            let call_graph = self.call_graph();
+            debug!("computing multi-module mod/ref analysis");
+            ModRefAnalysis::new(self.modules(), &call_graph)
+        })
+    }
+
+    /// Get the `PurityAnalysis` for the `Module`(s).
+    pub fn purity_analysis(&self) -> Ref<'_, PurityAnalysis<'m>> {
+        self.purity_analysis.get_or_insert_with(|| {
+            let call_graph = self.call_graph();
+            debug!("computing multi-module purity analysis");
+            PurityAnalysis::new(self.modules(), &call_graph)
+        })
+    }
+
+    /// Get the `NoReturnAnalysis` for the `Module`(s).
+    pub fn noreturn_analysis(&self) -> Ref<'_, NoReturnAnalysis<'m>> {
+        self.noreturn_analysis.get_or_insert_with(|| {
+            let call_graph = self.call_graph();
+            debug!("computing multi-module noreturn analysis");
+            NoReturnAnalysis::new(self.modules(), &call_graph)
+        })
+    }
+
     /// Get the `ModuleAnalysis` for the module with the given name.
     ///
     /// Panics if no module of that name exists in the `Module`(s) which the
-    /// `CrossModuleAnalysis` was created with.
+    /// `CrossModuleAnalysis` was created with. See
+    /// [`try_module_analysis()`](#method.try_module_analysis) for a
+    /// non-panicking version.
     pub fn module_analysis<'s>(&'s self, mod_name: &str) -> &'s ModuleAnalysis<'m> {
-        self.module_analyses.get(mod_name).unwrap_or_else(|| {
-            panic!(
-                "Module named {:?} not found in the CrossModuleAnalysis",
-                mod_name
-            )
-        })
+        self.try_module_analysis(mod_name)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get the `ModuleAnalysis` for the module with the given name, or a
+    /// `ModuleNotFound` error if no module of that name exists in the
+    /// `Module`(s) which the `CrossModuleAnalysis` was created with.
+    pub fn try_module_analysis<'s>(
+        &'s self,
+        mod_name: &str,
+    ) -> Result<&'s ModuleAnalysis<'m>, AnalysisError> {
+        self.module_analyses
+            .get(mod_name)
+            .ok_or_else(|| AnalysisError::ModuleNotFound(mod_name.into()))
+    }
+
+    /// Get the `FunctionAnalysis` for the function with the given name, from
+    /// whichever of the analyzed `Module`(s) defines it; or an error if none
+    /// of them do: `FunctionHasNoBody` if the function is only declared, or
+    /// `FunctionNotFound` if it doesn't exist at all.
+    pub fn try_fn_analysis<'s, 'n>(
+        &'s self,
+        func_name: impl Into<FunctionName<'n>>,
+    ) -> Result<&'s FunctionAnalysis<'m>, AnalysisError> {
+        let func_name = func_name.into();
+        let mut error = AnalysisError::FunctionNotFound(func_name.to_string());
+        for module in &self.modules {
+            match self.module_analyses[module.name.as_str()].try_fn_analysis(func_name) {
+                Ok(fn_analysis) => return Ok(fn_analysis),
+                Err(e @ AnalysisError::FunctionHasNoBody(_)) => error = e,
+                Err(_) => {}
+            }
+        }
+        Err(error)
     }
 
     /// Get the `Function` with the given name from the analyzed `Module`(s).
     ///
     /// Returns both the `Function` and the `Module` it was found in, or `None`
     /// if no function was found with that name.
-    pub fn get_func_by_name(&self, func_name: &str) -> Option<(&'m Function, &'m Module)> {
+    pub fn get_func_by_name<'n>(
+        &self,
+        func_name: impl Into<FunctionName<'n>>,
+    ) -> Option<(&'m Function, &'m Module)> {
+        let func_name = func_name.into();
         let mut retval = None;
         for &module in &self.modules {
-            if let Some(func) = module.get_func_by_name(func_name) {
+            if let Some(func) = function_by_name(module, func_name) {
                 match retval {
                     None => retval = Some((func, module)),
                     Some((_, retmod)) => panic!("Multiple functions found with name {:?}: one in module {:?}, another in module {:?}", func_name, &retmod.name, &module.name),
@@ -187,6 +725,8 @@ impl<'m> CrossModuleAnalysis<'m> {
 pub struct FunctionAnalysis<'m> {
     /// Reference to the `llvm-ir` `Function`
     function: &'m Function,
+    /// The `Module` containing the function, if known
+    module: Option<&'m Module>,
     /// Control flow graph for the function
     control_flow_graph: SimpleCache<ControlFlowGraph<'m>>,
     /// Dominator tree for the function
@@ -195,6 +735,51 @@ pub struct FunctionAnalysis<'m> {
     postdominator_tree: SimpleCache<PostDominatorTree<'m>>,
     /// Control dependence graph for the function
     control_dep_graph: SimpleCache<ControlDependenceGraph<'m>>,
+    /// Branch conditions controlling each block in the function
+    branch_conditions: SimpleCache<BranchConditions<'m>>,
+    /// Static cost estimates for each block in the function
+    block_costs: SimpleCache<BlockCosts<'m>>,
+    /// Single-entry single-exit regions of the function
+    region_info: SimpleCache<RegionInfo<'m>>,
+    /// Natural loops of the function
+    loop_analysis: SimpleCache<LoopAnalysis<'m>>,
+    /// Def-use chains of the function's local values
+    def_use_chains: SimpleCache<DefUseChains<'m>>,
+    /// Data dependence graph for the function
+    data_dep_graph: SimpleCache<DataDependenceGraph<'m>>,
+    /// Reaching definitions of SSA values and stack slots
+    reaching_defs: SimpleCache<ReachingDefinitions<'m>>,
+    /// Liveness of the function's local values
+    liveness: SimpleCache<Liveness<'m>>,
+    /// Type-based alias analysis for the function
+    type_based_alias_analysis: SimpleCache<TypeBasedAliasAnalysis<'m>>,
+    /// MemorySSA overlay for the function
+    memory_ssa: SimpleCache<MemorySSA<'m>>,
+    /// `ValueRanges` for the function
+    value_ranges: SimpleCache<ValueRanges<'m>>,
+    /// Sparse conditional constant propagation results for the function
+    constant_propagation: SimpleCache<ConstantPropagation<'m>>,
+    /// Very busy expressions of the function
+    very_busy_expressions: SimpleCache<VeryBusyExpressions<'m>>,
+    /// Weak topological ordering of the function's blocks
+    weak_topological_order: SimpleCache<WeakTopologicalOrder<'m>>,
+    /// Phi webs of the function's local values
+    phi_webs: SimpleCache<PhiWebs<'m>>,
+    /// Dominator-based value numbering of the function's local values
+    value_numbering: SimpleCache<ValueNumbering<'m>>,
+    /// Null checks dominating the function's pointer dereferences
+    null_checks: SimpleCache<NullChecks<'m>>,
+    /// Control flow graph for the function, without the edges which provably
+    /// never execute
+    pruned_control_flow_graph: SimpleCache<ControlFlowGraph<'m>>,
+    /// Dominator tree of the pruned control flow graph
+    pruned_dominator_tree: SimpleCache<DominatorTree<'m>>,
+    /// Postdominator tree of the pruned control flow graph
+    pruned_postdominator_tree: SimpleCache<PostDominatorTree<'m>>,
+    /// Control dependence graph of the pruned control flow graph
+    pruned_control_dep_graph: SimpleCache<ControlDependenceGraph<'m>>,
+    /// Values which the function's branch conditions depend on
+    condition_dependencies: SimpleCache<ConditionDependencies<'m>>,
 }
 
 impl<'m> FunctionAnalysis<'m> {
@@ -202,26 +787,109 @@ impl<'m> FunctionAnalysis<'m> {
     ///
     /// This method itself is cheap; individual analyses will be computed lazily
     /// on demand.
+    ///
+    /// Without the function's `Module`, which globals are `GlobalAlias`es of
+    /// others isn't known. So the alias analyses assume pointers to globals
+    /// may alias anything, and `ConditionDependencies` doesn't record loads
+    /// from globals. The `FunctionAnalysis`es of a
+    /// [`ModuleAnalysis`](struct.ModuleAnalysis.html) know their `Module`.
     pub fn new(function: &'m Function) -> Self {
         Self {
             function,
+            module: None,
             control_flow_graph: SimpleCache::new(),
             dominator_tree: SimpleCache::new(),
             postdominator_tree: SimpleCache::new(),
             control_dep_graph: SimpleCache::new(),
+            branch_conditions: SimpleCache::new(),
+            block_costs: SimpleCache::new(),
+            region_info: SimpleCache::new(),
+            loop_analysis: SimpleCache::new(),
+            def_use_chains: SimpleCache::new(),
+            data_dep_graph: SimpleCache::new(),
+            reaching_defs: SimpleCache::new(),
+            liveness: SimpleCache::new(),
+            type_based_alias_analysis: SimpleCache::new(),
+            memory_ssa: SimpleCache::new(),
+            value_ranges: SimpleCache::new(),
+            constant_propagation: SimpleCache::new(),
+            very_busy_expressions: SimpleCache::new(),
+            weak_topological_order: SimpleCache::new(),
+            phi_webs: SimpleCache::new(),
+            value_numbering: SimpleCache::new(),
+            null_checks: SimpleCache::new(),
+            pruned_control_flow_graph: SimpleCache::new(),
+            pruned_dominator_tree: SimpleCache::new(),
+            pruned_postdominator_tree: SimpleCache::new(),
+            pruned_control_dep_graph: SimpleCache::new(),
+            condition_dependencies: SimpleCache::new(),
+        }
+    }
+
+    /// Create a new `FunctionAnalysis` for the given `Function`, which is
+    /// defined in the given `Module`
+    pub(crate) fn in_module(function: &'m Function, module: &'m Module) -> Self {
+        Self {
+            module: Some(module),
+            ..Self::new(function)
         }
     }
 
     /// Get the `ControlFlowGraph` for the function.
-    pub fn control_flow_graph(&self) -> Ref<ControlFlowGraph<'m>> {
-        self.control_flow_graph.get_or_insert_with(|| {
+    ///
+    /// Panics if the function has no basic blocks. See
+    /// [`try_control_flow_graph()`](#method.try_control_flow_graph) for a
+    /// non-panicking version.
+    pub fn control_flow_graph(&self) -> Ref<'_, ControlFlowGraph<'m>> {
+        self.try_control_flow_graph()
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get the `ControlFlowGraph` for the function, or a `FunctionHasNoBody`
+    /// error if the function has no basic blocks. The other analyses of the
+    /// function are built on the `ControlFlowGraph`, so if this succeeds, they
+    /// will too.
+    pub fn try_control_flow_graph(&self) -> Result<Ref<'_, ControlFlowGraph<'m>>, AnalysisError> {
+        if self.function.basic_blocks.is_empty() {
+            return Err(AnalysisError::FunctionHasNoBody(self.function.name.clone()));
+        }
+        Ok(self.control_flow_graph.get_or_insert_with(|| {
             debug!("computing control flow graph for {}", &self.function.name);
             ControlFlowGraph::new(self.function)
-        })
+        }))
+    }
+
+    /// Compute the `ControlFlowGraph` for the function, with edges to
+    /// `CFGNode::Return` for only the given kinds of exit. For instance,
+    /// `&[ExitKind::Return]` gives a CFG whose only exits are normal returns,
+    /// `&[ExitKind::Return, ExitKind::Unreachable, ExitKind::Unwind]`
+    /// treats `unreachable` as an exit too, and adding `ExitKind::InfiniteLoop`
+    /// gives every infinite loop a virtual exit.
+    ///
+    /// Unlike [`control_flow_graph()`](#method.control_flow_graph), which uses
+    /// [`ExitKind::DEFAULT`](enum.ExitKind.html#associatedconstant.DEFAULT),
+    /// the result of this method is not cached.
+    pub fn control_flow_graph_with_exits(&self, exits: &[ExitKind]) -> ControlFlowGraph<'m> {
+        ControlFlowGraph::with_exits(self.function, exits)
+    }
+
+    /// Compute the `ControlFlowGraph` for the function, with edges to
+    /// `CFGNode::Return` for the given kinds of exit (as in
+    /// [`control_flow_graph_with_exits()`](#method.control_flow_graph_with_exits)),
+    /// and with an edge from each `callbr` to every block it may possibly jump
+    /// to, not just the ones it's known to jump to. See
+    /// [`ControlFlowGraph`](struct.ControlFlowGraph.html#callbr) for details.
+    ///
+    /// The result of this method is not cached.
+    pub fn control_flow_graph_with_all_callbr_edges(
+        &self,
+        exits: &[ExitKind],
+    ) -> ControlFlowGraph<'m> {
+        ControlFlowGraph::with_options(self.function, exits, true)
     }
 
     /// Get the `DominatorTree` for the function.
-    pub fn dominator_tree(&self) -> Ref<DominatorTree<'m>> {
+    pub fn dominator_tree(&self) -> Ref<'_, DominatorTree<'m>> {
         self.dominator_tree.get_or_insert_with(|| {
             let cfg = self.control_flow_graph();
             debug!("computing dominator tree for {}", &self.function.name);
@@ -230,7 +898,7 @@ impl<'m> FunctionAnalysis<'m> {
     }
 
     /// Get the `PostDominatorTree` for the function.
-    pub fn postdominator_tree(&self) -> Ref<PostDominatorTree<'m>> {
+    pub fn postdominator_tree(&self) -> Ref<'_, PostDominatorTree<'m>> {
         self.postdominator_tree.get_or_insert_with(|| {
             let cfg = self.control_flow_graph();
             debug!("computing postdominator tree for {}", &self.function.name);
@@ -238,8 +906,20 @@ impl<'m> FunctionAnalysis<'m> {
         })
     }
 
+    /// Compute the `PostDominatorTree` for the function with respect to only
+    /// the given kinds of exit (see
+    /// [`control_flow_graph_with_exits()`](#method.control_flow_graph_with_exits)).
+    /// Blocks which can't reach any of those exits don't appear in the tree.
+    ///
+    /// Unlike [`postdominator_tree()`](#method.postdominator_tree), the result
+    /// of this method is not cached.
+    pub fn postdominator_tree_with_exits(&self, exits: &[ExitKind]) -> PostDominatorTree<'m> {
+        let cfg = self.control_flow_graph_with_exits(exits);
+        PostDominatorTree::new(&cfg)
+    }
+
     /// Get the `ControlDependenceGraph` for the function.
-    pub fn control_dependence_graph(&self) -> Ref<ControlDependenceGraph<'m>> {
+    pub fn control_dependence_graph(&self) -> Ref<'_, ControlDependenceGraph<'m>> {
         self.control_dep_graph.get_or_insert_with(|| {
             let cfg = self.control_flow_graph();
             let postdomtree = self.postdominator_tree();
@@ -250,6 +930,404 @@ impl<'m> FunctionAnalysis<'m> {
             ControlDependenceGraph::new(&cfg, &postdomtree)
         })
     }
+
+    /// Compute the `ControlDependenceGraph` for the function with respect to
+    /// only the given kinds of exit (see
+    /// [`control_flow_graph_with_exits()`](#method.control_flow_graph_with_exits)).
+    /// For instance, including
+    /// [`ExitKind::InfiniteLoop`](enum.ExitKind.html#variant.InfiniteLoop)
+    /// gives control dependencies for blocks which can't reach any other exit.
+    ///
+    /// Unlike [`control_dependence_graph()`](#method.control_dependence_graph),
+    /// the result of this method is not cached.
+    pub fn control_dependence_graph_with_exits(
+        &self,
+        exits: &[ExitKind],
+    ) -> ControlDependenceGraph<'m> {
+        let cfg = self.control_flow_graph_with_exits(exits);
+        let postdomtree = PostDominatorTree::new(&cfg);
+        ControlDependenceGraph::new(&cfg, &postdomtree)
+    }
+
+    /// Iterate over the function's blocks in dominator-tree preorder: each
+    /// block is visited before all the blocks it dominates, and the children of
+    /// each block in the dominator tree are visited in the order they appear in
+    /// the function. Unreachable blocks are not included. Each block comes with
+    /// its immediate dominator, depth in the dominator tree, CFG predecessors
+    /// and successors, and immediate control dependencies.
+    ///
+    /// This uses the cached `ControlFlowGraph`, `DominatorTree`, and
+    /// `ControlDependenceGraph`.
+    pub fn blocks_in_dominator_order(&self) -> impl Iterator<Item = DominatorOrderBlock<'m>> {
+        let cfg = self.control_flow_graph();
+        let domtree = self.dominator_tree();
+        let cdg = self.control_dependence_graph();
+        dominator_order::blocks_in_dominator_order(self.function, &cfg, &domtree, &cdg).into_iter()
+    }
+
+    /// Get the `BranchConditions` for the function.
+    pub fn branch_conditions(&self) -> Ref<'_, BranchConditions<'m>> {
+        self.branch_conditions.get_or_insert_with(|| {
+            let postdomtree = self.postdominator_tree();
+            let cdg = self.control_dependence_graph();
+            debug!("computing branch conditions for {}", &self.function.name);
+            BranchConditions::new(self.function, &postdomtree, &cdg)
+        })
+    }
+
+    /// Get the `BlockCosts` for the function, using the default `CostModel`.
+    pub fn block_costs(&self) -> Ref<'_, BlockCosts<'m>> {
+        self.block_costs.get_or_insert_with(|| {
+            let cfg = self.control_flow_graph();
+            let domtree = self.dominator_tree();
+            debug!("computing block costs for {}", &self.function.name);
+            BlockCosts::new(self.function, &cfg, &domtree, &CostModel::default())
+        })
+    }
+
+    /// Compute `BlockCosts` for the function using the given `CostModel`.
+    ///
+    /// Unlike [`block_costs()`](#method.block_costs), the result of this method
+    /// is not cached.
+    pub fn block_costs_with_model(&self, model: &CostModel) -> BlockCosts<'m> {
+        let cfg = self.control_flow_graph();
+        let domtree = self.dominator_tree();
+        BlockCosts::new(self.function, &cfg, &domtree, model)
+    }
+
+    /// Get the `CFGMetrics` for the function.
+    pub fn cfg_metrics(&self) -> CFGMetrics {
+        let cfg = self.control_flow_graph();
+        let loops = self.loop_analysis();
+        CFGMetrics::new(self.function, &cfg, &loops)
+    }
+
+    /// Get the `RegionInfo` for the function.
+    pub fn region_info(&self) -> Ref<'_, RegionInfo<'m>> {
+        self.region_info.get_or_insert_with(|| {
+            let cfg = self.control_flow_graph();
+            let domtree = self.dominator_tree();
+            let postdomtree = self.postdominator_tree();
+            debug!("computing region info for {}", &self.function.name);
+            RegionInfo::new(&cfg, &domtree, &postdomtree)
+        })
+    }
+
+    /// Get the `LoopAnalysis` for the function.
+    pub fn loop_analysis(&self) -> Ref<'_, LoopAnalysis<'m>> {
+        self.loop_analysis.get_or_insert_with(|| {
+            let cfg = self.control_flow_graph();
+            let domtree = self.dominator_tree();
+            debug!("computing loop analysis for {}", &self.function.name);
+            LoopAnalysis::new(self.function, &cfg, &domtree)
+        })
+    }
+
+    /// Get the `DefUseChains` for the function.
+    pub fn def_use_chains(&self) -> Ref<'_, DefUseChains<'m>> {
+        self.def_use_chains.get_or_insert_with(|| {
+            debug!("computing def-use chains for {}", &self.function.name);
+            DefUseChains::new(self.function)
+        })
+    }
+
+    /// Get the `DataDependenceGraph` for the function.
+    pub fn data_dependence_graph(&self) -> Ref<'_, DataDependenceGraph<'m>> {
+        self.data_dep_graph.get_or_insert_with(|| {
+            let cfg = self.control_flow_graph();
+            let def_use = self.def_use_chains();
+            debug!(
+                "computing data dependence graph for {}",
+                &self.function.name
+            );
+            let alias_analysis = UnderlyingObjectAliasAnalysis::new(self.function, self.module);
+            DataDependenceGraph::new(self.function, &cfg, &def_use, &alias_analysis)
+        })
+    }
+
+    /// Compute the `DataDependenceGraph` for the function, using the given
+    /// [`AliasAnalysis`](trait.AliasAnalysis.html) to decide which memory
+    /// accesses may depend on each other. For instance, use
+    /// [`type_based_alias_analysis()`](#method.type_based_alias_analysis) for
+    /// fewer memory dependences in code which obeys strict aliasing.
+    ///
+    /// Unlike [`data_dependence_graph()`](#method.data_dependence_graph), the
+    /// result of this method is not cached.
+    pub fn data_dependence_graph_with_alias_analysis(
+        &self,
+        alias_analysis: &dyn AliasAnalysis<'m>,
+    ) -> DataDependenceGraph<'m> {
+        let cfg = self.control_flow_graph();
+        let def_use = self.def_use_chains();
+        DataDependenceGraph::new(self.function, &cfg, &def_use, alias_analysis)
+    }
+
+    /// Get the `TypeBasedAliasAnalysis` for the function.
+    pub fn type_based_alias_analysis(&self) -> Ref<'_, TypeBasedAliasAnalysis<'m>> {
+        self.type_based_alias_analysis.get_or_insert_with(|| {
+            debug!(
+                "computing type-based alias analysis for {}",
+                &self.function.name
+            );
+            TypeBasedAliasAnalysis::new(self.function, self.module)
+        })
+    }
+
+    /// Get the `MemorySSA` for the function, using the same alias analysis as
+    /// [`data_dependence_graph()`](#method.data_dependence_graph).
+    pub fn memory_ssa(&self) -> Ref<'_, MemorySSA<'m>> {
+        self.memory_ssa.get_or_insert_with(|| {
+            let cfg = self.control_flow_graph();
+            let domtree = self.dominator_tree();
+            debug!("computing MemorySSA for {}", &self.function.name);
+            let alias_analysis = UnderlyingObjectAliasAnalysis::new(self.function, self.module);
+            MemorySSA::new(self.function, &cfg, &domtree, &alias_analysis)
+        })
+    }
+
+    /// Compute the `MemorySSA` for the function, using the given
+    /// [`AliasAnalysis`](trait.AliasAnalysis.html) to find clobbering
+    /// definitions.
+    ///
+    /// Unlike [`memory_ssa()`](#method.memory_ssa), the result of this method
+    /// is not cached.
+    pub fn memory_ssa_with_alias_analysis(
+        &self,
+        alias_analysis: &dyn AliasAnalysis<'m>,
+    ) -> MemorySSA<'m> {
+        let cfg = self.control_flow_graph();
+        let domtree = self.dominator_tree();
+        MemorySSA::new(self.function, &cfg, &domtree, alias_analysis)
+    }
+
+    /// Get the `ReachingDefinitions` for the function.
+    pub fn reaching_definitions(&self) -> Ref<'_, ReachingDefinitions<'m>> {
+        self.reaching_defs.get_or_insert_with(|| {
+            let cfg = self.control_flow_graph();
+            let def_use = self.def_use_chains();
+            debug!("computing reaching definitions for {}", &self.function.name);
+            ReachingDefinitions::new(self.function, &cfg, &def_use)
+        })
+    }
+
+    /// Get the `Liveness` for the function.
+    pub fn liveness(&self) -> Ref<'_, Liveness<'m>> {
+        self.liveness.get_or_insert_with(|| {
+            let cfg = self.control_flow_graph();
+            debug!("computing liveness for {}", &self.function.name);
+            Liveness::new(self.function, &cfg)
+        })
+    }
+
+    /// Get the `ValueRanges` for the function.
+    pub fn value_ranges(&self) -> Ref<'_, ValueRanges<'m>> {
+        self.value_ranges.get_or_insert_with(|| {
+            let cfg = self.control_flow_graph();
+            let wto = self.weak_topological_order();
+            debug!("computing value ranges for {}", &self.function.name);
+            ValueRanges::new(self.function, &cfg, &wto)
+        })
+    }
+
+    /// Compute a fixpoint of the given abstract semantics over the function,
+    /// using an [`AbstractInterpreter`](struct.AbstractInterpreter.html) with
+    /// the default settings. For example, the
+    /// [`IntervalSemantics`](struct.IntervalSemantics.html) give the same
+    /// results as [`value_ranges()`](#method.value_ranges).
+    ///
+    /// Unlike [`value_ranges()`](#method.value_ranges), the result of this
+    /// method is not cached.
+    pub fn abstract_interpretation<S: AbstractSemantics<'m>>(
+        &self,
+        semantics: S,
+    ) -> AbstractInterpretation<'m, S> {
+        let cfg = self.control_flow_graph();
+        let wto = self.weak_topological_order();
+        debug!(
+            "computing abstract interpretation for {}",
+            &self.function.name
+        );
+        AbstractInterpreter::new().run(self.function, &cfg, &wto, semantics)
+    }
+
+    /// Get the `ConstantPropagation` for the function.
+    pub fn constant_propagation(&self) -> Ref<'_, ConstantPropagation<'m>> {
+        self.constant_propagation.get_or_insert_with(|| {
+            let cfg = self.control_flow_graph();
+            let def_use = self.def_use_chains();
+            debug!("computing constant propagation for {}", &self.function.name);
+            ConstantPropagation::new(self.function, &cfg, &def_use)
+        })
+    }
+
+    /// Get the `ControlFlowGraph` for the function, without the edges which
+    /// provably never execute according to
+    /// [`constant_propagation()`](#method.constant_propagation) (see
+    /// [`ConstantPropagation::dead_edges()`](struct.ConstantPropagation.html#method.dead_edges)).
+    /// So branches whose conditions fold to constants have only the edges
+    /// they take, and blocks which never execute are unreachable.
+    pub fn pruned_control_flow_graph(&self) -> Ref<'_, ControlFlowGraph<'m>> {
+        self.pruned_control_flow_graph.get_or_insert_with(|| {
+            let cfg = self.control_flow_graph();
+            let sccp = self.constant_propagation();
+            debug!(
+                "computing pruned control flow graph for {}",
+                &self.function.name
+            );
+            cfg.without_edges(sccp.dead_edges().iter().copied())
+        })
+    }
+
+    /// Get the `DominatorTree` of the
+    /// [`pruned_control_flow_graph()`](#method.pruned_control_flow_graph).
+    pub fn pruned_dominator_tree(&self) -> Ref<'_, DominatorTree<'m>> {
+        self.pruned_dominator_tree.get_or_insert_with(|| {
+            let cfg = self.pruned_control_flow_graph();
+            debug!(
+                "computing pruned dominator tree for {}",
+                &self.function.name
+            );
+            DominatorTree::new(&cfg)
+        })
+    }
+
+    /// Get the `PostDominatorTree` of the
+    /// [`pruned_control_flow_graph()`](#method.pruned_control_flow_graph).
+    pub fn pruned_postdominator_tree(&self) -> Ref<'_, PostDominatorTree<'m>> {
+        self.pruned_postdominator_tree.get_or_insert_with(|| {
+            let cfg = self.pruned_control_flow_graph();
+            debug!(
+                "computing pruned postdominator tree for {}",
+                &self.function.name
+            );
+            PostDominatorTree::new(&cfg)
+        })
+    }
+
+    /// Get the `ControlDependenceGraph` of the
+    /// [`pruned_control_flow_graph()`](#method.pruned_control_flow_graph).
+    /// Blocks aren't control dependent on branches whose conditions are
+    /// constant.
+    pub fn pruned_control_dependence_graph(&self) -> Ref<'_, ControlDependenceGraph<'m>> {
+        self.pruned_control_dep_graph.get_or_insert_with(|| {
+            let cfg = self.pruned_control_flow_graph();
+            let postdomtree = self.pruned_postdominator_tree();
+            debug!(
+                "computing pruned control dependence graph for {}",
+                &self.function.name
+            );
+            ControlDependenceGraph::new(&cfg, &postdomtree)
+        })
+    }
+
+    /// Get the `VeryBusyExpressions` for the function.
+    pub fn very_busy_expressions(&self) -> Ref<'_, VeryBusyExpressions<'m>> {
+        self.very_busy_expressions.get_or_insert_with(|| {
+            let cfg = self.control_flow_graph();
+            debug!(
+                "computing very busy expressions for {}",
+                &self.function.name
+            );
+            VeryBusyExpressions::new(self.function, &cfg)
+        })
+    }
+
+    /// Get the `WeakTopologicalOrder` for the function.
+    pub fn weak_topological_order(&self) -> Ref<'_, WeakTopologicalOrder<'m>> {
+        self.weak_topological_order.get_or_insert_with(|| {
+            let cfg = self.control_flow_graph();
+            debug!(
+                "computing weak topological order for {}",
+                &self.function.name
+            );
+            WeakTopologicalOrder::new(&cfg)
+        })
+    }
+
+    /// Get the `PhiWebs` for the function.
+    pub fn phi_webs(&self) -> Ref<'_, PhiWebs<'m>> {
+        self.phi_webs.get_or_insert_with(|| {
+            debug!("computing phi webs for {}", &self.function.name);
+            PhiWebs::new(self.function)
+        })
+    }
+
+    /// Get the `ValueNumbering` for the function.
+    pub fn value_numbering(&self) -> Ref<'_, ValueNumbering<'m>> {
+        self.value_numbering.get_or_insert_with(|| {
+            let domtree = self.dominator_tree();
+            debug!("computing value numbering for {}", &self.function.name);
+            ValueNumbering::new(self.function, &domtree)
+        })
+    }
+
+    /// Get the `NullChecks` for the function.
+    pub fn null_checks(&self) -> Ref<'_, NullChecks<'m>> {
+        self.null_checks.get_or_insert_with(|| {
+            let cfg = self.control_flow_graph();
+            let domtree = self.dominator_tree();
+            debug!("computing null checks for {}", &self.function.name);
+            NullChecks::new(self.function, &cfg, &domtree)
+        })
+    }
+
+    /// Get the `ConditionDependencies` for the function.
+    pub fn condition_dependencies(&self) -> Ref<'_, ConditionDependencies<'m>> {
+        self.condition_dependencies.get_or_insert_with(|| {
+            let def_use = self.def_use_chains();
+            debug!(
+                "computing condition dependencies for {}",
+                &self.function.name
+            );
+            ConditionDependencies::new(self.function, self.module, &def_use)
+        })
+    }
+
+    /// Compute the backward slice of the function with respect to the given
+    /// criterion: every instruction and terminator which the criterion may
+    /// (transitively) depend on, through data dependences (see
+    /// [`data_dependence_graph()`](#method.data_dependence_graph)) or control
+    /// dependences (see
+    /// [`control_dependence_graph()`](#method.control_dependence_graph)).
+    /// An instruction is control dependent on the terminators of the blocks its
+    /// block is immediately control dependent on; a `phi` is additionally
+    /// control dependent on the terminators deciding which of its incoming
+    /// blocks executes.
+    ///
+    /// Panics if the criterion isn't an instruction or local value in this
+    /// function.
+    ///
+    /// This uses the cached `ControlDependenceGraph`, `DataDependenceGraph`,
+    /// and `DefUseChains`, but the slice itself is not cached.
+    pub fn backward_slice(&self, criterion: SliceCriterion<'_>) -> Slice<'m> {
+        self.slice(criterion, SliceDirection::Backward)
+    }
+
+    /// Compute the forward slice of the function with respect to the given
+    /// criterion: every instruction and terminator which may (transitively)
+    /// depend on the criterion. This is the inverse of
+    /// [`backward_slice()`](#method.backward_slice); see its docs for the
+    /// dependences considered.
+    ///
+    /// Panics if the criterion isn't an instruction or local value in this
+    /// function.
+    ///
+    /// This uses the cached `ControlDependenceGraph`, `DataDependenceGraph`,
+    /// and `DefUseChains`, but the slice itself is not cached.
+    pub fn forward_slice(&self, criterion: SliceCriterion<'_>) -> Slice<'m> {
+        self.slice(criterion, SliceDirection::Forward)
+    }
+
+    fn slice(&self, criterion: SliceCriterion<'_>, direction: SliceDirection) -> Slice<'m> {
+        let cdg = self.control_dependence_graph();
+        let ddg = self.data_dependence_graph();
+        let def_use = self.def_use_chains();
+        debug!(
+            "computing {:?} slice for {}",
+            direction, &self.function.name
+        );
+        Slicer::new(self.function, &cdg, &ddg, &def_use).slice(criterion, direction)
+    }
 }
 
 struct SimpleCache<T> {
@@ -266,7 +1344,7 @@ impl<T> SimpleCache<T> {
 
     /// Get the cached value, or if no value is cached, compute the value using
     /// the given closure, then cache that result and return it
-    fn get_or_insert_with(&self, f: impl FnOnce() -> T) -> Ref<T> {
+    fn get_or_insert_with(&self, f: impl FnOnce() -> T) -> Ref<'_, T> {
         // borrow mutably only if it's empty. else don't even try to borrow mutably
         let need_mutable_borrow = self.data.borrow().is_none();
         if need_mutable_borrow {
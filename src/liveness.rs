@@ -0,0 +1,205 @@
+use crate::control_flow_graph::{CFGNode, ControlFlowGraph};
+use crate::operands::{instruction_operands, terminator_operands};
+use llvm_ir::instruction::Phi;
+use llvm_ir::{BasicBlock, Function, Instruction, Name, Operand};
+use std::collections::{HashMap, HashSet};
+
+/// Liveness of the local SSA values (parameters and instruction results) of a
+/// particular function: at each point, which values may still be used later.
+///
+/// `phi`s follow the usual SSA convention. A value used by a `phi` is used at
+/// the end of the corresponding predecessor, so it is live-out of that
+/// predecessor but not (because of that use) live-in to the `phi`'s block. The
+/// `phi`s of a block all execute together on entry to the block, so their
+/// results are live-in to the block if they are used at all.
+///
+/// To construct a `Liveness`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct Liveness<'m> {
+    /// The function
+    function: &'m Function,
+    /// Values live on entry to each block
+    live_in: HashMap<&'m Name, HashSet<&'m Name>>,
+    /// Values live on exit from each block
+    live_out: HashMap<&'m Name, HashSet<&'m Name>>,
+}
+
+impl<'m> Liveness<'m> {
+    pub(crate) fn new(function: &'m Function, cfg: &ControlFlowGraph<'m>) -> Self {
+        let mut liveness = Self {
+            function,
+            live_in: function
+                .basic_blocks
+                .iter()
+                .map(|bb| (&bb.name, HashSet::new()))
+                .collect(),
+            live_out: function
+                .basic_blocks
+                .iter()
+                .map(|bb| (&bb.name, HashSet::new()))
+                .collect(),
+        };
+
+        let mut worklist: Vec<&'m Name> = function.basic_blocks.iter().map(|bb| &bb.name).collect();
+        let mut on_worklist: HashSet<&'m Name> = worklist.iter().copied().collect();
+        while let Some(block) = worklist.pop() {
+            on_worklist.remove(block);
+            let mut live_out: HashSet<&'m Name> = HashSet::new();
+            for succ in cfg.succs(block) {
+                if let CFGNode::Block(succ) = succ {
+                    let succ_bb = liveness.block(succ);
+                    let phis = phis(succ_bb);
+                    live_out.extend(
+                        liveness.live_in[succ]
+                            .iter()
+                            .copied()
+                            .filter(|&value| !phis.iter().any(|phi| &phi.dest == value)),
+                    );
+                    for phi in phis {
+                        for (value, pred) in &phi.incoming_values {
+                            if pred == block {
+                                live_out.extend(local_name(value));
+                            }
+                        }
+                    }
+                }
+            }
+            let live_in = liveness.live_before(block, 0, live_out.clone());
+            liveness.live_out.insert(block, live_out);
+            if live_in != liveness.live_in[block] {
+                liveness.live_in.insert(block, live_in);
+                for pred in cfg.preds(block) {
+                    if on_worklist.insert(pred) {
+                        worklist.push(pred);
+                    }
+                }
+            }
+        }
+        liveness
+    }
+
+    /// Iterate over the values live on entry to the block with the given
+    /// `Name`
+    pub fn live_in<'s>(&'s self, block: &'m Name) -> impl Iterator<Item = &'m Name> + 's {
+        self.block_set(&self.live_in, block).iter().copied()
+    }
+
+    /// Iterate over the values live on exit from the block with the given
+    /// `Name`
+    pub fn live_out<'s>(&'s self, block: &'m Name) -> impl Iterator<Item = &'m Name> + 's {
+        self.block_set(&self.live_out, block).iter().copied()
+    }
+
+    /// Is the value with the given `Name` live on entry to the given block?
+    pub fn is_live_in(&self, value: &Name, block: &'m Name) -> bool {
+        self.block_set(&self.live_in, block).contains(value)
+    }
+
+    /// Is the value with the given `Name` live on exit from the given block?
+    pub fn is_live_out(&self, value: &Name, block: &'m Name) -> bool {
+        self.block_set(&self.live_out, block).contains(value)
+    }
+
+    /// Get the values live just before the instruction at index `idx` of the
+    /// given block executes, where index `instrs.len()` refers to the block's
+    /// terminator. Since the block's `phi`s execute together on entry to the
+    /// block, for the index of any `phi` this is the block's live-in set.
+    ///
+    /// Panics if the block isn't in this function, or the index is out of
+    /// range.
+    pub fn live_at(&self, block: &'m Name, idx: usize) -> HashSet<&'m Name> {
+        let live_out = self.block_set(&self.live_out, block).clone();
+        self.live_before(block, idx, live_out)
+    }
+
+    /// Is the value with the given `Name` live just before the instruction at
+    /// index `idx` of the given block executes? See
+    /// [`live_at()`](#method.live_at).
+    ///
+    /// Panics if the block isn't in this function, or the index is out of
+    /// range.
+    pub fn is_live_at(&self, value: &Name, block: &'m Name, idx: usize) -> bool {
+        self.live_at(block, idx).contains(value)
+    }
+
+    /// Get the values live just before the instruction at index `idx` of the
+    /// given block, given the values live on exit from the block
+    fn live_before(
+        &self,
+        block: &'m Name,
+        idx: usize,
+        mut live: HashSet<&'m Name>,
+    ) -> HashSet<&'m Name> {
+        let bb = self.block(block);
+        if idx > bb.instrs.len() {
+            panic!(
+                "live_at(): index {} out of range for block {} in function {}",
+                idx, block, self.function.name
+            );
+        }
+        if let Some(result) = bb.term.try_get_result() {
+            live.remove(result);
+        }
+        live.extend(
+            terminator_operands(&bb.term)
+                .into_iter()
+                .filter_map(local_name),
+        );
+        // the `phi`s' operands are used in the predecessors, and their
+        // results are defined on entry, so we stop at the first non-`phi`
+        let first = idx.max(phis(bb).len());
+        for inst in bb.instrs.iter().skip(first).rev() {
+            if let Some(result) = inst.try_get_result() {
+                live.remove(result);
+            }
+            live.extend(
+                instruction_operands(inst)
+                    .into_iter()
+                    .filter_map(local_name),
+            );
+        }
+        live
+    }
+
+    fn block(&self, block: &Name) -> &'m BasicBlock {
+        self.function.get_bb_by_name(block).unwrap_or_else(|| {
+            panic!(
+                "Failed to find block {} in function {}",
+                block, self.function.name
+            )
+        })
+    }
+
+    fn block_set<'s>(
+        &'s self,
+        sets: &'s HashMap<&'m Name, HashSet<&'m Name>>,
+        block: &Name,
+    ) -> &'s HashSet<&'m Name> {
+        sets.get(block).unwrap_or_else(|| {
+            panic!(
+                "Failed to find block {} in function {}",
+                block, self.function.name
+            )
+        })
+    }
+}
+
+/// Get the `phi`s at the start of the given block
+fn phis(bb: &BasicBlock) -> Vec<&Phi> {
+    bb.instrs
+        .iter()
+        .map_while(|inst| match inst {
+            Instruction::Phi(phi) => Some(phi),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Get the `Name` of the given operand, if it's a local value
+fn local_name(operand: &Operand) -> Option<&Name> {
+    match operand {
+        Operand::LocalOperand { name, .. } => Some(name),
+        _ => None,
+    }
+}
@@ -0,0 +1,844 @@
+use crate::control_flow_graph::{CFGNode, ControlFlowGraph};
+use crate::dominator_tree::DominatorTree;
+use crate::operands::instruction_operands;
+use either::Either;
+use llvm_ir::instruction::InlineAssembly;
+use llvm_ir::{Constant, Function, Instruction, IntPredicate, Name, Operand, Terminator};
+use std::collections::{HashMap, HashSet};
+
+/// The natural loops of a particular function (similar to LLVM's `LoopInfo`).
+///
+/// Each loop is identified by its header, the block which dominates the rest
+/// of the loop. All of the back edges to the same header (see
+/// [`ControlFlowGraph::back_edges()`](struct.ControlFlowGraph.html#method.back_edges))
+/// form a single loop. Blocks which are unreachable from the entry block are
+/// never part of a loop.
+///
+/// As with the other loop methods of `ControlFlowGraph`, cycles in irreducible
+/// control flow have no back edge, and so are not found.
+///
+/// To construct a `LoopAnalysis`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct LoopAnalysis<'m> {
+    /// All of the loops. Larger loops come first, so each loop comes before
+    /// all of the loops nested in it.
+    loops: Vec<Loop<'m>>,
+
+    /// Map from each block in a loop to the index of the innermost loop
+    /// containing it
+    innermost: HashMap<&'m Name, usize>,
+}
+
+/// A natural loop in a function; see [`LoopAnalysis`](struct.LoopAnalysis.html)
+#[derive(Clone, PartialEq, Debug)]
+pub struct Loop<'m> {
+    /// The loop's header: the block through which control enters the loop,
+    /// and the destination of all of its back edges
+    pub header: &'m Name,
+    /// The blocks in the loop, including the header and the blocks of any
+    /// nested loops
+    blocks: HashSet<&'m Name>,
+    /// The blocks in the loop with a back edge to the header, in the order
+    /// they appear in the function
+    latches: Vec<&'m Name>,
+    /// The reachable blocks outside the loop with an edge to the header, in
+    /// the order they appear in the function
+    entering_blocks: Vec<&'m Name>,
+    /// The loop's preheader, if it has one
+    preheader: Option<&'m Name>,
+    /// The blocks in the loop with a successor outside the loop, in the order
+    /// they appear in the function
+    exiting_blocks: Vec<&'m Name>,
+    /// The blocks outside the loop with a predecessor in the loop, in the
+    /// order they appear in the function
+    exit_blocks: Vec<&'m Name>,
+    /// The edges from blocks in the loop to blocks outside it, as pairs
+    /// `(exiting, exit)`, in the order of the exiting blocks in the function
+    exit_edges: Vec<(&'m Name, &'m Name)>,
+    /// Index of the loop immediately containing this one, or `None` for a
+    /// top-level loop
+    parent: Option<usize>,
+    /// Indices of the loops immediately nested in this one
+    children: Vec<usize>,
+    /// Whether all of the (reachable) predecessors of each exit block are in
+    /// the loop
+    dedicated_exits: bool,
+    /// Static trip count of the loop, if one could be computed
+    trip_count: Option<TripCount>,
+    /// Summary of the loop's side effects and exit conditions
+    effects: LoopEffects<'m>,
+}
+
+/// A summary of what a loop may write, and of the values which decide when it
+/// exits. See [`Loop::effects()`](struct.Loop.html#method.effects).
+///
+/// This is a syntactic summary of the instructions in the loop, including any
+/// nested loops: e.g., a loop with no store targets and no callees doesn't
+/// write to memory, though it may still read memory.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LoopEffects<'m> {
+    /// The addresses written by `store`, `cmpxchg`, and `atomicrmw`
+    /// instructions in the loop, in the order they appear in the function
+    pub store_targets: Vec<&'m Operand>,
+    /// The functions called by `call`, `invoke`, and `callbr` instructions in
+    /// the loop, in the order they appear in the function. Calls to
+    /// intrinsics are included.
+    pub callees: Vec<&'m Either<InlineAssembly, Operand>>,
+    /// The conditions the loop's exiting blocks branch on (the condition of
+    /// a `br`, or the operand of a `switch` or `indirectbr`), paired with the
+    /// exiting block, in the order of the exiting blocks in the function
+    pub exit_conditions: Vec<(&'m Name, &'m Operand)>,
+    /// The local values which the exit conditions depend on, directly or
+    /// through instructions in the loop, starting with the exit conditions
+    /// themselves. Values defined outside the loop (e.g., parameters) are
+    /// included, but their own operands are not.
+    pub exit_condition_values: Vec<&'m Name>,
+}
+
+/// A static trip count for a loop: the number of times the loop's header
+/// executes each time control enters the loop. See
+/// [`Loop::trip_count()`](struct.Loop.html#method.trip_count).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TripCount {
+    /// The header executes exactly this many times
+    Exact(u64),
+    /// The header executes at most this many times. The loop may leave
+    /// earlier through an exit whose condition couldn't be analyzed.
+    AtMost(u64),
+}
+
+impl TripCount {
+    /// Get the trip count, or the upper bound on it
+    pub fn max(self) -> u64 {
+        match self {
+            TripCount::Exact(count) => count,
+            TripCount::AtMost(count) => count,
+        }
+    }
+}
+
+impl<'m> Loop<'m> {
+    /// Iterate over the blocks in the loop, including the header and the blocks
+    /// of any nested loops
+    pub fn blocks<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
+        self.blocks.iter().copied()
+    }
+
+    /// Get the number of blocks in the loop, including the header and the
+    /// blocks of any nested loops
+    pub fn num_blocks(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Does the loop (or a loop nested in it) contain the block with the given
+    /// `Name`?
+    pub fn contains(&self, block: &'m Name) -> bool {
+        self.blocks.contains(block)
+    }
+
+    /// Get the loop's latches: the blocks in the loop with a back edge to the
+    /// header, in the order they appear in the function
+    pub fn latches(&self) -> &[&'m Name] {
+        &self.latches
+    }
+
+    /// Get the loop's entering blocks: the blocks outside the loop with an edge
+    /// to the header, in the order they appear in the function. Blocks which
+    /// are unreachable from the entry block are not included.
+    pub fn entering_blocks(&self) -> &[&'m Name] {
+        &self.entering_blocks
+    }
+
+    /// Get the loop's preheader, if it has one: the loop's only entering block
+    /// (see [`entering_blocks()`](#method.entering_blocks)), provided that its
+    /// only successor is the header. As in LLVM, this is where code can be
+    /// placed to run once before the loop.
+    pub fn preheader(&self) -> Option<&'m Name> {
+        self.preheader
+    }
+
+    /// Get the loop's exiting blocks: the blocks in the loop with a successor
+    /// outside the loop, in the order they appear in the function.
+    ///
+    /// Blocks which leave the loop only by exiting the function (i.e., whose
+    /// only successor outside the loop is `CFGNode::Return`) are not included,
+    /// as in LLVM.
+    pub fn exiting_blocks(&self) -> &[&'m Name] {
+        &self.exiting_blocks
+    }
+
+    /// Get the loop's exit blocks: the blocks outside the loop with a
+    /// predecessor in the loop, in the order they appear in the function
+    pub fn exit_blocks(&self) -> &[&'m Name] {
+        &self.exit_blocks
+    }
+
+    /// Get the loop's exit edges: the edges from blocks in the loop to blocks
+    /// outside it, as pairs `(exiting, exit)`, in the order of the exiting
+    /// blocks in the function
+    pub fn exit_edges(&self) -> &[(&'m Name, &'m Name)] {
+        &self.exit_edges
+    }
+
+    /// Get the loop's latch, if it has exactly one (see
+    /// [`latches()`](#method.latches))
+    pub fn latch(&self) -> Option<&'m Name> {
+        match self.latches.as_slice() {
+            [latch] => Some(latch),
+            _ => None,
+        }
+    }
+
+    /// Get the loop's exiting block, if it has exactly one (see
+    /// [`exiting_blocks()`](#method.exiting_blocks))
+    pub fn exiting_block(&self) -> Option<&'m Name> {
+        match self.exiting_blocks.as_slice() {
+            [block] => Some(block),
+            _ => None,
+        }
+    }
+
+    /// Get the loop's exit block, if it has exactly one (see
+    /// [`exit_blocks()`](#method.exit_blocks))
+    pub fn exit_block(&self) -> Option<&'m Name> {
+        match self.exit_blocks.as_slice() {
+            [block] => Some(block),
+            _ => None,
+        }
+    }
+
+    /// Does the loop have dedicated exits? That is, are all of the
+    /// predecessors of each exit block in the loop? As with
+    /// [`entering_blocks()`](#method.entering_blocks), blocks which are
+    /// unreachable from the entry block are ignored.
+    pub fn has_dedicated_exits(&self) -> bool {
+        self.dedicated_exits
+    }
+
+    /// Is the loop in the simplified form which LLVM's `LoopSimplify` pass
+    /// establishes, and which many LLVM loop algorithms assume? That is, does
+    /// it have a [`preheader()`](#method.preheader), a single
+    /// [`latch()`](#method.latch), and
+    /// [dedicated exits](#method.has_dedicated_exits)?
+    pub fn is_loop_simplify_form(&self) -> bool {
+        self.preheader.is_some() && self.latch().is_some() && self.dedicated_exits
+    }
+
+    /// Get the loop's static trip count, if one could be computed: the number
+    /// of times the header executes each time control enters the loop.
+    ///
+    /// A trip count is computed from each exiting block which is executed on
+    /// every iteration (i.e., which dominates all of the latches and isn't in a
+    /// nested loop), and which branches on an `icmp` of a constant with an
+    /// induction variable. The induction variable must be a `phi` in the
+    /// header (or the `phi` plus a constant) which starts at a constant and is
+    /// incremented or decremented by a constant on each iteration. For `eq`
+    /// and `ne` comparisons, the induction variable may wrap around; for other
+    /// comparisons, a loop which would only exit after the induction variable
+    /// overflows gets no trip count.
+    ///
+    /// The result is [`TripCount::Exact`](enum.TripCount.html#variant.Exact)
+    /// if that exiting block is the only way out of the loop, and otherwise
+    /// [`TripCount::AtMost`](enum.TripCount.html#variant.AtMost) the
+    /// smallest of the computed trip counts. Calls in the loop are assumed to
+    /// return.
+    pub fn trip_count(&self) -> Option<TripCount> {
+        self.trip_count
+    }
+
+    /// Get a summary of the loop's side effects and of the values which
+    /// decide when it exits
+    pub fn effects(&self) -> &LoopEffects<'m> {
+        &self.effects
+    }
+}
+
+impl<'m> LoopAnalysis<'m> {
+    pub(crate) fn new(
+        function: &'m Function,
+        cfg: &ControlFlowGraph<'m>,
+        domtree: &DominatorTree<'m>,
+    ) -> Self {
+        let positions: HashMap<&'m Name, usize> = cfg
+            .blocks()
+            .enumerate()
+            .map(|(i, block)| (block, i))
+            .collect();
+        let entry = CFGNode::Block(cfg.entry());
+        let mut loops: Vec<Loop<'m>> = cfg
+            .natural_loops(domtree)
+            .into_iter()
+            .map(|(header, blocks)| {
+                let mut latches: Vec<&'m Name> = cfg
+                    .preds(header)
+                    .filter(|&pred| blocks.contains(pred))
+                    .collect();
+                let mut entering_blocks: Vec<&'m Name> = cfg
+                    .preds(header)
+                    .filter(|&pred| {
+                        !blocks.contains(pred) && domtree.dominates(entry, CFGNode::Block(pred))
+                    })
+                    .collect();
+                let mut exit_edges: Vec<(&'m Name, &'m Name)> = blocks
+                    .iter()
+                    .flat_map(|&block| {
+                        cfg.succs(block).filter_map(move |succ| match succ {
+                            CFGNode::Block(succ) => Some((block, succ)),
+                            CFGNode::Return => None,
+                        })
+                    })
+                    .filter(|(_, succ)| !blocks.contains(succ))
+                    .collect();
+                exit_edges.sort_by_key(|&(exiting, exit)| (positions[exiting], positions[exit]));
+                let mut exiting_blocks: Vec<&'m Name> =
+                    exit_edges.iter().map(|&(exiting, _)| exiting).collect();
+                let mut exit_blocks: Vec<&'m Name> =
+                    exit_edges.iter().map(|&(_, exit)| exit).collect();
+                for list in [
+                    &mut latches,
+                    &mut entering_blocks,
+                    &mut exiting_blocks,
+                    &mut exit_blocks,
+                ] {
+                    list.sort_by_key(|block| positions[*block]);
+                    list.dedup();
+                }
+                let preheader = match entering_blocks.as_slice() {
+                    [pred] if cfg.succs(pred).count() == 1 => Some(*pred),
+                    _ => None,
+                };
+                let dedicated_exits = exit_blocks.iter().all(|&exit| {
+                    cfg.preds(exit).all(|pred| {
+                        blocks.contains(pred) || !domtree.dominates(entry, CFGNode::Block(pred))
+                    })
+                });
+                Loop {
+                    header,
+                    blocks,
+                    latches,
+                    entering_blocks,
+                    preheader,
+                    dedicated_exits,
+                    exiting_blocks,
+                    exit_blocks,
+                    exit_edges,
+                    parent: None,
+                    children: Vec::new(),
+                    trip_count: None,
+                    effects: LoopEffects {
+                        store_targets: Vec::new(),
+                        callees: Vec::new(),
+                        exit_conditions: Vec::new(),
+                        exit_condition_values: Vec::new(),
+                    },
+                }
+            })
+            .collect();
+        loops.sort_by_key(|l| (std::cmp::Reverse(l.blocks.len()), positions[l.header]));
+
+        // natural loops with different headers are either nested or disjoint,
+        // so the innermost loop so far containing a loop's header is its
+        // parent
+        let mut innermost: HashMap<&'m Name, usize> = HashMap::new();
+        for index in 0..loops.len() {
+            let parent = innermost.get(loops[index].header).copied();
+            if let Some(parent) = parent {
+                loops[parent].children.push(index);
+            }
+            loops[index].parent = parent;
+            for &block in &loops[index].blocks {
+                innermost.insert(block, index);
+            }
+        }
+
+        let defs: HashMap<&'m Name, &'m Instruction> = function
+            .basic_blocks
+            .iter()
+            .flat_map(|bb| bb.instrs.iter())
+            .filter_map(|inst| inst.try_get_result().map(|dest| (dest, inst)))
+            .collect();
+        let terms: HashMap<&'m Name, &'m Terminator> = function
+            .basic_blocks
+            .iter()
+            .map(|bb| (&bb.name, &bb.term))
+            .collect();
+        for (index, l) in loops.iter_mut().enumerate() {
+            let counts: Vec<u64> = l
+                .exiting_blocks
+                .iter()
+                .filter(|&&exiting| {
+                    innermost[exiting] == index
+                        && l.latches.iter().all(|&latch| {
+                            domtree.dominates(CFGNode::Block(exiting), CFGNode::Block(latch))
+                        })
+                })
+                .filter_map(|&exiting| exit_trip_count(l, &defs, terms[exiting]))
+                .collect();
+            let may_return = l
+                .blocks
+                .iter()
+                .any(|&block| cfg.succs(block).any(|succ| succ == CFGNode::Return));
+            let trip_count = counts.iter().min().map(|&count| {
+                if counts.len() == 1 && l.exiting_blocks.len() == 1 && !may_return {
+                    TripCount::Exact(count)
+                } else {
+                    TripCount::AtMost(count)
+                }
+            });
+            l.trip_count = trip_count;
+            l.effects = loop_effects(function, l, &defs, &terms);
+        }
+
+        Self { loops, innermost }
+    }
+
+    /// Iterate over all of the loops in the function. Each loop appears before
+    /// all of the loops nested in it.
+    pub fn loops(&self) -> impl Iterator<Item = &Loop<'m>> {
+        self.loops.iter()
+    }
+
+    /// Get the number of loops in the function
+    pub fn num_loops(&self) -> usize {
+        self.loops.len()
+    }
+
+    /// Get the loop with the given header, if there is one
+    pub fn loop_with_header(&self, header: &'m Name) -> Option<&Loop<'m>> {
+        self.loops.iter().find(|l| l.header == header)
+    }
+
+    /// Is the block with the given `Name` the header of a loop?
+    pub fn is_header(&self, block: &'m Name) -> bool {
+        self.loop_with_header(block).is_some()
+    }
+
+    /// Iterate over the loops which aren't nested in any other loop
+    pub fn top_level_loops(&self) -> impl Iterator<Item = &Loop<'m>> {
+        self.loops.iter().filter(|l| l.parent.is_none())
+    }
+
+    /// Are all of the loops in the function in the simplified form which
+    /// LLVM's `LoopSimplify` pass establishes? See
+    /// [`Loop::is_loop_simplify_form()`](struct.Loop.html#method.is_loop_simplify_form).
+    pub fn is_loop_simplify_form(&self) -> bool {
+        self.loops.iter().all(Loop::is_loop_simplify_form)
+    }
+
+    /// Get the innermost loop containing the block with the given `Name`, or
+    /// `None` if the block isn't in any loop
+    pub fn loop_of(&self, block: &'m Name) -> Option<&Loop<'m>> {
+        self.innermost.get(block).map(|&index| &self.loops[index])
+    }
+
+    /// Get the loop immediately containing the given loop, or `None` if the
+    /// given loop is a top-level loop
+    pub fn parent_loop(&self, l: &Loop<'m>) -> Option<&Loop<'m>> {
+        l.parent.map(|index| &self.loops[index])
+    }
+
+    /// Get the loops immediately nested in the given loop
+    pub fn subloops<'s>(&'s self, l: &'s Loop<'m>) -> impl Iterator<Item = &'s Loop<'m>> + 's {
+        l.children.iter().map(move |&index| &self.loops[index])
+    }
+
+    /// Get the nesting depth of the given loop: 1 for a top-level loop, 2 for
+    /// the loops nested in it, etc
+    pub fn depth(&self, l: &Loop<'m>) -> usize {
+        std::iter::successors(Some(l), |l| self.parent_loop(l)).count()
+    }
+
+    /// Get the loop nesting depth of the block with the given `Name`: the
+    /// number of loops containing it, or 0 if it isn't in any loop
+    pub fn loop_depth(&self, block: &'m Name) -> usize {
+        self.loop_of(block).map_or(0, |l| self.depth(l))
+    }
+
+    /// Get the maximum loop nesting depth of any block in the function, or 0
+    /// if the function has no loops
+    pub fn max_loop_depth(&self) -> usize {
+        self.loops
+            .iter()
+            .filter(|l| l.children.is_empty())
+            .map(|l| self.depth(l))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Compute the `LoopEffects` of the given loop
+fn loop_effects<'m>(
+    function: &'m Function,
+    l: &Loop<'m>,
+    defs: &HashMap<&'m Name, &'m Instruction>,
+    terms: &HashMap<&'m Name, &'m Terminator>,
+) -> LoopEffects<'m> {
+    let mut store_targets = Vec::new();
+    let mut callees = Vec::new();
+    // locals defined in the loop
+    let mut loop_defs: HashSet<&'m Name> = HashSet::new();
+    for bb in function
+        .basic_blocks
+        .iter()
+        .filter(|bb| l.blocks.contains(&bb.name))
+    {
+        for inst in &bb.instrs {
+            match inst {
+                Instruction::Store(store) => store_targets.push(&store.address),
+                Instruction::CmpXchg(cmpxchg) => store_targets.push(&cmpxchg.address),
+                Instruction::AtomicRMW(rmw) => store_targets.push(&rmw.address),
+                Instruction::Call(call) => callees.push(&call.function),
+                _ => {}
+            }
+            loop_defs.extend(inst.try_get_result());
+        }
+        match &bb.term {
+            Terminator::Invoke(invoke) => {
+                callees.push(&invoke.function);
+                loop_defs.insert(&invoke.result);
+            }
+            Terminator::CallBr(callbr) => {
+                callees.push(&callbr.function);
+                loop_defs.insert(&callbr.result);
+            }
+            _ => {}
+        }
+    }
+
+    let exit_conditions: Vec<(&'m Name, &'m Operand)> = l
+        .exiting_blocks
+        .iter()
+        .filter_map(|&exiting| {
+            let condition = match terms[exiting] {
+                Terminator::CondBr(condbr) => &condbr.condition,
+                Terminator::Switch(switch) => &switch.operand,
+                Terminator::IndirectBr(indirectbr) => &indirectbr.operand,
+                _ => return None,
+            };
+            Some((exiting, condition))
+        })
+        .collect();
+
+    let mut exit_condition_values: Vec<&'m Name> = Vec::new();
+    let mut seen: HashSet<&'m Name> = HashSet::new();
+    let mut worklist: Vec<&'m Operand> = exit_conditions
+        .iter()
+        .rev()
+        .map(|&(_, condition)| condition)
+        .collect();
+    while let Some(op) = worklist.pop() {
+        let Some(name) = local_name(op) else {
+            continue;
+        };
+        if !seen.insert(name) {
+            continue;
+        }
+        exit_condition_values.push(name);
+        if loop_defs.contains(name) {
+            if let Some(inst) = defs.get(name) {
+                worklist.extend(instruction_operands(inst).into_iter().rev());
+            }
+        }
+    }
+
+    LoopEffects {
+        store_targets,
+        callees,
+        exit_conditions,
+        exit_condition_values,
+    }
+}
+
+/// An induction variable of a loop: a header `phi` with value
+/// `init + step * k` on the `k`th iteration (counting from 0), or the update of
+/// such a `phi`, with value `init + step * (k + 1)`. `init` and `step` are
+/// given as `bits`-wide two's complement values.
+struct InductionVariable {
+    bits: u32,
+    init: u64,
+    step: u64,
+}
+
+/// Get the trip count implied by the given exiting block's terminator, if
+/// possible; see [`Loop::trip_count()`](struct.Loop.html#method.trip_count)
+fn exit_trip_count(
+    l: &Loop,
+    defs: &HashMap<&Name, &Instruction>,
+    term: &Terminator,
+) -> Option<u64> {
+    let Terminator::CondBr(condbr) = term else {
+        return None;
+    };
+    // whether the loop exits when the condition is true
+    let exit_on_true = match (
+        l.blocks.contains(&condbr.true_dest),
+        l.blocks.contains(&condbr.false_dest),
+    ) {
+        (false, true) => true,
+        (true, false) => false,
+        _ => return None,
+    };
+    let Some(Instruction::ICmp(icmp)) = defs.get(local_name(&condbr.condition)?) else {
+        return None;
+    };
+    let (iv, bound, predicate) = match (const_int(&icmp.operand0), const_int(&icmp.operand1)) {
+        (None, Some(bound)) => (&icmp.operand0, bound, icmp.predicate),
+        (Some(bound), None) => (&icmp.operand1, bound, swap(icmp.predicate)),
+        _ => return None,
+    };
+    let predicate = if exit_on_true {
+        predicate
+    } else {
+        negate(predicate)
+    };
+    let iv = induction_variable(l, defs, local_name(iv)?)?;
+    let (bits, bound) = bound;
+    if iv.bits != bits || bits == 0 || bits > 64 {
+        return None;
+    }
+    // first iteration (counting from 0) on which the loop exits
+    let k = if matches!(predicate, IntPredicate::EQ | IntPredicate::NE) {
+        first_wrapping(predicate, bits, iv.init, iv.step, bound)?
+    } else {
+        first_nonwrapping(predicate, bits, iv.init, iv.step, bound)?
+    };
+    u64::try_from(k + 1).ok()
+}
+
+/// Get the induction variable with the given name, if it is one
+fn induction_variable(
+    l: &Loop,
+    defs: &HashMap<&Name, &Instruction>,
+    name: &Name,
+) -> Option<InductionVariable> {
+    match defs.get(name)? {
+        Instruction::Phi(_) => {
+            let (bits, init, step, _) = header_phi(l, defs, name)?;
+            Some(InductionVariable { bits, init, step })
+        }
+        Instruction::Add(_) | Instruction::Sub(_) => {
+            let (phi, _) = update_of(defs.get(name)?)?;
+            let (bits, init, step, update) = header_phi(l, defs, phi)?;
+            if update != name {
+                return None;
+            }
+            Some(InductionVariable {
+                bits,
+                init: init.wrapping_add(step),
+                step,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// If the given name is a `phi` in the loop's header which starts at a
+/// constant and is updated by a constant on each iteration, get its width,
+/// initial value, step, and the name of its update
+fn header_phi<'a>(
+    l: &Loop,
+    defs: &HashMap<&Name, &'a Instruction>,
+    name: &Name,
+) -> Option<(u32, u64, u64, &'a Name)> {
+    let Instruction::Phi(phi) = defs.get(name)? else {
+        return None;
+    };
+    let mut init: Option<(u32, u64)> = None;
+    let mut update: Option<&'a Name> = None;
+    for (value, pred) in &phi.incoming_values {
+        if l.blocks.contains(pred) {
+            let value = local_name(value)?;
+            if update.is_some_and(|update| update != value) {
+                return None;
+            }
+            update = Some(value);
+        } else {
+            let value = const_int(value)?;
+            if init.is_some_and(|init| init != value) {
+                return None;
+            }
+            init = Some(value);
+        }
+    }
+    let (bits, init) = init?;
+    let update = update?;
+    let (updated, (step_bits, step)) = update_of(defs.get(update)?)?;
+    if updated != name || step_bits != bits {
+        return None;
+    }
+    Some((bits, init, step, update))
+}
+
+/// If the given instruction adds a constant to (or subtracts a constant from)
+/// a local, get the local and the constant added, as a `(bits, value)` pair
+fn update_of(inst: &Instruction) -> Option<(&Name, (u32, u64))> {
+    match inst {
+        Instruction::Add(add) => match (
+            local_name(&add.operand0),
+            const_int(&add.operand1),
+            const_int(&add.operand0),
+            local_name(&add.operand1),
+        ) {
+            (Some(name), Some(step), _, _) | (_, _, Some(step), Some(name)) => Some((name, step)),
+            _ => None,
+        },
+        Instruction::Sub(sub) => {
+            let name = local_name(&sub.operand0)?;
+            let (bits, step) = const_int(&sub.operand1)?;
+            Some((name, (bits, step.wrapping_neg())))
+        }
+        _ => None,
+    }
+}
+
+fn local_name(op: &Operand) -> Option<&Name> {
+    match op {
+        Operand::LocalOperand { name, .. } => Some(name),
+        _ => None,
+    }
+}
+
+fn const_int(op: &Operand) -> Option<(u32, u64)> {
+    match op {
+        Operand::ConstantOperand(c) => match c.as_ref() {
+            Constant::Int { bits, value } => Some((*bits, *value)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Get the predicate `p` such that `a p b` iff `b predicate a`
+pub(crate) fn swap(predicate: IntPredicate) -> IntPredicate {
+    match predicate {
+        IntPredicate::EQ => IntPredicate::EQ,
+        IntPredicate::NE => IntPredicate::NE,
+        IntPredicate::UGT => IntPredicate::ULT,
+        IntPredicate::UGE => IntPredicate::ULE,
+        IntPredicate::ULT => IntPredicate::UGT,
+        IntPredicate::ULE => IntPredicate::UGE,
+        IntPredicate::SGT => IntPredicate::SLT,
+        IntPredicate::SGE => IntPredicate::SLE,
+        IntPredicate::SLT => IntPredicate::SGT,
+        IntPredicate::SLE => IntPredicate::SGE,
+    }
+}
+
+/// Get the predicate `p` such that `a p b` iff not `a predicate b`
+pub(crate) fn negate(predicate: IntPredicate) -> IntPredicate {
+    match predicate {
+        IntPredicate::EQ => IntPredicate::NE,
+        IntPredicate::NE => IntPredicate::EQ,
+        IntPredicate::UGT => IntPredicate::ULE,
+        IntPredicate::UGE => IntPredicate::ULT,
+        IntPredicate::ULT => IntPredicate::UGE,
+        IntPredicate::ULE => IntPredicate::UGT,
+        IntPredicate::SGT => IntPredicate::SLE,
+        IntPredicate::SGE => IntPredicate::SLT,
+        IntPredicate::SLT => IntPredicate::SGE,
+        IntPredicate::SLE => IntPredicate::SGT,
+    }
+}
+
+/// Sign-extend the `bits`-wide value to an `i128`
+pub(crate) fn signed(bits: u32, value: u64) -> i128 {
+    let shift = 64 - bits;
+    i128::from(((value << shift) as i64) >> shift)
+}
+
+/// Zero-extend the `bits`-wide value to an `i128`
+fn unsigned(bits: u32, value: u64) -> i128 {
+    i128::from(value & (u64::MAX >> (64 - bits)))
+}
+
+/// Get the smallest `k` such that `init + step * k` (with `bits`-wide
+/// wrapping arithmetic) is equal (for `EQ`) or not equal (for `NE`) to
+/// `bound`
+fn first_wrapping(
+    predicate: IntPredicate,
+    bits: u32,
+    init: u64,
+    step: u64,
+    bound: u64,
+) -> Option<i128> {
+    let modulus: u128 = 1 << bits;
+    let init = unsigned(bits, init) as u128;
+    let step = unsigned(bits, step) as u128;
+    let bound = unsigned(bits, bound) as u128;
+    if predicate == IntPredicate::NE {
+        return match (init != bound, step != 0) {
+            (true, _) => Some(0),
+            (false, true) => Some(1),
+            (false, false) => None,
+        };
+    }
+    // solve `step * k == bound - init` modulo `2^bits`
+    let diff = (bound + modulus - init) % modulus;
+    if diff == 0 {
+        return Some(0);
+    }
+    if step == 0 {
+        return None;
+    }
+    let twos = step.trailing_zeros();
+    if diff.trailing_zeros() < twos {
+        return None;
+    }
+    let modulus = modulus >> twos;
+    let odd = (step >> twos) % modulus;
+    // inverse of an odd number modulo a power of two, by Newton's method
+    let mut inverse: u128 = 1;
+    for _ in 0..7 {
+        inverse = inverse.wrapping_mul(2u128.wrapping_sub(odd.wrapping_mul(inverse)));
+    }
+    let k = ((diff >> twos) % modulus).wrapping_mul(inverse) % modulus;
+    Some(k as i128)
+}
+
+/// Get the smallest `k` such that `init + step * k` satisfies the relational
+/// `predicate` with `bound`, provided no `init + step * j` for `j <= k`
+/// overflows the `bits`-wide type
+fn first_nonwrapping(
+    predicate: IntPredicate,
+    bits: u32,
+    init: u64,
+    step: u64,
+    bound: u64,
+) -> Option<i128> {
+    let (min, max, init, bound) = match predicate {
+        IntPredicate::SGT | IntPredicate::SGE | IntPredicate::SLT | IntPredicate::SLE => (
+            -(1i128 << (bits - 1)),
+            (1i128 << (bits - 1)) - 1,
+            signed(bits, init),
+            signed(bits, bound),
+        ),
+        _ => (
+            0,
+            (1i128 << bits) - 1,
+            unsigned(bits, init),
+            unsigned(bits, bound),
+        ),
+    };
+    let step = signed(bits, step);
+    // normalize to an exit condition of `value > bound`, where `value` must
+    // stay at most `max`
+    let (init, step, bound, max) = match predicate {
+        IntPredicate::UGT | IntPredicate::SGT => (init, step, bound, max),
+        IntPredicate::UGE | IntPredicate::SGE => (init, step, bound - 1, max),
+        IntPredicate::ULT | IntPredicate::SLT => (-init, -step, -bound, -min),
+        IntPredicate::ULE | IntPredicate::SLE => (-init, -step, -bound - 1, -min),
+        IntPredicate::EQ | IntPredicate::NE => return None,
+    };
+    if init > bound {
+        return Some(0);
+    }
+    if step <= 0 {
+        return None;
+    }
+    let k = (bound - init) / step + 1;
+    if init + step * k > max {
+        return None;
+    }
+    Some(k)
+}
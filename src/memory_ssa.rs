@@ -0,0 +1,368 @@
+use crate::alias::AliasAnalysis;
+use crate::control_flow_graph::{CFGNode, ControlFlowGraph};
+use crate::data_dep_graph::MemoryAccess;
+use crate::def_use::InstructionLocation;
+use crate::dominator_tree::DominatorTree;
+use either::Either;
+use llvm_ir::{Function, Instruction, Name, Operand};
+use std::collections::{HashMap, HashSet};
+
+/// A version of memory in [`MemorySSA`](struct.MemorySSA.html): what a memory
+/// access reads, or overwrites
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub enum MemoryDefinition<'m> {
+    /// Memory as it was on entry to the function
+    LiveOnEntry,
+    /// Memory as written by the instruction (or terminator) at this location
+    Def(InstructionLocation<'m>),
+    /// The memory `phi` at the start of the block with this `Name`, merging the
+    /// versions of memory from the block's predecessors
+    Phi(&'m Name),
+}
+
+/// A MemorySSA overlay for a particular function, in the style of LLVM's
+/// `MemorySSA`: memory is treated as a single variable in SSA form. Each
+/// instruction which may write memory is a memory def, creating a new version
+/// of memory; each other instruction which may read memory is a memory use;
+/// and memory `phi`s merge versions of memory where control flow joins.
+///
+/// Each def and use is linked to its _defining access_, the version of memory
+/// it reads (or, for a def, overwrites). This doesn't consider aliasing; to
+/// find the def which actually may have written the memory an access reads,
+/// use [`clobbering_definition()`](#method.clobbering_definition), which walks
+/// up from the defining access, skipping defs which the configured
+/// [`AliasAnalysis`](trait.AliasAnalysis.html) says can't alias it.
+///
+/// Which instructions read and write memory is decided as for the
+/// [`DataDependenceGraph`](struct.DataDependenceGraph.html). Instructions in
+/// unreachable blocks are not included.
+///
+/// To construct a `MemorySSA`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct MemorySSA<'m> {
+    /// The function
+    function: &'m Function,
+    /// The defining access of each memory def and use
+    defining: HashMap<InstructionLocation<'m>, MemoryDefinition<'m>>,
+    /// The memory defs (every other location in `defining` is a memory use)
+    defs: HashSet<InstructionLocation<'m>>,
+    /// The incoming (predecessor, version) pairs of each memory `phi`
+    phis: HashMap<&'m Name, Vec<(&'m Name, MemoryDefinition<'m>)>>,
+    /// The version of memory on exit from each reachable block
+    exit_versions: HashMap<&'m Name, MemoryDefinition<'m>>,
+    /// The clobbering definition of each memory def and use
+    clobbers: HashMap<InstructionLocation<'m>, MemoryDefinition<'m>>,
+}
+
+impl<'m> MemorySSA<'m> {
+    pub(crate) fn new(
+        function: &'m Function,
+        cfg: &ControlFlowGraph<'m>,
+        domtree: &DominatorTree<'m>,
+        alias_analysis: &dyn AliasAnalysis<'m>,
+    ) -> Self {
+        let inst_defs: HashMap<&'m Name, &'m Instruction> = function
+            .basic_blocks
+            .iter()
+            .flat_map(|bb| bb.instrs.iter())
+            .filter_map(|inst| inst.try_get_result().map(|dest| (dest, inst)))
+            .collect();
+        // reachable blocks, in dominator-tree preorder
+        let blocks: Vec<&'m Name> = domtree
+            .preorder()
+            .filter_map(|node| match node {
+                CFGNode::Block(block) => Some(block),
+                CFGNode::Return => None,
+            })
+            .collect();
+        let reachable: HashSet<&'m Name> = blocks.iter().copied().collect();
+
+        // the memory accesses in each block, as (location, access)
+        let mut accesses: HashMap<&'m Name, Vec<(InstructionLocation<'m>, MemoryAccess<'m>)>> =
+            HashMap::new();
+        for &block in &blocks {
+            let bb = function.get_bb_by_name(block).unwrap_or_else(|| {
+                panic!(
+                    "Failed to find block {} in function {}",
+                    block, function.name
+                )
+            });
+            let insts = bb
+                .instrs
+                .iter()
+                .map(Either::Left)
+                .chain(std::iter::once(Either::Right(&bb.term)));
+            let block_accesses = insts
+                .enumerate()
+                .filter_map(|(index, inst)| {
+                    MemoryAccess::of(inst, &inst_defs)
+                        .map(|access| (InstructionLocation { block, index }, access))
+                })
+                .collect();
+            accesses.insert(block, block_accesses);
+        }
+
+        // place memory `phi`s at the iterated dominance frontier of the blocks
+        // containing memory defs
+        let frontiers = dominance_frontiers(cfg, domtree, &blocks, &reachable);
+        let mut phi_blocks: HashSet<&'m Name> = HashSet::new();
+        let mut worklist: Vec<&'m Name> = blocks
+            .iter()
+            .copied()
+            .filter(|block| accesses[block].iter().any(|(_, access)| access.writes))
+            .collect();
+        while let Some(block) = worklist.pop() {
+            for &frontier in frontiers.get(block).into_iter().flatten() {
+                if phi_blocks.insert(frontier) {
+                    worklist.push(frontier);
+                }
+            }
+        }
+
+        // link each access to its defining access, visiting blocks in
+        // dominator-tree preorder so that each block's immediate dominator is
+        // visited before it
+        let mut defining = HashMap::new();
+        let mut defs = HashSet::new();
+        let mut exit_versions: HashMap<&'m Name, MemoryDefinition<'m>> = HashMap::new();
+        for &block in &blocks {
+            let mut version = if phi_blocks.contains(block) {
+                MemoryDefinition::Phi(block)
+            } else {
+                match domtree.idom(block) {
+                    Some(idom) => exit_versions[idom],
+                    None => MemoryDefinition::LiveOnEntry,
+                }
+            };
+            for (location, access) in &accesses[block] {
+                defining.insert(*location, version);
+                if access.writes {
+                    defs.insert(*location);
+                    version = MemoryDefinition::Def(*location);
+                }
+            }
+            exit_versions.insert(block, version);
+        }
+        let phis = phi_blocks
+            .iter()
+            .map(|&block| {
+                let incoming = cfg
+                    .preds(block)
+                    .filter(|pred| reachable.contains(pred))
+                    .map(|pred| (pred, exit_versions[pred]))
+                    .collect();
+                (block, incoming)
+            })
+            .collect();
+
+        let mut mssa = Self {
+            function,
+            defining,
+            defs,
+            phis,
+            exit_versions,
+            clobbers: HashMap::new(),
+        };
+        let addresses: HashMap<InstructionLocation<'m>, Option<&'m Operand>> = accesses
+            .values()
+            .flatten()
+            .map(|(location, access)| (*location, access.address))
+            .collect();
+        let clobbers = addresses
+            .iter()
+            .map(|(&location, &address)| {
+                let defining = mssa.defining[&location];
+                let clobber = match address {
+                    Some(address) => mssa
+                        .walk(
+                            defining,
+                            address,
+                            &addresses,
+                            alias_analysis,
+                            &mut HashMap::new(),
+                            &mut HashMap::new(),
+                        )
+                        .0
+                        .unwrap_or(defining),
+                    None => defining,
+                };
+                (location, clobber)
+            })
+            .collect();
+        mssa.clobbers = clobbers;
+        mssa
+    }
+
+    /// Walk up from `def` to the nearest definition which may write memory
+    /// aliasing `address`. Returns `None` if every path leads back to a `phi`
+    /// on the current path.
+    ///
+    /// `on_path` maps each `phi` on the current path to its depth on the path.
+    /// The walk also returns the smallest depth of such a `phi` which it
+    /// reached (or `usize::MAX`); a `phi`'s result which doesn't depend on any
+    /// `phi` above it on the path is saved in `memo`, so that it's the same
+    /// however the `phi` is reached, and each `phi` is walked from only once.
+    fn walk(
+        &self,
+        mut def: MemoryDefinition<'m>,
+        address: &'m Operand,
+        addresses: &HashMap<InstructionLocation<'m>, Option<&'m Operand>>,
+        alias_analysis: &dyn AliasAnalysis<'m>,
+        on_path: &mut HashMap<&'m Name, usize>,
+        memo: &mut HashMap<&'m Name, Option<MemoryDefinition<'m>>>,
+    ) -> (Option<MemoryDefinition<'m>>, usize) {
+        loop {
+            match def {
+                MemoryDefinition::LiveOnEntry => return (Some(def), usize::MAX),
+                MemoryDefinition::Def(location) => {
+                    let may_alias = match addresses[&location] {
+                        Some(other) => alias_analysis.may_alias(address, other),
+                        None => true,
+                    };
+                    if may_alias {
+                        return (Some(def), usize::MAX);
+                    }
+                    def = self.defining[&location];
+                }
+                MemoryDefinition::Phi(block) => {
+                    if let Some(&clobber) = memo.get(block) {
+                        return (clobber, usize::MAX);
+                    }
+                    if let Some(&depth) = on_path.get(block) {
+                        return (None, depth);
+                    }
+                    let depth = on_path.len();
+                    on_path.insert(block, depth);
+                    let mut clobbers: Vec<MemoryDefinition<'m>> = Vec::new();
+                    let mut lowest = usize::MAX;
+                    for &(_, incoming) in &self.phis[block] {
+                        let (clobber, low) =
+                            self.walk(incoming, address, addresses, alias_analysis, on_path, memo);
+                        lowest = lowest.min(low);
+                        if let Some(clobber) = clobber {
+                            if !clobbers.contains(&clobber) {
+                                clobbers.push(clobber);
+                            }
+                        }
+                    }
+                    on_path.remove(block);
+                    // the `phi` is its own clobber whenever the clobbers of its
+                    // incoming versions differ
+                    let clobber = match clobbers.as_slice() {
+                        [] => None,
+                        [clobber] => Some(*clobber),
+                        _ => Some(def),
+                    };
+                    if lowest >= depth {
+                        memo.insert(block, clobber);
+                        lowest = usize::MAX;
+                    }
+                    return (clobber, lowest);
+                }
+            }
+        }
+    }
+
+    /// Get the defining access of the memory def or use at the given location:
+    /// the version of memory it reads, or for a def, overwrites. Returns `None`
+    /// if the instruction doesn't access memory (or is unreachable).
+    pub fn defining_access(&self, inst: InstructionLocation<'m>) -> Option<MemoryDefinition<'m>> {
+        self.defining.get(&inst).copied()
+    }
+
+    /// Get the nearest definition above the memory def or use at the given
+    /// location which may write memory it accesses, according to the
+    /// configured [`AliasAnalysis`](trait.AliasAnalysis.html). Where versions
+    /// of memory with different clobbers merge, this is the memory `phi`.
+    /// Returns `None` if the instruction doesn't access memory (or is
+    /// unreachable).
+    pub fn clobbering_definition(
+        &self,
+        inst: InstructionLocation<'m>,
+    ) -> Option<MemoryDefinition<'m>> {
+        self.clobbers.get(&inst).copied()
+    }
+
+    /// Is the instruction at the given location a memory def, i.e., may it
+    /// write memory?
+    pub fn is_memory_def(&self, inst: InstructionLocation<'m>) -> bool {
+        self.defs.contains(&inst)
+    }
+
+    /// Is the instruction at the given location a memory use, i.e., may it
+    /// read, but not write, memory?
+    pub fn is_memory_use(&self, inst: InstructionLocation<'m>) -> bool {
+        self.defining.contains_key(&inst) && !self.defs.contains(&inst)
+    }
+
+    /// Get the incoming (predecessor, version of memory) pairs of the memory
+    /// `phi` at the start of the block with the given `Name`, or `None` if the
+    /// block has no memory `phi`
+    pub fn phi(&self, block: &Name) -> Option<&[(&'m Name, MemoryDefinition<'m>)]> {
+        self.phis.get(block).map(|incoming| incoming.as_slice())
+    }
+
+    /// Iterate over the blocks which have a memory `phi`
+    pub fn phi_blocks<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
+        self.phis.keys().copied()
+    }
+
+    /// Get the version of memory on exit from the block with the given `Name`.
+    ///
+    /// Panics if the block isn't in this function, or is unreachable.
+    pub fn exit_version(&self, block: &Name) -> MemoryDefinition<'m> {
+        *self.exit_versions.get(block).unwrap_or_else(|| {
+            panic!(
+                "exit_version(): block {} not found (or unreachable) in function {}",
+                block, self.function.name
+            )
+        })
+    }
+
+    /// Get the memory defs and uses whose defining access is the given
+    /// definition, sorted. This doesn't include memory `phi`s; see
+    /// [`phi()`](#method.phi).
+    pub fn uses(&self, def: MemoryDefinition<'m>) -> Vec<InstructionLocation<'m>> {
+        let mut uses: Vec<_> = self
+            .defining
+            .iter()
+            .filter(|&(_, &defining)| defining == def)
+            .map(|(&location, _)| location)
+            .collect();
+        uses.sort_unstable();
+        uses
+    }
+}
+
+/// Compute the dominance frontier of each reachable block, using the algorithm
+/// of Cooper, Harvey, and Kennedy
+fn dominance_frontiers<'m>(
+    cfg: &ControlFlowGraph<'m>,
+    domtree: &DominatorTree<'m>,
+    blocks: &[&'m Name],
+    reachable: &HashSet<&'m Name>,
+) -> HashMap<&'m Name, HashSet<&'m Name>> {
+    let mut frontiers: HashMap<&'m Name, HashSet<&'m Name>> = HashMap::new();
+    for &block in blocks {
+        let preds: Vec<&'m Name> = cfg
+            .preds(block)
+            .filter(|pred| reachable.contains(pred))
+            .collect();
+        if preds.len() < 2 {
+            continue;
+        }
+        let idom = domtree.idom(block);
+        for pred in preds {
+            let mut runner = Some(pred);
+            while let Some(r) = runner {
+                if Some(r) == idom {
+                    break;
+                }
+                frontiers.entry(r).or_default().insert(block);
+                runner = domtree.idom(r);
+            }
+        }
+    }
+    frontiers
+}
@@ -0,0 +1,464 @@
+use crate::alias::{underlying_object, MemoryObject};
+use crate::call_graph::{direct_callee, functions_with_names, CallGraph, FunctionName};
+use either::Either;
+use llvm_ir::{
+    function::{FunctionAttribute, ParameterAttribute},
+    instruction::InlineAssembly,
+    Constant, Function, Instruction, Module, Name, Operand, Terminator,
+};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Summarizes, for each function in the analyzed `Module`(s), which memory it
+/// may modify (write) and which it may reference (read), including
+/// transitively through the functions it calls.
+///
+/// Memory is described in terms the function's callers can use: globals, and
+/// memory pointed to by the function's pointer parameters. Accesses through
+/// pointers based on neither (e.g., pointers loaded from memory, or returned
+/// by calls) are recorded as accesses of unknown memory. Accesses of the
+/// function's own `alloca`s aren't visible to its callers, and are ignored.
+/// A pointer is based on whatever it's computed from by `getelementptr`s,
+/// casts, `phi`s, and `select`s.
+///
+/// At each call site, the summaries of the functions which may be called
+/// there are translated into the caller's terms, by replacing each parameter
+/// of the callee with whatever the corresponding argument is based on. The
+/// call site's function attributes (e.g., `readonly` or `argmemonly`) further
+/// restrict what it may access. Functions which are only declared in the
+/// analyzed `Module`(s) may modify and reference unknown memory, except for
+/// the `llvm.memcpy`, `llvm.memmove`, and `llvm.memset` intrinsics, which
+/// only access memory pointed to by their arguments, and the `llvm.dbg` and
+/// `llvm.lifetime` intrinsics, which access no memory. Calls of garbage
+/// collection statepoints, which pass their arguments on to some other
+/// function, may modify and reference unknown memory.
+///
+/// This analysis conservatively assumes that function pointers may point to
+/// any function in the analyzed `Module`(s) that has the appropriate type
+/// (see [`CallGraph`](struct.CallGraph.html)).
+///
+/// To construct a `ModRefAnalysis`, use [`ModuleAnalysis`](struct.ModuleAnalysis.html)
+/// or [`CrossModuleAnalysis`](struct.CrossModuleAnalysis.html).
+pub struct ModRefAnalysis<'m> {
+    /// Map from the name of each function in the analyzed `Module`(s) to its
+    /// summary
+    summaries: HashMap<FunctionName<'m>, Summary<'m>>,
+}
+
+/// The memory which a function may modify, or may reference; see
+/// [`ModRefAnalysis`](struct.ModRefAnalysis.html)
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ModRefSet<'m> {
+    /// Names of the globals which may be accessed
+    pub globals: BTreeSet<&'m Name>,
+    /// Indices of the function's parameters whose pointed-to memory may be
+    /// accessed
+    pub parameters: BTreeSet<usize>,
+    /// Whether memory not known to be based on a global or a parameter may
+    /// be accessed. Such memory may include any of the globals, or the memory
+    /// pointed to by any of the parameters.
+    pub unknown: bool,
+}
+
+impl<'m> ModRefSet<'m> {
+    /// Is no memory accessed at all?
+    pub fn is_empty(&self) -> bool {
+        self.globals.is_empty() && self.parameters.is_empty() && !self.unknown
+    }
+
+    /// May the global with the given name be accessed, either directly or
+    /// through unknown memory?
+    pub fn may_access_global(&self, global: &Name) -> bool {
+        self.unknown || self.globals.contains(global)
+    }
+
+    /// May the memory pointed to by the parameter with the given index be
+    /// accessed, either directly or through unknown memory?
+    pub fn may_access_parameter(&self, index: usize) -> bool {
+        self.unknown || self.parameters.contains(&index)
+    }
+
+    /// Add everything in `other` to this set, returning whether anything was
+    /// added
+    fn union(&mut self, other: &Self) -> bool {
+        let len = self.globals.len() + self.parameters.len();
+        let unknown = self.unknown;
+        self.globals.extend(other.globals.iter().copied());
+        self.parameters.extend(other.parameters.iter().copied());
+        self.unknown |= other.unknown;
+        self.globals.len() + self.parameters.len() != len || self.unknown != unknown
+    }
+
+    /// The set which is everything unknown
+    fn unknown() -> Self {
+        Self {
+            unknown: true,
+            ..Self::default()
+        }
+    }
+}
+
+/// The memory a function (or call) may modify and reference
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+struct Summary<'m> {
+    mod_set: ModRefSet<'m>,
+    ref_set: ModRefSet<'m>,
+}
+
+impl<'m> Summary<'m> {
+    /// The summary of a function which is only declared
+    fn of_declaration(func_name: FunctionName) -> Self {
+        let func_name = func_name.as_named().unwrap_or_default();
+        let parameters = |indices: &[usize]| ModRefSet {
+            parameters: indices.iter().copied().collect(),
+            ..ModRefSet::default()
+        };
+        if func_name.starts_with("llvm.dbg.") || func_name.starts_with("llvm.lifetime.") {
+            Self::default()
+        } else if func_name.starts_with("llvm.memcpy.") || func_name.starts_with("llvm.memmove.") {
+            Self {
+                mod_set: parameters(&[0]),
+                ref_set: parameters(&[1]),
+            }
+        } else if func_name.starts_with("llvm.memset.") {
+            Self {
+                mod_set: parameters(&[0]),
+                ref_set: ModRefSet::default(),
+            }
+        } else {
+            Self {
+                mod_set: ModRefSet::unknown(),
+                ref_set: ModRefSet::unknown(),
+            }
+        }
+    }
+
+    fn union(&mut self, other: &Self) -> bool {
+        let modified = self.mod_set.union(&other.mod_set);
+        self.ref_set.union(&other.ref_set) || modified
+    }
+}
+
+impl<'m> ModRefAnalysis<'m> {
+    pub(crate) fn new(
+        modules: impl IntoIterator<Item = &'m Module>,
+        call_graph: &CallGraph<'m>,
+    ) -> Self {
+        // several functions may share a name (e.g., internal functions of
+        // different `Module`s), so their summaries are combined
+        let mut functions: HashMap<FunctionName<'m>, Vec<(&'m Function, &'m Module)>> =
+            HashMap::new();
+        for module in modules {
+            for (f, name) in functions_with_names(module) {
+                functions.entry(name).or_default().push((f, module));
+            }
+        }
+
+        let callees = call_graph.callees_by_site();
+        let mut summaries: HashMap<FunctionName<'m>, Summary<'m>> = call_graph
+            .graph
+            .nodes()
+            .filter(|func_name| !functions.contains_key(func_name))
+            .map(|func_name| (func_name, Summary::of_declaration(func_name)))
+            .collect();
+        for scc in call_graph.sccs_bottom_up() {
+            for &func_name in &scc {
+                if functions.contains_key(&func_name) {
+                    summaries.insert(func_name, Summary::default());
+                }
+            }
+            loop {
+                let mut changed = false;
+                for &func_name in &scc {
+                    for &(f, module) in functions.get(&func_name).into_iter().flatten() {
+                        let summary = summarize(f, module, func_name, &callees, &summaries);
+                        changed |= summaries
+                            .get_mut(&func_name)
+                            .expect("should have a summary for each function in the SCC")
+                            .union(&summary);
+                    }
+                }
+                if !changed {
+                    break;
+                }
+            }
+        }
+
+        Self { summaries }
+    }
+
+    /// Get the memory which the function with the given name may modify,
+    /// directly or through the functions it calls.
+    ///
+    /// Panics if the function is not found in the analyzed `Module`(s).
+    pub fn mod_set(&self, func_name: impl Into<FunctionName<'m>>) -> &ModRefSet<'m> {
+        &self.summary("mod_set", func_name.into()).mod_set
+    }
+
+    /// Get the memory which the function with the given name may reference,
+    /// directly or through the functions it calls.
+    ///
+    /// Panics if the function is not found in the analyzed `Module`(s).
+    pub fn ref_set(&self, func_name: impl Into<FunctionName<'m>>) -> &ModRefSet<'m> {
+        &self.summary("ref_set", func_name.into()).ref_set
+    }
+
+    fn summary(&self, method: &str, func_name: FunctionName<'m>) -> &Summary<'m> {
+        self.summaries.get(&func_name).unwrap_or_else(|| {
+            panic!(
+                "{}(): function named {:?} not found in the Module(s)",
+                method, func_name
+            )
+        })
+    }
+}
+
+/// Compute the summary of the given function, which is defined in `module` and
+/// has the given name (see `functions_with_names()`), given the current
+/// summaries of the functions it may call
+fn summarize<'m>(
+    f: &'m Function,
+    module: &'m Module,
+    func_name: FunctionName<'m>,
+    callees: &HashMap<(FunctionName<'m>, &'m Name, usize), Vec<FunctionName<'m>>>,
+    summaries: &HashMap<FunctionName<'m>, Summary<'m>>,
+) -> Summary<'m> {
+    let bases = Bases::new(f, module);
+    let mut summary = Summary::default();
+    for bb in &f.basic_blocks {
+        for (index, inst) in bb.instrs.iter().enumerate() {
+            match inst {
+                Instruction::Load(load) => bases.add(&load.address, &mut summary.ref_set),
+                Instruction::Store(store) => bases.add(&store.address, &mut summary.mod_set),
+                Instruction::CmpXchg(cmpxchg) => {
+                    bases.add(&cmpxchg.address, &mut summary.mod_set);
+                    bases.add(&cmpxchg.address, &mut summary.ref_set);
+                }
+                Instruction::AtomicRMW(rmw) => {
+                    bases.add(&rmw.address, &mut summary.mod_set);
+                    bases.add(&rmw.address, &mut summary.ref_set);
+                }
+                Instruction::VAArg(va_arg) => {
+                    bases.add(&va_arg.arg_list, &mut summary.mod_set);
+                    bases.add(&va_arg.arg_list, &mut summary.ref_set);
+                }
+                Instruction::Call(call) => {
+                    let site_callees = callees.get(&(func_name, &bb.name, index));
+                    summary.union(&call_summary(
+                        &call.function,
+                        &call.arguments,
+                        &call.function_attributes,
+                        site_callees.map(Vec::as_slice),
+                        summaries,
+                        &bases,
+                    ));
+                }
+                _ => {}
+            }
+        }
+        let call = match &bb.term {
+            Terminator::Invoke(invoke) => Some((
+                &invoke.function,
+                &invoke.arguments,
+                &invoke.function_attributes,
+            )),
+            Terminator::CallBr(callbr) => Some((
+                &callbr.function,
+                &callbr.arguments,
+                &callbr.function_attributes,
+            )),
+            _ => None,
+        };
+        if let Some((function, arguments, attributes)) = call {
+            let site_callees = callees.get(&(func_name, &bb.name, bb.instrs.len()));
+            summary.union(&call_summary(
+                function,
+                arguments,
+                attributes,
+                site_callees.map(Vec::as_slice),
+                summaries,
+                &bases,
+            ));
+        }
+    }
+    summary
+}
+
+/// Compute the summary of a call, in the caller's terms
+fn call_summary<'m>(
+    function: &'m Either<InlineAssembly, Operand>,
+    arguments: &'m [(Operand, Vec<ParameterAttribute>)],
+    attributes: &[FunctionAttribute],
+    callees: Option<&[FunctionName<'m>]>,
+    summaries: &HashMap<FunctionName<'m>, Summary<'m>>,
+    bases: &Bases<'m>,
+) -> Summary<'m> {
+    let mut summary = Summary::default();
+    let is_statepoint = function
+        .as_ref()
+        .right()
+        .and_then(direct_callee)
+        .is_some_and(|name| name.starts_with("llvm.experimental.gc."));
+    match callees {
+        Some(callees) if !is_statepoint => {
+            // translate each callee's summary through the arguments
+            let translate = |set: &ModRefSet<'m>, translated: &mut ModRefSet<'m>| {
+                translated.globals.extend(set.globals.iter().copied());
+                translated.unknown |= set.unknown;
+                for &index in &set.parameters {
+                    if let Some((arg, _)) = arguments.get(index) {
+                        bases.add(arg, translated);
+                    }
+                }
+            };
+            for callee in callees {
+                let callee_summary = &summaries[callee];
+                translate(&callee_summary.mod_set, &mut summary.mod_set);
+                translate(&callee_summary.ref_set, &mut summary.ref_set);
+            }
+        }
+        // inline assembly, statepoints, and calls of function pointers which
+        // may not point to any function in the analyzed `Module`(s)
+        _ => {
+            summary.mod_set = ModRefSet::unknown();
+            summary.ref_set = ModRefSet::unknown();
+        }
+    }
+
+    let (reads, writes, argmem_only) = attribute_effects(attributes);
+    if !reads {
+        summary.ref_set = ModRefSet::default();
+    }
+    if !writes {
+        summary.mod_set = ModRefSet::default();
+    }
+    if argmem_only {
+        // unknown memory can only be what the arguments point to
+        let mut argmem = ModRefSet::default();
+        for (arg, _) in arguments {
+            bases.add(arg, &mut argmem);
+        }
+        for set in [&mut summary.mod_set, &mut summary.ref_set] {
+            if set.unknown {
+                set.unknown = false;
+                set.union(&argmem);
+            }
+        }
+    }
+    summary
+}
+
+/// Whether a call may read and write memory, respectively, and whether it
+/// may only access memory pointed to by its arguments, according to its
+/// function attributes
+fn attribute_effects(attributes: &[FunctionAttribute]) -> (bool, bool, bool) {
+    let (mut reads, mut writes, mut argmem_only) = (true, true, false);
+    for attribute in attributes {
+        match attribute {
+            // memory which isn't accessible to the analyzed `Module`(s) can't
+            // be a global or the memory pointed to by a parameter
+            FunctionAttribute::ReadNone | FunctionAttribute::InaccessibleMemOnly => {
+                return (false, false, false)
+            }
+            FunctionAttribute::ReadOnly => writes = false,
+            FunctionAttribute::WriteOnly => reads = false,
+            FunctionAttribute::ArgMemOnly | FunctionAttribute::InaccessibleMemOrArgMemOnly => {
+                argmem_only = true
+            }
+            #[cfg(feature = "llvm-16-or-greater")]
+            FunctionAttribute::Memory {
+                default, argmem, ..
+            } => {
+                use llvm_ir::function::MemoryEffect;
+                let effects = [default, argmem];
+                reads = effects
+                    .iter()
+                    .any(|e| matches!(e, MemoryEffect::Read | MemoryEffect::ReadWrite));
+                writes = effects
+                    .iter()
+                    .any(|e| matches!(e, MemoryEffect::Write | MemoryEffect::ReadWrite));
+                argmem_only = matches!(default, MemoryEffect::None);
+            }
+            _ => {}
+        }
+    }
+    (reads, writes, argmem_only)
+}
+
+/// Finds what the pointers in a particular function are based on
+struct Bases<'m> {
+    /// The instruction defining each local value
+    defs: HashMap<&'m Name, &'m Instruction>,
+    /// The index of each parameter
+    parameters: HashMap<&'m Name, usize>,
+    /// The `Module` containing the function, for resolving `GlobalAlias`es
+    module: &'m Module,
+}
+
+impl<'m> Bases<'m> {
+    fn new(f: &'m Function, module: &'m Module) -> Self {
+        Self {
+            module,
+            defs: f
+                .basic_blocks
+                .iter()
+                .flat_map(|bb| &bb.instrs)
+                .filter_map(|inst| inst.try_get_result().map(|dest| (dest, inst)))
+                .collect(),
+            parameters: f
+                .parameters
+                .iter()
+                .enumerate()
+                .map(|(i, param)| (&param.name, i))
+                .collect(),
+        }
+    }
+
+    /// Add the globals and parameters which the given pointer is based on to
+    /// `set`, or mark it unknown if the pointer may be based on something
+    /// else (other than an `alloca`)
+    fn add(&self, pointer: &'m Operand, set: &mut ModRefSet<'m>) {
+        let mut visited: HashSet<&'m Name> = HashSet::new();
+        let mut worklist = vec![pointer];
+        while let Some(pointer) = worklist.pop() {
+            let name = match pointer {
+                Operand::LocalOperand { name, .. } => name,
+                Operand::ConstantOperand(constant) => {
+                    match underlying_object(pointer, &self.defs, Some(self.module)) {
+                        Some(MemoryObject::Global(global)) => {
+                            set.globals.insert(global);
+                        }
+                        _ => match constant.as_ref() {
+                            Constant::Null(_) | Constant::Undef(_) => {}
+                            #[cfg(feature = "llvm-12-or-greater")]
+                            Constant::Poison(_) => {}
+                            _ => set.unknown = true,
+                        },
+                    }
+                    continue;
+                }
+                Operand::MetadataOperand => continue,
+            };
+            if !visited.insert(name) {
+                continue;
+            }
+            if let Some(&index) = self.parameters.get(name) {
+                set.parameters.insert(index);
+                continue;
+            }
+            match self.defs.get(name) {
+                Some(Instruction::Alloca(_)) => {}
+                Some(Instruction::GetElementPtr(gep)) => worklist.push(&gep.address),
+                Some(Instruction::BitCast(bitcast)) => worklist.push(&bitcast.operand),
+                Some(Instruction::AddrSpaceCast(cast)) => worklist.push(&cast.operand),
+                Some(Instruction::Phi(phi)) => {
+                    worklist.extend(phi.incoming_values.iter().map(|(value, _)| value))
+                }
+                Some(Instruction::Select(select)) => {
+                    worklist.push(&select.true_value);
+                    worklist.push(&select.false_value);
+                }
+                _ => set.unknown = true,
+            }
+        }
+    }
+}
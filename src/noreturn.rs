@@ -0,0 +1,390 @@
+use crate::call_graph::{direct_callee, functions_with_names, CallGraph, FunctionName};
+use crate::control_flow_graph::{CFGEdgeKind, CFGNode, ControlFlowGraph, ExitKind};
+use either::Either;
+use llvm_ir::{
+    function::FunctionAttribute, instruction::InlineAssembly, BasicBlock, Function, Instruction,
+    Module, Name, Operand, Terminator,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Names of well-known functions which never return
+const NORETURN_FUNCS: &[&str] = &[
+    "abort",
+    "exit",
+    "_exit",
+    "_Exit",
+    "quick_exit",
+    "pthread_exit",
+    "thrd_exit",
+    "__assert_fail",
+    "__assert_rtn",
+    "_wassert",
+    "__stack_chk_fail",
+    "__cxa_throw",
+    "__cxa_rethrow",
+    "__cxa_bad_cast",
+    "__cxa_bad_typeid",
+    "_Unwind_Resume",
+    "longjmp",
+    "_longjmp",
+    "siglongjmp",
+    "__longjmp_chk",
+    "err",
+    "errx",
+    "verr",
+    "verrx",
+    "llvm.trap",
+];
+
+/// Identifies the functions in the analyzed `Module`(s) which never return
+/// normally, whether or not they're marked `noreturn`.
+///
+/// A function never returns if none of its `ret`s can be reached: e.g., every
+/// path through it ends in `unreachable`, an infinite loop, or a call of a
+/// function which never returns. (It may still unwind.) This is inferred
+/// bottom-up over the [`CallGraph`](struct.CallGraph.html), assuming that
+/// (mutually) recursive functions never return unless shown otherwise, so
+/// that, e.g., a function which unconditionally calls itself never returns.
+///
+/// Functions and calls marked `noreturn` are trusted never to return.
+/// Functions which are only declared in the analyzed `Module`(s) are assumed
+/// to return, unless they're well-known functions such as `exit`, `abort`,
+/// or `__cxa_throw`. Calls through function pointers never return if every
+/// function they may call never returns (see
+/// [`CallGraph`](struct.CallGraph.html)), and there is at least one.
+///
+/// A call of a function which never returns effectively ends its block, but
+/// clang only follows such calls with `unreachable` if the callee is marked
+/// `noreturn`; so calls of, e.g., panic wrappers without the attribute make
+/// the rest of their blocks look reachable, and pollute postdominance. See
+/// [`cfg_with_noreturn_calls()`](#method.cfg_with_noreturn_calls) for a CFG
+/// which corrects this.
+///
+/// To construct a `NoReturnAnalysis`, use [`ModuleAnalysis`](struct.ModuleAnalysis.html)
+/// or [`CrossModuleAnalysis`](struct.CrossModuleAnalysis.html).
+pub struct NoReturnAnalysis<'m> {
+    /// Map from the name of each function in the analyzed `Module`(s) to
+    /// whether it never returns
+    noreturn: HashMap<FunctionName<'m>, bool>,
+    /// Map from the name of each function defined in the analyzed
+    /// `Module`(s) to that `Function` (or the first, if several share the name)
+    functions: HashMap<FunctionName<'m>, &'m Function>,
+    /// Map from the name of each function defined in the analyzed
+    /// `Module`(s) to its calls which never return, as pairs of (block name,
+    /// index of the call within the block), in order
+    noreturn_calls: HashMap<FunctionName<'m>, Vec<(&'m Name, usize)>>,
+}
+
+impl<'m> NoReturnAnalysis<'m> {
+    pub(crate) fn new(
+        modules: impl IntoIterator<Item = &'m Module>,
+        call_graph: &CallGraph<'m>,
+    ) -> Self {
+        // several functions may share a name (e.g., internal functions of
+        // different `Module`s); such a name never returns if none of them do
+        let mut grouped: HashMap<FunctionName<'m>, Vec<(&'m Function, ControlFlowGraph<'m>)>> =
+            HashMap::new();
+        for module in modules {
+            for (f, name) in functions_with_names(module) {
+                let cfg = ControlFlowGraph::with_exits(f, &[ExitKind::Return]);
+                grouped.entry(name).or_default().push((f, cfg));
+            }
+        }
+        let callees = call_graph.callees_by_site();
+
+        let mut noreturn: HashMap<FunctionName<'m>, bool> = call_graph
+            .graph
+            .nodes()
+            .filter(|func_name| !grouped.contains_key(func_name))
+            .map(|func_name| {
+                let is_noreturn = func_name
+                    .as_named()
+                    .is_some_and(|name| NORETURN_FUNCS.contains(&name));
+                (func_name, is_noreturn)
+            })
+            .collect();
+        for scc in call_graph.sccs_bottom_up() {
+            // optimistically assume the functions in the SCC never return,
+            // until one of their `ret`s is found to be reachable
+            for &func_name in &scc {
+                if grouped.contains_key(&func_name) {
+                    noreturn.insert(func_name, true);
+                }
+            }
+            loop {
+                let mut changed = false;
+                for &func_name in &scc {
+                    let Some(funcs) = grouped.get(&func_name) else {
+                        continue;
+                    };
+                    if !noreturn[&func_name] {
+                        continue;
+                    }
+                    let returns = funcs.iter().any(|(f, cfg)| {
+                        !f.function_attributes.contains(&FunctionAttribute::NoReturn)
+                            && may_return(f, func_name, cfg, &callees, &noreturn)
+                    });
+                    if returns {
+                        noreturn.insert(func_name, false);
+                        changed = true;
+                    }
+                }
+                if !changed {
+                    break;
+                }
+            }
+        }
+
+        // for names shared by several functions, only the first one's calls
+        // are recorded
+        let mut functions = HashMap::new();
+        let mut noreturn_calls = HashMap::new();
+        for (&func_name, funcs) in &grouped {
+            let (f, _) = funcs[0];
+            let calls: Vec<(&'m Name, usize)> = f
+                .basic_blocks
+                .iter()
+                .flat_map(|bb| {
+                    calls_in_block(bb)
+                        .filter(|(index, call)| {
+                            let site_callees = callees.get(&(func_name, &bb.name, *index));
+                            call.never_returns(site_callees, &noreturn)
+                        })
+                        .map(move |(index, _)| (&bb.name, index))
+                })
+                .collect();
+            functions.insert(func_name, f);
+            noreturn_calls.insert(func_name, calls);
+        }
+
+        Self {
+            noreturn,
+            functions,
+            noreturn_calls,
+        }
+    }
+
+    /// Does the function with the given name never return normally?
+    ///
+    /// Panics if the function is not found in the analyzed `Module`(s).
+    pub fn is_noreturn<'n>(&self, func_name: impl Into<FunctionName<'n>>) -> bool {
+        let func_name = func_name.into();
+        *self.noreturn.get(&func_name).unwrap_or_else(|| {
+            panic!(
+                "is_noreturn(): function named {:?} not found in the Module(s)",
+                func_name
+            )
+        })
+    }
+
+    /// Iterate over the names of all the functions in the analyzed
+    /// `Module`(s) which never return normally, in sorted order.
+    pub fn noreturn_functions(&self) -> impl Iterator<Item = FunctionName<'m>> {
+        let mut funcs: Vec<FunctionName<'m>> = self
+            .noreturn
+            .iter()
+            .filter(|&(_, &noreturn)| noreturn)
+            .map(|(&func_name, _)| func_name)
+            .collect();
+        funcs.sort_unstable();
+        funcs.into_iter()
+    }
+
+    /// Iterate over the names of the functions defined in the analyzed
+    /// `Module`(s) which never return normally, but aren't marked
+    /// `noreturn`, in sorted order.
+    pub fn inferred_noreturn_functions(&self) -> impl Iterator<Item = FunctionName<'m>> + '_ {
+        self.noreturn_functions().filter(move |func_name| {
+            self.functions
+                .get(func_name)
+                .is_some_and(|f| !f.function_attributes.contains(&FunctionAttribute::NoReturn))
+        })
+    }
+
+    /// Get the calls (and `invoke`s) in the function with the given name
+    /// which never return, as pairs of (block name, index of the call within
+    /// the block), in the order they appear in the function. If the call is
+    /// the block's terminator (i.e., an `invoke`), its index is equal to the
+    /// number of (non-terminator) instructions in the block.
+    ///
+    /// This is empty for functions which are only declared in the analyzed
+    /// `Module`(s).
+    pub fn noreturn_calls<'s>(
+        &'s self,
+        func_name: impl Into<FunctionName<'m>>,
+    ) -> impl Iterator<Item = (&'m Name, usize)> + 's {
+        self.noreturn_calls
+            .get(&func_name.into())
+            .into_iter()
+            .flatten()
+            .copied()
+    }
+
+    /// Get a `ControlFlowGraph` for the function with the given name, with
+    /// edges to `CFGNode::Return` for only the given kinds of exit (see
+    /// [`FunctionAnalysis::control_flow_graph_with_exits()`](struct.FunctionAnalysis.html#method.control_flow_graph_with_exits)),
+    /// in which each call which never returns ends its block.
+    ///
+    /// Blocks containing such calls are treated as if they ended in
+    /// `unreachable`: they have no successors (except the exceptional
+    /// destination of an `invoke` which never returns), and if
+    /// `ExitKind::Unreachable` is among the exits, an edge to
+    /// `CFGNode::Return` of kind `CFGEdgeKind::Unreachable`. To compute
+    /// postdominators or control dependencies of the result, pass it to
+    /// [`PostDominatorTree::new()`](struct.PostDominatorTree.html#method.new)
+    /// or
+    /// [`ControlDependenceGraph::new()`](struct.ControlDependenceGraph.html#method.new).
+    ///
+    /// Returns `None` if the function isn't defined in the analyzed
+    /// `Module`(s).
+    pub fn cfg_with_noreturn_calls(
+        &self,
+        func_name: impl Into<FunctionName<'m>>,
+        exits: &[ExitKind],
+    ) -> Option<ControlFlowGraph<'m>> {
+        let func_name = func_name.into();
+        let f = self.functions.get(&func_name)?;
+        // for each block with a call which never returns, whether that call
+        // is the block's `invoke`
+        let mut ends: HashMap<&'m Name, bool> = HashMap::new();
+        for (block, index) in self.noreturn_calls(func_name) {
+            let is_invoke = f
+                .get_bb_by_name(block)
+                .is_some_and(|bb| index == bb.instrs.len());
+            ends.entry(block).or_insert(is_invoke);
+        }
+        let mut cfg = ControlFlowGraph::with_exits(f, exits).filter(
+            |_| true,
+            |from, _, kind| match from {
+                CFGNode::Block(block) => match ends.get(block) {
+                    Some(&is_invoke) => is_invoke && *kind == CFGEdgeKind::InvokeException,
+                    None => true,
+                },
+                CFGNode::Return => true,
+            },
+        );
+        if exits.contains(&ExitKind::Unreachable) {
+            for block in f.basic_blocks.iter().map(|bb| &bb.name) {
+                if ends.contains_key(block) {
+                    cfg.add_edge(
+                        CFGNode::Block(block),
+                        CFGNode::Return,
+                        CFGEdgeKind::Unreachable,
+                    );
+                }
+            }
+        }
+        Some(cfg)
+    }
+}
+
+/// May any of the given function's `ret`s be reached, given its name and CFG
+/// (with only `ExitKind::Return` exits) and the current knowledge of which
+/// functions never return?
+fn may_return<'m>(
+    f: &'m Function,
+    func_name: FunctionName<'m>,
+    cfg: &ControlFlowGraph<'m>,
+    callees: &HashMap<(FunctionName<'m>, &'m Name, usize), Vec<FunctionName<'m>>>,
+    noreturn: &HashMap<FunctionName<'m>, bool>,
+) -> bool {
+    let mut visited: HashSet<&'m Name> = HashSet::new();
+    let mut worklist: Vec<&'m Name> = vec![cfg.entry()];
+    while let Some(block) = worklist.pop() {
+        if !visited.insert(block) {
+            continue;
+        }
+        let bb = f
+            .get_bb_by_name(block)
+            .expect("CFG blocks should be in the function");
+        let end = first_noreturn_call(func_name, bb, callees, noreturn);
+        for (succ, kind) in cfg.labeled_succs(block) {
+            let taken = match end {
+                None => true,
+                Some(index) => index == bb.instrs.len() && kind == CFGEdgeKind::InvokeException,
+            };
+            match succ {
+                _ if !taken => {}
+                CFGNode::Block(succ) => worklist.push(succ),
+                CFGNode::Return => return true,
+            }
+        }
+    }
+    false
+}
+
+/// Get the index within the given block of its first call (or `invoke`) which
+/// never returns, if any
+fn first_noreturn_call<'m>(
+    func_name: FunctionName<'m>,
+    bb: &'m BasicBlock,
+    callees: &HashMap<(FunctionName<'m>, &'m Name, usize), Vec<FunctionName<'m>>>,
+    noreturn: &HashMap<FunctionName<'m>, bool>,
+) -> Option<usize> {
+    calls_in_block(bb)
+        .find(|(index, call)| {
+            call.never_returns(callees.get(&(func_name, &bb.name, *index)), noreturn)
+        })
+        .map(|(index, _)| index)
+}
+
+/// A call or `invoke` of a function
+struct Call<'m> {
+    function: &'m Either<InlineAssembly, Operand>,
+    attributes: &'m [FunctionAttribute],
+}
+
+impl<'m> Call<'m> {
+    /// Does the call never return, given the functions which the call graph
+    /// says it may call (if any), and the current knowledge of which functions
+    /// never return?
+    fn never_returns(
+        &self,
+        callees: Option<&Vec<FunctionName>>,
+        noreturn: &HashMap<FunctionName, bool>,
+    ) -> bool {
+        if self.attributes.contains(&FunctionAttribute::NoReturn) {
+            return true;
+        }
+        let callee_noreturn = |name: &FunctionName| noreturn.get(name).copied().unwrap_or(false);
+        match (self.function, callees) {
+            (Either::Right(_), Some(callees)) => {
+                !callees.is_empty() && callees.iter().all(|name| callee_noreturn(name))
+            }
+            (Either::Right(callee), None) => {
+                direct_callee(callee).is_some_and(|name| NORETURN_FUNCS.contains(&name))
+            }
+            (Either::Left(_), _) => false, // inline assembly
+        }
+    }
+}
+
+/// Iterate over the calls (and `invoke`s) in the given basic block, yielding
+/// for each its index in the block
+fn calls_in_block(bb: &BasicBlock) -> impl Iterator<Item = (usize, Call<'_>)> {
+    let calls = bb
+        .instrs
+        .iter()
+        .enumerate()
+        .filter_map(|(index, inst)| match inst {
+            Instruction::Call(call) => Some((
+                index,
+                Call {
+                    function: &call.function,
+                    attributes: &call.function_attributes,
+                },
+            )),
+            _ => None,
+        });
+    let invoke = match &bb.term {
+        Terminator::Invoke(invoke) => Some((
+            bb.instrs.len(),
+            Call {
+                function: &invoke.function,
+                attributes: &invoke.function_attributes,
+            },
+        )),
+        _ => None,
+    };
+    calls.chain(invoke)
+}
@@ -0,0 +1,280 @@
+use crate::control_flow_graph::ControlFlowGraph;
+use crate::def_use::InstructionLocation;
+use crate::dominator_tree::DominatorTree;
+use either::Either;
+use llvm_ir::{Constant, Function, Instruction, IntPredicate, Name, Operand, Terminator};
+use std::collections::HashMap;
+
+/// For each pointer dereference in a particular function, the comparisons of
+/// that pointer against null which dominate it.
+///
+/// A null check is a conditional `br` on the result of an `icmp eq` or
+/// `icmp ne` of a pointer against `null`. It dominates a dereference if one of
+/// the branch's edges does (see
+/// [`DominatorTree::edge_dominates()`](struct.DominatorTree.html#method.edge_dominates)),
+/// in which case the pointer is known to be null, or known to be non-null, at
+/// the dereference. Pointers are compared after looking through `bitcast`s
+/// and `addrspacecast`s, so a check of `%p` applies to a dereference of
+/// `bitcast %p`, and vice versa.
+///
+/// A dereference with no dominating null check isn't necessarily unsafe (the
+/// pointer may be known non-null for other reasons, e.g., it's the address of
+/// an `alloca` or a global), and one with a dominating check isn't necessarily
+/// safe (e.g., the check may be of a different pointer derived from the same
+/// object); this is a syntactic check, meant for triage.
+///
+/// To construct a `NullChecks`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct NullChecks<'m> {
+    /// Every dereference in the function, in order
+    dereferences: Vec<Dereference<'m>>,
+    /// Index of each dereference in `dereferences`, by location
+    indices: HashMap<InstructionLocation<'m>, usize>,
+}
+
+/// A dereference of a pointer, and the null checks of that pointer which
+/// dominate it
+#[derive(Clone, PartialEq, Debug)]
+pub struct Dereference<'m> {
+    /// The instruction (or terminator) dereferencing the pointer
+    pub location: InstructionLocation<'m>,
+    /// How the pointer is dereferenced
+    pub kind: DereferenceKind,
+    /// The pointer being dereferenced
+    pub pointer: &'m Operand,
+    /// The null checks of the pointer which dominate the dereference, nearest
+    /// (i.e., last executed) first
+    pub checks: Vec<NullCheck<'m>>,
+}
+
+/// A way of dereferencing a pointer
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum DereferenceKind {
+    /// The pointer is the address of a `load`
+    Load,
+    /// The pointer is the address of a `store`
+    Store,
+    /// The pointer is the address of a `cmpxchg` or `atomicrmw`
+    Atomic,
+    /// The pointer is the callee of a `call` or `invoke` through a function
+    /// pointer
+    IndirectCall,
+}
+
+/// A comparison of a pointer against null, whose outcome is known at some
+/// point because a branch on it dominates that point
+#[derive(Clone, PartialEq, Debug)]
+pub struct NullCheck<'m> {
+    /// The block whose conditional `br` branches on the comparison
+    pub block: &'m Name,
+    /// The result of the `icmp` comparing the pointer against null
+    pub icmp: &'m Name,
+    /// The outcome of the branch which leads to the point: `true` for the
+    /// branch's true destination, `false` for its false destination
+    pub outcome: bool,
+    /// Whether the pointer is null on that outcome
+    pub is_null: bool,
+}
+
+impl<'m> Dereference<'m> {
+    /// Is the pointer known to be non-null at the dereference, because a null
+    /// check dominating it succeeded?
+    pub fn is_checked_nonnull(&self) -> bool {
+        self.checks.iter().any(|check| !check.is_null)
+    }
+
+    /// Is the pointer known to be null at the dereference, because a null
+    /// check dominating it failed?
+    pub fn is_checked_null(&self) -> bool {
+        self.checks.iter().any(|check| check.is_null)
+    }
+}
+
+/// A conditional branch on a null check
+struct CheckingBranch<'m> {
+    /// The underlying pointer being checked
+    pointer: &'m Operand,
+    icmp: &'m Name,
+    true_dest: &'m Name,
+    false_dest: &'m Name,
+    /// Whether the pointer is null if the condition is true
+    null_if_true: bool,
+}
+
+impl<'m> NullChecks<'m> {
+    pub(crate) fn new(
+        function: &'m Function,
+        cfg: &ControlFlowGraph<'m>,
+        domtree: &DominatorTree<'m>,
+    ) -> Self {
+        // operands of the casts in the function, to look through
+        let mut casts: HashMap<&'m Name, &'m Operand> = HashMap::new();
+        // pointer compared against null by each null-check `icmp`, and whether
+        // the `icmp` is true when the pointer is null
+        let mut icmps: HashMap<&'m Name, (&'m Operand, bool)> = HashMap::new();
+        for inst in function.basic_blocks.iter().flat_map(|bb| &bb.instrs) {
+            match inst {
+                Instruction::BitCast(bitcast) => {
+                    casts.insert(&bitcast.dest, &bitcast.operand);
+                }
+                Instruction::AddrSpaceCast(cast) => {
+                    casts.insert(&cast.dest, &cast.operand);
+                }
+                Instruction::ICmp(icmp) => {
+                    let null_if_true = match icmp.predicate {
+                        IntPredicate::EQ => true,
+                        IntPredicate::NE => false,
+                        _ => continue,
+                    };
+                    let pointer = match (is_null(&icmp.operand0), is_null(&icmp.operand1)) {
+                        (false, true) => &icmp.operand0,
+                        (true, false) => &icmp.operand1,
+                        _ => continue,
+                    };
+                    icmps.insert(&icmp.dest, (pointer, null_if_true));
+                }
+                _ => {}
+            }
+        }
+        let underlying = |mut pointer: &'m Operand| {
+            while let Operand::LocalOperand { name, .. } = pointer {
+                match casts.get(name) {
+                    Some(operand) => pointer = operand,
+                    None => break,
+                }
+            }
+            pointer
+        };
+
+        let branches: HashMap<&'m Name, CheckingBranch<'m>> = function
+            .basic_blocks
+            .iter()
+            .filter_map(|bb| match &bb.term {
+                Terminator::CondBr(condbr) => match &condbr.condition {
+                    Operand::LocalOperand { name, .. } => {
+                        icmps
+                            .get_key_value(name)
+                            .map(|(icmp, &(pointer, null_if_true))| {
+                                let branch = CheckingBranch {
+                                    pointer: underlying(pointer),
+                                    icmp,
+                                    true_dest: &condbr.true_dest,
+                                    false_dest: &condbr.false_dest,
+                                    null_if_true,
+                                };
+                                (&bb.name, branch)
+                            })
+                    }
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+
+        let mut dereferences = Vec::new();
+        for bb in &function.basic_blocks {
+            let derefs = bb
+                .instrs
+                .iter()
+                .map(Either::Left)
+                .chain(std::iter::once(Either::Right(&bb.term)))
+                .enumerate()
+                .filter_map(|(index, inst)| dereferenced(inst).map(|deref| (index, deref)));
+            for (index, (kind, pointer)) in derefs {
+                let underlying_pointer = underlying(pointer);
+                let checks = domtree
+                    .idom_chain(&bb.name)
+                    .filter_map(|block| branches.get_key_value(block))
+                    .filter(|(_, branch)| branch.pointer == underlying_pointer)
+                    .flat_map(|(&block, branch)| {
+                        [(true, branch.true_dest), (false, branch.false_dest)]
+                            .into_iter()
+                            .filter(move |&(_, dest)| {
+                                domtree.edge_dominates(cfg, block, dest, &bb.name)
+                            })
+                            .map(move |(outcome, _)| NullCheck {
+                                block,
+                                icmp: branch.icmp,
+                                outcome,
+                                is_null: outcome == branch.null_if_true,
+                            })
+                    })
+                    .collect();
+                dereferences.push(Dereference {
+                    location: InstructionLocation {
+                        block: &bb.name,
+                        index,
+                    },
+                    kind,
+                    pointer,
+                    checks,
+                });
+            }
+        }
+        let indices = dereferences
+            .iter()
+            .enumerate()
+            .map(|(i, deref)| (deref.location, i))
+            .collect();
+        Self {
+            dereferences,
+            indices,
+        }
+    }
+
+    /// Iterate over every dereference in the function, in order
+    pub fn dereferences(&self) -> impl Iterator<Item = &Dereference<'m>> {
+        self.dereferences.iter()
+    }
+
+    /// Get the dereference at the given location, or `None` if the
+    /// instruction there doesn't dereference a pointer
+    pub fn dereference(&self, location: InstructionLocation<'m>) -> Option<&Dereference<'m>> {
+        self.indices.get(&location).map(|&i| &self.dereferences[i])
+    }
+
+    /// Iterate over the dereferences which aren't dominated by any null check
+    /// of their pointer, in order
+    pub fn unchecked(&self) -> impl Iterator<Item = &Dereference<'m>> {
+        self.dereferences
+            .iter()
+            .filter(|deref| deref.checks.is_empty())
+    }
+}
+
+/// Is the given operand a null pointer constant?
+fn is_null(operand: &Operand) -> bool {
+    match operand {
+        Operand::ConstantOperand(constant) => matches!(constant.as_ref(), Constant::Null(_)),
+        _ => false,
+    }
+}
+
+/// Get the pointer dereferenced by the given instruction or terminator, if
+/// any
+fn dereferenced<'m>(
+    inst: Either<&'m Instruction, &'m Terminator>,
+) -> Option<(DereferenceKind, &'m Operand)> {
+    match inst {
+        Either::Left(Instruction::Load(load)) => Some((DereferenceKind::Load, &load.address)),
+        Either::Left(Instruction::Store(store)) => Some((DereferenceKind::Store, &store.address)),
+        Either::Left(Instruction::CmpXchg(cmpxchg)) => {
+            Some((DereferenceKind::Atomic, &cmpxchg.address))
+        }
+        Either::Left(Instruction::AtomicRMW(rmw)) => Some((DereferenceKind::Atomic, &rmw.address)),
+        Either::Left(Instruction::Call(call)) => match &call.function {
+            Either::Right(callee @ Operand::LocalOperand { .. }) => {
+                Some((DereferenceKind::IndirectCall, callee))
+            }
+            _ => None,
+        },
+        Either::Right(Terminator::Invoke(invoke)) => match &invoke.function {
+            Either::Right(callee @ Operand::LocalOperand { .. }) => {
+                Some((DereferenceKind::IndirectCall, callee))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
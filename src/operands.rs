@@ -0,0 +1,199 @@
+//! Helpers for enumerating the operands of instructions and terminators, and
+//! the globals referenced by constants
+
+use llvm_ir::{Constant, Instruction, Name, Operand, Terminator};
+
+/// Get all the `Operand`s used by the given `Instruction`.
+///
+/// For calls, this includes the called function (unless it is inline
+/// assembly) followed by the arguments.
+pub(crate) fn instruction_operands(inst: &Instruction) -> Vec<&Operand> {
+    match inst {
+        Instruction::Add(i) => vec![&i.operand0, &i.operand1],
+        Instruction::Sub(i) => vec![&i.operand0, &i.operand1],
+        Instruction::Mul(i) => vec![&i.operand0, &i.operand1],
+        Instruction::UDiv(i) => vec![&i.operand0, &i.operand1],
+        Instruction::SDiv(i) => vec![&i.operand0, &i.operand1],
+        Instruction::URem(i) => vec![&i.operand0, &i.operand1],
+        Instruction::SRem(i) => vec![&i.operand0, &i.operand1],
+        Instruction::And(i) => vec![&i.operand0, &i.operand1],
+        Instruction::Or(i) => vec![&i.operand0, &i.operand1],
+        Instruction::Xor(i) => vec![&i.operand0, &i.operand1],
+        Instruction::Shl(i) => vec![&i.operand0, &i.operand1],
+        Instruction::LShr(i) => vec![&i.operand0, &i.operand1],
+        Instruction::AShr(i) => vec![&i.operand0, &i.operand1],
+        Instruction::FAdd(i) => vec![&i.operand0, &i.operand1],
+        Instruction::FSub(i) => vec![&i.operand0, &i.operand1],
+        Instruction::FMul(i) => vec![&i.operand0, &i.operand1],
+        Instruction::FDiv(i) => vec![&i.operand0, &i.operand1],
+        Instruction::FRem(i) => vec![&i.operand0, &i.operand1],
+        Instruction::FNeg(i) => vec![&i.operand],
+        Instruction::ExtractElement(i) => vec![&i.vector, &i.index],
+        Instruction::InsertElement(i) => vec![&i.vector, &i.element, &i.index],
+        Instruction::ShuffleVector(i) => vec![&i.operand0, &i.operand1],
+        Instruction::ExtractValue(i) => vec![&i.aggregate],
+        Instruction::InsertValue(i) => vec![&i.aggregate, &i.element],
+        Instruction::Alloca(i) => vec![&i.num_elements],
+        Instruction::Load(i) => vec![&i.address],
+        Instruction::Store(i) => vec![&i.address, &i.value],
+        Instruction::Fence(_) => vec![],
+        Instruction::CmpXchg(i) => vec![&i.address, &i.expected, &i.replacement],
+        Instruction::AtomicRMW(i) => vec![&i.address, &i.value],
+        Instruction::GetElementPtr(i) => std::iter::once(&i.address)
+            .chain(i.indices.iter())
+            .collect(),
+        Instruction::Trunc(i) => vec![&i.operand],
+        Instruction::ZExt(i) => vec![&i.operand],
+        Instruction::SExt(i) => vec![&i.operand],
+        Instruction::FPTrunc(i) => vec![&i.operand],
+        Instruction::FPExt(i) => vec![&i.operand],
+        Instruction::FPToUI(i) => vec![&i.operand],
+        Instruction::FPToSI(i) => vec![&i.operand],
+        Instruction::UIToFP(i) => vec![&i.operand],
+        Instruction::SIToFP(i) => vec![&i.operand],
+        Instruction::PtrToInt(i) => vec![&i.operand],
+        Instruction::IntToPtr(i) => vec![&i.operand],
+        Instruction::BitCast(i) => vec![&i.operand],
+        Instruction::AddrSpaceCast(i) => vec![&i.operand],
+        Instruction::ICmp(i) => vec![&i.operand0, &i.operand1],
+        Instruction::FCmp(i) => vec![&i.operand0, &i.operand1],
+        Instruction::Phi(i) => i.incoming_values.iter().map(|(op, _)| op).collect(),
+        Instruction::Select(i) => vec![&i.condition, &i.true_value, &i.false_value],
+        #[cfg(feature = "llvm-10-or-greater")]
+        Instruction::Freeze(i) => vec![&i.operand],
+        Instruction::Call(i) => i
+            .function
+            .as_ref()
+            .right()
+            .into_iter()
+            .chain(i.arguments.iter().map(|(op, _)| op))
+            .collect(),
+        Instruction::VAArg(i) => vec![&i.arg_list],
+        Instruction::LandingPad(_) => vec![],
+        Instruction::CatchPad(i) => std::iter::once(&i.catch_switch)
+            .chain(i.args.iter())
+            .collect(),
+        Instruction::CleanupPad(i) => std::iter::once(&i.parent_pad)
+            .chain(i.args.iter())
+            .collect(),
+    }
+}
+
+/// Get all the `Operand`s used by the given `Terminator`.
+///
+/// For `Invoke` and `CallBr`, this includes the called function (unless it is
+/// inline assembly) followed by the arguments.
+pub(crate) fn terminator_operands(term: &Terminator) -> Vec<&Operand> {
+    match term {
+        Terminator::Ret(t) => t.return_operand.iter().collect(),
+        Terminator::Br(_) => vec![],
+        Terminator::CondBr(t) => vec![&t.condition],
+        Terminator::Switch(t) => vec![&t.operand],
+        Terminator::IndirectBr(t) => vec![&t.operand],
+        Terminator::Invoke(t) => t
+            .function
+            .as_ref()
+            .right()
+            .into_iter()
+            .chain(t.arguments.iter().map(|(op, _)| op))
+            .collect(),
+        Terminator::Resume(t) => vec![&t.operand],
+        Terminator::Unreachable(_) => vec![],
+        Terminator::CleanupRet(t) => vec![&t.cleanup_pad],
+        Terminator::CatchRet(t) => vec![&t.catch_pad],
+        Terminator::CatchSwitch(t) => vec![&t.parent_pad],
+        Terminator::CallBr(t) => t
+            .function
+            .as_ref()
+            .right()
+            .into_iter()
+            .chain(t.arguments.iter().map(|(op, _)| op))
+            .collect(),
+    }
+}
+
+/// Push onto `out` the names of all the globals (global variables, functions,
+/// aliases, etc) referenced by the given `Constant`, including inside constant
+/// expressions and aggregates.
+pub(crate) fn globals_in_constant<'m>(constant: &'m Constant, out: &mut Vec<&'m Name>) {
+    match constant {
+        Constant::GlobalReference { name, .. } => out.push(name),
+        Constant::Struct { values, .. } => {
+            for value in values {
+                globals_in_constant(value, out);
+            }
+        }
+        Constant::Array { elements, .. } => {
+            for element in elements {
+                globals_in_constant(element, out);
+            }
+        }
+        Constant::Vector(elements) => {
+            for element in elements {
+                globals_in_constant(element, out);
+            }
+        }
+        Constant::Add(c) => {
+            globals_in_constant(&c.operand0, out);
+            globals_in_constant(&c.operand1, out);
+        }
+        Constant::Sub(c) => {
+            globals_in_constant(&c.operand0, out);
+            globals_in_constant(&c.operand1, out);
+        }
+        Constant::Mul(c) => {
+            globals_in_constant(&c.operand0, out);
+            globals_in_constant(&c.operand1, out);
+        }
+        Constant::Xor(c) => {
+            globals_in_constant(&c.operand0, out);
+            globals_in_constant(&c.operand1, out);
+        }
+        Constant::ICmp(c) => {
+            globals_in_constant(&c.operand0, out);
+            globals_in_constant(&c.operand1, out);
+        }
+        #[cfg(feature = "llvm-16-or-lower")]
+        Constant::Select(c) => {
+            globals_in_constant(&c.condition, out);
+            globals_in_constant(&c.true_value, out);
+            globals_in_constant(&c.false_value, out);
+        }
+        Constant::ExtractElement(c) => globals_in_constant(&c.vector, out),
+        Constant::InsertElement(c) => {
+            globals_in_constant(&c.vector, out);
+            globals_in_constant(&c.element, out);
+        }
+        Constant::ShuffleVector(c) => {
+            globals_in_constant(&c.operand0, out);
+            globals_in_constant(&c.operand1, out);
+        }
+        Constant::GetElementPtr(c) => {
+            globals_in_constant(&c.address, out);
+            for index in &c.indices {
+                globals_in_constant(index, out);
+            }
+        }
+        Constant::Trunc(c) => globals_in_constant(&c.operand, out),
+        Constant::PtrToInt(c) => globals_in_constant(&c.operand, out),
+        Constant::IntToPtr(c) => globals_in_constant(&c.operand, out),
+        Constant::BitCast(c) => globals_in_constant(&c.operand, out),
+        Constant::AddrSpaceCast(c) => globals_in_constant(&c.operand, out),
+        // remaining constants are either scalars, or (version-dependent)
+        // arithmetic constant expressions which don't usually involve globals
+        _ => {}
+    }
+}
+
+/// Push onto `out` the names of all the globals referenced by the given
+/// `Operand`s
+pub(crate) fn globals_in_operands<'m>(
+    operands: impl IntoIterator<Item = &'m Operand>,
+    out: &mut Vec<&'m Name>,
+) {
+    for op in operands {
+        if let Operand::ConstantOperand(cref) = op {
+            globals_in_constant(cref, out);
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use crate::call_graph::{functions_with_names, FunctionName};
+use llvm_ir::{Constant, ConstantRef, Module};
+use std::collections::BTreeSet;
+
+/// The exception-handling scheme used by a function, as determined by its
+/// personality function
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub enum EHScheme {
+    /// The function has no personality function
+    None,
+    /// Itanium C++ ABI exception handling (e.g., `__gxx_personality_v0`)
+    ItaniumCxx,
+    /// GNU C cleanups (e.g., `__gcc_personality_v0`), as used by
+    /// `__attribute__((cleanup))`
+    GnuC,
+    /// Rust panics (`rust_eh_personality`)
+    Rust,
+    /// Windows structured exception handling, or MSVC C++ exceptions (e.g.,
+    /// `__C_specific_handler` or `__CxxFrameHandler3`)
+    Seh,
+    /// The function has a personality function which is not recognized
+    Unknown,
+}
+
+impl EHScheme {
+    /// Classify the EH scheme implied by the personality function with the
+    /// given name
+    pub fn from_personality(name: &str) -> Self {
+        match name {
+            "__gxx_personality_v0"
+            | "__gxx_personality_sj0"
+            | "__gxx_personality_seh0"
+            | "__gxx_wasm_personality_v0" => EHScheme::ItaniumCxx,
+            "__gcc_personality_v0" | "__gcc_personality_sj0" | "__gcc_personality_seh0" => {
+                EHScheme::GnuC
+            }
+            "rust_eh_personality" => EHScheme::Rust,
+            "__C_specific_handler"
+            | "_except_handler3"
+            | "_except_handler4"
+            | "__CxxFrameHandler3"
+            | "__CxxFrameHandler4"
+            | "ProcessCLRException" => EHScheme::Seh,
+            _ => EHScheme::Unknown,
+        }
+    }
+}
+
+/// Reports each function's personality function, and classifies the
+/// exception-handling scheme it uses.
+///
+/// This is useful when analyzing modules which mix languages (e.g., after LTO),
+/// to determine how exceptions should be modeled in each function.
+///
+/// To construct a `PersonalityAnalysis`, use [`ModuleAnalysis`](struct.ModuleAnalysis.html)
+/// or [`CrossModuleAnalysis`](struct.CrossModuleAnalysis.html).
+pub struct PersonalityAnalysis<'m> {
+    /// The personality information for every function defined in the analyzed
+    /// `Module`(s)
+    functions: Vec<FunctionPersonality<'m>>,
+}
+
+/// The personality function and EH scheme of a single function
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct FunctionPersonality<'m> {
+    /// Name of the function
+    pub function: FunctionName<'m>,
+    /// Name of the `Module` containing the function
+    pub module: &'m str,
+    /// Name of the function's personality function, if it has one
+    pub personality: Option<&'m str>,
+    /// The EH scheme implied by the personality function
+    pub scheme: EHScheme,
+}
+
+impl<'m> PersonalityAnalysis<'m> {
+    pub(crate) fn new(modules: impl IntoIterator<Item = &'m Module>) -> Self {
+        let mut functions = vec![];
+        for module in modules {
+            for (f, name) in functions_with_names(module) {
+                let personality = f.personality_function.as_ref().map(personality_name);
+                let scheme = match personality {
+                    None => EHScheme::None,
+                    Some(Some(name)) => EHScheme::from_personality(name),
+                    Some(None) => EHScheme::Unknown,
+                };
+                functions.push(FunctionPersonality {
+                    function: name,
+                    module: &module.name,
+                    personality: personality.flatten(),
+                    scheme,
+                });
+            }
+        }
+        Self { functions }
+    }
+
+    /// Iterate over the personality information for every function defined in
+    /// the analyzed `Module`(s).
+    pub fn functions<'s>(&'s self) -> impl Iterator<Item = &'s FunctionPersonality<'m>> + 's {
+        self.functions.iter()
+    }
+
+    /// Get the personality information for the function with the given name.
+    ///
+    /// Panics if the given function is not found in the analyzed `Module`(s).
+    pub fn function<'n>(&self, func_name: impl Into<FunctionName<'n>>) -> &FunctionPersonality<'m> {
+        let func_name = func_name.into();
+        self.functions
+            .iter()
+            .find(|fp| fp.function == func_name)
+            .unwrap_or_else(|| {
+                panic!(
+                    "function(): function named {:?} not found in the Module(s)",
+                    func_name
+                )
+            })
+    }
+
+    /// Get the name of the personality function of the function with the given
+    /// name, if it has one.
+    ///
+    /// Panics if the given function is not found in the analyzed `Module`(s).
+    pub fn personality<'n>(&self, func_name: impl Into<FunctionName<'n>>) -> Option<&'m str> {
+        self.function(func_name).personality
+    }
+
+    /// Get the EH scheme of the function with the given name.
+    ///
+    /// Panics if the given function is not found in the analyzed `Module`(s).
+    pub fn scheme<'n>(&self, func_name: impl Into<FunctionName<'n>>) -> EHScheme {
+        self.function(func_name).scheme
+    }
+
+    /// Iterate over the names of the functions which use the given EH scheme.
+    pub fn functions_with_scheme<'s>(
+        &'s self,
+        scheme: EHScheme,
+    ) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        self.functions
+            .iter()
+            .filter(move |fp| fp.scheme == scheme)
+            .map(|fp| fp.function)
+    }
+
+    /// Get the EH schemes used by any function in the analyzed `Module`(s),
+    /// not including `EHScheme::None`.
+    pub fn schemes(&self) -> BTreeSet<EHScheme> {
+        self.schemes_where(|_| true)
+    }
+
+    /// Get the EH schemes used by any function in the `Module` with the given
+    /// name, not including `EHScheme::None`.
+    pub fn schemes_in_module(&self, mod_name: &str) -> BTreeSet<EHScheme> {
+        self.schemes_where(|fp| fp.module == mod_name)
+    }
+
+    /// Do the analyzed `Module`(s) use more than one EH scheme (not counting
+    /// functions with no personality function)?
+    pub fn is_mixed(&self) -> bool {
+        self.schemes().len() > 1
+    }
+
+    fn schemes_where(&self, pred: impl Fn(&FunctionPersonality) -> bool) -> BTreeSet<EHScheme> {
+        self.functions
+            .iter()
+            .filter(|fp| fp.scheme != EHScheme::None && pred(fp))
+            .map(|fp| fp.scheme)
+            .collect()
+    }
+}
+
+/// Get the name of a personality function, looking through any casts
+fn personality_name(personality: &ConstantRef) -> Option<&str> {
+    match personality.as_ref() {
+        Constant::GlobalReference { name, .. } => match name {
+            llvm_ir::Name::Name(name) => Some(name),
+            llvm_ir::Name::Number(_) => None,
+        },
+        Constant::BitCast(bitcast) => personality_name(&bitcast.operand),
+        Constant::AddrSpaceCast(cast) => personality_name(&cast.operand),
+        _ => None,
+    }
+}
@@ -0,0 +1,116 @@
+use llvm_ir::{Function, Instruction, Name, Operand};
+use std::collections::HashMap;
+
+/// The phi webs of a particular function: the local SSA values (parameters
+/// and instruction results) grouped into classes which are connected through
+/// `phi`s.
+///
+/// Each `phi`'s result is in the same web as each of its local incoming
+/// values, transitively. This is the usual approximation of which SSA values
+/// came from the same source variable: e.g., a loop counter `%i`, its
+/// increment `%i.next`, and its value after the loop are one web. Values which
+/// aren't connected to any `phi` are each in a web of their own.
+///
+/// To construct a `PhiWebs`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct PhiWebs<'m> {
+    /// The values in each web, in the order they're defined in the function
+    /// (parameters first). The webs are in the order of their first values.
+    webs: Vec<Vec<&'m Name>>,
+    /// Map from each value to the index of its web in `webs`
+    web_of: HashMap<&'m Name, usize>,
+}
+
+impl<'m> PhiWebs<'m> {
+    pub(crate) fn new(function: &'m Function) -> Self {
+        let values: Vec<&'m Name> = function
+            .parameters
+            .iter()
+            .map(|param| &param.name)
+            .chain(function.basic_blocks.iter().flat_map(|bb| {
+                bb.instrs
+                    .iter()
+                    .filter_map(|inst| inst.try_get_result())
+                    .chain(bb.term.try_get_result())
+            }))
+            .collect();
+        let index: HashMap<&'m Name, usize> = values
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| (value, i))
+            .collect();
+
+        // union-find over the indices of the values
+        let mut parents: Vec<usize> = (0..values.len()).collect();
+        for inst in function.basic_blocks.iter().flat_map(|bb| &bb.instrs) {
+            if let Instruction::Phi(phi) = inst {
+                for (incoming, _) in &phi.incoming_values {
+                    if let Operand::LocalOperand { name, .. } = incoming {
+                        if let (Some(&a), Some(&b)) = (index.get(&phi.dest), index.get(name)) {
+                            let (a, b) = (find(&mut parents, a), find(&mut parents, b));
+                            // the root is always the earliest value
+                            parents[a.max(b)] = a.min(b);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut webs: Vec<Vec<&'m Name>> = Vec::new();
+        let mut web_of: HashMap<&'m Name, usize> = HashMap::new();
+        let mut web_of_root: HashMap<usize, usize> = HashMap::new();
+        for (i, &value) in values.iter().enumerate() {
+            let root = find(&mut parents, i);
+            let web = *web_of_root.entry(root).or_insert_with(|| {
+                webs.push(Vec::new());
+                webs.len() - 1
+            });
+            webs[web].push(value);
+            web_of.insert(value, web);
+        }
+        Self { webs, web_of }
+    }
+
+    /// Get the values in the same web as the local value with the given
+    /// `Name` (including itself), in the order they're defined in the
+    /// function. Returns `None` if the value isn't in this function.
+    pub fn web(&self, value: &Name) -> Option<&[&'m Name]> {
+        self.web_of.get(value).map(|&web| self.webs[web].as_slice())
+    }
+
+    /// Get the first value (in the order they're defined in the function) in
+    /// the same web as the local value with the given `Name`, which
+    /// identifies the web. Returns `None` if the value isn't in this function.
+    pub fn representative(&self, value: &Name) -> Option<&'m Name> {
+        self.web(value).map(|web| web[0])
+    }
+
+    /// Are the local values with the given `Name`s in the same web?
+    pub fn same_web(&self, a: &Name, b: &Name) -> bool {
+        match (self.web_of.get(a), self.web_of.get(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Iterate over the webs with more than one value, i.e., those connected
+    /// through at least one `phi`, in the order of their representatives.
+    /// Each web's values are in the order they're defined in the function.
+    pub fn webs(&self) -> impl Iterator<Item = &[&'m Name]> {
+        self.webs
+            .iter()
+            .filter(|web| web.len() > 1)
+            .map(Vec::as_slice)
+    }
+}
+
+/// Find the root of the given index in the union-find forest `parents`,
+/// halving the path to it as we go
+fn find(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
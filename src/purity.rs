@@ -0,0 +1,337 @@
+use crate::alias::{underlying_object, MemoryObject};
+use crate::call_graph::{direct_callee, functions_with_names, CallGraph, FunctionName};
+use either::Either;
+use llvm_ir::{
+    function::FunctionAttribute, instruction::InlineAssembly, Function, Instruction, Module, Name,
+    Operand, Terminator,
+};
+use std::collections::HashMap;
+
+/// Classifies each function in the analyzed `Module`(s) by its effect on
+/// memory: whether it may read memory, may write memory, or may call code
+/// whose effects aren't known.
+///
+/// The classification of a function combines the effects of its own
+/// instructions with the classifications of the functions it may call, and is
+/// computed bottom-up over the [`CallGraph`](struct.CallGraph.html). It
+/// doesn't depend on the function's own attributes (such as `readonly`),
+/// which can be compared with it using
+/// [`declared_purity()`](#method.declared_purity) and
+/// [`attribute_conflicts()`](#method.attribute_conflicts). The attributes of
+/// call sites are trusted, though: a call marked `readnone` is assumed not to
+/// access memory, whatever it calls.
+///
+/// As in LLVM, accesses of a function's own `alloca`s don't count, since they
+/// aren't visible to its callers; but `volatile` accesses, and fences, count
+/// as writes. Only effects on memory are considered, so a `ReadNone` function
+/// may still, e.g., not return, or throw an exception.
+///
+/// Functions which are only declared in the analyzed `Module`(s) have
+/// `Unknown` purity, except for the `llvm.dbg` and `llvm.lifetime`
+/// intrinsics, which are `ReadNone`, and the `llvm.memcpy`, `llvm.memmove`,
+/// and `llvm.memset` intrinsics, which write memory. Calls to inline assembly
+/// and of garbage collection statepoints, and calls through function pointers
+/// which may not point to any function in the analyzed `Module`(s), have
+/// `Unknown` effects. Calls through function pointers are otherwise assumed
+/// to call any function in the analyzed `Module`(s) that has the appropriate
+/// type.
+///
+/// To construct a `PurityAnalysis`, use [`ModuleAnalysis`](struct.ModuleAnalysis.html)
+/// or [`CrossModuleAnalysis`](struct.CrossModuleAnalysis.html).
+pub struct PurityAnalysis<'m> {
+    /// Map from the name of each function in the analyzed `Module`(s) to its
+    /// purity
+    purities: HashMap<FunctionName<'m>, Purity>,
+    /// Map from the name of each function defined in the analyzed `Module`(s)
+    /// to the purity its attributes declare, if any
+    declared: HashMap<FunctionName<'m>, Purity>,
+}
+
+/// The effect of a function (or call) on memory; see
+/// [`PurityAnalysis`](struct.PurityAnalysis.html)
+///
+/// Purities are ordered from the most pure to the least, so the purity of a
+/// function is the maximum of the purities of everything it does.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub enum Purity {
+    /// Neither reads nor writes memory
+    ReadNone,
+    /// May read memory, but doesn't write it
+    ReadOnly,
+    /// May write memory
+    WritesMemory,
+    /// May call code whose effects aren't known, so may read or write memory
+    Unknown,
+}
+
+impl Purity {
+    /// Is this `ReadNone`?
+    pub fn is_pure(self) -> bool {
+        self == Purity::ReadNone
+    }
+
+    /// May memory be read?
+    pub fn may_read(self) -> bool {
+        self != Purity::ReadNone
+    }
+
+    /// May memory be written?
+    pub fn may_write(self) -> bool {
+        self >= Purity::WritesMemory
+    }
+
+    /// The purity of a function which is only declared
+    fn of_declaration(func_name: FunctionName) -> Self {
+        let func_name = func_name.as_named().unwrap_or_default();
+        if func_name.starts_with("llvm.dbg.") || func_name.starts_with("llvm.lifetime.") {
+            Purity::ReadNone
+        } else if func_name.starts_with("llvm.memcpy.")
+            || func_name.starts_with("llvm.memmove.")
+            || func_name.starts_with("llvm.memset.")
+        {
+            Purity::WritesMemory
+        } else {
+            Purity::Unknown
+        }
+    }
+
+    /// The purity declared by the given function attributes, if any
+    fn declared(attributes: &[FunctionAttribute]) -> Option<Self> {
+        attributes.iter().find_map(|attribute| match attribute {
+            FunctionAttribute::ReadNone => Some(Purity::ReadNone),
+            FunctionAttribute::ReadOnly => Some(Purity::ReadOnly),
+            #[cfg(feature = "llvm-16-or-greater")]
+            FunctionAttribute::Memory {
+                default,
+                argmem,
+                inaccessible_mem,
+            } => {
+                use llvm_ir::function::MemoryEffect;
+                let effects = [default, argmem, inaccessible_mem];
+                if effects.iter().all(|e| matches!(e, MemoryEffect::None)) {
+                    Some(Purity::ReadNone)
+                } else if effects
+                    .iter()
+                    .all(|e| matches!(e, MemoryEffect::None | MemoryEffect::Read))
+                {
+                    Some(Purity::ReadOnly)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        })
+    }
+}
+
+impl<'m> PurityAnalysis<'m> {
+    pub(crate) fn new(
+        modules: impl IntoIterator<Item = &'m Module>,
+        call_graph: &CallGraph<'m>,
+    ) -> Self {
+        // several functions may share a name (e.g., internal functions of
+        // different `Module`s), so their purities are combined
+        let mut functions: HashMap<FunctionName<'m>, Vec<&'m Function>> = HashMap::new();
+        for module in modules {
+            for (f, name) in functions_with_names(module) {
+                functions.entry(name).or_default().push(f);
+            }
+        }
+        let declared = functions
+            .iter()
+            .filter_map(|(&func_name, funcs)| {
+                let declared = funcs
+                    .iter()
+                    .map(|f| Purity::declared(&f.function_attributes))
+                    .collect::<Option<Vec<Purity>>>()?;
+                Some((func_name, declared.into_iter().max()?))
+            })
+            .collect();
+
+        let callees = call_graph.callees_by_site();
+        let mut purities: HashMap<FunctionName<'m>, Purity> = call_graph
+            .graph
+            .nodes()
+            .filter(|func_name| !functions.contains_key(func_name))
+            .map(|func_name| (func_name, Purity::of_declaration(func_name)))
+            .collect();
+        for scc in call_graph.sccs_bottom_up() {
+            for &func_name in &scc {
+                if functions.contains_key(&func_name) {
+                    purities.insert(func_name, Purity::ReadNone);
+                }
+            }
+            loop {
+                let mut changed = false;
+                for &func_name in &scc {
+                    for &f in functions.get(&func_name).into_iter().flatten() {
+                        let purity = function_purity(f, func_name, &callees, &purities);
+                        let current = purities
+                            .get_mut(&func_name)
+                            .expect("should have a purity for each function in the SCC");
+                        if purity > *current {
+                            *current = purity;
+                            changed = true;
+                        }
+                    }
+                }
+                if !changed {
+                    break;
+                }
+            }
+        }
+
+        Self { purities, declared }
+    }
+
+    /// Get the `Purity` of the function with the given name.
+    ///
+    /// Panics if the function is not found in the analyzed `Module`(s).
+    pub fn purity<'n>(&self, func_name: impl Into<FunctionName<'n>>) -> Purity {
+        let func_name = func_name.into();
+        *self.purities.get(&func_name).unwrap_or_else(|| {
+            panic!(
+                "purity(): function named {:?} not found in the Module(s)",
+                func_name
+            )
+        })
+    }
+
+    /// Iterate over the names of the functions with the given `Purity`, in
+    /// sorted order.
+    pub fn functions_with_purity(&self, purity: Purity) -> impl Iterator<Item = FunctionName<'m>> {
+        let mut funcs: Vec<FunctionName<'m>> = self
+            .purities
+            .iter()
+            .filter(|&(_, &p)| p == purity)
+            .map(|(&func_name, _)| func_name)
+            .collect();
+        funcs.sort_unstable();
+        funcs.into_iter()
+    }
+
+    /// Get the `Purity` declared by the attributes (`readnone` or `readonly`)
+    /// of the function with the given name, or `None` if it has no such
+    /// attributes or is only declared in the analyzed `Module`(s).
+    pub fn declared_purity<'n>(&self, func_name: impl Into<FunctionName<'n>>) -> Option<Purity> {
+        self.declared.get(&func_name.into()).copied()
+    }
+
+    /// Iterate over the names of the functions whose computed `Purity`
+    /// contradicts the `Purity` declared by their attributes (see
+    /// [`declared_purity()`](#method.declared_purity)), in sorted order:
+    /// functions found to read or write memory when their attributes say they
+    /// don't. Functions of `Unknown` purity are never included, since their
+    /// attributes may well be right.
+    pub fn attribute_conflicts(&self) -> impl Iterator<Item = FunctionName<'m>> {
+        let mut funcs: Vec<FunctionName<'m>> = self
+            .declared
+            .iter()
+            .filter(|&(func_name, &declared)| {
+                let purity = self.purities[func_name];
+                purity > declared && purity != Purity::Unknown
+            })
+            .map(|(&func_name, _)| func_name)
+            .collect();
+        funcs.sort_unstable();
+        funcs.into_iter()
+    }
+}
+
+/// Compute the purity of the given function, which has the given name (see
+/// `functions_with_names()`), given the current purities of the functions it
+/// may call
+fn function_purity<'m>(
+    f: &'m Function,
+    func_name: FunctionName<'m>,
+    callees: &HashMap<(FunctionName<'m>, &'m Name, usize), Vec<FunctionName<'m>>>,
+    purities: &HashMap<FunctionName<'m>, Purity>,
+) -> Purity {
+    let defs: HashMap<&'m Name, &'m Instruction> = f
+        .basic_blocks
+        .iter()
+        .flat_map(|bb| &bb.instrs)
+        .filter_map(|inst| inst.try_get_result().map(|dest| (dest, inst)))
+        .collect();
+    // accesses of the function's own `alloca`s don't count, unless volatile
+    let access = |address: &'m Operand, volatile: bool, purity: Purity| {
+        if volatile {
+            Purity::WritesMemory
+        } else if let Some(MemoryObject::Alloca(_)) = underlying_object(address, &defs, None) {
+            Purity::ReadNone
+        } else {
+            purity
+        }
+    };
+    let mut purity = Purity::ReadNone;
+    for bb in &f.basic_blocks {
+        for (index, inst) in bb.instrs.iter().enumerate() {
+            let inst_purity = match inst {
+                Instruction::Load(load) => access(&load.address, load.volatile, Purity::ReadOnly),
+                Instruction::Store(store) => {
+                    access(&store.address, store.volatile, Purity::WritesMemory)
+                }
+                Instruction::CmpXchg(cmpxchg) => {
+                    access(&cmpxchg.address, cmpxchg.volatile, Purity::WritesMemory)
+                }
+                Instruction::AtomicRMW(rmw) => {
+                    access(&rmw.address, rmw.volatile, Purity::WritesMemory)
+                }
+                Instruction::VAArg(va_arg) => access(&va_arg.arg_list, false, Purity::WritesMemory),
+                Instruction::Fence(_) => Purity::WritesMemory,
+                Instruction::Call(call) => call_purity(
+                    &call.function,
+                    &call.function_attributes,
+                    callees.get(&(func_name, &bb.name, index)),
+                    purities,
+                ),
+                _ => Purity::ReadNone,
+            };
+            purity = purity.max(inst_purity);
+        }
+        let term_purity = match &bb.term {
+            Terminator::Invoke(invoke) => call_purity(
+                &invoke.function,
+                &invoke.function_attributes,
+                callees.get(&(func_name, &bb.name, bb.instrs.len())),
+                purities,
+            ),
+            Terminator::CallBr(callbr) => call_purity(
+                &callbr.function,
+                &callbr.function_attributes,
+                callees.get(&(func_name, &bb.name, bb.instrs.len())),
+                purities,
+            ),
+            _ => Purity::ReadNone,
+        };
+        purity = purity.max(term_purity);
+    }
+    purity
+}
+
+/// Compute the purity of a call
+fn call_purity(
+    function: &Either<InlineAssembly, Operand>,
+    attributes: &[FunctionAttribute],
+    callees: Option<&Vec<FunctionName>>,
+    purities: &HashMap<FunctionName, Purity>,
+) -> Purity {
+    let is_statepoint = function
+        .as_ref()
+        .right()
+        .and_then(direct_callee)
+        .is_some_and(|name| name.starts_with("llvm.experimental.gc."));
+    let purity = match callees {
+        Some(callees) if !is_statepoint => callees
+            .iter()
+            .map(|callee| purities[callee])
+            .max()
+            .unwrap_or(Purity::ReadNone),
+        _ => Purity::Unknown,
+    };
+    let mut bound = Purity::declared(attributes).unwrap_or(Purity::Unknown);
+    if attributes.contains(&FunctionAttribute::WriteOnly) {
+        bound = bound.min(Purity::WritesMemory);
+    }
+    purity.min(bound)
+}
@@ -0,0 +1,105 @@
+use crate::call_graph::{CallGraph, FunctionName};
+use std::collections::HashMap;
+
+/// A precomputed index answering "may this function (transitively) call that
+/// function?" in constant time, for clients which issue many such queries.
+///
+/// The index stores one bit for each pair of strongly connected components
+/// (SCCs) of the [`CallGraph`](struct.CallGraph.html), so for a call graph
+/// with `n` SCCs it takes `n * n / 8` bytes and is built in time proportional
+/// to `n` times the number of edges in the call graph's condensation. For
+/// occasional queries, [`CallGraph::transitive_callees()`](struct.CallGraph.html#method.transitive_callees)
+/// is cheaper.
+///
+/// Like the `CallGraph` it was built from, this conservatively assumes that
+/// function pointers may point to any function in the analyzed `Module`(s)
+/// that has the appropriate type.
+///
+/// To construct a `ReachabilityIndex`, use
+/// [`CallGraph::reachability_index()`](struct.CallGraph.html#method.reachability_index).
+pub struct ReachabilityIndex<'m> {
+    /// Map from function name to the index of its SCC
+    scc_indices: HashMap<FunctionName<'m>, usize>,
+    /// For each SCC, whether it contains a cycle, i.e., whether its functions
+    /// may call themselves
+    cyclic: Vec<bool>,
+    /// Number of `u64` words in each row of `bits`
+    row_len: usize,
+    /// Bit matrix, stored row by row: bit `j` of row `i` is set if some
+    /// function in SCC `i` may call some function in SCC `j`, for `i != j`
+    bits: Vec<u64>,
+}
+
+impl<'m> ReachabilityIndex<'m> {
+    pub(crate) fn new(call_graph: &CallGraph<'m>) -> Self {
+        let condensation = call_graph.condensation();
+        let num_sccs = condensation.num_sccs();
+        // this wastes a word per row when `num_sccs` is a multiple of 64, but
+        // rounding up exactly (with `div_ceil()`) needs a newer Rust than
+        // our MSRV
+        let row_len = num_sccs / 64 + 1;
+        let mut bits = vec![0; num_sccs * row_len];
+        // SCCs are in reverse topological order, so each SCC's successors
+        // have already been processed when we get to it
+        for idx in 0..num_sccs {
+            for succ in condensation.succs(idx) {
+                bits[idx * row_len + succ / 64] |= 1 << (succ % 64);
+                for word in 0..row_len {
+                    bits[idx * row_len + word] |= bits[succ * row_len + word];
+                }
+            }
+        }
+        let cyclic = condensation
+            .sccs()
+            .iter()
+            .map(|scc| scc.len() > 1 || call_graph.inner().contains_edge(scc[0], scc[0]))
+            .collect();
+        let scc_indices = condensation
+            .sccs()
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, scc)| scc.iter().map(move |&func| (func, idx)))
+            .collect();
+        Self {
+            scc_indices,
+            cyclic,
+            row_len,
+            bits,
+        }
+    }
+
+    /// May the function `caller` (directly or transitively) call the function
+    /// `callee`? As in
+    /// [`CallGraph::transitive_callees()`](struct.CallGraph.html#method.transitive_callees),
+    /// a function may call itself only if it is (directly or mutually)
+    /// recursive.
+    ///
+    /// Panics if `caller` or `callee` is not found in the analyzed `Module`(s).
+    pub fn may_call_transitively<'n>(
+        &self,
+        caller: impl Into<FunctionName<'n>>,
+        callee: impl Into<FunctionName<'n>>,
+    ) -> bool {
+        let from = self.scc_index("may_call_transitively", caller.into());
+        let to = self.scc_index("may_call_transitively", callee.into());
+        if from == to {
+            self.cyclic[from]
+        } else {
+            self.bits[from * self.row_len + to / 64] & (1 << (to % 64)) != 0
+        }
+    }
+
+    /// Get the number of functions in the index
+    pub fn num_functions(&self) -> usize {
+        self.scc_indices.len()
+    }
+
+    fn scc_index(&self, method: &str, func_name: FunctionName) -> usize {
+        *self.scc_indices.get(&func_name).unwrap_or_else(|| {
+            panic!(
+                "{}(): function named {:?} not found in the Module(s)",
+                method, func_name
+            )
+        })
+    }
+}
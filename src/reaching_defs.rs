@@ -0,0 +1,330 @@
+use crate::alias::MemoryObject;
+use crate::call_graph::direct_callee;
+use crate::control_flow_graph::{CFGNode, ControlFlowGraph};
+use crate::data_dep_graph::MemoryAccess;
+use crate::def_use::{DefUseChains, InstructionLocation};
+use either::Either;
+use llvm_ir::{Function, Instruction, Name, Operand};
+use std::collections::{HashMap, HashSet};
+
+/// Something which can be defined: an SSA value, or the contents of a stack
+/// slot
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub enum Variable<'m> {
+    /// The local SSA value with this `Name`
+    Value(&'m Name),
+    /// The memory allocated by the `alloca` with this result
+    StackSlot(&'m Name),
+}
+
+/// A definition of a [`Variable`](enum.Variable.html) by a particular
+/// instruction (or terminator)
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub struct ReachingDefinition<'m> {
+    /// The variable defined
+    pub variable: Variable<'m>,
+    /// The location of the defining instruction
+    pub location: InstructionLocation<'m>,
+}
+
+/// Reaching definitions for a particular function: for each point in the
+/// function, which definitions may reach it along some path through the CFG
+/// without being overwritten.
+///
+/// This covers both SSA values and stack slots. Each instruction with a result
+/// defines the corresponding SSA value, and since SSA values are never
+/// redefined, its definition reaches every point reachable from it. Function
+/// parameters have no defining instruction, and are not included.
+///
+/// A stack slot is defined by its `alloca` (representing the uninitialized
+/// contents), and by each instruction which may write it. Only a `store` to the
+/// `alloca`'s result itself overwrites the whole slot and kills its previous
+/// definitions; a `store` through a `getelementptr` or cast may write only part
+/// of the slot, so it does not. If the slot's address escapes (it's used for
+/// anything other than loading, storing, `getelementptr`s, casts, and the
+/// `llvm.dbg.*` and `llvm.lifetime.*` intrinsics), then every call and store
+/// which may write unknown memory also (non-killingly) defines it.
+///
+/// Per-block results are computed up front; results for individual
+/// instructions are computed on demand from the results for their block.
+///
+/// To construct a `ReachingDefinitions`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct ReachingDefinitions<'m> {
+    /// The function
+    function: &'m Function,
+    /// The definitions made by each instruction which makes any, along with
+    /// whether each one kills the previous definitions of its variable
+    definitions: HashMap<InstructionLocation<'m>, Vec<(ReachingDefinition<'m>, bool)>>,
+    /// Definitions reaching the entry of each block
+    block_in: HashMap<&'m Name, HashSet<ReachingDefinition<'m>>>,
+    /// Definitions reaching the exit of each block
+    block_out: HashMap<&'m Name, HashSet<ReachingDefinition<'m>>>,
+    /// Stack slots whose address escapes
+    escaped: HashSet<&'m Name>,
+}
+
+impl<'m> ReachingDefinitions<'m> {
+    pub(crate) fn new(
+        function: &'m Function,
+        cfg: &ControlFlowGraph<'m>,
+        def_use: &DefUseChains<'m>,
+    ) -> Self {
+        let defs: HashMap<&'m Name, &'m Instruction> = function
+            .basic_blocks
+            .iter()
+            .flat_map(|bb| bb.instrs.iter())
+            .filter_map(|inst| inst.try_get_result().map(|dest| (dest, inst)))
+            .collect();
+        let slots: Vec<&'m Name> = function
+            .basic_blocks
+            .iter()
+            .flat_map(|bb| bb.instrs.iter())
+            .filter_map(|inst| match inst {
+                Instruction::Alloca(alloca) => Some(&alloca.dest),
+                _ => None,
+            })
+            .collect();
+        let escaped: HashSet<&'m Name> = slots
+            .iter()
+            .copied()
+            .filter(|&slot| escapes(slot, def_use))
+            .collect();
+
+        let mut definitions: HashMap<InstructionLocation<'m>, Vec<_>> = HashMap::new();
+        for bb in &function.basic_blocks {
+            let insts = bb
+                .instrs
+                .iter()
+                .map(Either::Left)
+                .chain(std::iter::once(Either::Right(&bb.term)));
+            for (index, inst) in insts.enumerate() {
+                let location = InstructionLocation {
+                    block: &bb.name,
+                    index,
+                };
+                let mut defined = Vec::new();
+                let mut define = |variable, kills| {
+                    defined.push((ReachingDefinition { variable, location }, kills))
+                };
+                let result = match inst {
+                    Either::Left(inst) => inst.try_get_result(),
+                    Either::Right(term) => term.try_get_result(),
+                };
+                if let Some(result) = result {
+                    define(Variable::Value(result), true);
+                }
+                let write = MemoryAccess::of(inst, &defs).filter(|access| access.writes);
+                if let Either::Left(Instruction::Alloca(alloca)) = inst {
+                    define(Variable::StackSlot(&alloca.dest), true);
+                } else if let Some(write) = write {
+                    match write.object {
+                        Some(MemoryObject::Alloca(slot)) => {
+                            let whole = match inst {
+                                Either::Left(Instruction::Store(store)) => matches!(
+                                    &store.address,
+                                    Operand::LocalOperand { name, .. } if name == slot
+                                ),
+                                _ => false,
+                            };
+                            define(Variable::StackSlot(slot), whole);
+                        }
+                        Some(MemoryObject::Global(_)) => {}
+                        None => {
+                            for &slot in &slots {
+                                if escaped.contains(slot) {
+                                    define(Variable::StackSlot(slot), false);
+                                }
+                            }
+                        }
+                    }
+                }
+                if !defined.is_empty() {
+                    definitions.insert(location, defined);
+                }
+            }
+        }
+
+        let mut rd = Self {
+            function,
+            definitions,
+            block_in: HashMap::new(),
+            block_out: HashMap::new(),
+            escaped,
+        };
+        rd.solve(cfg);
+        rd
+    }
+
+    /// Compute `block_in` and `block_out` by iterating to a fixpoint
+    fn solve(&mut self, cfg: &ControlFlowGraph<'m>) {
+        for bb in &self.function.basic_blocks {
+            self.block_in.insert(&bb.name, HashSet::new());
+            self.block_out.insert(&bb.name, HashSet::new());
+        }
+        let mut worklist: Vec<&'m Name> = cfg.reverse_post_order().collect();
+        worklist.reverse();
+        let mut on_worklist: HashSet<&'m Name> = worklist.iter().copied().collect();
+        while let Some(block) = worklist.pop() {
+            on_worklist.remove(block);
+            let mut reaching: HashSet<ReachingDefinition<'m>> = cfg
+                .preds(block)
+                .flat_map(|pred| self.block_out[pred].iter().copied())
+                .collect();
+            self.block_in.insert(block, reaching.clone());
+            let bb = self.function.get_bb_by_name(block).unwrap_or_else(|| {
+                panic!(
+                    "Failed to find block {} in function {}",
+                    block, self.function.name
+                )
+            });
+            for index in 0..=bb.instrs.len() {
+                self.transfer(&mut reaching, InstructionLocation { block, index });
+            }
+            if reaching != self.block_out[block] {
+                self.block_out.insert(block, reaching);
+                for succ in cfg.succs(block) {
+                    if let CFGNode::Block(succ) = succ {
+                        if on_worklist.insert(succ) {
+                            worklist.push(succ);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Update `reaching` with the effect of the instruction at `location`
+    fn transfer(
+        &self,
+        reaching: &mut HashSet<ReachingDefinition<'m>>,
+        location: InstructionLocation<'m>,
+    ) {
+        for &(def, kills) in self.definitions.get(&location).into_iter().flatten() {
+            if kills {
+                reaching.retain(|other| other.variable != def.variable);
+            }
+            reaching.insert(def);
+        }
+    }
+
+    /// Iterate over the definitions reaching the entry of the block with the
+    /// given `Name`
+    pub fn reaching_block_entry<'s>(
+        &'s self,
+        block: &'m Name,
+    ) -> impl Iterator<Item = ReachingDefinition<'m>> + 's {
+        self.block_set(&self.block_in, block).iter().copied()
+    }
+
+    /// Iterate over the definitions reaching the exit of the block with the
+    /// given `Name`, i.e., just after its terminator
+    pub fn reaching_block_exit<'s>(
+        &'s self,
+        block: &'m Name,
+    ) -> impl Iterator<Item = ReachingDefinition<'m>> + 's {
+        self.block_set(&self.block_out, block).iter().copied()
+    }
+
+    /// Get the definitions reaching the instruction (or terminator) at the
+    /// given location, i.e., just before it executes.
+    ///
+    /// Panics if the location isn't in this function.
+    pub fn reaching_instruction(
+        &self,
+        inst: InstructionLocation<'m>,
+    ) -> HashSet<ReachingDefinition<'m>> {
+        let mut reaching = self.block_set(&self.block_in, inst.block).clone();
+        for index in 0..inst.index {
+            self.transfer(&mut reaching, InstructionLocation { index, ..inst });
+        }
+        reaching
+    }
+
+    /// Get the locations of the definitions of the given `Variable` which
+    /// reach the instruction (or terminator) at the given location, sorted.
+    /// For instance, for a `load` from a stack slot, these are the
+    /// instructions which may have written the value it loads.
+    ///
+    /// Panics if the location isn't in this function.
+    pub fn definitions_reaching(
+        &self,
+        inst: InstructionLocation<'m>,
+        variable: Variable<'m>,
+    ) -> Vec<InstructionLocation<'m>> {
+        let mut locations: Vec<_> = self
+            .reaching_instruction(inst)
+            .into_iter()
+            .filter(|def| def.variable == variable)
+            .map(|def| def.location)
+            .collect();
+        locations.sort_unstable();
+        locations
+    }
+
+    /// Does the address of the stack slot allocated by the `alloca` with the
+    /// given `Name` escape? If so, calls and stores to unknown memory may
+    /// write it.
+    pub fn is_escaped(&self, slot: &Name) -> bool {
+        self.escaped.contains(slot)
+    }
+
+    fn block_set<'s>(
+        &'s self,
+        sets: &'s HashMap<&'m Name, HashSet<ReachingDefinition<'m>>>,
+        block: &Name,
+    ) -> &'s HashSet<ReachingDefinition<'m>> {
+        sets.get(block).unwrap_or_else(|| {
+            panic!(
+                "Failed to find block {} in function {}",
+                block, self.function.name
+            )
+        })
+    }
+}
+
+/// Does the address of the stack slot allocated by the `alloca` with the given
+/// result escape?
+fn escapes(slot: &Name, def_use: &DefUseChains) -> bool {
+    let is_pointer = |operand: &Operand, pointers: &HashSet<&Name>| matches!(operand, Operand::LocalOperand { name, .. } if pointers.contains(name));
+    // `slot`, and the pointers derived from it
+    let mut pointers: HashSet<&Name> = HashSet::new();
+    pointers.insert(slot);
+    let mut worklist = vec![slot];
+    while let Some(pointer) = worklist.pop() {
+        for user in def_use.users(pointer) {
+            let derived = match user {
+                Either::Left(Instruction::Load(_)) => None,
+                Either::Left(Instruction::Store(store)) => {
+                    if is_pointer(&store.value, &pointers) {
+                        return true;
+                    }
+                    None
+                }
+                Either::Left(Instruction::GetElementPtr(gep)) => Some(&gep.dest),
+                Either::Left(Instruction::BitCast(bitcast)) => Some(&bitcast.dest),
+                Either::Left(Instruction::AddrSpaceCast(cast)) => Some(&cast.dest),
+                Either::Left(Instruction::Call(call)) => {
+                    let ignored = match &call.function {
+                        Either::Right(callee) => direct_callee(callee).is_some_and(|name| {
+                            name.starts_with("llvm.dbg.") || name.starts_with("llvm.lifetime.")
+                        }),
+                        Either::Left(_) => false,
+                    };
+                    if !ignored {
+                        return true;
+                    }
+                    None
+                }
+                _ => return true,
+            };
+            if let Some(derived) = derived {
+                if pointers.insert(derived) {
+                    worklist.push(derived);
+                }
+            }
+        }
+    }
+    false
+}
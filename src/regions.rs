@@ -0,0 +1,244 @@
+use crate::control_flow_graph::{CFGNode, ControlFlowGraph};
+use crate::dominator_tree::{DominatorTree, PostDominatorTree};
+use llvm_ir::Name;
+use std::collections::{HashMap, HashSet};
+
+/// The single-entry single-exit (SESE) regions of a particular function,
+/// arranged in a tree by nesting (similar to LLVM's `RegionInfo`).
+///
+/// A region consists of an `entry` block and the blocks reachable from it
+/// without going through its `exit`, where
+///   - control enters the region only through `entry` (either from outside the
+///     region, or along a back edge from inside it), and
+///   - control leaves the region only by going to `exit`, a block outside the
+///     region.
+///
+/// As in LLVM, a region is omitted if its entry block has only one successor,
+/// which is the region's exit, since every such block trivially forms a
+/// region. Likewise, a sequence of regions (where each one's exit is the
+/// next one's entry) generally isn't itself reported as a region, unless it's
+/// the whole function. The resulting regions are either nested or disjoint,
+/// but several regions may share an entry block. The whole function is the
+/// top-level region, which is the root of the tree.
+///
+/// To construct a `RegionInfo`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct RegionInfo<'m> {
+    /// All of the regions, with the top-level region first. Each region's
+    /// parent comes before it.
+    regions: Vec<Region<'m>>,
+
+    /// Map from each block to the index of the innermost region containing it
+    innermost: HashMap<&'m Name, usize>,
+}
+
+/// A single-entry single-exit region of a function; see
+/// [`RegionInfo`](struct.RegionInfo.html)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Region<'m> {
+    /// The block through which control enters the region
+    pub entry: &'m Name,
+    /// Where control goes when it leaves the region. This is
+    /// `CFGNode::Return` for the top-level region, and a block outside the
+    /// region for every other region.
+    pub exit: CFGNode<'m>,
+    /// The blocks in the region, including those in subregions
+    blocks: HashSet<&'m Name>,
+    /// Index of this region in `RegionInfo::regions`
+    index: usize,
+    /// Index of the parent region, or `None` for the top-level region
+    parent: Option<usize>,
+    /// Indices of the immediate subregions
+    children: Vec<usize>,
+}
+
+impl<'m> Region<'m> {
+    fn new(entry: &'m Name, exit: CFGNode<'m>, blocks: HashSet<&'m Name>) -> Self {
+        Self {
+            entry,
+            exit,
+            blocks,
+            index: 0,
+            parent: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// Iterate over the blocks in the region, including those in its
+    /// subregions
+    pub fn blocks<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
+        self.blocks.iter().copied()
+    }
+
+    /// Get the number of blocks in the region, including those in its
+    /// subregions
+    pub fn num_blocks(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Does the region (or one of its subregions) contain the block with the
+    /// given `Name`?
+    pub fn contains(&self, block: &'m Name) -> bool {
+        self.blocks.contains(block)
+    }
+
+    /// Is this the top-level region, i.e., the whole function?
+    pub fn is_top_level(&self) -> bool {
+        self.parent.is_none()
+    }
+}
+
+impl<'m> RegionInfo<'m> {
+    pub(crate) fn new(
+        cfg: &ControlFlowGraph<'m>,
+        domtree: &DominatorTree<'m>,
+        postdomtree: &PostDominatorTree<'m>,
+    ) -> Self {
+        // algorithm adapted from LLVM's `RegionInfo`. For each potential entry
+        // block, the potential exits are the blocks up its postdominator tree,
+        // except that we skip over the regions already found for each exit
+        // (which is why entries are visited in post-order, so that blocks are
+        // visited before the blocks which dominate them).
+        let reachable: HashSet<&'m Name> = cfg.reverse_post_order().collect();
+        let mut candidates: Vec<Region<'m>> = Vec::new();
+        // map from an entry to the farthest exit of its regions
+        let mut shortcuts: HashMap<&'m Name, &'m Name> = HashMap::new();
+        for entry in cfg.post_order() {
+            let mut last_exit = None;
+            let mut exit = postdomtree.ipostdom(entry);
+            while let Some(CFGNode::Block(exit_block)) = exit {
+                if let Some(blocks) = region_blocks(cfg, &reachable, entry, exit_block) {
+                    // every block with a single successor trivially forms a
+                    // region with it
+                    let mut succs = cfg.succs(entry);
+                    let trivial = succs.next() == exit && succs.next().is_none();
+                    if !trivial {
+                        candidates.push(Region::new(entry, CFGNode::Block(exit_block), blocks));
+                    }
+                    last_exit = Some(exit_block);
+                }
+                if !domtree.dominates(CFGNode::Block(entry), CFGNode::Block(exit_block)) {
+                    break;
+                }
+                let next = shortcuts.get(exit_block).copied().unwrap_or(exit_block);
+                exit = postdomtree.ipostdom(next);
+            }
+            if let Some(last_exit) = last_exit {
+                let farthest = shortcuts.get(last_exit).copied().unwrap_or(last_exit);
+                shortcuts.insert(entry, farthest);
+            }
+        }
+
+        // larger regions first, so that each region's parent precedes it
+        candidates.sort_by_key(|region| std::cmp::Reverse(region.blocks.len()));
+        let top_level = Region::new(cfg.entry(), CFGNode::Return, reachable.clone());
+        let mut regions = vec![top_level];
+        let mut innermost: HashMap<&'m Name, usize> =
+            regions[0].blocks.iter().map(|&block| (block, 0)).collect();
+        for mut region in candidates {
+            // the innermost region so far containing the entry is the parent,
+            // since regions are nested or disjoint
+            let parent = innermost[region.entry];
+            region.index = regions.len();
+            region.parent = Some(parent);
+            for &block in &region.blocks {
+                innermost.insert(block, region.index);
+            }
+            regions[parent].children.push(region.index);
+            regions.push(region);
+        }
+
+        Self { regions, innermost }
+    }
+
+    /// Get the top-level region, i.e., the whole function
+    pub fn top_level_region(&self) -> &Region<'m> {
+        &self.regions[0]
+    }
+
+    /// Iterate over all of the regions, including the top-level region.
+    /// Each region appears before all of its subregions.
+    pub fn regions(&self) -> impl Iterator<Item = &Region<'m>> {
+        self.regions.iter()
+    }
+
+    /// Get the innermost region containing the block with the given `Name`.
+    ///
+    /// Returns `None` if the block is unreachable from the entry block.
+    pub fn region_of(&self, block: &'m Name) -> Option<&Region<'m>> {
+        self.innermost.get(block).map(|&index| &self.regions[index])
+    }
+
+    /// Get the region with the given entry block which isn't the top-level
+    /// region, if there is one
+    pub fn region_with_entry(&self, entry: &'m Name) -> Option<&Region<'m>> {
+        self.regions[1..]
+            .iter()
+            .find(|region| region.entry == entry)
+    }
+
+    /// Get the region immediately containing the given region, or `None` if
+    /// the given region is the top-level region
+    pub fn parent(&self, region: &Region<'m>) -> Option<&Region<'m>> {
+        region.parent.map(|index| &self.regions[index])
+    }
+
+    /// Get the immediate subregions of the given region
+    pub fn subregions<'s>(
+        &'s self,
+        region: &'s Region<'m>,
+    ) -> impl Iterator<Item = &'s Region<'m>> + 's {
+        region
+            .children
+            .iter()
+            .map(move |&index| &self.regions[index])
+    }
+
+    /// Get the nesting depth of the given region: 0 for the top-level region,
+    /// 1 for its subregions, etc
+    pub fn depth(&self, region: &Region<'m>) -> usize {
+        std::iter::successors(self.parent(region), |region| self.parent(region)).count()
+    }
+}
+
+/// If `entry` and `exit` delimit a SESE region, get the blocks in that region;
+/// else `None`
+fn region_blocks<'m>(
+    cfg: &ControlFlowGraph<'m>,
+    reachable: &HashSet<&'m Name>,
+    entry: &'m Name,
+    exit: &'m Name,
+) -> Option<HashSet<&'m Name>> {
+    let exit = CFGNode::Block(exit);
+    let mut blocks: HashSet<&'m Name> = HashSet::new();
+    let mut worklist = vec![entry];
+    while let Some(block) = worklist.pop() {
+        if blocks.insert(block) {
+            for succ in cfg.succs(block) {
+                match succ {
+                    _ if succ == exit => {}
+                    // control may only leave the region through `exit`
+                    CFGNode::Return => return None,
+                    CFGNode::Block(succ) => worklist.push(succ),
+                }
+            }
+        }
+    }
+    // control may only enter the region through `entry`, so no other block in
+    // the region may be the function's entry block or have a predecessor
+    // outside the region. (Predecessors which are unreachable don't count, so
+    // that unreachable code doesn't break up regions.)
+    let entered_elsewhere = blocks.iter().any(|&block| {
+        block != entry
+            && (block == cfg.entry()
+                || cfg
+                    .preds(block)
+                    .any(|pred| !blocks.contains(pred) && reachable.contains(pred)))
+    });
+    if entered_elsewhere {
+        None
+    } else {
+        Some(blocks)
+    }
+}
@@ -0,0 +1,272 @@
+//! `serde::Serialize` implementations for the analysis graphs, enabled by the
+//! `serde` feature.
+//!
+//! Block names are serialized as strings in LLVM syntax (e.g., `"%5"` or
+//! `"%entry"`), and the special `CFGNode::Return` as the string `"Return"`.
+//! Function names are serialized as strings, except for functions with
+//! numbered names, which are serialized as structs with `module` and `n`
+//! fields, so that `@0` in one `Module` is distinct from `@0` in another.
+//! Graphs are serialized as structs whose fields list their nodes and edges in
+//! a deterministic order.
+
+use crate::call_graph::{CallEdgeKind, CallGraph, CallSite, CallSiteKind, FunctionName};
+use crate::control_dep_graph::ControlDependenceGraph;
+use crate::control_flow_graph::{CFGEdgeKind, CFGNode, ControlFlowGraph, ExitKind};
+use crate::dominator_tree::{DominatorTree, PostDominatorTree};
+use petgraph::prelude::{DiGraphMap, Direction};
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, SerializeStruct, Serializer};
+
+impl<'m> Serialize for CFGNode<'m> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'m> Serialize for CFGEdgeKind<'m> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        const NAME: &str = "CFGEdgeKind";
+        match self {
+            CFGEdgeKind::Unconditional => {
+                serializer.serialize_unit_variant(NAME, 0, "Unconditional")
+            }
+            CFGEdgeKind::CondBr(b) => serializer.serialize_newtype_variant(NAME, 1, "CondBr", b),
+            CFGEdgeKind::SwitchCase(value) => {
+                serializer.serialize_newtype_variant(NAME, 2, "SwitchCase", &value.to_string())
+            }
+            CFGEdgeKind::SwitchDefault => {
+                serializer.serialize_unit_variant(NAME, 3, "SwitchDefault")
+            }
+            CFGEdgeKind::IndirectBr => serializer.serialize_unit_variant(NAME, 4, "IndirectBr"),
+            CFGEdgeKind::InvokeNormal => serializer.serialize_unit_variant(NAME, 5, "InvokeNormal"),
+            CFGEdgeKind::InvokeException => {
+                serializer.serialize_unit_variant(NAME, 6, "InvokeException")
+            }
+            CFGEdgeKind::CatchHandler => serializer.serialize_unit_variant(NAME, 7, "CatchHandler"),
+            CFGEdgeKind::Unwind => serializer.serialize_unit_variant(NAME, 8, "Unwind"),
+            CFGEdgeKind::Return => serializer.serialize_unit_variant(NAME, 9, "Return"),
+            CFGEdgeKind::Resume => serializer.serialize_unit_variant(NAME, 10, "Resume"),
+            CFGEdgeKind::Unreachable => serializer.serialize_unit_variant(NAME, 11, "Unreachable"),
+            CFGEdgeKind::CallBrFallthrough => {
+                serializer.serialize_unit_variant(NAME, 12, "CallBrFallthrough")
+            }
+            CFGEdgeKind::CallBrIndirect => {
+                serializer.serialize_unit_variant(NAME, 13, "CallBrIndirect")
+            }
+            CFGEdgeKind::Longjmp => serializer.serialize_unit_variant(NAME, 14, "Longjmp"),
+            CFGEdgeKind::InfiniteLoop => {
+                serializer.serialize_unit_variant(NAME, 15, "InfiniteLoop")
+            }
+        }
+    }
+}
+
+impl Serialize for ExitKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        const NAME: &str = "ExitKind";
+        match self {
+            ExitKind::Return => serializer.serialize_unit_variant(NAME, 0, "Return"),
+            ExitKind::Unreachable => serializer.serialize_unit_variant(NAME, 1, "Unreachable"),
+            ExitKind::Unwind => serializer.serialize_unit_variant(NAME, 2, "Unwind"),
+            ExitKind::InfiniteLoop => serializer.serialize_unit_variant(NAME, 3, "InfiniteLoop"),
+        }
+    }
+}
+
+/// A single edge of a graph, serialized as a struct with `from` and `to`
+/// fields, and the edge's weight in a field named `weight_name`
+struct Edge<'a, N, W> {
+    from: N,
+    to: N,
+    weight_name: &'static str,
+    weight: &'a W,
+}
+
+impl<'a, N: Serialize, W: Serialize> Serialize for Edge<'a, N, W> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Edge", 3)?;
+        state.serialize_field("from", &self.from)?;
+        state.serialize_field("to", &self.to)?;
+        state.serialize_field(self.weight_name, self.weight)?;
+        state.end()
+    }
+}
+
+/// Serializes the edges of a graph as a sequence of `Edge`s
+struct Edges<'a, N, W> {
+    graph: &'a DiGraphMap<N, W>,
+    weight_name: &'static str,
+}
+
+impl<'a, N, W> Serialize for Edges<'a, N, W>
+where
+    N: Serialize + Copy + Ord + std::hash::Hash,
+    W: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.graph.edge_count()))?;
+        for (from, to, weight) in self.graph.all_edges() {
+            seq.serialize_element(&Edge {
+                from,
+                to,
+                weight_name: self.weight_name,
+                weight,
+            })?;
+        }
+        seq.end()
+    }
+}
+
+/// Serializes a tree, stored as a graph with edges from parent to child, as a
+/// map from each child to its parent
+struct Parents<'a, 'm> {
+    tree: &'a DiGraphMap<CFGNode<'m>, ()>,
+}
+
+impl<'a, 'm> Serialize for Parents<'a, 'm> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.tree.edge_count()))?;
+        for (parent, child, _) in self.tree.all_edges() {
+            map.serialize_entry(&child, &parent)?;
+        }
+        map.end()
+    }
+}
+
+impl<'m> Serialize for ControlFlowGraph<'m> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let blocks: Vec<CFGNode<'m>> = self
+            .graph
+            .nodes()
+            .filter(|&node| node != CFGNode::Return)
+            .collect();
+        let mut state = serializer.serialize_struct("ControlFlowGraph", 4)?;
+        state.serialize_field("entry", &self.entry_node)?;
+        state.serialize_field("exits", &self.exits)?;
+        state.serialize_field("blocks", &blocks)?;
+        state.serialize_field(
+            "edges",
+            &Edges {
+                graph: &self.graph,
+                weight_name: "kinds",
+            },
+        )?;
+        state.end()
+    }
+}
+
+impl<'m> Serialize for DominatorTree<'m> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("DominatorTree", 2)?;
+        state.serialize_field("entry", &self.entry_node)?;
+        state.serialize_field("idoms", &Parents { tree: &self.graph })?;
+        state.end()
+    }
+}
+
+impl<'m> Serialize for PostDominatorTree<'m> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("PostDominatorTree", 1)?;
+        state.serialize_field("ipostdoms", &Parents { tree: &self.graph })?;
+        state.end()
+    }
+}
+
+/// Serializes the immediate control dependencies of each node which has any,
+/// as a map
+struct ImmControlDependencies<'a, 'm> {
+    graph: &'a DiGraphMap<CFGNode<'m>, Vec<CFGEdgeKind<'m>>>,
+}
+
+impl<'a, 'm> Serialize for ImmControlDependencies<'a, 'm> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        for node in self.graph.nodes() {
+            let deps: Vec<CFGNode<'m>> = self
+                .graph
+                .neighbors_directed(node, Direction::Outgoing)
+                .collect();
+            if !deps.is_empty() {
+                map.serialize_entry(&node, &deps)?;
+            }
+        }
+        map.end()
+    }
+}
+
+impl<'m> Serialize for ControlDependenceGraph<'m> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ControlDependenceGraph", 2)?;
+        state.serialize_field("entry", &self.entry_node)?;
+        state.serialize_field(
+            "imm_control_dependencies",
+            &ImmControlDependencies { graph: &self.graph },
+        )?;
+        state.end()
+    }
+}
+
+impl Serialize for CallSiteKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        const NAME: &str = "CallSiteKind";
+        match self {
+            CallSiteKind::Call => serializer.serialize_unit_variant(NAME, 0, "Call"),
+            CallSiteKind::Invoke => serializer.serialize_unit_variant(NAME, 1, "Invoke"),
+            CallSiteKind::CallBr => serializer.serialize_unit_variant(NAME, 2, "CallBr"),
+        }
+    }
+}
+
+impl Serialize for CallEdgeKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        const NAME: &str = "CallEdgeKind";
+        match self {
+            CallEdgeKind::Direct => serializer.serialize_unit_variant(NAME, 0, "Direct"),
+            CallEdgeKind::Indirect => serializer.serialize_unit_variant(NAME, 1, "Indirect"),
+            CallEdgeKind::Spawn => serializer.serialize_unit_variant(NAME, 2, "Spawn"),
+            CallEdgeKind::Callback => serializer.serialize_unit_variant(NAME, 3, "Callback"),
+        }
+    }
+}
+
+impl<'m> Serialize for FunctionName<'m> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            FunctionName::Named(name) => serializer.serialize_str(name),
+            FunctionName::Numbered { module, n } => {
+                let mut state = serializer.serialize_struct("Numbered", 2)?;
+                state.serialize_field("module", module)?;
+                state.serialize_field("n", n)?;
+                state.end()
+            }
+        }
+    }
+}
+
+impl<'m> Serialize for CallSite<'m> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("CallSite", 6)?;
+        state.serialize_field("caller", &self.caller)?;
+        state.serialize_field("block", &self.block.to_string())?;
+        state.serialize_field("index", &self.index)?;
+        state.serialize_field("kind", &self.kind)?;
+        state.serialize_field("edge_kind", &self.edge_kind)?;
+        state.serialize_field("is_tail_call", &self.is_tail_call)?;
+        state.end()
+    }
+}
+
+impl<'m> Serialize for CallGraph<'m> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let functions: Vec<FunctionName<'m>> = self.graph.nodes().collect();
+        let mut state = serializer.serialize_struct("CallGraph", 2)?;
+        state.serialize_field("functions", &functions)?;
+        state.serialize_field(
+            "edges",
+            &Edges {
+                graph: &self.graph,
+                weight_name: "call_sites",
+            },
+        )?;
+        state.end()
+    }
+}
@@ -0,0 +1,279 @@
+use crate::call_graph::{direct_callee, functions_with_names, CallGraph, FunctionName};
+use crate::control_flow_graph::{CFGEdgeKind, CFGNode, ControlFlowGraph};
+use llvm_ir::{function::FunctionAttribute, Function, Instruction, Module, Name, Terminator};
+use petgraph::prelude::Direction;
+use std::collections::{HashMap, HashSet};
+
+/// Names of functions which, like `setjmp`, may return more than once
+const SETJMP_FUNCS: &[&str] = &[
+    "setjmp",
+    "_setjmp",
+    "__setjmp",
+    "sigsetjmp",
+    "__sigsetjmp",
+    "savectx",
+    "vfork",
+    "getcontext",
+];
+
+/// Names of functions which, like `longjmp`, transfer control back to a
+/// previous `setjmp`
+const LONGJMP_FUNCS: &[&str] = &[
+    "longjmp",
+    "_longjmp",
+    "__longjmp_chk",
+    "siglongjmp",
+    "__siglongjmp",
+    "setcontext",
+];
+
+/// Identifies `setjmp`-family and `longjmp`-family call sites in the analyzed
+/// `Module`(s), and the functions whose CFGs are affected by them.
+///
+/// A function which calls `setjmp` (or any other function that may return
+/// twice) has control flow which is not represented in its
+/// [`ControlFlowGraph`](struct.ControlFlowGraph.html): a later `longjmp` may
+/// transfer control back to just after the `setjmp` call. Dominance and
+/// control-dependence results for such functions should therefore be treated
+/// with caution.
+///
+/// To construct a `SetjmpAnalysis`, use [`ModuleAnalysis`](struct.ModuleAnalysis.html)
+/// or [`CrossModuleAnalysis`](struct.CrossModuleAnalysis.html).
+pub struct SetjmpAnalysis<'m> {
+    /// All calls to `setjmp`-family functions
+    setjmp_calls: Vec<JmpCallSite<'m>>,
+    /// All calls to `longjmp`-family functions
+    longjmp_calls: Vec<JmpCallSite<'m>>,
+    /// Map from the name of each function which calls a `setjmp`-family
+    /// function, to that `Function`
+    setjmp_funcs: HashMap<FunctionName<'m>, &'m Function>,
+    /// Map from the name of each function which calls a `setjmp`-family
+    /// function, to the abnormal edges which should be added to its CFG.
+    /// Each edge is from a block containing a call which may (directly or
+    /// transitively) reach a `longjmp`, to a block containing a `setjmp`
+    abnormal_edges: HashMap<FunctionName<'m>, Vec<(&'m Name, &'m Name)>>,
+}
+
+/// A call to a `setjmp`-family or `longjmp`-family function
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct JmpCallSite<'m> {
+    /// Name of the function containing the call
+    pub caller: FunctionName<'m>,
+    /// Name of the basic block containing the call
+    pub block: &'m Name,
+    /// Index of the call within the basic block. If the call is the block's
+    /// terminator (e.g., an `Invoke`), this is equal to the number of
+    /// (non-terminator) instructions in the block.
+    pub index: usize,
+    /// Name of the function being called (e.g., `"_setjmp"` or `"longjmp"`),
+    /// or `None` for a call through a function pointer, which is a `setjmp`
+    /// if the call is marked `returns_twice`
+    pub callee: Option<&'m str>,
+}
+
+impl<'m> SetjmpAnalysis<'m> {
+    pub(crate) fn new(
+        modules: impl IntoIterator<Item = &'m Module>,
+        call_graph: &CallGraph<'m>,
+    ) -> Self {
+        let mut setjmp_calls = vec![];
+        let mut longjmp_calls = vec![];
+        let mut setjmp_funcs = HashMap::new();
+
+        for module in modules {
+            for (f, name) in functions_with_names(module) {
+                for bb in &f.basic_blocks {
+                    for (index, callee, attrs) in calls_in_block(bb) {
+                        let site = JmpCallSite {
+                            caller: name,
+                            block: &bb.name,
+                            index,
+                            callee,
+                        };
+                        // a call through a function pointer is a `setjmp` if
+                        // it's marked `returns_twice`
+                        if callee.is_some_and(|callee| SETJMP_FUNCS.contains(&callee))
+                            || attrs.contains(&FunctionAttribute::ReturnsTwice)
+                        {
+                            setjmp_calls.push(site);
+                            setjmp_funcs.insert(name, f);
+                        } else if callee.is_some_and(|callee| LONGJMP_FUNCS.contains(&callee)) {
+                            longjmp_calls.push(site);
+                        }
+                    }
+                }
+            }
+        }
+
+        // All functions which may (directly or transitively) call a
+        // `longjmp`-family function, including those functions themselves
+        let mut reaches_longjmp: HashSet<FunctionName<'m>> = HashSet::new();
+        let mut worklist: Vec<FunctionName<'m>> = longjmp_calls
+            .iter()
+            .filter_map(|site| site.callee.map(FunctionName::Named))
+            .collect();
+        while let Some(func) = worklist.pop() {
+            if reaches_longjmp.insert(func) && call_graph.graph.contains_node(func) {
+                worklist.extend(
+                    call_graph
+                        .graph
+                        .neighbors_directed(func, Direction::Incoming),
+                );
+            }
+        }
+
+        let mut abnormal_edges: HashMap<FunctionName<'m>, Vec<(&'m Name, &'m Name)>> =
+            HashMap::new();
+        for (&func_name, func) in &setjmp_funcs {
+            let setjmp_sites: HashSet<(&'m Name, usize)> = setjmp_calls
+                .iter()
+                .filter(|site| site.caller == func_name)
+                .map(|site| (site.block, site.index))
+                .collect();
+            let setjmp_blocks: Vec<&'m Name> = setjmp_calls
+                .iter()
+                .filter(|site| site.caller == func_name)
+                .map(|site| site.block)
+                .collect();
+            let edges = abnormal_edges.entry(func_name).or_default();
+            for bb in &func.basic_blocks {
+                let may_longjmp = calls_in_block(bb)
+                    // the `setjmp` calls themselves don't `longjmp`, even
+                    // through a function pointer
+                    .filter(|&(index, _, _)| !setjmp_sites.contains(&(&bb.name, index)))
+                    .any(|(_, callee, _)| match callee {
+                        Some(callee) => reaches_longjmp.contains(&FunctionName::Named(callee)),
+                        // we don't know where an indirect call goes, so
+                        // conservatively assume it may reach a `longjmp`
+                        None => !reaches_longjmp.is_empty(),
+                    });
+                if may_longjmp {
+                    for &setjmp_block in &setjmp_blocks {
+                        edges.push((&bb.name, setjmp_block));
+                    }
+                }
+            }
+        }
+
+        Self {
+            setjmp_calls,
+            longjmp_calls,
+            setjmp_funcs,
+            abnormal_edges,
+        }
+    }
+
+    /// Iterate over all of the calls to `setjmp`-family functions (functions
+    /// which may return twice) in the analyzed `Module`(s).
+    pub fn setjmp_calls<'s>(&'s self) -> impl Iterator<Item = &'s JmpCallSite<'m>> + 's {
+        self.setjmp_calls.iter()
+    }
+
+    /// Iterate over all of the calls to `longjmp`-family functions in the
+    /// analyzed `Module`(s).
+    pub fn longjmp_calls<'s>(&'s self) -> impl Iterator<Item = &'s JmpCallSite<'m>> + 's {
+        self.longjmp_calls.iter()
+    }
+
+    /// Iterate over the names of all functions which call a `setjmp`-family
+    /// function, sorted by name. The CFGs of these functions do not represent
+    /// the control flow introduced by `longjmp`, so dominance results for them
+    /// are unsound.
+    pub fn functions_with_setjmp<'s>(&'s self) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        let mut funcs: Vec<FunctionName<'m>> = self.setjmp_funcs.keys().copied().collect();
+        funcs.sort_unstable();
+        funcs.into_iter()
+    }
+
+    /// Does the function with the given name call a `setjmp`-family function?
+    pub fn has_setjmp<'n>(&self, func_name: impl Into<FunctionName<'n>>) -> bool {
+        self.setjmp_funcs.contains_key(&func_name.into())
+    }
+
+    /// Get the abnormal edges for the function with the given name.
+    ///
+    /// Each edge is a pair `(from, to)`, where `from` is a block containing a
+    /// call which may (directly or transitively) reach a `longjmp`, and `to` is
+    /// a block containing a `setjmp` which the `longjmp` may return to.
+    /// Indirect calls are conservatively assumed to reach a `longjmp` if any
+    /// function in the analyzed `Module`(s) does.
+    ///
+    /// This will be empty for functions which do not call a `setjmp`-family
+    /// function.
+    pub fn abnormal_edges<'s>(
+        &'s self,
+        func_name: impl Into<FunctionName<'m>>,
+    ) -> impl Iterator<Item = (&'m Name, &'m Name)> + 's {
+        self.abnormal_edges
+            .get(&func_name.into())
+            .into_iter()
+            .flatten()
+            .copied()
+    }
+
+    /// Get a `ControlFlowGraph` for the function with the given name which
+    /// includes the `abnormal_edges()` in addition to the normal edges.
+    ///
+    /// The CFG's nodes are whole basic blocks, so each abnormal edge goes to
+    /// the block containing the `setjmp` call, not to the point just after the
+    /// call where a `longjmp` actually resumes. The CFG therefore says the
+    /// instructions before the `setjmp` in that block may run again, which is
+    /// conservative for dominance, but may be imprecise for analyses which
+    /// look at the instructions in a block (e.g. definitions before the
+    /// `setjmp` appear to be reached by the `longjmp`).
+    ///
+    /// Returns `None` if the function does not call a `setjmp`-family function
+    /// (in which case its ordinary `ControlFlowGraph` is already sound).
+    pub fn cfg_with_abnormal_edges(
+        &self,
+        func_name: impl Into<FunctionName<'m>>,
+    ) -> Option<ControlFlowGraph<'m>> {
+        let func_name = func_name.into();
+        let func = self.setjmp_funcs.get(&func_name)?;
+        let mut cfg = ControlFlowGraph::new(func);
+        for (from, to) in self.abnormal_edges(func_name) {
+            cfg.add_edge(
+                CFGNode::Block(from),
+                CFGNode::Block(to),
+                CFGEdgeKind::Longjmp,
+            );
+        }
+        Some(cfg)
+    }
+}
+
+/// Iterate over the calls (and invokes) in the given basic block, yielding
+/// for each its index in the block, the name of the called function (or `None`
+/// if the call is not a direct call to a named function), and the call's
+/// function attributes.
+///
+/// Calls to inline assembly are skipped.
+fn calls_in_block(
+    bb: &llvm_ir::BasicBlock,
+) -> impl Iterator<Item = (usize, Option<&str>, &[FunctionAttribute])> {
+    let calls = bb
+        .instrs
+        .iter()
+        .enumerate()
+        .filter_map(|(i, inst)| match inst {
+            Instruction::Call(call) => Some((
+                i,
+                call.function.as_ref().right()?,
+                call.function_attributes.as_slice(),
+            )),
+            _ => None,
+        });
+    let invoke = match &bb.term {
+        Terminator::Invoke(invoke) => invoke.function.as_ref().right().map(|callee| {
+            (
+                bb.instrs.len(),
+                callee,
+                invoke.function_attributes.as_slice(),
+            )
+        }),
+        _ => None,
+    };
+    calls
+        .chain(invoke)
+        .map(|(i, callee, attrs)| (i, direct_callee(callee), attrs))
+}
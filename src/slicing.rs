@@ -0,0 +1,245 @@
+use crate::control_dep_graph::ControlDependenceGraph;
+use crate::data_dep_graph::DataDependenceGraph;
+use crate::def_use::{DefUseChains, Definition, InstructionLocation};
+use llvm_ir::{Function, Instruction, Name, Terminator};
+use std::collections::{HashMap, HashSet};
+
+/// The starting point of a program slice; see
+/// [`FunctionAnalysis::backward_slice()`](struct.FunctionAnalysis.html#method.backward_slice)
+/// and
+/// [`FunctionAnalysis::forward_slice()`](struct.FunctionAnalysis.html#method.forward_slice)
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum SliceCriterion<'m> {
+    /// The instruction (or terminator) at the given location
+    Instruction(InstructionLocation<'m>),
+    /// The local value with the given `Name`: the instruction defining it, or
+    /// for a parameter, the parameter itself
+    Value(&'m Name),
+}
+
+/// A program slice of a particular function: the instructions (and
+/// terminators) which may influence, or may be influenced by, a
+/// [`SliceCriterion`](enum.SliceCriterion.html), through data or control
+/// dependences.
+///
+/// To compute a `Slice`, use
+/// [`FunctionAnalysis::backward_slice()`](struct.FunctionAnalysis.html#method.backward_slice)
+/// or
+/// [`FunctionAnalysis::forward_slice()`](struct.FunctionAnalysis.html#method.forward_slice).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Slice<'m> {
+    /// The instructions in the slice, in the order they appear in the function
+    instructions: Vec<InstructionLocation<'m>>,
+    /// The same instructions, for fast lookup
+    instruction_set: HashSet<InstructionLocation<'m>>,
+    /// The blocks containing at least one instruction in the slice, in the
+    /// order they appear in the function
+    blocks: Vec<&'m Name>,
+}
+
+impl<'m> Slice<'m> {
+    /// Get the instructions (and terminators) in the slice, in the order they
+    /// appear in the function. This includes the criterion itself, if it's an
+    /// instruction or the result of one.
+    pub fn instructions(&self) -> &[InstructionLocation<'m>] {
+        &self.instructions
+    }
+
+    /// Get the blocks containing at least one instruction in the slice, in the
+    /// order they appear in the function
+    pub fn blocks(&self) -> &[&'m Name] {
+        &self.blocks
+    }
+
+    /// Is the instruction at the given location in the slice?
+    pub fn contains(&self, inst: InstructionLocation<'m>) -> bool {
+        self.instruction_set.contains(&inst)
+    }
+
+    /// Does the slice contain at least one instruction of the block with the
+    /// given `Name`?
+    pub fn contains_block(&self, block: &Name) -> bool {
+        self.blocks.contains(&block)
+    }
+
+    /// Get the number of instructions in the slice
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+    /// Is the slice empty? This is only the case for a criterion which is a
+    /// parameter that no instruction depends on (in a forward slice) or any
+    /// parameter (in a backward slice).
+    pub fn is_empty(&self) -> bool {
+        self.instructions.is_empty()
+    }
+}
+
+/// The direction of a slice
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum SliceDirection {
+    Backward,
+    Forward,
+}
+
+/// The data and control dependences of a function at the instruction level,
+/// i.e., its program dependence graph
+pub(crate) struct Slicer<'a, 'm> {
+    function: &'m Function,
+    ddg: &'a DataDependenceGraph<'m>,
+    def_use: &'a DefUseChains<'m>,
+    /// Map from each instruction to the terminators it is immediately control
+    /// dependent on
+    control_deps: HashMap<InstructionLocation<'m>, Vec<InstructionLocation<'m>>>,
+    /// The inverse of `control_deps`
+    control_dependents: HashMap<InstructionLocation<'m>, Vec<InstructionLocation<'m>>>,
+}
+
+impl<'a, 'm> Slicer<'a, 'm> {
+    pub(crate) fn new(
+        function: &'m Function,
+        cdg: &ControlDependenceGraph<'m>,
+        ddg: &'a DataDependenceGraph<'m>,
+        def_use: &'a DefUseChains<'m>,
+    ) -> Self {
+        let terminators: HashMap<&'m Name, InstructionLocation<'m>> = function
+            .basic_blocks
+            .iter()
+            .map(|bb| {
+                let term = InstructionLocation {
+                    block: &bb.name,
+                    index: bb.instrs.len(),
+                };
+                (&bb.name, term)
+            })
+            .collect();
+        let block_controllers = |block: &'m Name| -> Vec<InstructionLocation<'m>> {
+            cdg.get_imm_control_dependencies(block)
+                .map(|controller| terminators[controller])
+                .collect()
+        };
+        let mut control_deps: HashMap<InstructionLocation<'m>, Vec<InstructionLocation<'m>>> =
+            HashMap::new();
+        for bb in &function.basic_blocks {
+            let controllers = block_controllers(&bb.name);
+            for index in 0..=bb.instrs.len() {
+                let location = InstructionLocation {
+                    block: &bb.name,
+                    index,
+                };
+                let mut deps = controllers.clone();
+                // which incoming value a `phi` takes is decided by the
+                // branches which decide which predecessor executes
+                if let Some(Instruction::Phi(phi)) = bb.instrs.get(index) {
+                    for (_, pred) in &phi.incoming_values {
+                        if let Some(&pred_term) = terminators.get(pred) {
+                            // an unconditional `br` decides nothing
+                            let pred_term_inst = function.get_bb_by_name(pred).map(|bb| &bb.term);
+                            if !matches!(pred_term_inst, Some(Terminator::Br(_))) {
+                                deps.push(pred_term);
+                            }
+                            deps.extend(block_controllers(pred));
+                        }
+                    }
+                }
+                deps.sort_unstable();
+                deps.dedup();
+                control_deps.insert(location, deps);
+            }
+        }
+        let mut control_dependents: HashMap<_, Vec<_>> = HashMap::new();
+        for (&location, deps) in &control_deps {
+            for &dep in deps {
+                control_dependents.entry(dep).or_default().push(location);
+            }
+        }
+        Self {
+            function,
+            ddg,
+            def_use,
+            control_deps,
+            control_dependents,
+        }
+    }
+
+    pub(crate) fn slice(
+        &self,
+        criterion: SliceCriterion<'_>,
+        direction: SliceDirection,
+    ) -> Slice<'m> {
+        let mut worklist: Vec<InstructionLocation<'m>> = match criterion {
+            SliceCriterion::Instruction(inst) => {
+                let bb = self.function.get_bb_by_name(inst.block).unwrap_or_else(|| {
+                    panic!(
+                        "slice(): block {} not found in function {}",
+                        inst.block, self.function.name
+                    )
+                });
+                vec![InstructionLocation {
+                    block: &bb.name,
+                    index: inst.index,
+                }]
+            }
+            SliceCriterion::Value(value) => match self.def_use.definition(value) {
+                Some(Definition::Instruction(inst)) => vec![inst],
+                Some(Definition::Parameter(_)) => match direction {
+                    SliceDirection::Backward => vec![],
+                    SliceDirection::Forward => self.def_use.uses(value).to_vec(),
+                },
+                None => panic!(
+                    "slice(): value {} not found in function {}",
+                    value, self.function.name
+                ),
+            },
+        };
+        let mut instruction_set: HashSet<InstructionLocation<'m>> =
+            worklist.iter().copied().collect();
+        while let Some(inst) = worklist.pop() {
+            let next: Vec<InstructionLocation<'m>> = match direction {
+                SliceDirection::Backward => self
+                    .ddg
+                    .dependencies(inst)
+                    .chain(self.control_deps[&inst].iter().copied())
+                    .collect(),
+                SliceDirection::Forward => self
+                    .ddg
+                    .dependents(inst)
+                    .chain(
+                        self.control_dependents
+                            .get(&inst)
+                            .into_iter()
+                            .flatten()
+                            .copied(),
+                    )
+                    .collect(),
+            };
+            for next in next {
+                if instruction_set.insert(next) {
+                    worklist.push(next);
+                }
+            }
+        }
+
+        let mut instructions = Vec::new();
+        let mut blocks = Vec::new();
+        for bb in &self.function.basic_blocks {
+            let before = instructions.len();
+            instructions.extend(
+                (0..=bb.instrs.len())
+                    .map(|index| InstructionLocation {
+                        block: &bb.name,
+                        index,
+                    })
+                    .filter(|location| instruction_set.contains(location)),
+            );
+            if instructions.len() > before {
+                blocks.push(&bb.name);
+            }
+        }
+        Slice {
+            instructions,
+            instruction_set,
+            blocks,
+        }
+    }
+}
@@ -0,0 +1,523 @@
+use crate::call_graph::{direct_callee, functions_with_names, FunctionName};
+use crate::data_dep_graph::DataDependenceKind;
+use crate::def_use::InstructionLocation;
+use crate::ModuleAnalysis;
+use either::Either;
+use llvm_ir::function::ParameterAttribute;
+use llvm_ir::instruction::InlineAssembly;
+use llvm_ir::{Function, Instruction, Name, Operand, Terminator};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+/// A way of selecting functions by name, for specifying the sources and sinks
+/// of a [`TaintConfig`](struct.TaintConfig.html)
+pub enum FunctionMatcher {
+    /// Match the function with exactly this name
+    Name(String),
+    /// Match the functions whose names match this regex anywhere (use `^` and
+    /// `$` to match the whole name). Requires the `regex` feature.
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+    /// Match the functions whose names this callback returns `true` for
+    Callback(Box<dyn Fn(&str) -> bool>),
+}
+
+impl FunctionMatcher {
+    /// Does the function with the given name match?
+    pub fn matches(&self, func_name: &str) -> bool {
+        match self {
+            Self::Name(name) => name == func_name,
+            #[cfg(feature = "regex")]
+            Self::Regex(regex) => regex.is_match(func_name),
+            Self::Callback(callback) => callback(func_name),
+        }
+    }
+
+    /// Does the given function match? A function with a numbered name is
+    /// matched by its displayed name, e.g. `@0`.
+    fn matches_function(&self, func_name: FunctionName) -> bool {
+        match func_name {
+            FunctionName::Named(name) => self.matches(name),
+            FunctionName::Numbered { .. } => self.matches(&func_name.to_string()),
+        }
+    }
+}
+
+impl fmt::Debug for FunctionMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Name(name) => f.debug_tuple("Name").field(name).finish(),
+            #[cfg(feature = "regex")]
+            Self::Regex(regex) => f.debug_tuple("Regex").field(regex).finish(),
+            Self::Callback(_) => f.write_str("Callback(..)"),
+        }
+    }
+}
+
+impl From<&str> for FunctionMatcher {
+    fn from(name: &str) -> Self {
+        Self::Name(name.into())
+    }
+}
+
+impl From<String> for FunctionMatcher {
+    fn from(name: String) -> Self {
+        Self::Name(name)
+    }
+}
+
+#[cfg(feature = "regex")]
+impl From<regex::Regex> for FunctionMatcher {
+    fn from(regex: regex::Regex) -> Self {
+        Self::Regex(regex)
+    }
+}
+
+/// Where tainted data comes from
+#[derive(Debug)]
+enum TaintSource {
+    /// The result of every call to a matching function
+    ReturnValue(FunctionMatcher),
+    /// The parameter with the given index of every matching function defined
+    /// in the `Module`
+    Parameter(FunctionMatcher, usize),
+}
+
+/// Where tainted data must not go: the argument with the given index (or any
+/// argument, if `None`) of every call to a matching function
+#[derive(Debug)]
+struct TaintSink(FunctionMatcher, Option<usize>);
+
+/// The sources and sinks for a [`TaintAnalysis`](struct.TaintAnalysis.html).
+///
+/// Sources and sinks select functions with a
+/// [`FunctionMatcher`](enum.FunctionMatcher.html); anything convertible into
+/// one (e.g., a `&str` function name) may be passed.
+#[derive(Debug, Default)]
+pub struct TaintConfig {
+    sources: Vec<TaintSource>,
+    sinks: Vec<TaintSink>,
+}
+
+impl TaintConfig {
+    /// Create a `TaintConfig` with no sources or sinks
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the results of calls to the matching functions (e.g., `getenv`) as
+    /// a source, returning the modified `TaintConfig`
+    pub fn with_source_return(mut self, function: impl Into<FunctionMatcher>) -> Self {
+        self.sources.push(TaintSource::ReturnValue(function.into()));
+        self
+    }
+
+    /// Add the parameter with the given index of the matching functions (e.g.,
+    /// `argv` of `main`) as a source, returning the modified `TaintConfig`.
+    /// Only functions defined in the `Module` have parameters to taint.
+    pub fn with_source_parameter(
+        mut self,
+        function: impl Into<FunctionMatcher>,
+        index: usize,
+    ) -> Self {
+        self.sources
+            .push(TaintSource::Parameter(function.into(), index));
+        self
+    }
+
+    /// Add the argument with the given index (or any argument, if `None`) of
+    /// calls to the matching functions (e.g., `system`) as a sink, returning
+    /// the modified `TaintConfig`
+    pub fn with_sink(
+        mut self,
+        function: impl Into<FunctionMatcher>,
+        argument: Option<usize>,
+    ) -> Self {
+        self.sinks.push(TaintSink(function.into(), argument));
+        self
+    }
+}
+
+/// A step in the path of a [`TaintFlow`](struct.TaintFlow.html)
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub enum TaintStep<'m> {
+    /// The parameter with the given index of the given function
+    Parameter {
+        /// Name of the function
+        function: FunctionName<'m>,
+        /// Index of the parameter
+        index: usize,
+    },
+    /// The instruction (or terminator) at the given location in the given
+    /// function
+    Instruction {
+        /// Name of the function
+        function: FunctionName<'m>,
+        /// Location of the instruction within the function
+        location: InstructionLocation<'m>,
+    },
+}
+
+/// A flow of tainted data from a source to a sink
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TaintFlow<'m> {
+    /// Name of the sink function called
+    pub sink: FunctionName<'m>,
+    /// Index of the tainted argument of the call to `sink`
+    pub argument: usize,
+    /// A witness of the flow: the steps the tainted data takes, starting with
+    /// the source (a call to a source function, or a source parameter) and
+    /// ending with the call to `sink`. Each step is a parameter, or an
+    /// instruction which produces tainted data, stores it to memory, returns
+    /// it, or passes it to a function.
+    pub path: Vec<TaintStep<'m>>,
+}
+
+impl<'m> TaintFlow<'m> {
+    /// Get the source where the tainted data originates
+    pub fn source(&self) -> TaintStep<'m> {
+        self.path[0]
+    }
+
+    /// Get the call to the sink function which the tainted data reaches
+    pub fn sink_call(&self) -> TaintStep<'m> {
+        self.path[self.path.len() - 1]
+    }
+}
+
+/// A fact in the taint propagation
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+enum Node<'m> {
+    /// The result of the instruction at this location is tainted
+    Value(FunctionName<'m>, InstructionLocation<'m>),
+    /// The parameter with this index is tainted
+    Parameter(FunctionName<'m>, usize),
+    /// The `store` at this location writes tainted data to memory
+    Memory(FunctionName<'m>, InstructionLocation<'m>),
+    /// The call at this location is passed tainted data as the argument with
+    /// this index
+    Argument(FunctionName<'m>, InstructionLocation<'m>, usize),
+    /// The `ret` at this location returns tainted data
+    Return(FunctionName<'m>, InstructionLocation<'m>),
+}
+
+impl<'m> Node<'m> {
+    fn step(self) -> TaintStep<'m> {
+        match self {
+            Self::Parameter(function, index) => TaintStep::Parameter { function, index },
+            Self::Value(function, location)
+            | Self::Memory(function, location)
+            | Self::Argument(function, location, _)
+            | Self::Return(function, location) => TaintStep::Instruction { function, location },
+        }
+    }
+}
+
+/// The results of an interprocedural taint analysis of a `Module`: the flows
+/// of data from the sources to the sinks of a
+/// [`TaintConfig`](struct.TaintConfig.html).
+///
+/// Taint propagates along explicit data flow only: from each operand of an
+/// instruction to its result, from a tainted value stored to memory to each
+/// `load` which may read it (according to each function's
+/// [`DataDependenceGraph`](struct.DataDependenceGraph.html)), from the
+/// arguments of a direct call of a function defined in the `Module` to its
+/// parameters, and from the value it returns to the results of all of its
+/// direct calls. Calls of other functions (declarations, indirect calls, and
+/// inline assembly) propagate taint from their arguments to their result.
+/// Branching on tainted data taints nothing, and a tainted address taints
+/// the value loaded from it, but not the memory stored to it.
+///
+/// The analysis is context-insensitive: if a function returns tainted data
+/// for one of its calls, all of its calls are considered tainted. So a
+/// witness path may enter a function through one call and leave through
+/// another.
+///
+/// To construct a `TaintAnalysis`, use
+/// [`ModuleAnalysis::taint_analysis()`](struct.ModuleAnalysis.html#method.taint_analysis).
+pub struct TaintAnalysis<'m> {
+    /// The flows found, sorted by the call to the sink and the argument
+    flows: Vec<TaintFlow<'m>>,
+    /// The tainted local values of each function
+    tainted: HashMap<FunctionName<'m>, HashSet<&'m Name>>,
+}
+
+impl<'m> TaintAnalysis<'m> {
+    pub(crate) fn new(analysis: &ModuleAnalysis<'m>, config: &TaintConfig) -> Self {
+        let module = analysis.module();
+        let functions: HashMap<FunctionName<'m>, &'m Function> = functions_with_names(module)
+            .map(|(f, name)| (name, f))
+            .collect();
+        // Map from each function name to the direct calls of it
+        let mut calls: HashMap<FunctionName<'m>, Vec<(FunctionName<'m>, InstructionLocation<'m>)>> =
+            HashMap::new();
+        for (f, name) in functions_with_names(module) {
+            for bb in &f.basic_blocks {
+                let insts = bb
+                    .instrs
+                    .iter()
+                    .map(Either::Left)
+                    .chain(std::iter::once(Either::Right(&bb.term)));
+                for (index, inst) in insts.enumerate() {
+                    if let Some((Either::Right(callee), _)) = call_info(inst) {
+                        if let Some(callee) = direct_callee(callee) {
+                            let location = InstructionLocation {
+                                block: &bb.name,
+                                index,
+                            };
+                            calls
+                                .entry(FunctionName::Named(callee))
+                                .or_default()
+                                .push((name, location));
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut worklist: VecDeque<Node<'m>> = VecDeque::new();
+        let mut preds: HashMap<Node<'m>, Option<Node<'m>>> = HashMap::new();
+
+        let mut callees: Vec<FunctionName<'m>> = calls.keys().copied().collect();
+        callees.sort_unstable();
+        let mut defined: Vec<(&'m Function, FunctionName<'m>)> =
+            functions_with_names(module).collect();
+        defined.sort_unstable_by_key(|&(_, name)| name);
+        for source in &config.sources {
+            match source {
+                TaintSource::ReturnValue(matcher) => {
+                    for &callee in callees
+                        .iter()
+                        .filter(|&&callee| matcher.matches_function(callee))
+                    {
+                        for &(caller, location) in &calls[&callee] {
+                            reach(
+                                Node::Value(caller, location),
+                                None,
+                                &mut preds,
+                                &mut worklist,
+                            );
+                        }
+                    }
+                }
+                TaintSource::Parameter(matcher, index) => {
+                    for &(f, name) in defined
+                        .iter()
+                        .filter(|&&(_, name)| matcher.matches_function(name))
+                    {
+                        if *index < f.parameters.len() {
+                            reach(
+                                Node::Parameter(name, *index),
+                                None,
+                                &mut preds,
+                                &mut worklist,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut flows: Vec<TaintFlow<'m>> = Vec::new();
+        let mut tainted: HashMap<FunctionName<'m>, HashSet<&'m Name>> = HashMap::new();
+        while let Some(node) = worklist.pop_front() {
+            let mut next: Vec<Node<'m>> = Vec::new();
+            match node {
+                Node::Value(function, _) | Node::Parameter(function, _) => {
+                    let f = functions[&function];
+                    let def_use = analysis.fn_analysis(function).def_use_chains();
+                    let value = match node {
+                        Node::Parameter(_, index) => &f.parameters[index].name,
+                        Node::Value(_, location) => match result(def_use.instruction(location)) {
+                            Some(result) => result,
+                            None => continue,
+                        },
+                        _ => unreachable!(),
+                    };
+                    tainted.entry(function).or_default().insert(value);
+                    let is_value = |operand: &Operand| matches!(operand, Operand::LocalOperand { name, .. } if name == value);
+                    for &location in def_use.uses(value) {
+                        let user = def_use.instruction(location);
+                        if let Some((_, arguments)) = call_info(user) {
+                            next.extend(
+                                arguments
+                                    .iter()
+                                    .enumerate()
+                                    .filter(|(_, (argument, _))| is_value(argument))
+                                    .map(|(index, _)| Node::Argument(function, location, index)),
+                            );
+                            continue;
+                        }
+                        match user {
+                            Either::Left(Instruction::Store(store)) => {
+                                if is_value(&store.value) {
+                                    next.push(Node::Memory(function, location));
+                                }
+                            }
+                            Either::Right(Terminator::Ret(_)) => {
+                                next.push(Node::Return(function, location))
+                            }
+                            _ => next.push(Node::Value(function, location)),
+                        }
+                    }
+                }
+                Node::Memory(function, location) => {
+                    let fn_analysis = analysis.fn_analysis(function);
+                    let def_use = fn_analysis.def_use_chains();
+                    let ddg = fn_analysis.data_dependence_graph();
+                    for dependent in ddg.dependents(location) {
+                        if ddg
+                            .edge_kinds(dependent, location)
+                            .contains(&DataDependenceKind::Memory)
+                            && matches!(
+                                def_use.instruction(dependent),
+                                Either::Left(Instruction::Load(_))
+                            )
+                        {
+                            next.push(Node::Value(function, dependent));
+                        }
+                    }
+                }
+                Node::Argument(function, location, index) => {
+                    let def_use = analysis.fn_analysis(function).def_use_chains();
+                    let callee = match call_info(def_use.instruction(location)) {
+                        Some((Either::Right(callee), _)) => direct_callee(callee),
+                        _ => None,
+                    };
+                    if let Some(callee) = callee {
+                        let is_sink = config.sinks.iter().any(|TaintSink(matcher, argument)| {
+                            (argument.is_none() || *argument == Some(index))
+                                && matcher.matches(callee)
+                        });
+                        if is_sink {
+                            flows.push(TaintFlow {
+                                sink: FunctionName::Named(callee),
+                                argument: index,
+                                path: witness(node, &preds),
+                            });
+                        }
+                    }
+                    match callee
+                        .and_then(|callee| functions.get_key_value(&FunctionName::Named(callee)))
+                    {
+                        Some((&callee, f)) => {
+                            // arguments beyond the parameters are varargs
+                            if index < f.parameters.len() {
+                                next.push(Node::Parameter(callee, index));
+                            }
+                        }
+                        _ => next.push(Node::Value(function, location)),
+                    }
+                }
+                Node::Return(function, _) => {
+                    next.extend(
+                        calls
+                            .get(&function)
+                            .into_iter()
+                            .flatten()
+                            .map(|&(caller, location)| Node::Value(caller, location)),
+                    );
+                }
+            }
+            for next in next {
+                reach(next, Some(node), &mut preds, &mut worklist);
+            }
+        }
+
+        flows
+            .sort_unstable_by(|a, b| (a.sink_call(), a.argument).cmp(&(b.sink_call(), b.argument)));
+        Self { flows, tainted }
+    }
+
+    /// Get the flows of tainted data to sinks, sorted by the call to the sink
+    /// and then the argument. Each tainted argument of each call to a sink is
+    /// reported once, with one (shortest) witness path.
+    pub fn flows(&self) -> &[TaintFlow<'m>] {
+        &self.flows
+    }
+
+    /// Iterate over the flows of tainted data to calls to the sink function
+    /// with the given name
+    pub fn flows_to<'s>(&'s self, sink: &'s str) -> impl Iterator<Item = &'s TaintFlow<'m>> + 's {
+        self.flows.iter().filter(move |flow| flow.sink == sink)
+    }
+
+    /// Is the local value (parameter or instruction result) with the given
+    /// `Name` in the given function tainted?
+    pub fn is_tainted<'n>(&self, func_name: impl Into<FunctionName<'n>>, value: &Name) -> bool {
+        self.tainted
+            .get(&func_name.into())
+            .is_some_and(|values| values.contains(value))
+    }
+
+    /// Iterate over the tainted local values (parameters and instruction
+    /// results) of the given function, in no particular order
+    pub fn tainted_values<'s>(
+        &'s self,
+        func_name: impl Into<FunctionName<'m>>,
+    ) -> impl Iterator<Item = &'m Name> + 's {
+        self.tainted
+            .get(&func_name.into())
+            .into_iter()
+            .flatten()
+            .copied()
+    }
+}
+
+/// Get the callee and arguments of the given instruction or terminator, if
+/// it's a call
+#[allow(clippy::type_complexity)]
+fn call_info<'m>(
+    inst: Either<&'m Instruction, &'m Terminator>,
+) -> Option<(
+    &'m Either<InlineAssembly, Operand>,
+    &'m [(Operand, Vec<ParameterAttribute>)],
+)> {
+    match inst {
+        Either::Left(Instruction::Call(call)) => Some((&call.function, &call.arguments)),
+        Either::Right(Terminator::Invoke(invoke)) => Some((&invoke.function, &invoke.arguments)),
+        Either::Right(Terminator::CallBr(callbr)) => Some((&callbr.function, &callbr.arguments)),
+        _ => None,
+    }
+}
+
+/// Get the result of the given instruction or terminator, if it has one
+fn result<'m>(inst: Either<&'m Instruction, &'m Terminator>) -> Option<&'m Name> {
+    match inst {
+        Either::Left(inst) => inst.try_get_result(),
+        Either::Right(term) => term.try_get_result(),
+    }
+}
+
+/// Record that `node` is reached from `pred` (or is a source, if `pred` is
+/// `None`), if it hasn't been reached already
+fn reach<'m>(
+    node: Node<'m>,
+    pred: Option<Node<'m>>,
+    preds: &mut HashMap<Node<'m>, Option<Node<'m>>>,
+    worklist: &mut VecDeque<Node<'m>>,
+) {
+    if let Entry::Vacant(entry) = preds.entry(node) {
+        entry.insert(pred);
+        worklist.push_back(node);
+    }
+}
+
+/// Get the path from a source to `node`, following `preds`
+fn witness<'m>(
+    mut node: Node<'m>,
+    preds: &HashMap<Node<'m>, Option<Node<'m>>>,
+) -> Vec<TaintStep<'m>> {
+    let mut path = vec![node.step()];
+    while let Some(pred) = preds[&node] {
+        // e.g., an `Argument` of a call to a declaration, followed by the
+        // `Value` of the same call
+        if path.last() != Some(&pred.step()) {
+            path.push(pred.step());
+        }
+        node = pred;
+    }
+    path.reverse();
+    path
+}
@@ -0,0 +1,187 @@
+use crate::control_flow_graph::CFGNode;
+use crate::dominator_tree::DominatorTree;
+use crate::expression::Expression;
+use llvm_ir::{Function, Name};
+use std::collections::HashMap;
+
+/// Dominator-based value numbering of a particular function: each local value
+/// (parameter or instruction result) gets a value number, and two values with
+/// the same number are provably equal.
+///
+/// Values computed by pure instructions (see
+/// [`Expression`](struct.Expression.html)) get the same number when they
+/// compute the same expression of the same numbered operands, and the first
+/// value computing it dominates the others; so e.g. if `%x = add %a, %b`
+/// dominates `%y = add %b, %a`, then `%y` is redundant with `%x`, and so is
+/// `%w = mul %y, 2` with `%z = mul %x, 2`. The first value is the leader of
+/// its number, and the others are redundant: each could be replaced by its
+/// leader. Every other value (parameters, and the results of `phi`s, `load`s,
+/// `call`s, etc.) gets a number of its own, as do values in blocks unreachable
+/// from the entry.
+///
+/// Since leaders must dominate, the same expression computed on two arms of a
+/// conditional isn't redundant; see
+/// [`VeryBusyExpressions`](struct.VeryBusyExpressions.html) for whether it
+/// could be hoisted.
+///
+/// To construct a `ValueNumbering`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct ValueNumbering<'m> {
+    /// The leader of each value number, i.e., the value numbers index this
+    /// vector
+    leaders: Vec<&'m Name>,
+    /// The value number of each local value
+    numbers: HashMap<&'m Name, usize>,
+    /// Each redundant value and its leader, in dominator-tree preorder
+    redundant: Vec<(&'m Name, &'m Name)>,
+}
+
+/// State of the walk over the dominator tree
+struct Numbering<'a, 'm> {
+    function: &'m Function,
+    domtree: &'a DominatorTree<'m>,
+    vn: ValueNumbering<'m>,
+    /// Value number of each numbered expression computed by a leader in a
+    /// block dominating the current block
+    available: HashMap<Expression, usize>,
+}
+
+impl<'m> ValueNumbering<'m> {
+    pub(crate) fn new(function: &'m Function, domtree: &DominatorTree<'m>) -> Self {
+        let mut numbering = Numbering {
+            function,
+            domtree,
+            vn: Self {
+                leaders: Vec::new(),
+                numbers: HashMap::new(),
+                redundant: Vec::new(),
+            },
+            available: HashMap::new(),
+        };
+        for param in &function.parameters {
+            numbering.vn.add_leader(&param.name);
+        }
+        numbering.visit(domtree.entry());
+
+        // values in unreachable blocks each get their own number
+        for bb in &function.basic_blocks {
+            for value in bb
+                .instrs
+                .iter()
+                .filter_map(|inst| inst.try_get_result())
+                .chain(bb.term.try_get_result())
+            {
+                if !numbering.vn.numbers.contains_key(value) {
+                    numbering.vn.add_leader(value);
+                }
+            }
+        }
+        numbering.vn
+    }
+
+    /// Get the value number of the local value with the given `Name`, or
+    /// `None` if the value isn't in this function. Values with the same number
+    /// are equal.
+    pub fn value_number(&self, value: &Name) -> Option<usize> {
+        self.numbers.get(value).copied()
+    }
+
+    /// Get the leader of the value number of the local value with the given
+    /// `Name`: the value which dominates all the others with the same number.
+    /// This is the value itself unless it's redundant. Returns `None` if the
+    /// value isn't in this function.
+    pub fn leader(&self, value: &Name) -> Option<&'m Name> {
+        self.value_number(value).map(|number| self.leaders[number])
+    }
+
+    /// Is the local value with the given `Name` redundant, i.e., equal to a
+    /// different value which dominates it?
+    pub fn is_redundant(&self, value: &Name) -> bool {
+        self.leader(value).is_some_and(|leader| leader != value)
+    }
+
+    /// Are the local values with the given `Name`s provably equal, i.e., do
+    /// they have the same value number?
+    pub fn congruent(&self, a: &Name, b: &Name) -> bool {
+        match (self.value_number(a), self.value_number(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Iterate over the redundant values, each with its leader, in an order
+    /// where each value comes after all the values dominating it
+    pub fn redundant(&self) -> impl Iterator<Item = (&'m Name, &'m Name)> + '_ {
+        self.redundant.iter().copied()
+    }
+
+    /// Get the number of distinct value numbers
+    pub fn num_value_numbers(&self) -> usize {
+        self.leaders.len()
+    }
+
+    fn add_leader(&mut self, value: &'m Name) -> usize {
+        let number = self.leaders.len();
+        self.leaders.push(value);
+        self.numbers.insert(value, number);
+        number
+    }
+}
+
+impl<'a, 'm> Numbering<'a, 'm> {
+    /// Number the values in the given block and the blocks it dominates
+    fn visit(&mut self, block: &'m Name) {
+        let bb = self.function.get_bb_by_name(block).unwrap_or_else(|| {
+            panic!(
+                "Failed to find block {} in function {}",
+                block, self.function.name
+            )
+        });
+        // the expressions first computed in this block, which are no longer
+        // available once we leave the blocks it dominates
+        let mut scope = Vec::new();
+        for inst in &bb.instrs {
+            let result = match inst.try_get_result() {
+                Some(result) => result,
+                None => continue,
+            };
+            let expression = Expression::new(inst)
+                .map(|expression| expression.numbered(|name| self.vn.value_number(name)));
+            match expression {
+                Some(expression) => match self.available.get(&expression) {
+                    Some(&number) => {
+                        self.vn.numbers.insert(result, number);
+                        self.vn.redundant.push((result, self.vn.leaders[number]));
+                    }
+                    None => {
+                        let number = self.vn.add_leader(result);
+                        self.available.insert(expression.clone(), number);
+                        scope.push(expression);
+                    }
+                },
+                None => {
+                    self.vn.add_leader(result);
+                }
+            }
+        }
+        if let Some(result) = bb.term.try_get_result() {
+            self.vn.add_leader(result);
+        }
+
+        let children: Vec<&'m Name> = self
+            .domtree
+            .children(block)
+            .filter_map(|child| match child {
+                CFGNode::Block(child) => Some(child),
+                CFGNode::Return => None,
+            })
+            .collect();
+        for child in children {
+            self.visit(child);
+        }
+        for expression in scope {
+            self.available.remove(&expression);
+        }
+    }
+}
@@ -0,0 +1,1028 @@
+use crate::abstract_interpretation::{AbstractDomain, AbstractInterpreter, AbstractSemantics};
+use crate::control_flow_graph::{CFGEdgeKind, ControlFlowGraph};
+use crate::def_use::InstructionLocation;
+use crate::loops::{negate, signed, swap};
+use crate::operands::{instruction_operands, terminator_operands};
+use crate::weak_topological_order::WeakTopologicalOrder;
+use llvm_ir::instruction::{ICmp, Phi};
+use llvm_ir::types::Type;
+use llvm_ir::{
+    BasicBlock, Constant, Function, Instruction, IntPredicate, Name, Operand, Terminator,
+};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// A range of integer values, `min..=max`.
+///
+/// Values are interpreted as signed, except for `i1`s, which range over `0`
+/// (false) and `1` (true).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct ValueRange {
+    /// The smallest value in the range
+    pub min: i128,
+    /// The largest value in the range
+    pub max: i128,
+}
+
+impl ValueRange {
+    /// Get the range of all values of the integer type with the given number
+    /// of bits, which must be from 1 to 64, like the integers this analysis
+    /// tracks.
+    ///
+    /// Panics if `bits` is out of that range.
+    pub fn full(bits: u32) -> Self {
+        assert!(
+            (1..=64).contains(&bits),
+            "ValueRange::full(): expected 1 to 64 bits, got {}",
+            bits
+        );
+        if bits == 1 {
+            Self { min: 0, max: 1 }
+        } else {
+            Self {
+                min: -(1 << (bits - 1)),
+                max: (1 << (bits - 1)) - 1,
+            }
+        }
+    }
+
+    /// Get the range containing only the given value
+    pub fn constant(value: i128) -> Self {
+        Self {
+            min: value,
+            max: value,
+        }
+    }
+
+    /// Does the range contain the given value?
+    pub fn contains(&self, value: i128) -> bool {
+        self.min <= value && value <= self.max
+    }
+
+    /// If the range contains a single value, get it
+    pub fn as_constant(&self) -> Option<i128> {
+        if self.min == self.max {
+            Some(self.min)
+        } else {
+            None
+        }
+    }
+
+    /// Get the smallest range containing both ranges
+    fn join(self, other: Self) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    /// Get the values in both ranges, or `None` if there are none
+    fn intersect(self, other: Self) -> Option<Self> {
+        let range = Self {
+            min: self.min.max(other.min),
+            max: self.max.min(other.max),
+        };
+        if range.min <= range.max {
+            Some(range)
+        } else {
+            None
+        }
+    }
+
+    /// Is every value in this range also in `other`?
+    fn within(self, other: Self) -> bool {
+        other.min <= self.min && self.max <= other.max
+    }
+
+    fn is_non_negative(self) -> bool {
+        self.min >= 0
+    }
+
+    /// Get the range of the negations of the values in this range. Signed
+    /// comparisons treat an `i1` `true` as `-1` rather than `1`, so this
+    /// converts the range of an `i1` to the range of its signed values, and
+    /// back.
+    fn negated(self) -> Self {
+        Self {
+            min: -self.max,
+            max: -self.min,
+        }
+    }
+}
+
+/// The ranges of the integer values at some point in a function, as computed
+/// by [`IntervalSemantics`](struct.IntervalSemantics.html)
+#[derive(Clone, Debug)]
+pub struct IntervalState<'m> {
+    /// The range of each value which is defined at this point
+    ranges: HashMap<&'m Name, ValueRange>,
+    /// Number of bits of each integer value of at most 64 bits, shared with
+    /// the `IntervalSemantics`
+    bits: Rc<HashMap<&'m Name, u32>>,
+}
+
+impl<'m> IntervalState<'m> {
+    /// Get the range of the local integer value with the given `Name`, or
+    /// `None` if no path to this point defines it
+    pub fn range(&self, value: &Name) -> Option<ValueRange> {
+        self.ranges.get(value).copied()
+    }
+
+    /// Iterate over the local integer values defined at this point, with their
+    /// ranges, in no particular order
+    pub fn ranges<'s>(&'s self) -> impl Iterator<Item = (&'m Name, ValueRange)> + 's {
+        self.ranges.iter().map(|(&value, &range)| (value, range))
+    }
+}
+
+impl<'m> PartialEq for IntervalState<'m> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ranges == other.ranges
+    }
+}
+
+impl<'m> AbstractDomain for IntervalState<'m> {
+    /// Each value's range is the join of its ranges in both states
+    fn join(&self, other: &Self) -> Self {
+        let mut joined = self.clone();
+        for (&value, &range) in &other.ranges {
+            joined
+                .ranges
+                .entry(value)
+                .and_modify(|joined| *joined = joined.join(range))
+                .or_insert(range);
+        }
+        joined
+    }
+
+    /// Any bound which has moved is moved all the way to the limit of the
+    /// value's type
+    fn widen(&self, newer: &Self) -> Self {
+        let mut widened = self.join(newer);
+        for (value, range) in widened.ranges.iter_mut() {
+            if let (Some(old), Some(width)) = (self.ranges.get(value), self.bits.get(value)) {
+                let full = ValueRange::full(*width);
+                if range.min < old.min {
+                    range.min = full.min;
+                }
+                if range.max > old.max {
+                    range.max = full.max;
+                }
+            }
+        }
+        widened
+    }
+}
+
+/// A value-range (interval) analysis of the integer SSA values (parameters
+/// and instruction results) of a particular function: at each point in the
+/// function, a range which each integer value is known to be within.
+///
+/// Ranges are computed from constants and from the operations performed on
+/// the values (e.g., an `and` with `255` is in `0..=255`), and are refined by
+/// the branches which guard each point: e.g., after `br i1 %c`, where `%c` is
+/// `icmp slt i32 %x, 10`, `%x` is at most 9 in the true successor. Both
+/// conditional `br`s and `switch`es are used, as well as the conditions of
+/// `select`s. Values which the analysis knows nothing about, such as the
+/// results of `load`s and `call`s, have the full range of their type.
+///
+/// Blocks which the analysis finds can never execute, because every branch to
+/// them is decided against them, have no ranges at all.
+///
+/// Loops are handled by widening ranges which keep growing to the full range
+/// of their type, then refining them again with a few more passes over the
+/// function. Only integers of at most 64 bits are tracked. LLVM's `!range`
+/// metadata isn't exposed by `llvm-ir`, so it isn't used; ranges known from
+/// elsewhere can be supplied with
+/// [`IntervalSemantics::with_known_range()`](struct.IntervalSemantics.html#method.with_known_range),
+/// and the semantics run with
+/// [`FunctionAnalysis::abstract_interpretation()`](struct.FunctionAnalysis.html#method.abstract_interpretation).
+///
+/// These are the results of running an
+/// [`AbstractInterpreter`](struct.AbstractInterpreter.html) with the
+/// [`IntervalSemantics`](struct.IntervalSemantics.html).
+///
+/// To construct a `ValueRanges`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct ValueRanges<'m> {
+    /// The function
+    function: &'m Function,
+    /// The semantics
+    semantics: IntervalSemantics<'m>,
+    /// Ranges of the values at the entry of each block which may execute, at
+    /// the fixpoint of the semantics
+    block_entry: HashMap<&'m Name, IntervalState<'m>>,
+    /// Map from each instruction (or terminator) result to its location
+    def_locations: HashMap<&'m Name, InstructionLocation<'m>>,
+}
+
+impl<'m> ValueRanges<'m> {
+    pub(crate) fn new(
+        function: &'m Function,
+        cfg: &ControlFlowGraph<'m>,
+        wto: &WeakTopologicalOrder<'m>,
+    ) -> Self {
+        let mut def_locations = HashMap::new();
+        for bb in &function.basic_blocks {
+            for (index, inst) in bb.instrs.iter().enumerate() {
+                if let Some(result) = inst.try_get_result() {
+                    def_locations.insert(
+                        result,
+                        InstructionLocation {
+                            block: &bb.name,
+                            index,
+                        },
+                    );
+                }
+            }
+            if let Some(result) = bb.term.try_get_result() {
+                def_locations.insert(
+                    result,
+                    InstructionLocation {
+                        block: &bb.name,
+                        index: bb.instrs.len(),
+                    },
+                );
+            }
+        }
+        let (semantics, block_entry) = AbstractInterpreter::new()
+            .run(function, cfg, wto, IntervalSemantics::new(function))
+            .into_parts();
+        Self {
+            function,
+            semantics,
+            block_entry,
+            def_locations,
+        }
+    }
+
+    /// Get the range of the local integer value with the given `Name` just
+    /// before the instruction (or terminator) at the given location executes.
+    ///
+    /// Returns `None` if the value isn't an integer of at most 64 bits, if no
+    /// path to that point defines it, or if the instruction can never execute.
+    /// The range of a value at a point which its definition doesn't dominate
+    /// is that of its last definition, if any.
+    ///
+    /// Panics if the location isn't in this function.
+    pub fn range_at(&self, value: &Name, inst: InstructionLocation<'m>) -> Option<ValueRange> {
+        let bb = self.block(inst.block);
+        if inst.index > bb.instrs.len() {
+            panic!(
+                "range_at(): index {} out of range for block {} in function {}",
+                inst.index, inst.block, self.function.name
+            );
+        }
+        let mut state = self.block_entry.get(inst.block)?.clone();
+        for inst in &bb.instrs[..inst.index] {
+            self.semantics.transfer(&mut state, inst);
+        }
+        state.range(value)
+    }
+
+    /// Get the range of the local integer value with the given `Name` at the
+    /// entry of the given block. See [`range_at()`](#method.range_at).
+    ///
+    /// Panics if the block isn't in this function.
+    pub fn range_at_block_entry(&self, value: &Name, block: &'m Name) -> Option<ValueRange> {
+        self.range_at(value, InstructionLocation { block, index: 0 })
+    }
+
+    /// Get the range of the local integer value with the given `Name` where
+    /// it's defined, i.e., the range of every value it may take. See
+    /// [`range_at()`](#method.range_at).
+    pub fn range(&self, value: &Name) -> Option<ValueRange> {
+        match self.def_locations.get(value) {
+            Some(&location) => {
+                let bb = self.block(location.block);
+                if location.index < bb.instrs.len() {
+                    self.range_at(
+                        value,
+                        InstructionLocation {
+                            index: location.index + 1,
+                            ..location
+                        },
+                    )
+                } else if self.is_block_reachable(location.block) {
+                    self.semantics
+                        .bits
+                        .get(value)
+                        .map(|width| ValueRange::full(*width))
+                } else {
+                    None
+                }
+            }
+            None => self.range_at_block_entry(value, &self.function.basic_blocks[0].name),
+        }
+    }
+
+    /// Can the block with the given `Name` execute, as far as the analysis
+    /// knows? If not, every branch to it is decided against it.
+    pub fn is_block_reachable(&self, block: &Name) -> bool {
+        self.block_entry.contains_key(block)
+    }
+
+    fn block(&self, block: &Name) -> &'m BasicBlock {
+        self.function.get_bb_by_name(block).unwrap_or_else(|| {
+            panic!(
+                "Failed to find block {} in function {}",
+                block, self.function.name
+            )
+        })
+    }
+}
+
+/// The abstract semantics of the integer SSA values of a function over
+/// intervals: the reference implementation of
+/// [`AbstractSemantics`](trait.AbstractSemantics.html), which
+/// [`ValueRanges`](struct.ValueRanges.html) uses. See `ValueRanges` for what
+/// is tracked.
+pub struct IntervalSemantics<'m> {
+    /// The function
+    function: &'m Function,
+    /// Number of bits of each integer value of at most 64 bits
+    bits: Rc<HashMap<&'m Name, u32>>,
+    /// Map from each instruction result to the instruction producing it
+    defs: HashMap<&'m Name, &'m Instruction>,
+    /// Ranges of values which are known to be within them, as given to
+    /// `with_known_range()`
+    known: HashMap<&'m Name, ValueRange>,
+}
+
+impl<'m> IntervalSemantics<'m> {
+    /// Get the interval semantics of the given function
+    pub fn new(function: &'m Function) -> Self {
+        let mut bits: HashMap<&'m Name, u32> = HashMap::new();
+        let mut defs = HashMap::new();
+        for param in &function.parameters {
+            if let Some(width) = int_bits(&param.ty) {
+                bits.insert(&param.name, width);
+            }
+        }
+        let mut record_operands = |operands: Vec<&'m Operand>| {
+            for operand in operands {
+                if let Operand::LocalOperand { name, ty } = operand {
+                    if let Some(width) = int_bits(ty) {
+                        bits.insert(name, width);
+                    }
+                }
+            }
+        };
+        for bb in &function.basic_blocks {
+            for inst in &bb.instrs {
+                record_operands(instruction_operands(inst));
+                if let Some(result) = inst.try_get_result() {
+                    defs.insert(result, inst);
+                }
+            }
+            record_operands(terminator_operands(&bb.term));
+        }
+        // values which are never used
+        for inst in function.basic_blocks.iter().flat_map(|bb| bb.instrs.iter()) {
+            if let (Some(result), Some(width)) = (inst.try_get_result(), result_bits(inst)) {
+                bits.entry(result).or_insert(width);
+            }
+        }
+        Self {
+            function,
+            bits: Rc::new(bits),
+            defs,
+            known: HashMap::new(),
+        }
+    }
+
+    /// Use the knowledge that the local integer value with the given `Name`
+    /// (a parameter, or the result of an instruction or terminator) is always
+    /// within `range`; e.g., from the `!range` metadata of the `load` or
+    /// `call` producing it, which `llvm-ir` doesn't expose. Wherever the value
+    /// is defined, its range is intersected with `range`. If they don't
+    /// intersect, the range computed by the analysis is used instead.
+    pub fn with_known_range(mut self, value: &'m Name, range: ValueRange) -> Self {
+        self.known.insert(value, range);
+        self
+    }
+
+    /// Record the range of the given value, where it's defined, in `state`,
+    /// narrowed to its known range (see `with_known_range()`)
+    fn define(&self, state: &mut IntervalState<'m>, value: &'m Name, range: ValueRange) {
+        let range = match self.known.get(value) {
+            Some(known) => range.intersect(*known).unwrap_or(range),
+            None => range,
+        };
+        state.ranges.insert(value, range);
+    }
+
+    /// Update `state` with the effect of the given instruction
+    fn transfer(&self, state: &mut IntervalState<'m>, inst: &'m Instruction) {
+        // the `phi`s' results are computed on entry to the block
+        if let Instruction::Phi(_) = inst {
+            return;
+        }
+        let result = match inst.try_get_result() {
+            Some(result) => result,
+            None => return,
+        };
+        let width = match self.bits.get(result) {
+            Some(width) => *width,
+            None => return,
+        };
+        let full = ValueRange::full(width);
+        let range = self
+            .evaluate(state, inst)
+            .filter(|range| range.within(full))
+            .unwrap_or(full);
+        self.define(state, result, range);
+    }
+
+    /// Get the range of the result of the given instruction, or `None` if
+    /// nothing is known about it (or it may wrap around)
+    fn evaluate(&self, state: &IntervalState<'m>, inst: &'m Instruction) -> Option<ValueRange> {
+        let range = |operand| self.operand_range(state, operand);
+        let shift_amount = |operand| {
+            let amount = range(operand)?.as_constant()?;
+            if (0..64).contains(&amount) {
+                Some(amount as u32)
+            } else {
+                None
+            }
+        };
+        match inst {
+            Instruction::Add(add) => {
+                let (a, b) = (range(&add.operand0)?, range(&add.operand1)?);
+                Some(ValueRange {
+                    min: a.min + b.min,
+                    max: a.max + b.max,
+                })
+            }
+            Instruction::Sub(sub) => {
+                let (a, b) = (range(&sub.operand0)?, range(&sub.operand1)?);
+                Some(ValueRange {
+                    min: a.min - b.max,
+                    max: a.max - b.min,
+                })
+            }
+            Instruction::Mul(mul) => {
+                let (a, b) = (range(&mul.operand0)?, range(&mul.operand1)?);
+                corners(a, b, |x, y| Some(x * y))
+            }
+            Instruction::UDiv(udiv) => {
+                let (a, b) = (range(&udiv.operand0)?, range(&udiv.operand1)?);
+                if a.is_non_negative() && b.min > 0 {
+                    Some(ValueRange {
+                        min: a.min / b.max,
+                        max: a.max / b.min,
+                    })
+                } else {
+                    None
+                }
+            }
+            Instruction::SDiv(sdiv) => {
+                let (a, b) = (range(&sdiv.operand0)?, range(&sdiv.operand1)?);
+                if b.contains(0) {
+                    None
+                } else {
+                    corners(a, b, |x, y| Some(x / y))
+                }
+            }
+            Instruction::URem(urem) => {
+                let (a, b) = (range(&urem.operand0)?, range(&urem.operand1)?);
+                if a.is_non_negative() && b.min > 0 {
+                    Some(ValueRange {
+                        min: 0,
+                        max: a.max.min(b.max - 1),
+                    })
+                } else {
+                    None
+                }
+            }
+            Instruction::SRem(srem) => {
+                let (a, b) = (range(&srem.operand0)?, range(&srem.operand1)?);
+                if b.contains(0) {
+                    return None;
+                }
+                // the result has the sign of the dividend, and is smaller in
+                // magnitude than the divisor
+                let bound = b.min.abs().max(b.max.abs()) - 1;
+                Some(ValueRange {
+                    min: if a.is_non_negative() {
+                        0
+                    } else {
+                        a.min.max(-bound)
+                    },
+                    max: if a.max <= 0 { 0 } else { a.max.min(bound) },
+                })
+            }
+            Instruction::And(and) => {
+                let (a, b) = (range(&and.operand0)?, range(&and.operand1)?);
+                match (a.is_non_negative(), b.is_non_negative()) {
+                    (true, true) => Some(ValueRange {
+                        min: 0,
+                        max: a.max.min(b.max),
+                    }),
+                    (true, false) => Some(ValueRange { min: 0, max: a.max }),
+                    (false, true) => Some(ValueRange { min: 0, max: b.max }),
+                    (false, false) => None,
+                }
+            }
+            Instruction::Or(or) => {
+                let (a, b) = (range(&or.operand0)?, range(&or.operand1)?);
+                if a.is_non_negative() && b.is_non_negative() {
+                    Some(ValueRange {
+                        min: a.min.max(b.min),
+                        max: all_ones_above(a.max.max(b.max)),
+                    })
+                } else {
+                    None
+                }
+            }
+            Instruction::Xor(xor) => {
+                let (a, b) = (range(&xor.operand0)?, range(&xor.operand1)?);
+                if a.is_non_negative() && b.is_non_negative() {
+                    Some(ValueRange {
+                        min: 0,
+                        max: all_ones_above(a.max.max(b.max)),
+                    })
+                } else {
+                    None
+                }
+            }
+            Instruction::Shl(shl) => {
+                let (a, amount) = (range(&shl.operand0)?, shift_amount(&shl.operand1)?);
+                Some(ValueRange {
+                    min: a.min << amount,
+                    max: a.max << amount,
+                })
+            }
+            Instruction::AShr(ashr) => {
+                let (a, amount) = (range(&ashr.operand0)?, shift_amount(&ashr.operand1)?);
+                Some(ValueRange {
+                    min: a.min >> amount,
+                    max: a.max >> amount,
+                })
+            }
+            Instruction::LShr(lshr) => {
+                let (a, amount) = (range(&lshr.operand0)?, shift_amount(&lshr.operand1)?);
+                if a.is_non_negative() {
+                    Some(ValueRange {
+                        min: a.min >> amount,
+                        max: a.max >> amount,
+                    })
+                } else if amount > 0 {
+                    let width = self.bits.get(&lshr.dest)?;
+                    Some(ValueRange {
+                        min: 0,
+                        max: ((1 << width) - 1) >> amount,
+                    })
+                } else {
+                    None
+                }
+            }
+            Instruction::Trunc(trunc) => range(&trunc.operand),
+            Instruction::ZExt(zext) => {
+                let a = range(&zext.operand)?;
+                let from = 1i128 << operand_bits(&zext.operand)?;
+                if a.is_non_negative() {
+                    Some(a)
+                } else if a.max < 0 {
+                    Some(ValueRange {
+                        min: a.min + from,
+                        max: a.max + from,
+                    })
+                } else {
+                    Some(ValueRange {
+                        min: 0,
+                        max: from - 1,
+                    })
+                }
+            }
+            Instruction::SExt(sext) => {
+                let a = range(&sext.operand)?;
+                if operand_bits(&sext.operand)? == 1 {
+                    // `true` is all ones
+                    Some(ValueRange {
+                        min: -a.max,
+                        max: -a.min,
+                    })
+                } else {
+                    Some(a)
+                }
+            }
+            #[cfg(feature = "llvm-10-or-greater")]
+            Instruction::Freeze(freeze) => range(&freeze.operand),
+            Instruction::Select(select) => {
+                let taken = self
+                    .refine(state.clone(), &select.condition, true)
+                    .and_then(|taken| self.operand_range(&taken, &select.true_value));
+                let not_taken = self
+                    .refine(state.clone(), &select.condition, false)
+                    .and_then(|not_taken| self.operand_range(&not_taken, &select.false_value));
+                match (taken, not_taken) {
+                    (Some(a), Some(b)) => Some(a.join(b)),
+                    (a, b) => a.or(b),
+                }
+            }
+            Instruction::ICmp(icmp) => {
+                let (a, b) = (range(&icmp.operand0)?, range(&icmp.operand1)?);
+                let (a, b) = if is_signed_i1_comparison(icmp) {
+                    (a.negated(), b.negated())
+                } else {
+                    (a, b)
+                };
+                match compare(icmp.predicate, a, b) {
+                    Some(result) => Some(ValueRange::constant(result.into())),
+                    None => Some(ValueRange::full(1)),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Refine `state` with the knowledge that `condition` is `truth`, or get
+    /// `None` if it can't be
+    fn refine(
+        &self,
+        mut state: IntervalState<'m>,
+        condition: &'m Operand,
+        truth: bool,
+    ) -> Option<IntervalState<'m>> {
+        let value = ValueRange::constant(truth.into());
+        if let Some(range) = self.operand_range(&state, condition) {
+            range.intersect(value)?;
+        }
+        let name = match condition {
+            Operand::LocalOperand { name, .. } => name,
+            _ => return Some(state),
+        };
+        state.ranges.insert(name, value);
+        match self.defs.get(name) {
+            Some(Instruction::ICmp(icmp)) => {
+                let predicate = if truth {
+                    icmp.predicate
+                } else {
+                    negate(icmp.predicate)
+                };
+                // refine signed comparisons of `i1`s in terms of their signed
+                // values
+                let signed_i1 = |range: ValueRange| {
+                    if is_signed_i1_comparison(icmp) {
+                        range.negated()
+                    } else {
+                        range
+                    }
+                };
+                let a = self.operand_range(&state, &icmp.operand0).map(signed_i1);
+                let b = self.operand_range(&state, &icmp.operand1).map(signed_i1);
+                if let (Some(a), Some(b)) = (a, b) {
+                    if let Operand::LocalOperand { name, .. } = &icmp.operand0 {
+                        let range = refine_range(predicate, a, b)?;
+                        state.ranges.insert(name, signed_i1(range));
+                    }
+                    if let Operand::LocalOperand { name, .. } = &icmp.operand1 {
+                        let range = refine_range(swap(predicate), b, a)?;
+                        state.ranges.insert(name, signed_i1(range));
+                    }
+                }
+                Some(state)
+            }
+            Some(Instruction::And(and)) if truth => {
+                let state = self.refine(state, &and.operand0, true)?;
+                self.refine(state, &and.operand1, true)
+            }
+            Some(Instruction::Or(or)) if !truth => {
+                let state = self.refine(state, &or.operand0, false)?;
+                self.refine(state, &or.operand1, false)
+            }
+            Some(Instruction::Xor(xor)) if operand_bits(&xor.operand0) == Some(1) => {
+                // `xor %c, true` is the negation of `%c`
+                let one = Some(ValueRange::constant(1));
+                if self.operand_range(&state, &xor.operand1) == one {
+                    self.refine(state, &xor.operand0, !truth)
+                } else if self.operand_range(&state, &xor.operand0) == one {
+                    self.refine(state, &xor.operand1, !truth)
+                } else {
+                    Some(state)
+                }
+            }
+            _ => Some(state),
+        }
+    }
+
+    /// Get the range of the given operand, or `None` if it isn't a tracked
+    /// integer
+    fn operand_range(&self, state: &IntervalState<'m>, operand: &Operand) -> Option<ValueRange> {
+        match operand {
+            Operand::LocalOperand { name, ty } => state
+                .ranges
+                .get(name)
+                .copied()
+                .or_else(|| int_bits(ty).map(ValueRange::full)),
+            Operand::ConstantOperand(constant) => match constant.as_ref() {
+                Constant::Undef(ty) => int_bits(ty).map(ValueRange::full),
+                #[cfg(feature = "llvm-12-or-greater")]
+                Constant::Poison(ty) => int_bits(ty).map(ValueRange::full),
+                _ => const_range(constant),
+            },
+            Operand::MetadataOperand => None,
+        }
+    }
+}
+
+impl<'m> AbstractSemantics<'m> for IntervalSemantics<'m> {
+    type State = IntervalState<'m>;
+
+    /// Each integer parameter has the full range of its type, or its known
+    /// range
+    fn entry_state(&self) -> IntervalState<'m> {
+        let mut state = IntervalState {
+            ranges: HashMap::new(),
+            bits: Rc::clone(&self.bits),
+        };
+        for param in &self.function.parameters {
+            if let Some(width) = self.bits.get(&param.name) {
+                self.define(&mut state, &param.name, ValueRange::full(*width));
+            }
+        }
+        state
+    }
+
+    fn instruction(&self, state: &mut IntervalState<'m>, inst: &'m Instruction) {
+        self.transfer(state, inst)
+    }
+
+    fn terminator(&self, state: &mut IntervalState<'m>, term: &'m Terminator) {
+        if let Some(result) = term.try_get_result() {
+            if let Some(width) = self.bits.get(result) {
+                self.define(state, result, ValueRange::full(*width));
+            }
+        }
+    }
+
+    /// Refines the state with the condition of a conditional `br`, or the
+    /// case of a `switch`
+    fn edge(
+        &self,
+        state: &IntervalState<'m>,
+        term: &'m Terminator,
+        kind: CFGEdgeKind<'m>,
+    ) -> Option<IntervalState<'m>> {
+        match (term, kind) {
+            (Terminator::CondBr(condbr), CFGEdgeKind::CondBr(truth)) => {
+                self.refine(state.clone(), &condbr.condition, truth)
+            }
+            (Terminator::Switch(switch), CFGEdgeKind::SwitchCase(case)) => {
+                let case = match const_range(case) {
+                    Some(case) => case,
+                    None => return Some(state.clone()),
+                };
+                if let Some(range) = self.operand_range(state, &switch.operand) {
+                    range.intersect(case)?;
+                }
+                let mut case_state = state.clone();
+                if let Operand::LocalOperand { name, .. } = &switch.operand {
+                    case_state.ranges.insert(name, case);
+                }
+                Some(case_state)
+            }
+            (Terminator::Switch(switch), CFGEdgeKind::SwitchDefault) => {
+                let value = self
+                    .operand_range(state, &switch.operand)
+                    .and_then(|range| range.as_constant());
+                if value.is_some()
+                    && switch.dests.iter().any(|(case, _)| {
+                        const_range(case).and_then(|case| case.as_constant()) == value
+                    })
+                {
+                    None
+                } else {
+                    Some(state.clone())
+                }
+            }
+            _ => Some(state.clone()),
+        }
+    }
+
+    fn phi(
+        &self,
+        edge: &IntervalState<'m>,
+        state: &mut IntervalState<'m>,
+        phi: &'m Phi,
+        incoming: &'m Operand,
+    ) {
+        if let Some(width) = self.bits.get(&phi.dest) {
+            let range = self
+                .operand_range(edge, incoming)
+                .unwrap_or_else(|| ValueRange::full(*width));
+            self.define(state, &phi.dest, range);
+        }
+    }
+}
+
+/// Get the range of `f(x, y)` for `x` in `a` and `y` in `b`, for a function
+/// `f` which is monotonic in each argument
+fn corners(
+    a: ValueRange,
+    b: ValueRange,
+    f: impl Fn(i128, i128) -> Option<i128>,
+) -> Option<ValueRange> {
+    let values = [
+        f(a.min, b.min)?,
+        f(a.min, b.max)?,
+        f(a.max, b.min)?,
+        f(a.max, b.max)?,
+    ];
+    Some(ValueRange {
+        min: *values.iter().min()?,
+        max: *values.iter().max()?,
+    })
+}
+
+/// Get the smallest number of the form `2^k - 1` which is at least `value`
+/// (which is non-negative)
+fn all_ones_above(value: i128) -> i128 {
+    let mut ones = 0;
+    while ones < value {
+        ones = (ones << 1) | 1;
+    }
+    ones
+}
+
+/// Decide `a predicate b` for `a` in the range `a` and `b` in the range `b`,
+/// or get `None` if it may be either
+fn compare(predicate: IntPredicate, a: ValueRange, b: ValueRange) -> Option<bool> {
+    let predicate = as_signed(predicate, a, b)?;
+    match predicate {
+        IntPredicate::EQ => {
+            if a.as_constant().is_some() && a == b {
+                Some(true)
+            } else if a.intersect(b).is_none() {
+                Some(false)
+            } else {
+                None
+            }
+        }
+        IntPredicate::NE => compare(IntPredicate::EQ, a, b).map(|equal| !equal),
+        IntPredicate::SLT => compare(IntPredicate::SGE, a, b).map(|ge| !ge),
+        IntPredicate::SLE => compare(IntPredicate::SGT, a, b).map(|gt| !gt),
+        IntPredicate::SGT => {
+            if a.min > b.max {
+                Some(true)
+            } else if a.max <= b.min {
+                Some(false)
+            } else {
+                None
+            }
+        }
+        IntPredicate::SGE => {
+            if a.min >= b.max {
+                Some(true)
+            } else if a.max < b.min {
+                Some(false)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Is the given `icmp` a signed comparison of `i1`s? These treat `true` as
+/// `-1`, unlike the ranges of `i1`s (see `ValueRange::negated()`).
+fn is_signed_i1_comparison(icmp: &ICmp) -> bool {
+    operand_bits(&icmp.operand0) == Some(1)
+        && matches!(
+            icmp.predicate,
+            IntPredicate::SGT | IntPredicate::SGE | IntPredicate::SLT | IntPredicate::SLE
+        )
+}
+
+/// Get the signed predicate equivalent to the given predicate for values in
+/// the ranges `a` and `b`, or `None` if there isn't one
+fn as_signed(predicate: IntPredicate, a: ValueRange, b: ValueRange) -> Option<IntPredicate> {
+    let non_negative = a.is_non_negative() && b.is_non_negative();
+    match predicate {
+        IntPredicate::UGT if non_negative => Some(IntPredicate::SGT),
+        IntPredicate::UGE if non_negative => Some(IntPredicate::SGE),
+        IntPredicate::ULT if non_negative => Some(IntPredicate::SLT),
+        IntPredicate::ULE if non_negative => Some(IntPredicate::SLE),
+        IntPredicate::UGT | IntPredicate::UGE | IntPredicate::ULT | IntPredicate::ULE => None,
+        predicate => Some(predicate),
+    }
+}
+
+/// Refine the range `a` with the knowledge that `a predicate b` for some `b`
+/// in the range `b`, or get `None` if that's impossible
+fn refine_range(predicate: IntPredicate, a: ValueRange, b: ValueRange) -> Option<ValueRange> {
+    let at_most = |max| {
+        a.intersect(ValueRange {
+            min: i128::MIN,
+            max,
+        })
+    };
+    let at_least = |min| {
+        a.intersect(ValueRange {
+            min,
+            max: i128::MAX,
+        })
+    };
+    match predicate {
+        IntPredicate::EQ => a.intersect(b),
+        IntPredicate::NE => match b.as_constant() {
+            Some(c) if a.min == c => at_least(c + 1),
+            Some(c) if a.max == c => at_most(c - 1),
+            _ => Some(a),
+        },
+        IntPredicate::SLT => at_most(b.max - 1),
+        IntPredicate::SLE => at_most(b.max),
+        IntPredicate::SGT => at_least(b.min + 1),
+        IntPredicate::SGE => at_least(b.min),
+        // a value less than a non-negative value (as unsigned) is itself
+        // non-negative
+        IntPredicate::ULT if b.is_non_negative() => a.intersect(ValueRange {
+            min: 0,
+            max: b.max - 1,
+        }),
+        IntPredicate::ULE if b.is_non_negative() => a.intersect(ValueRange { min: 0, max: b.max }),
+        IntPredicate::UGT if a.is_non_negative() && b.is_non_negative() => at_least(b.min + 1),
+        IntPredicate::UGE if a.is_non_negative() && b.is_non_negative() => at_least(b.min),
+        _ => Some(a),
+    }
+}
+
+/// Get the number of bits of the given type, if it's an integer type of at
+/// most 64 bits
+fn int_bits(ty: &Type) -> Option<u32> {
+    match ty {
+        Type::IntegerType { bits } if *bits <= 64 => Some(*bits),
+        _ => None,
+    }
+}
+
+/// Get the number of bits of the given operand, if it's an integer of at most
+/// 64 bits
+fn operand_bits(operand: &Operand) -> Option<u32> {
+    match operand {
+        Operand::LocalOperand { ty, .. } => int_bits(ty),
+        Operand::ConstantOperand(constant) => match constant.as_ref() {
+            Constant::Int { bits, .. } if *bits <= 64 => Some(*bits),
+            _ => None,
+        },
+        Operand::MetadataOperand => None,
+    }
+}
+
+/// Get the number of bits of the result of the given instruction, if it's an
+/// integer of at most 64 bits and the instruction's operands tell us
+fn result_bits(inst: &Instruction) -> Option<u32> {
+    match inst {
+        Instruction::Add(i) => operand_bits(&i.operand0),
+        Instruction::Sub(i) => operand_bits(&i.operand0),
+        Instruction::Mul(i) => operand_bits(&i.operand0),
+        Instruction::UDiv(i) => operand_bits(&i.operand0),
+        Instruction::SDiv(i) => operand_bits(&i.operand0),
+        Instruction::URem(i) => operand_bits(&i.operand0),
+        Instruction::SRem(i) => operand_bits(&i.operand0),
+        Instruction::And(i) => operand_bits(&i.operand0),
+        Instruction::Or(i) => operand_bits(&i.operand0),
+        Instruction::Xor(i) => operand_bits(&i.operand0),
+        Instruction::Shl(i) => operand_bits(&i.operand0),
+        Instruction::LShr(i) => operand_bits(&i.operand0),
+        Instruction::AShr(i) => operand_bits(&i.operand0),
+        Instruction::Trunc(i) => int_bits(&i.to_type),
+        Instruction::ZExt(i) => int_bits(&i.to_type),
+        Instruction::SExt(i) => int_bits(&i.to_type),
+        Instruction::Phi(i) => int_bits(&i.to_type),
+        Instruction::Select(i) => operand_bits(&i.true_value),
+        #[cfg(feature = "llvm-14-or-lower")]
+        Instruction::Load(i) => match &i.address {
+            Operand::LocalOperand { ty, .. } => match ty.as_ref() {
+                Type::PointerType { pointee_type, .. } => int_bits(pointee_type),
+                _ => None,
+            },
+            _ => None,
+        },
+        #[cfg(feature = "llvm-15-or-greater")]
+        Instruction::Load(i) => int_bits(&i.loaded_ty),
+        Instruction::ICmp(i) => {
+            let is_vector = |operand: &Operand| matches!(operand, Operand::LocalOperand { ty, .. } if matches!(ty.as_ref(), Type::VectorType { .. }));
+            if is_vector(&i.operand0) || is_vector(&i.operand1) {
+                None
+            } else {
+                Some(1)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Get the range containing only the given constant, if it's an integer of at
+/// most 64 bits
+fn const_range(constant: &Constant) -> Option<ValueRange> {
+    match constant {
+        Constant::Int { bits: 1, value } => Some(ValueRange::constant(i128::from(value & 1))),
+        Constant::Int { bits, value } if *bits <= 64 => {
+            Some(ValueRange::constant(signed(*bits, *value)))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,390 @@
+use crate::call_graph::{
+    callee_global, direct_callee, function_names, functions_with_names, global_name,
+    num_numbered_functions, FunctionName,
+};
+use crate::control_flow_graph::{CFGNode, ControlFlowGraph};
+use crate::dominator_tree::DominatorTree;
+use llvm_ir::{Function, Instruction, Module, Name, Operand, Terminator, TypeRef};
+use std::collections::HashMap;
+
+/// Tracks `va_list` creation (`va_start`), copying (`va_copy`), destruction
+/// (`va_end`) and `va_arg` extraction inside variadic functions, and links the
+/// extra arguments at calls to variadic functions to the `va_arg` instructions
+/// which consume them, where this can be determined.
+///
+/// Note that many targets (including x86-64) have their `va_arg` lowered by
+/// the frontend into explicit loads from the `va_list`; this analysis only
+/// sees LLVM `va_arg` instructions.
+///
+/// To construct a `VarargsAnalysis`, use [`ModuleAnalysis`](struct.ModuleAnalysis.html)
+/// or [`CrossModuleAnalysis`](struct.CrossModuleAnalysis.html).
+pub struct VarargsAnalysis<'m> {
+    /// Map from the name of each variadic function defined in the analyzed
+    /// `Module`(s) to its info
+    functions: HashMap<FunctionName<'m>, VariadicFunctionInfo<'m>>,
+    /// All calls to variadic functions (defined or declared) in the analyzed
+    /// `Module`(s)
+    call_sites: Vec<VariadicCallSite<'m>>,
+}
+
+/// Information about the `va_list` operations in a single variadic function
+#[derive(Clone, Debug)]
+pub struct VariadicFunctionInfo<'m> {
+    /// Calls to `llvm.va_start`
+    pub va_starts: Vec<VaListOp<'m>>,
+    /// Calls to `llvm.va_copy`
+    pub va_copies: Vec<VaCopy<'m>>,
+    /// Calls to `llvm.va_end`
+    pub va_ends: Vec<VaListOp<'m>>,
+    /// `va_arg` instructions
+    pub va_args: Vec<VaArgSite<'m>>,
+}
+
+/// A `va_start` or `va_end` of a `va_list`
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct VaListOp<'m> {
+    /// Name of the basic block containing the operation
+    pub block: &'m Name,
+    /// Index of the operation within the basic block
+    pub index: usize,
+    /// The `va_list` being operated on, traced back through bitcasts and
+    /// GEPs to the local (usually an `alloca`) it is based on. `None` if the
+    /// `va_list` is not a local (e.g., a global or a constant expression).
+    pub va_list: Option<&'m Name>,
+}
+
+/// A `va_copy` from one `va_list` to another
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct VaCopy<'m> {
+    /// Name of the basic block containing the `va_copy`
+    pub block: &'m Name,
+    /// Index of the `va_copy` within the basic block
+    pub index: usize,
+    /// The destination `va_list`, traced as in [`VaListOp`](struct.VaListOp.html)
+    pub dest: Option<&'m Name>,
+    /// The source `va_list`, traced as in [`VaListOp`](struct.VaListOp.html)
+    pub src: Option<&'m Name>,
+}
+
+/// A `va_arg` instruction
+#[derive(Clone, PartialEq, Debug)]
+pub struct VaArgSite<'m> {
+    /// Name of the basic block containing the `va_arg`
+    pub block: &'m Name,
+    /// Index of the `va_arg` within the basic block
+    pub index: usize,
+    /// The `va_list` being read from, traced as in [`VaListOp`](struct.VaListOp.html)
+    pub va_list: Option<&'m Name>,
+    /// The result of the `va_arg`
+    pub dest: &'m Name,
+    /// The type of the value extracted
+    pub ty: TypeRef,
+    /// Which of the variadic arguments this `va_arg` consumes (`0` is the
+    /// first argument after the fixed parameters), if this can be determined.
+    ///
+    /// This is only determined for `va_arg`s of a `va_list` which is
+    /// initialized by exactly one `va_start` (not a `va_copy`), and only when
+    /// all of that `va_list`'s `va_arg`s are outside of loops and each
+    /// dominates the next.
+    pub position: Option<usize>,
+}
+
+/// A call to a variadic function
+#[derive(Clone, PartialEq, Debug)]
+pub struct VariadicCallSite<'m> {
+    /// Name of the function containing the call
+    pub caller: FunctionName<'m>,
+    /// Name of the basic block containing the call
+    pub block: &'m Name,
+    /// Index of the call within the basic block. If the call is the block's
+    /// terminator (e.g., an `Invoke`), this is equal to the number of
+    /// (non-terminator) instructions in the block.
+    pub index: usize,
+    /// Name of the variadic function being called
+    pub callee: FunctionName<'m>,
+    /// The arguments passed in the variadic portion of the call, i.e., after
+    /// the fixed parameters
+    pub extra_args: Vec<&'m Operand>,
+}
+
+impl<'m> VarargsAnalysis<'m> {
+    pub(crate) fn new(modules: impl IntoIterator<Item = &'m Module>) -> Self {
+        let modules: Vec<&'m Module> = modules.into_iter().collect();
+
+        // number of fixed parameters of each variadic function, defined or declared
+        let mut num_fixed_params: HashMap<FunctionName<'m>, usize> = HashMap::new();
+        let mut functions = HashMap::new();
+        for module in &modules {
+            for (f, name) in functions_with_names(module).filter(|(f, _)| f.is_var_arg) {
+                functions.insert(name, VariadicFunctionInfo::new(f));
+            }
+            let signatures = module
+                .functions
+                .iter()
+                .map(|f| (f.is_var_arg, f.parameters.len()))
+                .chain(
+                    module
+                        .func_declarations
+                        .iter()
+                        .map(|d| (d.is_var_arg, d.parameters.len())),
+                );
+            for (name, (is_var_arg, num_params)) in function_names(module).zip(signatures) {
+                if is_var_arg {
+                    num_fixed_params.insert(name, num_params);
+                }
+            }
+        }
+
+        let mut call_sites = vec![];
+        for module in &modules {
+            let num_numbered = num_numbered_functions(module);
+            for (f, name) in functions_with_names(module) {
+                for bb in &f.basic_blocks {
+                    let calls = bb
+                        .instrs
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(i, inst)| match inst {
+                            Instruction::Call(call) => {
+                                Some((i, call.function.as_ref().right()?, &call.arguments))
+                            }
+                            _ => None,
+                        });
+                    let invoke = match &bb.term {
+                        Terminator::Invoke(invoke) => invoke
+                            .function
+                            .as_ref()
+                            .right()
+                            .map(|callee| (bb.instrs.len(), callee, &invoke.arguments)),
+                        _ => None,
+                    };
+                    for (index, callee, arguments) in calls.chain(invoke) {
+                        let Operand::ConstantOperand(callee) = callee else {
+                            continue;
+                        };
+                        let Some(callee) = callee_global(callee)
+                            .and_then(|callee| global_name(module, num_numbered, callee))
+                        else {
+                            continue;
+                        };
+                        let Some(&num_fixed) = num_fixed_params.get(&callee) else {
+                            continue;
+                        };
+                        call_sites.push(VariadicCallSite {
+                            caller: name,
+                            block: &bb.name,
+                            index,
+                            callee,
+                            extra_args: arguments
+                                .iter()
+                                .skip(num_fixed)
+                                .map(|(op, _)| op)
+                                .collect(),
+                        });
+                    }
+                }
+            }
+        }
+
+        Self {
+            functions,
+            call_sites,
+        }
+    }
+
+    /// Iterate over the names of all of the variadic functions defined in the
+    /// analyzed `Module`(s).
+    pub fn variadic_functions<'s>(&'s self) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        self.functions.keys().copied()
+    }
+
+    /// Get the `VariadicFunctionInfo` for the variadic function with the given
+    /// name, or `None` if no variadic function with that name is defined in
+    /// the analyzed `Module`(s).
+    pub fn function_info(
+        &self,
+        func_name: impl Into<FunctionName<'m>>,
+    ) -> Option<&VariadicFunctionInfo<'m>> {
+        self.functions.get(&func_name.into())
+    }
+
+    /// Iterate over all the calls to variadic functions in the analyzed
+    /// `Module`(s). This includes calls to variadic functions which are only
+    /// declared (e.g., `printf`).
+    pub fn call_sites<'s>(&'s self) -> impl Iterator<Item = &'s VariadicCallSite<'m>> + 's {
+        self.call_sites.iter()
+    }
+
+    /// Iterate over all the calls to the variadic function with the given name.
+    pub fn call_sites_of<'s>(
+        &'s self,
+        func_name: impl Into<FunctionName<'s>>,
+    ) -> impl Iterator<Item = &'s VariadicCallSite<'m>> + 's {
+        let func_name = func_name.into();
+        let sites: Vec<&'s VariadicCallSite<'m>> = self
+            .call_sites
+            .iter()
+            .filter(|site| site.callee == func_name)
+            .collect();
+        sites.into_iter()
+    }
+
+    /// Get the `va_arg` which consumes the extra argument with the given index
+    /// (`0` is the first argument after the fixed parameters) at the given call
+    /// site.
+    ///
+    /// Returns `None` if the callee is not defined in the analyzed
+    /// `Module`(s), or if the consumer can't be determined (see notes on
+    /// [`VaArgSite.position`](struct.VaArgSite.html#structfield.position)).
+    pub fn consumer(
+        &self,
+        call_site: &VariadicCallSite<'m>,
+        extra_arg_index: usize,
+    ) -> Option<&VaArgSite<'m>> {
+        self.functions
+            .get(&call_site.callee)?
+            .va_args
+            .iter()
+            .find(|va_arg| va_arg.position == Some(extra_arg_index))
+    }
+}
+
+impl<'m> VariadicFunctionInfo<'m> {
+    fn new(func: &'m Function) -> Self {
+        let defs: HashMap<&'m Name, &'m Instruction> = func
+            .basic_blocks
+            .iter()
+            .flat_map(|bb| bb.instrs.iter())
+            .filter_map(|inst| Some((inst.try_get_result()?, inst)))
+            .collect();
+        let base = |op: &'m Operand| va_list_base(&defs, op);
+
+        let mut info = Self {
+            va_starts: vec![],
+            va_copies: vec![],
+            va_ends: vec![],
+            va_args: vec![],
+        };
+        for bb in &func.basic_blocks {
+            for (index, inst) in bb.instrs.iter().enumerate() {
+                match inst {
+                    Instruction::VAArg(va_arg) => info.va_args.push(VaArgSite {
+                        block: &bb.name,
+                        index,
+                        va_list: base(&va_arg.arg_list),
+                        dest: &va_arg.dest,
+                        ty: va_arg.cur_type.clone(),
+                        position: None,
+                    }),
+                    Instruction::Call(call) => {
+                        let Some(callee) = call.function.as_ref().right().and_then(direct_callee)
+                        else {
+                            continue;
+                        };
+                        let arg = |i: usize| call.arguments.get(i).and_then(|(op, _)| base(op));
+                        let op = VaListOp {
+                            block: &bb.name,
+                            index,
+                            va_list: arg(0),
+                        };
+                        if callee.starts_with("llvm.va_start") {
+                            info.va_starts.push(op);
+                        } else if callee.starts_with("llvm.va_end") {
+                            info.va_ends.push(op);
+                        } else if callee.starts_with("llvm.va_copy") {
+                            info.va_copies.push(VaCopy {
+                                block: &bb.name,
+                                index,
+                                dest: arg(0),
+                                src: arg(1),
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if !info.va_args.is_empty() {
+            info.compute_positions(func);
+        }
+        info
+    }
+
+    /// Fill in the `position` of each `va_arg`, where it can be determined
+    fn compute_positions(&mut self, func: &'m Function) {
+        let cfg = ControlFlowGraph::new(func);
+        let domtree = DominatorTree::new(&cfg);
+        let in_loop = |block: &'m Name| {
+            cfg.succs(block).any(|succ| {
+                petgraph::algo::has_path_connecting(&cfg.graph, succ, CFGNode::Block(block), None)
+            })
+        };
+        // does the `va_arg` with index `a` execute before the one with index
+        // `b` on every path through the function
+        let precedes = |a: &VaArgSite<'m>, b: &VaArgSite<'m>| {
+            if a.block == b.block {
+                a.index < b.index
+            } else {
+                domtree.strictly_dominates(CFGNode::Block(a.block), CFGNode::Block(b.block))
+            }
+        };
+
+        for va_start in &self.va_starts {
+            let Some(va_list) = va_start.va_list else {
+                continue;
+            };
+            if self
+                .va_starts
+                .iter()
+                .filter(|s| s.va_list == Some(va_list))
+                .count()
+                != 1
+                || self.va_copies.iter().any(|c| c.dest == Some(va_list))
+            {
+                continue;
+            }
+            let mut remaining: Vec<usize> = (0..self.va_args.len())
+                .filter(|&i| self.va_args[i].va_list == Some(va_list))
+                .collect();
+            if remaining.iter().any(|&i| in_loop(self.va_args[i].block)) {
+                continue;
+            }
+            // repeatedly pick the `va_arg` which precedes all the others
+            let mut order = vec![];
+            while !remaining.is_empty() {
+                let Some(pos) = remaining.iter().position(|&i| {
+                    remaining
+                        .iter()
+                        .all(|&j| i == j || precedes(&self.va_args[i], &self.va_args[j]))
+                }) else {
+                    break;
+                };
+                order.push(remaining.swap_remove(pos));
+            }
+            if remaining.is_empty() {
+                for (position, i) in order.into_iter().enumerate() {
+                    self.va_args[i].position = Some(position);
+                }
+            }
+        }
+    }
+}
+
+/// Trace the given `va_list` pointer back through bitcasts and GEPs to the
+/// local it is based on
+fn va_list_base<'m>(
+    defs: &HashMap<&'m Name, &'m Instruction>,
+    mut op: &'m Operand,
+) -> Option<&'m Name> {
+    loop {
+        let Operand::LocalOperand { name, .. } = op else {
+            return None;
+        };
+        match defs.get(name) {
+            Some(Instruction::BitCast(bitcast)) => op = &bitcast.operand,
+            Some(Instruction::GetElementPtr(gep)) => op = &gep.address,
+            _ => return Some(name),
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use crate::control_flow_graph::{CFGNode, ControlFlowGraph};
+use crate::expression::Expression;
+use crate::operands::instruction_operands;
+use llvm_ir::{BasicBlock, Function, Instruction, Name, Operand};
+use std::collections::{HashMap, HashSet};
+
+/// Very busy (or anticipated) expressions of a particular function: at each
+/// point, which [`Expression`](struct.Expression.html)s will be computed on
+/// every path from that point, before any of their operands could change.
+///
+/// This is the backward dual of available expressions. An expression which is
+/// very busy at some point can be computed there instead, without computing it
+/// on any path where it wasn't computed before; so it can be hoisted to the
+/// end of any block where it's very busy on exit, and in particular out of
+/// both arms of a conditional.
+///
+/// In SSA form, operands change only at their definitions: so an expression
+/// isn't very busy before the definition of any of its operands, including
+/// `phi`s. Paths to the end of the function (through `ret`, `unreachable`, or
+/// `resume`) compute no more expressions.
+///
+/// To construct a `VeryBusyExpressions`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct VeryBusyExpressions<'m> {
+    /// The function
+    function: &'m Function,
+    /// Every distinct expression computed in the function, in the order they
+    /// first appear
+    expressions: Vec<Expression>,
+    /// Index of each expression in `expressions`
+    indices: HashMap<Expression, usize>,
+    /// Expression computed by the instruction defining each local value, as
+    /// an index into `expressions`
+    computed: HashMap<&'m Name, usize>,
+    /// Expressions using each local value, as indices into `expressions`
+    users: HashMap<&'m Name, Vec<usize>>,
+    /// Expressions very busy on entry to each block
+    very_busy_in: HashMap<&'m Name, HashSet<usize>>,
+    /// Expressions very busy on exit from each block
+    very_busy_out: HashMap<&'m Name, HashSet<usize>>,
+}
+
+impl<'m> VeryBusyExpressions<'m> {
+    pub(crate) fn new(function: &'m Function, cfg: &ControlFlowGraph<'m>) -> Self {
+        let mut expressions = Vec::new();
+        let mut indices = HashMap::new();
+        let mut computed = HashMap::new();
+        let mut users: HashMap<&'m Name, Vec<usize>> = HashMap::new();
+        for inst in function.basic_blocks.iter().flat_map(|bb| &bb.instrs) {
+            if let Some(expression) = Expression::new(inst) {
+                let index = *indices.entry(expression.clone()).or_insert_with(|| {
+                    let index = expressions.len();
+                    for operand in instruction_operands(inst) {
+                        if let Operand::LocalOperand { name, .. } = operand {
+                            users.entry(name).or_default().push(index);
+                        }
+                    }
+                    expressions.push(expression);
+                    index
+                });
+                if let Some(result) = inst.try_get_result() {
+                    computed.insert(result, index);
+                }
+            }
+        }
+
+        // start from every expression, and remove those which aren't very busy
+        let all: HashSet<usize> = (0..expressions.len()).collect();
+        let mut very_busy = Self {
+            function,
+            expressions,
+            indices,
+            computed,
+            users,
+            very_busy_in: function
+                .basic_blocks
+                .iter()
+                .map(|bb| (&bb.name, all.clone()))
+                .collect(),
+            very_busy_out: HashMap::new(),
+        };
+
+        let mut worklist: Vec<&'m Name> = function.basic_blocks.iter().map(|bb| &bb.name).collect();
+        let mut on_worklist: HashSet<&'m Name> = worklist.iter().copied().collect();
+        while let Some(block) = worklist.pop() {
+            on_worklist.remove(block);
+            let mut very_busy_out: Option<HashSet<usize>> = None;
+            for succ in cfg.succs(block) {
+                let succ_in = match succ {
+                    CFGNode::Block(succ) => &very_busy.very_busy_in[succ],
+                    CFGNode::Return => {
+                        very_busy_out = Some(HashSet::new());
+                        break;
+                    }
+                };
+                very_busy_out = Some(match very_busy_out {
+                    None => succ_in.clone(),
+                    Some(out) => out.intersection(succ_in).copied().collect(),
+                });
+            }
+            let very_busy_out = very_busy_out.unwrap_or_default();
+            let very_busy_in = very_busy.very_busy_before(block, 0, very_busy_out.clone());
+            very_busy.very_busy_out.insert(block, very_busy_out);
+            if very_busy_in != very_busy.very_busy_in[block] {
+                very_busy.very_busy_in.insert(block, very_busy_in);
+                for pred in cfg.preds(block) {
+                    if on_worklist.insert(pred) {
+                        worklist.push(pred);
+                    }
+                }
+            }
+        }
+        very_busy
+    }
+
+    /// Iterate over every distinct expression computed in the function, in
+    /// the order they first appear
+    pub fn expressions(&self) -> impl Iterator<Item = &Expression> {
+        self.expressions.iter()
+    }
+
+    /// Get the expression computed by the instruction defining the local value
+    /// with the given `Name`, or `None` if that instruction isn't pure (see
+    /// [`Expression`](struct.Expression.html))
+    pub fn expression(&self, value: &Name) -> Option<&Expression> {
+        self.computed
+            .get(value)
+            .map(|&index| &self.expressions[index])
+    }
+
+    /// Iterate over the expressions very busy on entry to the block with the
+    /// given `Name`, in no particular order
+    pub fn very_busy_in<'s>(&'s self, block: &'m Name) -> impl Iterator<Item = &'s Expression> {
+        self.block_set(&self.very_busy_in, block)
+            .iter()
+            .map(move |&index| &self.expressions[index])
+    }
+
+    /// Iterate over the expressions very busy on exit from the block with the
+    /// given `Name`, in no particular order
+    pub fn very_busy_out<'s>(&'s self, block: &'m Name) -> impl Iterator<Item = &'s Expression> {
+        self.block_set(&self.very_busy_out, block)
+            .iter()
+            .map(move |&index| &self.expressions[index])
+    }
+
+    /// Is the given expression very busy on entry to the given block?
+    pub fn is_very_busy_in(&self, expression: &Expression, block: &'m Name) -> bool {
+        self.indices
+            .get(expression)
+            .is_some_and(|index| self.block_set(&self.very_busy_in, block).contains(index))
+    }
+
+    /// Is the given expression very busy on exit from the given block?
+    pub fn is_very_busy_out(&self, expression: &Expression, block: &'m Name) -> bool {
+        self.indices
+            .get(expression)
+            .is_some_and(|index| self.block_set(&self.very_busy_out, block).contains(index))
+    }
+
+    /// Get the expressions very busy just before the instruction at index
+    /// `idx` of the given block executes, where index `instrs.len()` refers to
+    /// the block's terminator. Since the block's `phi`s execute together on
+    /// entry to the block, for the index of any `phi` this is the set of
+    /// expressions very busy on entry to the block.
+    ///
+    /// Panics if the block isn't in this function, or the index is out of
+    /// range.
+    pub fn very_busy_at(&self, block: &'m Name, idx: usize) -> HashSet<&Expression> {
+        let very_busy_out = self.block_set(&self.very_busy_out, block).clone();
+        self.very_busy_before(block, idx, very_busy_out)
+            .into_iter()
+            .map(|index| &self.expressions[index])
+            .collect()
+    }
+
+    /// Is the given expression very busy just before the instruction at index
+    /// `idx` of the given block executes? See
+    /// [`very_busy_at()`](#method.very_busy_at).
+    ///
+    /// Panics if the block isn't in this function, or the index is out of
+    /// range.
+    pub fn is_very_busy_at(&self, expression: &Expression, block: &'m Name, idx: usize) -> bool {
+        self.very_busy_at(block, idx).contains(expression)
+    }
+
+    /// Get the expressions very busy just before the instruction at index
+    /// `idx` of the given block, given those very busy on exit from the block
+    fn very_busy_before(
+        &self,
+        block: &'m Name,
+        idx: usize,
+        mut very_busy: HashSet<usize>,
+    ) -> HashSet<usize> {
+        let bb = self.block(block);
+        if idx > bb.instrs.len() {
+            panic!(
+                "very_busy_at(): index {} out of range for block {} in function {}",
+                idx, block, self.function.name
+            );
+        }
+        if let Some(result) = bb.term.try_get_result() {
+            self.kill(&mut very_busy, result);
+        }
+        // the `phi`s' results are defined together on entry, so we handle
+        // them after the other instructions, whichever index we stop at
+        let num_phis = bb
+            .instrs
+            .iter()
+            .take_while(|inst| matches!(inst, Instruction::Phi(_)))
+            .count();
+        for inst in bb.instrs.iter().skip(idx.max(num_phis)).rev() {
+            if let Some(result) = inst.try_get_result() {
+                self.kill(&mut very_busy, result);
+                if let Some(&index) = self.computed.get(result) {
+                    very_busy.insert(index);
+                }
+            }
+        }
+        if idx < num_phis {
+            for inst in &bb.instrs[..num_phis] {
+                if let Some(result) = inst.try_get_result() {
+                    self.kill(&mut very_busy, result);
+                }
+            }
+        }
+        very_busy
+    }
+
+    /// Remove the expressions using the given value from the given set
+    fn kill(&self, very_busy: &mut HashSet<usize>, value: &Name) {
+        for index in self.users.get(value).into_iter().flatten() {
+            very_busy.remove(index);
+        }
+    }
+
+    fn block(&self, block: &Name) -> &'m BasicBlock {
+        self.function.get_bb_by_name(block).unwrap_or_else(|| {
+            panic!(
+                "Failed to find block {} in function {}",
+                block, self.function.name
+            )
+        })
+    }
+
+    fn block_set<'s>(
+        &'s self,
+        sets: &'s HashMap<&'m Name, HashSet<usize>>,
+        block: &Name,
+    ) -> &'s HashSet<usize> {
+        sets.get(block).unwrap_or_else(|| {
+            panic!(
+                "Failed to find block {} in function {}",
+                block, self.function.name
+            )
+        })
+    }
+}
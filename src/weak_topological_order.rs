@@ -0,0 +1,264 @@
+use crate::control_flow_graph::{CFGNode, ControlFlowGraph};
+use llvm_ir::Name;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A weak topological ordering (WTO) of the blocks of a particular function,
+/// as defined by Bourdoncle ("Efficient chaotic iteration strategies with
+/// widenings", 1993).
+///
+/// A WTO is a sequence of blocks and nested components, each of which is a
+/// `head` block followed by a WTO of the rest of the component. Every edge
+/// `a -> b` either goes forward in the ordering, or goes backward to the head
+/// `b` of a component containing `a`. So the heads are a set of widening
+/// points which cut every cycle in the CFG; for reducible CFGs, they're the
+/// loop headers, and the components are the loops.
+///
+/// This is the standard iteration strategy for abstract interpretation: visit
+/// the blocks in order, stabilizing each component (by iterating over its
+/// contents until its head's state stops changing, widening at the head)
+/// before moving on.
+///
+/// Only blocks reachable from the entry are in the ordering.
+///
+/// To construct a `WeakTopologicalOrder`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct WeakTopologicalOrder<'m> {
+    /// The top-level components, in order
+    components: Vec<WTOComponent<'m>>,
+    /// Map from each block to the heads of the components containing it,
+    /// outermost first
+    heads: HashMap<&'m Name, Vec<&'m Name>>,
+}
+
+/// An element of a [`WeakTopologicalOrder`](struct.WeakTopologicalOrder.html)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum WTOComponent<'m> {
+    /// A single block which isn't the head of a component
+    Vertex(&'m Name),
+    /// A component: a head block, followed by the WTO of the rest of the
+    /// component
+    Component {
+        head: &'m Name,
+        components: Vec<WTOComponent<'m>>,
+    },
+}
+
+impl<'m> WTOComponent<'m> {
+    /// Get the first block of the component: its head, or the block itself
+    /// for a `Vertex`
+    pub fn head(&self) -> &'m Name {
+        match self {
+            WTOComponent::Vertex(block) => block,
+            WTOComponent::Component { head, .. } => head,
+        }
+    }
+
+    /// Iterate over the blocks in the component, including those in nested
+    /// components, in order
+    pub fn blocks(&self) -> impl Iterator<Item = &'m Name> {
+        let mut blocks = Vec::new();
+        self.collect_blocks(&mut blocks);
+        blocks.into_iter()
+    }
+
+    fn collect_blocks(&self, blocks: &mut Vec<&'m Name>) {
+        match self {
+            WTOComponent::Vertex(block) => blocks.push(block),
+            WTOComponent::Component { head, components } => {
+                blocks.push(head);
+                for component in components {
+                    component.collect_blocks(blocks);
+                }
+            }
+        }
+    }
+}
+
+/// State of Bourdoncle's algorithm
+struct Builder<'a, 'm> {
+    cfg: &'a ControlFlowGraph<'m>,
+    /// Depth-first number of each block visited so far, or `usize::MAX` for
+    /// blocks whose component is complete
+    dfn: HashMap<&'m Name, usize>,
+    /// Number of blocks visited so far
+    num: usize,
+    /// Blocks visited but not yet placed in a component
+    stack: Vec<&'m Name>,
+}
+
+impl<'m> WeakTopologicalOrder<'m> {
+    pub(crate) fn new(cfg: &ControlFlowGraph<'m>) -> Self {
+        let mut builder = Builder {
+            cfg,
+            dfn: HashMap::new(),
+            num: 0,
+            stack: Vec::new(),
+        };
+        // components are prepended to partitions, so we build each partition
+        // in reverse
+        let mut components = Vec::new();
+        builder.visit(cfg.entry(), &mut components);
+        components.reverse();
+
+        let mut wto = Self {
+            components,
+            heads: HashMap::new(),
+        };
+        let mut enclosing = Vec::new();
+        for component in &wto.components {
+            record_heads(component, &mut enclosing, &mut wto.heads);
+        }
+        wto
+    }
+
+    /// Get the top-level components of the ordering, in order
+    pub fn components(&self) -> &[WTOComponent<'m>] {
+        &self.components
+    }
+
+    /// Iterate over all the blocks in the ordering (i.e., the blocks
+    /// reachable from the entry), in order
+    pub fn blocks<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
+        self.components
+            .iter()
+            .flat_map(|component| component.blocks())
+    }
+
+    /// Iterate over the heads of all the components, in order. These are the
+    /// widening points of the ordering.
+    pub fn heads<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
+        self.blocks().filter(move |block| self.is_head(block))
+    }
+
+    /// Is the given block the head of a component?
+    pub fn is_head(&self, block: &'m Name) -> bool {
+        self.heads_of(block).last() == Some(&block)
+    }
+
+    /// Get the heads of the components containing the given block, outermost
+    /// first. A head is contained in its own component. This is empty for
+    /// blocks not in any component, and for blocks unreachable from the
+    /// entry.
+    pub fn heads_of(&self, block: &'m Name) -> &[&'m Name] {
+        self.heads.get(block).map_or(&[], |heads| heads.as_slice())
+    }
+
+    /// Get the number of components containing the given block
+    pub fn depth(&self, block: &'m Name) -> usize {
+        self.heads_of(block).len()
+    }
+}
+
+impl<'m> fmt::Display for WeakTopologicalOrder<'m> {
+    /// Display the ordering in Bourdoncle's notation, e.g.,
+    /// `%entry (%loop %body) %exit`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_components(&self.components, f)
+    }
+}
+
+fn fmt_components(components: &[WTOComponent], f: &mut fmt::Formatter) -> fmt::Result {
+    for (i, component) in components.iter().enumerate() {
+        if i > 0 {
+            write!(f, " ")?;
+        }
+        match component {
+            WTOComponent::Vertex(block) => write!(f, "{}", block)?,
+            WTOComponent::Component { head, components } => {
+                write!(f, "({}", head)?;
+                if !components.is_empty() {
+                    write!(f, " ")?;
+                    fmt_components(components, f)?;
+                }
+                write!(f, ")")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Record the heads of the components containing each block in the given
+/// component, where `enclosing` are the heads of the components containing
+/// the given component
+fn record_heads<'m>(
+    component: &WTOComponent<'m>,
+    enclosing: &mut Vec<&'m Name>,
+    heads: &mut HashMap<&'m Name, Vec<&'m Name>>,
+) {
+    match component {
+        WTOComponent::Vertex(block) => {
+            heads.insert(block, enclosing.clone());
+        }
+        WTOComponent::Component { head, components } => {
+            enclosing.push(head);
+            heads.insert(head, enclosing.clone());
+            for component in components {
+                record_heads(component, enclosing, heads);
+            }
+            enclosing.pop();
+        }
+    }
+}
+
+impl<'a, 'm> Builder<'a, 'm> {
+    /// Visit the given block, prepending any completed components to
+    /// `partition` (which is in reverse). Returns the lowest depth-first
+    /// number reachable from the block through blocks still on the stack.
+    fn visit(&mut self, block: &'m Name, partition: &mut Vec<WTOComponent<'m>>) -> usize {
+        self.stack.push(block);
+        self.num += 1;
+        let dfn = self.num;
+        self.dfn.insert(block, dfn);
+        let mut head = dfn;
+        let mut is_loop = false;
+        for succ in self.succs(block) {
+            let min = match self.dfn.get(succ) {
+                None => self.visit(succ, partition),
+                Some(&succ_dfn) => succ_dfn,
+            };
+            if min <= head {
+                head = min;
+                is_loop = true;
+            }
+        }
+        if head == dfn {
+            self.dfn.insert(block, usize::MAX);
+            let mut element = self.stack.pop().expect("block should be on the stack");
+            if is_loop {
+                // the rest of the component will be revisited
+                while element != block {
+                    self.dfn.remove(element);
+                    element = self.stack.pop().expect("head should be on the stack");
+                }
+                partition.push(self.component(block));
+            } else {
+                partition.push(WTOComponent::Vertex(block));
+            }
+        }
+        head
+    }
+
+    /// Build the component with the given head
+    fn component(&mut self, head: &'m Name) -> WTOComponent<'m> {
+        let mut components = Vec::new();
+        for succ in self.succs(head) {
+            if !self.dfn.contains_key(succ) {
+                self.visit(succ, &mut components);
+            }
+        }
+        components.reverse();
+        WTOComponent::Component { head, components }
+    }
+
+    fn succs(&self, block: &'m Name) -> Vec<&'m Name> {
+        self.cfg
+            .succs(block)
+            .filter_map(|succ| match succ {
+                CFGNode::Block(succ) => Some(succ),
+                CFGNode::Return => None,
+            })
+            .collect()
+    }
+}
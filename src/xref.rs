@@ -0,0 +1,203 @@
+use crate::call_graph::{functions_with_names, FunctionName};
+use crate::operands::{
+    globals_in_constant, globals_in_operands, instruction_operands, terminator_operands,
+};
+use llvm_ir::{Module, Name};
+use std::collections::HashMap;
+
+/// A cross-reference index of the analyzed `Module`(s): for each symbol
+/// (function, global variable, alias, etc), every instruction and every global
+/// initializer which references it.
+///
+/// References are found anywhere in an operand, including inside constant
+/// expressions (e.g., a `getelementptr` or `bitcast` of a global) and inside
+/// aggregate constants (e.g., a table of function pointers). Direct calls count
+/// as references to the called function.
+///
+/// Symbols are matched by name, so in a `CrossModuleAnalysis`, a reference to a
+/// function declared in one module and defined in another is reported as a
+/// reference to that function.
+///
+/// To construct an `XRefIndex`, use [`ModuleAnalysis`](struct.ModuleAnalysis.html)
+/// or [`CrossModuleAnalysis`](struct.CrossModuleAnalysis.html).
+pub struct XRefIndex<'m> {
+    /// Map from symbol name to all the places which reference that symbol
+    refs: HashMap<&'m Name, Vec<XRef<'m>>>,
+}
+
+/// A single place which references a symbol
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum XRef<'m> {
+    /// An instruction or terminator within a function
+    Instruction {
+        /// Name of the `Module` containing the function
+        module: &'m str,
+        /// Name of the function containing the instruction
+        function: FunctionName<'m>,
+        /// Name of the basic block containing the instruction
+        block: &'m Name,
+        /// Index of the instruction within the basic block. If the reference
+        /// is in the block's terminator, this is equal to the number of
+        /// (non-terminator) instructions in the block.
+        index: usize,
+    },
+    /// The initializer of a global variable
+    GlobalInitializer {
+        /// Name of the `Module` containing the global variable
+        module: &'m str,
+        /// Name of the global variable
+        global: &'m Name,
+    },
+    /// The aliasee of a global alias
+    Alias {
+        /// Name of the `Module` containing the alias
+        module: &'m str,
+        /// Name of the alias
+        alias: &'m Name,
+    },
+    /// The resolver function of an IFunc
+    IFunc {
+        /// Name of the `Module` containing the IFunc
+        module: &'m str,
+        /// Name of the IFunc
+        ifunc: &'m Name,
+    },
+}
+
+impl<'m> XRef<'m> {
+    /// Get the name of the `Module` containing the reference
+    pub fn module(&self) -> &'m str {
+        match self {
+            XRef::Instruction { module, .. } => module,
+            XRef::GlobalInitializer { module, .. } => module,
+            XRef::Alias { module, .. } => module,
+            XRef::IFunc { module, .. } => module,
+        }
+    }
+
+    /// If the reference is in an instruction, get the name of the function
+    /// containing it
+    pub fn function(&self) -> Option<FunctionName<'m>> {
+        match self {
+            XRef::Instruction { function, .. } => Some(*function),
+            _ => None,
+        }
+    }
+}
+
+impl<'m> XRefIndex<'m> {
+    pub(crate) fn new(modules: impl IntoIterator<Item = &'m Module>) -> Self {
+        let mut refs: HashMap<&'m Name, Vec<XRef<'m>>> = HashMap::new();
+        let mut add_refs = |names: &mut Vec<&'m Name>, xref: XRef<'m>| {
+            // a single location is reported only once per symbol, even if it
+            // references that symbol multiple times
+            names.sort_unstable();
+            names.dedup();
+            for name in names.drain(..) {
+                refs.entry(name).or_default().push(xref.clone());
+            }
+        };
+        let mut names = vec![];
+        for module in modules {
+            for (f, name) in functions_with_names(module) {
+                for bb in &f.basic_blocks {
+                    for (index, inst) in bb.instrs.iter().enumerate() {
+                        globals_in_operands(instruction_operands(inst), &mut names);
+                        add_refs(
+                            &mut names,
+                            XRef::Instruction {
+                                module: &module.name,
+                                function: name,
+                                block: &bb.name,
+                                index,
+                            },
+                        );
+                    }
+                    globals_in_operands(terminator_operands(&bb.term), &mut names);
+                    add_refs(
+                        &mut names,
+                        XRef::Instruction {
+                            module: &module.name,
+                            function: name,
+                            block: &bb.name,
+                            index: bb.instrs.len(),
+                        },
+                    );
+                }
+            }
+            for global in &module.global_vars {
+                if let Some(initializer) = &global.initializer {
+                    globals_in_constant(initializer, &mut names);
+                    add_refs(
+                        &mut names,
+                        XRef::GlobalInitializer {
+                            module: &module.name,
+                            global: &global.name,
+                        },
+                    );
+                }
+            }
+            for alias in &module.global_aliases {
+                globals_in_constant(&alias.aliasee, &mut names);
+                add_refs(
+                    &mut names,
+                    XRef::Alias {
+                        module: &module.name,
+                        alias: &alias.name,
+                    },
+                );
+            }
+            for ifunc in &module.global_ifuncs {
+                globals_in_constant(&ifunc.resolver_fn, &mut names);
+                add_refs(
+                    &mut names,
+                    XRef::IFunc {
+                        module: &module.name,
+                        ifunc: &ifunc.name,
+                    },
+                );
+            }
+        }
+        Self { refs }
+    }
+
+    /// Iterate over every place which references the symbol with the given
+    /// name, across all the analyzed `Module`(s).
+    ///
+    /// The symbol may be a function, global variable, alias, or IFunc. If the
+    /// symbol is never referenced (or doesn't exist), this will be empty.
+    pub fn references_to<'s>(&'s self, symbol: &str) -> impl Iterator<Item = &'s XRef<'m>> + 's {
+        self.refs
+            .get(&Name::from(symbol))
+            .into_iter()
+            .flat_map(|xrefs| xrefs.iter())
+    }
+
+    /// Is the symbol with the given name referenced anywhere in the analyzed
+    /// `Module`(s)?
+    pub fn is_referenced(&self, symbol: &str) -> bool {
+        self.refs.contains_key(&Name::from(symbol))
+    }
+
+    /// Iterate over the names of the functions which contain at least one
+    /// reference to the symbol with the given name. Each function is only
+    /// reported once.
+    pub fn referencing_functions<'s>(
+        &'s self,
+        symbol: &str,
+    ) -> impl Iterator<Item = FunctionName<'m>> + 's {
+        let mut funcs: Vec<FunctionName<'m>> = self
+            .references_to(symbol)
+            .filter_map(XRef::function)
+            .collect();
+        funcs.sort_unstable();
+        funcs.dedup();
+        funcs.into_iter()
+    }
+
+    /// Iterate over the names of all symbols which are referenced anywhere in
+    /// the analyzed `Module`(s).
+    pub fn referenced_symbols<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
+        self.refs.keys().copied()
+    }
+}
@@ -0,0 +1,256 @@
+#![allow(clippy::redundant_static_lifetimes)]
+
+use llvm_ir::instruction::Phi;
+use llvm_ir::{Constant, Instruction, Module, Name, Operand};
+use llvm_ir_analysis::*;
+use std::collections::HashMap;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+const RANGES_BC_PATH: &'static str = "tests/bcfiles/ranges.bc";
+const SCCP_BC_PATH: &'static str = "tests/bcfiles/sccp.bc";
+
+fn range(min: i128, max: i128) -> Option<ValueRange> {
+    Some(ValueRange { min, max })
+}
+
+#[test]
+fn interval_interpretation() {
+    init_logging();
+    let module = Module::from_bc_path(RANGES_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let func = module.get_func_by_name("count").unwrap();
+    let fn_analysis = analysis.fn_analysis("count");
+    let interpretation = fn_analysis.abstract_interpretation(IntervalSemantics::new(func));
+
+    let entry_name = Name::from("entry");
+    let loop_name = Name::from("loop");
+    let body_name = Name::from("body");
+    let exit_name = Name::from("exit");
+    let i = Name::from("i");
+
+    let loop_state = interpretation.state_at_block_entry(&loop_name).unwrap();
+    assert_eq!(loop_state.range(&i), range(0, 10));
+    let body_state = interpretation.state_at_block_entry(&body_name).unwrap();
+    assert_eq!(body_state.range(&i), range(0, 9));
+    let exit_state = interpretation.state_at_block_entry(&exit_name).unwrap();
+    assert_eq!(exit_state.range(&i), range(10, 10));
+    let entry_state = interpretation.state_at_block_entry(&entry_name).unwrap();
+    assert_eq!(entry_state.range(&i), None);
+    assert!(interpretation.is_block_reachable(&body_name));
+
+    // just before the terminator, after %i.next is defined
+    let state = interpretation
+        .state_at(InstructionLocation {
+            block: &body_name,
+            index: 4,
+        })
+        .unwrap();
+    assert_eq!(state.range(&Name::from("i.next")), range(1, 10));
+
+    // the interpretation agrees with `ValueRanges` everywhere
+    let ranges = fn_analysis.value_ranges();
+    for bb in &func.basic_blocks {
+        let state = interpretation.state_at_block_entry(&bb.name).unwrap();
+        for (value, value_range) in state.ranges() {
+            assert_eq!(
+                ranges.range_at_block_entry(value, &bb.name),
+                Some(value_range)
+            );
+        }
+    }
+}
+
+#[test]
+fn interpreter_settings() {
+    init_logging();
+    let module = Module::from_bc_path(RANGES_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let func = module.get_func_by_name("count").unwrap();
+    let fn_analysis = analysis.fn_analysis("count");
+    let cfg = fn_analysis.control_flow_graph();
+    let wto = fn_analysis.weak_topological_order();
+
+    let loop_name = Name::from("loop");
+    let i = Name::from("i");
+
+    // widening immediately, without narrowing, loses the loop bound
+    let interpretation = AbstractInterpreter::new()
+        .with_widening_delay(0)
+        .with_narrowing_passes(0)
+        .run(func, &cfg, &wto, IntervalSemantics::new(func));
+    let loop_state = interpretation.state_at_block_entry(&loop_name).unwrap();
+    assert_eq!(loop_state.range(&i), range(0, i32::MAX.into()));
+
+    // narrowing recovers it
+    let interpretation = AbstractInterpreter::new().with_widening_delay(0).run(
+        func,
+        &cfg,
+        &wto,
+        IntervalSemantics::new(func),
+    );
+    let loop_state = interpretation.state_at_block_entry(&loop_name).unwrap();
+    assert_eq!(loop_state.range(&i), range(0, 10));
+}
+
+#[test]
+fn known_ranges() {
+    init_logging();
+    let module = Module::from_bc_path(RANGES_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let func = module.get_func_by_name("clamp").unwrap();
+    let fn_analysis = analysis.fn_analysis("clamp");
+
+    let x = Name::from("x");
+    let r = Name::from("r");
+    let negative_name = Name::from("negative");
+    let join_name = Name::from("join");
+
+    // if %x is known to be in 10..=20, it's never clamped
+    let semantics =
+        IntervalSemantics::new(func).with_known_range(&x, ValueRange { min: 10, max: 20 });
+    let interpretation = fn_analysis.abstract_interpretation(semantics);
+    assert!(!interpretation.is_block_reachable(&negative_name));
+    let state = interpretation
+        .state_at(InstructionLocation {
+            block: &join_name,
+            index: 1,
+        })
+        .unwrap();
+    assert_eq!(state.range(&x), range(10, 20));
+    assert_eq!(state.range(&r), range(10, 20));
+
+    // a known range which the computed one rules out is ignored
+    let semantics =
+        IntervalSemantics::new(func).with_known_range(&r, ValueRange { min: 200, max: 300 });
+    let interpretation = fn_analysis.abstract_interpretation(semantics);
+    let state = interpretation.state_at_block_entry(&join_name).unwrap();
+    assert_eq!(state.range(&r), range(0, 100));
+}
+
+/// The parity of an integer
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Parity {
+    Even,
+    Odd,
+    Unknown,
+}
+
+impl Parity {
+    fn join(self, other: Self) -> Self {
+        if self == other {
+            self
+        } else {
+            Parity::Unknown
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        match (self, other) {
+            (Parity::Unknown, _) | (_, Parity::Unknown) => Parity::Unknown,
+            (a, b) if a == b => Parity::Even,
+            _ => Parity::Odd,
+        }
+    }
+}
+
+/// A finite domain, which can use the default widening and narrowing
+#[derive(Clone, PartialEq, Debug)]
+struct ParityState<'m>(HashMap<&'m Name, Parity>);
+
+impl<'m> AbstractDomain for ParityState<'m> {
+    fn join(&self, other: &Self) -> Self {
+        let mut joined = self.clone();
+        for (&value, &parity) in &other.0 {
+            joined
+                .0
+                .entry(value)
+                .and_modify(|joined| *joined = joined.join(parity))
+                .or_insert(parity);
+        }
+        joined
+    }
+}
+
+/// The parities of the results of `add`s, which leaves edges and terminators
+/// to the default implementations
+struct ParitySemantics;
+
+impl ParitySemantics {
+    fn operand(state: &ParityState, operand: &Operand) -> Parity {
+        match operand {
+            Operand::LocalOperand { name, .. } => {
+                state.0.get(name).copied().unwrap_or(Parity::Unknown)
+            }
+            Operand::ConstantOperand(constant) => match constant.as_ref() {
+                Constant::Int { value, .. } if value % 2 == 0 => Parity::Even,
+                Constant::Int { .. } => Parity::Odd,
+                _ => Parity::Unknown,
+            },
+            Operand::MetadataOperand => Parity::Unknown,
+        }
+    }
+}
+
+impl<'m> AbstractSemantics<'m> for ParitySemantics {
+    type State = ParityState<'m>;
+
+    fn entry_state(&self) -> ParityState<'m> {
+        ParityState(HashMap::new())
+    }
+
+    fn instruction(&self, state: &mut ParityState<'m>, inst: &'m Instruction) {
+        if let Instruction::Add(add) = inst {
+            let parity =
+                Self::operand(state, &add.operand0).add(Self::operand(state, &add.operand1));
+            state.0.insert(&add.dest, parity);
+        }
+    }
+
+    fn phi(
+        &self,
+        edge: &ParityState<'m>,
+        state: &mut ParityState<'m>,
+        phi: &'m Phi,
+        incoming: &'m Operand,
+    ) {
+        state.0.insert(&phi.dest, Self::operand(edge, incoming));
+    }
+}
+
+#[test]
+fn custom_domain() {
+    init_logging();
+    let module = Module::from_bc_path(SCCP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("loop_invariant");
+    let interpretation = fn_analysis.abstract_interpretation(ParitySemantics);
+
+    let loop_name = Name::from("loop");
+    let exit_name = Name::from("exit");
+
+    let loop_state = interpretation.state_at_block_entry(&loop_name).unwrap();
+    assert_eq!(loop_state.0[&Name::from("k")], Parity::Odd);
+    assert_eq!(loop_state.0[&Name::from("i")], Parity::Unknown);
+    let exit_state = interpretation.state_at_block_entry(&exit_name).unwrap();
+    assert_eq!(exit_state.0[&Name::from("k.next")], Parity::Odd);
+    assert_eq!(exit_state.0[&Name::from("i.next")], Parity::Unknown);
+    // before %k.next is defined, the state still has its parity from the
+    // previous iteration
+    let state = interpretation
+        .state_at(InstructionLocation {
+            block: &loop_name,
+            index: 2,
+        })
+        .unwrap();
+    assert_eq!(state.0.get(&Name::from("k.next")), Some(&Parity::Odd));
+    // the default edge semantics don't decide the loop's exit condition
+    assert!(interpretation.is_block_reachable(&exit_name));
+}
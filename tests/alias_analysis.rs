@@ -0,0 +1,266 @@
+// tbaa.ll relies on typed pointers
+#![cfg(feature = "llvm-14-or-lower")]
+#![allow(clippy::redundant_static_lifetimes)]
+
+use itertools::Itertools;
+use llvm_ir::{Instruction, Module, Name, Operand};
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+/// tbaa.ll is hand-written, with accesses through pointers of several types
+const TBAA_BC_PATH: &'static str = "tests/bcfiles/tbaa.bc";
+
+#[test]
+fn type_based_alias_analysis() {
+    init_logging();
+    let module = Module::from_bc_path(TBAA_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("type_punning");
+    let aa = fn_analysis.type_based_alias_analysis();
+
+    let func = module.get_func_by_name("type_punning").unwrap();
+    let bb = &func.basic_blocks[0];
+    // the pointer accessed by the instruction at the given index
+    let address = |index: usize| -> &Operand {
+        match &bb.instrs[index] {
+            Instruction::Load(load) => &load.address,
+            Instruction::Store(store) => &store.address,
+            Instruction::GetElementPtr(gep) => &gep.address,
+            inst => panic!("Expected a load, store, or GEP, got {}", inst),
+        }
+    };
+    let f = address(0);
+    let c = address(1);
+    let l = address(2);
+    let i = address(3);
+    let s = address(4);
+    let sl = address(5);
+    let fp = address(6);
+    let pp = address(7);
+    let p = address(8);
+
+    // scalar types of different sizes don't alias
+    assert!(!aa.may_alias(i, l));
+    assert!(!aa.may_alias(l, f));
+    // but those of the same size may be reinterpretations of each other
+    assert!(aa.may_alias(i, f));
+    // but the same type may
+    assert!(aa.may_alias(i, i));
+    assert!(aa.may_alias(sl, l));
+    // `i8` may alias anything
+    assert!(aa.may_alias(c, i));
+    assert!(aa.may_alias(f, c));
+    // a struct may alias its members' types, and scalars of the same size as
+    // its members
+    assert!(aa.may_alias(s, i));
+    assert!(aa.may_alias(l, s));
+    assert!(aa.may_alias(s, f));
+    // pointers may alias each other, whatever they point to; and since their
+    // size isn't known, they may be reinterpretations of anything else
+    assert!(aa.may_alias(fp, pp));
+    assert!(aa.may_alias(fp, i));
+    // we don't know what's in a named struct
+    assert!(aa.may_alias(p, l));
+}
+
+#[test]
+fn union_punning() {
+    init_logging();
+    let module = Module::from_bc_path(TBAA_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("union_punning");
+    let aa = fn_analysis.type_based_alias_analysis();
+
+    let func = module.get_func_by_name("union_punning").unwrap();
+    let bb = &func.basic_blocks[0];
+    let (ip, fp) = match (&bb.instrs[3], &bb.instrs[4]) {
+        (Instruction::Store(store), Instruction::Load(load)) => (&store.address, &load.address),
+        (a, b) => panic!("Expected a store and a load, got {} and {}", a, b),
+    };
+
+    // both pointers are into the same union, so they may alias, whatever
+    // their types; and so the load depends on the store
+    assert!(aa.may_alias(ip, fp));
+    let ddg = fn_analysis.data_dependence_graph_with_alias_analysis(&*aa);
+    let entry_name = Name::from("entry");
+    let loc = |index| InstructionLocation {
+        block: &entry_name,
+        index,
+    };
+    assert_eq!(
+        ddg.dependencies(loc(4)).sorted().collect::<Vec<_>>(),
+        vec![loc(2), loc(3)]
+    );
+}
+
+#[test]
+fn self_referential_pointer() {
+    init_logging();
+    let module = Module::from_bc_path(TBAA_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("self_referential");
+    let aa = fn_analysis.type_based_alias_analysis();
+
+    let func = module.get_func_by_name("self_referential").unwrap();
+    let (p, q) = match (
+        &func.basic_blocks[0].instrs[0],
+        &func.basic_blocks[1].instrs[1],
+    ) {
+        (Instruction::Load(load), Instruction::Store(store)) => (&load.address, &store.address),
+        (a, b) => panic!("Expected a load and a store, got {} and {}", a, b),
+    };
+
+    // %q isn't based on any known object, so only the types are considered
+    assert!(aa.may_alias(p, q));
+    assert!(aa.may_alias(q, q));
+}
+
+#[test]
+fn global_alias() {
+    init_logging();
+    let module = Module::from_bc_path(TBAA_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("global_alias");
+    let aa = fn_analysis.type_based_alias_analysis();
+
+    let func = module.get_func_by_name("global_alias").unwrap();
+    let address = |index: usize| -> &Operand {
+        match &func.basic_blocks[0].instrs[index] {
+            Instruction::Store(store) => &store.address,
+            inst => panic!("Expected a store, got {}", inst),
+        }
+    };
+    let g = address(0);
+    let g_alias = address(1);
+    let h = address(2);
+
+    // the alias is resolved to the global it aliases
+    assert!(aa.may_alias(g, g_alias));
+    assert!(!aa.may_alias(g, h));
+    assert!(!aa.may_alias(g_alias, h));
+
+    // without the `Module`, any global may be an alias of any other
+    let fn_analysis = FunctionAnalysis::new(func);
+    let aa = fn_analysis.type_based_alias_analysis();
+    assert!(aa.may_alias(g, g_alias));
+    assert!(aa.may_alias(g, h));
+}
+
+#[test]
+fn bitcast_punning() {
+    init_logging();
+    let module = Module::from_bc_path(TBAA_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("bitcast_punning");
+    let aa = fn_analysis.type_based_alias_analysis();
+
+    let func = module.get_func_by_name("bitcast_punning").unwrap();
+    let bb = &func.basic_blocks[0];
+    let (s, l, f) = match (&bb.instrs[0], &bb.instrs[2], &bb.instrs[3]) {
+        (Instruction::Store(store), Instruction::Load(l), Instruction::Load(f)) => {
+            (&store.address, &l.address, &f.address)
+        }
+        (a, b, c) => panic!(
+            "Expected a store and two loads, got {}, {}, and {}",
+            a, b, c
+        ),
+    };
+
+    // the `i64` load reads the pair of `i32`s stored through %s
+    assert!(aa.may_alias(s, l));
+    // a `float` may be a reinterpretation of either `i32`, but not of an `i64`
+    assert!(aa.may_alias(s, f));
+    assert!(!aa.may_alias(l, f));
+}
+
+#[test]
+fn type_based_ddg() {
+    init_logging();
+    let module = Module::from_bc_path(TBAA_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("type_punning");
+
+    let entry_name = Name::from("entry");
+    let loc = |index| InstructionLocation {
+        block: &entry_name,
+        index,
+    };
+
+    // by default, the parameters may all alias each other
+    let ddg = fn_analysis.data_dependence_graph();
+    assert_eq!(
+        ddg.dependencies(loc(3)).sorted().collect::<Vec<_>>(),
+        vec![loc(0), loc(1), loc(2)]
+    );
+    assert_eq!(
+        ddg.dependencies(loc(5)).sorted().collect::<Vec<_>>(),
+        vec![loc(0), loc(1), loc(2), loc(4)]
+    );
+
+    // with type-based alias analysis, the `i32` load depends only on the
+    // `float` and `i8` stores, and the `i64` load only on the `i8` and `i64`
+    // stores
+    let aa = fn_analysis.type_based_alias_analysis();
+    let ddg = fn_analysis.data_dependence_graph_with_alias_analysis(&*aa);
+    assert_eq!(
+        ddg.dependencies(loc(3)).sorted().collect::<Vec<_>>(),
+        vec![loc(0), loc(1)]
+    );
+    assert_eq!(
+        ddg.dependencies(loc(5)).sorted().collect::<Vec<_>>(),
+        vec![loc(1), loc(2), loc(4)]
+    );
+    // the load of a `float*` may read what any of the scalar stores wrote
+    assert_eq!(
+        ddg.dependencies(loc(6)).sorted().collect::<Vec<_>>(),
+        vec![loc(0), loc(1), loc(2)]
+    );
+}
+
+#[test]
+fn type_based_memory_ssa() {
+    init_logging();
+    let module = Module::from_bc_path(TBAA_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("type_punning");
+
+    let entry_name = Name::from("entry");
+    let loc = |index| InstructionLocation {
+        block: &entry_name,
+        index,
+    };
+    let def = |index| MemoryDefinition::Def(loc(index));
+
+    // by default, each access is clobbered by the store just before it
+    let mssa = fn_analysis.memory_ssa();
+    assert_eq!(mssa.phi_blocks().count(), 0);
+    assert_eq!(mssa.defining_access(loc(3)), Some(def(2)));
+    assert_eq!(mssa.clobbering_definition(loc(3)), Some(def(2)));
+    assert_eq!(mssa.clobbering_definition(loc(6)), Some(def(2)));
+    assert_eq!(mssa.defining_access(loc(7)), Some(def(2)));
+
+    // with type-based alias analysis, the walk skips stores of other types;
+    // the defining accesses are the same
+    let aa = fn_analysis.type_based_alias_analysis();
+    let mssa = fn_analysis.memory_ssa_with_alias_analysis(&*aa);
+    assert_eq!(mssa.defining_access(loc(3)), Some(def(2)));
+    assert_eq!(mssa.clobbering_definition(loc(3)), Some(def(1)));
+    assert_eq!(mssa.clobbering_definition(loc(5)), Some(def(2)));
+    assert_eq!(mssa.clobbering_definition(loc(6)), Some(def(2)));
+    assert_eq!(
+        mssa.clobbering_definition(loc(0)),
+        Some(MemoryDefinition::LiveOnEntry)
+    );
+    assert_eq!(mssa.uses(def(2)), vec![loc(3), loc(5), loc(6), loc(7)]);
+}
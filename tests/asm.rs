@@ -0,0 +1,76 @@
+use llvm_ir::Module;
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+const ASM_BC_PATH: &str = "tests/bcfiles/asm.bc";
+
+#[test]
+fn inline_asm_inventory() {
+    init_logging();
+    let module = Module::from_bc_path(ASM_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let inventory = analysis.inline_asm_inventory();
+
+    let funcs: Vec<FunctionName> = inventory.functions_with_asm().collect();
+    assert_eq!(funcs, vec!["add_asm", "barrier"]);
+    assert_eq!(inventory.call_sites().count(), 2);
+
+    let sites: Vec<&InlineAsmCallSite> = inventory.call_sites_in("add_asm").collect();
+    assert_eq!(sites.len(), 1);
+    assert_eq!(sites[0].index, 0);
+    assert_eq!(sites[0].arguments.len(), 2);
+    assert!(sites[0].dest.is_some());
+    assert!(!sites[0].is_callbr);
+
+    let sites: Vec<&InlineAsmCallSite> = inventory.call_sites_in("barrier").collect();
+    assert_eq!(sites.len(), 1);
+    assert!(sites[0].arguments.is_empty());
+    assert!(sites[0].dest.is_none());
+
+    assert_eq!(inventory.call_sites_in("no_asm").count(), 0);
+
+    let module_asm: Vec<&ModuleAsm> = inventory.module_asm().collect();
+    assert_eq!(module_asm.len(), 1);
+    let lines: Vec<&str> = module_asm[0].lines().collect();
+    assert_eq!(
+        lines,
+        vec![".globl module_asm_sym", "module_asm_sym:", "ret"]
+    );
+}
+
+#[test]
+fn asm_constraints() {
+    let constraints = parse_asm_constraints("=r,0,r,~{cc},~{dirflag},~{fpsr},~{flags}");
+    assert_eq!(constraints.len(), 7);
+    assert_eq!(constraints[0].kind, AsmConstraintKind::Output);
+    assert_eq!(constraints[0].codes, vec!["r"]);
+    assert_eq!(constraints[1].kind, AsmConstraintKind::Input);
+    assert_eq!(constraints[1].codes, vec!["0"]);
+    assert_eq!(constraints[2].kind, AsmConstraintKind::Input);
+    assert_eq!(constraints[2].codes, vec!["r"]);
+    let clobbers: Vec<&str> = constraints
+        .iter()
+        .filter(|c| c.kind == AsmConstraintKind::Clobber)
+        .map(|c| c.codes[0].as_str())
+        .collect();
+    assert_eq!(clobbers, vec!["cc", "dirflag", "fpsr", "flags"]);
+
+    let constraints = parse_asm_constraints("=&{eax},=*m,imr,~{memory}");
+    assert_eq!(constraints.len(), 4);
+    assert!(constraints[0].early_clobber);
+    assert!(!constraints[0].indirect);
+    assert_eq!(constraints[0].codes, vec!["{eax}"]);
+    assert_eq!(constraints[1].kind, AsmConstraintKind::Output);
+    assert!(constraints[1].indirect);
+    assert_eq!(constraints[1].codes, vec!["m"]);
+    assert_eq!(constraints[2].codes, vec!["i", "m", "r"]);
+    assert_eq!(constraints[3].kind, AsmConstraintKind::Clobber);
+    assert_eq!(constraints[3].codes, vec!["memory"]);
+
+    assert!(parse_asm_constraints("").is_empty());
+}
@@ -1,5 +1,8 @@
+#![allow(clippy::bool_assert_comparison, clippy::redundant_static_lifetimes)]
+
+use either::Either;
 use itertools::Itertools;
-use llvm_ir::{Module, Name};
+use llvm_ir::{Constant, Instruction, IntPredicate, Module, Name, Operand, Terminator};
 use llvm_ir_analysis::*;
 
 fn init_logging() {
@@ -61,13 +64,13 @@ fn functions_by_type() {
     assert_eq!(fbt.functions_with_type(&functy).count(), 0);
 
     let functy = module.types.func_type(module.types.i32(), vec![], false);
-    let func_names: Vec<&str> = fbt.functions_with_type(&functy).sorted().collect();
+    let func_names: Vec<FunctionName> = fbt.functions_with_type(&functy).sorted().collect();
     assert_eq!(func_names, vec!["no_args_nozero", "no_args_zero"]);
 
     let functy = module
         .types
         .func_type(module.types.i32(), vec![module.types.i32()], false);
-    let func_names: Vec<&str> = fbt.functions_with_type(&functy).sorted().collect();
+    let func_names: Vec<FunctionName> = fbt.functions_with_type(&functy).sorted().collect();
     assert_eq!(func_names, vec!["one_arg"]);
 
     let functy = module.types.func_type(
@@ -75,7 +78,7 @@ fn functions_by_type() {
         vec![module.types.i32(), module.types.i32()],
         false,
     );
-    let func_names: Vec<&str> = fbt.functions_with_type(&functy).sorted().collect();
+    let func_names: Vec<FunctionName> = fbt.functions_with_type(&functy).sorted().collect();
     assert_eq!(
         func_names,
         vec![
@@ -95,7 +98,7 @@ fn functions_by_type() {
         vec![module.types.i32(), module.types.i32(), module.types.i32()],
         false,
     );
-    let func_names: Vec<&str> = fbt.functions_with_type(&functy).sorted().collect();
+    let func_names: Vec<FunctionName> = fbt.functions_with_type(&functy).sorted().collect();
     assert_eq!(func_names, vec!["three_args"]);
 
     let functy = module.types.func_type(
@@ -108,7 +111,7 @@ fn functions_by_type() {
         ],
         false,
     );
-    let func_names: Vec<&str> = fbt.functions_with_type(&functy).sorted().collect();
+    let func_names: Vec<FunctionName> = fbt.functions_with_type(&functy).sorted().collect();
     assert_eq!(func_names, vec!["four_args"]);
 
     let functy = module.types.func_type(
@@ -122,7 +125,7 @@ fn functions_by_type() {
         ],
         false,
     );
-    let func_names: Vec<&str> = fbt.functions_with_type(&functy).sorted().collect();
+    let func_names: Vec<FunctionName> = fbt.functions_with_type(&functy).sorted().collect();
     assert_eq!(func_names, vec!["five_args"]);
 
     let functy = module.types.func_type(
@@ -130,7 +133,7 @@ fn functions_by_type() {
         vec![module.types.i8(), module.types.i8()],
         false,
     );
-    let func_names: Vec<&str> = fbt.functions_with_type(&functy).sorted().collect();
+    let func_names: Vec<FunctionName> = fbt.functions_with_type(&functy).sorted().collect();
     assert_eq!(func_names, vec!["int8t"]);
 
     let functy = module.types.func_type(
@@ -138,7 +141,7 @@ fn functions_by_type() {
         vec![module.types.i16(), module.types.i16()],
         false,
     );
-    let func_names: Vec<&str> = fbt.functions_with_type(&functy).sorted().collect();
+    let func_names: Vec<FunctionName> = fbt.functions_with_type(&functy).sorted().collect();
     assert_eq!(func_names, vec!["int16t"]);
 
     let functy = module.types.func_type(
@@ -146,7 +149,7 @@ fn functions_by_type() {
         vec![module.types.i64(), module.types.i64()],
         false,
     );
-    let func_names: Vec<&str> = fbt.functions_with_type(&functy).sorted().collect();
+    let func_names: Vec<FunctionName> = fbt.functions_with_type(&functy).sorted().collect();
     assert_eq!(func_names, vec!["int64t"]);
 
     let functy = module.types.func_type(
@@ -159,10 +162,34 @@ fn functions_by_type() {
         ],
         false,
     );
-    let func_names: Vec<&str> = fbt.functions_with_type(&functy).sorted().collect();
+    let func_names: Vec<FunctionName> = fbt.functions_with_type(&functy).sorted().collect();
     assert_eq!(func_names, vec!["mixed_bitwidths"]);
 }
 
+#[test]
+fn structural_hashes() {
+    init_logging();
+    let module = Module::from_bc_path(BASIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let hash = |func_name: &str| {
+        analysis
+            .fn_analysis(func_name)
+            .control_flow_graph()
+            .structural_hash()
+    };
+
+    // functions with the same CFG shape have the same hash
+    assert_eq!(hash("no_args_zero"), hash("one_arg"));
+    assert_eq!(hash("conditional_true"), hash("conditional_false"));
+    // functions with different shapes don't
+    assert_ne!(hash("one_arg"), hash("conditional_true"));
+    assert_ne!(hash("conditional_true"), hash("conditional_nozero"));
+    assert_ne!(hash("conditional_true"), hash("has_switch"));
+    // the hash is stable
+    assert_eq!(hash("one_arg"), 15618300277385413526);
+}
+
 #[test]
 fn trivial_cfgs() {
     init_logging();
@@ -191,6 +218,9 @@ fn trivial_cfgs() {
         assert_eq!(cfg.preds(entry).count(), 0);
         let succs = cfg.succs(entry).collect::<Vec<_>>();
         assert_eq!(succs, vec![CFGNode::Return]);
+        assert_eq!(cfg.blocks().collect::<Vec<_>>(), vec![entry]);
+        assert_eq!(cfg.num_blocks(), 1);
+        assert_eq!(cfg.num_edges(), 1);
     }
 }
 
@@ -363,6 +393,14 @@ fn conditional_nozero_cfg() {
     );
     let bb14_succs: Vec<CFGNode> = cfg.succs(&bb14_name).sorted().collect();
     assert_eq!(bb14_succs, vec![CFGNode::Return]);
+
+    let blocks: Vec<&Name> = cfg.blocks().collect();
+    assert_eq!(
+        blocks,
+        vec![&bb2_name, &bb4_name, &bb6_name, &bb8_name, &bb10_name, &bb12_name, &bb14_name]
+    );
+    assert_eq!(cfg.num_blocks(), 7);
+    assert_eq!(cfg.num_edges(), 10);
 }
 
 #[test]
@@ -450,6 +488,162 @@ fn has_switch_cfg() {
     assert_eq!(bb14_succs, vec![CFGNode::Return]);
 }
 
+#[test]
+fn labeled_cfg_edges() {
+    init_logging();
+    let module = Module::from_bc_path(BASIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+
+    let bb2_name = Name::from(2);
+    let bb4_name = Name::from(4);
+    let bb8_name = Name::from(8);
+    let bb12_name = Name::from(12);
+    let cfg = analysis
+        .fn_analysis("conditional_true")
+        .control_flow_graph();
+    assert_eq!(
+        cfg.edge_kinds(&bb2_name, CFGNode::Block(&bb4_name)),
+        &[CFGEdgeKind::CondBr(true)]
+    );
+    assert_eq!(
+        cfg.edge_kinds(&bb2_name, CFGNode::Block(&bb8_name)),
+        &[CFGEdgeKind::CondBr(false)]
+    );
+    assert_eq!(
+        cfg.edge_kinds(&bb4_name, CFGNode::Block(&bb12_name)),
+        &[CFGEdgeKind::Unconditional]
+    );
+    assert_eq!(
+        cfg.edge_kinds(&bb12_name, CFGNode::Return),
+        &[CFGEdgeKind::Return]
+    );
+    assert!(cfg
+        .edge_kinds(&bb4_name, CFGNode::Block(&bb8_name))
+        .is_empty());
+
+    let bb7_name = Name::from(7);
+    let bb14_name = Name::from(14);
+    let cfg = analysis.fn_analysis("has_switch").control_flow_graph();
+    match cfg.edge_kinds(&bb2_name, CFGNode::Block(&bb7_name)) {
+        [CFGEdgeKind::SwitchCase(value)] => match value.as_ref() {
+            Constant::Int { value, .. } => assert_eq!(*value, 3),
+            c => panic!("Expected an integer constant, got {:?}", c),
+        },
+        kinds => panic!("Expected a single case, got {:?}", kinds),
+    }
+    assert_eq!(
+        cfg.edge_kinds(&bb2_name, CFGNode::Block(&bb12_name)),
+        &[CFGEdgeKind::SwitchDefault]
+    );
+    // one successor per outcome: six cases and the default
+    assert_eq!(cfg.labeled_succs(&bb2_name).count(), 7);
+    assert_eq!(cfg.succs(&bb2_name).count(), 7);
+    assert!(cfg
+        .labeled_succs(&bb2_name)
+        .any(|(succ, _)| succ == CFGNode::Block(&bb14_name)));
+}
+
+#[test]
+fn cfg_edge_weights() {
+    init_logging();
+    let module = Module::from_bc_path(BASIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+
+    let bb2_name = Name::from(2);
+    let bb4_name = Name::from(4);
+    let bb8_name = Name::from(8);
+    let bb12_name = Name::from(12);
+    let cfg = analysis
+        .fn_analysis("conditional_true")
+        .control_flow_graph();
+    assert_eq!(cfg.edge_weight(&bb2_name, CFGNode::Block(&bb4_name)), None);
+
+    // weights of edges which aren't in the CFG are ignored
+    let weighted = cfg.with_edge_weights([
+        (&bb2_name, CFGNode::Block(&bb4_name), 2000),
+        (&bb2_name, CFGNode::Block(&bb8_name), 1),
+        (&bb4_name, CFGNode::Block(&bb8_name), 7),
+    ]);
+    assert_eq!(
+        weighted.edge_weight(&bb2_name, CFGNode::Block(&bb4_name)),
+        Some(2000)
+    );
+    assert_eq!(
+        weighted.edge_weight(&bb2_name, CFGNode::Block(&bb8_name)),
+        Some(1)
+    );
+    assert_eq!(
+        weighted.edge_weight(&bb4_name, CFGNode::Block(&bb12_name)),
+        None
+    );
+    assert_eq!(
+        weighted.edge_weight(&bb4_name, CFGNode::Block(&bb8_name)),
+        None
+    );
+    // the original CFG is unchanged
+    assert_eq!(cfg.edge_weight(&bb2_name, CFGNode::Block(&bb4_name)), None);
+
+    // removing an edge removes its weight, but keeps the others
+    let pruned = weighted.without_edges([(&bb2_name, CFGNode::Block(&bb8_name))]);
+    assert_eq!(
+        pruned.edge_weight(&bb2_name, CFGNode::Block(&bb4_name)),
+        Some(2000)
+    );
+    assert_eq!(
+        pruned.edge_weight(&bb2_name, CFGNode::Block(&bb8_name)),
+        None
+    );
+}
+
+#[test]
+fn conditional_nozero_regions() {
+    init_logging();
+    let module = Module::from_bc_path(BASIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let regions = analysis.fn_analysis("conditional_nozero").region_info();
+
+    // CFG: see conditional_nozero_cfg()
+
+    let bb2_name = Name::from(2);
+    let bb4_name = Name::from(4);
+    let bb8_name = Name::from(8);
+    let bb10_name = Name::from(10);
+    let bb14_name = Name::from(14);
+
+    let top = regions.top_level_region();
+    assert!(top.is_top_level());
+    assert_eq!(top.entry, &bb2_name);
+    assert_eq!(top.exit, CFGNode::Return);
+    assert_eq!(top.num_blocks(), 7);
+    assert_eq!(regions.regions().count(), 4);
+
+    // each conditional is a region ending at %14, nested in the previous one
+    let region = regions.region_of(&bb10_name).unwrap();
+    assert_eq!(region.entry, &bb8_name);
+    assert_eq!(region.exit, CFGNode::Block(&bb14_name));
+    assert_eq!(region.num_blocks(), 3);
+    assert!(!region.contains(&bb14_name));
+    assert_eq!(regions.depth(region), 3);
+    let parent = regions.parent(region).unwrap();
+    assert_eq!(parent.entry, &bb4_name);
+    assert_eq!(parent.exit, CFGNode::Block(&bb14_name));
+    let grandparent = regions.parent(parent).unwrap();
+    assert_eq!(grandparent.entry, &bb2_name);
+    assert_eq!(grandparent.exit, CFGNode::Block(&bb14_name));
+    assert_eq!(regions.parent(grandparent), Some(top));
+    assert_eq!(
+        regions.subregions(top).collect::<Vec<_>>(),
+        vec![grandparent]
+    );
+    assert_eq!(regions.subregions(region).count(), 0);
+
+    // %14 is only in the top-level region
+    assert_eq!(regions.region_of(&bb14_name), Some(top));
+}
+
 #[test]
 fn trivial_domtrees() {
     init_logging();
@@ -635,6 +829,24 @@ fn conditional_true_domtree() {
     );
 }
 
+#[test]
+fn conditional_true_domtree_dot() {
+    init_logging();
+    let module = Module::from_bc_path(BASIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("conditional_true");
+
+    assert_eq!(
+        fn_analysis.dominator_tree().to_dot(),
+        "digraph DominatorTree {\n    \"%2\";\n    \"%4\";\n    \"%8\";\n    \"%12\";\n    \"Return\";\n    \"%2\" -> \"%4\";\n    \"%2\" -> \"%8\";\n    \"%2\" -> \"%12\";\n    \"%12\" -> \"Return\";\n}\n"
+    );
+    assert_eq!(
+        fn_analysis.postdominator_tree().to_dot(),
+        "digraph PostDominatorTree {\n    \"%2\";\n    \"%4\";\n    \"%8\";\n    \"%12\";\n    \"Return\";\n    \"%12\" -> \"%2\";\n    \"%12\" -> \"%4\";\n    \"%12\" -> \"%8\";\n    \"Return\" -> \"%12\";\n}\n"
+    );
+}
+
 #[test]
 fn conditional_false_domtree() {
     init_logging();
@@ -1264,3 +1476,364 @@ fn has_switch_cdg() {
     assert_eq!(cdg.is_control_dependent(&bb14_name, &bb2_name), false);
     assert_eq!(cdg.is_control_dependent(&bb4_name, &bb12_name), false);
 }
+
+#[test]
+fn conditional_true_branch_conditions() {
+    init_logging();
+    let module = Module::from_bc_path(BASIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+
+    let bb2_name = Name::from(2);
+    let bb4_name = Name::from(4);
+    let bb8_name = Name::from(8);
+    let bb12_name = Name::from(12);
+
+    let conditions = analysis.fn_analysis("conditional_true").branch_conditions();
+
+    let bb4_conditions: Vec<&ControlCondition> =
+        conditions.controlling_conditions(&bb4_name).collect();
+    assert_eq!(bb4_conditions.len(), 1);
+    assert_eq!(bb4_conditions[0].block, &bb2_name);
+    assert_eq!(bb4_conditions[0].outcomes, vec![BranchOutcome::True]);
+    match &bb4_conditions[0].condition {
+        BranchCondition::Bool {
+            value: Operand::LocalOperand { name, .. },
+            icmp: Some(icmp),
+        } => {
+            assert_eq!(name, &Name::from(3));
+            assert_eq!(icmp.predicate, IntPredicate::SGT);
+            match (icmp.operand0, icmp.operand1) {
+                (
+                    Operand::LocalOperand { name: op0, .. },
+                    Operand::LocalOperand { name: op1, .. },
+                ) => {
+                    assert_eq!(op0, &Name::from(0));
+                    assert_eq!(op1, &Name::from(1));
+                }
+                ops => panic!("Expected two local operands, got {:?}", ops),
+            }
+        }
+        cond => panic!("Expected a branch on an icmp, got {:?}", cond),
+    }
+
+    let bb8_conditions: Vec<&ControlCondition> =
+        conditions.controlling_conditions(&bb8_name).collect();
+    assert_eq!(bb8_conditions.len(), 1);
+    assert_eq!(bb8_conditions[0].block, &bb2_name);
+    assert_eq!(bb8_conditions[0].outcomes, vec![BranchOutcome::False]);
+
+    assert_eq!(conditions.controlling_conditions(&bb2_name).count(), 0);
+    assert_eq!(conditions.controlling_conditions(&bb12_name).count(), 0);
+    assert_eq!(
+        conditions.branch_condition(&bb4_name),
+        BranchCondition::None
+    );
+}
+
+#[test]
+fn has_switch_branch_conditions() {
+    init_logging();
+    let module = Module::from_bc_path(BASIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+
+    let bb2_name = Name::from(2);
+    let bb7_name = Name::from(7);
+    let bb12_name = Name::from(12);
+
+    let conditions = analysis.fn_analysis("has_switch").branch_conditions();
+
+    match conditions.branch_condition(&bb2_name) {
+        BranchCondition::Switch {
+            value: Operand::LocalOperand { name, .. },
+        } => assert_eq!(name, &Name::from(3)),
+        cond => panic!("Expected a switch on a local, got {:?}", cond),
+    }
+
+    let bb7_conditions: Vec<&ControlCondition> =
+        conditions.controlling_conditions(&bb7_name).collect();
+    assert_eq!(bb7_conditions.len(), 1);
+    assert_eq!(bb7_conditions[0].block, &bb2_name);
+    match bb7_conditions[0].outcomes.as_slice() {
+        [BranchOutcome::Case(value)] => match value.as_ref() {
+            Constant::Int { value, .. } => assert_eq!(*value, 3),
+            c => panic!("Expected an integer constant, got {:?}", c),
+        },
+        outcomes => panic!("Expected a single case, got {:?}", outcomes),
+    }
+
+    let bb12_conditions: Vec<&ControlCondition> =
+        conditions.controlling_conditions(&bb12_name).collect();
+    assert_eq!(bb12_conditions.len(), 1);
+    assert_eq!(bb12_conditions[0].outcomes, vec![BranchOutcome::Default]);
+}
+
+#[test]
+fn def_use_chains() {
+    init_logging();
+    let module = Module::from_bc_path(BASIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("conditional_true");
+    let def_use = fn_analysis.def_use_chains();
+
+    let bb2_name = Name::from(2);
+    let bb4_name = Name::from(4);
+    let bb8_name = Name::from(8);
+    let bb12_name = Name::from(12);
+    let loc = |block, index| InstructionLocation { block, index };
+
+    // the parameters are used in every block but the last
+    assert_eq!(
+        def_use.uses(&Name::from(0)),
+        &[loc(&bb2_name, 0), loc(&bb4_name, 0), loc(&bb8_name, 0)]
+    );
+    assert_eq!(
+        def_use.uses(&Name::from(1)),
+        &[loc(&bb2_name, 0), loc(&bb4_name, 1), loc(&bb8_name, 0)]
+    );
+    // used by the terminator of %2
+    assert_eq!(def_use.uses(&Name::from(3)), &[loc(&bb2_name, 1)]);
+    // used by the phi in %12
+    assert_eq!(def_use.uses(&Name::from(7)), &[loc(&bb12_name, 0)]);
+    assert_eq!(def_use.uses(&Name::from(11)), &[loc(&bb12_name, 0)]);
+    assert_eq!(def_use.num_uses(&Name::from(13)), 1);
+    let users: Vec<_> = def_use.users(&Name::from(13)).collect();
+    match users.as_slice() {
+        [Either::Right(Terminator::Ret(_))] => {}
+        users => panic!("Expected a single ret, got {:?}", users),
+    }
+    match def_use.instruction(loc(&bb4_name, 2)) {
+        Either::Left(Instruction::Mul(mul)) => assert_eq!(mul.dest, Name::from(7)),
+        inst => panic!("Expected a mul, got {:?}", inst),
+    }
+    assert!(def_use.is_used(&Name::from(5)));
+    assert!(!def_use.is_used(&Name::from(99)));
+    assert_eq!(def_use.uses(&Name::from(99)), &[]);
+
+    // use-def: the inverse lookup
+    assert_eq!(
+        def_use.definition(&Name::from(0)),
+        Some(Definition::Parameter(0))
+    );
+    assert_eq!(
+        def_use.definition(&Name::from(1)),
+        Some(Definition::Parameter(1))
+    );
+    assert_eq!(
+        def_use.definition(&Name::from(3)),
+        Some(Definition::Instruction(loc(&bb2_name, 0)))
+    );
+    assert_eq!(
+        def_use.definition(&Name::from(13)),
+        Some(Definition::Instruction(loc(&bb12_name, 0)))
+    );
+    assert!(def_use.defining_instruction(&Name::from(1)).is_none());
+    match def_use.defining_instruction(&Name::from(10)) {
+        Some(Either::Left(Instruction::SRem(srem))) => assert_eq!(srem.dest, Name::from(10)),
+        inst => panic!("Expected an srem, got {:?}", inst),
+    }
+    // block names and unknown names aren't values
+    assert_eq!(def_use.definition(&bb4_name), None);
+    assert_eq!(def_use.definition(&Name::from(99)), None);
+    // every use of every value is dominated by its definition, except uses by
+    // phis, which are in the corresponding predecessor
+    let domtree = fn_analysis.dominator_tree();
+    for bb in &module
+        .functions
+        .iter()
+        .find(|f| f.name == "conditional_true")
+        .unwrap()
+        .basic_blocks
+    {
+        for inst in &bb.instrs {
+            let Some(dest) = inst.try_get_result() else {
+                continue;
+            };
+            let Some(Definition::Instruction(def)) = def_use.definition(dest) else {
+                panic!("no definition for {}", dest);
+            };
+            assert_eq!(def.block, &bb.name);
+            for use_ in def_use.uses(dest) {
+                if !matches!(
+                    def_use.instruction(*use_),
+                    Either::Left(Instruction::Phi(_))
+                ) {
+                    assert!(
+                        domtree.dominates(CFGNode::Block(def.block), CFGNode::Block(use_.block))
+                    );
+                }
+            }
+        }
+    }
+
+    let fn_analysis = analysis.fn_analysis("has_switch");
+    let def_use = fn_analysis.def_use_chains();
+    // used by the switch, and in the default destination
+    assert_eq!(
+        def_use.uses(&Name::from(3)),
+        &[loc(&bb2_name, 1), loc(&bb12_name, 0)]
+    );
+    // `mul %1, %0` uses each parameter once
+    let bb7_name = Name::from(7);
+    assert_eq!(
+        def_use.uses(&Name::from(1)),
+        &[loc(&bb2_name, 0), loc(&bb7_name, 0)]
+    );
+    // a block label is not a value
+    assert!(!def_use.is_used(&Name::from(14)));
+}
+
+#[test]
+fn conditional_nozero_slices() {
+    init_logging();
+    let module = Module::from_bc_path(BASIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("conditional_nozero");
+
+    let bb2_name = Name::from(2);
+    let bb4_name = Name::from(4);
+    let bb6_name = Name::from(6);
+    let bb8_name = Name::from(8);
+    let bb10_name = Name::from(10);
+    let bb12_name = Name::from(12);
+    let bb14_name = Name::from(14);
+    let loc = |block, index| InstructionLocation { block, index };
+
+    // the return value depends on every incoming value of the phi, and on
+    // every conditional branch deciding which of them it takes; but not on the
+    // unconditional branches into %14
+    let slice = fn_analysis.backward_slice(SliceCriterion::Instruction(loc(&bb14_name, 1)));
+    assert_eq!(
+        slice.instructions(),
+        &[
+            loc(&bb2_name, 0),
+            loc(&bb2_name, 1),
+            loc(&bb4_name, 0),
+            loc(&bb4_name, 1),
+            loc(&bb6_name, 0),
+            loc(&bb8_name, 0),
+            loc(&bb8_name, 1),
+            loc(&bb10_name, 0),
+            loc(&bb12_name, 0),
+            loc(&bb14_name, 0),
+            loc(&bb14_name, 1),
+        ]
+    );
+    assert_eq!(
+        slice.blocks(),
+        &[&bb2_name, &bb4_name, &bb6_name, &bb8_name, &bb10_name, &bb12_name, &bb14_name]
+    );
+    assert!(!slice.contains(loc(&bb6_name, 1)));
+
+    // %9 depends on the branches which lead to %8
+    let slice = fn_analysis.backward_slice(SliceCriterion::Value(&Name::from(9)));
+    assert_eq!(
+        slice.instructions(),
+        &[
+            loc(&bb2_name, 0),
+            loc(&bb2_name, 1),
+            loc(&bb4_name, 0),
+            loc(&bb4_name, 1),
+            loc(&bb8_name, 0),
+        ]
+    );
+    assert!(!slice.contains_block(&bb6_name));
+
+    // a parameter depends on nothing
+    let param0 = Name::from(0);
+    assert!(fn_analysis
+        .backward_slice(SliceCriterion::Value(&param0))
+        .is_empty());
+
+    // everything but the entry block depends on %1, since %1 decides the
+    // branch in %4
+    let slice = fn_analysis.forward_slice(SliceCriterion::Value(&Name::from(1)));
+    assert_eq!(slice.len(), 12);
+    assert!(!slice.contains_block(&bb2_name));
+    assert!(slice.contains(loc(&bb10_name, 0)));
+    assert!(slice.contains(loc(&bb14_name, 1)));
+}
+
+#[test]
+fn conditional_nozero_liveness() {
+    init_logging();
+    let module = Module::from_bc_path(BASIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("conditional_nozero");
+    let liveness = fn_analysis.liveness();
+
+    let bb2_name = Name::from(2);
+    let bb4_name = Name::from(4);
+    let bb6_name = Name::from(6);
+    let bb12_name = Name::from(12);
+    let bb14_name = Name::from(14);
+    let param0 = Name::from(0);
+    let param1 = Name::from(1);
+
+    assert_eq!(
+        liveness.live_in(&bb2_name).sorted().collect::<Vec<_>>(),
+        vec![&param0, &param1]
+    );
+    assert_eq!(
+        liveness.live_out(&bb4_name).sorted().collect::<Vec<_>>(),
+        vec![&param0, &param1]
+    );
+    // %7 is used by the phi in %14, so it's live-out of %6 but not live-in to
+    // %14; while the phi's result is live-in to %14
+    assert_eq!(
+        liveness.live_out(&bb6_name).collect::<Vec<_>>(),
+        vec![&Name::from(7)]
+    );
+    assert_eq!(
+        liveness.live_in(&bb14_name).collect::<Vec<_>>(),
+        vec![&Name::from(15)]
+    );
+    assert_eq!(liveness.live_out(&bb14_name).count(), 0);
+    assert!(liveness.is_live_in(&param1, &bb6_name));
+    assert!(!liveness.is_live_in(&param0, &bb6_name));
+    assert!(!liveness.is_live_out(&Name::from(5), &bb4_name));
+
+    // %5 is live only between its definition and the branch using it
+    assert!(!liveness.is_live_at(&Name::from(5), &bb4_name, 0));
+    assert!(liveness.is_live_at(&Name::from(5), &bb4_name, 1));
+    assert!(liveness.is_live_at(&Name::from(7), &bb6_name, 1));
+    assert!(!liveness.is_live_at(&Name::from(7), &bb14_name, 0));
+    assert!(liveness.is_live_at(&Name::from(15), &bb14_name, 0));
+    assert!(liveness.is_live_at(&Name::from(15), &bb14_name, 1));
+    assert_eq!(
+        liveness
+            .live_at(&bb12_name, 0)
+            .into_iter()
+            .sorted()
+            .collect::<Vec<_>>(),
+        vec![&param0, &param1]
+    );
+    assert_eq!(
+        liveness
+            .live_at(&bb12_name, 1)
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec![&Name::from(13)]
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serializable_graphs() {
+    fn assert_serialize<T: serde::Serialize>(_: &T) {}
+
+    init_logging();
+    let module = Module::from_bc_path(BASIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    assert_serialize(&*analysis.call_graph());
+    let fn_analysis = analysis.fn_analysis("has_switch");
+    assert_serialize(&*fn_analysis.control_flow_graph());
+    assert_serialize(&*fn_analysis.dominator_tree());
+    assert_serialize(&*fn_analysis.postdominator_tree());
+    assert_serialize(&*fn_analysis.control_dependence_graph());
+}
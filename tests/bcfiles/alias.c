@@ -0,0 +1,32 @@
+// Calls through a GlobalAlias and a GlobalIFunc
+
+int cpu_has_fast_impl;
+
+int real(int x) {
+    return x * 2;
+}
+
+int aliased(int x) __attribute__((alias("real")));
+int aliased_again(int x) __attribute__((alias("aliased")));
+
+static int impl_generic(int x) {
+    return x + 1;
+}
+
+static int impl_fast(int x) {
+    return x + 2;
+}
+
+static int (*resolve_impl(void))(int) {
+    return cpu_has_fast_impl ? impl_fast : impl_generic;
+}
+
+int impl(int x) __attribute__((ifunc("resolve_impl")));
+
+int calls_alias(int x) {
+    return aliased(x) + aliased_again(x);
+}
+
+int calls_ifunc(int x) {
+    return impl(x);
+}
@@ -0,0 +1,65 @@
+; ModuleID = 'alias.c'
+source_filename = "alias.c"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-pc-linux-gnu"
+
+@cpu_has_fast_impl = dso_local local_unnamed_addr global i32 0, align 4
+
+@aliased = dso_local alias i32 (i32), i32 (i32)* @real
+@aliased_again = dso_local alias i32 (i32), i32 (i32)* @aliased
+@impl = ifunc i32 (i32), i32 (i32)* ()* @resolve_impl
+
+; Function Attrs: norecurse nounwind readnone uwtable
+define dso_local i32 @real(i32) #0 {
+  %2 = shl nsw i32 %0, 1
+  ret i32 %2
+}
+
+; Function Attrs: nounwind uwtable
+define dso_local i32 @calls_alias(i32) local_unnamed_addr #1 {
+  %2 = tail call i32 @aliased(i32 %0) #3
+  %3 = tail call i32 @aliased_again(i32 %0) #3
+  %4 = add nsw i32 %3, %2
+  ret i32 %4
+}
+
+; Function Attrs: nounwind uwtable
+define dso_local i32 @calls_ifunc(i32) local_unnamed_addr #1 {
+  %2 = tail call i32 @impl(i32 %0) #3
+  ret i32 %2
+}
+
+; Function Attrs: norecurse nounwind readonly uwtable
+define internal i32 (i32)* @resolve_impl() #2 {
+  %1 = load i32, i32* @cpu_has_fast_impl, align 4, !tbaa !2
+  %2 = icmp eq i32 %1, 0
+  %3 = select i1 %2, i32 (i32)* @impl_generic, i32 (i32)* @impl_fast
+  ret i32 (i32)* %3
+}
+
+; Function Attrs: norecurse nounwind readnone uwtable
+define internal i32 @impl_generic(i32) #0 {
+  %2 = add nsw i32 %0, 1
+  ret i32 %2
+}
+
+; Function Attrs: norecurse nounwind readnone uwtable
+define internal i32 @impl_fast(i32) #0 {
+  %2 = add nsw i32 %0, 2
+  ret i32 %2
+}
+
+attributes #0 = { norecurse nounwind readnone uwtable "correctly-rounded-divide-sqrt-fp-math"="false" "disable-tail-calls"="false" "less-precise-fpmad"="false" "min-legal-vector-width"="0" "no-frame-pointer-elim"="false" "no-infs-fp-math"="false" "no-jump-tables"="false" "no-nans-fp-math"="false" "no-signed-zeros-fp-math"="false" "no-trapping-math"="false" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "unsafe-fp-math"="false" "use-soft-float"="false" }
+attributes #1 = { nounwind uwtable "correctly-rounded-divide-sqrt-fp-math"="false" "disable-tail-calls"="false" "less-precise-fpmad"="false" "min-legal-vector-width"="0" "no-frame-pointer-elim"="false" "no-infs-fp-math"="false" "no-jump-tables"="false" "no-nans-fp-math"="false" "no-signed-zeros-fp-math"="false" "no-trapping-math"="false" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "unsafe-fp-math"="false" "use-soft-float"="false" }
+attributes #2 = { norecurse nounwind readonly uwtable "correctly-rounded-divide-sqrt-fp-math"="false" "disable-tail-calls"="false" "less-precise-fpmad"="false" "min-legal-vector-width"="0" "no-frame-pointer-elim"="false" "no-infs-fp-math"="false" "no-jump-tables"="false" "no-nans-fp-math"="false" "no-signed-zeros-fp-math"="false" "no-trapping-math"="false" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "unsafe-fp-math"="false" "use-soft-float"="false" }
+attributes #3 = { nounwind }
+
+!llvm.module.flags = !{!0}
+!llvm.ident = !{!1}
+
+!0 = !{i32 1, !"wchar_size", i32 4}
+!1 = !{!"clang version 9.0.1-12 "}
+!2 = !{!3, !3, i64 0}
+!3 = !{!"int", !4, i64 0}
+!4 = !{!"omnipotent char", !5, i64 0}
+!5 = !{!"Simple C/C++ TBAA"}
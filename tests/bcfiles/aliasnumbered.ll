@@ -0,0 +1,19 @@
+; Calls through an alias of a function with a numbered name, and a call of a
+; global which isn't a function
+
+@data = global i32 0
+@named_alias = alias i32 (i32), i32 (i32)* @0
+
+define internal i32 @0(i32 %0) {
+  ret i32 %0
+}
+
+define i32 @calls_numbered_alias(i32 %0) {
+  %2 = call i32 @named_alias(i32 %0)
+  ret i32 %2
+}
+
+define i32 @calls_data(i32 %0) {
+  %2 = call i32 bitcast (i32* @data to i32 (i32)*)(i32 %0)
+  ret i32 %2
+}
@@ -0,0 +1,17 @@
+__asm__(".globl module_asm_sym\n"
+        "module_asm_sym:\n"
+        "  ret");
+
+int add_asm(int a, int b) {
+  int r;
+  __asm__("addl %2, %0" : "=r"(r) : "0"(a), "r"(b) : "cc");
+  return r;
+}
+
+void barrier(void) {
+  __asm__ volatile("" ::: "memory");
+}
+
+int no_asm(int x) {
+  return x + 1;
+}
@@ -0,0 +1,30 @@
+; ModuleID = 'asm.c'
+source_filename = "asm.c"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-pc-linux-gnu"
+
+module asm ".globl module_asm_sym"
+module asm "module_asm_sym:"
+module asm "  ret"
+
+; Function Attrs: nounwind uwtable
+define dso_local i32 @add_asm(i32 %0, i32 %1) local_unnamed_addr #0 {
+  %3 = tail call i32 asm "addl $2, $0", "=r,0,r,~{cc},~{dirflag},~{fpsr},~{flags}"(i32 %0, i32 %1) #1
+  ret i32 %3
+}
+
+; Function Attrs: nounwind uwtable
+define dso_local void @barrier() local_unnamed_addr #0 {
+  tail call void asm sideeffect "", "~{memory},~{dirflag},~{fpsr},~{flags}"() #1
+  ret void
+}
+
+; Function Attrs: norecurse nounwind readnone uwtable
+define dso_local i32 @no_asm(i32 %0) local_unnamed_addr #2 {
+  %2 = add nsw i32 %0, 1
+  ret i32 %2
+}
+
+attributes #0 = { nounwind uwtable }
+attributes #1 = { nounwind }
+attributes #2 = { norecurse nounwind readnone uwtable }
@@ -0,0 +1,6 @@
+int unprototyped();
+int prototyped(int x);
+
+int calls_unprototyped(int x) {
+    return unprototyped(x, 2) + prototyped(x);
+}
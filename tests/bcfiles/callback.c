@@ -0,0 +1,26 @@
+// Functions which are only called as callbacks
+
+#include <stdlib.h>
+
+static int compare_ints(const void *a, const void *b) {
+    int x = *(const int *)a;
+    int y = *(const int *)b;
+    return (x > y) - (x < y);
+}
+
+void sort_ints(int *arr, size_t n) {
+    qsort(arr, n, sizeof(int), compare_ints);
+}
+
+typedef void (*handler_t)(int);
+void register_handler(int event, handler_t handler);
+
+int last_event;
+
+static void on_event(int event) {
+    last_event = event;
+}
+
+void setup(void) {
+    register_handler(1, on_event);
+}
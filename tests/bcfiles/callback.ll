@@ -0,0 +1,58 @@
+; ModuleID = 'callback.c'
+source_filename = "callback.c"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-pc-linux-gnu"
+
+@last_event = dso_local local_unnamed_addr global i32 0, align 4
+
+; Function Attrs: nounwind uwtable
+define dso_local void @sort_ints(i32*, i64) local_unnamed_addr #0 {
+  %3 = bitcast i32* %0 to i8*
+  tail call void @qsort(i8* %3, i64 %1, i64 4, i32 (i8*, i8*)* nonnull @compare_ints) #3
+  ret void
+}
+
+declare dso_local void @qsort(i8*, i64, i64, i32 (i8*, i8*)* nocapture) local_unnamed_addr #1
+
+; Function Attrs: norecurse nounwind readonly uwtable
+define internal i32 @compare_ints(i8* nocapture readonly, i8* nocapture readonly) #2 {
+  %3 = bitcast i8* %0 to i32*
+  %4 = load i32, i32* %3, align 4, !tbaa !2
+  %5 = bitcast i8* %1 to i32*
+  %6 = load i32, i32* %5, align 4, !tbaa !2
+  %7 = icmp sgt i32 %4, %6
+  %8 = zext i1 %7 to i32
+  %9 = icmp slt i32 %4, %6
+  %10 = zext i1 %9 to i32
+  %11 = sub nsw i32 %8, %10
+  ret i32 %11
+}
+
+; Function Attrs: nounwind uwtable
+define dso_local void @setup() local_unnamed_addr #0 {
+  tail call void @register_handler(i32 1, void (i32)* nonnull @on_event) #3
+  ret void
+}
+
+declare dso_local void @register_handler(i32, void (i32)*) local_unnamed_addr #1
+
+; Function Attrs: norecurse nounwind uwtable
+define internal void @on_event(i32) #2 {
+  store i32 %0, i32* @last_event, align 4, !tbaa !2
+  ret void
+}
+
+attributes #0 = { nounwind uwtable "correctly-rounded-divide-sqrt-fp-math"="false" "disable-tail-calls"="false" "less-precise-fpmad"="false" "min-legal-vector-width"="0" "no-frame-pointer-elim"="false" "no-infs-fp-math"="false" "no-jump-tables"="false" "no-nans-fp-math"="false" "no-signed-zeros-fp-math"="false" "no-trapping-math"="false" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "unsafe-fp-math"="false" "use-soft-float"="false" }
+attributes #1 = { "correctly-rounded-divide-sqrt-fp-math"="false" "disable-tail-calls"="false" "less-precise-fpmad"="false" "no-frame-pointer-elim"="false" "no-infs-fp-math"="false" "no-nans-fp-math"="false" "no-signed-zeros-fp-math"="false" "no-trapping-math"="false" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "unsafe-fp-math"="false" "use-soft-float"="false" }
+attributes #2 = { norecurse nounwind uwtable "correctly-rounded-divide-sqrt-fp-math"="false" "disable-tail-calls"="false" "less-precise-fpmad"="false" "min-legal-vector-width"="0" "no-frame-pointer-elim"="false" "no-infs-fp-math"="false" "no-jump-tables"="false" "no-nans-fp-math"="false" "no-signed-zeros-fp-math"="false" "no-trapping-math"="false" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "unsafe-fp-math"="false" "use-soft-float"="false" }
+attributes #3 = { nounwind }
+
+!llvm.module.flags = !{!0}
+!llvm.ident = !{!1}
+
+!0 = !{i32 1, !"wchar_size", i32 4}
+!1 = !{!"clang version 9.0.1-12 "}
+!2 = !{!3, !3, i64 0}
+!3 = !{!"int", !4, i64 0}
+!4 = !{!"omnipotent char", !5, i64 0}
+!5 = !{!"Simple C/C++ TBAA"}
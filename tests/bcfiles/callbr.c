@@ -0,0 +1,12 @@
+int helper(int x);
+
+int asm_goto(int x) {
+    asm goto("testl %0, %0; jz %l1" : : "r"(x) : : zero);
+    return helper(x);
+zero:
+    return helper(0) + 1;
+}
+
+int calls_asm_goto(int x) {
+    return asm_goto(x) + 3;
+}
@@ -0,0 +1,42 @@
+; ModuleID = 'callbr.c'
+source_filename = "callbr.c"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-pc-linux-gnu"
+
+; Function Attrs: nounwind uwtable
+define dso_local i32 @asm_goto(i32 %0) local_unnamed_addr #0 {
+  callbr void asm sideeffect "testl $0, $0; jz ${1:l}", "r,X,~{dirflag},~{fpsr},~{flags}"(i32 %0, i8* blockaddress(@asm_goto, %4)) #2
+          to label %2 [label %4]
+
+2:                                                ; preds = %1
+  %3 = tail call i32 @helper(i32 %0) #2
+  br label %7
+
+4:                                                ; preds = %1
+  %5 = tail call i32 @helper(i32 0) #2
+  %6 = add nsw i32 %5, 1
+  br label %7
+
+7:                                                ; preds = %4, %2
+  %8 = phi i32 [ %3, %2 ], [ %6, %4 ]
+  ret i32 %8
+}
+
+declare dso_local i32 @helper(i32) local_unnamed_addr #1
+
+; Function Attrs: nounwind uwtable
+define dso_local i32 @calls_asm_goto(i32 %0) local_unnamed_addr #0 {
+  %2 = tail call i32 @asm_goto(i32 %0)
+  %3 = add nsw i32 %2, 3
+  ret i32 %3
+}
+
+attributes #0 = { nounwind uwtable "correctly-rounded-divide-sqrt-fp-math"="false" "disable-tail-calls"="false" "less-precise-fpmad"="false" "min-legal-vector-width"="0" "no-frame-pointer-elim"="false" "no-infs-fp-math"="false" "no-jump-tables"="false" "no-nans-fp-math"="false" "no-signed-zeros-fp-math"="false" "no-trapping-math"="false" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "unsafe-fp-math"="false" "use-soft-float"="false" }
+attributes #1 = { "correctly-rounded-divide-sqrt-fp-math"="false" "disable-tail-calls"="false" "less-precise-fpmad"="false" "no-frame-pointer-elim"="false" "no-infs-fp-math"="false" "no-nans-fp-math"="false" "no-signed-zeros-fp-math"="false" "no-trapping-math"="false" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "unsafe-fp-math"="false" "use-soft-float"="false" }
+attributes #2 = { nounwind }
+
+!llvm.module.flags = !{!0}
+!llvm.ident = !{!1}
+
+!0 = !{i32 1, !"wchar_size", i32 4}
+!1 = !{!"clang version 9.0.1-12 "}
@@ -0,0 +1,42 @@
+; Hand-written function for testing branch condition dependencies, parsing
+; an input buffer
+
+@g = global i32 0
+@arr = global [4 x i32] zeroinitializer
+
+; the first two branches depend on %buf, the third on %len and @g, and the
+; loop exit on %mode and @arr
+define i32 @parse(i8* %buf, i32 %len, i32 %mode) {
+entry:
+  %b0 = load i8, i8* %buf
+  %magic = icmp eq i8 %b0, 127
+  br i1 %magic, label %header, label %fail
+
+header:
+  %p1 = getelementptr i8, i8* %buf, i64 1
+  %b1 = load i8, i8* %p1
+  %kind = zext i8 %b1 to i32
+  switch i32 %kind, label %fail [
+    i32 1, label %one
+    i32 2, label %loop
+  ]
+
+one:
+  %limit = load i32, i32* @g
+  %big = icmp sgt i32 %len, %limit
+  br i1 %big, label %fail, label %ok
+
+loop:
+  %i = phi i32 [ 0, %header ], [ %i.next, %loop ]
+  %slot = getelementptr [4 x i32], [4 x i32]* @arr, i32 0, i32 %i
+  %x = load i32, i32* %slot
+  %i.next = add i32 %i, %x
+  %done = icmp sge i32 %i.next, %mode
+  br i1 %done, label %ok, label %loop
+
+ok:
+  ret i32 0
+
+fail:
+  ret i32 1
+}
@@ -0,0 +1,32 @@
+static int counter;
+
+static int __attribute__((noinline)) helper(int x) {
+  return x + 1;
+}
+
+static int __attribute__((noinline)) times_two(int x) {
+  return x * 2;
+}
+
+static int __attribute__((noinline)) times_three(int x) {
+  return x * 3;
+}
+
+static int (*const table[2])(int) = { times_two, times_three };
+
+static void __attribute__((constructor)) init(void) {
+  counter = 1;
+}
+
+int __attribute__((noinline)) exported_helper(int x) {
+  return x - counter;
+}
+
+int exported(int x) {
+  return exported_helper(x);
+}
+
+int main(int argc, char **argv) {
+  (void)argv;
+  return helper(argc) + table[argc & 1](argc);
+}
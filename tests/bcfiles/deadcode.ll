@@ -0,0 +1,76 @@
+; ModuleID = 'deadcode.c'
+source_filename = "deadcode.c"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-pc-linux-gnu"
+
+@counter = internal unnamed_addr global i32 0, align 4
+@table = internal unnamed_addr constant [2 x i32 (i32)*] [i32 (i32)* @times_two, i32 (i32)* @times_three], align 16
+@llvm.global_ctors = appending global [1 x { i32, void ()*, i8* }] [{ i32, void ()*, i8* } { i32 65535, void ()* @init, i8* null }]
+
+; Function Attrs: norecurse nounwind uwtable
+define internal void @init() #1 section ".text.startup" {
+  store i32 1, i32* @counter, align 4, !tbaa !2
+  ret void
+}
+
+; Function Attrs: noinline norecurse nounwind readonly uwtable
+define dso_local i32 @exported_helper(i32) local_unnamed_addr #2 {
+  %2 = load i32, i32* @counter, align 4, !tbaa !2
+  %3 = sub nsw i32 %0, %2
+  ret i32 %3
+}
+
+; Function Attrs: nounwind readonly uwtable
+define dso_local i32 @exported(i32) local_unnamed_addr #3 {
+  %2 = tail call i32 @exported_helper(i32 %0)
+  ret i32 %2
+}
+
+; Function Attrs: nounwind uwtable
+define dso_local i32 @main(i32, i8** nocapture readnone) local_unnamed_addr #4 {
+  %3 = tail call fastcc i32 @helper(i32 %0)
+  %4 = and i32 %0, 1
+  %5 = zext i32 %4 to i64
+  %6 = getelementptr inbounds [2 x i32 (i32)*], [2 x i32 (i32)*]* @table, i64 0, i64 %5
+  %7 = load i32 (i32)*, i32 (i32)** %6, align 8, !tbaa !6
+  %8 = tail call i32 %7(i32 %0) #5
+  %9 = add nsw i32 %8, %3
+  ret i32 %9
+}
+
+; Function Attrs: noinline norecurse nounwind readnone uwtable
+define internal fastcc i32 @helper(i32) unnamed_addr #0 {
+  %2 = add nsw i32 %0, 1
+  ret i32 %2
+}
+
+; Function Attrs: noinline norecurse nounwind readnone uwtable
+define internal i32 @times_two(i32) #0 {
+  %2 = shl nsw i32 %0, 1
+  ret i32 %2
+}
+
+; Function Attrs: noinline norecurse nounwind readnone uwtable
+define internal i32 @times_three(i32) #0 {
+  %2 = mul nsw i32 %0, 3
+  ret i32 %2
+}
+
+attributes #0 = { noinline norecurse nounwind readnone uwtable "correctly-rounded-divide-sqrt-fp-math"="false" "disable-tail-calls"="false" "less-precise-fpmad"="false" "min-legal-vector-width"="0" "no-frame-pointer-elim"="false" "no-infs-fp-math"="false" "no-jump-tables"="false" "no-nans-fp-math"="false" "no-signed-zeros-fp-math"="false" "no-trapping-math"="false" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "unsafe-fp-math"="false" "use-soft-float"="false" }
+attributes #1 = { norecurse nounwind uwtable "correctly-rounded-divide-sqrt-fp-math"="false" "disable-tail-calls"="false" "less-precise-fpmad"="false" "min-legal-vector-width"="0" "no-frame-pointer-elim"="false" "no-infs-fp-math"="false" "no-jump-tables"="false" "no-nans-fp-math"="false" "no-signed-zeros-fp-math"="false" "no-trapping-math"="false" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "unsafe-fp-math"="false" "use-soft-float"="false" }
+attributes #2 = { noinline norecurse nounwind readonly uwtable "correctly-rounded-divide-sqrt-fp-math"="false" "disable-tail-calls"="false" "less-precise-fpmad"="false" "min-legal-vector-width"="0" "no-frame-pointer-elim"="false" "no-infs-fp-math"="false" "no-jump-tables"="false" "no-nans-fp-math"="false" "no-signed-zeros-fp-math"="false" "no-trapping-math"="false" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "unsafe-fp-math"="false" "use-soft-float"="false" }
+attributes #3 = { nounwind readonly uwtable "correctly-rounded-divide-sqrt-fp-math"="false" "disable-tail-calls"="false" "less-precise-fpmad"="false" "min-legal-vector-width"="0" "no-frame-pointer-elim"="false" "no-infs-fp-math"="false" "no-jump-tables"="false" "no-nans-fp-math"="false" "no-signed-zeros-fp-math"="false" "no-trapping-math"="false" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "unsafe-fp-math"="false" "use-soft-float"="false" }
+attributes #4 = { nounwind uwtable "correctly-rounded-divide-sqrt-fp-math"="false" "disable-tail-calls"="false" "less-precise-fpmad"="false" "min-legal-vector-width"="0" "no-frame-pointer-elim"="false" "no-infs-fp-math"="false" "no-jump-tables"="false" "no-nans-fp-math"="false" "no-signed-zeros-fp-math"="false" "no-trapping-math"="false" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "unsafe-fp-math"="false" "use-soft-float"="false" }
+attributes #5 = { nounwind }
+
+!llvm.module.flags = !{!0}
+!llvm.ident = !{!1}
+
+!0 = !{i32 1, !"wchar_size", i32 4}
+!1 = !{!"clang version 9.0.1-12 "}
+!2 = !{!3, !3, i64 0}
+!3 = !{!"int", !4, i64 0}
+!4 = !{!"omnipotent char", !5, i64 0}
+!5 = !{!"Simple C/C++ TBAA"}
+!6 = !{!7, !7, i64 0}
+!7 = !{!"any pointer", !4, i64 0}
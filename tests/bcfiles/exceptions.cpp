@@ -0,0 +1,47 @@
+struct Error {
+  int code;
+};
+
+struct Guard {
+  ~Guard();
+};
+
+__attribute__((noinline)) void thrower(int x) {
+  if (x > 3) throw Error{x};
+}
+
+__attribute__((noinline)) int propagates(int x) {
+  thrower(x);
+  return x + 1;
+}
+
+__attribute__((noinline)) int catches(int x) {
+  try {
+    return propagates(x);
+  } catch (const Error &e) {
+    return e.code;
+  }
+}
+
+__attribute__((noinline)) int cleans_up(int x) {
+  Guard g;
+  return propagates(x);
+}
+
+__attribute__((noinline)) void catch_all(int x) {
+  try {
+    thrower(x);
+  } catch (...) {
+  }
+}
+
+__attribute__((noinline)) int no_exceptions(int x) noexcept {
+  return x * 2;
+}
+
+int main() {
+  int a = catches(5);
+  int b = cleans_up(a);
+  catch_all(b);
+  return no_exceptions(b);
+}
@@ -0,0 +1,145 @@
+; ModuleID = 'exceptions.cpp'
+source_filename = "exceptions.cpp"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-pc-linux-gnu"
+
+%struct.Guard = type { i8 }
+
+@_ZTVN10__cxxabiv117__class_type_infoE = external global i8*
+@_ZTS5Error = linkonce_odr dso_local constant [7 x i8] c"5Error\00", comdat, align 1
+@_ZTI5Error = linkonce_odr dso_local constant { i8*, i8* } { i8* bitcast (i8** getelementptr inbounds (i8*, i8** @_ZTVN10__cxxabiv117__class_type_infoE, i64 2) to i8*), i8* getelementptr inbounds ([7 x i8], [7 x i8]* @_ZTS5Error, i32 0, i32 0) }, comdat, align 8
+
+$_ZTS5Error = comdat any
+
+$_ZTI5Error = comdat any
+
+; Function Attrs: noinline uwtable
+define dso_local void @_Z7throweri(i32 %0) local_unnamed_addr #0 {
+  %2 = icmp sgt i32 %0, 3
+  br i1 %2, label %3, label %6
+
+3:                                                ; preds = %1
+  %4 = tail call i8* @__cxa_allocate_exception(i64 4) #4
+  %5 = bitcast i8* %4 to i32*
+  store i32 %0, i32* %5, align 16
+  tail call void @__cxa_throw(i8* %4, i8* bitcast ({ i8*, i8* }* @_ZTI5Error to i8*), i8* null) #5
+  unreachable
+
+6:                                                ; preds = %1
+  ret void
+}
+
+declare dso_local i8* @__cxa_allocate_exception(i64) local_unnamed_addr
+
+declare dso_local void @__cxa_throw(i8*, i8*, i8*) local_unnamed_addr
+
+; Function Attrs: noinline uwtable
+define dso_local i32 @_Z10propagatesi(i32 %0) local_unnamed_addr #0 {
+  tail call void @_Z7throweri(i32 %0)
+  %2 = add nsw i32 %0, 1
+  ret i32 %2
+}
+
+; Function Attrs: noinline uwtable
+define dso_local i32 @_Z7catchesi(i32 %0) local_unnamed_addr #0 personality i8* bitcast (i32 (...)* @__gxx_personality_v0 to i8*) {
+  %2 = invoke i32 @_Z10propagatesi(i32 %0)
+          to label %14 unwind label %3
+
+3:                                                ; preds = %1
+  %4 = landingpad { i8*, i32 }
+          catch i8* bitcast ({ i8*, i8* }* @_ZTI5Error to i8*)
+  %5 = extractvalue { i8*, i32 } %4, 1
+  %6 = tail call i32 @llvm.eh.typeid.for(i8* bitcast ({ i8*, i8* }* @_ZTI5Error to i8*)) #4
+  %7 = icmp eq i32 %5, %6
+  br i1 %7, label %8, label %13
+
+8:                                                ; preds = %3
+  %9 = extractvalue { i8*, i32 } %4, 0
+  %10 = tail call i8* @__cxa_begin_catch(i8* %9) #4
+  %11 = bitcast i8* %10 to i32*
+  %12 = load i32, i32* %11, align 4
+  tail call void @__cxa_end_catch() #4
+  br label %14
+
+13:                                               ; preds = %3
+  resume { i8*, i32 } %4
+
+14:                                               ; preds = %1, %8
+  %15 = phi i32 [ %2, %1 ], [ %12, %8 ]
+  ret i32 %15
+}
+
+declare dso_local i32 @__gxx_personality_v0(...)
+
+; Function Attrs: nounwind readnone
+declare i32 @llvm.eh.typeid.for(i8*) #1
+
+declare dso_local i8* @__cxa_begin_catch(i8*) local_unnamed_addr
+
+declare dso_local void @__cxa_end_catch() local_unnamed_addr
+
+; Function Attrs: noinline uwtable
+define dso_local i32 @_Z9cleans_upi(i32 %0) local_unnamed_addr #0 personality i8* bitcast (i32 (...)* @__gxx_personality_v0 to i8*) {
+  %2 = alloca %struct.Guard, align 1
+  %3 = invoke i32 @_Z10propagatesi(i32 %0)
+          to label %4 unwind label %5
+
+4:                                                ; preds = %1
+  call void @_ZN5GuardD1Ev(%struct.Guard* nonnull %2) #4
+  ret i32 %3
+
+5:                                                ; preds = %1
+  %6 = landingpad { i8*, i32 }
+          cleanup
+  call void @_ZN5GuardD1Ev(%struct.Guard* nonnull %2) #4
+  resume { i8*, i32 } %6
+}
+
+; Function Attrs: nounwind
+declare dso_local void @_ZN5GuardD1Ev(%struct.Guard*) unnamed_addr #2
+
+; Function Attrs: noinline uwtable
+define dso_local void @_Z9catch_alli(i32 %0) local_unnamed_addr #0 personality i8* bitcast (i32 (...)* @__gxx_personality_v0 to i8*) {
+  invoke void @_Z7throweri(i32 %0)
+          to label %6 unwind label %2
+
+2:                                                ; preds = %1
+  %3 = landingpad { i8*, i32 }
+          catch i8* null
+  %4 = extractvalue { i8*, i32 } %3, 0
+  %5 = tail call i8* @__cxa_begin_catch(i8* %4) #4
+  tail call void @__cxa_end_catch() #4
+  br label %6
+
+6:                                                ; preds = %1, %2
+  ret void
+}
+
+; Function Attrs: noinline norecurse nounwind readnone uwtable
+define dso_local i32 @_Z13no_exceptionsi(i32 %0) local_unnamed_addr #3 {
+  %2 = shl nsw i32 %0, 1
+  ret i32 %2
+}
+
+; Function Attrs: norecurse uwtable
+define dso_local i32 @main() local_unnamed_addr #0 {
+  %1 = tail call i32 @_Z7catchesi(i32 5)
+  %2 = tail call i32 @_Z9cleans_upi(i32 %1)
+  tail call void @_Z9catch_alli(i32 %2)
+  %3 = tail call i32 @_Z13no_exceptionsi(i32 %2)
+  ret i32 %3
+}
+
+attributes #0 = { noinline uwtable "frame-pointer"="none" "min-legal-vector-width"="0" "no-trapping-math"="true" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" }
+attributes #1 = { nounwind readnone }
+attributes #2 = { nounwind "frame-pointer"="none" "no-trapping-math"="true" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" }
+attributes #3 = { noinline norecurse nounwind readnone uwtable "frame-pointer"="none" "min-legal-vector-width"="0" "no-trapping-math"="true" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" }
+attributes #4 = { nounwind }
+attributes #5 = { noreturn }
+
+!llvm.module.flags = !{!0, !1}
+!llvm.ident = !{!2}
+
+!0 = !{i32 1, !"wchar_size", i32 4}
+!1 = !{i32 7, !"uwtable", i32 1}
+!2 = !{!"clang version 9.0.1 "}
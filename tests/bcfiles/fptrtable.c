@@ -0,0 +1,44 @@
+// Indirect calls through function pointers stored in global tables
+
+struct ops {
+    int (*open)(int);
+    int (*close)(int);
+};
+
+static int foo_open(int x) {
+    return x + 1;
+}
+
+static int foo_close(int x) {
+    return x - 1;
+}
+
+static int bar_open(int x) {
+    return x * 2;
+}
+
+static int bar_close(int x) {
+    return x / 2;
+}
+
+// same type as the functions above, but not in any table
+static int unrelated(int x) {
+    return x;
+}
+
+int (*volatile unrelated_ptr)(int) = unrelated;
+
+const struct ops drivers[2] = {
+    { foo_open, foo_close },
+    { bar_open, bar_close },
+};
+
+struct ops mutable_ops = { foo_open, foo_close };
+
+int open_driver(int i, int x) {
+    return drivers[i].open(x);
+}
+
+int close_mutable(int x) {
+    return mutable_ops.close(x);
+}
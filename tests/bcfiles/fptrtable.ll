@@ -0,0 +1,71 @@
+; ModuleID = 'fptrtable.c'
+source_filename = "fptrtable.c"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-pc-linux-gnu"
+
+%struct.ops = type { i32 (i32)*, i32 (i32)* }
+
+@unrelated_ptr = dso_local global i32 (i32)* @unrelated, align 8
+@drivers = dso_local local_unnamed_addr constant [2 x %struct.ops] [%struct.ops { i32 (i32)* @foo_open, i32 (i32)* @foo_close }, %struct.ops { i32 (i32)* @bar_open, i32 (i32)* @bar_close }], align 16
+@mutable_ops = dso_local local_unnamed_addr global %struct.ops { i32 (i32)* @foo_open, i32 (i32)* @foo_close }, align 8
+
+; Function Attrs: nounwind uwtable
+define dso_local i32 @open_driver(i32, i32) local_unnamed_addr #0 {
+  %3 = sext i32 %0 to i64
+  %4 = getelementptr inbounds [2 x %struct.ops], [2 x %struct.ops]* @drivers, i64 0, i64 %3, i32 0
+  %5 = load i32 (i32)*, i32 (i32)** %4, align 16, !tbaa !2
+  %6 = tail call i32 %5(i32 %1) #2
+  ret i32 %6
+}
+
+; Function Attrs: nounwind uwtable
+define dso_local i32 @close_mutable(i32) local_unnamed_addr #0 {
+  %2 = load i32 (i32)*, i32 (i32)** getelementptr inbounds (%struct.ops, %struct.ops* @mutable_ops, i64 0, i32 1), align 8, !tbaa !7
+  %3 = tail call i32 %2(i32 %0) #2
+  ret i32 %3
+}
+
+; Function Attrs: norecurse nounwind readnone uwtable
+define internal i32 @unrelated(i32 returned) #1 {
+  ret i32 %0
+}
+
+; Function Attrs: norecurse nounwind readnone uwtable
+define internal i32 @foo_open(i32) #1 {
+  %2 = add nsw i32 %0, 1
+  ret i32 %2
+}
+
+; Function Attrs: norecurse nounwind readnone uwtable
+define internal i32 @foo_close(i32) #1 {
+  %2 = add nsw i32 %0, -1
+  ret i32 %2
+}
+
+; Function Attrs: norecurse nounwind readnone uwtable
+define internal i32 @bar_open(i32) #1 {
+  %2 = shl nsw i32 %0, 1
+  ret i32 %2
+}
+
+; Function Attrs: norecurse nounwind readnone uwtable
+define internal i32 @bar_close(i32) #1 {
+  %2 = sdiv i32 %0, 2
+  ret i32 %2
+}
+
+attributes #0 = { nounwind uwtable "correctly-rounded-divide-sqrt-fp-math"="false" "disable-tail-calls"="false" "less-precise-fpmad"="false" "min-legal-vector-width"="0" "no-frame-pointer-elim"="false" "no-infs-fp-math"="false" "no-jump-tables"="false" "no-nans-fp-math"="false" "no-signed-zeros-fp-math"="false" "no-trapping-math"="false" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "unsafe-fp-math"="false" "use-soft-float"="false" }
+attributes #1 = { norecurse nounwind readnone uwtable "correctly-rounded-divide-sqrt-fp-math"="false" "disable-tail-calls"="false" "less-precise-fpmad"="false" "min-legal-vector-width"="0" "no-frame-pointer-elim"="false" "no-infs-fp-math"="false" "no-jump-tables"="false" "no-nans-fp-math"="false" "no-signed-zeros-fp-math"="false" "no-trapping-math"="false" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "unsafe-fp-math"="false" "use-soft-float"="false" }
+attributes #2 = { nounwind }
+
+!llvm.module.flags = !{!0}
+!llvm.ident = !{!1}
+
+!0 = !{i32 1, !"wchar_size", i32 4}
+!1 = !{!"clang version 9.0.1-12 "}
+!2 = !{!3, !4, i64 0}
+!3 = !{!"ops", !4, i64 0, !4, i64 8}
+!4 = !{!"any pointer", !5, i64 0}
+!5 = !{!"omnipotent char", !6, i64 0}
+!6 = !{!"Simple C/C++ TBAA"}
+!7 = !{!3, !4, i64 8}
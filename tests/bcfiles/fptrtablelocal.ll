@@ -0,0 +1,56 @@
+; Hand-written module for testing function pointer tables across modules: its
+; internal @drivers has the same name as the (external) one in fptrtable.c. It
+; also has tables of pointers to an alias and an IFunc, which clang can't emit
+; from C.
+
+%struct.ops = type { i32 (i32)*, i32 (i32)* }
+
+@drivers = internal constant [1 x %struct.ops] [%struct.ops { i32 (i32)* @baz_open, i32 (i32)* @baz_close }]
+@aliased_ops = internal constant %struct.ops { i32 (i32)* @baz_open_alias, i32 (i32)* @baz_close }
+@ifunc_ops = internal constant %struct.ops { i32 (i32)* @baz_ifunc, i32 (i32)* @baz_close }
+
+@baz_open_alias = internal alias i32 (i32), i32 (i32)* @baz_open
+@baz_ifunc = internal ifunc i32 (i32), i32 (i32)* ()* @baz_resolver
+
+define internal i32 @baz_open(i32 %x) {
+entry:
+  %r = add i32 %x, 3
+  ret i32 %r
+}
+
+define internal i32 @baz_close(i32 %x) {
+entry:
+  %r = sub i32 %x, 3
+  ret i32 %r
+}
+
+; can only call @baz_open, through this module's own @drivers
+define i32 @open_local_driver(i32 %i, i32 %x) {
+entry:
+  %idx = sext i32 %i to i64
+  %slot = getelementptr inbounds [1 x %struct.ops], [1 x %struct.ops]* @drivers, i64 0, i64 %idx, i32 0
+  %open = load i32 (i32)*, i32 (i32)** %slot
+  %r = call i32 %open(i32 %x)
+  ret i32 %r
+}
+
+define internal i32 (i32)* @baz_resolver() {
+entry:
+  ret i32 (i32)* @baz_open
+}
+
+; can only call @baz_open, through the alias
+define i32 @open_aliased(i32 %x) {
+entry:
+  %open = load i32 (i32)*, i32 (i32)** getelementptr inbounds (%struct.ops, %struct.ops* @aliased_ops, i64 0, i32 0)
+  %r = call i32 %open(i32 %x)
+  ret i32 %r
+}
+
+; calls whatever @baz_resolver returns, which the table doesn't tell us
+define i32 @open_ifunc(i32 %x) {
+entry:
+  %open = load i32 (i32)*, i32 (i32)** getelementptr inbounds (%struct.ops, %struct.ops* @ifunc_ops, i64 0, i32 0)
+  %r = call i32 %open(i32 %x)
+  ret i32 %r
+}
@@ -0,0 +1,57 @@
+; Hand-written functions for testing value numbering, with redundant
+; computations left in
+
+declare i32 @f(i32)
+
+; %y repeats %x in a block %x dominates, and so %w repeats %z; %v repeats %x
+; with its operands commuted, and %g repeats %e. %r and %q repeat %l, but %l
+; doesn't dominate them
+define i32 @redundant(i32 %a, i32 %b, i1 %c) {
+entry:
+  %x = add i32 %a, %b
+  %z = mul i32 %x, 2
+  br i1 %c, label %left, label %right
+
+left:
+  %y = add i32 %a, %b
+  %w = mul i32 %y, 2
+  %l = sub i32 %a, %b
+  %n = sub i32 %b, %a
+  br label %join
+
+right:
+  %v = add i32 %b, %a
+  %r = sub i32 %a, %b
+  br label %join
+
+join:
+  %p = phi i32 [ %w, %left ], [ %r, %right ]
+  %q = sub i32 %a, %b
+  %s = add i32 %p, %z
+  %t = add i32 %z, %p
+  %e = icmp eq i32 %s, %t
+  %g = icmp eq i32 %t, %s
+  %k1 = call i32 @f(i32 %a)
+  %k2 = call i32 @f(i32 %a)
+  %u = xor i32 %k1, %k2
+  ret i32 %u
+}
+
+; %j repeats %h in every iteration, but %i's phis don't repeat each other
+define i32 @loop(i32 %n, i32 %a) {
+entry:
+  %h = shl i32 %a, 1
+  br label %loop
+
+loop:
+  %i = phi i32 [ 0, %entry ], [ %i.next, %loop ]
+  %i2 = phi i32 [ 0, %entry ], [ %i2.next, %loop ]
+  %j = shl i32 %a, 1
+  %i.next = add i32 %i, %j
+  %i2.next = add i32 %i2, %h
+  %done = icmp sge i32 %i.next, %n
+  br i1 %done, label %exit, label %loop
+
+exit:
+  ret i32 %i2
+}
@@ -0,0 +1,48 @@
+; Hand-written functions for testing MemorySSA, with control flow which clang
+; would simplify
+
+@x = global i32 0
+@y = global i32 0
+
+; %q branches to %s, %r, and %p; %s stores to @x, and %p loads from it, so the
+; load is clobbered by the memory `phi` in %p
+define i32 @nested_merge(i32 %k) {
+q:
+  switch i32 %k, label %p [
+    i32 0, label %s
+    i32 1, label %r
+  ]
+
+s:
+  store i32 1, i32* @x
+  br label %r
+
+r:
+  br label %p
+
+p:
+  %v = load i32, i32* @x
+  ret i32 %v
+}
+
+; the loop only stores to @y, so the load of @x after it is clobbered by the
+; store before it
+define i32 @loop_skips(i32 %n) {
+entry:
+  store i32 1, i32* @x
+  br label %header
+
+header:
+  %i = phi i32 [ 0, %entry ], [ %next, %body ]
+  %done = icmp eq i32 %i, %n
+  br i1 %done, label %exit, label %body
+
+body:
+  store i32 %i, i32* @y
+  %next = add i32 %i, 1
+  br label %header
+
+exit:
+  %v = load i32, i32* @x
+  ret i32 %v
+}
@@ -0,0 +1,88 @@
+; Hand-written functions for testing mod/ref summaries, with calls which
+; clang would inline or fold left in
+
+@g = global i32 0
+@h = global i32 0
+
+declare void @ext(i32*)
+declare void @llvm.memcpy.p0i8.p0i8.i64(i8* noalias nocapture writeonly, i8* noalias nocapture readonly, i64, i1 immarg)
+
+; reads through %x, and writes @g
+define void @leaf(i32* %x) {
+entry:
+  %v = load i32, i32* %x
+  store i32 %v, i32* @g
+  ret void
+}
+
+; reads @h, writes through a pointer based on %a, and passes %b to @leaf
+define void @mid(i32* %a, i32* %b) {
+entry:
+  %hv = load i32, i32* @h
+  %slot = getelementptr i32, i32* %a, i64 1
+  store i32 %hv, i32* %slot
+  call void @leaf(i32* %b)
+  ret void
+}
+
+; passes a local and @h to @mid
+define i32 @top() {
+entry:
+  %local = alloca i32
+  call void @mid(i32* %local, i32* @h)
+  %r = load i32, i32* %local
+  ret i32 %r
+}
+
+; writes through a pointer loaded from memory
+define void @indirect(i32** %pp) {
+entry:
+  %q = load i32*, i32** %pp
+  store i32 1, i32* %q
+  ret void
+}
+
+; writes through either %a or @g
+define void @chooses(i1 %c, i32* %a) {
+entry:
+  %p = select i1 %c, i32* %a, i32* @g
+  store i32 0, i32* %p
+  ret void
+}
+
+; mutually recursive, each writing through %out or to @g
+define void @even(i32 %n, i32* %out) {
+entry:
+  %z = icmp eq i32 %n, 0
+  br i1 %z, label %done, label %rec
+rec:
+  %m = sub i32 %n, 1
+  call void @odd(i32 %m, i32* %out)
+  ret void
+done:
+  store i32 1, i32* %out
+  ret void
+}
+
+define void @odd(i32 %n, i32* %out) {
+entry:
+  %z = icmp eq i32 %n, 0
+  br i1 %z, label %done, label %rec
+rec:
+  %m = sub i32 %n, 1
+  call void @even(i32 %m, i32* %out)
+  ret void
+done:
+  store i32 0, i32* @g
+  ret void
+}
+
+; calls of a declared function, restricted by call-site attributes, and of
+; an intrinsic
+define void @calls(i8* %dst, i32* %arg) {
+entry:
+  call void @ext(i32* @g) readonly
+  call void @llvm.memcpy.p0i8.p0i8.i64(i8* %dst, i8* bitcast (i32* @h to i8*), i64 4, i1 false)
+  call void @ext(i32* %arg) argmemonly
+  ret void
+}
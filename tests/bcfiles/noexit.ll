@@ -0,0 +1,87 @@
+; Hand-written, since clang tends to optimize away the branches in functions
+; which can never return. Each function has at least one block which can't
+; reach a `ret`.
+; ModuleID = 'noexit.ll'
+source_filename = "noexit.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+
+; returns if %0 <= 0; otherwise loops forever
+define i32 @loop_or_return(i32 %0) {
+  %2 = alloca i32, align 4
+  store volatile i32 0, i32* %2, align 4
+  %3 = icmp sgt i32 %0, 0
+  br i1 %3, label %4, label %7
+
+4:                                                ; preds = %1, %4
+  %5 = load volatile i32, i32* %2, align 4
+  %6 = add nsw i32 %5, 1
+  store volatile i32 %6, i32* %2, align 4
+  br label %4
+
+7:                                                ; preds = %1
+  ret i32 %0
+}
+
+; loops forever in one of two loops, depending on %0
+define void @two_infinite_loops(i32 %0) {
+  %2 = alloca i32, align 4
+  store volatile i32 0, i32* %2, align 4
+  %3 = icmp sgt i32 %0, 0
+  br i1 %3, label %4, label %7
+
+4:                                                ; preds = %1, %4
+  %5 = load volatile i32, i32* %2, align 4
+  %6 = add nsw i32 %5, 1
+  store volatile i32 %6, i32* %2, align 4
+  br label %4
+
+7:                                                ; preds = %1, %7
+  %8 = load volatile i32, i32* %2, align 4
+  %9 = add nsw i32 %8, -1
+  store volatile i32 %9, i32* %2, align 4
+  br label %7
+}
+
+; loops forever, with a conditional inside the loop
+define void @infinite_loop_with_branch(i32 %0) {
+  %2 = alloca i32, align 4
+  store volatile i32 0, i32* %2, align 4
+  br label %3
+
+3:                                                ; preds = %1, %10
+  %4 = load volatile i32, i32* %2, align 4
+  %5 = icmp sgt i32 %4, %0
+  br i1 %5, label %6, label %7
+
+6:                                                ; preds = %3
+  store volatile i32 0, i32* %2, align 4
+  br label %10
+
+7:                                                ; preds = %3
+  %8 = load volatile i32, i32* %2, align 4
+  %9 = add nsw i32 %8, 1
+  store volatile i32 %9, i32* %2, align 4
+  br label %10
+
+10:                                               ; preds = %6, %7
+  br label %3
+}
+
+; calls @sink() with 1 or 2 depending on %0, and then never returns
+define void @only_unreachable(i32 %0) {
+  %2 = icmp sgt i32 %0, 0
+  br i1 %2, label %3, label %4
+
+3:                                                ; preds = %1
+  call void @sink(i32 1)
+  br label %5
+
+4:                                                ; preds = %1
+  call void @sink(i32 2)
+  br label %5
+
+5:                                                ; preds = %3, %4
+  unreachable
+}
+
+declare void @sink(i32)
@@ -0,0 +1,114 @@
+declare void @exit(i32)
+declare void @ext()
+declare i32 @__gxx_personality_v0(...)
+
+; a panic wrapper without the `noreturn` attribute
+define void @die(i32 %code) {
+entry:
+  call void @exit(i32 %code)
+  unreachable
+}
+
+; calls @die, but isn't followed by `unreachable`
+define void @fatal() {
+entry:
+  call void @die(i32 1)
+  br label %after
+
+after:
+  ret void
+}
+
+define void @spin() {
+entry:
+  br label %loop
+
+loop:
+  br label %loop
+}
+
+; every path ends in `unreachable` or a call which never returns
+define void @check(i1 %c) {
+entry:
+  br i1 %c, label %bad, label %worse
+
+bad:
+  call void @fatal()
+  ret void
+
+worse:
+  unreachable
+}
+
+; mutually recursive, and never returning
+define void @ping() {
+entry:
+  call void @pong()
+  ret void
+}
+
+define void @pong() {
+entry:
+  call void @ping()
+  ret void
+}
+
+; the call site is marked `noreturn`, though @ext isn't
+define void @site_noreturn() {
+entry:
+  call void @ext() noreturn
+  br label %after
+
+after:
+  ret void
+}
+
+; only the exceptional destination of the `invoke` is reachable
+define void @invokes_die() personality i8* bitcast (i32 (...)* @__gxx_personality_v0 to i8*) {
+entry:
+  invoke void @die(i32 2)
+          to label %normal unwind label %lpad
+
+normal:
+  ret void
+
+lpad:
+  %lp = landingpad { i8*, i32 }
+          cleanup
+  resume { i8*, i32 } %lp
+}
+
+; returns, unless %x is negative
+define i32 @guarded(i32 %x) {
+entry:
+  %neg = icmp slt i32 %x, 0
+  br i1 %neg, label %fail, label %work
+
+fail:
+  call void @fatal()
+  br label %work
+
+work:
+  call void @ext()
+  ret i32 %x
+}
+
+
+; numbered functions: @0 never returns, but @1 may, despite calling @0
+define internal void @0() {
+entry:
+  call void @die(i32 3)
+  ret void
+}
+
+define internal void @1(i1 %c) {
+entry:
+  br i1 %c, label %fail, label %done
+
+fail:
+  call void @0()
+  br label %done
+
+done:
+  ret void
+}
@@ -0,0 +1,88 @@
+; Hand-written functions for testing null-check dominance, with redundant
+; checks left in
+
+; the load is checked non-null, the store checked null, and the load in %exit
+; can be reached either way
+define i32 @guarded(i32* %p) {
+entry:
+  %isnull = icmp eq i32* %p, null
+  br i1 %isnull, label %null, label %nonnull
+
+nonnull:
+  %v = load i32, i32* %p
+  br label %exit
+
+null:
+  store i32 0, i32* %p
+  br label %exit
+
+exit:
+  %w = load i32, i32* %p
+  ret i32 %w
+}
+
+; %c is checked, which applies to %q too; but %f isn't
+define void @casts(i8* %q, void ()* %f) {
+entry:
+  %c = bitcast i8* %q to i32*
+  %nonnull = icmp ne i32* %c, null
+  br i1 %nonnull, label %then, label %exit
+
+then:
+  %x = load i8, i8* %q
+  %t = atomicrmw add i32* %c, i32 1 seq_cst
+  call void %f()
+  br label %exit
+
+exit:
+  ret void
+}
+
+; the inner check is nearer than the outer one; and %p is checked on every
+; iteration of the loop, but both outcomes of %c lead to %join
+define void @nested(i32* %p, i32 %n, i1 %c) {
+entry:
+  %isnull = icmp eq i32* %p, null
+  br i1 %isnull, label %exit, label %inner
+
+inner:
+  %nonnull = icmp ne i32* null, %p
+  br i1 %nonnull, label %loop, label %exit
+
+loop:
+  %i = phi i32 [ 0, %inner ], [ %i.next, %join ]
+  %again = icmp eq i32* %p, null
+  br i1 %again, label %exit, label %body
+
+body:
+  store i32 %i, i32* %p
+  %check = icmp eq i32* %p, null
+  br i1 %check, label %join, label %join
+
+join:
+  %v = load i32, i32* %p
+  %i.next = add i32 %i, 1
+  %done = icmp sge i32 %i.next, %n
+  br i1 %done, label %exit, label %loop
+
+exit:
+  ret void
+}
+
+; %dead can't be reached from the entry block, so the check still guards the
+; load in %then
+define i32 @dead_pred(i32* %p) {
+entry:
+  %nonnull = icmp ne i32* %p, null
+  br i1 %nonnull, label %then, label %exit
+
+then:
+  %v = load i32, i32* %p
+  ret i32 %v
+
+dead:
+  br label %then
+
+exit:
+  ret i32 0
+}
@@ -0,0 +1,11 @@
+__attribute__((noinline)) static int first(int x) {
+    return x + 1;
+}
+
+__attribute__((noinline)) static int second(int x) {
+    return first(x) * 2;
+}
+
+int entry(int x) {
+    return second(x) + first(x);
+}
@@ -0,0 +1,34 @@
+; ModuleID = 'numbered.c'
+source_filename = "numbered.c"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-pc-linux-gnu"
+
+; Function Attrs: nounwind uwtable
+define dso_local i32 @entry(i32 %0) local_unnamed_addr #0 {
+  %2 = tail call fastcc i32 @0(i32 %0)
+  %3 = tail call fastcc i32 @1(i32 %0)
+  %4 = add nsw i32 %3, %2
+  ret i32 %4
+}
+
+; Function Attrs: noinline norecurse nounwind readnone uwtable
+define internal fastcc i32 @0(i32 %0) unnamed_addr #1 {
+  %2 = tail call fastcc i32 @1(i32 %0)
+  %3 = shl nsw i32 %2, 1
+  ret i32 %3
+}
+
+; Function Attrs: noinline norecurse nounwind readnone uwtable
+define internal fastcc i32 @1(i32 %0) unnamed_addr #1 {
+  %2 = add nsw i32 %0, 1
+  ret i32 %2
+}
+
+attributes #0 = { nounwind uwtable "correctly-rounded-divide-sqrt-fp-math"="false" "disable-tail-calls"="false" "less-precise-fpmad"="false" "min-legal-vector-width"="0" "no-frame-pointer-elim"="false" "no-infs-fp-math"="false" "no-jump-tables"="false" "no-nans-fp-math"="false" "no-signed-zeros-fp-math"="false" "no-trapping-math"="false" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "unsafe-fp-math"="false" "use-soft-float"="false" }
+attributes #1 = { noinline norecurse nounwind readnone uwtable "correctly-rounded-divide-sqrt-fp-math"="false" "disable-tail-calls"="false" "less-precise-fpmad"="false" "min-legal-vector-width"="0" "no-frame-pointer-elim"="false" "no-infs-fp-math"="false" "no-jump-tables"="false" "no-nans-fp-math"="false" "no-signed-zeros-fp-math"="false" "no-trapping-math"="false" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "unsafe-fp-math"="false" "use-soft-float"="false" }
+
+!llvm.module.flags = !{!0}
+!llvm.ident = !{!1}
+
+!0 = !{i32 1, !"wchar_size", i32 4}
+!1 = !{!"clang version 9.0.1-12 "}
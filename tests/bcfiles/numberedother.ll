@@ -0,0 +1,11 @@
+; A second module with a function with a numbered name, to check that it is
+; kept separate from the numbered functions in numbered.c
+
+define i32 @other_entry(i32 %0) {
+  %2 = call i32 @0(i32 %0)
+  ret i32 %2
+}
+
+define internal i32 @0(i32 %0) {
+  ret i32 %0
+}
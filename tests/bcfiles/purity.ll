@@ -0,0 +1,83 @@
+; Hand-written functions for testing purity classification, with some
+; attributes which contradict the functions' bodies
+
+@g = global i32 0
+
+declare void @ext()
+
+define i32 @square(i32 %x) readnone {
+entry:
+  %sq = mul i32 %x, %x
+  ret i32 %sq
+}
+
+define i32 @get() {
+entry:
+  %v = load i32, i32* @g
+  ret i32 %v
+}
+
+; only accesses its own `alloca`
+define i32 @local(i32 %x) {
+entry:
+  %slot = alloca i32
+  store i32 %x, i32* %slot
+  %v = load i32, i32* %slot
+  ret i32 %v
+}
+
+define void @set(i32 %x) {
+entry:
+  store i32 %x, i32* @g
+  ret void
+}
+
+; claims not to read memory, but @get does
+define i32 @calls_get() readnone {
+entry:
+  %v = call i32 @get()
+  ret i32 %v
+}
+
+; claims not to write memory, which may be true
+define void @calls_ext() readonly {
+entry:
+  call void @ext()
+  ret void
+}
+
+; the call site is trusted
+define void @trusted() {
+entry:
+  call void @ext() readnone
+  ret void
+}
+
+; mutually recursive, one of them reading memory
+define i32 @ping(i32 %n) {
+entry:
+  %z = icmp eq i32 %n, 0
+  br i1 %z, label %done, label %rec
+rec:
+  %m = sub i32 %n, 1
+  %r = call i32 @pong(i32 %m)
+  ret i32 %r
+done:
+  ret i32 0
+}
+
+define i32 @pong(i32 %n) {
+entry:
+  %v = call i32 @ping(i32 %n)
+  %w = load i32, i32* @g
+  %r = add i32 %v, %w
+  ret i32 %r
+}
+
+; a volatile access of its own `alloca`
+define i32 @volatile_local() {
+entry:
+  %slot = alloca i32
+  %v = load volatile i32, i32* %slot
+  ret i32 %v
+}
@@ -0,0 +1,105 @@
+; Hand-written functions for testing value-range analysis, with the shapes of
+; branches and loops fixed
+
+; %r is in 0..=100
+define i32 @clamp(i32 %x) {
+entry:
+  %neg = icmp slt i32 %x, 0
+  br i1 %neg, label %negative, label %nonnegative
+
+negative:
+  br label %join
+
+nonnegative:
+  %big = icmp sgt i32 %x, 100
+  %c = select i1 %big, i32 100, i32 %x
+  br label %join
+
+join:
+  %r = phi i32 [ 0, %negative ], [ %c, %nonnegative ]
+  ret i32 %r
+}
+
+; %i is in 0..=9 in the body, and exactly 10 at the exit
+define i32 @count(i32* %a) {
+entry:
+  br label %loop
+
+loop:
+  %i = phi i32 [ 0, %entry ], [ %i.next, %body ]
+  %cmp = icmp slt i32 %i, 10
+  br i1 %cmp, label %body, label %exit
+
+body:
+  %idx = sext i32 %i to i64
+  %p = getelementptr inbounds i32, i32* %a, i64 %idx
+  %v = load i32, i32* %p, align 4
+  %i.next = add nsw i32 %i, 1
+  br label %loop
+
+exit:
+  ret i32 %i
+}
+
+; unsigned comparisons of masked values, and a branch which is never taken
+define i32 @masked(i32 %x) {
+entry:
+  %m = and i32 %x, 255
+  %small = icmp ult i32 %m, 16
+  br i1 %small, label %low, label %high
+
+low:
+  %sh = shl i32 %m, 2
+  %z = zext i1 %small to i32
+  ret i32 %sh
+
+high:
+  %d = udiv i32 %m, 16
+  %huge = icmp ugt i32 %m, 1000
+  br i1 %huge, label %never, label %done
+
+never:
+  ret i32 -1
+
+done:
+  ret i32 %d
+}
+
+; %x is known in each case of the switch
+define i8 @pick(i8 %x) {
+entry:
+  switch i8 %x, label %other [
+    i8 1, label %one
+    i8 2, label %two
+    i8 3, label %two
+  ]
+
+one:
+  %a = add i8 %x, 10
+  br label %join
+
+two:
+  %b = mul i8 %x, -2
+  br label %join
+
+other:
+  br label %join
+
+join:
+  %r = phi i8 [ %a, %one ], [ %b, %two ], [ 0, %other ]
+  ret i8 %r
+}
+
+; signed comparisons treat an `i1` `true` as -1
+define i32 @signed_bool(i1 %b) {
+entry:
+  %t = icmp sgt i1 false, true
+  %neg = icmp slt i1 %b, false
+  br i1 %neg, label %negative, label %nonnegative
+
+negative:
+  ret i32 1
+
+nonnegative:
+  ret i32 0
+}
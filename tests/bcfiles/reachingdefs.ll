@@ -0,0 +1,39 @@
+; Hand-written functions with stack slots, for testing reaching definitions;
+; clang at -O3 would promote most of these slots to SSA values
+
+declare void @init(i32*)
+declare i32 @opaque()
+
+; %x escapes to @init, so the call to @opaque may write it, but not %y
+define i32 @escaped(i1 %c) {
+entry:
+  %x = alloca i32, align 4
+  %y = alloca i32, align 4
+  store i32 1, i32* %x, align 4
+  store i32 2, i32* %y, align 4
+  call void @init(i32* %x)
+  br i1 %c, label %then, label %join
+
+then:
+  store i32 3, i32* %y, align 4
+  %z = call i32 @opaque()
+  br label %join
+
+join:
+  %a = load i32, i32* %x, align 4
+  %b = load i32, i32* %y, align 4
+  %s = add i32 %a, %b
+  ret i32 %s
+}
+
+; the store through %q writes only part of %p, so it doesn't kill the store
+; of the whole array
+define i32 @partial() {
+entry:
+  %p = alloca [2 x i32], align 4
+  %q = getelementptr [2 x i32], [2 x i32]* %p, i64 0, i64 1
+  store [2 x i32] zeroinitializer, [2 x i32]* %p, align 4
+  store i32 5, i32* %q, align 4
+  %r = load i32, i32* %q, align 4
+  ret i32 %r
+}
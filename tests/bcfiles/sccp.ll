@@ -0,0 +1,56 @@
+; Hand-written functions for testing sparse conditional constant propagation,
+; with constants left unfolded
+
+; %a is 5, so only %then executes, and %r is 20
+define i32 @folded(i32 %x) {
+entry:
+  %a = add i32 2, 3
+  %cond = icmp eq i32 %a, 5
+  br i1 %cond, label %then, label %else
+
+then:
+  %b = mul i32 %a, 4
+  br label %join
+
+else:
+  %c = add i32 %x, 1
+  br label %join
+
+join:
+  %r = phi i32 [ %b, %then ], [ %c, %else ]
+  switch i32 %r, label %default [
+    i32 20, label %twenty
+    i32 30, label %thirty
+  ]
+
+twenty:
+  %w = add i8 127, 1
+  %t = trunc i32 300 to i8
+  %s = sext i8 %w to i32
+  ret i32 %s
+
+thirty:
+  ret i32 30
+
+default:
+  %d = udiv i32 %r, 0
+  ret i32 %d
+}
+
+; %k is 7 on every iteration, but %i isn't constant
+define i32 @loop_invariant(i32 %n) {
+entry:
+  br label %loop
+
+loop:
+  %i = phi i32 [ 0, %entry ], [ %i.next, %loop ]
+  %k = phi i32 [ 7, %entry ], [ %k.next, %loop ]
+  %k.next = add i32 %k, 0
+  %i.next = add i32 %i, 1
+  %done = icmp sge i32 %i.next, %n
+  br i1 %done, label %exit, label %loop
+
+exit:
+  %sum = add i32 %i, %k
+  ret i32 %k
+}
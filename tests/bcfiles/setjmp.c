@@ -0,0 +1,23 @@
+#include <setjmp.h>
+
+static jmp_buf buf;
+
+__attribute__((noinline)) void jumps_back(int x) {
+  if (x > 3) longjmp(buf, 1);
+}
+
+__attribute__((noinline)) int calls_jumper(int x) {
+  jumps_back(x);
+  return x + 1;
+}
+
+int has_setjmp(int x) {
+  if (setjmp(buf) != 0) {
+    return -1;
+  }
+  return calls_jumper(x) + 2;
+}
+
+int no_setjmp(int x) {
+  return calls_jumper(x) * 2;
+}
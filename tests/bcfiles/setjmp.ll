@@ -0,0 +1,65 @@
+; ModuleID = 'setjmp.c'
+source_filename = "setjmp.c"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-pc-linux-gnu"
+
+%struct.__jmp_buf_tag = type { [8 x i64], i32, %struct.__sigset_t }
+%struct.__sigset_t = type { [16 x i64] }
+
+@buf = internal global [1 x %struct.__jmp_buf_tag] zeroinitializer, align 16
+
+; Function Attrs: noinline nounwind uwtable
+define dso_local void @jumps_back(i32 %0) local_unnamed_addr #0 {
+  %2 = icmp sgt i32 %0, 3
+  br i1 %2, label %3, label %4
+
+3:                                                ; preds = %1
+  tail call void @longjmp(%struct.__jmp_buf_tag* getelementptr inbounds ([1 x %struct.__jmp_buf_tag], [1 x %struct.__jmp_buf_tag]* @buf, i64 0, i64 0), i32 1) #5
+  unreachable
+
+4:                                                ; preds = %1
+  ret void
+}
+
+; Function Attrs: noreturn nounwind
+declare dso_local void @longjmp(%struct.__jmp_buf_tag*, i32) local_unnamed_addr #1
+
+; Function Attrs: noinline nounwind uwtable
+define dso_local i32 @calls_jumper(i32 %0) local_unnamed_addr #0 {
+  tail call void @jumps_back(i32 %0)
+  %2 = add nsw i32 %0, 1
+  ret i32 %2
+}
+
+; Function Attrs: nounwind uwtable
+define dso_local i32 @has_setjmp(i32 %0) local_unnamed_addr #2 {
+  %2 = call i32 @_setjmp(%struct.__jmp_buf_tag* getelementptr inbounds ([1 x %struct.__jmp_buf_tag], [1 x %struct.__jmp_buf_tag]* @buf, i64 0, i64 0)) #4
+  %3 = icmp eq i32 %2, 0
+  br i1 %3, label %4, label %7
+
+4:                                                ; preds = %1
+  %5 = tail call i32 @calls_jumper(i32 %0)
+  %6 = add nsw i32 %5, 2
+  br label %7
+
+7:                                                ; preds = %1, %4
+  %8 = phi i32 [ %6, %4 ], [ -1, %1 ]
+  ret i32 %8
+}
+
+; Function Attrs: nounwind returns_twice
+declare dso_local i32 @_setjmp(%struct.__jmp_buf_tag*) local_unnamed_addr #3
+
+; Function Attrs: nounwind uwtable
+define dso_local i32 @no_setjmp(i32 %0) local_unnamed_addr #2 {
+  %2 = tail call i32 @calls_jumper(i32 %0)
+  %3 = shl nsw i32 %2, 1
+  ret i32 %3
+}
+
+attributes #0 = { noinline nounwind uwtable }
+attributes #1 = { noreturn nounwind }
+attributes #2 = { nounwind uwtable }
+attributes #3 = { nounwind returns_twice }
+attributes #4 = { nounwind returns_twice }
+attributes #5 = { noreturn nounwind }
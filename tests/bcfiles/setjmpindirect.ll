@@ -0,0 +1,26 @@
+; Hand-written call to a `setjmp`-family function through a function pointer,
+; marked `returns_twice` at the call site
+
+declare void @longjmp(i8*, i32)
+
+define i32 @indirect_setjmp(i32 (i8*)* %fp, i8* %buf, i32 %x) {
+entry:
+  %r = call i32 %fp(i8* %buf) #0
+  %c = icmp eq i32 %r, 0
+  br i1 %c, label %first, label %again
+
+first:
+  call void @longjmp(i8* %buf, i32 1)
+  unreachable
+
+again:
+  ret i32 %x
+}
+
+define i32 @indirect_call(i32 (i8*)* %fp, i8* %buf) {
+entry:
+  %r = call i32 %fp(i8* %buf)
+  ret i32 %r
+}
+
+attributes #0 = { returns_twice }
@@ -0,0 +1,44 @@
+; Hand-written, since clang doesn't emit statepoints. These are what
+; `opt -rewrite-statepoints-for-gc` produces for calls in functions using a
+; statepoint-based GC strategy.
+; ModuleID = 'statepoint.ll'
+source_filename = "statepoint.ll"
+target datalayout = "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-pc-linux-gnu"
+
+define i8 addrspace(1)* @alloc(i32 %0) gc "statepoint-example" {
+  ret i8 addrspace(1)* null
+}
+
+define void @safepoint() gc "statepoint-example" {
+  ret void
+}
+
+define i32 @callback_target(i32 %0) {
+  ret i32 %0
+}
+
+define void @takes_callback(i32 (i32)* %0) {
+  ret void
+}
+
+define i8 addrspace(1)* @caller(i8 addrspace(1)* %0) gc "statepoint-example" {
+  %2 = call token (i64, i32, void ()*, i32, i32, ...) @llvm.experimental.gc.statepoint.p0f_isVoidf(i64 0, i32 0, void ()* elementtype(void ()) @safepoint, i32 0, i32 0, i32 0, i32 0) [ "gc-live"(i8 addrspace(1)* %0) ]
+  %3 = call i8 addrspace(1)* @llvm.experimental.gc.relocate.p1i8(token %2, i32 0, i32 0)
+  %4 = call token (i64, i32, i8 addrspace(1)* (i32)*, i32, i32, ...) @llvm.experimental.gc.statepoint.p0f_p1i8i32f(i64 0, i32 0, i8 addrspace(1)* (i32)* elementtype(i8 addrspace(1)* (i32)) @alloc, i32 1, i32 0, i32 16, i32 0, i32 0)
+  %5 = call i8 addrspace(1)* @llvm.experimental.gc.result.p1i8(token %4)
+  %6 = call token (i64, i32, void (i32 (i32)*)*, i32, i32, ...) @llvm.experimental.gc.statepoint.p0f_isVoidp0f_i32i32ff(i64 0, i32 0, void (i32 (i32)*)* elementtype(void (i32 (i32)*)) @takes_callback, i32 1, i32 0, i32 (i32)* @callback_target, i32 0, i32 0)
+  call void @safepoint() [ "deopt"(i32 1, i32 2) ]
+  ret i8 addrspace(1)* %5
+}
+
+define void @indirect(void ()* %0) gc "statepoint-example" {
+  %2 = call token (i64, i32, void ()*, i32, i32, ...) @llvm.experimental.gc.statepoint.p0f_isVoidf(i64 0, i32 0, void ()* elementtype(void ()) %0, i32 0, i32 0, i32 0, i32 0)
+  ret void
+}
+
+declare token @llvm.experimental.gc.statepoint.p0f_isVoidf(i64 immarg, i32 immarg, void ()*, i32 immarg, i32 immarg, ...)
+declare token @llvm.experimental.gc.statepoint.p0f_p1i8i32f(i64 immarg, i32 immarg, i8 addrspace(1)* (i32)*, i32 immarg, i32 immarg, ...)
+declare token @llvm.experimental.gc.statepoint.p0f_isVoidp0f_i32i32ff(i64 immarg, i32 immarg, void (i32 (i32)*)*, i32 immarg, i32 immarg, ...)
+declare i8 addrspace(1)* @llvm.experimental.gc.relocate.p1i8(token, i32 immarg, i32 immarg)
+declare i8 addrspace(1)* @llvm.experimental.gc.result.p1i8(token)
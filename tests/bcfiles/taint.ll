@@ -0,0 +1,47 @@
+; Hand-written functions for testing taint analysis, with tainted data flowing
+; through calls, returns, and a stack slot
+
+@cmd = private unnamed_addr constant [3 x i8] c"ls\00", align 1
+@fmt = private unnamed_addr constant [4 x i8] c"%s\0A\00", align 1
+
+declare i8* @read_input()
+declare i32 @system(i8*)
+declare i32 @printf(i8*, ...)
+declare i64 @strlen(i8*)
+
+define i8* @identity(i8* %p) {
+entry:
+  ret i8* %p
+}
+
+define void @run(i8* %command) {
+entry:
+  %r = call i32 @system(i8* %command)
+  ret void
+}
+
+; the input reaches @system through @identity, %slot, and @run; the length of
+; the input is tainted, but the constant command is not
+define i64 @process() {
+entry:
+  %slot = alloca i8*, align 8
+  %input = call i8* @read_input()
+  %id = call i8* @identity(i8* %input)
+  store i8* %id, i8** %slot, align 8
+  %loaded = load i8*, i8** %slot, align 8
+  call void @run(i8* %loaded)
+  %len = call i64 @strlen(i8* %input)
+  %safe = call i32 @system(i8* getelementptr inbounds ([3 x i8], [3 x i8]* @cmd, i64 0, i64 0))
+  %p = call i32 (i8*, ...) @printf(i8* getelementptr inbounds ([4 x i8], [4 x i8]* @fmt, i64 0, i64 0), i8* %input)
+  ret i64 %len
+}
+
+; the strings %argv points to are tainted
+define i32 @main(i32 %argc, i8** %argv) {
+entry:
+  %arg = getelementptr inbounds i8*, i8** %argv, i64 1
+  %s = load i8*, i8** %arg, align 8
+  %r = call i32 @system(i8* %s)
+  %n = call i64 @process()
+  ret i32 %r
+}
@@ -0,0 +1,67 @@
+; Hand-written accesses through pointers of various types, for testing
+; type-based alias analysis; it relies on typed pointers, so it's written
+; for LLVM 14
+
+%struct.pair = type { i32, float }
+
+@g = global i32 0
+@g_alias = alias i32, i32* @g
+@h = global i32 0
+
+define i32 @type_punning(i32* %i, float* %f, i8* %c, i64* %l, { i32, i64 }* %s, i32** %pp, float** %fp, %struct.pair* %p) {
+entry:
+  store float 1.0, float* %f, align 4
+  store i8 0, i8* %c, align 1
+  store i64 2, i64* %l, align 8
+  %x = load i32, i32* %i, align 4
+  %sl = getelementptr { i32, i64 }, { i32, i64 }* %s, i64 0, i32 1
+  %y = load i64, i64* %sl, align 8
+  %fptr = load float*, float** %fp, align 8
+  store i32* null, i32** %pp, align 8
+  %pf = getelementptr %struct.pair, %struct.pair* %p, i64 0, i32 1
+  ret i32 %x
+}
+
+%union.u = type { i32 }
+
+; accesses a union as each of its members
+define float @union_punning() {
+entry:
+  %u = alloca %union.u, align 4
+  %ip = bitcast %union.u* %u to i32*
+  %fp = bitcast %union.u* %u to float*
+  store i32 1, i32* %ip, align 4
+  %x = load float, float* %fp, align 4
+  ret float %x
+}
+
+; in the unreachable block, %q is based on itself
+define i32 @self_referential(i32* %p) {
+entry:
+  %x = load i32, i32* %p, align 4
+  ret i32 %x
+
+dead:
+  %q = getelementptr i32, i32* %q, i64 1
+  store i32 0, i32* %q, align 4
+  br label %dead
+}
+
+; @g_alias is the same object as @g, but @h isn't
+define void @global_alias() {
+entry:
+  store i32 1, i32* @g, align 4
+  store i32 2, i32* @g_alias, align 4
+  store i32 3, i32* @h, align 4
+  ret void
+}
+
+; stores a pair of `i32`s, and loads them as a single `i64`
+define i64 @bitcast_punning({ i32, i32 }* %s, float* %f) {
+entry:
+  store { i32, i32 } { i32 1, i32 2 }, { i32, i32 }* %s, align 4
+  %l = bitcast { i32, i32 }* %s to i64*
+  %x = load i64, i64* %l, align 4
+  %y = load float, float* %f, align 4
+  ret i64 %x
+}
@@ -0,0 +1,12 @@
+// A function which is only called as a thread's entry function
+
+#include <pthread.h>
+
+static void *worker(void *arg) {
+    return arg;
+}
+
+int start_worker(void) {
+    pthread_t thread;
+    return pthread_create(&thread, NULL, worker, NULL);
+}
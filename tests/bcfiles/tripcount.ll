@@ -0,0 +1,173 @@
+; Hand-written loops with constant trip counts (and a few without), for
+; testing trip-count estimation without depending on how clang shapes loops
+
+; for (i = 0; i < 10; i += 3) {}
+; the exit test is in the header, so the header runs once more than the body
+define void @header_exit() {
+entry:
+  br label %header
+
+header:
+  %i = phi i32 [ 0, %entry ], [ %next, %body ]
+  %cond = icmp slt i32 %i, 10
+  br i1 %cond, label %body, label %exit
+
+body:
+  %next = add nsw i32 %i, 3
+  br label %header
+
+exit:
+  ret void
+}
+
+; i = 10; do { } while (--i != 0);
+define void @count_down() {
+entry:
+  br label %loop
+
+loop:
+  %i = phi i32 [ 10, %entry ], [ %dec, %loop ]
+  %dec = sub i32 %i, 1
+  %cond = icmp ne i32 %dec, 0
+  br i1 %cond, label %loop, label %exit
+
+exit:
+  ret void
+}
+
+; for (i8 i = 250; i != 4; i++) {}, which wraps around
+define void @wrap_around() {
+entry:
+  br label %header
+
+header:
+  %i = phi i8 [ -6, %entry ], [ %next, %header ]
+  %done = icmp eq i8 %i, 4
+  %next = add i8 %i, 1
+  br i1 %done, label %exit, label %header
+
+exit:
+  ret void
+}
+
+; for (i = 0; i != 7; i += 2) {}, which never exits
+define void @never_equal() {
+entry:
+  br label %header
+
+header:
+  %i = phi i32 [ 0, %entry ], [ %next, %header ]
+  %next = add i32 %i, 2
+  %done = icmp eq i32 %next, 7
+  br i1 %done, label %exit, label %header
+
+exit:
+  ret void
+}
+
+; for (u8 i = 0; i < 300; i += 100) {}, where i overflows before the exit
+define void @overflow() {
+entry:
+  br label %header
+
+header:
+  %i = phi i8 [ 0, %entry ], [ %next, %header ]
+  %next = add i8 %i, 100
+  %cond = icmp ult i8 %next, 250
+  br i1 %cond, label %header, label %exit
+
+exit:
+  ret void
+}
+
+; for (i = 0; i < 100; i++) { if (a[i] == 0) return 1; } return 0;
+define i32 @early_return(i32* %a) {
+entry:
+  br label %header
+
+header:
+  %i = phi i64 [ 0, %entry ], [ %next, %latch ]
+  %cond = icmp ult i64 %i, 100
+  br i1 %cond, label %body, label %exit
+
+body:
+  %ptr = getelementptr inbounds i32, i32* %a, i64 %i
+  %x = load i32, i32* %ptr
+  %found = icmp eq i32 %x, 0
+  br i1 %found, label %return, label %latch
+
+latch:
+  %next = add nuw i64 %i, 1
+  br label %header
+
+return:
+  ret i32 1
+
+exit:
+  ret i32 0
+}
+
+declare void @consume(i32)
+
+; for (i = 0; i < 4; i++) { consume(i); a[i] = i; }, where the call is assumed
+; to return
+define void @call_in_loop(i32* %a) {
+entry:
+  br label %loop
+
+loop:
+  %i = phi i32 [ 0, %entry ], [ %next, %loop ]
+  call void @consume(i32 %i)
+  %ptr = getelementptr inbounds i32, i32* %a, i32 %i
+  store i32 %i, i32* %ptr
+  %next = add nuw nsw i32 %i, 1
+  %cond = icmp slt i32 %next, 4
+  br i1 %cond, label %loop, label %exit
+
+exit:
+  ret void
+}
+
+; for (i = 0; i != 4; i++) {}, plus a block unreachable from the entry block,
+; which branches to itself and into the loop
+define void @unreachable_self_loop() {
+entry:
+  br label %loop
+
+loop:
+  %i = phi i32 [ 0, %entry ], [ %next, %body ]
+  %done = icmp eq i32 %i, 4
+  br i1 %done, label %exit, label %body
+
+body:
+  %next = add nuw nsw i32 %i, 1
+  br label %loop
+
+dead:
+  br i1 undef, label %dead, label %body
+
+exit:
+  ret void
+}
+
+; %dead, which is unreachable, branches into the loop's header and body
+define void @dead_branch_into_loop() {
+entry:
+  br label %loop
+
+loop:
+  %i = phi i32 [ 0, %entry ], [ %next, %body ], [ 0, %dead ]
+  %done = icmp eq i32 %i, 4
+  br i1 %done, label %exit, label %body
+
+body:
+  %j = phi i32 [ %i, %loop ], [ 0, %dead ]
+  %next = add nuw nsw i32 %j, 1
+  br label %loop
+
+dead:
+  br i1 undef, label %loop, label %body
+
+exit:
+  ret void
+}
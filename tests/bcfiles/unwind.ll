@@ -0,0 +1,70 @@
+; Hand-written functions for testing which functions may unwind, with
+; landing pads which can't be reached and calls clang would fold left in
+
+declare void @ext()
+declare i32 @__gxx_personality_v0(...)
+
+define void @quiet() nounwind {
+entry:
+  ret void
+}
+
+define void @throws() {
+entry:
+  call void @ext()
+  ret void
+}
+
+define void @nounwind_site() {
+entry:
+  call void @ext() nounwind
+  ret void
+}
+
+; the landing pad can't be reached, since @quiet doesn't throw
+define void @invokes_quiet() personality i8* bitcast (i32 (...)* @__gxx_personality_v0 to i8*) {
+entry:
+  invoke void @quiet() to label %ok unwind label %lpad
+ok:
+  ret void
+lpad:
+  %lp = landingpad { i8*, i32 } cleanup
+  resume { i8*, i32 } %lp
+}
+
+define void @invokes_throws() personality i8* bitcast (i32 (...)* @__gxx_personality_v0 to i8*) {
+entry:
+  invoke void @throws() to label %ok unwind label %lpad
+ok:
+  ret void
+lpad:
+  %lp = landingpad { i8*, i32 } cleanup
+  resume { i8*, i32 } %lp
+}
+
+; the `resume` is in a block which can't be reached at all
+define void @dead_resume() personality i8* bitcast (i32 (...)* @__gxx_personality_v0 to i8*) {
+entry:
+  ret void
+dead:
+  resume { i8*, i32 } undef
+}
+
+; the only functions of type `void (i8)` don't throw
+define void @quiet_byte(i8 %x) {
+entry:
+  ret void
+}
+
+define void @calls_byte(void (i8)* %f) {
+entry:
+  call void %f(i8 0)
+  ret void
+}
+
+; no function has type `void (i16)`
+define void @calls_short(void (i16)* %f) {
+entry:
+  call void %f(i16 0)
+  ret void
+}
@@ -0,0 +1,32 @@
+#include <stdarg.h>
+
+int sum_three(int count, ...) {
+  va_list ap;
+  va_start(ap, count);
+  int a = va_arg(ap, int);
+  int b = va_arg(ap, int);
+  int c = va_arg(ap, int);
+  va_end(ap);
+  return a + b + c + count;
+}
+
+int sum_n(int count, ...) {
+  va_list ap, ap2;
+  va_start(ap, count);
+  va_copy(ap2, ap);
+  int sum = 0;
+  for (int i = 0; i < count; i++) {
+    sum += va_arg(ap2, int);
+  }
+  va_end(ap2);
+  va_end(ap);
+  return sum;
+}
+
+int calls_sum_three(int x) {
+  return sum_three(3, x, x + 1, 7);
+}
+
+int calls_sum_n(int x) {
+  return sum_n(2, x, 5);
+}
@@ -0,0 +1,80 @@
+; ModuleID = 'varargs.c'
+source_filename = "varargs.c"
+target datalayout = "e-p:32:32-i64:64"
+target triple = "le32-unknown-nacl"
+
+; Function Attrs: nounwind
+define hidden i32 @sum_three(i32 %0, ...) local_unnamed_addr #0 {
+  %2 = alloca i8*, align 4
+  %3 = bitcast i8** %2 to i8*
+  call void @llvm.lifetime.start.p0i8(i64 4, i8* nonnull %3) #2
+  call void @llvm.va_start(i8* nonnull %3)
+  %4 = va_arg i8** %2, i32
+  %5 = va_arg i8** %2, i32
+  %6 = va_arg i8** %2, i32
+  call void @llvm.va_end(i8* nonnull %3)
+  %7 = add nsw i32 %5, %4
+  %8 = add nsw i32 %7, %6
+  %9 = add nsw i32 %8, %0
+  call void @llvm.lifetime.end.p0i8(i64 4, i8* nonnull %3) #2
+  ret i32 %9
+}
+
+; Function Attrs: argmemonly nounwind willreturn
+declare void @llvm.lifetime.start.p0i8(i64 immarg, i8* nocapture) #1
+
+; Function Attrs: nounwind
+declare void @llvm.va_start(i8*) #2
+
+; Function Attrs: nounwind
+declare void @llvm.va_end(i8*) #2
+
+; Function Attrs: argmemonly nounwind willreturn
+declare void @llvm.lifetime.end.p0i8(i64 immarg, i8* nocapture) #1
+
+; Function Attrs: nounwind
+define hidden i32 @sum_n(i32 %0, ...) local_unnamed_addr #0 {
+  %2 = alloca i8*, align 4
+  %3 = alloca i8*, align 4
+  %4 = bitcast i8** %2 to i8*
+  call void @llvm.va_start(i8* nonnull %4)
+  %5 = bitcast i8** %3 to i8*
+  call void @llvm.va_copy(i8* nonnull %5, i8* nonnull %4)
+  %6 = icmp sgt i32 %0, 0
+  br i1 %6, label %7, label %14
+
+7:                                                ; preds = %1, %7
+  %8 = phi i32 [ %11, %7 ], [ 0, %1 ]
+  %9 = phi i32 [ %12, %7 ], [ 0, %1 ]
+  %10 = va_arg i8** %3, i32
+  %11 = add nuw nsw i32 %8, 1
+  %12 = add nsw i32 %10, %9
+  %13 = icmp eq i32 %11, %0
+  br i1 %13, label %14, label %7
+
+14:                                               ; preds = %7, %1
+  %15 = phi i32 [ 0, %1 ], [ %12, %7 ]
+  call void @llvm.va_end(i8* nonnull %5)
+  call void @llvm.va_end(i8* nonnull %4)
+  ret i32 %15
+}
+
+; Function Attrs: nounwind
+declare void @llvm.va_copy(i8*, i8*) #2
+
+; Function Attrs: nounwind
+define hidden i32 @calls_sum_three(i32 %0) local_unnamed_addr #0 {
+  %2 = add nsw i32 %0, 1
+  %3 = tail call i32 (i32, ...) @sum_three(i32 3, i32 %0, i32 %2, i32 7)
+  ret i32 %3
+}
+
+; Function Attrs: nounwind
+define hidden i32 @calls_sum_n(i32 %0) local_unnamed_addr #0 {
+  %2 = tail call i32 (i32, ...) @sum_n(i32 2, i32 %0, i32 5)
+  ret i32 %2
+}
+
+attributes #0 = { nounwind }
+attributes #1 = { argmemonly nounwind willreturn }
+attributes #2 = { nounwind }
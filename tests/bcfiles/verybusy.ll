@@ -0,0 +1,40 @@
+; Hand-written functions for testing very busy expressions, with the same
+; expressions computed in different blocks
+
+; %a + %b is computed on both arms, but %a * 2 on only one
+define i32 @hoist(i32 %a, i32 %b, i1 %c) {
+entry:
+  br i1 %c, label %left, label %right
+
+left:
+  %x = add i32 %a, %b
+  %m = mul i32 %a, 2
+  br label %join
+
+right:
+  %y = add i32 %a, %b
+  br label %join
+
+join:
+  %p = phi i32 [ %x, %left ], [ %y, %right ]
+  %z = sub i32 %a, %b
+  %r = add i32 %p, %z
+  ret i32 %r
+}
+
+; %a << 1 is computed on every iteration, but %i + %a depends on the phi
+define i32 @loop(i32 %n, i32 %a) {
+entry:
+  br label %loop
+
+loop:
+  %i = phi i32 [ 0, %entry ], [ %i.next, %loop ]
+  %s = shl i32 %a, 1
+  %t = add i32 %i, %a
+  %i.next = add i32 %i, 1
+  %done = icmp sge i32 %i.next, %n
+  br i1 %done, label %exit, label %loop
+
+exit:
+  ret i32 %t
+}
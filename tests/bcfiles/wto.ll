@@ -0,0 +1,44 @@
+; Hand-written functions for testing weak topological orderings, with the
+; shapes of their loops fixed
+
+; an outer loop containing a self-loop
+define void @nested(i32 %n) {
+entry:
+  br label %outer
+
+outer:
+  %i = phi i32 [ 0, %entry ], [ %i.next, %latch ]
+  br label %inner
+
+inner:
+  %j = phi i32 [ 0, %outer ], [ %j.next, %inner ]
+  %j.next = add i32 %j, 1
+  %inner.done = icmp sge i32 %j.next, %n
+  br i1 %inner.done, label %latch, label %inner
+
+latch:
+  %i.next = add i32 %i, 1
+  %outer.done = icmp sge i32 %i.next, %n
+  br i1 %outer.done, label %exit, label %outer
+
+exit:
+  ret void
+}
+
+; a cycle between %a and %b, which can each be entered from %entry
+define void @irreducible(i1 %c, i1 %d) {
+entry:
+  br i1 %c, label %a, label %b
+
+a:
+  br label %b
+
+b:
+  br i1 %d, label %a, label %exit
+
+exit:
+  ret void
+
+dead:
+  br label %a
+}
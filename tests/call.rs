@@ -1,6 +1,9 @@
+#![allow(clippy::bool_assert_comparison, clippy::redundant_static_lifetimes)]
+
 use itertools::Itertools;
-use llvm_ir::Module;
+use llvm_ir::{Module, Name};
 use llvm_ir_analysis::*;
+use std::collections::HashSet;
 
 fn init_logging() {
     // capture log messages with test harness
@@ -14,14 +17,37 @@ fn init_logging() {
 const CALL_BC_PATH: &'static str = "tests/bcfiles/call.bc";
 const FUNCTIONPTR_BC_PATH: &'static str = "tests/bcfiles/functionptr.bc";
 const CROSSMOD_BC_PATH: &'static str = "tests/bcfiles/crossmod.bc";
+const NUMBERED_BC_PATH: &'static str = "tests/bcfiles/numbered.bc";
+/// numberedother.ll is hand-written, with a numbered function like those in
+/// numbered.c
+const NUMBEREDOTHER_BC_PATH: &'static str = "tests/bcfiles/numberedother.bc";
+const BITCAST_BC_PATH: &'static str = "tests/bcfiles/bitcast.bc";
+const ALIAS_BC_PATH: &'static str = "tests/bcfiles/alias.bc";
+/// aliasnumbered.ll is hand-written, with an alias of a numbered function
+const ALIASNUMBERED_BC_PATH: &'static str = "tests/bcfiles/aliasnumbered.bc";
+const FPTRTABLE_BC_PATH: &'static str = "tests/bcfiles/fptrtable.bc";
+/// fptrtablelocal.ll is hand-written, with an internal table named like one in
+/// fptrtable.c
+const FPTRTABLELOCAL_BC_PATH: &'static str = "tests/bcfiles/fptrtablelocal.bc";
+const CALLBACK_BC_PATH: &'static str = "tests/bcfiles/callback.bc";
+const THREAD_BC_PATH: &'static str = "tests/bcfiles/thread.bc";
+const STATEPOINT_BC_PATH: &'static str = "tests/bcfiles/statepoint.bc";
+const DEADCODE_BC_PATH: &'static str = "tests/bcfiles/deadcode.bc";
 
 /// Assert that each entry in `actual` starts with the prefix given by the
 /// corresponding entry in `expected`
 #[track_caller]
-fn assert_vec_entries(actual: &[&str], expected: &[&str]) {
-    assert_eq!(actual.len(), expected.len(), "\n  actual: {actual:?}\n  expected: {expected:?}");
+fn assert_vec_entries(actual: &[FunctionName], expected: &[&str]) {
+    assert_eq!(
+        actual.len(),
+        expected.len(),
+        "\n  actual: {actual:?}\n  expected: {expected:?}"
+    );
     for (a, e) in actual.iter().zip(expected.iter()) {
-        assert!(a.starts_with(e), "\n  actual: {a:?}\n  expected prefix: {e:?}");
+        assert!(
+            a.to_string().starts_with(e),
+            "\n  actual: {a:?}\n  expected prefix: {e:?}"
+        );
     }
 }
 
@@ -33,7 +59,7 @@ fn call_graph() {
     let analysis = ModuleAnalysis::new(&module);
     let callgraph = analysis.call_graph();
 
-    let callers: Vec<&str> = callgraph.callers("simple_callee").sorted().collect();
+    let callers: Vec<FunctionName> = callgraph.callers("simple_callee").sorted().collect();
     assert_vec_entries(
         &callers,
         &[
@@ -42,88 +68,82 @@ fn call_graph() {
             "recursive_and_normal_caller",
             "simple_caller",
             "twice_caller",
-        ]
+        ],
     );
-    let callees: Vec<&str> = callgraph.callees("simple_callee").sorted().collect();
+    let callees: Vec<FunctionName> = callgraph.callees("simple_callee").sorted().collect();
     assert!(callees.is_empty());
 
-    let callers: Vec<&str> = callgraph.callers("simple_caller").sorted().collect();
+    let callers: Vec<FunctionName> = callgraph.callers("simple_caller").sorted().collect();
     assert_vec_entries(&callers, &["nested_caller"]);
-    let callees: Vec<&str> = callgraph.callees("simple_caller").sorted().collect();
+    let callees: Vec<FunctionName> = callgraph.callees("simple_caller").sorted().collect();
     assert_vec_entries(&callees, &["simple_callee"]);
 
-    let callers: Vec<&str> = callgraph.callers("conditional_caller").sorted().collect();
+    let callers: Vec<FunctionName> = callgraph.callers("conditional_caller").sorted().collect();
     assert!(callers.is_empty());
-    let callees: Vec<&str> = callgraph.callees("conditional_caller").sorted().collect();
+    let callees: Vec<FunctionName> = callgraph.callees("conditional_caller").sorted().collect();
     assert_vec_entries(&callees, &["simple_callee"]);
 
-    let callers: Vec<&str> = callgraph.callers("twice_caller").sorted().collect();
+    let callers: Vec<FunctionName> = callgraph.callers("twice_caller").sorted().collect();
     assert!(callers.is_empty());
-    let callees: Vec<&str> = callgraph.callees("twice_caller").sorted().collect();
+    let callees: Vec<FunctionName> = callgraph.callees("twice_caller").sorted().collect();
     assert_vec_entries(&callees, &["simple_callee"]);
 
-    let callers: Vec<&str> = callgraph.callers("nested_caller").sorted().collect();
+    let callers: Vec<FunctionName> = callgraph.callers("nested_caller").sorted().collect();
     assert!(callers.is_empty());
-    let callees: Vec<&str> = callgraph.callees("nested_caller").sorted().collect();
+    let callees: Vec<FunctionName> = callgraph.callees("nested_caller").sorted().collect();
     assert_vec_entries(&callees, &["simple_caller"]);
 
-    let callers: Vec<&str> = callgraph.callers("callee_with_loop").sorted().collect();
+    let callers: Vec<FunctionName> = callgraph.callers("callee_with_loop").sorted().collect();
     assert_vec_entries(&callers, &["caller_of_loop"]);
-    let callees: Vec<&str> = callgraph.callees("callee_with_loop").sorted().collect();
-    assert_vec_entries(
-        &callees,
-        &["llvm.lifetime.end", "llvm.lifetime.start"]
-    );
+    let callees: Vec<FunctionName> = callgraph.callees("callee_with_loop").sorted().collect();
+    assert_vec_entries(&callees, &["llvm.lifetime.end", "llvm.lifetime.start"]);
 
-    let callers: Vec<&str> = callgraph.callers("caller_of_loop").sorted().collect();
+    let callers: Vec<FunctionName> = callgraph.callers("caller_of_loop").sorted().collect();
     assert!(callers.is_empty());
-    let callees: Vec<&str> = callgraph.callees("caller_of_loop").sorted().collect();
+    let callees: Vec<FunctionName> = callgraph.callees("caller_of_loop").sorted().collect();
     assert_vec_entries(&callees, &["callee_with_loop"]);
 
-    let callers: Vec<&str> = callgraph.callers("caller_with_loop").sorted().collect();
+    let callers: Vec<FunctionName> = callgraph.callers("caller_with_loop").sorted().collect();
     assert!(callers.is_empty());
-    let callees: Vec<&str> = callgraph.callees("caller_with_loop").sorted().collect();
+    let callees: Vec<FunctionName> = callgraph.callees("caller_with_loop").sorted().collect();
     assert_vec_entries(
         &callees,
         &[
             "llvm.lifetime.end.p0",
             "llvm.lifetime.start.p0",
-            "simple_callee"
-        ]
+            "simple_callee",
+        ],
     );
 
-    let callers: Vec<&str> = callgraph.callers("recursive_simple").sorted().collect();
+    let callers: Vec<FunctionName> = callgraph.callers("recursive_simple").sorted().collect();
     assert_vec_entries(&callers, &["recursive_simple"]);
-    let callees: Vec<&str> = callgraph.callees("recursive_simple").sorted().collect();
+    let callees: Vec<FunctionName> = callgraph.callees("recursive_simple").sorted().collect();
     assert_vec_entries(&callees, &["recursive_simple"]);
 
-    let callers: Vec<&str> = callgraph.callers("recursive_double").sorted().collect();
+    let callers: Vec<FunctionName> = callgraph.callers("recursive_double").sorted().collect();
     assert_vec_entries(&callers, &["recursive_double"]);
-    let callees: Vec<&str> = callgraph.callees("recursive_double").sorted().collect();
+    let callees: Vec<FunctionName> = callgraph.callees("recursive_double").sorted().collect();
     assert_vec_entries(&callees, &["recursive_double"]);
 
-    let callers: Vec<&str> = callgraph
+    let callers: Vec<FunctionName> = callgraph
         .callers("recursive_and_normal_caller")
         .sorted()
         .collect();
     assert_vec_entries(&callers, &["recursive_and_normal_caller"]);
-    let callees: Vec<&str> = callgraph
+    let callees: Vec<FunctionName> = callgraph
         .callees("recursive_and_normal_caller")
         .sorted()
         .collect();
-    assert_vec_entries(
-        &callees,
-        &["recursive_and_normal_caller", "simple_callee"]
-    );
+    assert_vec_entries(&callees, &["recursive_and_normal_caller", "simple_callee"]);
 
-    let callers: Vec<&str> = callgraph.callers("mutually_recursive_a").sorted().collect();
+    let callers: Vec<FunctionName> = callgraph.callers("mutually_recursive_a").sorted().collect();
     assert_vec_entries(&callers, &["mutually_recursive_b"]);
-    let callees: Vec<&str> = callgraph.callees("mutually_recursive_a").sorted().collect();
+    let callees: Vec<FunctionName> = callgraph.callees("mutually_recursive_a").sorted().collect();
     assert_vec_entries(&callees, &["mutually_recursive_b"]);
 
-    let callers: Vec<&str> = callgraph.callers("mutually_recursive_b").sorted().collect();
+    let callers: Vec<FunctionName> = callgraph.callers("mutually_recursive_b").sorted().collect();
     assert_vec_entries(&callers, &["mutually_recursive_a"]);
-    let callees: Vec<&str> = callgraph.callees("mutually_recursive_b").sorted().collect();
+    let callees: Vec<FunctionName> = callgraph.callees("mutually_recursive_b").sorted().collect();
     assert_vec_entries(&callees, &["mutually_recursive_a"]);
 }
 
@@ -136,7 +156,7 @@ fn functionptr_call_graph() {
     let fbt = analysis.functions_by_type();
     let callgraph = analysis.call_graph();
 
-    let footype_functions: Vec<&str> = fbt
+    let footype_functions: Vec<FunctionName> = fbt
         .functions_with_type(&module.types.func_type(
             module.types.i32(),
             vec![module.types.i32(), module.types.i32()],
@@ -146,34 +166,34 @@ fn functionptr_call_graph() {
         .collect();
     assert_vec_entries(&footype_functions, &["bar", "foo"]);
 
-    let callers: Vec<&str> = callgraph.callers("foo").sorted().collect();
+    let callers: Vec<FunctionName> = callgraph.callers("foo").sorted().collect();
     assert_vec_entries(&callers, &["calls_fptr", "calls_through_struct"]);
-    let callees: Vec<&str> = callgraph.callees("foo").sorted().collect();
+    let callees: Vec<FunctionName> = callgraph.callees("foo").sorted().collect();
     assert!(callees.is_empty());
 
-    let callers: Vec<&str> = callgraph.callers("bar").sorted().collect();
+    let callers: Vec<FunctionName> = callgraph.callers("bar").sorted().collect();
     assert_vec_entries(&callers, &["calls_fptr", "calls_through_struct"]);
-    let callees: Vec<&str> = callgraph.callees("bar").sorted().collect();
+    let callees: Vec<FunctionName> = callgraph.callees("bar").sorted().collect();
     assert!(callees.is_empty());
 
-    let callers: Vec<&str> = callgraph.callers("calls_fptr").sorted().collect();
+    let callers: Vec<FunctionName> = callgraph.callers("calls_fptr").sorted().collect();
     assert_vec_entries(&callers, &["fptr_driver"]);
-    let callees: Vec<&str> = callgraph.callees("calls_fptr").sorted().collect();
+    let callees: Vec<FunctionName> = callgraph.callees("calls_fptr").sorted().collect();
     assert_vec_entries(&callees, &["bar", "foo"]);
 
-    let callers: Vec<&str> = callgraph.callers("get_function_ptr").sorted().collect();
+    let callers: Vec<FunctionName> = callgraph.callers("get_function_ptr").sorted().collect();
     assert_vec_entries(&callers, &["fptr_driver", "struct_driver"]);
-    let callees: Vec<&str> = callgraph.callees("get_function_ptr").sorted().collect();
+    let callees: Vec<FunctionName> = callgraph.callees("get_function_ptr").sorted().collect();
     assert!(callees.is_empty());
 
-    let callers: Vec<&str> = callgraph.callers("calls_through_struct").sorted().collect();
+    let callers: Vec<FunctionName> = callgraph.callers("calls_through_struct").sorted().collect();
     assert_vec_entries(&callers, &["struct_driver"]);
-    let callees: Vec<&str> = callgraph.callees("calls_through_struct").sorted().collect();
+    let callees: Vec<FunctionName> = callgraph.callees("calls_through_struct").sorted().collect();
     assert_vec_entries(&callees, &["bar", "foo"]);
 
-    let callers: Vec<&str> = callgraph.callers("struct_driver").sorted().collect();
+    let callers: Vec<FunctionName> = callgraph.callers("struct_driver").sorted().collect();
     assert!(callers.is_empty());
-    let callees: Vec<&str> = callgraph.callees("struct_driver").sorted().collect();
+    let callees: Vec<FunctionName> = callgraph.callees("struct_driver").sorted().collect();
     assert_vec_entries(
         &callees,
         &[
@@ -182,7 +202,7 @@ fn functionptr_call_graph() {
             "llvm.lifetime.end",
             "llvm.lifetime.start",
             "llvm.memset",
-        ]
+        ],
     );
 }
 
@@ -198,25 +218,25 @@ fn crossmod_call_graph() {
     let callgraph = analysis.call_graph();
 
     // this function isn't involved in cross-module calls, it should still have the same results
-    let callers: Vec<&str> = callgraph.callers("conditional_caller").sorted().collect();
+    let callers: Vec<FunctionName> = callgraph.callers("conditional_caller").sorted().collect();
     assert!(callers.is_empty());
-    let callees: Vec<&str> = callgraph.callees("conditional_caller").sorted().collect();
+    let callees: Vec<FunctionName> = callgraph.callees("conditional_caller").sorted().collect();
     assert_vec_entries(&callees, &["simple_callee"]);
 
     // this function also isn't involved in cross-module calls; it sits in the other module
-    let callers: Vec<&str> = callgraph
+    let callers: Vec<FunctionName> = callgraph
         .callers("cross_module_nested_near_caller")
         .sorted()
         .collect();
     assert!(callers.is_empty());
-    let callees: Vec<&str> = callgraph
+    let callees: Vec<FunctionName> = callgraph
         .callees("cross_module_nested_near_caller")
         .sorted()
         .collect();
     assert_vec_entries(&callees, &["cross_module_simple_caller"]);
 
     // this function is called cross-module
-    let callers: Vec<&str> = callgraph.callers("simple_callee").sorted().collect();
+    let callers: Vec<FunctionName> = callgraph.callers("simple_callee").sorted().collect();
     assert_vec_entries(
         &callers,
         &[
@@ -227,8 +247,1137 @@ fn crossmod_call_graph() {
             "recursive_and_normal_caller",
             "simple_caller",
             "twice_caller",
+        ],
+    );
+    let callees: Vec<FunctionName> = callgraph.callees("simple_callee").sorted().collect();
+    assert!(callees.is_empty());
+}
+
+#[test]
+fn reachable_excluding() {
+    init_logging();
+    let module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    assert!(callgraph.is_reachable_excluding("nested_caller", "simple_callee", &[]));
+    assert!(!callgraph.is_reachable_excluding(
+        "nested_caller",
+        "simple_callee",
+        &["simple_caller".into()]
+    ));
+    assert!(callgraph.is_reachable_excluding("nested_caller", "nested_caller", &[]));
+    assert!(!callgraph.is_reachable_excluding("simple_callee", "simple_caller", &[]));
+    assert!(!callgraph.is_reachable_excluding(
+        "nested_caller",
+        "simple_callee",
+        &["simple_callee".into()]
+    ));
+
+    let reachable: Vec<FunctionName> = callgraph
+        .reachable_excluding("mutually_recursive_a", &[])
+        .into_iter()
+        .sorted()
+        .collect();
+    assert_vec_entries(
+        &reachable,
+        &["mutually_recursive_a", "mutually_recursive_b"],
+    );
+    let reachable: Vec<FunctionName> = callgraph
+        .reachable_excluding("nested_caller", &["simple_callee".into()])
+        .into_iter()
+        .sorted()
+        .collect();
+    assert_vec_entries(&reachable, &["nested_caller", "simple_caller"]);
+    assert!(callgraph
+        .reachable_excluding("nested_caller", &["nested_caller".into()])
+        .is_empty());
+
+    let module = Module::from_bc_path(FUNCTIONPTR_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    assert!(callgraph.is_reachable_excluding("struct_driver", "foo", &["calls_fptr".into()]));
+    assert!(!callgraph.is_reachable_excluding(
+        "struct_driver",
+        "foo",
+        &["calls_through_struct".into()]
+    ));
+}
+
+#[test]
+fn call_block_costs() {
+    init_logging();
+    let module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("simple_caller");
+
+    // calls of non-intrinsic functions incur the call penalty
+    let model = CostModel::default();
+    assert_eq!(
+        fn_analysis.block_costs().function_cost(),
+        1 + model.call_penalty + 1
+    );
+    let model = CostModel {
+        call_penalty: 0,
+        ..CostModel::default()
+    };
+    assert_eq!(
+        fn_analysis.block_costs_with_model(&model).function_cost(),
+        2
+    );
+}
+
+#[test]
+fn call_sites() {
+    init_logging();
+    let module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    let bb1_name = Name::from(1);
+    let bb4_name = Name::from(4);
+    let sites: Vec<&CallSite> = callgraph
+        .call_sites("twice_caller", "simple_callee")
+        .collect();
+    assert_eq!(
+        sites,
+        vec![
+            &CallSite {
+                caller: "twice_caller".into(),
+                block: &bb1_name,
+                index: 0,
+                kind: CallSiteKind::Call,
+                edge_kind: CallEdgeKind::Direct,
+                is_tail_call: true,
+            },
+            &CallSite {
+                caller: "twice_caller".into(),
+                block: &bb1_name,
+                index: 1,
+                kind: CallSiteKind::Call,
+                edge_kind: CallEdgeKind::Direct,
+                is_tail_call: true,
+            },
         ]
     );
-    let callees: Vec<&str> = callgraph.callees("simple_callee").sorted().collect();
+
+    let sites: Vec<&CallSite> = callgraph
+        .call_sites("conditional_caller", "simple_callee")
+        .collect();
+    assert_eq!(sites.len(), 1);
+    assert_eq!(sites[0].block, &bb4_name);
+    assert_eq!(sites[0].index, 0);
+
+    assert_eq!(
+        callgraph
+            .call_sites("simple_callee", "twice_caller")
+            .count(),
+        0
+    );
+
+    // a call through a function pointer is a call site for each possible target
+    let module = Module::from_bc_path(FUNCTIONPTR_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+    let foo_sites: Vec<&CallSite> = callgraph.call_sites("calls_fptr", "foo").collect();
+    let bar_sites: Vec<&CallSite> = callgraph.call_sites("calls_fptr", "bar").collect();
+    assert_eq!(foo_sites.len(), 1);
+    assert_eq!(foo_sites, bar_sites);
+}
+
+#[test]
+fn call_graph_sccs() {
+    init_logging();
+    let module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    let sccs = callgraph.sccs();
+    assert!(sccs
+        .iter()
+        .any(|scc| scc == &["mutually_recursive_a", "mutually_recursive_b"]));
+    assert!(sccs.iter().any(|scc| scc == &["recursive_simple"]));
+    // each function is in exactly one SCC
+    let funcs: Vec<FunctionName> = sccs.iter().flatten().copied().collect();
+    assert_eq!(funcs.len(), funcs.iter().unique().count());
+    assert!(module
+        .functions
+        .iter()
+        .all(|f| funcs.contains(&f.name.as_str().into())));
+
+    // callees come before their callers
+    let position = |func: &str| {
+        sccs.iter()
+            .position(|scc| scc.iter().any(|&f| f == func))
+            .unwrap()
+    };
+    assert!(position("simple_callee") < position("simple_caller"));
+    assert!(position("simple_caller") < position("nested_caller"));
+
+    let condensation = callgraph.condensation();
+    assert_eq!(condensation.num_sccs(), sccs.len());
+    let a = condensation.scc_index("mutually_recursive_a");
+    assert_eq!(a, condensation.scc_index("mutually_recursive_b"));
+    assert_eq!(
+        condensation.scc(a),
+        &["mutually_recursive_a", "mutually_recursive_b"]
+    );
+    assert_eq!(condensation.succs(a).count(), 0);
+    assert_eq!(condensation.preds(a).count(), 0);
+
+    // self-recursion doesn't produce an edge in the condensation
+    let recursive = condensation.scc_index("recursive_and_normal_caller");
+    let callee = condensation.scc_index("simple_callee");
+    let succs: Vec<usize> = condensation.succs(recursive).collect();
+    assert_eq!(succs, vec![callee]);
+    let preds: Vec<FunctionName> = condensation
+        .preds(callee)
+        .flat_map(|idx| condensation.scc(idx).iter().copied())
+        .sorted()
+        .collect();
+    assert_vec_entries(
+        &preds,
+        &[
+            "caller_with_loop",
+            "conditional_caller",
+            "recursive_and_normal_caller",
+            "simple_caller",
+            "twice_caller",
+        ],
+    );
+}
+
+#[test]
+fn transitive_callers_and_callees() {
+    init_logging();
+    let module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    let callees: Vec<FunctionName> = callgraph.transitive_callees("nested_caller").collect();
+    assert_vec_entries(&callees, &["simple_caller", "simple_callee"]);
+    let callers: Vec<FunctionName> = callgraph.transitive_callers("simple_caller").collect();
+    assert_vec_entries(&callers, &["nested_caller"]);
+    let callers: Vec<FunctionName> = callgraph
+        .transitive_callers("simple_callee")
+        .sorted()
+        .collect();
+    assert_vec_entries(
+        &callers,
+        &[
+            "caller_with_loop",
+            "conditional_caller",
+            "nested_caller",
+            "recursive_and_normal_caller",
+            "simple_caller",
+            "twice_caller",
+        ],
+    );
+    assert_eq!(callgraph.transitive_callees("simple_callee").count(), 0);
+
+    // recursive functions are included in their own closure
+    let callees: Vec<FunctionName> = callgraph
+        .transitive_callees("mutually_recursive_a")
+        .collect();
+    assert_vec_entries(&callees, &["mutually_recursive_b", "mutually_recursive_a"]);
+    let callers: Vec<FunctionName> = callgraph.transitive_callers("recursive_simple").collect();
+    assert_vec_entries(&callers, &["recursive_simple"]);
+}
+
+#[test]
+fn reachability_index() {
+    init_logging();
+    let module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+    let index = callgraph.reachability_index();
+
+    assert!(index.may_call_transitively("nested_caller", "simple_callee"));
+    assert!(!index.may_call_transitively("simple_callee", "nested_caller"));
+    assert!(!index.may_call_transitively("simple_callee", "simple_callee"));
+    assert!(index.may_call_transitively("mutually_recursive_a", "mutually_recursive_a"));
+    assert!(index.may_call_transitively("recursive_simple", "recursive_simple"));
+
+    // agrees with `transitive_callees()` everywhere
+    let functions: Vec<FunctionName> = callgraph.inner().nodes().collect();
+    assert_eq!(index.num_functions(), functions.len());
+    for &caller in &functions {
+        let callees: HashSet<FunctionName> = callgraph.transitive_callees(caller).collect();
+        for &callee in &functions {
+            assert_eq!(
+                index.may_call_transitively(caller, callee),
+                callees.contains(&callee),
+                "{} -> {}",
+                caller,
+                callee
+            );
+        }
+    }
+}
+
+#[test]
+fn direct_and_indirect_edges() {
+    init_logging();
+    let module = Module::from_bc_path(FUNCTIONPTR_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    assert_eq!(
+        callgraph.edge_kind("calls_fptr", "foo"),
+        Some(CallEdgeKind::Indirect)
+    );
+    assert_eq!(
+        callgraph.edge_kind("fptr_driver", "calls_fptr"),
+        Some(CallEdgeKind::Direct)
+    );
+    assert_eq!(callgraph.edge_kind("foo", "calls_fptr"), None);
+    let sites: Vec<&CallSite> = callgraph.call_sites("calls_fptr", "bar").collect();
+    assert_eq!(sites.len(), 1);
+    assert_eq!(sites[0].edge_kind, CallEdgeKind::Indirect);
+
+    let callees: Vec<FunctionName> = callgraph.direct_callees("calls_fptr").collect();
     assert!(callees.is_empty());
+    let callers: Vec<FunctionName> = callgraph.direct_callers("foo").collect();
+    assert!(callers.is_empty());
+    let callees: Vec<FunctionName> = callgraph.direct_callees("struct_driver").sorted().collect();
+    assert_vec_entries(
+        &callees,
+        &[
+            "calls_through_struct",
+            "get_function_ptr",
+            "llvm.lifetime.end",
+            "llvm.lifetime.start",
+            "llvm.memset",
+        ],
+    );
+    let callers: Vec<FunctionName> = callgraph
+        .direct_callers("get_function_ptr")
+        .sorted()
+        .collect();
+    assert_vec_entries(&callers, &["fptr_driver", "struct_driver"]);
+}
+
+#[test]
+fn call_graph_without_intrinsics() {
+    init_logging();
+    let module = Module::from_bc_path(FUNCTIONPTR_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph().without_intrinsics();
+
+    let callees: Vec<FunctionName> = callgraph.callees("struct_driver").sorted().collect();
+    assert_vec_entries(&callees, &["calls_through_struct", "get_function_ptr"]);
+    let callees: Vec<FunctionName> = callgraph.callees("calls_fptr").sorted().collect();
+    assert_vec_entries(&callees, &["bar", "foo"]);
+    assert_eq!(callgraph.call_sites("fptr_driver", "calls_fptr").count(), 1);
+
+    let callgraph = callgraph.filtered(|func| func != "foo");
+    let callees: Vec<FunctionName> = callgraph.callees("calls_fptr").sorted().collect();
+    assert_vec_entries(&callees, &["bar"]);
+}
+
+#[test]
+fn roots_and_leaves() {
+    init_logging();
+    let module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    let roots: Vec<FunctionName> = callgraph.roots().sorted().collect();
+    assert!(roots.contains(&"nested_caller".into()));
+    assert!(roots.contains(&"recursive_simple".into()));
+    assert!(!roots.contains(&"simple_caller".into()));
+    assert!(!roots.contains(&"mutually_recursive_a".into()));
+    assert!(roots
+        .iter()
+        .all(|&f| callgraph.callers(f).all(|caller| caller == f)));
+
+    let leaves: Vec<FunctionName> = callgraph.leaves().sorted().collect();
+    assert!(leaves.contains(&"simple_callee".into()));
+    assert!(leaves.contains(&"recursive_double".into()));
+    assert!(leaves
+        .iter()
+        .any(|f| f.to_string().starts_with("llvm.lifetime.start")));
+    assert!(!leaves.contains(&"simple_caller".into()));
+    assert!(!leaves.contains(&"mutually_recursive_b".into()));
+}
+
+#[test]
+fn recursion() {
+    init_logging();
+    let module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    assert!(callgraph.is_directly_recursive("recursive_simple"));
+    assert!(callgraph.is_recursive("recursive_simple"));
+    assert!(!callgraph.is_directly_recursive("mutually_recursive_a"));
+    assert!(callgraph.is_recursive("mutually_recursive_a"));
+    assert!(!callgraph.is_recursive("simple_caller"));
+    assert!(!callgraph.is_recursive("simple_callee"));
+
+    let cycles: Vec<Vec<FunctionName>> =
+        callgraph.recursion_cycles().into_iter().sorted().collect();
+    assert_eq!(
+        cycles,
+        vec![
+            vec!["mutually_recursive_a", "mutually_recursive_b"],
+            vec!["recursive_and_normal_caller"],
+            vec!["recursive_double"],
+            vec!["recursive_not_tail"],
+            vec!["recursive_simple"],
+        ]
+    );
+}
+
+#[test]
+fn call_counts() {
+    init_logging();
+    let module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    assert_eq!(callgraph.call_count("twice_caller", "simple_callee"), 2);
+    assert_eq!(callgraph.call_count("simple_caller", "simple_callee"), 1);
+    assert_eq!(callgraph.call_count("simple_callee", "simple_caller"), 0);
+    assert_eq!(callgraph.call_count("nested_caller", "simple_callee"), 0);
+}
+
+#[test]
+fn numbered_functions_call_graph() {
+    init_logging();
+    let module = Module::from_bc_path(NUMBERED_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    // `@0` is `second()` and `@1` is `first()`, which `second()` calls
+    let zero = FunctionName::Numbered {
+        module: &module.name,
+        n: 0,
+    };
+    let one = FunctionName::Numbered {
+        module: &module.name,
+        n: 1,
+    };
+    let callees: Vec<FunctionName> = callgraph.callees("entry").sorted().collect();
+    assert_eq!(callees, vec![zero, one]);
+    assert_eq!(callees, vec!["@0", "@1"]);
+    assert_eq!(callgraph.call_count("entry", zero), 1);
+    assert_eq!(
+        callgraph.edge_kind("entry", one),
+        Some(CallEdgeKind::Direct)
+    );
+    let callees: Vec<FunctionName> = callgraph.callees(zero).collect();
+    assert_eq!(callees, vec![one]);
+    assert_eq!(callgraph.callees(one).count(), 0);
+    let callers: Vec<FunctionName> = callgraph.callers(one).sorted().collect();
+    assert_eq!(callers, vec![zero, "entry".into()]);
+    let callers: Vec<FunctionName> = callgraph.callers(zero).collect();
+    assert_eq!(callers, vec!["entry"]);
+    assert!(!callgraph.is_recursive(zero));
+    assert!(!callgraph.is_recursive(one));
+
+    // the numbered names work with the rest of the analyses too
+    assert!(analysis.try_fn_analysis(zero).is_ok());
+    assert!(analysis.try_fn_analysis("@0").is_err());
+    assert!(analysis.try_fn_analysis("").is_err());
+
+    // `@0` in another module is a different function
+    let modules = [
+        Module::from_bc_path(NUMBERED_BC_PATH)
+            .unwrap_or_else(|e| panic!("Failed to parse module: {}", e)),
+        Module::from_bc_path(NUMBEREDOTHER_BC_PATH)
+            .unwrap_or_else(|e| panic!("Failed to parse module: {}", e)),
+    ];
+    let analysis = CrossModuleAnalysis::new(&modules);
+    let callgraph = analysis.call_graph();
+    let zero = FunctionName::Numbered {
+        module: &modules[0].name,
+        n: 0,
+    };
+    let other_zero = FunctionName::Numbered {
+        module: &modules[1].name,
+        n: 0,
+    };
+    assert_ne!(zero, other_zero);
+    let callers: Vec<FunctionName> = callgraph.callers(zero).collect();
+    assert_eq!(callers, vec!["entry"]);
+    let callers: Vec<FunctionName> = callgraph.callers(other_zero).collect();
+    assert_eq!(callers, vec!["other_entry"]);
+    assert_eq!(callgraph.callees(other_zero).count(), 0);
+    assert_eq!(
+        callgraph.defining_module(other_zero),
+        Some(modules[1].name.as_str())
+    );
+    assert!(analysis.try_fn_analysis(other_zero).is_ok());
+}
+
+#[test]
+fn declarations() {
+    init_logging();
+    let module = Module::from_bc_path(FUNCTIONPTR_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    assert!(!callgraph.is_declaration("struct_driver"));
+    assert!(callgraph.is_declaration("llvm.memset.p0i8.i64"));
+    let decls: Vec<FunctionName> = callgraph.declarations().sorted().collect();
+    assert_vec_entries(
+        &decls,
+        &["llvm.lifetime.end", "llvm.lifetime.start", "llvm.memset"],
+    );
+    let decls: Vec<FunctionName> = callgraph.without_intrinsics().declarations().collect();
+    assert!(decls.is_empty());
+
+    // functions declared in one module but defined in another are not
+    // declarations
+    let modules = [
+        Module::from_bc_path(CALL_BC_PATH)
+            .unwrap_or_else(|e| panic!("Failed to parse module: {}", e)),
+        Module::from_bc_path(CROSSMOD_BC_PATH)
+            .unwrap_or_else(|e| panic!("Failed to parse module: {}", e)),
+    ];
+    let analysis = CrossModuleAnalysis::new(&modules);
+    let callgraph = analysis.call_graph();
+    assert!(!callgraph.is_declaration("simple_callee"));
+    assert!(!callgraph.is_declaration("simple_caller"));
+    // these are defined in a module which we don't analyze here
+    let decls: Vec<FunctionName> = callgraph
+        .without_intrinsics()
+        .declarations()
+        .sorted()
+        .collect();
+    assert_vec_entries(&decls, &["modify_global", "read_global"]);
+}
+
+#[test]
+fn bottom_up_sccs() {
+    init_logging();
+    let module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    // every callee outside an SCC has been visited before the SCC itself
+    let mut visited: Vec<FunctionName> = vec![];
+    for scc in callgraph.sccs_bottom_up() {
+        for &func in &scc {
+            assert!(callgraph
+                .callees(func)
+                .all(|callee| scc.contains(&callee) || visited.contains(&callee)));
+        }
+        visited.extend(scc);
+    }
+    assert_eq!(visited.len(), visited.iter().unique().count());
+
+    // and every caller outside an SCC has been visited before it top-down
+    let mut visited: Vec<FunctionName> = vec![];
+    for scc in callgraph.sccs_top_down() {
+        for &func in &scc {
+            assert!(callgraph
+                .callers(func)
+                .all(|caller| scc.contains(&caller) || visited.contains(&caller)));
+        }
+        visited.extend(scc);
+    }
+    let pos = |f: &str| visited.iter().position(|&v| v == f).unwrap();
+    assert!(pos("nested_caller") < pos("simple_caller"));
+    assert!(pos("simple_caller") < pos("simple_callee"));
+}
+
+#[test]
+fn shortest_call_path() {
+    init_logging();
+    let module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    assert_eq!(
+        callgraph
+            .shortest_path("nested_caller", "simple_callee")
+            .unwrap(),
+        vec!["nested_caller", "simple_caller", "simple_callee"]
+    );
+    assert_eq!(
+        callgraph
+            .shortest_path("simple_caller", "simple_caller")
+            .unwrap(),
+        vec!["simple_caller"]
+    );
+    assert_eq!(
+        callgraph.shortest_path("simple_callee", "nested_caller"),
+        None
+    );
+    assert_eq!(
+        callgraph
+            .shortest_path("mutually_recursive_b", "mutually_recursive_a")
+            .unwrap(),
+        vec!["mutually_recursive_b", "mutually_recursive_a"]
+    );
+}
+
+#[test]
+fn call_graph_as_petgraph() {
+    init_logging();
+    let module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    let inner = callgraph.inner();
+    assert!(inner.contains_edge("simple_caller".into(), "simple_callee".into()));
+    assert_eq!(
+        inner
+            .edge_weight("twice_caller".into(), "simple_callee".into())
+            .map(Vec::len),
+        Some(2)
+    );
+
+    let (graph, indices) = callgraph.to_graph();
+    assert_eq!(graph.node_count(), inner.node_count());
+    assert_eq!(graph.edge_count(), inner.edge_count());
+    let caller = indices[&FunctionName::Named("nested_caller")];
+    let callee = indices[&FunctionName::Named("simple_callee")];
+    assert_eq!(graph[caller], "nested_caller");
+    assert!(petgraph::algo::has_path_connecting(
+        &graph, caller, callee, None
+    ));
+    assert!(!petgraph::algo::has_path_connecting(
+        &graph, callee, caller, None
+    ));
+}
+
+#[test]
+fn bitcast_callee() {
+    init_logging();
+    let module = Module::from_bc_path(BITCAST_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    // the call to the unprototyped function goes through a bitcast of the
+    // function, but is still a direct call
+    let callees: Vec<FunctionName> = callgraph.callees("calls_unprototyped").sorted().collect();
+    assert_eq!(callees, vec!["prototyped", "unprototyped"]);
+    assert_eq!(
+        callgraph.edge_kind("calls_unprototyped", "unprototyped"),
+        Some(CallEdgeKind::Direct)
+    );
+    let callers: Vec<FunctionName> = callgraph.callers("unprototyped").collect();
+    assert_eq!(callers, vec!["calls_unprototyped"]);
+}
+
+#[test]
+fn alias_and_ifunc_callees() {
+    init_logging();
+    let module = Module::from_bc_path(ALIAS_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    // both calls go through (chains of) aliases of `real`
+    let callees: Vec<FunctionName> = callgraph.callees("calls_alias").collect();
+    assert_eq!(callees, vec!["real"]);
+    assert_eq!(callgraph.call_count("calls_alias", "real"), 2);
+    assert_eq!(
+        callgraph.edge_kind("calls_alias", "real"),
+        Some(CallEdgeKind::Direct)
+    );
+
+    // the call through the IFunc may call either function its resolver returns
+    let callees: Vec<FunctionName> = callgraph.callees("calls_ifunc").sorted().collect();
+    assert_eq!(callees, vec!["impl_fast", "impl_generic"]);
+    assert_eq!(
+        callgraph.edge_kind("calls_ifunc", "impl_fast"),
+        Some(CallEdgeKind::Indirect)
+    );
+    assert!(!callgraph.inner().contains_node("aliased".into()));
+    assert!(!callgraph.inner().contains_node("impl".into()));
+}
+
+#[test]
+fn numbered_aliasee_and_non_function_callees() {
+    init_logging();
+    let module = Module::from_bc_path(ALIASNUMBERED_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    // the call through the alias is a direct call of the numbered function
+    let zero = FunctionName::Numbered {
+        module: &module.name,
+        n: 0,
+    };
+    let callees: Vec<FunctionName> = callgraph.callees("calls_numbered_alias").collect();
+    assert_eq!(callees, vec![zero]);
+    assert_eq!(
+        callgraph.edge_kind("calls_numbered_alias", zero),
+        Some(CallEdgeKind::Direct)
+    );
+    assert!(!callgraph.inner().contains_node("named_alias".into()));
+
+    // `data` isn't a function, so the call of it is treated like a call through
+    // a function pointer
+    assert!(!callgraph.inner().contains_node("data".into()));
+    let callees: Vec<FunctionName> = callgraph.callees("calls_data").sorted().collect();
+    assert_eq!(callees, vec!["@0", "calls_data", "calls_numbered_alias"]);
+    assert_eq!(
+        callgraph.edge_kind("calls_data", zero),
+        Some(CallEdgeKind::Indirect)
+    );
+}
+
+#[test]
+fn function_ptr_tables() {
+    init_logging();
+    let module = Module::from_bc_path(FPTRTABLE_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let tables = analysis.function_ptr_tables();
+
+    let globals: Vec<&str> = tables.tables().sorted().collect();
+    assert_eq!(globals, vec!["drivers", "mutable_ops", "unrelated_ptr"]);
+    let entries: Vec<(&[u64], String)> = tables
+        .entries("drivers")
+        .map(|entry| (entry.path.as_slice(), entry.function.to_string()))
+        .collect();
+    assert_eq!(
+        entries,
+        vec![
+            (&[0, 0][..], "foo_open".to_string()),
+            (&[0, 1][..], "foo_close".to_string()),
+            (&[1, 0][..], "bar_open".to_string()),
+            (&[1, 1][..], "bar_close".to_string()),
+        ]
+    );
+    let entries: Vec<&FunctionPtrTableEntry> = tables.entries("unrelated_ptr").collect();
+    assert_eq!(
+        entries,
+        vec![&FunctionPtrTableEntry {
+            global: "unrelated_ptr",
+            path: vec![],
+            function: FunctionName::Named("unrelated"),
+        }]
+    );
+    let funcs: Vec<FunctionName> = tables.functions_in("mutable_ops").collect();
+    assert_eq!(funcs, vec!["foo_close", "foo_open"]);
+    let globals: Vec<&str> = tables.tables_containing("foo_open").sorted().collect();
+    assert_eq!(globals, vec!["drivers", "mutable_ops"]);
+    assert_eq!(tables.entries("nonexistent").count(), 0);
+
+    // the call in `open_driver` loads from the `open` field of some element of
+    // a constant table, so it can only call the `open` functions
+    let callgraph = analysis.call_graph();
+    let callees: Vec<FunctionName> = callgraph.callees("open_driver").sorted().collect();
+    assert_eq!(callees, vec!["bar_open", "foo_open"]);
+    assert_eq!(
+        callgraph.edge_kind("open_driver", "foo_open"),
+        Some(CallEdgeKind::Indirect)
+    );
+
+    // `mutable_ops` may be modified at runtime, so the call in `close_mutable`
+    // could call any function of the appropriate type
+    let callees: Vec<FunctionName> = callgraph.callees("close_mutable").sorted().collect();
+    assert_eq!(
+        callees,
+        vec![
+            "bar_close",
+            "bar_open",
+            "close_mutable",
+            "foo_close",
+            "foo_open",
+            "unrelated"
+        ]
+    );
+}
+
+#[test]
+fn crossmod_function_ptr_tables() {
+    init_logging();
+    let fptrtable_module = Module::from_bc_path(FPTRTABLE_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let local_module = Module::from_bc_path(FPTRTABLELOCAL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let modules = [fptrtable_module, local_module];
+    let analysis = CrossModuleAnalysis::new(&modules);
+    let tables = analysis.function_ptr_tables();
+
+    // both modules' `drivers` are kept
+    let globals: Vec<&str> = tables.tables().collect();
+    assert_eq!(
+        globals,
+        vec![
+            "aliased_ops",
+            "drivers",
+            "ifunc_ops",
+            "mutable_ops",
+            "unrelated_ptr"
+        ]
+    );
+    let funcs: Vec<FunctionName> = tables.functions_in("drivers").collect();
+    assert_eq!(
+        funcs,
+        vec![
+            "bar_close",
+            "bar_open",
+            "baz_close",
+            "baz_open",
+            "foo_close",
+            "foo_open"
+        ]
+    );
+
+    // each call loads from its own module's `drivers`
+    let callgraph = analysis.call_graph();
+    let callees: Vec<FunctionName> = callgraph.callees("open_driver").sorted().collect();
+    assert_eq!(callees, vec!["bar_open", "foo_open"]);
+    let callees: Vec<FunctionName> = callgraph.callees("open_local_driver").collect();
+    assert_eq!(callees, vec!["baz_open"]);
+}
+
+#[test]
+fn function_ptr_tables_with_aliases() {
+    init_logging();
+    let module = Module::from_bc_path(FPTRTABLELOCAL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let tables = analysis.function_ptr_tables();
+
+    // a pointer to an alias is a pointer to its aliasee, but we don't know
+    // which function a pointer to an IFunc points to
+    let funcs: Vec<FunctionName> = tables.functions_in("aliased_ops").collect();
+    assert_eq!(funcs, vec!["baz_close", "baz_open"]);
+    let funcs: Vec<FunctionName> = tables.functions_in("ifunc_ops").collect();
+    assert_eq!(funcs, vec!["baz_close"]);
+
+    let callgraph = analysis.call_graph();
+    let callees: Vec<FunctionName> = callgraph.callees("open_aliased").collect();
+    assert_eq!(callees, vec!["baz_open"]);
+    // so the call in `open_ifunc` could call any function of the appropriate
+    // type
+    let callees: Vec<FunctionName> = callgraph.callees("open_ifunc").sorted().collect();
+    assert_eq!(callees, vec!["baz_close", "baz_open"]);
+}
+
+#[test]
+fn callback_registrations() {
+    init_logging();
+    let module = Module::from_bc_path(CALLBACK_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    let bb2_name = Name::from(2);
+    let bb0_name = Name::from(0);
+    let regs: Vec<&CallbackRegistration> = callgraph.callback_registrations().collect();
+    assert_eq!(
+        regs,
+        vec![
+            &CallbackRegistration {
+                site: CallSite {
+                    caller: FunctionName::Named("sort_ints"),
+                    block: &bb2_name,
+                    index: 1,
+                    kind: CallSiteKind::Call,
+                    edge_kind: CallEdgeKind::Callback,
+                    is_tail_call: true,
+                },
+                callee: Some(FunctionName::Named("qsort")),
+                callback: FunctionName::Named("compare_ints"),
+                arg_index: 3,
+            },
+            &CallbackRegistration {
+                site: CallSite {
+                    caller: FunctionName::Named("setup"),
+                    block: &bb0_name,
+                    index: 0,
+                    kind: CallSiteKind::Call,
+                    edge_kind: CallEdgeKind::Callback,
+                    is_tail_call: true,
+                },
+                callee: Some(FunctionName::Named("register_handler")),
+                callback: FunctionName::Named("on_event"),
+                arg_index: 1,
+            },
+        ]
+    );
+    assert_eq!(callgraph.callback_registrations_of("on_event").count(), 1);
+    assert_eq!(callgraph.callback_registrations_of("setup").count(), 0);
+
+    // by default, the callbacks appear to be uncalled
+    assert_eq!(callgraph.callers("compare_ints").count(), 0);
+    let callees: Vec<FunctionName> = callgraph.callees("sort_ints").collect();
+    assert_eq!(callees, vec!["qsort"]);
+
+    let with_callbacks = callgraph.with_callback_edges();
+    let callees: Vec<FunctionName> = with_callbacks.callees("sort_ints").sorted().collect();
+    assert_eq!(callees, vec!["compare_ints", "qsort"]);
+    assert_eq!(
+        with_callbacks.edge_kind("sort_ints", "compare_ints"),
+        Some(CallEdgeKind::Callback)
+    );
+    assert_eq!(
+        with_callbacks.edge_kind("sort_ints", "qsort"),
+        Some(CallEdgeKind::Direct)
+    );
+    let callers: Vec<FunctionName> = with_callbacks.callers("on_event").collect();
+    assert_eq!(callers, vec!["setup"]);
+    assert_eq!(with_callbacks.direct_callers("on_event").count(), 0);
+}
+
+#[test]
+fn call_graph_for_modules() {
+    init_logging();
+    let call_module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let crossmod_module = Module::from_bc_path(CROSSMOD_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let modules = [call_module, crossmod_module];
+    let analysis = CrossModuleAnalysis::new(&modules);
+    let callgraph = analysis.call_graph();
+
+    assert_eq!(
+        callgraph.defining_module("simple_callee"),
+        Some(modules[0].name.as_str())
+    );
+    assert_eq!(
+        callgraph.defining_module("cross_module_simple_caller"),
+        Some(modules[1].name.as_str())
+    );
+    assert_eq!(callgraph.defining_module("read_global"), None);
+    let funcs: Vec<FunctionName> = callgraph
+        .functions_in_module(&modules[1].name)
+        .filter(|func| func.to_string().starts_with("cross_module_simple"))
+        .collect();
+    assert_eq!(funcs, vec!["cross_module_simple_caller"]);
+
+    // only the functions in crossmod.c, and the functions which are declared
+    // but not defined anywhere
+    let crossmod_graph = analysis.call_graph_for_modules(&[&modules[1].name]);
+    assert!(!crossmod_graph.inner().contains_node("simple_callee".into()));
+    assert!(crossmod_graph.inner().contains_node("read_global".into()));
+    let callees: Vec<FunctionName> = crossmod_graph
+        .callees("cross_module_nested_near_caller")
+        .collect();
+    assert_eq!(callees, vec!["cross_module_simple_caller"]);
+    assert_eq!(
+        crossmod_graph.callees("cross_module_simple_caller").count(),
+        0
+    );
+
+    let both = analysis.call_graph_for_modules(&[&modules[0].name, &modules[1].name]);
+    assert_eq!(both.inner().node_count(), callgraph.inner().node_count());
+}
+
+#[test]
+fn tail_calls() {
+    init_logging();
+    let module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    // the calls of the `llvm.lifetime` intrinsics are not tail calls
+    let callees: Vec<FunctionName> = callgraph.callees("callee_with_loop").sorted().collect();
+    assert_vec_entries(&callees, &["llvm.lifetime.end", "llvm.lifetime.start"]);
+    assert_eq!(callgraph.tail_callees("callee_with_loop").count(), 0);
+
+    let callees: Vec<FunctionName> = callgraph.tail_callees("twice_caller").collect();
+    assert_eq!(callees, vec!["simple_callee"]);
+    let callers: Vec<FunctionName> = callgraph.tail_callers("recursive_double").collect();
+    assert_eq!(callers, vec!["recursive_double"]);
+    assert_eq!(
+        callgraph.tail_callers("llvm.lifetime.start.p0i8").count(),
+        0
+    );
+    assert!(callgraph
+        .call_sites("mutually_recursive_a", "mutually_recursive_b")
+        .all(|site| site.is_tail_call));
+}
+
+#[test]
+fn thread_spawns() {
+    init_logging();
+    let module = Module::from_bc_path(THREAD_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    let bb0_name = Name::from(0);
+    let spawns: Vec<&CallbackRegistration> = callgraph
+        .thread_spawns(CallGraph::THREAD_SPAWN_APIS)
+        .collect();
+    assert_eq!(
+        spawns,
+        vec![&CallbackRegistration {
+            site: CallSite {
+                caller: FunctionName::Named("start_worker"),
+                block: &bb0_name,
+                index: 3,
+                kind: CallSiteKind::Call,
+                edge_kind: CallEdgeKind::Callback,
+                is_tail_call: false,
+            },
+            callee: Some(FunctionName::Named("pthread_create")),
+            callback: FunctionName::Named("worker"),
+            arg_index: 2,
+        }]
+    );
+    // the wrong argument index, or some other function, isn't a spawn
+    assert_eq!(callgraph.thread_spawns(&[("pthread_create", 3)]).count(), 0);
+    assert_eq!(callgraph.thread_spawns(&[("thrd_create", 2)]).count(), 0);
+
+    assert_eq!(callgraph.callers("worker").count(), 0);
+    let with_spawns = callgraph.with_thread_spawn_edges(CallGraph::THREAD_SPAWN_APIS);
+    let callers: Vec<FunctionName> = with_spawns.callers("worker").collect();
+    assert_eq!(callers, vec!["start_worker"]);
+    assert_eq!(
+        with_spawns.edge_kind("start_worker", "worker"),
+        Some(CallEdgeKind::Spawn)
+    );
+    let roots: Vec<FunctionName> = with_spawns.roots().collect();
+    assert_eq!(roots, vec!["start_worker"]);
+}
+
+#[test]
+fn statepoint_callees() {
+    init_logging();
+    let module = Module::from_bc_path(STATEPOINT_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    // statepoints are calls of the functions they wrap, and `gc.relocate` and
+    // `gc.result` aren't calls at all
+    let callees: Vec<FunctionName> = callgraph.callees("caller").sorted().collect();
+    assert_eq!(callees, vec!["alloc", "safepoint", "takes_callback"]);
+    assert_eq!(
+        callgraph.edge_kind("caller", "safepoint"),
+        Some(CallEdgeKind::Direct)
+    );
+    // one statepoint, and one call with a "deopt" operand bundle
+    assert_eq!(callgraph.call_sites("caller", "safepoint").count(), 2);
+    assert!(!callgraph
+        .inner()
+        .contains_node("llvm.experimental.gc.statepoint.p0f_isVoidf".into()));
+    assert!(!callgraph
+        .inner()
+        .contains_node("llvm.experimental.gc.relocate.p1i8".into()));
+
+    // arguments of the wrapped call are indexed relative to that call
+    let registrations: Vec<&CallbackRegistration> = callgraph
+        .callback_registrations_of("callback_target")
+        .collect();
+    assert_eq!(registrations.len(), 1);
+    assert_eq!(
+        registrations[0].callee,
+        Some(FunctionName::Named("takes_callback"))
+    );
+    assert_eq!(registrations[0].arg_index, 0);
+
+    // a statepoint wrapping a function pointer is an indirect call
+    let callees: Vec<FunctionName> = callgraph.callees("indirect").collect();
+    assert_eq!(callees, vec!["safepoint"]);
+    assert_eq!(
+        callgraph.edge_kind("indirect", "safepoint"),
+        Some(CallEdgeKind::Indirect)
+    );
+}
+
+#[test]
+fn dead_functions() {
+    init_logging();
+    let module = Module::from_bc_path(DEADCODE_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+
+    // by default, externally visible functions are entry points, and
+    // everything else is reachable from one of them, through a table of
+    // function pointers, or from `llvm.global_ctors`
+    let dead = analysis.dead_function_analysis();
+    let entry_points: Vec<FunctionName> = dead.entry_points().collect();
+    assert_eq!(entry_points, vec!["exported", "exported_helper", "main"]);
+    assert_eq!(dead.dead_functions().count(), 0);
+    assert!(!dead.is_dead("times_three"));
+    assert!(!dead.is_dead("init"));
+
+    let dead = analysis.dead_function_analysis_with_entry_points(&["main".into()]);
+    let dead_functions: Vec<FunctionName> = dead.dead_functions().collect();
+    assert_eq!(dead_functions, vec!["exported", "exported_helper"]);
+    let live: Vec<FunctionName> = dead.live_functions().collect();
+    assert_eq!(
+        live,
+        vec!["helper", "init", "main", "times_three", "times_two"]
+    );
+
+    // with `exported` as an entry point, only `main` and its callees are dead
+    let dead = analysis.dead_function_analysis_with_entry_points(&["exported".into()]);
+    let dead_functions: Vec<FunctionName> = dead.dead_functions().collect();
+    assert_eq!(
+        dead_functions,
+        vec!["helper", "main", "times_three", "times_two"]
+    );
+}
+
+#[test]
+fn try_analyses() {
+    init_logging();
+    let call_module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let crossmod_module = Module::from_bc_path(CROSSMOD_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+
+    // `simple_callee` is only declared in crossmod.bc
+    let analysis = ModuleAnalysis::new(&crossmod_module);
+    assert!(analysis
+        .try_fn_analysis("cross_module_simple_caller")
+        .is_ok());
+    assert_eq!(
+        analysis.try_fn_analysis("simple_callee").err(),
+        Some(AnalysisError::FunctionHasNoBody("simple_callee".into()))
+    );
+    assert_eq!(
+        analysis.try_fn_analysis("nonexistent").err(),
+        Some(AnalysisError::FunctionNotFound("nonexistent".into()))
+    );
+
+    // but it's defined in call.bc
+    let modules = [call_module, crossmod_module];
+    let analysis = CrossModuleAnalysis::new(&modules);
+    let fn_analysis = analysis
+        .try_fn_analysis("simple_callee")
+        .unwrap_or_else(|e| panic!("{}", e));
+    assert!(fn_analysis.try_control_flow_graph().is_ok());
+    assert_eq!(
+        analysis.try_fn_analysis("nonexistent").err(),
+        Some(AnalysisError::FunctionNotFound("nonexistent".into()))
+    );
+    assert!(analysis.try_module_analysis(&modules[0].name).is_ok());
+    assert_eq!(
+        analysis.try_module_analysis("nonexistent").err(),
+        Some(AnalysisError::ModuleNotFound("nonexistent".into()))
+    );
+
+    let empty = llvm_ir::Function::new("empty");
+    let fn_analysis = FunctionAnalysis::new(&empty);
+    assert_eq!(
+        fn_analysis.try_control_flow_graph().err(),
+        Some(AnalysisError::FunctionHasNoBody("empty".into()))
+    );
 }
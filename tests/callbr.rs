@@ -0,0 +1,97 @@
+use itertools::Itertools;
+use llvm_ir::{Module, Name};
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+const CALLBR_BC_PATH: &str = "tests/bcfiles/callbr.bc";
+
+#[test]
+fn callbr_call_graph() {
+    init_logging();
+    let module = Module::from_bc_path(CALLBR_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    // the `callbr` itself calls inline assembly, which isn't in the call graph,
+    // but calls in the blocks it branches to are
+    let callees: Vec<FunctionName> = callgraph.callees("asm_goto").collect();
+    assert_eq!(callees, vec!["helper"]);
+    let callers: Vec<FunctionName> = callgraph.callers("asm_goto").collect();
+    assert_eq!(callers, vec!["calls_asm_goto"]);
+    // llvm-ir gives the result of the void `callbr` a number, so the block
+    // numbered %2 in callbr.ll is %3
+    let bb3_name = Name::from(3);
+    let sites: Vec<&CallSite> = callgraph.call_sites("asm_goto", "helper").collect();
+    assert_eq!(sites.len(), 2);
+    assert_eq!(sites[0].block, &bb3_name);
+    assert_eq!(sites[0].kind, CallSiteKind::Call);
+}
+
+#[test]
+fn callbr_cfg() {
+    init_logging();
+    let module = Module::from_bc_path(CALLBR_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("asm_goto");
+    let cfg = fn_analysis.control_flow_graph();
+
+    // CFG (with llvm-ir's numbering):
+    //     1
+    //    / \
+    //   3   5
+    //    \ /
+    //     8
+
+    let bb1_name = Name::from(1);
+    let bb3_name = Name::from(3);
+    let bb5_name = Name::from(5);
+    let bb8_name = Name::from(8);
+
+    // %5 has no phi, so it isn't known to be an indirect destination, and the
+    // default CFG has no edge to it
+    let succs: Vec<(CFGNode, CFGEdgeKind)> = cfg.labeled_succs(&bb1_name).collect();
+    assert_eq!(
+        succs,
+        vec![(CFGNode::Block(&bb3_name), CFGEdgeKind::CallBrFallthrough)]
+    );
+    assert_eq!(cfg.preds(&bb5_name).count(), 0);
+
+    let conditions = fn_analysis.branch_conditions();
+    assert_eq!(
+        conditions.branch_condition(&bb1_name),
+        BranchCondition::Call
+    );
+    assert_eq!(
+        conditions.outcomes(&bb1_name),
+        vec![(BranchOutcome::Normal, &bb3_name)]
+    );
+
+    // with all possible edges, the phi in %8 rules it out as an indirect
+    // destination, but %5 may be one
+    let cfg = fn_analysis.control_flow_graph_with_all_callbr_edges(ExitKind::DEFAULT);
+    let succs: Vec<(CFGNode, CFGEdgeKind)> = cfg
+        .labeled_succs(&bb1_name)
+        .sorted_by_key(|&(succ, _)| succ)
+        .collect();
+    assert_eq!(
+        succs,
+        vec![
+            (CFGNode::Block(&bb3_name), CFGEdgeKind::CallBrFallthrough),
+            (CFGNode::Block(&bb5_name), CFGEdgeKind::CallBrIndirect),
+        ]
+    );
+    assert_eq!(
+        cfg.preds(&bb8_name).sorted().collect::<Vec<_>>(),
+        vec![&bb3_name, &bb5_name]
+    );
+
+    let domtree = DominatorTree::new(&cfg);
+    assert_eq!(domtree.idom(&bb5_name), Some(&bb1_name));
+    assert_eq!(domtree.idom(&bb8_name), Some(&bb1_name));
+}
@@ -0,0 +1,157 @@
+#![allow(clippy::redundant_static_lifetimes)]
+
+use llvm_ir::{Module, Name, Operand, Terminator};
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+/// conddeps.ll is hand-written, with the dependencies of its conditions fixed
+const CONDDEPS_BC_PATH: &'static str = "tests/bcfiles/conddeps.bc";
+const LOOP_BC_PATH: &'static str = "tests/bcfiles/loop.bc";
+
+fn names(names: &[&str]) -> Vec<Name> {
+    names.iter().map(|&name| Name::from(name)).collect()
+}
+
+#[test]
+fn parser_branches() {
+    init_logging();
+    let module = Module::from_bc_path(CONDDEPS_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("parse");
+    let deps = fn_analysis.condition_dependencies();
+
+    let entry_name = Name::from("entry");
+    let header_name = Name::from("header");
+    let one_name = Name::from("one");
+    let loop_name = Name::from("loop");
+    let g_name = Name::from("g");
+    let arr_name = Name::from("arr");
+
+    assert_eq!(
+        deps.branches()
+            .map(|branch| branch.block)
+            .collect::<Vec<_>>(),
+        vec![&entry_name, &header_name, &one_name, &loop_name]
+    );
+
+    let entry = deps.branch(&entry_name).expect("entry should branch");
+    assert_eq!(entry.condition.to_string(), "i1 %magic");
+    assert_eq!(
+        entry.values,
+        names(&["buf", "b0", "magic"]).iter().collect::<Vec<_>>()
+    );
+    assert_eq!(entry.parameters, vec![0]);
+    assert!(entry.loaded_globals.is_empty());
+
+    // the `switch`, which depends on %buf through a `getelementptr`
+    let header = deps.branch(&header_name).expect("header should switch");
+    assert_eq!(header.condition.to_string(), "i32 %kind");
+    assert_eq!(
+        header.values,
+        names(&["buf", "p1", "b1", "kind"])
+            .iter()
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(header.parameters, vec![0]);
+
+    let one = deps.branch(&one_name).expect("one should branch");
+    assert_eq!(
+        one.values,
+        names(&["len", "limit", "big"]).iter().collect::<Vec<_>>()
+    );
+    assert_eq!(one.parameters, vec![1]);
+    assert_eq!(one.loaded_globals, vec![&g_name]);
+
+    // dependencies through the `phi` and the address of the load
+    let lp = deps.branch(&loop_name).expect("loop should branch");
+    assert_eq!(
+        lp.values,
+        names(&["mode", "i", "slot", "x", "i.next", "done"])
+            .iter()
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(lp.parameters, vec![2]);
+    assert_eq!(lp.loaded_globals, vec![&arr_name]);
+
+    // `ret`s don't branch
+    assert!(deps.branch(&Name::from("ok")).is_none());
+    assert!(deps.branch(&Name::from("nonexistent")).is_none());
+
+    assert_eq!(
+        deps.branches_depending_on_parameter(0)
+            .map(|branch| branch.block)
+            .collect::<Vec<_>>(),
+        vec![&entry_name, &header_name]
+    );
+    assert_eq!(deps.branches_depending_on_parameter(3).count(), 0);
+    assert_eq!(
+        deps.branches_depending_on_global(&g_name)
+            .map(|branch| branch.block)
+            .collect::<Vec<_>>(),
+        vec![&one_name]
+    );
+}
+
+/// Check that every conditional branch and `switch` has dependencies, which
+/// include its condition (if local) and are closed under the operands of
+/// their definitions
+#[test]
+fn dependencies_of_loops() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    for func in &module.functions {
+        let fn_analysis = analysis.fn_analysis(&func.name);
+        let deps = fn_analysis.condition_dependencies();
+        let def_use = fn_analysis.def_use_chains();
+        for bb in &func.basic_blocks {
+            let is_branch = matches!(bb.term, Terminator::CondBr(_) | Terminator::Switch(_));
+            assert_eq!(deps.branch(&bb.name).is_some(), is_branch);
+        }
+        let all_values: Vec<&Name> = func
+            .parameters
+            .iter()
+            .map(|param| &param.name)
+            .chain(func.basic_blocks.iter().flat_map(|bb| {
+                bb.instrs
+                    .iter()
+                    .filter_map(|inst| inst.try_get_result())
+                    .chain(bb.term.try_get_result())
+            }))
+            .collect();
+        for branch in deps.branches() {
+            if let Operand::LocalOperand { name, .. } = branch.condition {
+                assert!(branch.values.contains(&name));
+            }
+            for &value in &branch.values {
+                match def_use.definition(value) {
+                    Some(Definition::Parameter(index)) => {
+                        assert!(branch.parameters.contains(&index))
+                    }
+                    Some(Definition::Instruction(location)) => {
+                        // every value used by the definition is a dependency
+                        for &operand in &all_values {
+                            if def_use.uses(operand).contains(&location) {
+                                assert!(
+                                    branch.values.contains(&operand),
+                                    "{} in {}: {} uses {}",
+                                    branch.block,
+                                    func.name,
+                                    value,
+                                    operand
+                                );
+                            }
+                        }
+                    }
+                    None => panic!("{} isn't a value of {}", value, func.name),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,462 @@
+use itertools::Itertools;
+use llvm_ir::{Module, Name};
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+const EXCEPTIONS_BC_PATH: &str = "tests/bcfiles/exceptions.bc";
+const PANIC_BC_PATH: &str = "tests/bcfiles/panic.bc";
+const UNWIND_BC_PATH: &str = "tests/bcfiles/unwind.bc";
+
+#[test]
+fn may_throw() {
+    init_logging();
+    let module = Module::from_bc_path(EXCEPTIONS_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let eh = analysis.exception_propagation();
+
+    assert!(eh.may_throw("_Z7throweri"));
+    assert!(eh.may_throw("_Z10propagatesi"));
+    assert!(eh.may_throw("_Z7catchesi")); // may rethrow exceptions of other types
+    assert!(eh.may_throw("_Z9cleans_upi"));
+    assert!(!eh.may_throw("_Z9catch_alli"));
+    assert!(!eh.may_throw("_Z13no_exceptionsi"));
+    assert!(eh.may_throw("main"));
+    assert!(eh.may_throw("__cxa_throw"));
+    assert!(!eh.may_throw("llvm.eh.typeid.for"));
+
+    let sites: Vec<&UnwindSite> = eh.sites_in("main").collect();
+    assert_eq!(sites.len(), 2);
+    assert_eq!(sites[0].callees, vec!["_Z7catchesi"]);
+    assert_eq!(sites[0].handler, None);
+    assert_eq!(sites[1].callees, vec!["_Z9cleans_upi"]);
+
+    // llvm-ir gives the result of the void `invoke` a number, so the landing
+    // pad is %3 rather than %2
+    let lpad_name = Name::from(3);
+    let sites: Vec<&UnwindSite> = eh.sites_in("_Z9catch_alli").collect();
+    assert_eq!(sites.len(), 1);
+    assert_eq!(sites[0].callees, vec!["_Z7throweri"]);
+    assert_eq!(sites[0].index, 0);
+    assert_eq!(sites[0].handler, Some(&lpad_name));
+}
+
+#[test]
+fn may_unwind() {
+    init_logging();
+    let module = Module::from_bc_path(UNWIND_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let eh = analysis.exception_propagation();
+
+    assert!(!eh.may_throw("quiet"));
+    assert!(eh.may_throw("throws"));
+    assert!(eh.may_throw("ext"));
+    assert!(!eh.may_throw("nounwind_site"));
+    // only landing pads of `invoke`s which may throw can `resume`
+    assert!(!eh.may_throw("invokes_quiet"));
+    assert!(eh.may_throw("invokes_throws"));
+    assert!(!eh.may_throw("dead_resume"));
+    // calls through function pointers may only call functions of their type
+    assert!(!eh.may_throw("calls_byte"));
+    assert!(eh.may_throw("calls_short"));
+
+    assert_eq!(eh.sites_in("invokes_quiet").count(), 0);
+    let lpad_name = Name::from("lpad");
+    let sites: Vec<&UnwindSite> = eh.sites_in("invokes_throws").collect();
+    assert_eq!(sites.len(), 1);
+    assert_eq!(sites[0].callees, vec!["throws"]);
+    assert_eq!(sites[0].handler, Some(&lpad_name));
+    assert_eq!(eh.sites_in("calls_byte").count(), 0);
+    let sites: Vec<&UnwindSite> = eh.sites_in("calls_short").collect();
+    assert_eq!(sites.len(), 1);
+    assert!(sites[0].callees.is_empty());
+}
+
+#[test]
+fn handlers() {
+    init_logging();
+    let module = Module::from_bc_path(EXCEPTIONS_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let eh = analysis.exception_propagation();
+
+    let bb3_name = Name::from(3);
+    let bb5_name = Name::from(5);
+    let handlers: Vec<(FunctionName, &Name)> = eh.handlers("__cxa_throw").collect();
+    assert_eq!(
+        handlers,
+        vec![
+            (FunctionName::Named("_Z7catchesi"), &bb3_name),
+            (FunctionName::Named("_Z9catch_alli"), &bb3_name),
+            (FunctionName::Named("_Z9cleans_upi"), &bb5_name),
+        ]
+    );
+    assert!(eh.escapes("__cxa_throw"));
+
+    // exceptions from `propagates` don't reach the handler in `catch_all`
+    let handlers: Vec<(FunctionName, &Name)> = eh.handlers("_Z10propagatesi").collect();
+    assert_eq!(
+        handlers,
+        vec![
+            (FunctionName::Named("_Z7catchesi"), &bb3_name),
+            (FunctionName::Named("_Z9cleans_upi"), &bb5_name)
+        ]
+    );
+
+    // the handler in `catches` may resume, and `cleans_up` is only a cleanup
+    let succs: Vec<ExceptionNode> = eh
+        .succs(ExceptionNode::Handler {
+            function: "_Z9cleans_upi".into(),
+            block: &bb5_name,
+        })
+        .collect();
+    assert_eq!(succs, vec![ExceptionNode::Function("_Z9cleans_upi".into())]);
+    assert_eq!(
+        eh.succs(ExceptionNode::Handler {
+            function: "_Z9catch_alli".into(),
+            block: &bb3_name,
+        })
+        .count(),
+        0
+    );
+    let preds: Vec<ExceptionNode> = eh.preds(ExceptionNode::Escape).collect();
+    assert_eq!(preds, vec![ExceptionNode::Function("main".into())]);
+
+    assert_eq!(eh.handlers("_Z9catch_alli").count(), 0);
+    assert!(!eh.escapes("_Z9catch_alli"));
+    assert!(!eh.escapes("_Z13no_exceptionsi"));
+}
+
+#[test]
+fn personalities() {
+    init_logging();
+    let module = Module::from_bc_path(EXCEPTIONS_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let personalities = analysis.personality_analysis();
+
+    assert_eq!(
+        personalities.personality("_Z7catchesi"),
+        Some("__gxx_personality_v0")
+    );
+    assert_eq!(personalities.scheme("_Z7catchesi"), EHScheme::ItaniumCxx);
+    assert_eq!(personalities.personality("_Z7throweri"), None);
+    assert_eq!(personalities.scheme("_Z7throweri"), EHScheme::None);
+    let funcs: Vec<FunctionName> = personalities
+        .functions_with_scheme(EHScheme::ItaniumCxx)
+        .sorted()
+        .collect();
+    assert_eq!(funcs, vec!["_Z7catchesi", "_Z9catch_alli", "_Z9cleans_upi"]);
+    assert_eq!(
+        personalities.schemes().into_iter().collect::<Vec<_>>(),
+        vec![EHScheme::ItaniumCxx]
+    );
+    assert!(!personalities.is_mixed());
+}
+
+#[test]
+fn mixed_personalities() {
+    init_logging();
+    let cxx_module = Module::from_bc_path(EXCEPTIONS_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let rust_module = Module::from_bc_path(PANIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let modules = [cxx_module, rust_module];
+    let analysis = CrossModuleAnalysis::new(&modules);
+    let personalities = analysis.personality_analysis();
+
+    assert_eq!(
+        personalities.scheme("_ZN3std9panicking11begin_panic17h5ae0871c3ba84f98E"),
+        EHScheme::Rust
+    );
+    assert_eq!(
+        personalities.functions_with_scheme(EHScheme::Rust).count(),
+        6
+    );
+    assert!(personalities.is_mixed());
+    assert_eq!(
+        personalities
+            .schemes_in_module(&modules[1].name)
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec![EHScheme::Rust]
+    );
+    assert_eq!(
+        EHScheme::from_personality("__CxxFrameHandler3"),
+        EHScheme::Seh
+    );
+    assert_eq!(
+        EHScheme::from_personality("__gcc_personality_v0"),
+        EHScheme::GnuC
+    );
+}
+
+#[test]
+fn invoke_call_sites() {
+    init_logging();
+    let module = Module::from_bc_path(EXCEPTIONS_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    let bb1_name = Name::from(1);
+    let sites: Vec<&CallSite> = callgraph
+        .call_sites("_Z9catch_alli", "_Z7throweri")
+        .collect();
+    assert_eq!(
+        sites,
+        vec![&CallSite {
+            caller: "_Z9catch_alli".into(),
+            block: &bb1_name,
+            index: 0,
+            kind: CallSiteKind::Invoke,
+            edge_kind: CallEdgeKind::Direct,
+            is_tail_call: false,
+        }]
+    );
+    let sites: Vec<&CallSite> = callgraph.call_sites("main", "_Z7catchesi").collect();
+    assert_eq!(sites.len(), 1);
+    assert_eq!(sites[0].kind, CallSiteKind::Call);
+}
+
+#[test]
+fn call_paths() {
+    init_logging();
+    let module = Module::from_bc_path(EXCEPTIONS_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    let paths: Vec<Vec<FunctionName>> = callgraph
+        .call_paths("main", "__cxa_throw", None, None)
+        .into_iter()
+        .sorted()
+        .collect();
+    assert_eq!(
+        paths,
+        vec![
+            vec![
+                "main",
+                "_Z7catchesi",
+                "_Z10propagatesi",
+                "_Z7throweri",
+                "__cxa_throw"
+            ],
+            vec!["main", "_Z9catch_alli", "_Z7throweri", "__cxa_throw"],
+            vec![
+                "main",
+                "_Z9cleans_upi",
+                "_Z10propagatesi",
+                "_Z7throweri",
+                "__cxa_throw"
+            ],
+        ]
+    );
+
+    let paths = callgraph.call_paths("main", "__cxa_throw", Some(3), None);
+    assert_eq!(
+        paths,
+        vec![vec!["main", "_Z9catch_alli", "_Z7throweri", "__cxa_throw"]]
+    );
+    assert_eq!(
+        callgraph
+            .call_paths("main", "__cxa_throw", Some(2), None)
+            .len(),
+        0
+    );
+    assert_eq!(
+        callgraph
+            .call_paths("main", "__cxa_throw", None, Some(2))
+            .len(),
+        2
+    );
+    assert_eq!(
+        callgraph.call_paths("main", "main", None, None),
+        vec![vec!["main"]]
+    );
+    assert!(callgraph
+        .call_paths("_Z7throweri", "main", None, None)
+        .is_empty());
+}
+
+#[cfg(feature = "demangle")]
+#[test]
+fn demangled_call_graph_queries() {
+    init_logging();
+    let module = Module::from_bc_path(EXCEPTIONS_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    assert_eq!(demangle("_Z7catchesi").as_deref(), Some("catches(int)"));
+    // with or without the parameter types
+    assert_eq!(
+        callgraph.resolve_demangled("catches(int)"),
+        vec!["_Z7catchesi"]
+    );
+    assert_eq!(callgraph.resolve_demangled("catches"), vec!["_Z7catchesi"]);
+    assert_eq!(callgraph.resolve_demangled("main"), vec!["main"]);
+
+    assert_eq!(
+        callgraph.callers_demangled("thrower"),
+        vec!["_Z10propagatesi", "_Z9catch_alli"]
+    );
+    let callees = callgraph.callees_demangled("catch_all(int)");
+    assert!(callees.contains(&"_Z7throweri".into()));
+}
+
+#[test]
+fn exit_kinds() {
+    init_logging();
+    let module = Module::from_bc_path(EXCEPTIONS_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+
+    // `catches` returns from %14, and resumes from %13
+    let fn_analysis = analysis.fn_analysis("_Z7catchesi");
+    let bb3_name = Name::from(3);
+    let bb13_name = Name::from(13);
+    let bb8_name = Name::from(8);
+    let bb14_name = Name::from(14);
+    let cfg = fn_analysis.control_flow_graph();
+    assert_eq!(cfg.exits(), ExitKind::DEFAULT);
+    assert_eq!(
+        cfg.preds_of_return().sorted().collect::<Vec<_>>(),
+        vec![&bb13_name, &bb14_name]
+    );
+    assert_eq!(
+        cfg.preds_of_exit(ExitKind::Unwind).collect::<Vec<_>>(),
+        vec![&bb13_name]
+    );
+    assert_eq!(
+        cfg.edge_kinds(&bb13_name, CFGNode::Return),
+        &[CFGEdgeKind::Resume]
+    );
+    let postdomtree = fn_analysis.postdominator_tree();
+    assert_eq!(postdomtree.ipostdom(&bb3_name), Some(CFGNode::Return));
+
+    // considering only normal returns, the landing pad %3 must go through its
+    // matching handler %8, and %13 isn't in the tree at all
+    let cfg = fn_analysis.control_flow_graph_with_exits(&[ExitKind::Return]);
+    assert_eq!(cfg.preds_of_return().collect::<Vec<_>>(), vec![&bb14_name]);
+    assert_eq!(cfg.preds_of_exit(ExitKind::Unwind).count(), 0);
+    let postdomtree = fn_analysis.postdominator_tree_with_exits(&[ExitKind::Return]);
+    assert_eq!(
+        postdomtree.ipostdom(&bb3_name),
+        Some(CFGNode::Block(&bb8_name))
+    );
+    assert_eq!(postdomtree.ipostdom(&bb13_name), None);
+
+    // `thrower` ends in `unreachable` after calling `__cxa_throw`
+    let fn_analysis = analysis.fn_analysis("_Z7throweri");
+    let bb1_name = Name::from(1);
+    let bb3_name = Name::from(3);
+    let bb6_name = Name::from(6);
+    let postdomtree = fn_analysis.postdominator_tree();
+    assert_eq!(
+        postdomtree.ipostdom(&bb1_name),
+        Some(CFGNode::Block(&bb6_name))
+    );
+    let exits = [ExitKind::Return, ExitKind::Unreachable];
+    let cfg = fn_analysis.control_flow_graph_with_exits(&exits);
+    assert_eq!(
+        cfg.preds_of_exit(ExitKind::Unreachable).collect::<Vec<_>>(),
+        vec![&bb3_name]
+    );
+    assert_eq!(
+        cfg.edge_kinds(&bb3_name, CFGNode::Return),
+        &[CFGEdgeKind::Unreachable]
+    );
+    let postdomtree = fn_analysis.postdominator_tree_with_exits(&exits);
+    assert_eq!(postdomtree.ipostdom(&bb1_name), Some(CFGNode::Return));
+}
+
+#[test]
+fn exceptional_cfg_edges() {
+    init_logging();
+    let module = Module::from_bc_path(EXCEPTIONS_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let cfg = analysis.fn_analysis("_Z7catchesi").control_flow_graph();
+
+    let bb1_name = Name::from(1);
+    let bb3_name = Name::from(3);
+    let bb13_name = Name::from(13);
+    let bb14_name = Name::from(14);
+    assert!(!CFGEdgeKind::InvokeNormal.is_exceptional());
+    assert!(CFGEdgeKind::InvokeException.is_exceptional());
+    let edges: Vec<(&Name, CFGNode, CFGEdgeKind)> = cfg
+        .exceptional_edges()
+        .sorted_by_key(|&(from, to, _)| (from, to))
+        .collect();
+    assert_eq!(
+        edges,
+        vec![
+            (
+                &bb1_name,
+                CFGNode::Block(&bb3_name),
+                CFGEdgeKind::InvokeException
+            ),
+            (&bb13_name, CFGNode::Return, CFGEdgeKind::Resume),
+        ]
+    );
+
+    let normal = cfg.without_exceptional_edges();
+    assert_eq!(normal.exits(), &[ExitKind::Return]);
+    assert_eq!(
+        normal.succs(&bb1_name).collect::<Vec<_>>(),
+        vec![CFGNode::Block(&bb14_name)]
+    );
+    assert_eq!(
+        normal.preds_of_return().collect::<Vec<_>>(),
+        vec![&bb14_name]
+    );
+    assert_eq!(normal.exceptional_edges().count(), 0);
+    // the landing pad and its handlers are unreachable without exceptions
+    assert_eq!(
+        normal.reverse_post_order().collect::<Vec<_>>(),
+        vec![&bb1_name, &bb14_name]
+    );
+    // the original is unchanged
+    assert_eq!(cfg.succs(&bb1_name).count(), 2);
+}
+
+#[test]
+fn exit_blocks() {
+    init_logging();
+    let module = Module::from_bc_path(EXCEPTIONS_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+
+    // `thrower` returns from %6, and %3 ends in `unreachable`
+    let fn_analysis = analysis.fn_analysis("_Z7throweri");
+    let bb3_name = Name::from(3);
+    let bb6_name = Name::from(6);
+    let cfg = fn_analysis.control_flow_graph();
+    assert_eq!(
+        cfg.exit_blocks().collect::<Vec<_>>(),
+        vec![&bb3_name, &bb6_name]
+    );
+    assert_eq!(cfg.preds_of_return().collect::<Vec<_>>(), vec![&bb6_name]);
+
+    // `catches` resumes from %13 and returns from %14, regardless of which
+    // exits the CFG connects to `CFGNode::Return`
+    let fn_analysis = analysis.fn_analysis("_Z7catchesi");
+    let bb13_name = Name::from(13);
+    let bb14_name = Name::from(14);
+    let cfg = fn_analysis.control_flow_graph();
+    assert_eq!(
+        cfg.exit_blocks().collect::<Vec<_>>(),
+        vec![&bb13_name, &bb14_name]
+    );
+    let cfg = fn_analysis.control_flow_graph_with_exits(&[ExitKind::Return]);
+    assert_eq!(
+        cfg.exit_blocks().collect::<Vec<_>>(),
+        vec![&bb13_name, &bb14_name]
+    );
+}
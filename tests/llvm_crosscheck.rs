@@ -0,0 +1,239 @@
+//! Cross-check our dominator and postdominator trees against LLVM's own, as
+//! printed by `opt -passes='print<domtree>,print<postdomtree>'`.
+//!
+//! The LLVM C API (and therefore `llvm-sys`) doesn't expose LLVM's
+//! `DominatorTree`, so we run `opt` instead. This test only runs with the
+//! `llvm-crosscheck` feature. Set the `LLVM_OPT` environment variable to choose
+//! the `opt` binary. If it can't be run, or is for a different LLVM version
+//! than the one selected by this crate's features, the test is skipped with a
+//! message saying why; so are bitcode files which `opt` can't read.
+
+use llvm_ir::Module;
+use llvm_ir_analysis::*;
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+const BC_DIR: &str = "tests/bcfiles";
+
+/// The LLVM major version selected by this crate's features
+#[cfg(feature = "llvm-9")]
+const LLVM_VERSION: u32 = 9;
+#[cfg(feature = "llvm-10")]
+const LLVM_VERSION: u32 = 10;
+#[cfg(feature = "llvm-11")]
+const LLVM_VERSION: u32 = 11;
+#[cfg(feature = "llvm-12")]
+const LLVM_VERSION: u32 = 12;
+#[cfg(feature = "llvm-13")]
+const LLVM_VERSION: u32 = 13;
+#[cfg(feature = "llvm-14")]
+const LLVM_VERSION: u32 = 14;
+#[cfg(feature = "llvm-15")]
+const LLVM_VERSION: u32 = 15;
+#[cfg(feature = "llvm-16")]
+const LLVM_VERSION: u32 = 16;
+#[cfg(feature = "llvm-17")]
+const LLVM_VERSION: u32 = 17;
+
+/// LLVM's name for the virtual root of the postdominator tree
+const LLVM_EXIT_NODE: &str = "<<exit node>>";
+
+/// One tree as printed by `opt`: the parent of each node (absent for the
+/// root), and the `Roots:` line
+#[derive(Default)]
+struct LLVMTree {
+    parents: HashMap<String, String>,
+    roots: Vec<String>,
+}
+
+/// The trees printed by `opt` for one module, keyed by function name
+#[derive(Default)]
+struct LLVMTrees {
+    domtrees: HashMap<String, LLVMTree>,
+    postdomtrees: HashMap<String, LLVMTree>,
+}
+
+/// Find the `opt` binary to use, or explain why it can't be used
+fn find_opt() -> Result<String, String> {
+    let opt = std::env::var("LLVM_OPT").unwrap_or_else(|_| "opt".into());
+    let output = Command::new(&opt).arg("--version").output().map_err(|e| {
+        format!(
+            "couldn't run {} (set LLVM_OPT to choose the binary): {}",
+            opt, e
+        )
+    })?;
+    // e.g. `LLVM version 14.0.6`, possibly with a vendor prefix
+    let version = String::from_utf8_lossy(&output.stdout);
+    let major = version
+        .split_once("LLVM version ")
+        .and_then(|(_, rest)| rest.split('.').next())
+        .and_then(|major| major.trim().parse::<u32>().ok());
+    match major {
+        Some(major) if major == LLVM_VERSION => Ok(opt),
+        Some(major) => Err(format!(
+            "{} is from LLVM {}, but LLVM {} is selected",
+            opt, major, LLVM_VERSION
+        )),
+        None => Err(format!("couldn't determine the LLVM version of {}", opt)),
+    }
+}
+
+/// Run `opt` on the given bitcode file, or explain why that failed
+fn run_opt(opt: &str, path: &str) -> Result<String, String> {
+    let output = Command::new(opt)
+        .arg("-passes=print<domtree>,print<postdomtree>")
+        .arg("-disable-output")
+        .arg(path)
+        .output()
+        .map_err(|e| format!("couldn't run {}: {}", opt, e))?;
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    if output.status.success() {
+        Ok(stderr)
+    } else {
+        Err(format!("{} failed: {}", opt, stderr.trim()))
+    }
+}
+
+fn parse_opt_output(output: &str) -> LLVMTrees {
+    let mut trees = LLVMTrees::default();
+    let mut current: Option<&mut LLVMTree> = None;
+    // the most recent node at each depth, for finding parents
+    let mut stack: Vec<String> = Vec::new();
+    for line in output.lines() {
+        if let Some(func) = line.strip_prefix("DominatorTree for function: ") {
+            current = Some(trees.domtrees.entry(func.to_owned()).or_default());
+            stack.clear();
+        } else if let Some(func) = line.strip_prefix("PostDominatorTree for function: ") {
+            current = Some(trees.postdomtrees.entry(func.to_owned()).or_default());
+            stack.clear();
+        } else if let Some(roots) = line.strip_prefix("Roots: ") {
+            let tree = current.as_mut().expect("Roots: outside of a tree");
+            tree.roots = roots.split_whitespace().map(str::to_owned).collect();
+        } else if let Some(rest) = line.trim_start().strip_prefix('[') {
+            // a node, e.g. `    [2] %4 {4294967295,4294967295} [1]`
+            let tree = current.as_mut().expect("node outside of a tree");
+            let (depth, rest) = rest.split_once(']').expect("malformed node line");
+            let depth: usize = depth.parse().expect("malformed node depth");
+            let name = rest
+                .rsplit_once(" {")
+                .expect("malformed node line")
+                .0
+                .trim()
+                .to_owned();
+            stack.truncate(depth - 1);
+            if let Some(parent) = stack.last() {
+                tree.parents.insert(name.clone(), parent.clone());
+            }
+            stack.push(name);
+        }
+    }
+    trees
+}
+
+#[test]
+fn trees_match_llvm() {
+    init_logging();
+    let mut paths: Vec<_> = std::fs::read_dir(BC_DIR)
+        .expect("Failed to read bcfiles directory")
+        .map(|entry| entry.expect("Failed to read directory entry").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "bc"))
+        .collect();
+    paths.sort();
+    let opt = match find_opt() {
+        Ok(opt) => opt,
+        Err(reason) => {
+            eprintln!("skipping LLVM cross-check: {}", reason);
+            return;
+        }
+    };
+    for path in paths {
+        let path = path.to_str().expect("non-UTF-8 path");
+        let output = match run_opt(&opt, path) {
+            Ok(output) => output,
+            Err(reason) => {
+                eprintln!("skipping {} in LLVM cross-check: {}", path, reason);
+                continue;
+            }
+        };
+        let llvm_trees = parse_opt_output(&output);
+        let module =
+            Module::from_bc_path(path).unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+        let analysis = ModuleAnalysis::new(&module);
+        for func in &module.functions {
+            let fn_analysis = analysis.fn_analysis(&func.name);
+            let block_names: HashSet<String> = func
+                .basic_blocks
+                .iter()
+                .map(|bb| bb.name.to_string())
+                .collect();
+
+            let llvm_domtree = llvm_trees
+                .domtrees
+                .get(&func.name)
+                .unwrap_or_else(|| panic!("opt printed no domtree for {}", func.name));
+            // `llvm-ir` doesn't always number unnamed blocks the same way
+            // LLVM's printer does (e.g., after a void `callbr`), in which case
+            // we can't line the trees up
+            if !llvm_domtree
+                .parents
+                .keys()
+                .all(|name| block_names.contains(name))
+            {
+                continue;
+            }
+            let domtree = fn_analysis.dominator_tree();
+            for bb in &func.basic_blocks {
+                assert_eq!(
+                    domtree.idom(&bb.name).map(|idom| idom.to_string()),
+                    llvm_domtree.parents.get(&bb.name.to_string()).cloned(),
+                    "idom of {} in {} ({})",
+                    bb.name,
+                    func.name,
+                    path,
+                );
+            }
+
+            // LLVM's postdominator tree treats every block without successors
+            // as an exit. It also adds a virtual exit for each infinite loop,
+            // but picks the loop's exiting block differently than we do, so
+            // we only compare functions without infinite loops.
+            let llvm_postdomtree = llvm_trees
+                .postdomtrees
+                .get(&func.name)
+                .unwrap_or_else(|| panic!("opt printed no postdomtree for {}", func.name));
+            let exits = [ExitKind::Return, ExitKind::Unwind, ExitKind::Unreachable];
+            let cfg = fn_analysis.control_flow_graph_with_exits(&exits);
+            let exit_blocks: HashSet<String> =
+                cfg.preds_of_return().map(|bb| bb.to_string()).collect();
+            if !llvm_postdomtree
+                .roots
+                .iter()
+                .all(|root| exit_blocks.contains(root))
+            {
+                continue;
+            }
+            let postdomtree = fn_analysis.postdominator_tree_with_exits(&exits);
+            for bb in &func.basic_blocks {
+                let ipostdom = postdomtree
+                    .ipostdom(&bb.name)
+                    .map(|ipostdom| match ipostdom {
+                        CFGNode::Block(block) => block.to_string(),
+                        CFGNode::Return => LLVM_EXIT_NODE.to_owned(),
+                    });
+                assert_eq!(
+                    ipostdom,
+                    llvm_postdomtree.parents.get(&bb.name.to_string()).cloned(),
+                    "ipostdom of {} in {} ({})",
+                    bb.name,
+                    func.name,
+                    path,
+                );
+            }
+        }
+    }
+}
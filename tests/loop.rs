@@ -1,5 +1,8 @@
+#![allow(clippy::bool_assert_comparison, clippy::redundant_static_lifetimes)]
+
+use either::Either;
 use itertools::Itertools;
-use llvm_ir::{Module, Name};
+use llvm_ir::{Module, Name, Operand};
 use llvm_ir_analysis::*;
 
 fn init_logging() {
@@ -12,6 +15,9 @@ fn init_logging() {
 /// [`haybale`]: https://crates.io/crates/haybale
 const LOOP_BC_PATH: &'static str = "tests/bcfiles/loop.bc";
 
+/// tripcount.ll is hand-written, with loops shaped for testing trip counts
+const TRIPCOUNT_BC_PATH: &'static str = "tests/bcfiles/tripcount.bc";
+
 #[test]
 fn while_loop_cfg() {
     init_logging();
@@ -380,6 +386,862 @@ fn nested_loop_cfg() {
     assert_eq!(bb13_succs, vec![bb10_node, bb13_node]);
 }
 
+#[test]
+fn nested_loop_natural_loops() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("nested_loop");
+    let cfg = fn_analysis.control_flow_graph();
+    let domtree = fn_analysis.dominator_tree();
+
+    let bb1_name = Name::from(1);
+    let bb5_name = Name::from(5);
+    let bb7_name = Name::from(7);
+    let bb10_name = Name::from(10);
+    let bb13_name = Name::from(13);
+
+    let back_edges: Vec<(&Name, &Name)> = cfg.back_edges(&domtree).into_iter().sorted().collect();
+    assert_eq!(
+        back_edges,
+        vec![(&bb10_name, &bb5_name), (&bb13_name, &bb13_name)]
+    );
+
+    let outer: Vec<&Name> = cfg
+        .natural_loop(&bb5_name, &domtree)
+        .expect("Expected a loop headed by bb5")
+        .into_iter()
+        .sorted()
+        .collect();
+    assert_eq!(outer, vec![&bb5_name, &bb10_name, &bb13_name]);
+    let inner: Vec<&Name> = cfg
+        .natural_loop(&bb13_name, &domtree)
+        .expect("Expected a loop headed by bb13")
+        .into_iter()
+        .collect();
+    assert_eq!(inner, vec![&bb13_name]);
+    assert_eq!(cfg.natural_loop(&bb1_name, &domtree), None);
+    assert_eq!(cfg.natural_loop(&bb7_name, &domtree), None);
+    assert_eq!(cfg.natural_loops(&domtree).len(), 2);
+}
+
+#[test]
+fn unreachable_self_loop_natural_loops() {
+    init_logging();
+    let module = Module::from_bc_path(TRIPCOUNT_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("unreachable_self_loop");
+    let cfg = fn_analysis.control_flow_graph();
+    let domtree = fn_analysis.dominator_tree();
+
+    let loop_name = Name::from("loop");
+    let body_name = Name::from("body");
+    let dead_name = Name::from("dead");
+
+    // %dead's edge to itself isn't a back edge, since %dead is unreachable
+    assert_eq!(cfg.back_edges(&domtree), vec![(&body_name, &loop_name)]);
+    assert_eq!(cfg.natural_loop(&dead_name, &domtree), None);
+    // nor is %dead in the loop, though it branches to %body
+    let body = cfg.natural_loop(&loop_name, &domtree).unwrap();
+    assert_eq!(
+        body.into_iter().sorted().collect::<Vec<_>>(),
+        vec![&body_name, &loop_name]
+    );
+    assert_eq!(cfg.natural_loops(&domtree).len(), 1);
+}
+
+#[test]
+fn dead_branch_into_loop_natural_loops() {
+    init_logging();
+    let module = Module::from_bc_path(TRIPCOUNT_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("dead_branch_into_loop");
+    let cfg = fn_analysis.control_flow_graph();
+    let domtree = fn_analysis.dominator_tree();
+
+    let loop_name = Name::from("loop");
+    let body_name = Name::from("body");
+
+    // the unreachable %dead isn't part of the loop
+    assert_eq!(cfg.back_edges(&domtree), vec![(&body_name, &loop_name)]);
+    let body = cfg.natural_loop(&loop_name, &domtree).unwrap();
+    assert_eq!(
+        body.into_iter().sorted().collect::<Vec<_>>(),
+        vec![&body_name, &loop_name]
+    );
+    let loops = cfg.natural_loops(&domtree);
+    assert_eq!(loops.len(), 1);
+    assert_eq!(
+        loops[&loop_name]
+            .iter()
+            .copied()
+            .sorted()
+            .collect::<Vec<_>>(),
+        vec![&body_name, &loop_name]
+    );
+}
+
+#[test]
+fn nested_loop_block_orders() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("nested_loop");
+    let cfg = fn_analysis.control_flow_graph();
+    let domtree = fn_analysis.dominator_tree();
+
+    let rpo: Vec<&Name> = cfg.reverse_post_order().collect();
+    assert_eq!(rpo.len(), 5);
+    assert_eq!(rpo[0], &Name::from(1));
+    assert_eq!(rpo.iter().unique().count(), 5);
+    // every edge other than a back edge goes forward in the order
+    let back_edges = cfg.back_edges(&domtree);
+    let position = |block: &Name| rpo.iter().position(|&b| b == block).unwrap();
+    for &from in &rpo {
+        for succ in cfg.succs(from) {
+            if let CFGNode::Block(to) = succ {
+                if !back_edges.contains(&(from, to)) {
+                    assert!(position(from) < position(to), "{} -> {}", from, to);
+                }
+            }
+        }
+    }
+
+    let mut post_order: Vec<&Name> = cfg.post_order().collect();
+    post_order.reverse();
+    assert_eq!(post_order, rpo);
+}
+
+#[test]
+fn nested_loop_blocks_between() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let cfg = analysis.fn_analysis("nested_loop").control_flow_graph();
+
+    let bb1_name = Name::from(1);
+    let bb5_name = Name::from(5);
+    let bb7_name = Name::from(7);
+    let bb10_name = Name::from(10);
+    let bb13_name = Name::from(13);
+
+    let between: Vec<&Name> = cfg
+        .blocks_between(&bb1_name, &bb7_name)
+        .into_iter()
+        .sorted()
+        .collect();
+    assert_eq!(
+        between,
+        vec![&bb1_name, &bb5_name, &bb7_name, &bb10_name, &bb13_name]
+    );
+    // the paths from 5 back to itself go around the outer loop
+    let between: Vec<&Name> = cfg
+        .blocks_between(&bb5_name, &bb5_name)
+        .into_iter()
+        .sorted()
+        .collect();
+    assert_eq!(between, vec![&bb5_name, &bb10_name, &bb13_name]);
+    let between: Vec<&Name> = cfg
+        .blocks_between(&bb13_name, &bb13_name)
+        .into_iter()
+        .sorted()
+        .collect();
+    assert_eq!(between, vec![&bb5_name, &bb10_name, &bb13_name]);
+    let between: Vec<&Name> = cfg
+        .blocks_between(&bb10_name, &bb7_name)
+        .into_iter()
+        .sorted()
+        .collect();
+    assert_eq!(between, vec![&bb5_name, &bb7_name, &bb10_name, &bb13_name]);
+    assert!(cfg.blocks_between(&bb7_name, &bb1_name).is_empty());
+    assert!(cfg.blocks_between(&bb5_name, &bb1_name).is_empty());
+}
+
+#[test]
+fn nested_loop_regions() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let regions = analysis.fn_analysis("nested_loop").region_info();
+
+    let bb1_name = Name::from(1);
+    let bb5_name = Name::from(5);
+    let bb7_name = Name::from(7);
+    let bb10_name = Name::from(10);
+    let bb13_name = Name::from(13);
+
+    // the inner loop is a single block which loops back to itself
+    let inner = regions.region_of(&bb13_name).unwrap();
+    assert_eq!(inner.entry, &bb13_name);
+    assert_eq!(inner.exit, CFGNode::Block(&bb10_name));
+    assert_eq!(inner.blocks().collect::<Vec<_>>(), vec![&bb13_name]);
+
+    let outer = regions.parent(inner).unwrap();
+    assert_eq!(outer.entry, &bb5_name);
+    assert_eq!(outer.exit, CFGNode::Block(&bb7_name));
+    assert_eq!(
+        outer.blocks().sorted().collect::<Vec<_>>(),
+        vec![&bb5_name, &bb10_name, &bb13_name]
+    );
+    assert_eq!(regions.region_of(&bb10_name), Some(outer));
+
+    let guarded = regions.parent(outer).unwrap();
+    assert_eq!(guarded.entry, &bb1_name);
+    assert_eq!(guarded.exit, CFGNode::Block(&bb7_name));
+    assert!(regions.parent(guarded).unwrap().is_top_level());
+    assert_eq!(regions.depth(inner), 3);
+
+    // no regions in an infinite loop
+    let regions = analysis.fn_analysis("infinite_loop").region_info();
+    assert_eq!(regions.regions().count(), 1);
+}
+
+#[test]
+fn loop_analysis() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+
+    // nested_loop: the outer loop %5 contains the inner loop %13
+    let loops = analysis.fn_analysis("nested_loop").loop_analysis();
+    let bb1_name = Name::from(1);
+    let bb5_name = Name::from(5);
+    let bb7_name = Name::from(7);
+    let bb10_name = Name::from(10);
+    let bb13_name = Name::from(13);
+    assert_eq!(loops.num_loops(), 2);
+    let headers: Vec<&Name> = loops.loops().map(|l| l.header).collect();
+    assert_eq!(headers, vec![&bb5_name, &bb13_name]);
+    assert!(loops.is_header(&bb13_name));
+    assert!(!loops.is_header(&bb10_name));
+
+    let outer = loops.loop_with_header(&bb5_name).unwrap();
+    assert_eq!(
+        outer.blocks().sorted().collect::<Vec<_>>(),
+        vec![&bb5_name, &bb10_name, &bb13_name]
+    );
+    assert!(outer.contains(&bb13_name));
+    assert!(!outer.contains(&bb1_name));
+    assert_eq!(outer.latches(), &[&bb10_name]);
+    assert_eq!(outer.exiting_blocks(), &[&bb10_name]);
+    assert_eq!(outer.exit_blocks(), &[&bb7_name]);
+
+    let inner = loops.loop_with_header(&bb13_name).unwrap();
+    assert_eq!(inner.num_blocks(), 1);
+    assert_eq!(inner.latches(), &[&bb13_name]);
+    assert_eq!(inner.exiting_blocks(), &[&bb13_name]);
+    assert_eq!(inner.exit_blocks(), &[&bb10_name]);
+
+    // search_array: two loops one after the other, the second with two exits
+    let loops = analysis.fn_analysis("search_array").loop_analysis();
+    let bb4_name = Name::from(4);
+    let bb11_name = Name::from(11);
+    let bb16_name = Name::from(16);
+    let bb19_name = Name::from(19);
+    let bb21_name = Name::from(21);
+    assert_eq!(loops.num_loops(), 2);
+    let first = loops.loop_with_header(&bb4_name).unwrap();
+    assert_eq!(first.latches(), &[&bb4_name]);
+    assert_eq!(first.exit_blocks(), &[&bb11_name]);
+    let second = loops.loop_with_header(&bb11_name).unwrap();
+    assert_eq!(
+        second.blocks().sorted().collect::<Vec<_>>(),
+        vec![&bb11_name, &bb16_name]
+    );
+    assert_eq!(second.latches(), &[&bb16_name]);
+    assert_eq!(second.exiting_blocks(), &[&bb11_name, &bb16_name]);
+    assert_eq!(second.exit_blocks(), &[&bb19_name, &bb21_name]);
+
+    // infinite_loop: a loop with no exits
+    let loops = analysis.fn_analysis("infinite_loop").loop_analysis();
+    let bb1_name = Name::from(1);
+    let infinite = loops.loop_with_header(&bb1_name).unwrap();
+    assert_eq!(infinite.latches(), &[&bb1_name]);
+    assert!(infinite.exiting_blocks().is_empty());
+    assert!(infinite.exit_blocks().is_empty());
+}
+
+#[test]
+fn unreachable_self_loop_analysis() {
+    init_logging();
+    let module = Module::from_bc_path(TRIPCOUNT_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let loops = analysis
+        .fn_analysis("unreachable_self_loop")
+        .loop_analysis();
+
+    let entry_name = Name::from("entry");
+    let loop_name = Name::from("loop");
+    let body_name = Name::from("body");
+    let dead_name = Name::from("dead");
+
+    // the unreachable %dead neither heads a loop nor is in one
+    assert_eq!(loops.num_loops(), 1);
+    assert!(!loops.is_header(&dead_name));
+    assert!(loops.loop_of(&dead_name).is_none());
+    let l = loops.loop_with_header(&loop_name).unwrap();
+    assert_eq!(
+        l.blocks().sorted().collect::<Vec<_>>(),
+        vec![&body_name, &loop_name]
+    );
+    assert_eq!(l.latches(), &[&body_name]);
+    assert_eq!(l.preheader(), Some(&entry_name));
+}
+
+#[test]
+fn dead_branch_into_loop_analysis() {
+    init_logging();
+    let module = Module::from_bc_path(TRIPCOUNT_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let loops = analysis
+        .fn_analysis("dead_branch_into_loop")
+        .loop_analysis();
+
+    let entry_name = Name::from("entry");
+    let loop_name = Name::from("loop");
+    let body_name = Name::from("body");
+    let dead_name = Name::from("dead");
+
+    // the unreachable %dead is neither in the loop nor entering it
+    assert_eq!(loops.num_loops(), 1);
+    assert!(loops.loop_of(&dead_name).is_none());
+    let l = loops.loop_with_header(&loop_name).unwrap();
+    assert_eq!(
+        l.blocks().sorted().collect::<Vec<_>>(),
+        vec![&body_name, &loop_name]
+    );
+    assert_eq!(l.latches(), &[&body_name]);
+    assert_eq!(l.preheader(), Some(&entry_name));
+}
+
+#[test]
+fn loop_preheaders_and_exits() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+
+    // nested_loop: %1 branches either to the outer loop or past it, so the
+    // outer loop has no preheader; but %5 is the inner loop's preheader
+    let loops = analysis.fn_analysis("nested_loop").loop_analysis();
+    let bb1_name = Name::from(1);
+    let bb5_name = Name::from(5);
+    let bb7_name = Name::from(7);
+    let bb10_name = Name::from(10);
+    let bb13_name = Name::from(13);
+    let outer = loops.loop_with_header(&bb5_name).unwrap();
+    assert_eq!(outer.entering_blocks(), &[&bb1_name]);
+    assert_eq!(outer.preheader(), None);
+    assert_eq!(outer.exit_edges(), &[(&bb10_name, &bb7_name)]);
+    assert_eq!(outer.exiting_block(), Some(&bb10_name));
+    assert_eq!(outer.exit_block(), Some(&bb7_name));
+    let inner = loops.loop_with_header(&bb13_name).unwrap();
+    assert_eq!(inner.entering_blocks(), &[&bb5_name]);
+    assert_eq!(inner.preheader(), Some(&bb5_name));
+    assert_eq!(inner.exit_edges(), &[(&bb13_name, &bb10_name)]);
+
+    // search_array: the first loop falls through to the second, which has
+    // two exits
+    let loops = analysis.fn_analysis("search_array").loop_analysis();
+    let bb4_name = Name::from(4);
+    let bb11_name = Name::from(11);
+    let bb16_name = Name::from(16);
+    let bb19_name = Name::from(19);
+    let bb21_name = Name::from(21);
+    let first = loops.loop_with_header(&bb4_name).unwrap();
+    assert_eq!(first.preheader(), Some(&bb1_name));
+    let second = loops.loop_with_header(&bb11_name).unwrap();
+    assert_eq!(second.entering_blocks(), &[&bb4_name]);
+    assert_eq!(second.preheader(), None);
+    assert_eq!(
+        second.exit_edges(),
+        &[(&bb11_name, &bb19_name), (&bb16_name, &bb21_name)]
+    );
+    assert_eq!(second.exiting_block(), None);
+    assert_eq!(second.exit_block(), None);
+
+    // infinite_loop: no exits at all
+    let loops = analysis.fn_analysis("infinite_loop").loop_analysis();
+    let bb0_name = Name::from(0);
+    let infinite = loops.loop_with_header(&bb1_name).unwrap();
+    assert_eq!(infinite.preheader(), Some(&bb0_name));
+    assert!(infinite.exit_edges().is_empty());
+    assert_eq!(infinite.exiting_block(), None);
+    assert_eq!(infinite.exit_block(), None);
+}
+
+#[test]
+fn loop_trip_counts() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let trip_count = |func: &str, header: usize| {
+        analysis
+            .fn_analysis(func)
+            .loop_analysis()
+            .loop_with_header(&Name::from(header))
+            .unwrap_or_else(|| panic!("no loop with header %{} in {}", header, func))
+            .trip_count()
+    };
+
+    assert_eq!(trip_count("loop_over_array", 7), Some(TripCount::Exact(10)));
+    assert_eq!(trip_count("sum_of_array", 4), Some(TripCount::Exact(10)));
+    assert_eq!(trip_count("sum_of_array", 11), Some(TripCount::Exact(10)));
+    assert_eq!(trip_count("loop_inside_cond", 5), Some(TripCount::Exact(3)));
+    // the second loop may also exit early, when it finds the element
+    assert_eq!(trip_count("search_array", 4), Some(TripCount::Exact(10)));
+    assert_eq!(trip_count("search_array", 11), Some(TripCount::AtMost(10)));
+    assert_eq!(trip_count("nested_loop", 13), Some(TripCount::Exact(10)));
+    // the bound is the function's parameter
+    assert_eq!(trip_count("nested_loop", 5), None);
+    assert_eq!(trip_count("for_loop", 9), None);
+    // the induction variable is in memory
+    assert_eq!(trip_count("loop_with_cond", 6), None);
+    assert_eq!(trip_count("infinite_loop", 1), None);
+
+    let module = Module::from_bc_path(TRIPCOUNT_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let trip_count = |func: &str| {
+        let fn_analysis = analysis.fn_analysis(func);
+        let loop_analysis = fn_analysis.loop_analysis();
+        assert_eq!(loop_analysis.num_loops(), 1);
+        let trip_count = loop_analysis.loops().next().unwrap().trip_count();
+        trip_count
+    };
+
+    assert_eq!(trip_count("header_exit"), Some(TripCount::Exact(5)));
+    assert_eq!(trip_count("count_down"), Some(TripCount::Exact(10)));
+    assert_eq!(trip_count("wrap_around"), Some(TripCount::Exact(11)));
+    assert_eq!(trip_count("never_equal"), None);
+    assert_eq!(trip_count("overflow"), None);
+    assert_eq!(trip_count("early_return"), Some(TripCount::AtMost(101)));
+    assert_eq!(trip_count("call_in_loop"), Some(TripCount::Exact(4)));
+    assert_eq!(TripCount::AtMost(101).max(), 101);
+}
+
+#[test]
+fn loop_simplify_form() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let bb4_name = Name::from(4);
+    let bb5_name = Name::from(5);
+    let bb6_name = Name::from(6);
+    let bb7_name = Name::from(7);
+    let bb11_name = Name::from(11);
+    let bb13_name = Name::from(13);
+    let bb16_name = Name::from(16);
+
+    let fn_analysis = analysis.fn_analysis("loop_over_array");
+    let loop_analysis = fn_analysis.loop_analysis();
+    let l = loop_analysis.loop_with_header(&bb7_name).unwrap();
+    assert_eq!(l.latch(), Some(&bb7_name));
+    assert!(l.has_dedicated_exits());
+    assert!(l.is_loop_simplify_form());
+    assert!(loop_analysis.is_loop_simplify_form());
+
+    let fn_analysis = analysis.fn_analysis("loop_with_cond");
+    let loop_analysis = fn_analysis.loop_analysis();
+    let l = loop_analysis.loop_with_header(&bb6_name).unwrap();
+    assert_eq!(l.latch(), Some(&bb16_name));
+    assert!(l.is_loop_simplify_form());
+
+    // the outer loop has no preheader and no dedicated exits, since its
+    // entering block %1 also branches around the loop to its exit block %7;
+    // the inner loop is in simplified form
+    let fn_analysis = analysis.fn_analysis("nested_loop");
+    let loop_analysis = fn_analysis.loop_analysis();
+    let outer = loop_analysis.loop_with_header(&bb5_name).unwrap();
+    assert_eq!(outer.preheader(), None);
+    assert!(!outer.has_dedicated_exits());
+    assert!(!outer.is_loop_simplify_form());
+    let inner = loop_analysis.loop_with_header(&bb13_name).unwrap();
+    assert!(inner.is_loop_simplify_form());
+    assert!(!loop_analysis.is_loop_simplify_form());
+
+    // the first loop's exit block is also the second loop's header, so it
+    // isn't dedicated; and the second loop's exit block %21 is also reached
+    // from %19, outside the loop
+    let fn_analysis = analysis.fn_analysis("search_array");
+    let loop_analysis = fn_analysis.loop_analysis();
+    let first = loop_analysis.loop_with_header(&bb4_name).unwrap();
+    assert_eq!(first.preheader(), Some(&Name::from(1)));
+    assert_eq!(first.latch(), Some(&bb4_name));
+    assert!(!first.has_dedicated_exits());
+    assert!(!first.is_loop_simplify_form());
+    let second = loop_analysis.loop_with_header(&bb11_name).unwrap();
+    assert_eq!(second.preheader(), None);
+    assert_eq!(second.latch(), Some(&bb16_name));
+    assert!(!second.has_dedicated_exits());
+    assert!(!second.is_loop_simplify_form());
+
+    // a loop without exits trivially has dedicated exits
+    let fn_analysis = analysis.fn_analysis("infinite_loop");
+    assert!(fn_analysis.loop_analysis().is_loop_simplify_form());
+
+    let module = Module::from_bc_path(TRIPCOUNT_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    for func in &module.functions {
+        let fn_analysis = analysis.fn_analysis(&func.name);
+        assert!(
+            fn_analysis.loop_analysis().is_loop_simplify_form(),
+            "{} isn't in loop-simplify form",
+            func.name
+        );
+    }
+}
+
+#[test]
+fn loop_effects() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let local = |op: &Operand| match op {
+        Operand::LocalOperand { name, .. } => name.clone(),
+        op => panic!("expected a local, got {}", op),
+    };
+    let names = |names: &[usize]| names.iter().map(|&n| Name::from(n)).collect::<Vec<_>>();
+    let bb5_name = Name::from(5);
+    let bb7_name = Name::from(7);
+    let bb11_name = Name::from(11);
+
+    // stores to the array, and exits when the counter reaches 10
+    let fn_analysis = analysis.fn_analysis("loop_over_array");
+    let loop_analysis = fn_analysis.loop_analysis();
+    let effects = loop_analysis.loop_with_header(&bb7_name).unwrap().effects();
+    let store_targets: Vec<Name> = effects.store_targets.iter().map(|op| local(op)).collect();
+    assert_eq!(store_targets, names(&[11]));
+    assert!(effects.callees.is_empty());
+    let exit_conditions: Vec<(Name, Name)> = effects
+        .exit_conditions
+        .iter()
+        .map(|&(block, condition)| (block.clone(), local(condition)))
+        .collect();
+    assert_eq!(exit_conditions, vec![(Name::from(7), Name::from(13))]);
+    let values: Vec<Name> = effects
+        .exit_condition_values
+        .iter()
+        .map(|&n| n.clone())
+        .collect();
+    assert_eq!(values, names(&[13, 12, 8]));
+
+    // only reads memory, and exits either when it finds the element or when
+    // the counter reaches 10
+    let fn_analysis = analysis.fn_analysis("search_array");
+    let loop_analysis = fn_analysis.loop_analysis();
+    let effects = loop_analysis
+        .loop_with_header(&bb11_name)
+        .unwrap()
+        .effects();
+    assert!(effects.store_targets.is_empty());
+    assert!(effects.callees.is_empty());
+    let exiting: Vec<Name> = effects
+        .exit_conditions
+        .iter()
+        .map(|&(block, _)| block.clone())
+        .collect();
+    assert_eq!(exiting, names(&[11, 16]));
+    let values: Vec<Name> = effects
+        .exit_condition_values
+        .iter()
+        .map(|&n| n.clone())
+        .collect();
+    assert_eq!(values, names(&[15, 14, 13, 2, 12, 17, 18]));
+
+    // the outer loop includes the inner loop's store, and its exit depends on
+    // the parameter, which is defined outside the loop
+    let fn_analysis = analysis.fn_analysis("nested_loop");
+    let loop_analysis = fn_analysis.loop_analysis();
+    let effects = loop_analysis.loop_with_header(&bb5_name).unwrap().effects();
+    let store_targets: Vec<Name> = effects.store_targets.iter().map(|op| local(op)).collect();
+    assert_eq!(store_targets, names(&[2]));
+    let values: Vec<Name> = effects
+        .exit_condition_values
+        .iter()
+        .map(|&n| n.clone())
+        .collect();
+    assert_eq!(values, names(&[12, 11, 6, 0]));
+
+    let module = Module::from_bc_path(TRIPCOUNT_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("call_in_loop");
+    let loop_analysis = fn_analysis.loop_analysis();
+    let effects = loop_analysis.loops().next().unwrap().effects();
+    let store_targets: Vec<Name> = effects.store_targets.iter().map(|op| local(op)).collect();
+    assert_eq!(store_targets, vec![Name::from("ptr")]);
+    let callees: Vec<String> = effects
+        .callees
+        .iter()
+        .map(|callee| match callee {
+            Either::Right(callee) => callee.to_string(),
+            Either::Left(_) => panic!("unexpected inline assembly"),
+        })
+        .collect();
+    assert_eq!(callees, vec!["@consume".to_owned()]);
+}
+
+#[test]
+fn loop_nesting() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let loops = analysis.fn_analysis("nested_loop").loop_analysis();
+
+    let bb1_name = Name::from(1);
+    let bb5_name = Name::from(5);
+    let bb7_name = Name::from(7);
+    let bb10_name = Name::from(10);
+    let bb13_name = Name::from(13);
+
+    let outer = loops.loop_with_header(&bb5_name).unwrap();
+    let inner = loops.loop_with_header(&bb13_name).unwrap();
+    assert_eq!(loops.top_level_loops().collect::<Vec<_>>(), vec![outer]);
+    assert_eq!(loops.parent_loop(outer), None);
+    assert_eq!(loops.parent_loop(inner), Some(outer));
+    assert_eq!(loops.subloops(outer).collect::<Vec<_>>(), vec![inner]);
+    assert_eq!(loops.subloops(inner).count(), 0);
+    assert_eq!(loops.depth(outer), 1);
+    assert_eq!(loops.depth(inner), 2);
+
+    assert_eq!(loops.loop_of(&bb1_name), None);
+    assert_eq!(loops.loop_of(&bb5_name), Some(outer));
+    assert_eq!(loops.loop_of(&bb10_name), Some(outer));
+    assert_eq!(loops.loop_of(&bb13_name), Some(inner));
+    assert_eq!(loops.loop_depth(&bb1_name), 0);
+    assert_eq!(loops.loop_depth(&bb5_name), 1);
+    assert_eq!(loops.loop_depth(&bb7_name), 0);
+    assert_eq!(loops.loop_depth(&bb10_name), 1);
+    assert_eq!(loops.loop_depth(&bb13_name), 2);
+
+    // search_array: two top-level loops
+    let loops = analysis.fn_analysis("search_array").loop_analysis();
+    assert_eq!(loops.top_level_loops().count(), 2);
+    assert!(loops.loops().all(|l| loops.depth(l) == 1));
+}
+
+#[test]
+fn nested_loop_distances() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("nested_loop");
+    let cfg = fn_analysis.control_flow_graph();
+
+    let bb1_name = Name::from(1);
+    let bb5_name = Name::from(5);
+    let bb7_name = Name::from(7);
+    let bb10_name = Name::from(10);
+    let bb13_name = Name::from(13);
+
+    assert_eq!(cfg.distance_from_entry(&bb1_name), Some(0));
+    assert_eq!(cfg.distance_from_entry(&bb5_name), Some(1));
+    assert_eq!(cfg.distance_from_entry(&bb7_name), Some(1));
+    assert_eq!(cfg.distance_from_entry(&bb13_name), Some(2));
+    assert_eq!(cfg.distance_from_entry(&bb10_name), Some(3));
+
+    let distances = cfg.distances_from_entry();
+    assert_eq!(distances.len(), 5);
+    assert_eq!(distances[&bb10_name], 3);
+}
+
+#[test]
+fn nested_loop_paths() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("nested_loop");
+    let cfg = fn_analysis.control_flow_graph();
+
+    let bb1_name = Name::from(1);
+    let bb5_name = Name::from(5);
+    let bb7_name = Name::from(7);
+    let bb10_name = Name::from(10);
+    let bb13_name = Name::from(13);
+
+    let paths: Vec<Vec<&Name>> = cfg
+        .paths(&bb1_name, &bb7_name, 10, 10)
+        .into_iter()
+        .sorted()
+        .collect();
+    assert_eq!(
+        paths,
+        vec![
+            vec![&bb1_name, &bb5_name, &bb13_name, &bb10_name, &bb7_name],
+            vec![&bb1_name, &bb7_name],
+        ]
+    );
+    // bounded by number of paths
+    assert_eq!(cfg.paths(&bb1_name, &bb7_name, 1, 10).len(), 1);
+    // bounded by length
+    assert_eq!(
+        cfg.paths(&bb1_name, &bb7_name, 10, 4),
+        vec![vec![&bb1_name, &bb7_name]]
+    );
+    // paths don't go around loops
+    assert_eq!(
+        cfg.paths(&bb13_name, &bb5_name, 10, 10),
+        vec![vec![&bb13_name, &bb10_name, &bb5_name]]
+    );
+    assert_eq!(
+        cfg.paths(&bb5_name, &bb5_name, 10, 10),
+        vec![vec![&bb5_name]]
+    );
+    assert!(cfg.paths(&bb7_name, &bb1_name, 10, 10).is_empty());
+}
+
+#[test]
+fn nested_loop_what_if() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("nested_loop");
+    let cfg = fn_analysis.control_flow_graph();
+
+    let bb1_name = Name::from(1);
+    let bb5_name = Name::from(5);
+    let bb7_name = Name::from(7);
+    let bb10_name = Name::from(10);
+    let bb13_name = Name::from(13);
+
+    // if the loops were never entered, they'd be unreachable
+    let view = cfg.without_edges(vec![(&bb1_name, CFGNode::Block(&bb5_name))]);
+    assert_eq!(
+        view.reverse_post_order().collect::<Vec<_>>(),
+        vec![&bb1_name, &bb7_name]
+    );
+    assert_eq!(view.num_blocks(), 5);
+    let domtree = DominatorTree::new(&view);
+    assert_eq!(domtree.idom(&bb7_name), Some(&bb1_name));
+    assert_eq!(domtree.idom(&bb5_name), None);
+    assert_eq!(domtree.idom(&bb13_name), None);
+    // the original CFG is unchanged
+    assert_eq!(cfg.succs(&bb1_name).count(), 2);
+    assert_eq!(
+        fn_analysis.dominator_tree().idom(&bb13_name),
+        Some(&bb5_name)
+    );
+
+    // if the inner loop always ran exactly once, it would no longer depend on
+    // its own branch
+    assert!(fn_analysis
+        .control_dependence_graph()
+        .get_imm_control_dependencies(&bb13_name)
+        .contains(&&bb13_name));
+    let view = cfg.without_edges(vec![(&bb13_name, CFGNode::Block(&bb13_name))]);
+    let postdomtree = PostDominatorTree::new(&view);
+    assert_eq!(
+        postdomtree.ipostdom(&bb5_name),
+        Some(CFGNode::Block(&bb13_name))
+    );
+    let cdg = ControlDependenceGraph::new(&view, &postdomtree);
+    assert!(!cdg
+        .get_imm_control_dependencies(&bb13_name)
+        .contains(&&bb13_name));
+
+    // removing the outer loop's latch leaves the inner loop with no way out
+    let view = cfg.without_blocks(vec![&bb10_name]);
+    assert_eq!(
+        view.blocks().collect::<Vec<_>>(),
+        vec![&bb1_name, &bb5_name, &bb7_name, &bb13_name]
+    );
+    assert_eq!(
+        view.succs(&bb13_name).collect::<Vec<_>>(),
+        vec![CFGNode::Block(&bb13_name)]
+    );
+    let postdomtree = PostDominatorTree::new(&view);
+    assert_eq!(
+        postdomtree.ipostdom(&bb1_name),
+        Some(CFGNode::Block(&bb7_name))
+    );
+    assert_eq!(postdomtree.ipostdom(&bb5_name), None);
+}
+
+#[test]
+fn nested_loop_condensation() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let condensation = analysis
+        .fn_analysis("nested_loop")
+        .control_flow_graph()
+        .condensation();
+
+    let bb1_name = Name::from(1);
+    let bb5_name = Name::from(5);
+    let bb7_name = Name::from(7);
+    let bb10_name = Name::from(10);
+    let bb13_name = Name::from(13);
+
+    // both loops collapse into a single SCC
+    assert_eq!(condensation.num_sccs(), 3);
+    assert_eq!(
+        condensation.sccs(),
+        &[
+            vec![&bb1_name],
+            vec![&bb5_name, &bb10_name, &bb13_name],
+            vec![&bb7_name],
+        ]
+    );
+    assert_eq!(condensation.scc_index(&bb13_name), 1);
+    assert_eq!(condensation.scc(2), &[&bb7_name]);
+    assert!(!condensation.is_cyclic(0));
+    assert!(condensation.is_cyclic(1));
+    assert!(!condensation.is_cyclic(2));
+    assert_eq!(
+        condensation.succs(0).sorted().collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+    assert_eq!(
+        condensation.preds(2).sorted().collect::<Vec<_>>(),
+        vec![0, 1]
+    );
+    assert_eq!(condensation.succs(2).count(), 0);
+
+    // the longest path goes from the entry block through the loops to %7
+    assert_eq!(condensation.entry_scc(), 0);
+    assert_eq!(condensation.longest_path(), vec![0, 1, 2]);
+    let function = module
+        .get_func_by_name("nested_loop")
+        .expect("Failed to find function");
+    let (instrs, path) = condensation.longest_weighted_path(|blocks| {
+        blocks
+            .iter()
+            .map(|&block| function.get_bb_by_name(block).unwrap().instrs.len() as u64 + 1)
+            .sum()
+    });
+    assert_eq!(instrs, 22);
+    assert_eq!(path, vec![0, 1, 2]);
+
+    // a single block looping back to itself is cyclic
+    let condensation = analysis
+        .fn_analysis("infinite_loop")
+        .control_flow_graph()
+        .condensation();
+    assert_eq!(condensation.num_sccs(), 2);
+    assert!(!condensation.is_cyclic(0));
+    assert!(condensation.is_cyclic(1));
+}
+
 #[test]
 fn while_loop_domtree() {
     init_logging();
@@ -659,32 +1521,388 @@ fn nested_loop_domtree() {
     //  | /
     //  7
 
-    let domtree = fn_analysis.dominator_tree();
-    assert_eq!(domtree.idom(&Name::from(1)), None);
-    assert_eq!(domtree.idom(&Name::from(5)), Some(&Name::from(1)));
-    assert_eq!(domtree.idom(&Name::from(10)), Some(&Name::from(13)));
-    assert_eq!(domtree.idom(&Name::from(13)), Some(&Name::from(5)));
-    assert_eq!(domtree.idom(&Name::from(7)), Some(&Name::from(1)));
-    assert_eq!(domtree.idom_of_return(), Some(&Name::from(7)));
+    let domtree = fn_analysis.dominator_tree();
+    assert_eq!(domtree.idom(&Name::from(1)), None);
+    assert_eq!(domtree.idom(&Name::from(5)), Some(&Name::from(1)));
+    assert_eq!(domtree.idom(&Name::from(10)), Some(&Name::from(13)));
+    assert_eq!(domtree.idom(&Name::from(13)), Some(&Name::from(5)));
+    assert_eq!(domtree.idom(&Name::from(7)), Some(&Name::from(1)));
+    assert_eq!(domtree.idom_of_return(), Some(&Name::from(7)));
+
+    let postdomtree = fn_analysis.postdominator_tree();
+    assert_eq!(
+        postdomtree.ipostdom(&Name::from(1)),
+        Some(CFGNode::Block(&Name::from(7)))
+    );
+    assert_eq!(
+        postdomtree.ipostdom(&Name::from(5)),
+        Some(CFGNode::Block(&Name::from(13)))
+    );
+    assert_eq!(postdomtree.ipostdom(&Name::from(7)), Some(CFGNode::Return));
+    assert_eq!(
+        postdomtree.ipostdom(&Name::from(10)),
+        Some(CFGNode::Block(&Name::from(7)))
+    );
+    assert_eq!(
+        postdomtree.ipostdom(&Name::from(13)),
+        Some(CFGNode::Block(&Name::from(10)))
+    );
+}
+
+#[test]
+fn nested_loop_domtree_traversals() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("nested_loop");
+    let domtree = fn_analysis.dominator_tree();
+
+    let bb1_name = Name::from(1);
+    let bb5_name = Name::from(5);
+    let bb7_name = Name::from(7);
+    let bb10_name = Name::from(10);
+    let bb13_name = Name::from(13);
+    let bb99_name = Name::from(99);
+    let bb1 = CFGNode::Block(&bb1_name);
+    let bb5 = CFGNode::Block(&bb5_name);
+    let bb7 = CFGNode::Block(&bb7_name);
+    let bb10 = CFGNode::Block(&bb10_name);
+    let bb13 = CFGNode::Block(&bb13_name);
+    let bb99 = CFGNode::Block(&bb99_name);
+
+    // %1 dominates %5 and %7; %5 dominates %13, which dominates %10; and %7
+    // dominates Return
+    let preorder: Vec<CFGNode> = domtree.preorder().collect();
+    assert_eq!(preorder.len(), 6);
+    assert_eq!(preorder[0], bb1);
+    let position = |order: &[CFGNode], node| order.iter().position(|&n| n == node).unwrap();
+    assert!(position(&preorder, bb5) < position(&preorder, bb13));
+    assert!(position(&preorder, bb13) < position(&preorder, bb10));
+    assert!(position(&preorder, bb7) < position(&preorder, CFGNode::Return));
+
+    let postorder: Vec<CFGNode> = domtree.postorder().collect();
+    assert_eq!(postorder.len(), 6);
+    assert_eq!(postorder[5], bb1);
+    assert!(position(&postorder, bb10) < position(&postorder, bb13));
+    assert!(position(&postorder, bb13) < position(&postorder, bb5));
+    assert!(position(&postorder, CFGNode::Return) < position(&postorder, bb7));
+
+    // subtrees
+    assert_eq!(
+        domtree.preorder_from(bb5).collect::<Vec<_>>(),
+        vec![bb5, bb13, bb10]
+    );
+    assert_eq!(
+        domtree.postorder_from(bb5).collect::<Vec<_>>(),
+        vec![bb10, bb13, bb5]
+    );
+    assert_eq!(
+        domtree.preorder_from(bb7).collect::<Vec<_>>(),
+        vec![bb7, CFGNode::Return]
+    );
+    assert_eq!(domtree.preorder_from(bb99).count(), 0);
+    assert_eq!(domtree.postorder_from(bb99).count(), 0);
+}
+
+#[test]
+fn nested_loop_domtree_siblings_and_chains() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("nested_loop");
+    let domtree = fn_analysis.dominator_tree();
+
+    let bb1_name = Name::from(1);
+    let bb5_name = Name::from(5);
+    let bb7_name = Name::from(7);
+    let bb10_name = Name::from(10);
+    let bb13_name = Name::from(13);
+    let bb99_name = Name::from(99);
+
+    assert_eq!(
+        domtree.siblings(&bb5_name).collect::<Vec<_>>(),
+        vec![CFGNode::Block(&bb7_name)]
+    );
+    assert_eq!(
+        domtree.siblings(&bb7_name).collect::<Vec<_>>(),
+        vec![CFGNode::Block(&bb5_name)]
+    );
+    assert_eq!(domtree.siblings(&bb13_name).count(), 0);
+    assert_eq!(domtree.siblings(&bb1_name).count(), 0);
+    assert_eq!(domtree.siblings(&bb99_name).count(), 0);
+
+    assert_eq!(
+        domtree.idom_chain(&bb10_name).collect::<Vec<_>>(),
+        vec![&bb13_name, &bb5_name, &bb1_name]
+    );
+    assert_eq!(
+        domtree.idom_chain(&bb7_name).collect::<Vec<_>>(),
+        vec![&bb1_name]
+    );
+    assert_eq!(domtree.idom_chain(&bb1_name).count(), 0);
+    assert_eq!(domtree.idom_chain(&bb99_name).count(), 0);
+}
+
+#[test]
+fn nested_loop_blocks_in_dominator_order() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("nested_loop");
+
+    let bb1_name = Name::from(1);
+    let bb5_name = Name::from(5);
+    let bb7_name = Name::from(7);
+    let bb10_name = Name::from(10);
+    let bb13_name = Name::from(13);
+
+    let blocks: Vec<DominatorOrderBlock> = fn_analysis.blocks_in_dominator_order().collect();
+    let order: Vec<(&Name, usize)> = blocks
+        .iter()
+        .map(|info| (&info.block.name, info.depth))
+        .collect();
+    assert_eq!(
+        order,
+        vec![
+            (&bb1_name, 0),
+            (&bb5_name, 1),
+            (&bb13_name, 2),
+            (&bb10_name, 3),
+            (&bb7_name, 1),
+        ]
+    );
+
+    assert_eq!(blocks[0].idom, None);
+    assert_eq!(blocks[0].preds, Vec::<&Name>::new());
+    assert_eq!(
+        blocks[0].succs.iter().copied().sorted().collect::<Vec<_>>(),
+        vec![CFGNode::Block(&bb5_name), CFGNode::Block(&bb7_name)]
+    );
+    assert_eq!(blocks[0].control_dependencies, Vec::<&Name>::new());
+
+    // %10 is in the outer loop, which runs while %10's branch goes to %5
+    let bb10 = &blocks[3];
+    assert_eq!(bb10.idom, Some(&bb13_name));
+    assert_eq!(bb10.preds, vec![&bb13_name]);
+    assert_eq!(
+        bb10.control_dependencies
+            .iter()
+            .copied()
+            .sorted()
+            .collect::<Vec<_>>(),
+        vec![&bb1_name, &bb10_name]
+    );
+
+    let bb7 = &blocks[4];
+    assert_eq!(bb7.idom, Some(&bb1_name));
+    assert_eq!(bb7.succs, vec![CFGNode::Return]);
+    assert_eq!(bb7.control_dependencies, Vec::<&Name>::new());
+}
+
+#[test]
+fn nested_loop_postdomtree_queries() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("nested_loop");
+    let domtree = fn_analysis.dominator_tree();
+    let postdomtree = fn_analysis.postdominator_tree();
+
+    let bb1_name = Name::from(1);
+    let bb5_name = Name::from(5);
+    let bb7_name = Name::from(7);
+    let bb10_name = Name::from(10);
+    let bb13_name = Name::from(13);
+    let bb99_name = Name::from(99);
+    let bb1 = CFGNode::Block(&bb1_name);
+    let bb5 = CFGNode::Block(&bb5_name);
+    let bb7 = CFGNode::Block(&bb7_name);
+    let bb10 = CFGNode::Block(&bb10_name);
+    let bb13 = CFGNode::Block(&bb13_name);
+    let bb99 = CFGNode::Block(&bb99_name);
 
-    let postdomtree = fn_analysis.postdominator_tree();
+    // Return postdominates %7, which postdominates %1 and %10; %10
+    // postdominates %13, which postdominates %5
     assert_eq!(
-        postdomtree.ipostdom(&Name::from(1)),
-        Some(CFGNode::Block(&Name::from(7)))
+        postdomtree.siblings(&bb1_name).collect::<Vec<_>>(),
+        vec![bb10]
     );
+    assert_eq!(postdomtree.siblings(&bb7_name).count(), 0);
+    assert_eq!(postdomtree.siblings(&bb99_name).count(), 0);
     assert_eq!(
-        postdomtree.ipostdom(&Name::from(5)),
-        Some(CFGNode::Block(&Name::from(13)))
+        postdomtree.ipostdom_chain(&bb5_name).collect::<Vec<_>>(),
+        vec![bb13, bb10, bb7, CFGNode::Return]
     );
-    assert_eq!(postdomtree.ipostdom(&Name::from(7)), Some(CFGNode::Return));
     assert_eq!(
-        postdomtree.ipostdom(&Name::from(10)),
-        Some(CFGNode::Block(&Name::from(7)))
+        postdomtree.ipostdom_chain(&bb7_name).collect::<Vec<_>>(),
+        vec![CFGNode::Return]
     );
+    assert_eq!(postdomtree.ipostdom_chain(&bb99_name).count(), 0);
+
+    let preorder: Vec<CFGNode> = postdomtree.preorder().collect();
+    assert_eq!(preorder.len(), 6);
+    assert_eq!(preorder[0], CFGNode::Return);
+    assert_eq!(preorder[1], bb7);
+    let postorder: Vec<CFGNode> = postdomtree.postorder().collect();
+    assert_eq!(postorder.len(), 6);
+    assert_eq!(postorder[4], bb7);
+    assert_eq!(postorder[5], CFGNode::Return);
     assert_eq!(
-        postdomtree.ipostdom(&Name::from(13)),
-        Some(CFGNode::Block(&Name::from(10)))
+        postdomtree.preorder_from(bb10).collect::<Vec<_>>(),
+        vec![bb10, bb13, bb5]
+    );
+    assert_eq!(
+        postdomtree.postorder_from(bb10).collect::<Vec<_>>(),
+        vec![bb5, bb13, bb10]
+    );
+    assert_eq!(postdomtree.preorder_from(bb99).count(), 0);
+    assert_eq!(postdomtree.postorder_from(bb99).count(), 0);
+
+    assert_eq!(
+        postdomtree.postdominated_by(bb10),
+        [bb10, bb13, bb5].into_iter().collect()
+    );
+    assert_eq!(
+        postdomtree.postdominated_by(bb1),
+        [bb1].into_iter().collect()
+    );
+    assert!(postdomtree.postdominated_by(bb99).is_empty());
+    assert_eq!(
+        domtree.dominated_by(bb5),
+        [bb5, bb13, bb10].into_iter().collect()
     );
+    assert!(domtree.dominated_by(bb99).is_empty());
+
+    // the dominated sets agree with the constant-time queries
+    for node in postdomtree.preorder() {
+        let postdominated = postdomtree.postdominated_by(node);
+        for other in postdomtree.preorder() {
+            assert_eq!(
+                postdominated.contains(&other),
+                postdomtree.postdominates(node, other)
+            );
+        }
+    }
+}
+
+#[test]
+fn semi_nca_matches_iterative() {
+    init_logging();
+    // these modules include loops, irreducible control flow (from `callbr`),
+    // exception handling, and functions which never return
+    for bc_path in &[
+        LOOP_BC_PATH,
+        "tests/bcfiles/basic.bc",
+        "tests/bcfiles/callbr.bc",
+        "tests/bcfiles/exceptions.bc",
+        "tests/bcfiles/noexit.bc",
+    ] {
+        let module = Module::from_bc_path(bc_path)
+            .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+        let analysis = ModuleAnalysis::new(&module);
+        for func in module.functions.iter() {
+            let cfg = analysis.fn_analysis(&func.name).control_flow_graph();
+            let iterative = DominatorTree::with_algorithm(&cfg, DominatorAlgorithm::Iterative);
+            let semi_nca = DominatorTree::with_algorithm(&cfg, DominatorAlgorithm::SemiNCA);
+            let post_iterative =
+                PostDominatorTree::with_algorithm(&cfg, DominatorAlgorithm::Iterative);
+            let post_semi_nca =
+                PostDominatorTree::with_algorithm(&cfg, DominatorAlgorithm::SemiNCA);
+            for block in cfg.blocks() {
+                assert_eq!(
+                    iterative.idom(block),
+                    semi_nca.idom(block),
+                    "idom of {} in {}",
+                    block,
+                    func.name
+                );
+                assert_eq!(
+                    post_iterative.ipostdom(block),
+                    post_semi_nca.ipostdom(block),
+                    "ipostdom of {} in {}",
+                    block,
+                    func.name
+                );
+            }
+            assert_eq!(iterative.idom_of_return(), semi_nca.idom_of_return());
+            assert_eq!(
+                post_iterative
+                    .children_of_return()
+                    .sorted()
+                    .collect::<Vec<_>>(),
+                post_semi_nca
+                    .children_of_return()
+                    .sorted()
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+}
+
+#[test]
+fn dominance_matches_idom_chains() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    for func in module.functions.iter() {
+        let fn_analysis = analysis.fn_analysis(&func.name);
+        let cfg = fn_analysis.control_flow_graph();
+        let domtree = fn_analysis.dominator_tree();
+        let postdomtree = fn_analysis.postdominator_tree();
+        let nodes: Vec<CFGNode> = cfg
+            .blocks()
+            .map(CFGNode::Block)
+            .chain(std::iter::once(CFGNode::Return))
+            .collect();
+        for &b in &nodes {
+            // find the nodes strictly dominating and postdominating `b` by
+            // walking up the trees
+            let mut dominators = vec![];
+            let mut node = b;
+            while let Some(idom) = match node {
+                CFGNode::Block(block) => domtree.idom(block),
+                CFGNode::Return => domtree.idom_of_return(),
+            } {
+                node = CFGNode::Block(idom);
+                dominators.push(node);
+            }
+            let mut postdominators = vec![];
+            let mut node = b;
+            while let CFGNode::Block(block) = node {
+                match postdomtree.ipostdom(block) {
+                    Some(ipostdom) => {
+                        node = ipostdom;
+                        postdominators.push(node);
+                    }
+                    None => break,
+                }
+            }
+
+            for &a in &nodes {
+                assert_eq!(
+                    domtree.dominates(a, b),
+                    a == b || dominators.contains(&a),
+                    "does {} dominate {} in {}",
+                    a,
+                    b,
+                    func.name
+                );
+                assert_eq!(
+                    postdomtree.postdominates(a, b),
+                    a == b || postdominators.contains(&a),
+                    "does {} postdominate {} in {}",
+                    a,
+                    b,
+                    func.name
+                );
+            }
+        }
+    }
 }
 
 #[test]
@@ -951,6 +2169,337 @@ fn loop_with_cond_cdg() {
     );
 }
 
+#[test]
+fn loop_with_cond_ddg() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("loop_with_cond");
+    let ddg = fn_analysis.data_dependence_graph();
+
+    let bb1_name = Name::from(1);
+    let bb6_name = Name::from(6);
+    let bb10_name = Name::from(10);
+    let bb13_name = Name::from(13);
+    let bb16_name = Name::from(16);
+    let bb20_name = Name::from(20);
+    let loc = |block, index| InstructionLocation { block, index };
+
+    // %2 and %3 are distinct allocas, so loads of %3 depend only on the stores
+    // to %3: the initial one in %1, and the one in the loop
+    let load7 = loc(&bb6_name, 0);
+    assert_eq!(
+        ddg.dependencies(load7).sorted().collect::<Vec<_>>(),
+        vec![loc(&bb1_name, 1), loc(&bb1_name, 7), loc(&bb16_name, 2)]
+    );
+    assert_eq!(
+        ddg.edge_kinds(load7, loc(&bb1_name, 1)),
+        &[DataDependenceKind::Value(&Name::from(3))]
+    );
+    assert_eq!(
+        ddg.edge_kinds(load7, loc(&bb16_name, 2)),
+        &[DataDependenceKind::Memory]
+    );
+    // calls to `llvm.lifetime.*` don't count as writes
+    assert!(!ddg.is_data_dependent(load7, loc(&bb1_name, 6)));
+    // nor does the store to %2
+    assert!(!ddg.is_data_dependent(load7, loc(&bb1_name, 4)));
+
+    // the loads of %2 depend on the stores to %2
+    assert_eq!(
+        ddg.dependencies(loc(&bb20_name, 0))
+            .sorted()
+            .collect::<Vec<_>>(),
+        vec![loc(&bb1_name, 0), loc(&bb1_name, 4), loc(&bb13_name, 2)]
+    );
+    // the store in %13 reaches the load before it, around the loop
+    assert!(ddg.is_data_dependent(loc(&bb13_name, 0), loc(&bb13_name, 2)));
+
+    // the store to %3 in the loop is read by each load of %3, and depends on
+    // the value it stores and on the address
+    let store = loc(&bb16_name, 2);
+    assert_eq!(
+        ddg.dependents(store).sorted().collect::<Vec<_>>(),
+        vec![loc(&bb6_name, 0), loc(&bb10_name, 0), loc(&bb16_name, 0)]
+    );
+    let deps: Vec<_> = ddg
+        .labeled_dependencies(store)
+        .sorted_by_key(|&(dep, _)| dep)
+        .collect();
+    assert_eq!(
+        deps,
+        vec![
+            (loc(&bb1_name, 1), DataDependenceKind::Value(&Name::from(3))),
+            (
+                loc(&bb16_name, 1),
+                DataDependenceKind::Value(&Name::from(18))
+            ),
+        ]
+    );
+
+    // the conditional branch depends on the comparison
+    assert_eq!(
+        ddg.dependencies(loc(&bb16_name, 4)).collect::<Vec<_>>(),
+        vec![loc(&bb16_name, 3)]
+    );
+}
+
+#[test]
+fn loop_with_cond_slices() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("loop_with_cond");
+
+    let bb1_name = Name::from(1);
+    let bb6_name = Name::from(6);
+    let bb10_name = Name::from(10);
+    let bb13_name = Name::from(13);
+    let bb16_name = Name::from(16);
+    let bb20_name = Name::from(20);
+    let loc = |block, index| InstructionLocation { block, index };
+
+    // %12 depends on the loads and stores of %3, the branch in %6 leading to
+    // %10, and the loop's backedge condition; but not on %2
+    let slice = fn_analysis.backward_slice(SliceCriterion::Value(&Name::from(12)));
+    assert_eq!(
+        slice.instructions(),
+        &[
+            loc(&bb1_name, 1),
+            loc(&bb1_name, 7),
+            loc(&bb6_name, 0),
+            loc(&bb6_name, 1),
+            loc(&bb6_name, 2),
+            loc(&bb6_name, 3),
+            loc(&bb10_name, 0),
+            loc(&bb10_name, 1),
+            loc(&bb16_name, 0),
+            loc(&bb16_name, 1),
+            loc(&bb16_name, 2),
+            loc(&bb16_name, 3),
+            loc(&bb16_name, 4),
+        ]
+    );
+    assert_eq!(
+        slice.blocks(),
+        &[&bb1_name, &bb6_name, &bb10_name, &bb16_name]
+    );
+
+    // the load in %6 influences the branches in %6 and %10, and through them,
+    // everything in %13 and the final value of %2
+    let slice = fn_analysis.forward_slice(SliceCriterion::Instruction(loc(&bb6_name, 0)));
+    assert_eq!(
+        slice.blocks(),
+        &[&bb6_name, &bb10_name, &bb13_name, &bb20_name]
+    );
+    assert_eq!(
+        slice
+            .instructions()
+            .iter()
+            .filter(|inst| inst.block == &bb13_name)
+            .count(),
+        4
+    );
+    assert!(slice.contains(loc(&bb20_name, 4)));
+    // but not the lifetime intrinsics
+    assert!(!slice.contains(loc(&bb20_name, 2)));
+}
+
+#[test]
+fn loop_with_cond_reaching_defs() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("loop_with_cond");
+    let rd = fn_analysis.reaching_definitions();
+
+    let bb1_name = Name::from(1);
+    let bb6_name = Name::from(6);
+    let bb13_name = Name::from(13);
+    let bb16_name = Name::from(16);
+    let bb20_name = Name::from(20);
+    let slot2 = Name::from(2);
+    let slot3 = Name::from(3);
+    let loc = |block, index| InstructionLocation { block, index };
+
+    // the addresses of %2 and %3 are only passed to `llvm.lifetime.*`
+    assert!(!rd.is_escaped(&slot2));
+    assert!(!rd.is_escaped(&slot3));
+
+    // the initial store to %3 and the one in the loop both reach the header
+    assert_eq!(
+        rd.definitions_reaching(loc(&bb6_name, 0), Variable::StackSlot(&slot3)),
+        vec![loc(&bb1_name, 7), loc(&bb16_name, 2)]
+    );
+    // the store to %2 in %13 reaches its own block around the loop
+    assert_eq!(
+        rd.definitions_reaching(loc(&bb13_name, 0), Variable::StackSlot(&slot2)),
+        vec![loc(&bb1_name, 4), loc(&bb13_name, 2)]
+    );
+    assert_eq!(
+        rd.definitions_reaching(loc(&bb20_name, 0), Variable::StackSlot(&slot2)),
+        vec![loc(&bb1_name, 4), loc(&bb13_name, 2)]
+    );
+    // SSA values defined in the loop reach the header along the backedge
+    let def18 = ReachingDefinition {
+        variable: Variable::Value(&Name::from(18)),
+        location: loc(&bb16_name, 1),
+    };
+    assert!(rd.reaching_block_entry(&bb6_name).any(|def| def == def18));
+}
+
+#[test]
+fn loop_with_cond_liveness() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("loop_with_cond");
+    let liveness = fn_analysis.liveness();
+
+    let bb1_name = Name::from(1);
+    let bb6_name = Name::from(6);
+    let bb16_name = Name::from(16);
+    let bb20_name = Name::from(20);
+    let param0 = Name::from(0);
+
+    // the parameter is used by the loop's exit test, so it's live throughout
+    // the loop, including around the backedge, but not after the loop
+    assert!(liveness.is_live_out(&param0, &bb1_name));
+    assert!(liveness.is_live_in(&param0, &bb6_name));
+    assert!(liveness.is_live_out(&param0, &bb16_name));
+    assert!(!liveness.is_live_in(&param0, &bb20_name));
+
+    // the allocas' addresses are used until the `llvm.lifetime.end`s
+    assert_eq!(
+        liveness.live_in(&bb20_name).sorted().collect::<Vec<_>>(),
+        vec![&Name::from(2), &Name::from(4), &Name::from(5)]
+    );
+    assert!(liveness.is_live_in(&Name::from(3), &bb16_name));
+    assert!(!liveness.is_live_in(&Name::from(3), &bb20_name));
+
+    // %18 is live from its definition until the exit test
+    let value18 = Name::from(18);
+    assert!(!liveness.is_live_at(&value18, &bb16_name, 1));
+    assert!(liveness.is_live_at(&value18, &bb16_name, 2));
+    assert!(liveness.is_live_at(&value18, &bb16_name, 3));
+    assert!(!liveness.is_live_at(&value18, &bb16_name, 4));
+    assert!(!liveness.is_live_out(&value18, &bb16_name));
+}
+
+#[test]
+fn loop_with_cond_memory_ssa() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("loop_with_cond");
+    let mssa = fn_analysis.memory_ssa();
+
+    let bb1_name = Name::from(1);
+    let bb6_name = Name::from(6);
+    let bb10_name = Name::from(10);
+    let bb13_name = Name::from(13);
+    let bb16_name = Name::from(16);
+    let bb20_name = Name::from(20);
+    let loc = |block, index| InstructionLocation { block, index };
+    let def = |block, index| MemoryDefinition::Def(loc(block, index));
+
+    // the stores are defs and the loads are uses, but the `llvm.lifetime.*`
+    // calls don't access memory
+    assert!(mssa.is_memory_def(loc(&bb1_name, 4)));
+    assert!(mssa.is_memory_use(loc(&bb6_name, 0)));
+    assert!(!mssa.is_memory_def(loc(&bb1_name, 3)));
+    assert!(!mssa.is_memory_use(loc(&bb1_name, 3)));
+    assert_eq!(mssa.defining_access(loc(&bb1_name, 3)), None);
+
+    // memory `phi`s are needed at the loop header, and where the paths around
+    // the store in %13 join
+    assert_eq!(
+        mssa.phi_blocks().sorted().collect::<Vec<_>>(),
+        vec![&bb6_name, &bb16_name]
+    );
+    assert_eq!(
+        mssa.phi(&bb6_name)
+            .unwrap()
+            .iter()
+            .sorted()
+            .collect::<Vec<_>>(),
+        vec![
+            &(&bb1_name, def(&bb1_name, 7)),
+            &(&bb16_name, def(&bb16_name, 2))
+        ]
+    );
+    assert_eq!(
+        mssa.phi(&bb16_name)
+            .unwrap()
+            .iter()
+            .sorted()
+            .collect::<Vec<_>>(),
+        vec![
+            &(&bb10_name, MemoryDefinition::Phi(&bb6_name)),
+            &(&bb13_name, def(&bb13_name, 2))
+        ]
+    );
+    assert_eq!(mssa.phi(&bb10_name), None);
+
+    // defining accesses
+    assert_eq!(
+        mssa.defining_access(loc(&bb1_name, 4)),
+        Some(MemoryDefinition::LiveOnEntry)
+    );
+    assert_eq!(
+        mssa.defining_access(loc(&bb1_name, 7)),
+        Some(def(&bb1_name, 4))
+    );
+    assert_eq!(
+        mssa.defining_access(loc(&bb13_name, 2)),
+        Some(MemoryDefinition::Phi(&bb6_name))
+    );
+    assert_eq!(
+        mssa.defining_access(loc(&bb16_name, 0)),
+        Some(MemoryDefinition::Phi(&bb16_name))
+    );
+    assert_eq!(
+        mssa.defining_access(loc(&bb20_name, 0)),
+        Some(def(&bb16_name, 2))
+    );
+    assert_eq!(mssa.exit_version(&bb13_name), def(&bb13_name, 2));
+    assert_eq!(
+        mssa.uses(MemoryDefinition::Phi(&bb6_name)),
+        vec![
+            loc(&bb10_name, 0),
+            loc(&bb13_name, 0),
+            loc(&bb13_name, 2),
+            loc(&bb6_name, 0)
+        ]
+        .into_iter()
+        .sorted()
+        .collect::<Vec<_>>()
+    );
+
+    // the load of %3 in %16 isn't clobbered by the store to %2 in %13, so its
+    // clobber is the version of memory at the loop header
+    assert_eq!(
+        mssa.clobbering_definition(loc(&bb16_name, 0)),
+        Some(MemoryDefinition::Phi(&bb6_name))
+    );
+    // the load of %2 in %13 is clobbered by the initial store to %2, or by
+    // itself around the loop
+    assert_eq!(
+        mssa.clobbering_definition(loc(&bb13_name, 0)),
+        Some(MemoryDefinition::Phi(&bb6_name))
+    );
+    // the initial store to %3 isn't clobbered by the store to %2 before it
+    assert_eq!(
+        mssa.clobbering_definition(loc(&bb1_name, 7)),
+        Some(MemoryDefinition::LiveOnEntry)
+    );
+}
+
 #[test]
 fn loop_inside_cond_cdg() {
     init_logging();
@@ -1134,6 +2683,127 @@ fn nested_loop_cdg() {
             .collect::<Vec<_>>(),
         vec![&Name::from(1), &Name::from(10), &Name::from(13)]
     );
+
+    // the inverse queries: which blocks does each branch guard?
+    let bb5_name = Name::from(5);
+    let bb10_name = Name::from(10);
+    let bb13_name = Name::from(13);
+    let guarded = vec![
+        CFGNode::Block(&bb5_name),
+        CFGNode::Block(&bb10_name),
+        CFGNode::Block(&bb13_name),
+    ];
+    assert_eq!(
+        cdg.get_imm_control_dependents(&Name::from(1))
+            .sorted()
+            .collect::<Vec<_>>(),
+        guarded
+    );
+    assert_eq!(
+        cdg.get_imm_control_dependents(&bb10_name)
+            .sorted()
+            .collect::<Vec<_>>(),
+        guarded
+    );
+    assert_eq!(
+        cdg.get_imm_control_dependents(&bb13_name)
+            .collect::<Vec<_>>(),
+        vec![CFGNode::Block(&bb13_name)]
+    );
+    assert_eq!(cdg.get_imm_control_dependents(&bb5_name).count(), 0);
+    assert_eq!(cdg.get_imm_control_dependents(&Name::from(7)).count(), 0);
+    assert_eq!(
+        cdg.get_control_dependents(&Name::from(1))
+            .sorted()
+            .collect::<Vec<_>>(),
+        guarded
+    );
+    assert_eq!(
+        cdg.get_control_dependents(&bb13_name).collect::<Vec<_>>(),
+        vec![CFGNode::Block(&bb13_name)]
+    );
+    assert_eq!(cdg.get_control_dependents(&Name::from(7)).count(), 0);
+
+    // which way each branch must go: %1 branches to %5 on true, %10 to %5 on
+    // false, and %13 to itself on false
+    let bb1_name = Name::from(1);
+    assert_eq!(
+        cdg.edge_kinds(&bb5_name, &bb1_name),
+        &[CFGEdgeKind::CondBr(true)]
+    );
+    assert_eq!(
+        cdg.edge_kinds(&bb5_name, &bb10_name),
+        &[CFGEdgeKind::CondBr(false)]
+    );
+    assert_eq!(
+        cdg.edge_kinds(&bb10_name, &bb10_name),
+        &[CFGEdgeKind::CondBr(false)]
+    );
+    assert_eq!(
+        cdg.edge_kinds(&bb13_name, &bb13_name),
+        &[CFGEdgeKind::CondBr(false)]
+    );
+    assert_eq!(cdg.edge_kinds(&bb13_name, &bb5_name), &[]);
+    assert_eq!(
+        cdg.labeled_imm_control_dependencies(&bb13_name)
+            .sorted_by_key(|&(controller, _)| controller)
+            .collect::<Vec<_>>(),
+        vec![
+            (&bb1_name, CFGEdgeKind::CondBr(true)),
+            (&bb10_name, CFGEdgeKind::CondBr(false)),
+            (&bb13_name, CFGEdgeKind::CondBr(false)),
+        ]
+    );
+}
+
+#[test]
+fn nested_loop_cdg_regions() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("nested_loop");
+    let cdg = fn_analysis.control_dependence_graph();
+
+    let bb1_name = Name::from(1);
+    let bb5_name = Name::from(5);
+    let bb7_name = Name::from(7);
+    let bb10_name = Name::from(10);
+    let bb13_name = Name::from(13);
+
+    // %1 and %7 always execute; %5 and %10 execute once per iteration of the
+    // outer loop; and %13 once per iteration of the inner loop
+    let regions = cdg.regions();
+    assert_eq!(regions.len(), 3);
+    assert_eq!(regions[0].blocks, vec![&bb1_name, &bb7_name]);
+    assert_eq!(regions[0].dependences, vec![]);
+    assert_eq!(regions[1].blocks, vec![&bb5_name, &bb10_name]);
+    assert_eq!(
+        regions[1].dependences,
+        vec![
+            (&bb1_name, CFGEdgeKind::CondBr(true)),
+            (&bb10_name, CFGEdgeKind::CondBr(false)),
+        ]
+    );
+    assert_eq!(regions[2].blocks, vec![&bb13_name]);
+    assert_eq!(
+        regions[2].dependences,
+        vec![
+            (&bb1_name, CFGEdgeKind::CondBr(true)),
+            (&bb10_name, CFGEdgeKind::CondBr(false)),
+            (&bb13_name, CFGEdgeKind::CondBr(false)),
+        ]
+    );
+
+    // every block is in exactly one region
+    let mut blocks: Vec<&Name> = regions
+        .iter()
+        .flat_map(|region| region.blocks.iter().copied())
+        .collect();
+    blocks.sort();
+    let mut expected: Vec<&Name> = fn_analysis.control_flow_graph().blocks().collect();
+    expected.sort();
+    assert_eq!(blocks, expected);
 }
 
 #[test]
@@ -1150,3 +2820,127 @@ fn infinite_loop_cdg() {
     assert_eq!(cdg.get_imm_control_dependencies(&Name::from(1)).count(), 0);
     assert_eq!(cdg.get_control_dependencies(&Name::from(1)).count(), 0);
 }
+
+#[test]
+fn complexity_report() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let report = analysis.complexity_report();
+
+    assert_eq!(
+        *report.function("nested_loop"),
+        FunctionComplexity {
+            function: "nested_loop".into(),
+            blocks: 5,
+            instructions: 22,
+            cyclomatic_complexity: 4,
+            loop_depth: 2,
+            callees: 2,
+        }
+    );
+    assert_eq!(report.function("infinite_loop").loop_depth, 1);
+    assert_eq!(report.function("infinite_loop").cyclomatic_complexity, 1);
+
+    let deepest: Vec<FunctionName> = report
+        .top_n(ComplexityMetric::LoopDepth, 1)
+        .into_iter()
+        .map(|fc| fc.function)
+        .collect();
+    assert_eq!(deepest, vec!["nested_loop"]);
+    assert_eq!(report.top_n(ComplexityMetric::Blocks, 100).len(), 10);
+
+    let thresholds = ComplexityThresholds {
+        loop_depth: Some(1),
+        ..Default::default()
+    };
+    assert_eq!(
+        report.violations(&thresholds),
+        vec![ThresholdViolation {
+            function: "nested_loop".into(),
+            metric: ComplexityMetric::LoopDepth,
+            value: 2,
+            threshold: 1,
+        }]
+    );
+    assert!(report
+        .violations(&ComplexityThresholds::default())
+        .is_empty());
+}
+
+#[test]
+fn cfg_metrics() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+
+    assert_eq!(
+        analysis.fn_analysis("nested_loop").cfg_metrics(),
+        CFGMetrics {
+            blocks: 5,
+            edges: 8,
+            cyclomatic_complexity: 4,
+            exits: 1,
+            max_out_degree: 2,
+            max_loop_depth: 2,
+        }
+    );
+    let metrics = analysis.fn_analysis("infinite_loop").cfg_metrics();
+    assert_eq!(metrics.edges, 2);
+    assert_eq!(metrics.exits, 0);
+    assert_eq!(metrics.max_out_degree, 1);
+    assert_eq!(metrics.max_loop_depth, 1);
+    // two loops, one after the other
+    let fn_analysis = analysis.fn_analysis("search_array");
+    assert_eq!(fn_analysis.cfg_metrics().max_loop_depth, 1);
+    assert_eq!(fn_analysis.loop_analysis().max_loop_depth(), 1);
+    // consistent with the complexity report
+    let report = analysis.complexity_report();
+    for fc in report.functions() {
+        let metrics = analysis.fn_analysis(fc.function).cfg_metrics();
+        assert_eq!(metrics.blocks, fc.blocks);
+        assert_eq!(metrics.cyclomatic_complexity, fc.cyclomatic_complexity);
+        assert_eq!(metrics.max_loop_depth, fc.loop_depth);
+    }
+}
+
+#[test]
+fn nested_loop_block_costs() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("nested_loop");
+    let costs = fn_analysis.block_costs();
+
+    let bb1_name = Name::from(1);
+    let bb5_name = Name::from(5);
+    let bb7_name = Name::from(7);
+    let bb10_name = Name::from(10);
+    let bb13_name = Name::from(13);
+    let blocks: Vec<(&Name, u64)> = costs.blocks().collect();
+    assert_eq!(
+        blocks,
+        vec![
+            (&bb1_name, 8),
+            (&bb5_name, 1),
+            (&bb7_name, 7),
+            (&bb10_name, 3),
+            (&bb13_name, 12),
+        ]
+    );
+    assert_eq!(costs.block_cost(&bb13_name), 12);
+    assert_eq!(costs.function_cost(), 31);
+
+    // the outer loop includes the inner loop
+    let loops: Vec<(&Name, u64)> = costs.loop_costs().collect();
+    assert_eq!(loops, vec![(&bb5_name, 16), (&bb13_name, 12)]);
+    assert_eq!(costs.loop_cost(&bb7_name), None);
+
+    let model = CostModel::default().with_opcode_cost("load", 100);
+    let costs = fn_analysis.block_costs_with_model(&model);
+    assert_eq!(costs.block_cost(&bb13_name), 108);
+    assert_eq!(costs.loop_cost(&bb5_name), Some(112));
+}
@@ -0,0 +1,87 @@
+#![allow(clippy::redundant_static_lifetimes)]
+
+use llvm_ir::{Module, Name};
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+/// memoryssa.ll is hand-written, with control flow clang would simplify
+const MEMORYSSA_BC_PATH: &'static str = "tests/bcfiles/memoryssa.bc";
+
+#[test]
+fn nested_merge_clobber() {
+    init_logging();
+    let module = Module::from_bc_path(MEMORYSSA_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("nested_merge");
+    let mssa = fn_analysis.memory_ssa();
+
+    let q_name = Name::from("q");
+    let s_name = Name::from("s");
+    let r_name = Name::from("r");
+    let p_name = Name::from("p");
+    let loc = |block, index| InstructionLocation { block, index };
+    let store = MemoryDefinition::Def(loc(&s_name, 0));
+
+    let mut r_phi = mssa.phi(&r_name).unwrap().to_vec();
+    r_phi.sort();
+    assert_eq!(
+        r_phi,
+        vec![(&q_name, MemoryDefinition::LiveOnEntry), (&s_name, store)]
+    );
+    let mut p_phi = mssa.phi(&p_name).unwrap().to_vec();
+    p_phi.sort();
+    assert_eq!(
+        p_phi,
+        vec![
+            (&q_name, MemoryDefinition::LiveOnEntry),
+            (&r_name, MemoryDefinition::Phi(&r_name))
+        ]
+    );
+
+    // the load may see the store or not, whichever incoming edge of %p is
+    // walked first, so it's clobbered by the `phi` in %p, which dominates it
+    assert_eq!(
+        mssa.clobbering_definition(loc(&p_name, 0)),
+        Some(MemoryDefinition::Phi(&p_name))
+    );
+    assert_eq!(
+        mssa.clobbering_definition(loc(&s_name, 0)),
+        Some(MemoryDefinition::LiveOnEntry)
+    );
+}
+
+#[test]
+fn loop_skipped_by_walk() {
+    init_logging();
+    let module = Module::from_bc_path(MEMORYSSA_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("loop_skips");
+    let mssa = fn_analysis.memory_ssa();
+
+    let entry_name = Name::from("entry");
+    let header_name = Name::from("header");
+    let body_name = Name::from("body");
+    let exit_name = Name::from("exit");
+    let loc = |block, index| InstructionLocation { block, index };
+
+    assert_eq!(
+        mssa.defining_access(loc(&exit_name, 0)),
+        Some(MemoryDefinition::Phi(&header_name))
+    );
+    // the walk around the loop comes back to the `phi` in %header, so only
+    // the store before the loop clobbers the load of @x
+    assert_eq!(
+        mssa.clobbering_definition(loc(&exit_name, 0)),
+        Some(MemoryDefinition::Def(loc(&entry_name, 0)))
+    );
+    assert_eq!(
+        mssa.clobbering_definition(loc(&body_name, 0)),
+        Some(MemoryDefinition::Phi(&header_name))
+    );
+}
@@ -0,0 +1,173 @@
+#![allow(clippy::redundant_static_lifetimes)]
+
+use llvm_ir::{Constant, Instruction, Module, Name, Operand};
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+/// modref.ll is hand-written, with its calls left in
+const MODREF_BC_PATH: &'static str = "tests/bcfiles/modref.bc";
+const LOOP_BC_PATH: &'static str = "tests/bcfiles/loop.bc";
+const NUMBERED_BC_PATH: &'static str = "tests/bcfiles/numbered.bc";
+
+fn set<'m>(globals: &[&'m Name], parameters: &[usize], unknown: bool) -> ModRefSet<'m> {
+    ModRefSet {
+        globals: globals.iter().copied().collect(),
+        parameters: parameters.iter().copied().collect(),
+        unknown,
+    }
+}
+
+#[test]
+fn transitive_summaries() {
+    init_logging();
+    let module = Module::from_bc_path(MODREF_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let mod_ref = analysis.mod_ref_analysis();
+
+    let g_name = Name::from("g");
+    let h_name = Name::from("h");
+
+    assert_eq!(mod_ref.mod_set("leaf"), &set(&[&g_name], &[], false));
+    assert_eq!(mod_ref.ref_set("leaf"), &set(&[], &[0], false));
+
+    // @leaf's parameter is @mid's %b
+    assert_eq!(mod_ref.mod_set("mid"), &set(&[&g_name], &[0], false));
+    assert_eq!(mod_ref.ref_set("mid"), &set(&[&h_name], &[1], false));
+
+    // @mid's %a is @top's own `alloca`, and its %b is @h
+    assert_eq!(mod_ref.mod_set("top"), &set(&[&g_name], &[], false));
+    assert_eq!(mod_ref.ref_set("top"), &set(&[&h_name], &[], false));
+    assert!(!mod_ref.mod_set("top").may_access_global(&h_name));
+    assert!(mod_ref.ref_set("top").may_access_global(&h_name));
+
+    assert_eq!(mod_ref.mod_set("indirect"), &set(&[], &[], true));
+    assert_eq!(mod_ref.ref_set("indirect"), &set(&[], &[0], false));
+    assert!(mod_ref.mod_set("indirect").may_access_global(&h_name));
+    assert!(mod_ref.mod_set("indirect").may_access_parameter(0));
+
+    assert_eq!(mod_ref.mod_set("chooses"), &set(&[&g_name], &[1], false));
+    assert!(mod_ref.ref_set("chooses").is_empty());
+    assert!(!mod_ref.mod_set("chooses").may_access_parameter(0));
+}
+
+#[test]
+fn recursive_summaries() {
+    init_logging();
+    let module = Module::from_bc_path(MODREF_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = CrossModuleAnalysis::new(std::iter::once(&module));
+    let mod_ref = analysis.mod_ref_analysis();
+
+    let g_name = Name::from("g");
+
+    // each of the mutually recursive functions may do what the other does
+    for func_name in ["even", "odd"] {
+        assert_eq!(mod_ref.mod_set(func_name), &set(&[&g_name], &[1], false));
+        assert!(mod_ref.ref_set(func_name).is_empty());
+    }
+}
+
+#[test]
+fn declarations_and_attributes() {
+    init_logging();
+    let module = Module::from_bc_path(MODREF_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let mod_ref = analysis.mod_ref_analysis();
+
+    let h_name = Name::from("h");
+
+    assert_eq!(mod_ref.mod_set("ext"), &set(&[], &[], true));
+    assert_eq!(mod_ref.ref_set("ext"), &set(&[], &[], true));
+    let memcpy = "llvm.memcpy.p0i8.p0i8.i64";
+    assert_eq!(mod_ref.mod_set(memcpy), &set(&[], &[0], false));
+    assert_eq!(mod_ref.ref_set(memcpy), &set(&[], &[1], false));
+
+    // the `readonly` call of @ext only references unknown memory, the
+    // `argmemonly` one only accesses %arg, and the `memcpy` copies @h to %dst
+    assert_eq!(mod_ref.mod_set("calls"), &set(&[], &[0, 1], false));
+    assert_eq!(mod_ref.ref_set("calls"), &set(&[&h_name], &[1], true));
+}
+
+#[test]
+fn numbered_functions() {
+    init_logging();
+    let module = Module::from_bc_path(NUMBERED_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let mod_ref = analysis.mod_ref_analysis();
+
+    // the calls of @0 and @1 are resolved, so no function accesses any memory
+    let func_names = [
+        FunctionName::Named("entry"),
+        FunctionName::Numbered {
+            module: &module.name,
+            n: 0,
+        },
+        FunctionName::Numbered {
+            module: &module.name,
+            n: 1,
+        },
+    ];
+    for func_name in func_names {
+        assert!(mod_ref.mod_set(func_name).is_empty(), "{}", func_name);
+        assert!(mod_ref.ref_set(func_name).is_empty(), "{}", func_name);
+    }
+}
+
+#[test]
+#[should_panic(expected = "function named \"nonexistent\" not found")]
+fn nonexistent_function() {
+    let module = Module::from_bc_path(MODREF_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    analysis.mod_ref_analysis().mod_set("nonexistent");
+}
+
+/// Check that every function's summaries only mention its own parameters, and
+/// include every global it stores to or loads from directly
+#[test]
+fn direct_accesses_included() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let mod_ref = analysis.mod_ref_analysis();
+    let global = |address: &Operand| match address {
+        Operand::ConstantOperand(constant) => match constant.as_ref() {
+            Constant::GlobalReference { name, .. } => Some(name.clone()),
+            _ => None,
+        },
+        _ => None,
+    };
+    for func in &module.functions {
+        let mod_set = mod_ref.mod_set(&func.name);
+        let ref_set = mod_ref.ref_set(&func.name);
+        for set in [mod_set, ref_set] {
+            assert!(set
+                .parameters
+                .iter()
+                .all(|&index| index < func.parameters.len()));
+        }
+        for inst in func.basic_blocks.iter().flat_map(|bb| &bb.instrs) {
+            match inst {
+                Instruction::Store(store) => {
+                    if let Some(name) = global(&store.address) {
+                        assert!(mod_set.may_access_global(&name), "{}", func.name);
+                    }
+                }
+                Instruction::Load(load) => {
+                    if let Some(name) = global(&load.address) {
+                        assert!(ref_set.may_access_global(&name), "{}", func.name);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,281 @@
+#![allow(clippy::redundant_static_lifetimes)]
+
+use itertools::Itertools;
+use llvm_ir::{Module, Name};
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+const NOEXIT_BC_PATH: &'static str = "tests/bcfiles/noexit.bc";
+
+#[test]
+fn loop_or_return_domtrees() {
+    init_logging();
+    let module = Module::from_bc_path(NOEXIT_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("loop_or_return");
+
+    let bb1_name = Name::from(1);
+    let bb4_name = Name::from(4);
+    let bb7_name = Name::from(7);
+
+    let domtree = fn_analysis.dominator_tree();
+    assert_eq!(domtree.idom(&bb4_name), Some(&bb1_name));
+    assert_eq!(domtree.idom(&bb7_name), Some(&bb1_name));
+    assert_eq!(domtree.idom_of_return(), Some(&bb7_name));
+
+    // the infinite loop can't reach the exit, so it isn't in the
+    // postdominator tree, and the path through it is ignored when computing
+    // %1's postdominators
+    let postdomtree = fn_analysis.postdominator_tree();
+    assert_eq!(postdomtree.ipostdom(&bb4_name), None);
+    assert_eq!(postdomtree.ipostdom(&bb7_name), Some(CFGNode::Return));
+    assert_eq!(
+        postdomtree.ipostdom(&bb1_name),
+        Some(CFGNode::Block(&bb7_name))
+    );
+    assert_eq!(
+        postdomtree.children_of_return().collect::<Vec<_>>(),
+        vec![&bb7_name]
+    );
+    assert_eq!(postdomtree.children(&bb4_name).count(), 0);
+}
+
+#[test]
+fn two_infinite_loops_domtrees() {
+    init_logging();
+    let module = Module::from_bc_path(NOEXIT_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("two_infinite_loops");
+
+    let bb1_name = Name::from(1);
+    let bb4_name = Name::from(4);
+    let bb7_name = Name::from(7);
+
+    let domtree = fn_analysis.dominator_tree();
+    assert_eq!(domtree.idom(&bb4_name), Some(&bb1_name));
+    assert_eq!(domtree.idom(&bb7_name), Some(&bb1_name));
+    assert_eq!(domtree.idom_of_return(), None);
+
+    // the function never returns, so the postdominator tree is empty
+    let postdomtree = fn_analysis.postdominator_tree();
+    for bb_name in &[&bb1_name, &bb4_name, &bb7_name] {
+        assert_eq!(postdomtree.ipostdom(bb_name), None);
+        assert_eq!(postdomtree.children(bb_name).count(), 0);
+    }
+    assert_eq!(postdomtree.children_of_return().count(), 0);
+}
+
+#[test]
+fn only_unreachable_domtrees() {
+    init_logging();
+    let module = Module::from_bc_path(NOEXIT_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("only_unreachable");
+
+    let bb1_name = Name::from(1);
+    let bb3_name = Name::from(3);
+    let bb4_name = Name::from(4);
+    let bb5_name = Name::from(5);
+
+    let domtree = fn_analysis.dominator_tree();
+    assert_eq!(domtree.idom(&bb5_name), Some(&bb1_name));
+    assert_eq!(domtree.idom_of_return(), None);
+
+    // by default, `unreachable` isn't an exit, so the function never returns
+    let postdomtree = fn_analysis.postdominator_tree();
+    assert_eq!(postdomtree.ipostdom(&bb1_name), None);
+    assert_eq!(postdomtree.ipostdom(&bb5_name), None);
+    assert_eq!(postdomtree.children_of_return().count(), 0);
+
+    // but it can be
+    let postdomtree = fn_analysis.postdominator_tree_with_exits(&[ExitKind::Unreachable]);
+    assert_eq!(
+        postdomtree.ipostdom(&bb1_name),
+        Some(CFGNode::Block(&bb5_name))
+    );
+    assert_eq!(
+        postdomtree.ipostdom(&bb3_name),
+        Some(CFGNode::Block(&bb5_name))
+    );
+    assert_eq!(
+        postdomtree.ipostdom(&bb4_name),
+        Some(CFGNode::Block(&bb5_name))
+    );
+    assert_eq!(
+        postdomtree.children_of_return().collect::<Vec<_>>(),
+        vec![&bb5_name]
+    );
+}
+
+const EXITS_WITH_INFINITE_LOOPS: &'static [ExitKind] =
+    &[ExitKind::Return, ExitKind::Unwind, ExitKind::InfiniteLoop];
+
+#[test]
+fn loop_or_return_virtual_exit() {
+    init_logging();
+    let module = Module::from_bc_path(NOEXIT_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("loop_or_return");
+
+    let bb1_name = Name::from(1);
+    let bb4_name = Name::from(4);
+    let bb7_name = Name::from(7);
+
+    let cfg = fn_analysis.control_flow_graph_with_exits(EXITS_WITH_INFINITE_LOOPS);
+    assert_eq!(
+        cfg.preds_of_exit(ExitKind::InfiniteLoop)
+            .collect::<Vec<_>>(),
+        vec![&bb4_name]
+    );
+    assert_eq!(
+        cfg.edge_kinds(&bb4_name, CFGNode::Return),
+        &[CFGEdgeKind::InfiniteLoop]
+    );
+
+    // now the infinite loop is in the postdominator tree, and %1 is no longer
+    // postdominated by %7
+    let postdomtree = fn_analysis.postdominator_tree_with_exits(EXITS_WITH_INFINITE_LOOPS);
+    assert_eq!(postdomtree.ipostdom(&bb1_name), Some(CFGNode::Return));
+    assert_eq!(postdomtree.ipostdom(&bb4_name), Some(CFGNode::Return));
+    assert_eq!(postdomtree.ipostdom(&bb7_name), Some(CFGNode::Return));
+
+    // and both the loop and the return depend on %1's branch
+    let cdg = fn_analysis.control_dependence_graph_with_exits(EXITS_WITH_INFINITE_LOOPS);
+    let deps: Vec<&Name> = cdg
+        .get_imm_control_dependencies(&bb4_name)
+        .sorted()
+        .collect();
+    assert_eq!(deps, vec![&bb1_name, &bb4_name]);
+    let deps: Vec<&Name> = cdg.get_imm_control_dependencies(&bb7_name).collect();
+    assert_eq!(deps, vec![&bb1_name]);
+    assert_eq!(cdg.get_imm_control_dependencies(&bb1_name).count(), 0);
+
+    // the default control dependence graph ignores the infinite loop
+    let cdg = fn_analysis.control_dependence_graph();
+    assert_eq!(cdg.get_imm_control_dependencies(&bb4_name).count(), 0);
+    assert_eq!(cdg.get_imm_control_dependencies(&bb7_name).count(), 0);
+
+    // if the loop's back edge is removed, it's no longer an infinite loop
+    let view = cfg.without_edges(vec![(&bb4_name, CFGNode::Block(&bb4_name))]);
+    assert_eq!(view.preds_of_exit(ExitKind::InfiniteLoop).count(), 0);
+    assert_eq!(view.succs(&bb4_name).count(), 0);
+}
+
+#[test]
+fn two_infinite_loops_virtual_exits() {
+    init_logging();
+    let module = Module::from_bc_path(NOEXIT_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("two_infinite_loops");
+
+    let bb1_name = Name::from(1);
+    let bb4_name = Name::from(4);
+    let bb7_name = Name::from(7);
+
+    let cfg = fn_analysis.control_flow_graph_with_exits(EXITS_WITH_INFINITE_LOOPS);
+    let exits: Vec<&Name> = cfg.preds_of_exit(ExitKind::InfiniteLoop).sorted().collect();
+    assert_eq!(exits, vec![&bb4_name, &bb7_name]);
+
+    let postdomtree = fn_analysis.postdominator_tree_with_exits(EXITS_WITH_INFINITE_LOOPS);
+    assert_eq!(postdomtree.ipostdom(&bb1_name), Some(CFGNode::Return));
+
+    let cdg = fn_analysis.control_dependence_graph_with_exits(EXITS_WITH_INFINITE_LOOPS);
+    assert!(cdg
+        .get_imm_control_dependencies(&bb4_name)
+        .contains(&&bb1_name));
+    assert!(cdg
+        .get_imm_control_dependencies(&bb7_name)
+        .contains(&&bb1_name));
+}
+
+#[test]
+fn infinite_loop_with_branch_virtual_exit() {
+    init_logging();
+    let module = Module::from_bc_path(NOEXIT_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("infinite_loop_with_branch");
+
+    let bb1_name = Name::from(1);
+    let bb3_name = Name::from(3);
+    let bb6_name = Name::from(6);
+    let bb7_name = Name::from(7);
+    let bb10_name = Name::from(10);
+
+    // the virtual exit is from the last block of the loop
+    let cfg = fn_analysis.control_flow_graph_with_exits(EXITS_WITH_INFINITE_LOOPS);
+    assert_eq!(
+        cfg.preds_of_exit(ExitKind::InfiniteLoop)
+            .collect::<Vec<_>>(),
+        vec![&bb10_name]
+    );
+
+    let postdomtree = fn_analysis.postdominator_tree_with_exits(EXITS_WITH_INFINITE_LOOPS);
+    assert_eq!(
+        postdomtree.ipostdom(&bb1_name),
+        Some(CFGNode::Block(&bb3_name))
+    );
+    assert_eq!(
+        postdomtree.ipostdom(&bb3_name),
+        Some(CFGNode::Block(&bb10_name))
+    );
+    assert_eq!(
+        postdomtree.ipostdom(&bb6_name),
+        Some(CFGNode::Block(&bb10_name))
+    );
+    assert_eq!(postdomtree.ipostdom(&bb10_name), Some(CFGNode::Return));
+
+    // the two sides of the conditional depend on its branch
+    let cdg = fn_analysis.control_dependence_graph_with_exits(EXITS_WITH_INFINITE_LOOPS);
+    let deps: Vec<&Name> = cdg.get_imm_control_dependencies(&bb6_name).collect();
+    assert_eq!(deps, vec![&bb3_name]);
+    let deps: Vec<&Name> = cdg.get_imm_control_dependencies(&bb7_name).collect();
+    assert_eq!(deps, vec![&bb3_name]);
+}
+
+#[test]
+fn loop_or_return_divergent_blocks() {
+    init_logging();
+    let module = Module::from_bc_path(NOEXIT_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("loop_or_return");
+
+    let bb1_name = Name::from(1);
+    let bb4_name = Name::from(4);
+    let bb7_name = Name::from(7);
+    let bb4 = CFGNode::Block(&bb4_name);
+
+    // the infinite loop %4 can't reach the exit, and every query reports it
+    // as outside the postdominator tree, rather than panicking
+    let postdomtree = fn_analysis.postdominator_tree();
+    assert!(postdomtree.reaches_exit(&bb1_name));
+    assert!(!postdomtree.reaches_exit(&bb4_name));
+    assert!(postdomtree.reaches_exit(&bb7_name));
+    assert_eq!(postdomtree.ipostdom(&bb4_name), None);
+    assert_eq!(postdomtree.children(&bb4_name).count(), 0);
+    assert_eq!(postdomtree.siblings(&bb4_name).count(), 0);
+    assert_eq!(postdomtree.ipostdom_chain(&bb4_name).count(), 0);
+    assert!(postdomtree.postdominated_by(bb4).is_empty());
+    assert!(!postdomtree.postdominates(CFGNode::Return, bb4));
+    assert!(!postdomtree.postdominates(bb4, CFGNode::Block(&bb1_name)));
+    assert!(postdomtree.postdominates(bb4, bb4));
+    assert!(postdomtree.preorder().all(|node| node != bb4));
+
+    // with a virtual exit for the loop, it reaches the exit too
+    let postdomtree = fn_analysis.postdominator_tree_with_exits(EXITS_WITH_INFINITE_LOOPS);
+    assert!(postdomtree.reaches_exit(&bb4_name));
+    assert_eq!(
+        postdomtree.ipostdom_chain(&bb4_name).collect::<Vec<_>>(),
+        vec![CFGNode::Return]
+    );
+}
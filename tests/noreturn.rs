@@ -0,0 +1,205 @@
+#![allow(clippy::redundant_static_lifetimes)]
+
+use llvm_ir::{Module, Name};
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+/// noreturn.ll is hand-written, without `noreturn` attributes on its functions
+const NORETURN_BC_PATH: &'static str = "tests/bcfiles/noreturn.bc";
+const LOOP_BC_PATH: &'static str = "tests/bcfiles/loop.bc";
+
+#[test]
+fn inferred_functions() {
+    init_logging();
+    let module = Module::from_bc_path(NORETURN_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let noreturn = analysis.noreturn_analysis();
+
+    assert!(noreturn.is_noreturn("exit"));
+    assert!(!noreturn.is_noreturn("ext"));
+    assert!(noreturn.is_noreturn("die"));
+    // @fatal's `ret` is after its call of @die
+    assert!(noreturn.is_noreturn("fatal"));
+    assert!(noreturn.is_noreturn("spin"));
+    assert!(noreturn.is_noreturn("check"));
+    assert!(noreturn.is_noreturn("site_noreturn"));
+    // @invokes_die may still unwind
+    assert!(noreturn.is_noreturn("invokes_die"));
+    assert!(!noreturn.is_noreturn("guarded"));
+
+    assert_eq!(
+        noreturn.inferred_noreturn_functions().collect::<Vec<_>>(),
+        vec![
+            "@0",
+            "check",
+            "die",
+            "fatal",
+            "invokes_die",
+            "ping",
+            "pong",
+            "site_noreturn",
+            "spin"
+        ]
+    );
+    assert_eq!(noreturn.noreturn_functions().count(), 10);
+}
+
+#[test]
+fn numbered_functions() {
+    init_logging();
+    let module = Module::from_bc_path(NORETURN_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let noreturn = analysis.noreturn_analysis();
+
+    // each numbered function is analyzed on its own
+    let zero = FunctionName::Numbered {
+        module: &module.name,
+        n: 0,
+    };
+    let one = FunctionName::Numbered {
+        module: &module.name,
+        n: 1,
+    };
+    assert!(noreturn.is_noreturn(zero));
+    assert!(!noreturn.is_noreturn(one));
+    let callees: Vec<FunctionName> = analysis.call_graph().callees(one).collect();
+    assert_eq!(callees, vec![zero]);
+}
+
+#[test]
+fn recursive_functions() {
+    init_logging();
+    let module = Module::from_bc_path(NORETURN_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = CrossModuleAnalysis::new(std::iter::once(&module));
+    let noreturn = analysis.noreturn_analysis();
+
+    // neither of @ping and @pong can return unless the other does
+    assert!(noreturn.is_noreturn("ping"));
+    assert!(noreturn.is_noreturn("pong"));
+}
+
+#[test]
+fn noreturn_calls() {
+    init_logging();
+    let module = Module::from_bc_path(NORETURN_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let noreturn = analysis.noreturn_analysis();
+
+    let entry_name = Name::from("entry");
+    let fail_name = Name::from("fail");
+    let bad_name = Name::from("bad");
+
+    assert_eq!(
+        noreturn.noreturn_calls("guarded").collect::<Vec<_>>(),
+        vec![(&fail_name, 0)]
+    );
+    assert_eq!(
+        noreturn.noreturn_calls("check").collect::<Vec<_>>(),
+        vec![(&bad_name, 0)]
+    );
+    // the `invoke` is the block's terminator
+    assert_eq!(
+        noreturn.noreturn_calls("invokes_die").collect::<Vec<_>>(),
+        vec![(&entry_name, 0)]
+    );
+    assert_eq!(noreturn.noreturn_calls("spin").count(), 0);
+    assert_eq!(noreturn.noreturn_calls("exit").count(), 0);
+}
+
+#[test]
+fn noreturn_cfgs() {
+    init_logging();
+    let module = Module::from_bc_path(NORETURN_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let noreturn = analysis.noreturn_analysis();
+
+    let entry_name = Name::from("entry");
+    let fail_name = Name::from("fail");
+    let work_name = Name::from("work");
+    let normal_name = Name::from("normal");
+    let lpad_name = Name::from("lpad");
+
+    let cfg = noreturn
+        .cfg_with_noreturn_calls("guarded", ExitKind::DEFAULT)
+        .expect("@guarded should be defined");
+    assert_eq!(cfg.succs(&fail_name).count(), 0);
+    assert_eq!(
+        cfg.succs(&entry_name).collect::<Vec<_>>(),
+        vec![CFGNode::Block(&fail_name), CFGNode::Block(&work_name)]
+    );
+
+    let exits = [ExitKind::Return, ExitKind::Unreachable];
+    let cfg = noreturn
+        .cfg_with_noreturn_calls("guarded", &exits)
+        .expect("@guarded should be defined");
+    assert_eq!(
+        cfg.labeled_succs(&fail_name).collect::<Vec<_>>(),
+        vec![(CFGNode::Return, CFGEdgeKind::Unreachable)]
+    );
+
+    // the call of @fatal no longer makes %work postdominate %entry
+    let fn_analysis = analysis.fn_analysis("guarded");
+    let postdomtree = fn_analysis.postdominator_tree_with_exits(&exits);
+    assert_eq!(
+        postdomtree.ipostdom(&entry_name),
+        Some(CFGNode::Block(&work_name))
+    );
+    let cdg = fn_analysis.control_dependence_graph_with_exits(&exits);
+    assert!(!cdg.is_control_dependent(&work_name, &entry_name));
+    let postdomtree = PostDominatorTree::new(&cfg);
+    assert_eq!(postdomtree.ipostdom(&entry_name), Some(CFGNode::Return));
+    let cdg = ControlDependenceGraph::new(&cfg, &postdomtree);
+    assert!(cdg.is_control_dependent(&work_name, &entry_name));
+
+    // only the exceptional destination of the `invoke` remains
+    let cfg = noreturn
+        .cfg_with_noreturn_calls("invokes_die", ExitKind::DEFAULT)
+        .expect("@invokes_die should be defined");
+    assert_eq!(
+        cfg.labeled_succs(&entry_name).collect::<Vec<_>>(),
+        vec![(CFGNode::Block(&lpad_name), CFGEdgeKind::InvokeException)]
+    );
+    assert_eq!(cfg.preds(&normal_name).count(), 0);
+
+    assert!(noreturn
+        .cfg_with_noreturn_calls("exit", ExitKind::DEFAULT)
+        .is_none());
+}
+
+#[test]
+#[should_panic(expected = "function named \"nonexistent\" not found")]
+fn nonexistent_function() {
+    let module = Module::from_bc_path(NORETURN_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    analysis.noreturn_analysis().is_noreturn("nonexistent");
+}
+
+/// Check that a function never returns exactly when no `ret` is reachable in
+/// its CFG with calls which never return
+#[test]
+fn consistent_with_cfgs() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let noreturn = analysis.noreturn_analysis();
+    for func in &module.functions {
+        let cfg = noreturn
+            .cfg_with_noreturn_calls(&func.name, &[ExitKind::Return])
+            .expect("function should be defined");
+        let returns = cfg
+            .reverse_post_order()
+            .any(|block| cfg.succs(block).any(|succ| succ == CFGNode::Return));
+        assert_eq!(noreturn.is_noreturn(&func.name), !returns, "{}", func.name);
+    }
+}
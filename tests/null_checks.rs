@@ -0,0 +1,267 @@
+#![allow(clippy::redundant_static_lifetimes)]
+
+use llvm_ir::{Module, Name};
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+/// nullcheck.ll is hand-written, with its redundant checks left in
+const NULLCHECK_BC_PATH: &'static str = "tests/bcfiles/nullcheck.bc";
+const LOOP_BC_PATH: &'static str = "tests/bcfiles/loop.bc";
+
+#[test]
+fn checked_branches() {
+    init_logging();
+    let module = Module::from_bc_path(NULLCHECK_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("guarded");
+    let null_checks = fn_analysis.null_checks();
+
+    let entry_name = Name::from("entry");
+    let nonnull_name = Name::from("nonnull");
+    let null_name = Name::from("null");
+    let exit_name = Name::from("exit");
+    let isnull_name = Name::from("isnull");
+
+    let derefs: Vec<&Dereference> = null_checks.dereferences().collect();
+    assert_eq!(derefs.len(), 3);
+
+    let load = null_checks
+        .dereference(InstructionLocation {
+            block: &nonnull_name,
+            index: 0,
+        })
+        .expect("load should be a dereference");
+    assert_eq!(load.kind, DereferenceKind::Load);
+    assert_eq!(load.pointer.to_string(), "i32* %p");
+    assert_eq!(
+        load.checks,
+        vec![NullCheck {
+            block: &entry_name,
+            icmp: &isnull_name,
+            outcome: false,
+            is_null: false,
+        }]
+    );
+    assert!(load.is_checked_nonnull());
+    assert!(!load.is_checked_null());
+
+    let store = null_checks
+        .dereference(InstructionLocation {
+            block: &null_name,
+            index: 0,
+        })
+        .expect("store should be a dereference");
+    assert_eq!(store.kind, DereferenceKind::Store);
+    assert_eq!(
+        store.checks,
+        vec![NullCheck {
+            block: &entry_name,
+            icmp: &isnull_name,
+            outcome: true,
+            is_null: true,
+        }]
+    );
+    assert!(store.is_checked_null());
+    assert!(!store.is_checked_nonnull());
+
+    // %exit can be reached on either outcome
+    let unchecked: Vec<&Dereference> = null_checks.unchecked().collect();
+    assert_eq!(unchecked.len(), 1);
+    assert_eq!(
+        unchecked[0].location,
+        InstructionLocation {
+            block: &exit_name,
+            index: 0,
+        }
+    );
+
+    // the comparison and the terminators aren't dereferences
+    assert!(null_checks
+        .dereference(InstructionLocation {
+            block: &entry_name,
+            index: 0,
+        })
+        .is_none());
+    assert!(null_checks
+        .dereference(InstructionLocation {
+            block: &nonnull_name,
+            index: 1,
+        })
+        .is_none());
+}
+
+#[test]
+fn casts_and_calls() {
+    init_logging();
+    let module = Module::from_bc_path(NULLCHECK_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("casts");
+    let null_checks = fn_analysis.null_checks();
+
+    let entry_name = Name::from("entry");
+    let then_name = Name::from("then");
+    let nonnull_name = Name::from("nonnull");
+
+    let derefs: Vec<&Dereference> = null_checks.dereferences().collect();
+    assert_eq!(
+        derefs
+            .iter()
+            .map(|deref| (deref.location.index, deref.kind))
+            .collect::<Vec<_>>(),
+        vec![
+            (0, DereferenceKind::Load),
+            (1, DereferenceKind::Atomic),
+            (2, DereferenceKind::IndirectCall),
+        ]
+    );
+    assert!(derefs
+        .iter()
+        .all(|deref| deref.location.block == &then_name));
+
+    let check = NullCheck {
+        block: &entry_name,
+        icmp: &nonnull_name,
+        outcome: true,
+        is_null: false,
+    };
+    // %q is checked through its bitcast, and %c directly
+    assert_eq!(derefs[0].pointer.to_string(), "i8* %q");
+    assert_eq!(derefs[0].checks, vec![check.clone()]);
+    assert_eq!(derefs[1].pointer.to_string(), "i32* %c");
+    assert_eq!(derefs[1].checks, vec![check]);
+    // %f is never checked
+    assert_eq!(derefs[2].pointer.to_string(), "void ()* %f");
+    assert!(derefs[2].checks.is_empty());
+    assert!(!derefs[2].is_checked_nonnull());
+}
+
+#[test]
+fn nested_checks() {
+    init_logging();
+    let module = Module::from_bc_path(NULLCHECK_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("nested");
+    let null_checks = fn_analysis.null_checks();
+
+    let entry_name = Name::from("entry");
+    let inner_name = Name::from("inner");
+    let loop_name = Name::from("loop");
+    let body_name = Name::from("body");
+    let join_name = Name::from("join");
+    let isnull_name = Name::from("isnull");
+    let nonnull_name = Name::from("nonnull");
+    let again_name = Name::from("again");
+
+    let expected = vec![
+        NullCheck {
+            block: &loop_name,
+            icmp: &again_name,
+            outcome: false,
+            is_null: false,
+        },
+        NullCheck {
+            block: &inner_name,
+            icmp: &nonnull_name,
+            outcome: true,
+            is_null: false,
+        },
+        NullCheck {
+            block: &entry_name,
+            icmp: &isnull_name,
+            outcome: false,
+            is_null: false,
+        },
+    ];
+    let store = null_checks
+        .dereference(InstructionLocation {
+            block: &body_name,
+            index: 0,
+        })
+        .expect("store should be a dereference");
+    assert_eq!(store.checks, expected);
+
+    // both outcomes of %check lead to %join, so it adds nothing
+    let load = null_checks
+        .dereference(InstructionLocation {
+            block: &join_name,
+            index: 0,
+        })
+        .expect("load should be a dereference");
+    assert_eq!(load.checks, expected);
+    assert_eq!(null_checks.unchecked().count(), 0);
+
+    let cfg = fn_analysis.control_flow_graph();
+    let domtree = fn_analysis.dominator_tree();
+    assert!(domtree.edge_dominates(&cfg, &inner_name, &loop_name, &join_name));
+    assert!(!domtree.edge_dominates(&cfg, &body_name, &join_name, &join_name));
+    assert!(!domtree.edge_dominates(&cfg, &join_name, &loop_name, &body_name));
+    assert!(!domtree.edge_dominates(&cfg, &loop_name, &body_name, &loop_name));
+}
+
+#[test]
+fn dead_predecessor() {
+    init_logging();
+    let module = Module::from_bc_path(NULLCHECK_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("dead_pred");
+    let null_checks = fn_analysis.null_checks();
+
+    let entry_name = Name::from("entry");
+    let then_name = Name::from("then");
+    let nonnull_name = Name::from("nonnull");
+
+    // %dead also branches to %then, but it can't be reached, so the edge from
+    // %entry still dominates %then
+    let cfg = fn_analysis.control_flow_graph();
+    let domtree = fn_analysis.dominator_tree();
+    assert!(domtree.edge_dominates(&cfg, &entry_name, &then_name, &then_name));
+
+    let load = null_checks
+        .dereference(InstructionLocation {
+            block: &then_name,
+            index: 0,
+        })
+        .expect("load should be a dereference");
+    assert_eq!(
+        load.checks,
+        vec![NullCheck {
+            block: &entry_name,
+            icmp: &nonnull_name,
+            outcome: true,
+            is_null: false,
+        }]
+    );
+    assert!(load.is_checked_nonnull());
+}
+
+/// Check that every dereference can be found by its location, and that every
+/// null check of it is from a block strictly dominating it
+#[test]
+fn checks_dominate() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    for func in &module.functions {
+        let fn_analysis = analysis.fn_analysis(&func.name);
+        let null_checks = fn_analysis.null_checks();
+        let domtree = fn_analysis.dominator_tree();
+        for deref in null_checks.dereferences() {
+            assert_eq!(null_checks.dereference(deref.location), Some(deref));
+            for check in &deref.checks {
+                assert!(domtree.strictly_dominates(
+                    CFGNode::Block(check.block),
+                    CFGNode::Block(deref.location.block)
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,170 @@
+#![allow(clippy::redundant_static_lifetimes)]
+
+use llvm_ir::{instruction::Phi, Instruction, Module, Name, Operand};
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+/// sccp.ll, verybusy.ll and wto.ll are hand-written, with their `phi`s fixed
+const SCCP_BC_PATH: &'static str = "tests/bcfiles/sccp.bc";
+const VERYBUSY_BC_PATH: &'static str = "tests/bcfiles/verybusy.bc";
+const WTO_BC_PATH: &'static str = "tests/bcfiles/wto.bc";
+const LOOP_BC_PATH: &'static str = "tests/bcfiles/loop.bc";
+
+#[test]
+fn diamond_web() {
+    init_logging();
+    let module = Module::from_bc_path(SCCP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("folded");
+    let webs = fn_analysis.phi_webs();
+
+    let x_name = Name::from("x");
+    let a_name = Name::from("a");
+    let b_name = Name::from("b");
+    let c_name = Name::from("c");
+    let r_name = Name::from("r");
+    let d_name = Name::from("d");
+
+    assert_eq!(
+        webs.webs().collect::<Vec<_>>(),
+        vec![&[&b_name, &c_name, &r_name][..]]
+    );
+    assert_eq!(webs.web(&r_name), Some(&[&b_name, &c_name, &r_name][..]));
+    assert_eq!(webs.representative(&r_name), Some(&b_name));
+    assert_eq!(webs.representative(&c_name), Some(&b_name));
+    assert!(webs.same_web(&b_name, &c_name));
+
+    // values not connected to the phi are alone in their webs, even those
+    // which use it
+    assert_eq!(webs.web(&x_name), Some(&[&x_name][..]));
+    assert_eq!(webs.web(&a_name), Some(&[&a_name][..]));
+    assert_eq!(webs.representative(&d_name), Some(&d_name));
+    assert!(!webs.same_web(&a_name, &b_name));
+    assert!(!webs.same_web(&r_name, &d_name));
+
+    // names which aren't values of the function
+    assert_eq!(webs.web(&Name::from("join")), None);
+    assert_eq!(webs.representative(&Name::from("nonexistent")), None);
+    assert!(!webs.same_web(&Name::from("nonexistent"), &Name::from("nonexistent")));
+}
+
+#[test]
+fn loop_webs() {
+    init_logging();
+    let module = Module::from_bc_path(SCCP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("loop_invariant");
+    let webs = fn_analysis.phi_webs();
+
+    let n_name = Name::from("n");
+    let i_name = Name::from("i");
+    let k_name = Name::from("k");
+    let k_next_name = Name::from("k.next");
+    let i_next_name = Name::from("i.next");
+    let sum_name = Name::from("sum");
+
+    // constant incoming values don't join anything to the webs
+    assert_eq!(
+        webs.webs().collect::<Vec<_>>(),
+        vec![&[&i_name, &i_next_name][..], &[&k_name, &k_next_name][..]]
+    );
+    assert_eq!(webs.representative(&i_next_name), Some(&i_name));
+    assert_eq!(webs.representative(&k_next_name), Some(&k_name));
+    assert!(!webs.same_web(&i_name, &k_name));
+    assert!(!webs.same_web(&n_name, &i_next_name));
+    assert_eq!(webs.web(&sum_name), Some(&[&sum_name][..]));
+}
+
+#[test]
+fn parameters() {
+    init_logging();
+    let module = Module::from_bc_path(VERYBUSY_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("hoist");
+    let webs = fn_analysis.phi_webs();
+
+    let a_name = Name::from("a");
+    let b_name = Name::from("b");
+    let x_name = Name::from("x");
+    let y_name = Name::from("y");
+    let p_name = Name::from("p");
+
+    assert_eq!(
+        webs.webs().collect::<Vec<_>>(),
+        vec![&[&x_name, &y_name, &p_name][..]]
+    );
+    // %a and %b are only used by the values in the web, not merged into it
+    assert_eq!(webs.web(&a_name), Some(&[&a_name][..]));
+    assert_eq!(webs.web(&b_name), Some(&[&b_name][..]));
+    assert!(!webs.same_web(&a_name, &x_name));
+}
+
+#[test]
+fn nested_loops() {
+    init_logging();
+    let module = Module::from_bc_path(WTO_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("nested");
+    let webs = fn_analysis.phi_webs();
+
+    let i_name = Name::from("i");
+    let i_next_name = Name::from("i.next");
+    let j_name = Name::from("j");
+    let j_next_name = Name::from("j.next");
+
+    assert_eq!(
+        webs.webs().collect::<Vec<_>>(),
+        vec![&[&i_name, &i_next_name][..], &[&j_name, &j_next_name][..]]
+    );
+    assert!(!webs.same_web(&i_name, &j_name));
+}
+
+/// Check that every `phi` in the module is in the same web as each of its
+/// local incoming values, and that every web's representative is its first
+/// value
+#[test]
+fn phis_join_webs() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    for func in &module.functions {
+        let fn_analysis = analysis.fn_analysis(&func.name);
+        let webs = fn_analysis.phi_webs();
+        for inst in func.basic_blocks.iter().flat_map(|bb| &bb.instrs) {
+            if let Instruction::Phi(Phi {
+                dest,
+                incoming_values,
+                ..
+            }) = inst
+            {
+                for (incoming, _) in incoming_values {
+                    if let Operand::LocalOperand { name, .. } = incoming {
+                        assert!(
+                            webs.same_web(dest, name),
+                            "{} and {} in {}",
+                            dest,
+                            name,
+                            func.name
+                        );
+                    }
+                }
+            }
+        }
+        for web in webs.webs() {
+            assert!(web.len() > 1);
+            for value in web {
+                assert_eq!(webs.representative(value), Some(web[0]));
+                assert_eq!(webs.web(value), Some(web));
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+#![allow(clippy::redundant_static_lifetimes)]
+
+use llvm_ir::Module;
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+/// purity.ll is hand-written, with some attributes contradicting its code
+const PURITY_BC_PATH: &'static str = "tests/bcfiles/purity.bc";
+const LOOP_BC_PATH: &'static str = "tests/bcfiles/loop.bc";
+const NUMBERED_BC_PATH: &'static str = "tests/bcfiles/numbered.bc";
+
+#[test]
+fn classified_functions() {
+    init_logging();
+    let module = Module::from_bc_path(PURITY_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let purity = analysis.purity_analysis();
+
+    assert_eq!(purity.purity("square"), Purity::ReadNone);
+    assert_eq!(purity.purity("get"), Purity::ReadOnly);
+    assert_eq!(purity.purity("local"), Purity::ReadNone);
+    assert_eq!(purity.purity("set"), Purity::WritesMemory);
+    assert_eq!(purity.purity("calls_get"), Purity::ReadOnly);
+    assert_eq!(purity.purity("ext"), Purity::Unknown);
+    assert_eq!(purity.purity("calls_ext"), Purity::Unknown);
+    assert_eq!(purity.purity("trusted"), Purity::ReadNone);
+    assert_eq!(purity.purity("volatile_local"), Purity::WritesMemory);
+
+    assert!(purity.purity("square").is_pure());
+    assert!(!purity.purity("get").is_pure());
+    assert!(purity.purity("get").may_read());
+    assert!(!purity.purity("get").may_write());
+    assert!(purity.purity("ext").may_write());
+
+    assert_eq!(
+        purity
+            .functions_with_purity(Purity::ReadNone)
+            .collect::<Vec<_>>(),
+        vec!["local", "square", "trusted"]
+    );
+}
+
+#[test]
+fn recursive_functions() {
+    init_logging();
+    let module = Module::from_bc_path(PURITY_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = CrossModuleAnalysis::new(std::iter::once(&module));
+    let purity = analysis.purity_analysis();
+
+    // @ping only calls @pong, which reads memory
+    assert_eq!(purity.purity("ping"), Purity::ReadOnly);
+    assert_eq!(purity.purity("pong"), Purity::ReadOnly);
+}
+
+#[test]
+fn numbered_functions() {
+    init_logging();
+    let module = Module::from_bc_path(NUMBERED_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let purity = analysis.purity_analysis();
+
+    // @0 calls @1, and @entry calls both
+    for n in [0, 1] {
+        let func_name = FunctionName::Numbered {
+            module: &module.name,
+            n,
+        };
+        assert_eq!(purity.purity(func_name), Purity::ReadNone);
+    }
+    assert_eq!(purity.purity("entry"), Purity::ReadNone);
+}
+
+#[test]
+fn declared_attributes() {
+    init_logging();
+    let module = Module::from_bc_path(PURITY_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let purity = analysis.purity_analysis();
+
+    assert_eq!(purity.declared_purity("square"), Some(Purity::ReadNone));
+    assert_eq!(purity.declared_purity("calls_get"), Some(Purity::ReadNone));
+    assert_eq!(purity.declared_purity("calls_ext"), Some(Purity::ReadOnly));
+    assert_eq!(purity.declared_purity("get"), None);
+    assert_eq!(purity.declared_purity("ext"), None);
+
+    // @calls_ext's attribute may be right, since @ext is unknown
+    assert_eq!(
+        purity.attribute_conflicts().collect::<Vec<_>>(),
+        vec!["calls_get"]
+    );
+}
+
+/// Check that every function is at least as impure as each function it may
+/// call directly
+#[test]
+fn callers_as_impure_as_callees() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let call_graph = analysis.call_graph();
+    let purity = analysis.purity_analysis();
+    for func in &module.functions {
+        for callee in call_graph.direct_callees(&func.name) {
+            assert!(
+                purity.purity(&func.name) >= purity.purity(callee),
+                "{} calls {}",
+                func.name,
+                callee
+            );
+        }
+    }
+}
@@ -0,0 +1,89 @@
+#![allow(clippy::redundant_static_lifetimes)]
+
+use llvm_ir::{Module, Name};
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+/// reachingdefs.ll is hand-written, with values kept in stack slots
+const REACHINGDEFS_BC_PATH: &'static str = "tests/bcfiles/reachingdefs.bc";
+
+#[test]
+fn escaped_reaching_defs() {
+    init_logging();
+    let module = Module::from_bc_path(REACHINGDEFS_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("escaped");
+    let rd = fn_analysis.reaching_definitions();
+
+    let entry_name = Name::from("entry");
+    let then_name = Name::from("then");
+    let join_name = Name::from("join");
+    let x = Name::from("x");
+    let y = Name::from("y");
+    let z = Name::from("z");
+    let loc = |block, index| InstructionLocation { block, index };
+
+    assert!(rd.is_escaped(&x));
+    assert!(!rd.is_escaped(&y));
+
+    // the store to %x reaches the load of %x, and so do the calls which may
+    // write %x, since it escapes; the alloca itself is killed by the store
+    assert_eq!(
+        rd.definitions_reaching(loc(&join_name, 0), Variable::StackSlot(&x)),
+        vec![loc(&entry_name, 2), loc(&entry_name, 4), loc(&then_name, 1)]
+    );
+    // %y doesn't escape, so only its stores define it
+    assert_eq!(
+        rd.definitions_reaching(loc(&join_name, 1), Variable::StackSlot(&y)),
+        vec![loc(&entry_name, 3), loc(&then_name, 0)]
+    );
+    // the store in %then kills the earlier store to %y
+    assert_eq!(
+        rd.definitions_reaching(loc(&then_name, 1), Variable::StackSlot(&y)),
+        vec![loc(&then_name, 0)]
+    );
+
+    // %z reaches %join along one path, though it doesn't dominate it
+    let z_def = ReachingDefinition {
+        variable: Variable::Value(&z),
+        location: loc(&then_name, 1),
+    };
+    assert!(rd.reaching_block_entry(&join_name).any(|def| def == z_def));
+    assert!(!rd.reaching_block_entry(&then_name).any(|def| def == z_def));
+    assert!(rd.reaching_block_exit(&then_name).any(|def| def == z_def));
+    // nothing reaches the entry block
+    assert_eq!(rd.reaching_block_entry(&entry_name).count(), 0);
+}
+
+#[test]
+fn partial_store_reaching_defs() {
+    init_logging();
+    let module = Module::from_bc_path(REACHINGDEFS_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("partial");
+    let rd = fn_analysis.reaching_definitions();
+
+    let entry_name = Name::from("entry");
+    let p = Name::from("p");
+    let loc = |block, index| InstructionLocation { block, index };
+
+    assert!(!rd.is_escaped(&p));
+    // before the first store, the slot's contents are defined only by the
+    // alloca
+    assert_eq!(
+        rd.definitions_reaching(loc(&entry_name, 2), Variable::StackSlot(&p)),
+        vec![loc(&entry_name, 0)]
+    );
+    // the store through %q writes only part of %p, so both stores reach the
+    // load
+    assert_eq!(
+        rd.definitions_reaching(loc(&entry_name, 4), Variable::StackSlot(&p)),
+        vec![loc(&entry_name, 2), loc(&entry_name, 3)]
+    );
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::bool_assert_comparison, clippy::redundant_static_lifetimes)]
+
 use itertools::Itertools;
 use llvm_ir::{Module, Name};
 use llvm_ir_analysis::*;
@@ -294,3 +296,52 @@ fn begin_panic_cdg() {
         0
     );
 }
+
+#[cfg(feature = "demangle")]
+#[test]
+fn demangled_call_graph_queries() {
+    init_logging();
+    let module = Module::from_bc_path(PANIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    let begin_panic = "_ZN3std9panicking11begin_panic17h5ae0871c3ba84f98E";
+    assert_eq!(
+        demangle(begin_panic).as_deref(),
+        Some("std::panicking::begin_panic")
+    );
+    assert_eq!(demangle("rust_eh_personality"), None);
+
+    // with or without the hash
+    assert_eq!(
+        callgraph.resolve_demangled("std::panicking::begin_panic"),
+        vec![begin_panic]
+    );
+    assert_eq!(
+        callgraph.resolve_demangled("std::panicking::begin_panic::h5ae0871c3ba84f98"),
+        vec![begin_panic]
+    );
+    // several monomorphizations share a demangled name
+    assert_eq!(
+        callgraph
+            .resolve_demangled("core::ptr::drop_in_place")
+            .len(),
+        4
+    );
+    // unmangled names match themselves
+    assert_eq!(
+        callgraph.resolve_demangled("__rust_alloc"),
+        vec!["__rust_alloc"]
+    );
+    assert!(callgraph.resolve_demangled("nonexistent").is_empty());
+
+    assert_eq!(
+        callgraph.callers_demangled("std::panicking::begin_panic"),
+        vec!["_ZN5panic9may_panic17h044e5a8a5c34bdceE"]
+    );
+    assert_eq!(
+        callgraph.callees_demangled("panic::may_panic"),
+        vec![begin_panic]
+    );
+}
@@ -0,0 +1,211 @@
+#![allow(clippy::redundant_static_lifetimes)]
+
+use llvm_ir::{Constant, ConstantRef, Module, Name};
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+/// sccp.ll is hand-written, with constants left unfolded
+const SCCP_BC_PATH: &'static str = "tests/bcfiles/sccp.bc";
+const LOOP_BC_PATH: &'static str = "tests/bcfiles/loop.bc";
+
+fn int(bits: u32, value: u64) -> Option<ConstantRef> {
+    Some(ConstantRef::new(Constant::Int { bits, value }))
+}
+
+#[test]
+fn folded_constants() {
+    init_logging();
+    let module = Module::from_bc_path(SCCP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("folded");
+    let sccp = fn_analysis.constant_propagation();
+
+    let constant = |name: &str| sccp.constant(&Name::from(name)).cloned();
+    assert_eq!(constant("a"), int(32, 5));
+    assert_eq!(constant("cond"), int(1, 1));
+    assert_eq!(constant("b"), int(32, 20));
+    // the edge from %else never executes, so doesn't affect the phi
+    assert_eq!(constant("r"), int(32, 20));
+    // i8 arithmetic wraps around
+    assert_eq!(constant("w"), int(8, 128));
+    assert_eq!(constant("t"), int(8, 44));
+    assert_eq!(constant("s"), int(32, 0xffff_ff80));
+    // parameters aren't constant, nor are values which are never computed
+    assert!(!sccp.is_constant(&Name::from("x")));
+    assert!(!sccp.is_constant(&Name::from("c")));
+    assert!(!sccp.is_constant(&Name::from("d")));
+    assert_eq!(sccp.constants().count(), 7);
+}
+
+#[test]
+fn dead_edges() {
+    init_logging();
+    let module = Module::from_bc_path(SCCP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("folded");
+    let sccp = fn_analysis.constant_propagation();
+
+    let entry_name = Name::from("entry");
+    let then_name = Name::from("then");
+    let else_name = Name::from("else");
+    let join_name = Name::from("join");
+    let twenty_name = Name::from("twenty");
+    let thirty_name = Name::from("thirty");
+    let default_name = Name::from("default");
+
+    assert_eq!(
+        sccp.dead_blocks().collect::<Vec<_>>(),
+        vec![&else_name, &thirty_name, &default_name]
+    );
+    assert!(sccp.is_block_executable(&twenty_name));
+    assert!(sccp.is_edge_executable(&entry_name, CFGNode::Block(&then_name)));
+    assert!(!sccp.is_edge_executable(&entry_name, CFGNode::Block(&else_name)));
+    assert!(sccp.is_edge_executable(&twenty_name, CFGNode::Return));
+    assert!(!sccp.is_edge_executable(&twenty_name, CFGNode::Block(&entry_name)));
+
+    let dead_edges = sccp.dead_edges();
+    assert_eq!(dead_edges.len(), 6);
+    assert!(dead_edges.contains(&(&entry_name, CFGNode::Block(&else_name))));
+    assert!(dead_edges.contains(&(&else_name, CFGNode::Block(&join_name))));
+    assert!(dead_edges.contains(&(&join_name, CFGNode::Block(&thirty_name))));
+    assert!(dead_edges.contains(&(&join_name, CFGNode::Block(&default_name))));
+    assert!(dead_edges.contains(&(&thirty_name, CFGNode::Return)));
+    assert!(dead_edges.contains(&(&default_name, CFGNode::Return)));
+
+    // without the dead edges, %then dominates %join
+    let cfg = fn_analysis.control_flow_graph();
+    assert_eq!(
+        fn_analysis.dominator_tree().idom(&join_name),
+        Some(&entry_name)
+    );
+    let view = cfg.without_edges(dead_edges.iter().copied());
+    let domtree = DominatorTree::new(&view);
+    assert_eq!(domtree.idom(&join_name), Some(&then_name));
+    assert_eq!(domtree.idom(&twenty_name), Some(&join_name));
+    assert_eq!(domtree.idom(&else_name), None);
+}
+
+#[test]
+fn pruned_graphs() {
+    init_logging();
+    let module = Module::from_bc_path(SCCP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("folded");
+    let sccp = fn_analysis.constant_propagation();
+
+    let entry_name = Name::from("entry");
+    let then_name = Name::from("then");
+    let else_name = Name::from("else");
+    let join_name = Name::from("join");
+    let twenty_name = Name::from("twenty");
+    let thirty_name = Name::from("thirty");
+    let default_name = Name::from("default");
+
+    // the untaken outcomes of the `br` on %cond and the `switch` on %r
+    assert_eq!(
+        sccp.infeasible_edges().collect::<Vec<_>>(),
+        vec![
+            (&entry_name, CFGNode::Block(&else_name)),
+            (&join_name, CFGNode::Block(&default_name)),
+            (&join_name, CFGNode::Block(&thirty_name)),
+        ]
+    );
+
+    let cfg = fn_analysis.pruned_control_flow_graph();
+    assert_eq!(
+        cfg.succs(&entry_name).collect::<Vec<_>>(),
+        vec![CFGNode::Block(&then_name)]
+    );
+    assert_eq!(
+        cfg.succs(&join_name).collect::<Vec<_>>(),
+        vec![CFGNode::Block(&twenty_name)]
+    );
+    assert_eq!(cfg.preds(&join_name).collect::<Vec<_>>(), vec![&then_name]);
+    assert_eq!(cfg.succs(&else_name).count(), 0);
+    assert_eq!(cfg.num_edges(), 4);
+
+    let domtree = fn_analysis.pruned_dominator_tree();
+    assert_eq!(domtree.idom(&join_name), Some(&then_name));
+    assert_eq!(domtree.idom(&else_name), None);
+
+    let postdomtree = fn_analysis.pruned_postdominator_tree();
+    assert_eq!(
+        postdomtree.ipostdom(&entry_name),
+        Some(CFGNode::Block(&then_name))
+    );
+    assert!(!postdomtree.reaches_exit(&thirty_name));
+
+    // %then is control dependent on %entry, and %twenty on %join, only in
+    // the unpruned CDG
+    let full_cdg = fn_analysis.control_dependence_graph();
+    assert_eq!(
+        full_cdg
+            .get_imm_control_dependencies(&then_name)
+            .collect::<Vec<_>>(),
+        vec![&entry_name]
+    );
+    assert_eq!(
+        full_cdg
+            .get_imm_control_dependencies(&twenty_name)
+            .collect::<Vec<_>>(),
+        vec![&join_name]
+    );
+    let cdg = fn_analysis.pruned_control_dependence_graph();
+    for block in [&then_name, &join_name, &twenty_name] {
+        assert_eq!(
+            cdg.get_imm_control_dependencies(block).count(),
+            0,
+            "{}",
+            block
+        );
+    }
+}
+
+#[test]
+fn loop_invariant_constants() {
+    init_logging();
+    let module = Module::from_bc_path(SCCP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("loop_invariant");
+    let sccp = fn_analysis.constant_propagation();
+
+    assert_eq!(sccp.constant(&Name::from("k")).cloned(), int(32, 7));
+    assert_eq!(sccp.constant(&Name::from("k.next")).cloned(), int(32, 7));
+    assert!(!sccp.is_constant(&Name::from("i")));
+    assert!(!sccp.is_constant(&Name::from("i.next")));
+    assert!(!sccp.is_constant(&Name::from("done")));
+    assert!(!sccp.is_constant(&Name::from("sum")));
+    assert_eq!(sccp.dead_blocks().count(), 0);
+    assert!(sccp.dead_edges().is_empty());
+}
+
+#[test]
+fn constant_propagation_of_loops() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    for func in &module.functions {
+        let fn_analysis = analysis.fn_analysis(&func.name);
+        let sccp = fn_analysis.constant_propagation();
+        // every block of these functions can execute, and no parameter is
+        // constant
+        assert_eq!(sccp.dead_blocks().count(), 0, "{}", func.name);
+        assert!(sccp.dead_edges().is_empty(), "{}", func.name);
+        assert_eq!(
+            fn_analysis.pruned_control_flow_graph().num_edges(),
+            fn_analysis.control_flow_graph().num_edges()
+        );
+        for param in &func.parameters {
+            assert!(!sccp.is_constant(&param.name));
+        }
+    }
+}
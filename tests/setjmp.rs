@@ -0,0 +1,99 @@
+use itertools::Itertools;
+use llvm_ir::{Module, Name};
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+const SETJMP_BC_PATH: &str = "tests/bcfiles/setjmp.bc";
+
+#[test]
+fn setjmp_calls() {
+    init_logging();
+    let module = Module::from_bc_path(SETJMP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let setjmp = analysis.setjmp_analysis();
+
+    let setjmp_calls: Vec<&JmpCallSite> = setjmp.setjmp_calls().collect();
+    assert_eq!(setjmp_calls.len(), 1);
+    assert_eq!(setjmp_calls[0].caller, "has_setjmp");
+    assert_eq!(setjmp_calls[0].callee, Some("_setjmp"));
+    assert_eq!(setjmp_calls[0].block, &Name::from(1));
+    assert_eq!(setjmp_calls[0].index, 0);
+
+    let longjmp_calls: Vec<&JmpCallSite> = setjmp.longjmp_calls().collect();
+    assert_eq!(longjmp_calls.len(), 1);
+    assert_eq!(longjmp_calls[0].caller, "jumps_back");
+    assert_eq!(longjmp_calls[0].callee, Some("longjmp"));
+    assert_eq!(longjmp_calls[0].block, &Name::from(3));
+
+    let funcs: Vec<FunctionName> = setjmp.functions_with_setjmp().collect();
+    assert_eq!(funcs, vec!["has_setjmp"]);
+    assert!(setjmp.has_setjmp("has_setjmp"));
+    assert!(!setjmp.has_setjmp("no_setjmp"));
+    assert!(!setjmp.has_setjmp("jumps_back"));
+}
+
+#[test]
+fn setjmp_abnormal_edges() {
+    init_logging();
+    let module = Module::from_bc_path(SETJMP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let setjmp = analysis.setjmp_analysis();
+
+    // the call to `calls_jumper` in %4 may reach the `longjmp`, which returns
+    // to the `setjmp` in %1
+    let bb1_name = Name::from(1);
+    let bb4_name = Name::from(4);
+    let bb7_name = Name::from(7);
+    let edges: Vec<(&Name, &Name)> = setjmp.abnormal_edges("has_setjmp").collect();
+    assert_eq!(edges, vec![(&bb4_name, &bb1_name)]);
+    assert_eq!(setjmp.abnormal_edges("no_setjmp").count(), 0);
+
+    let cfg = setjmp
+        .cfg_with_abnormal_edges("has_setjmp")
+        .expect("has_setjmp should have a CFG with abnormal edges");
+    let bb4_succs: Vec<CFGNode> = cfg.succs(&bb4_name).sorted().collect();
+    assert_eq!(
+        bb4_succs,
+        vec![CFGNode::Block(&bb1_name), CFGNode::Block(&bb7_name)]
+    );
+    assert!(setjmp.cfg_with_abnormal_edges("no_setjmp").is_none());
+
+    // the ordinary CFG is unaffected
+    let cfg = analysis.fn_analysis("has_setjmp").control_flow_graph();
+    let bb4_succs: Vec<CFGNode> = cfg.succs(&bb4_name).sorted().collect();
+    assert_eq!(bb4_succs, vec![CFGNode::Block(&bb7_name)]);
+}
+
+#[test]
+fn indirect_setjmp() {
+    init_logging();
+    let module = Module::from_bc_path("tests/bcfiles/setjmpindirect.bc")
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let setjmp = analysis.setjmp_analysis();
+
+    // the call through `%fp` is marked `returns_twice`, so it's a `setjmp`
+    let setjmp_calls: Vec<&JmpCallSite> = setjmp.setjmp_calls().collect();
+    assert_eq!(setjmp_calls.len(), 1);
+    assert_eq!(setjmp_calls[0].caller, "indirect_setjmp");
+    assert_eq!(setjmp_calls[0].callee, None);
+    assert_eq!(setjmp_calls[0].block, &Name::from("entry"));
+    assert_eq!(setjmp_calls[0].index, 0);
+
+    // an unmarked call through a function pointer is not
+    let funcs: Vec<FunctionName> = setjmp.functions_with_setjmp().collect();
+    assert_eq!(funcs, vec!["indirect_setjmp"]);
+    assert!(!setjmp.has_setjmp("indirect_call"));
+
+    let entry = Name::from("entry");
+    let first = Name::from("first");
+    // the `setjmp` itself is an indirect call, but doesn't `longjmp`
+    let edges: Vec<(&Name, &Name)> = setjmp.abnormal_edges("indirect_setjmp").collect();
+    assert_eq!(edges, vec![(&first, &entry)]);
+}
@@ -0,0 +1,136 @@
+#![allow(clippy::redundant_static_lifetimes)]
+
+use llvm_ir::{Module, Name};
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+/// taint.ll is hand-written, with data flowing through calls and a stack slot
+const TAINT_BC_PATH: &'static str = "tests/bcfiles/taint.bc";
+
+#[test]
+fn taint_flows() {
+    init_logging();
+    let module = Module::from_bc_path(TAINT_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let config = TaintConfig::new()
+        .with_source_return("read_input")
+        .with_source_parameter("main", 1)
+        .with_sink("system", Some(0))
+        .with_sink("printf", None);
+    let taint = analysis.taint_analysis(&config);
+
+    let entry_name = Name::from("entry");
+    let inst = |function: &'static str, index| TaintStep::Instruction {
+        function: function.into(),
+        location: InstructionLocation {
+            block: &entry_name,
+            index,
+        },
+    };
+    let param = |function: &'static str, index| TaintStep::Parameter {
+        function: function.into(),
+        index,
+    };
+
+    let flows = taint.flows();
+    assert_eq!(flows.len(), 3);
+
+    // argv[1] is passed directly to system()
+    assert_eq!(flows[0].sink, "system");
+    assert_eq!(flows[0].argument, 0);
+    assert_eq!(
+        flows[0].path,
+        vec![
+            param("main", 1),
+            inst("main", 0),
+            inst("main", 1),
+            inst("main", 2)
+        ]
+    );
+
+    // the input is printed
+    assert_eq!(flows[1].sink, "printf");
+    assert_eq!(flows[1].argument, 1);
+    assert_eq!(flows[1].path, vec![inst("process", 1), inst("process", 8)]);
+
+    // the input passes through identity(), the stack slot, and run()
+    assert_eq!(flows[2].sink, "system");
+    assert_eq!(flows[2].argument, 0);
+    assert_eq!(
+        flows[2].path,
+        vec![
+            inst("process", 1),
+            inst("process", 2),
+            param("identity", 0),
+            inst("identity", 0),
+            inst("process", 2),
+            inst("process", 3),
+            inst("process", 4),
+            inst("process", 5),
+            param("run", 0),
+            inst("run", 0),
+        ]
+    );
+    assert_eq!(flows[2].source(), inst("process", 1));
+    assert_eq!(flows[2].sink_call(), inst("run", 0));
+
+    // the constant command passed to system() is untainted
+    assert_eq!(taint.flows_to("system").count(), 2);
+    assert_eq!(taint.flows_to("strlen").count(), 0);
+
+    assert!(taint.is_tainted("process", &Name::from("input")));
+    assert!(taint.is_tainted("process", &Name::from("loaded")));
+    assert!(taint.is_tainted("process", &Name::from("len")));
+    assert!(!taint.is_tainted("process", &Name::from("slot")));
+    assert!(!taint.is_tainted("process", &Name::from("safe")));
+    assert!(taint.is_tainted("identity", &Name::from("p")));
+    assert!(taint.is_tainted("main", &Name::from("n")));
+    assert!(!taint.is_tainted("main", &Name::from("argc")));
+}
+
+#[test]
+fn taint_callback_matchers() {
+    init_logging();
+    let module = Module::from_bc_path(TAINT_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let config = TaintConfig::new()
+        .with_source_return(FunctionMatcher::Callback(Box::new(|name| {
+            name.starts_with("read_")
+        })))
+        .with_sink(
+            FunctionMatcher::Callback(Box::new(|name| name.starts_with("str"))),
+            None,
+        );
+    let taint = analysis.taint_analysis(&config);
+
+    let flows = taint.flows();
+    assert_eq!(flows.len(), 1);
+    assert_eq!(flows[0].sink, "strlen");
+    assert_eq!(flows[0].argument, 0);
+    assert!(!taint.is_tainted("main", &Name::from("s")));
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn taint_regex_matchers() {
+    init_logging();
+    let module = Module::from_bc_path(TAINT_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let config = TaintConfig::new()
+        .with_source_parameter(regex::Regex::new("^ma").unwrap(), 1)
+        .with_sink(regex::Regex::new("^(system|printf)$").unwrap(), None);
+    let taint = analysis.taint_analysis(&config);
+
+    let flows = taint.flows();
+    assert_eq!(flows.len(), 1);
+    assert_eq!(flows[0].sink, "system");
+    assert!(taint.is_tainted("main", &Name::from("s")));
+    assert!(!taint.is_tainted("process", &Name::from("input")));
+}
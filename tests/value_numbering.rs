@@ -0,0 +1,133 @@
+#![allow(clippy::redundant_static_lifetimes)]
+
+use llvm_ir::{Module, Name};
+use llvm_ir_analysis::*;
+use std::collections::HashMap;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+/// gvn.ll is hand-written, with its redundant computations left in
+const GVN_BC_PATH: &'static str = "tests/bcfiles/gvn.bc";
+const LOOP_BC_PATH: &'static str = "tests/bcfiles/loop.bc";
+
+#[test]
+fn redundant_values() {
+    init_logging();
+    let module = Module::from_bc_path(GVN_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("redundant");
+    let vn = fn_analysis.value_numbering();
+
+    let a_name = Name::from("a");
+    let b_name = Name::from("b");
+    let x_name = Name::from("x");
+    let y_name = Name::from("y");
+    let z_name = Name::from("z");
+    let w_name = Name::from("w");
+    let v_name = Name::from("v");
+    let s_name = Name::from("s");
+    let t_name = Name::from("t");
+    let e_name = Name::from("e");
+    let g_name = Name::from("g");
+
+    let redundant: HashMap<&Name, &Name> = vn.redundant().collect();
+    assert_eq!(
+        redundant,
+        vec![
+            (&y_name, &x_name),
+            (&w_name, &z_name),
+            (&v_name, &x_name),
+            (&t_name, &s_name),
+            (&g_name, &e_name),
+        ]
+        .into_iter()
+        .collect()
+    );
+    assert!(vn.is_redundant(&y_name));
+    assert!(!vn.is_redundant(&x_name));
+    assert_eq!(vn.leader(&w_name), Some(&z_name));
+    assert_eq!(vn.leader(&z_name), Some(&z_name));
+    assert_eq!(vn.value_number(&y_name), vn.value_number(&x_name));
+    assert!(vn.congruent(&v_name, &y_name));
+    assert!(!vn.congruent(&a_name, &b_name));
+    assert!(!vn.congruent(&x_name, &z_name));
+
+    // the same expression in blocks which don't dominate each other
+    for value in ["l", "n", "r", "q"] {
+        assert!(!vn.is_redundant(&Name::from(value)), "{}", value);
+    }
+    assert!(!vn.congruent(&Name::from("l"), &Name::from("r")));
+    assert!(!vn.congruent(&Name::from("l"), &Name::from("q")));
+    // `sub` isn't commutative
+    assert!(!vn.congruent(&Name::from("l"), &Name::from("n")));
+    // calls aren't pure
+    assert!(!vn.congruent(&Name::from("k1"), &Name::from("k2")));
+
+    // 3 parameters and 17 instruction results, 5 of which are redundant
+    assert_eq!(vn.num_value_numbers(), 15);
+
+    assert_eq!(vn.value_number(&Name::from("join")), None);
+    assert_eq!(vn.leader(&Name::from("nonexistent")), None);
+    assert!(!vn.is_redundant(&Name::from("nonexistent")));
+    assert!(!vn.congruent(&Name::from("nonexistent"), &Name::from("nonexistent")));
+}
+
+#[test]
+fn loop_values() {
+    init_logging();
+    let module = Module::from_bc_path(GVN_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("loop");
+    let vn = fn_analysis.value_numbering();
+
+    let h_name = Name::from("h");
+    let j_name = Name::from("j");
+
+    assert_eq!(vn.redundant().collect::<Vec<_>>(), vec![(&j_name, &h_name)]);
+    // %i and %i2 start the same and are incremented by the same amount, but
+    // `phi`s each get a number of their own
+    assert!(!vn.congruent(&Name::from("i"), &Name::from("i2")));
+    assert!(!vn.congruent(&Name::from("i.next"), &Name::from("i2.next")));
+}
+
+/// Check that every redundant value is congruent with its leader, and
+/// dominated by it
+#[test]
+fn leaders_dominate() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    for func in &module.functions {
+        let fn_analysis = analysis.fn_analysis(&func.name);
+        let vn = fn_analysis.value_numbering();
+        let domtree = fn_analysis.dominator_tree();
+        let def_use = fn_analysis.def_use_chains();
+        for (value, leader) in vn.redundant() {
+            assert_ne!(value, leader);
+            assert_eq!(vn.leader(value), Some(leader));
+            assert!(vn.congruent(value, leader));
+            let value_loc = match def_use.definition(value) {
+                Some(Definition::Instruction(loc)) => loc,
+                def => panic!("{} in {} is defined by {:?}", value, func.name, def),
+            };
+            let leader_loc = match def_use.definition(leader) {
+                Some(Definition::Instruction(loc)) => loc,
+                def => panic!("{} in {} is defined by {:?}", leader, func.name, def),
+            };
+            if value_loc.block == leader_loc.block {
+                assert!(leader_loc.index < value_loc.index);
+            } else {
+                assert!(domtree.strictly_dominates(
+                    CFGNode::Block(leader_loc.block),
+                    CFGNode::Block(value_loc.block)
+                ));
+            }
+        }
+    }
+}
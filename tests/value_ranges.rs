@@ -0,0 +1,193 @@
+#![allow(clippy::redundant_static_lifetimes)]
+
+use llvm_ir::{Module, Name};
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+/// ranges.ll is hand-written, with the shapes of its branches and loops fixed
+const RANGES_BC_PATH: &'static str = "tests/bcfiles/ranges.bc";
+const LOOP_BC_PATH: &'static str = "tests/bcfiles/loop.bc";
+
+fn range(min: i128, max: i128) -> Option<ValueRange> {
+    Some(ValueRange { min, max })
+}
+
+#[test]
+fn clamp_ranges() {
+    init_logging();
+    let module = Module::from_bc_path(RANGES_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("clamp");
+    let ranges = fn_analysis.value_ranges();
+
+    let negative_name = Name::from("negative");
+    let nonnegative_name = Name::from("nonnegative");
+    let join_name = Name::from("join");
+    let x = Name::from("x");
+
+    assert_eq!(ranges.range(&x), Some(ValueRange::full(32)));
+    assert_eq!(
+        ranges.range_at_block_entry(&x, &negative_name),
+        range(i32::MIN.into(), -1)
+    );
+    assert_eq!(
+        ranges.range_at_block_entry(&x, &nonnegative_name),
+        range(0, i32::MAX.into())
+    );
+    assert_eq!(ranges.range(&Name::from("big")), range(0, 1));
+    assert_eq!(ranges.range(&Name::from("c")), range(0, 100));
+    assert_eq!(ranges.range(&Name::from("r")), range(0, 100));
+    assert_eq!(
+        ranges.range_at(
+            &Name::from("r"),
+            InstructionLocation {
+                block: &join_name,
+                index: 1,
+            }
+        ),
+        range(0, 100)
+    );
+    assert!(ranges.range(&Name::from("nonexistent")).is_none());
+}
+
+#[test]
+fn loop_ranges() {
+    init_logging();
+    let module = Module::from_bc_path(RANGES_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("count");
+    let ranges = fn_analysis.value_ranges();
+
+    let loop_name = Name::from("loop");
+    let body_name = Name::from("body");
+    let exit_name = Name::from("exit");
+    let i = Name::from("i");
+
+    assert_eq!(ranges.range(&i), range(0, 10));
+    assert_eq!(ranges.range_at_block_entry(&i, &loop_name), range(0, 10));
+    assert_eq!(ranges.range_at_block_entry(&i, &body_name), range(0, 9));
+    assert_eq!(ranges.range_at_block_entry(&i, &exit_name), range(10, 10));
+    assert_eq!(ranges.range(&Name::from("idx")), range(0, 9));
+    assert_eq!(ranges.range(&Name::from("i.next")), range(1, 10));
+    assert_eq!(ranges.range(&Name::from("v")), Some(ValueRange::full(32)));
+    // no path to the entry defines %i
+    assert!(ranges
+        .range_at_block_entry(&i, &Name::from("entry"))
+        .is_none());
+    // pointers aren't tracked
+    assert!(ranges.range(&Name::from("p")).is_none());
+}
+
+#[test]
+fn masked_ranges() {
+    init_logging();
+    let module = Module::from_bc_path(RANGES_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("masked");
+    let ranges = fn_analysis.value_ranges();
+
+    let low_name = Name::from("low");
+    let high_name = Name::from("high");
+    let never_name = Name::from("never");
+    let m = Name::from("m");
+
+    assert_eq!(ranges.range(&m), range(0, 255));
+    assert_eq!(ranges.range_at_block_entry(&m, &low_name), range(0, 15));
+    assert_eq!(ranges.range_at_block_entry(&m, &high_name), range(16, 255));
+    assert_eq!(ranges.range(&Name::from("sh")), range(0, 60));
+    assert_eq!(ranges.range(&Name::from("z")), range(1, 1));
+    assert_eq!(ranges.range(&Name::from("d")), range(1, 15));
+    assert_eq!(ranges.range(&Name::from("huge")), range(0, 0));
+
+    assert!(ranges.is_block_reachable(&high_name));
+    assert!(!ranges.is_block_reachable(&never_name));
+    assert!(ranges.range_at_block_entry(&m, &never_name).is_none());
+}
+
+#[test]
+fn switch_ranges() {
+    init_logging();
+    let module = Module::from_bc_path(RANGES_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("pick");
+    let ranges = fn_analysis.value_ranges();
+
+    let one_name = Name::from("one");
+    let two_name = Name::from("two");
+    let other_name = Name::from("other");
+    let x = Name::from("x");
+
+    assert_eq!(ranges.range_at_block_entry(&x, &one_name), range(1, 1));
+    assert_eq!(ranges.range_at_block_entry(&x, &two_name), range(2, 3));
+    assert_eq!(
+        ranges.range_at_block_entry(&x, &other_name),
+        Some(ValueRange::full(8))
+    );
+    assert_eq!(ranges.range(&Name::from("a")), range(11, 11));
+    assert_eq!(ranges.range(&Name::from("b")), range(-6, -4));
+    assert_eq!(ranges.range(&Name::from("r")), range(-6, 11));
+}
+
+#[test]
+fn signed_bool_ranges() {
+    init_logging();
+    let module = Module::from_bc_path(RANGES_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("signed_bool");
+    let ranges = fn_analysis.value_ranges();
+
+    let negative_name = Name::from("negative");
+    let nonnegative_name = Name::from("nonnegative");
+    let b = Name::from("b");
+
+    // as a signed value, `true` is -1, which is less than `false`
+    assert_eq!(ranges.range(&Name::from("t")), range(1, 1));
+    assert_eq!(ranges.range(&Name::from("neg")), range(0, 1));
+    assert_eq!(ranges.range_at_block_entry(&b, &negative_name), range(1, 1));
+    assert_eq!(
+        ranges.range_at_block_entry(&b, &nonnegative_name),
+        range(0, 0)
+    );
+}
+
+#[test]
+#[should_panic(expected = "expected 1 to 64 bits")]
+fn full_range_of_too_many_bits() {
+    ValueRange::full(128);
+}
+
+#[test]
+fn value_ranges_of_loops() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    for func in &module.functions {
+        let fn_analysis = analysis.fn_analysis(&func.name);
+        let ranges = fn_analysis.value_ranges();
+        // every block of these functions can execute, and every parameter
+        // has the full range of its type
+        for bb in &func.basic_blocks {
+            assert!(
+                ranges.is_block_reachable(&bb.name),
+                "{} in {}",
+                bb.name,
+                func.name
+            );
+        }
+        for param in &func.parameters {
+            if let llvm_ir::Type::IntegerType { bits } = param.ty.as_ref() {
+                assert_eq!(ranges.range(&param.name), Some(ValueRange::full(*bits)));
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use itertools::Itertools;
+use llvm_ir::{Constant, Module, Name, Operand};
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+const VARARGS_BC_PATH: &str = "tests/bcfiles/varargs.bc";
+
+#[test]
+fn va_list_ops() {
+    init_logging();
+    let module = Module::from_bc_path(VARARGS_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let varargs = analysis.varargs_analysis();
+
+    let funcs: Vec<FunctionName> = varargs.variadic_functions().sorted().collect();
+    assert_eq!(funcs, vec!["sum_n", "sum_three"]);
+    assert!(varargs.function_info("calls_sum_three").is_none());
+
+    // sum_three: one va_list, three va_args in straight-line code
+    let ap = Name::from(2);
+    let info = varargs.function_info("sum_three").unwrap();
+    assert_eq!(info.va_starts.len(), 1);
+    assert_eq!(info.va_starts[0].va_list, Some(&ap));
+    assert_eq!(info.va_ends.len(), 1);
+    assert_eq!(info.va_ends[0].va_list, Some(&ap));
+    assert!(info.va_copies.is_empty());
+    let dests: Vec<&Name> = info.va_args.iter().map(|va_arg| va_arg.dest).collect();
+    assert_eq!(dests, vec![&Name::from(4), &Name::from(5), &Name::from(6)]);
+    let positions: Vec<Option<usize>> = info.va_args.iter().map(|va_arg| va_arg.position).collect();
+    assert_eq!(positions, vec![Some(0), Some(1), Some(2)]);
+    assert!(info
+        .va_args
+        .iter()
+        .all(|va_arg| va_arg.va_list == Some(&ap)));
+
+    // sum_n: va_args from a va_copy'd list, inside a loop
+    let ap = Name::from(2);
+    let ap2 = Name::from(3);
+    let info = varargs.function_info("sum_n").unwrap();
+    assert_eq!(info.va_starts.len(), 1);
+    assert_eq!(info.va_starts[0].va_list, Some(&ap));
+    assert_eq!(info.va_copies.len(), 1);
+    assert_eq!(info.va_copies[0].dest, Some(&ap2));
+    assert_eq!(info.va_copies[0].src, Some(&ap));
+    let va_ends: Vec<Option<&Name>> = info.va_ends.iter().map(|op| op.va_list).collect();
+    assert_eq!(va_ends, vec![Some(&ap2), Some(&ap)]);
+    assert_eq!(info.va_args.len(), 1);
+    assert_eq!(info.va_args[0].va_list, Some(&ap2));
+    assert_eq!(info.va_args[0].position, None);
+}
+
+#[test]
+fn vararg_call_sites() {
+    init_logging();
+    let module = Module::from_bc_path(VARARGS_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let varargs = analysis.varargs_analysis();
+
+    assert_eq!(varargs.call_sites().count(), 2);
+
+    let sites: Vec<&VariadicCallSite> = varargs.call_sites_of("sum_three").collect();
+    assert_eq!(sites.len(), 1);
+    let site = sites[0];
+    assert_eq!(site.caller, "calls_sum_three");
+    assert_eq!(site.extra_args.len(), 3);
+    match site.extra_args[2] {
+        Operand::ConstantOperand(cref) => match cref.as_ref() {
+            Constant::Int { value, .. } => assert_eq!(*value, 7),
+            c => panic!("Expected an integer constant, got {:?}", c),
+        },
+        op => panic!("Expected a constant operand, got {:?}", op),
+    }
+    let consumers: Vec<&Name> = (0..3)
+        .map(|i| varargs.consumer(site, i).unwrap().dest)
+        .collect();
+    assert_eq!(
+        consumers,
+        vec![&Name::from(4), &Name::from(5), &Name::from(6)]
+    );
+    assert!(varargs.consumer(site, 3).is_none());
+
+    let sites: Vec<&VariadicCallSite> = varargs.call_sites_of("sum_n").collect();
+    assert_eq!(sites.len(), 1);
+    assert_eq!(sites[0].extra_args.len(), 2);
+    assert!(varargs.consumer(sites[0], 0).is_none());
+}
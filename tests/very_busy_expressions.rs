@@ -0,0 +1,137 @@
+#![allow(clippy::redundant_static_lifetimes)]
+
+use llvm_ir::{Module, Name};
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+/// verybusy.ll is hand-written, with the same expressions computed in
+/// different blocks
+const VERYBUSY_BC_PATH: &'static str = "tests/bcfiles/verybusy.bc";
+const LOOP_BC_PATH: &'static str = "tests/bcfiles/loop.bc";
+
+#[test]
+fn hoistable_expressions() {
+    init_logging();
+    let module = Module::from_bc_path(VERYBUSY_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("hoist");
+    let very_busy = fn_analysis.very_busy_expressions();
+
+    let entry_name = Name::from("entry");
+    let left_name = Name::from("left");
+    let right_name = Name::from("right");
+    let join_name = Name::from("join");
+
+    // both arms compute the same expression
+    let add = very_busy.expression(&Name::from("x")).unwrap();
+    assert_eq!(very_busy.expression(&Name::from("y")), Some(add));
+    assert_eq!(add.to_string(), "add i32 %a, i32 %b");
+    assert_eq!(
+        add.local_operands().collect::<Vec<_>>(),
+        vec![&Name::from("a"), &Name::from("b")]
+    );
+    let mul = very_busy.expression(&Name::from("m")).unwrap();
+    let sub = very_busy.expression(&Name::from("z")).unwrap();
+    let sum = very_busy.expression(&Name::from("r")).unwrap();
+    assert!(very_busy.expression(&Name::from("p")).is_none());
+    assert_eq!(very_busy.expressions().count(), 4);
+
+    // %a + %b can be hoisted out of both arms, but %a * 2 can't
+    assert!(very_busy.is_very_busy_out(add, &entry_name));
+    assert!(!very_busy.is_very_busy_out(mul, &entry_name));
+    assert!(very_busy.is_very_busy_in(mul, &left_name));
+    assert!(!very_busy.is_very_busy_in(mul, &right_name));
+    // %a - %b is computed on every path through the function
+    for block in [&entry_name, &left_name, &right_name, &join_name] {
+        assert!(very_busy.is_very_busy_in(sub, block), "{}", block);
+    }
+    // %p + %z can't be computed before %p is defined
+    assert!(!very_busy.is_very_busy_in(sum, &join_name));
+    assert!(!very_busy.is_very_busy_out(sum, &left_name));
+    assert!(very_busy.is_very_busy_at(sum, &join_name, 2));
+    assert!(!very_busy.is_very_busy_at(sum, &join_name, 1));
+    // nothing is computed after the end of the function
+    assert_eq!(very_busy.very_busy_out(&join_name).count(), 0);
+    assert_eq!(very_busy.very_busy_at(&join_name, 3).len(), 0);
+
+    let mut entry_in: Vec<String> = very_busy
+        .very_busy_in(&entry_name)
+        .map(|expression| expression.to_string())
+        .collect();
+    entry_in.sort();
+    assert_eq!(entry_in, vec!["add i32 %a, i32 %b", "sub i32 %a, i32 %b"]);
+}
+
+#[test]
+fn loop_expressions() {
+    init_logging();
+    let module = Module::from_bc_path(VERYBUSY_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("loop");
+    let very_busy = fn_analysis.very_busy_expressions();
+
+    let entry_name = Name::from("entry");
+    let loop_name = Name::from("loop");
+    let exit_name = Name::from("exit");
+
+    let shl = very_busy.expression(&Name::from("s")).unwrap();
+    let add = very_busy.expression(&Name::from("t")).unwrap();
+
+    // the loop body always executes at least once
+    assert!(very_busy.is_very_busy_out(shl, &entry_name));
+    assert!(very_busy.is_very_busy_in(shl, &loop_name));
+    // %i + %a uses the phi, so is very busy only after it
+    assert!(!very_busy.is_very_busy_out(add, &entry_name));
+    assert!(!very_busy.is_very_busy_in(add, &loop_name));
+    assert!(!very_busy.is_very_busy_at(add, &loop_name, 0));
+    assert!(very_busy.is_very_busy_at(add, &loop_name, 1));
+    // the loop may exit after any iteration
+    assert!(!very_busy.is_very_busy_out(shl, &loop_name));
+    assert!(!very_busy.is_very_busy_at(shl, &loop_name, 2));
+    assert_eq!(very_busy.very_busy_in(&exit_name).count(), 0);
+}
+
+#[test]
+fn very_busy_expressions_of_loops() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    for func in &module.functions {
+        let fn_analysis = analysis.fn_analysis(&func.name);
+        let very_busy = fn_analysis.very_busy_expressions();
+        for bb in &func.basic_blocks {
+            // every expression computed by a block which uses only
+            // parameters is very busy on entry to the block
+            for inst in &bb.instrs {
+                let expression = match inst.try_get_result() {
+                    Some(result) => very_busy.expression(result),
+                    None => None,
+                };
+                if let Some(expression) = expression {
+                    if expression
+                        .local_operands()
+                        .all(|operand| func.parameters.iter().any(|p| &p.name == operand))
+                    {
+                        assert!(
+                            very_busy.is_very_busy_in(expression, &bb.name),
+                            "{} in {}",
+                            bb.name,
+                            func.name
+                        );
+                    }
+                }
+            }
+            // nothing is very busy on exit from a block which returns
+            if matches!(bb.term, llvm_ir::Terminator::Ret(_)) {
+                assert_eq!(very_busy.very_busy_out(&bb.name).count(), 0);
+            }
+        }
+    }
+}
@@ -0,0 +1,165 @@
+#![allow(clippy::redundant_static_lifetimes)]
+
+use llvm_ir::{Module, Name};
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+/// wto.ll is hand-written, with the shapes of its loops fixed
+const WTO_BC_PATH: &'static str = "tests/bcfiles/wto.bc";
+const LOOP_BC_PATH: &'static str = "tests/bcfiles/loop.bc";
+const RANGES_BC_PATH: &'static str = "tests/bcfiles/ranges.bc";
+
+/// Check that every edge of the CFG goes forward in the ordering, or back to
+/// the head of a component containing its source
+fn check_edges(func: &llvm_ir::Function, cfg: &ControlFlowGraph, wto: &WeakTopologicalOrder) {
+    let order: Vec<&Name> = wto.blocks().collect();
+    let position = |block| order.iter().position(|&b| b == block);
+    for &block in &order {
+        for succ in cfg.succs(block) {
+            if let CFGNode::Block(succ) = succ {
+                if position(succ) <= position(block) {
+                    assert!(
+                        wto.heads_of(block).contains(&succ),
+                        "{} -> {} in {}: {}",
+                        block,
+                        succ,
+                        func.name,
+                        wto
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn nested_components() {
+    init_logging();
+    let module = Module::from_bc_path(WTO_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("nested");
+    let wto = fn_analysis.weak_topological_order();
+
+    let entry_name = Name::from("entry");
+    let outer_name = Name::from("outer");
+    let inner_name = Name::from("inner");
+    let latch_name = Name::from("latch");
+    let exit_name = Name::from("exit");
+
+    assert_eq!(wto.to_string(), "%entry (%outer (%inner) %latch) %exit");
+    assert_eq!(
+        wto.components(),
+        &[
+            WTOComponent::Vertex(&entry_name),
+            WTOComponent::Component {
+                head: &outer_name,
+                components: vec![
+                    WTOComponent::Component {
+                        head: &inner_name,
+                        components: vec![],
+                    },
+                    WTOComponent::Vertex(&latch_name),
+                ],
+            },
+            WTOComponent::Vertex(&exit_name),
+        ]
+    );
+    assert_eq!(wto.components()[1].head(), &outer_name);
+    assert_eq!(
+        wto.components()[1].blocks().collect::<Vec<_>>(),
+        vec![&outer_name, &inner_name, &latch_name]
+    );
+    assert_eq!(
+        wto.heads().collect::<Vec<_>>(),
+        vec![&outer_name, &inner_name]
+    );
+    assert!(wto.is_head(&inner_name));
+    assert!(!wto.is_head(&latch_name));
+    assert_eq!(wto.heads_of(&inner_name), &[&outer_name, &inner_name]);
+    assert_eq!(wto.heads_of(&latch_name), &[&outer_name]);
+    assert!(wto.heads_of(&exit_name).is_empty());
+    assert_eq!(wto.depth(&inner_name), 2);
+    assert_eq!(wto.depth(&entry_name), 0);
+
+    let cfg = fn_analysis.control_flow_graph();
+    check_edges(&module.functions[0], &cfg, &wto);
+}
+
+#[test]
+fn irreducible_component() {
+    init_logging();
+    let module = Module::from_bc_path(WTO_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let func = module.get_func_by_name("irreducible").unwrap();
+    let fn_analysis = analysis.fn_analysis("irreducible");
+    let wto = fn_analysis.weak_topological_order();
+
+    let a_name = Name::from("a");
+    let b_name = Name::from("b");
+    let dead_name = Name::from("dead");
+
+    // one of the cycle's two entries is chosen as its head
+    assert_eq!(wto.heads().count(), 1);
+    assert!(wto.is_head(&a_name) != wto.is_head(&b_name));
+    assert_eq!(wto.depth(&a_name), 1);
+    assert_eq!(wto.depth(&b_name), 1);
+    // unreachable blocks aren't in the ordering
+    assert_eq!(wto.blocks().count(), 4);
+    assert!(!wto.blocks().any(|block| block == &dead_name));
+    assert_eq!(wto.depth(&dead_name), 0);
+
+    let cfg = fn_analysis.control_flow_graph();
+    check_edges(func, &cfg, &wto);
+}
+
+#[test]
+fn wto_of_loop() {
+    init_logging();
+    let module = Module::from_bc_path(RANGES_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("count");
+    let wto = fn_analysis.weak_topological_order();
+    assert_eq!(wto.to_string(), "%entry (%loop %body) %exit");
+}
+
+#[test]
+fn heads_are_loop_headers() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    for func in &module.functions {
+        let fn_analysis = analysis.fn_analysis(&func.name);
+        let wto = fn_analysis.weak_topological_order();
+        let loops = fn_analysis.loop_analysis();
+        // these CFGs are reducible, so the components are the loops
+        for bb in &func.basic_blocks {
+            assert_eq!(
+                wto.is_head(&bb.name),
+                loops.is_header(&bb.name),
+                "{} in {}: {}",
+                bb.name,
+                func.name,
+                wto
+            );
+            assert_eq!(
+                wto.depth(&bb.name),
+                loops.loop_depth(&bb.name),
+                "{} in {}: {}",
+                bb.name,
+                func.name,
+                wto
+            );
+        }
+        assert_eq!(wto.blocks().count(), func.basic_blocks.len());
+        let cfg = fn_analysis.control_flow_graph();
+        check_edges(func, &cfg, &wto);
+    }
+}
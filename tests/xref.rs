@@ -0,0 +1,125 @@
+use llvm_ir::{Module, Name};
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+const CALL_BC_PATH: &str = "tests/bcfiles/call.bc";
+const CROSSMOD_BC_PATH: &str = "tests/bcfiles/crossmod.bc";
+const FUNCTIONPTR_BC_PATH: &str = "tests/bcfiles/functionptr.bc";
+const SETJMP_BC_PATH: &str = "tests/bcfiles/setjmp.bc";
+const PANIC_BC_PATH: &str = "tests/bcfiles/panic.bc";
+
+#[test]
+fn function_xrefs() {
+    init_logging();
+    let module = Module::from_bc_path(FUNCTIONPTR_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let xrefs = analysis.xref_index();
+
+    // `foo` and `bar` are never called directly, but their addresses are taken
+    // in `get_function_ptr`
+    let bb_name = Name::from(1);
+    let refs: Vec<&XRef> = xrefs.references_to("foo").collect();
+    assert_eq!(
+        refs,
+        vec![&XRef::Instruction {
+            module: &module.name,
+            function: "get_function_ptr".into(),
+            block: &bb_name,
+            index: 0,
+        }]
+    );
+    let funcs: Vec<FunctionName> = xrefs.referencing_functions("bar").collect();
+    assert_eq!(funcs, vec!["get_function_ptr"]);
+
+    // direct calls are references too
+    let funcs: Vec<FunctionName> = xrefs.referencing_functions("get_function_ptr").collect();
+    assert_eq!(funcs, vec!["fptr_driver", "struct_driver"]);
+
+    assert!(!xrefs.is_referenced("fptr_driver"));
+    assert_eq!(xrefs.references_to("fptr_driver").count(), 0);
+    assert_eq!(xrefs.references_to("no_such_symbol").count(), 0);
+}
+
+#[test]
+fn global_xrefs() {
+    init_logging();
+    let module = Module::from_bc_path(SETJMP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let xrefs = analysis.xref_index();
+
+    // `@buf` is only referenced inside `getelementptr` constant expressions
+    let funcs: Vec<FunctionName> = xrefs.referencing_functions("buf").collect();
+    assert_eq!(funcs, vec!["has_setjmp", "jumps_back"]);
+
+    // references from global initializers
+    let module = Module::from_bc_path(PANIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let xrefs = analysis.xref_index();
+    let alloc21_name = Name::from("alloc21");
+    let refs: Vec<&XRef> = xrefs.references_to("alloc20").collect();
+    assert_eq!(
+        refs,
+        vec![&XRef::GlobalInitializer {
+            module: &module.name,
+            global: &alloc21_name,
+        }]
+    );
+    assert!(xrefs
+        .referenced_symbols()
+        .any(|name| name == &Name::from("alloc21")));
+}
+
+#[test]
+fn crossmod_xrefs() {
+    init_logging();
+    let call_module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let crossmod_module = Module::from_bc_path(CROSSMOD_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let modules = [call_module, crossmod_module];
+    let analysis = CrossModuleAnalysis::new(&modules);
+    let xrefs = analysis.xref_index();
+
+    let funcs: Vec<FunctionName> = xrefs.referencing_functions("simple_callee").collect();
+    assert_eq!(
+        funcs,
+        vec![
+            "caller_with_loop",
+            "conditional_caller",
+            "cross_module_simple_caller",
+            "cross_module_twice_caller",
+            "recursive_and_normal_caller",
+            "simple_caller",
+            "twice_caller",
+        ]
+    );
+    // each call in `twice_caller` is a separate reference
+    let twice_refs = xrefs
+        .references_to("simple_callee")
+        .filter(|xref| xref.function() == Some("twice_caller"))
+        .count();
+    assert_eq!(twice_refs, 2);
+    let crossmod_refs = xrefs
+        .references_to("simple_callee")
+        .filter(|xref| xref.module() == modules[1].name)
+        .count();
+    assert_eq!(crossmod_refs, 3);
+
+    // `global3` is loaded from and stored to within the same block
+    let funcs: Vec<FunctionName> = xrefs.referencing_functions("global3").collect();
+    assert_eq!(
+        funcs,
+        vec![
+            "cross_module_modify_global",
+            "cross_module_modify_global_via_call"
+        ]
+    );
+    assert_eq!(xrefs.references_to("global3").count(), 3);
+}